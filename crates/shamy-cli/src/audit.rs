@@ -0,0 +1,165 @@
+#![allow(non_snake_case)]
+
+use crate::error::{CliError, fail};
+use crate::i18n::Msg;
+use crate::keygen_file::KeygenFile;
+use crate::output::Output;
+use crate::parser::OutputFormat;
+use k256::ProjectivePoint;
+use serde_json::{Value, json};
+use shamy_core::{
+    util::{fingerprint, hex_to_pp, hex_to_scalar},
+    vss::{public_share, verify_share},
+};
+use std::{collections::BTreeSet, fmt::Write as _, path::Path};
+
+//--------------------------------------------------------------------
+// Keygen audit
+//--------------------------------------------------------------------
+//
+// rechecks a stored keygen result (`keygen --output`)
+//
+//   X   == C_0
+//   len(C) == t,  len(participants) == n,  ids distinct and non-zero
+//   X_i == Σ id^j*C_j                      (every participant)
+//   x_i*G == X_i                           (when the share is in the file)
+//
+// public shares are checked even without x_i, so a file written with
+// --keystore or --share-dir can be audited as well.
+//
+
+struct Check {
+    ok: bool,
+    code: &'static str,
+    id: Option<u64>,
+    message: String,
+}
+
+impl Check {
+    fn new(ok: bool, code: &'static str, message: Msg) -> Self {
+        Self {
+            ok,
+            code,
+            id: None,
+            message: message.to_string(),
+        }
+    }
+
+    fn participant(ok: bool, code: &'static str, id: u64, message: Msg) -> Self {
+        Self {
+            id: Some(id),
+            ..Self::new(ok, code, message)
+        }
+    }
+}
+
+pub fn run(path: &Path, format: OutputFormat) {
+    let file = KeygenFile::load(path).unwrap_or_else(|e| fail(e));
+    let point = |hex: &str| {
+        hex_to_pp(hex)
+            .unwrap_or_else(|e| fail(CliError::input(Msg::InvalidKeygenFile(&e.to_string()))))
+    };
+    let X = point(&file.public_key);
+    let commitments = file
+        .commitments
+        .iter()
+        .map(|C| point(C))
+        .collect::<Vec<_>>();
+
+    let key_matches = commitments.first() == Some(&X);
+    let mut checks = vec![
+        Check::new(
+            key_matches,
+            "public_key",
+            match key_matches {
+                true => Msg::AuditPublicKeyOk,
+                false => Msg::AuditPublicKeyMismatch,
+            },
+        ),
+        Check::new(
+            commitments.len() == file.threshold,
+            "commitment_count",
+            Msg::AuditCommitmentCount(commitments.len(), file.threshold),
+        ),
+        Check::new(
+            file.participants.len() == file.num_shares,
+            "share_count",
+            Msg::AuditShareCount(file.participants.len(), file.num_shares),
+        ),
+    ];
+
+    let mut seen = BTreeSet::new();
+    for participant in &file.participants {
+        let id = participant.id;
+        if id == 0 || !seen.insert(id) {
+            checks.push(Check::participant(false, "id", id, Msg::AuditInvalidId(id)));
+            continue;
+        }
+
+        let X_i = point(&participant.X_i);
+        if commitments.is_empty() || X_i != public_share(id, &commitments) {
+            checks.push(Check::participant(
+                false,
+                "public_share",
+                id,
+                Msg::AuditPublicShareMismatch(id),
+            ));
+            continue;
+        }
+        if let Some(x_i) = &participant.x_i {
+            let x_i = hex_to_scalar(x_i)
+                .unwrap_or_else(|e| fail(CliError::input(Msg::InvalidKeygenFile(&e.to_string()))));
+            if ProjectivePoint::GENERATOR * x_i != X_i || !verify_share(id, x_i, &commitments) {
+                checks.push(Check::participant(
+                    false,
+                    "share",
+                    id,
+                    Msg::AuditShareMismatch(id),
+                ));
+                continue;
+            }
+        }
+        checks.push(Check::participant(
+            true,
+            "participant",
+            id,
+            Msg::AuditParticipantOk(id),
+        ));
+    }
+
+    let failed = checks.iter().filter(|c| !c.ok).count();
+    let mut text = format!("{}\n", Msg::AuditKey(&fingerprint(&X)));
+    for check in &checks {
+        let mark = if check.ok { "✅" } else { "❌" };
+        writeln!(text, "{} {}", mark, check.message).unwrap();
+    }
+    if failed == 0 {
+        write!(text, "{}", Msg::AuditPassed(checks.len())).unwrap();
+    }
+
+    Output::new(
+        text.trim_end(),
+        json!({
+            "public_key": file.public_key,
+            "fingerprint": fingerprint(&X),
+            "checks": checks
+                .iter()
+                .map(|check| {
+                    let mut value = json!({ "check": check.code, "ok": check.ok });
+                    if let Some(id) = check.id {
+                        value["id"] = Value::from(id);
+                    }
+                    value
+                })
+                .collect::<Vec<_>>(),
+            "valid": failed == 0,
+        }),
+    )
+    .print(format);
+    if failed > 0 {
+        fail(CliError::verification(Msg::AuditFailed(
+            failed,
+            checks.len(),
+        )));
+    }
+}
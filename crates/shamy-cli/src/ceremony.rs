@@ -0,0 +1,225 @@
+#![allow(non_snake_case)]
+
+use crate::error::{CliError, fail};
+use crate::i18n::Msg;
+use crate::keygen_file::KeygenFile;
+use crate::keystore::{Keystore, read_passphrase};
+use crate::message::Message;
+use crate::output::Output;
+use crate::parser::{CeremonyCommands, FramingArgs, OutputFormat};
+use crate::proof;
+use k256::ProjectivePoint;
+use serde_json::json;
+use shamy_core::{
+    schnorr::{SigningConfig, compute_nonce_point, generate_nonce},
+    threshold::{
+        AggregationProof, Participant, SignerSet, aggregate_nonce_with_signers,
+        finalize_signature_with_signers, partial_sign,
+    },
+    util::{hex_to_pp, hex_to_scalar, pp_to_hex, scalar_to_hex},
+};
+use std::{
+    io::{self, BufRead, IsTerminal, Write},
+    path::Path,
+};
+
+pub fn run(command: CeremonyCommands, format: OutputFormat) {
+    match command {
+        CeremonyCommands::Sign {
+            message,
+            public_key,
+            keygen_file,
+            keystore,
+            proof,
+            framing,
+        } => sign(
+            message,
+            public_key,
+            keygen_file.as_deref(),
+            keystore.as_deref(),
+            proof.as_deref(),
+            &framing,
+        )
+        .print(format),
+    }
+}
+
+/// the whole signing flow in one process, for an operator holding t shares.
+/// prompts and progress go to stderr so stdout only carries the result.
+fn sign(
+    message: Option<String>,
+    public_key: Option<String>,
+    keygen_file: Option<&Path>,
+    keystore: Option<&Path>,
+    proof_path: Option<&Path>,
+    framing: &FramingArgs,
+) -> Output {
+    let message = message.unwrap_or_else(|| prompt(Msg::PromptMessage));
+    let public_key = match keygen_file {
+        Some(path) => KeygenFile::load(path)
+            .and_then(|file| file.public_key())
+            .unwrap_or_else(|e| fail(e)),
+        None => {
+            let public_key = public_key.unwrap_or_else(|| prompt(Msg::PromptPublicKey));
+            hex_to_pp(&public_key).unwrap_or_else(|e| fail(e))
+        }
+    };
+
+    let config = framing.config();
+    let proof = quorum_sign(
+        &Message::Raw(message.as_bytes().to_vec()),
+        &public_key,
+        keystore,
+        &config,
+    );
+    let signature = proof.signature;
+    let ids = proof.nonces().iter().map(|(id, _)| *id).collect::<Vec<_>>();
+
+    if let Some(path) = proof_path {
+        proof::save(path, &message, &pp_to_hex(&public_key), &proof);
+        eprintln!("{}", Msg::Wrote(path));
+    }
+
+    Output::new(
+        Msg::CeremonyResult(&pp_to_hex(&signature.R), &scalar_to_hex(&signature.s)).to_string(),
+        json!({
+            "message": message,
+            "public_key": pp_to_hex(&public_key),
+            "ids": ids,
+            "nonce": pp_to_hex(&signature.R),
+            "signature": scalar_to_hex(&signature.s),
+            "valid": true,
+        }),
+    )
+}
+
+/// select t shares, from `keystore` or prompted, and run every round on
+/// them. the signature is verified before it is returned.
+pub fn quorum_sign(
+    message: &Message,
+    public_key: &ProjectivePoint,
+    keystore: Option<&Path>,
+    config: &SigningConfig,
+) -> AggregationProof {
+    // 1. select shares
+    let participants = match keystore {
+        Some(path) => keystore_shares(path),
+        None => prompted_shares(),
+    };
+    if participants.is_empty() {
+        fail(CliError::input(Msg::NoSharesSelected));
+    }
+    let ids = participants.iter().map(|p| p.id).collect::<Vec<_>>();
+    let signers = SignerSet::new(&ids, ids.len()).unwrap_or_else(|e| fail(e));
+    eprintln!("{}", Msg::CeremonySigningWith(&ids));
+
+    // 2. nonces
+    let nonces = participants
+        .iter()
+        .map(|_| generate_nonce())
+        .collect::<Vec<_>>();
+    let nonce_points = ids
+        .iter()
+        .zip(&nonces)
+        .map(|(id, r_i)| (*id, compute_nonce_point(r_i)))
+        .collect::<Vec<_>>();
+    for (id, R_i) in &nonce_points {
+        eprintln!("{}", Msg::CeremonyNonce(*id, &pp_to_hex(R_i)));
+    }
+
+    // 3. challenge
+    let R = aggregate_nonce_with_signers(&nonce_points, &signers).unwrap_or_else(|e| fail(e));
+    let c = message.challenge(config, &R, public_key);
+    eprintln!("{}", Msg::CeremonyChallenge(&scalar_to_hex(&c)));
+
+    // 4. partial signatures
+    let partials = participants
+        .iter()
+        .zip(&nonces)
+        .map(|(participant, r_i)| partial_sign(participant, r_i, &c))
+        .collect::<Vec<_>>();
+    for partial in &partials {
+        eprintln!(
+            "{}",
+            Msg::CeremonyPartial(partial.id, &scalar_to_hex(&partial.s_i))
+        );
+    }
+
+    // 5. combine
+    let signature =
+        finalize_signature_with_signers(&partials, R, &signers).unwrap_or_else(|e| fail(e));
+    eprintln!("{}", Msg::CeremonyCombined(&scalar_to_hex(&signature.s)));
+
+    // 6. verify
+    if !message.verify(&signature, public_key, config) {
+        fail(CliError::verification(Msg::CeremonyInvalid));
+    }
+    eprintln!("{}", Msg::CeremonyVerified);
+
+    AggregationProof::new(signature, &nonce_points, &partials).unwrap_or_else(|e| fail(e))
+}
+
+fn keystore_shares(path: &Path) -> Vec<Participant> {
+    let keystore = Keystore::load(path).unwrap_or_else(|e| fail(e));
+    eprintln!("{}", Msg::SharesIn(path, &keystore.ids()));
+
+    prompt(Msg::PromptShareIds)
+        .split([' ', ','])
+        .filter(|id| !id.is_empty())
+        .map(|id| {
+            let id = id
+                .parse::<u64>()
+                .unwrap_or_else(|_| fail(CliError::input(Msg::InvalidShareId(id))));
+            keystore
+                .decrypt(id, &read_passphrase(id))
+                .unwrap_or_else(|e| fail(e))
+        })
+        .collect()
+}
+
+fn prompted_shares() -> Vec<Participant> {
+    let mut participants: Vec<Participant> = Vec::new();
+    loop {
+        let id = prompt(Msg::PromptParticipantId);
+        if id.is_empty() {
+            return participants;
+        }
+        let id = id
+            .parse::<u64>()
+            .unwrap_or_else(|_| fail(CliError::input(Msg::InvalidParticipantId(&id))));
+        if participants.iter().any(|p| p.id == id) {
+            fail(CliError::input(Msg::ShareSelectedTwice(id)));
+        }
+
+        let x_i = prompt_secret(Msg::PromptShare(id));
+        let x_i = hex_to_scalar(&x_i).unwrap_or_else(|e| fail(e));
+        let participant = Participant::from_secret(id, x_i);
+        eprintln!(
+            "{}",
+            Msg::CeremonyPublicShare(id, &pp_to_hex(&participant.X_i))
+        );
+        participants.push(participant);
+    }
+}
+
+fn prompt(text: Msg) -> String {
+    eprint!("{}", text);
+    io::stderr().flush().unwrap();
+
+    let mut line = String::new();
+    if io::stdin().lock().read_line(&mut line).unwrap() == 0 {
+        fail(CliError::input(Msg::UnexpectedEndOfInput));
+    }
+    line.trim().to_string()
+}
+
+/// like `prompt`, without echoing when typed on a terminal.
+fn prompt_secret(text: Msg) -> String {
+    match io::stdin().is_terminal() {
+        true => rpassword::prompt_password(text.to_string())
+            .unwrap()
+            .trim()
+            .to_string(),
+        false => prompt(text),
+    }
+}
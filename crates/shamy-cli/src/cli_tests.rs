@@ -0,0 +1,2147 @@
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    #[test]
+    fn test_cli_basics() {
+        let output = Command::new("cargo")
+            .args(["run", "--", "help"])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+    }
+
+    #[test]
+    fn test_cli_keygen() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "--",
+                "keygen",
+                "--threshold",
+                "3",
+                "--num-shares",
+                "5",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+    }
+
+    #[test]
+    fn test_cli_combine() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "--",
+                "schnorr",
+                "combine",
+                "--nonce",
+                "031cb8610733456b7f163fb088a127118ddfe10689af097eb7646c96c025b8e5ae",
+                "--ids",
+                "1",
+                "--ids",
+                "2",
+                "--signatures",
+                "4ea64f5d0b0a68762d143eb45b6e00366923dc76d4fbc9830176b42223677016",
+                "--signatures",
+                "983f3626eb6cb6dddf7c9eada612b64ba7558c35db80cee908469d50b2b9441f",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+    }
+
+    #[test]
+    fn test_cli_verify() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "--",
+                "schnorr",
+                "verify",
+                "--message",
+                "rust is best",
+                "--nonce",
+                "032ab98218bf256c1e9a3d7a85f451f0879867fbc0923540c4cd2928d1f4b03303",
+                "--signature",
+                "2290a650e2d62d3f3155c52284d7db29cb0674ee5539be9340f816aca92c7262",
+                "--public-key",
+                "03dba6989ee4de1e4a4710fcd6fd7fc85970f30bb0efaa9dbd5c42f43476f95907",
+                "--unframed",
+                "--legacy-challenge",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+    }
+
+    #[test]
+    fn test_cli_nonce_verify() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "--",
+                "schnorr",
+                "nonce",
+                "verify",
+                "80dd81982ed5065cd5d8845ab5a7fa256ec1a029a5c3f85753f330d4ddc74e7b",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+    }
+
+    #[test]
+    fn test_cli_nonce_verify_invalid() {
+        let output = Command::new("cargo")
+            .args(["run", "--", "schnorr", "nonce", "verify", "invalid"])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+    }
+
+    #[test]
+    fn test_cli_nonce_generate() {
+        let output = Command::new("cargo")
+            .args(["run", "--", "schnorr", "nonce", "generate"])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+    }
+
+    #[test]
+    fn test_cli_schnorr_sign() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "--",
+                "schnorr",
+                "sign",
+                "--challange",
+                "cdc2e81d4d252008dbebafcf38b3cdf912fed03f3b9d2e0d656ed00dfd3965c0",
+                "--share",
+                "cdc2e81d4d252008dbebafcf38b3cdf912fed03f3b9d2e0d656ed00dfd3965c0",
+                "--id",
+                "1",
+                "--nonce",
+                "cf54c440ec2a5245f70c109b72816d35f6331e067fb4d26691998414dec2bc64",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+    }
+
+    #[test]
+    fn test_cli_schnorr_sign_invalid_challange() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "--",
+                "schnorr",
+                "sign",
+                "--challange",
+                "cdc2e81d4d252008dbebafcf38b3cdf912fed03f3b9d2e0d656ed00dfd3965",
+                "--share",
+                "cdc2e81d4d252008dbebafcf38b3cdf912fed03f3b9d2e0d656ed00dfd3965c0",
+                "--id",
+                "1",
+                "--nonce",
+                "cf54c440ec2a5245f70c109b72816d35f6331e067fb4d26691998414dec2bc64",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(
+            std::str::from_utf8(output.stderr.as_slice())
+                .unwrap()
+                .contains("Invalid scalar length")
+        );
+        assert!(!output.status.success());
+    }
+
+    #[test]
+    fn test_cli_schnorr_partial_verify() {
+        let path = std::env::temp_dir().join(format!("shamy-partial-{}.json", std::process::id()));
+        let path_arg = path.to_str().unwrap();
+        let run = |args: &[&str]| {
+            Command::new("cargo")
+                .args(["run", "-q", "--", "--format", "json"])
+                .args(args)
+                .output()
+                .expect("Failed to execute command")
+        };
+        let json = |output: std::process::Output| {
+            serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+        };
+
+        let keygen = json(run(&["keygen", "-t", "2", "-n", "3", "--output", path_arg]));
+        let nonce = json(run(&["schnorr", "nonce", "generate"]));
+        let challenge = "cdc2e81d4d252008dbebafcf38b3cdf912fed03f3b9d2e0d656ed00dfd3965c0";
+        let partial = json(run(&[
+            "schnorr",
+            "sign",
+            "--keygen-file",
+            path_arg,
+            "--id",
+            "1",
+            "--nonce",
+            nonce["nonce"].as_str().unwrap(),
+            "--challange",
+            challenge,
+        ]));
+
+        let verify = |id: &str, public_share: &[&str]| {
+            run(&[
+                &[
+                    "schnorr",
+                    "partial-verify",
+                    "--signature",
+                    partial["s_i"].as_str().unwrap(),
+                    "--id",
+                    id,
+                    "--nonce",
+                    nonce["nonce_point"].as_str().unwrap(),
+                    "--challange",
+                    challenge,
+                ][..],
+                public_share,
+            ]
+            .concat())
+        };
+        let valid = verify("1", &["--keygen-file", path_arg]);
+        assert!(valid.status.success());
+        assert_eq!(json(valid)["valid"], true);
+
+        // checked against someone else's public share
+        let X_2 = keygen["participants"][1]["X_i"].as_str().unwrap();
+        let invalid = verify("2", &["--public-share", X_2]);
+        assert!(!invalid.status.success());
+        assert_eq!(json(invalid)["valid"], false);
+        assert!(!verify("7", &["--keygen-file", path_arg]).status.success());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_cli_schnorr_challenge() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "--",
+                "schnorr",
+                "challenge",
+                "--message",
+                "rust is best",
+                "--ids",
+                "1",
+                "2",
+                "--nonces",
+                "03d8bdbc558c9ab0887e5f672ac1ce97b5cef2dc9cd4a627a8860c54ab7c0589de",
+                "031be5375e184e2e1053e342e9cfc862af99ed423b2860319d016993f935710012",
+                "--public-key",
+                "0280525d6b92596b827a51671e74a329411ac77a29e7d077be5d23b973c3fbcf59",
+                "--unframed",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+    }
+
+    #[test]
+    fn test_cli_schnorr_challenge_framed() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "--",
+                "schnorr",
+                "challenge",
+                "--message",
+                "rust is best",
+                "--ids",
+                "1",
+                "--nonces",
+                "03d8bdbc558c9ab0887e5f672ac1ce97b5cef2dc9cd4a627a8860c54ab7c0589de",
+                "--public-key",
+                "0280525d6b92596b827a51671e74a329411ac77a29e7d077be5d23b973c3fbcf59",
+                "--app-id",
+                "treasury",
+                "--purpose",
+                "withdrawal",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+    }
+
+    #[test]
+    fn test_cli_schnorr_challenge_session_id() {
+        let challenge = |extra: &[&str]| {
+            let output = Command::new("cargo")
+                .args([
+                    "run",
+                    "-q",
+                    "--",
+                    "--format",
+                    "json",
+                    "schnorr",
+                    "challenge",
+                ])
+                .args([
+                    "--message",
+                    "rust is best",
+                    "--ids",
+                    "1",
+                    "--app-id",
+                    "treasury",
+                ])
+                .args(["--purpose", "withdrawal", "--nonces"])
+                .arg("03d8bdbc558c9ab0887e5f672ac1ce97b5cef2dc9cd4a627a8860c54ab7c0589de")
+                .arg("--public-key")
+                .arg("0280525d6b92596b827a51671e74a329411ac77a29e7d077be5d23b973c3fbcf59")
+                .args(extra)
+                .output()
+                .expect("Failed to execute command");
+            match output.status.success() {
+                true => {
+                    let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+                    Ok(value["challenge"].as_str().unwrap().to_string())
+                }
+                false => Err(output.status.code()),
+            }
+        };
+        let session_a = "11".repeat(32);
+        let session_b = "22".repeat(32);
+
+        let plain = challenge(&[]).unwrap();
+        let a = challenge(&["--session-id", &session_a]).unwrap();
+        assert_ne!(plain, a);
+        assert_ne!(a, challenge(&["--session-id", &session_b]).unwrap());
+        assert_eq!(a, challenge(&["--session-id", &session_a]).unwrap());
+
+        assert_eq!(challenge(&["--session-id", "1122"]), Err(Some(2)));
+        let legacy = ["--session-id", &session_a, "--legacy-challenge"];
+        assert_eq!(challenge(&legacy), Err(Some(2)));
+    }
+
+    #[test]
+    fn test_cli_schnorr_challenge_requires_framing() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "--",
+                "schnorr",
+                "challenge",
+                "--message",
+                "rust is best",
+                "--ids",
+                "1",
+                "--nonces",
+                "03d8bdbc558c9ab0887e5f672ac1ce97b5cef2dc9cd4a627a8860c54ab7c0589de",
+                "--public-key",
+                "0280525d6b92596b827a51671e74a329411ac77a29e7d077be5d23b973c3fbcf59",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!output.status.success());
+    }
+
+    #[test]
+    fn test_cli_dkg_ceremony() {
+        let dir = std::env::temp_dir().join(format!("shamy-dkg-{}", std::process::id()));
+        let path = |name: &str| dir.join(name).to_str().unwrap().to_string();
+        let run = |args: &[&str]| {
+            let output = Command::new("cargo")
+                .args(["run", "--", "dkg"])
+                .args(args)
+                .output()
+                .expect("Failed to execute command");
+            assert!(output.status.success());
+            String::from_utf8(output.stdout).unwrap()
+        };
+
+        for id in ["1", "2"] {
+            run(&[
+                "round1",
+                "--id",
+                id,
+                "--threshold",
+                "2",
+                "--num-shares",
+                "2",
+                "--output-dir",
+                &path(""),
+            ]);
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let metadata = std::fs::metadata(path("dkg-round1-secret-1.json")).unwrap();
+            assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+        }
+
+        for id in ["1", "2"] {
+            run(&[
+                "round2",
+                "--secret",
+                &path(&format!("dkg-round1-secret-{}.json", id)),
+                "--packages",
+                &path("dkg-round1-1.json"),
+                &path("dkg-round1-2.json"),
+                "--output-dir",
+                &path(""),
+            ]);
+        }
+
+        let public_keys = [("1", "2"), ("2", "1")].map(|(id, other)| {
+            let stdout = run(&[
+                "finalize",
+                "--secret",
+                &path(&format!("dkg-round1-secret-{}.json", id)),
+                "--round1",
+                &path("dkg-round1-1.json"),
+                &path("dkg-round1-2.json"),
+                "--round2",
+                &path(&format!("dkg-round2-{}-to-{}.json", other, id)),
+            ]);
+            stdout
+                .lines()
+                .find(|line| line.starts_with("Public key X = "))
+                .unwrap()
+                .to_string()
+        });
+
+        assert_eq!(public_keys[0], public_keys[1]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_cli_keygen_json() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "--",
+                "--format",
+                "json",
+                "keygen",
+                "--threshold",
+                "2",
+                "--num-shares",
+                "3",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(value["threshold"], 2);
+        assert_eq!(value["participants"].as_array().unwrap().len(), 3);
+        assert_eq!(value["commitments"][0], value["public_key"]);
+    }
+
+    #[test]
+    fn test_cli_keygen_backup_restore() {
+        let output = Command::new("cargo")
+            .args([
+                "run", "--", "--format", "json", "keygen", "-t", "2", "-n", "3", "--backup",
+            ])
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success());
+        let keygen: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let backup = |i: usize| keygen["participants"][i]["backup"].as_str().unwrap();
+
+        let restore = |backups: &[&str]| {
+            let mut args = vec!["run", "--", "--format", "json", "keygen", "restore"];
+            for words in backups {
+                args.extend(["--words", words]);
+            }
+            Command::new("cargo")
+                .args(args)
+                .output()
+                .expect("Failed to execute command")
+        };
+
+        let output = restore(&[backup(0), backup(2)]);
+        assert!(output.status.success());
+        let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(value["public_key"], keygen["public_key"]);
+
+        // one backup is below the recorded threshold
+        assert!(!restore(&[backup(1)]).status.success());
+    }
+
+    #[test]
+    fn test_cli_keygen_mnemonic() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "--",
+                "--format",
+                "json",
+                "keygen",
+                "--threshold",
+                "2",
+                "--num-shares",
+                "3",
+                "--mnemonic",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        for participant in value["participants"].as_array().unwrap() {
+            let share =
+                shamy_core::util::mnemonic_to_share(participant["mnemonic"].as_str().unwrap())
+                    .unwrap();
+            assert_eq!(
+                shamy_core::util::scalar_to_hex(&share),
+                participant["x_i"].as_str().unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_cli_keygen_x_only() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "--",
+                "--format",
+                "json",
+                "keygen",
+                "--threshold",
+                "2",
+                "--num-shares",
+                "3",
+                "--x-only",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let public_key = value["public_key"].as_str().unwrap();
+        assert!(public_key.starts_with("02"));
+        assert_eq!(
+            value["public_key_x_only"].as_str().unwrap(),
+            &public_key[2..]
+        );
+
+        let shares = value["participants"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| {
+                let x_i = shamy_core::util::hex_to_scalar(p["x_i"].as_str().unwrap()).unwrap();
+                (p["id"].as_u64().unwrap(), x_i)
+            })
+            .collect::<Vec<_>>();
+        let secret = shamy_core::shamir::reconstruct_secret(&shares[..2]).unwrap();
+        assert_eq!(
+            shamy_core::util::pp_to_hex(&(k256::ProjectivePoint::GENERATOR * secret)),
+            public_key
+        );
+    }
+
+    #[test]
+    fn test_cli_verify_json() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "--",
+                "schnorr",
+                "verify",
+                "--message",
+                "rust is best",
+                "--nonce",
+                "032ab98218bf256c1e9a3d7a85f451f0879867fbc0923540c4cd2928d1f4b03303",
+                "--signature",
+                "2290a650e2d62d3f3155c52284d7db29cb0674ee5539be9340f816aca92c7262",
+                "--public-key",
+                "03dba6989ee4de1e4a4710fcd6fd7fc85970f30bb0efaa9dbd5c42f43476f95907",
+                "--unframed",
+                "--format",
+                "json",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        // signed with the legacy challenge, so invalid under the default one
+        assert_eq!(output.status.code(), Some(5));
+        let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(value["valid"], false);
+    }
+
+    #[test]
+    fn test_cli_keystore_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("shamy-keystore-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("shares.json");
+        let path = path.to_str().unwrap();
+        let share = "cdc2e81d4d252008dbebafcf38b3cdf912fed03f3b9d2e0d656ed00dfd3965c0";
+        let run = |args: &[&str], passphrase: &str| {
+            Command::new("cargo")
+                .args(["run", "--", "keystore"])
+                .args(args)
+                .env("SHAMY_PASSPHRASE", passphrase)
+                .output()
+                .expect("Failed to execute command")
+        };
+
+        assert!(run(&["init", "--path", path], "").status.success());
+        assert!(!run(&["init", "--path", path], "").status.success());
+
+        let import = run(
+            &["import", "--path", path, "--id", "1", "--share", share],
+            "correct horse",
+        );
+        assert!(import.status.success());
+        assert!(!std::fs::read_to_string(path).unwrap().contains(share));
+
+        let export = run(&["export", "--path", path, "--id", "1"], "correct horse");
+        assert!(export.status.success());
+        assert!(String::from_utf8(export.stdout).unwrap().contains(share));
+
+        let wrong = run(&["export", "--path", path, "--id", "1"], "battery staple");
+        assert!(!wrong.status.success());
+        assert!(
+            String::from_utf8(wrong.stderr)
+                .unwrap()
+                .contains("Wrong passphrase for share 1")
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_cli_keygen_keystore() {
+        let dir = std::env::temp_dir().join(format!("shamy-keygen-ks-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("shares.json");
+
+        let output = Command::new("cargo")
+            .args([
+                "run", "--", "--format", "json", "keygen", "-t", "2", "-n", "2",
+            ])
+            .arg("--keystore")
+            .arg(&path)
+            .env("SHAMY_PASSPHRASE_1", "first")
+            .env("SHAMY_PASSPHRASE_2", "second")
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert!(value["participants"][0].get("x_i").is_none());
+
+        let export = Command::new("cargo")
+            .args(["run", "--", "keystore", "export", "--id", "2", "--path"])
+            .arg(&path)
+            .env("SHAMY_PASSPHRASE_2", "second")
+            .output()
+            .expect("Failed to execute command");
+        assert!(export.status.success());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_cli_ceremony_sign() {
+        let keygen = Command::new("cargo")
+            .args([
+                "run", "--", "--format", "json", "keygen", "-t", "2", "-n", "3",
+            ])
+            .output()
+            .expect("Failed to execute command");
+        assert!(keygen.status.success());
+        let keygen: serde_json::Value = serde_json::from_slice(&keygen.stdout).unwrap();
+        let public_key = keygen["public_key"].as_str().unwrap();
+        let share = |i: usize| keygen["participants"][i]["x_i"].as_str().unwrap();
+
+        let mut child = Command::new("cargo")
+            .args(["run", "--", "--format", "json", "ceremony", "sign"])
+            .args(["--app-id", "treasury", "--purpose", "withdrawal"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("Failed to execute command");
+        let input = format!(
+            "rust is best\n{}\n1\n{}\n3\n{}\n\n",
+            public_key,
+            share(0),
+            share(2)
+        );
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(input.as_bytes())
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+
+        assert!(output.status.success());
+        let signed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(signed["ids"], serde_json::json!([1, 3]));
+        let progress = String::from_utf8_lossy(&output.stderr);
+        for step in [
+            "Public share X_3",
+            "[2/6] Nonce R_1",
+            "[4/6] Partial signature s_3",
+        ] {
+            assert!(progress.contains(step), "{step}");
+        }
+
+        let verify = Command::new("cargo")
+            .args([
+                "run",
+                "--",
+                "schnorr",
+                "verify",
+                "--message",
+                "rust is best",
+            ])
+            .args(["--nonce", signed["nonce"].as_str().unwrap()])
+            .args(["--signature", signed["signature"].as_str().unwrap()])
+            .args(["--public-key", public_key])
+            .args(["--app-id", "treasury", "--purpose", "withdrawal"])
+            .output()
+            .expect("Failed to execute command");
+        assert!(
+            String::from_utf8(verify.stdout)
+                .unwrap()
+                .contains("is valid")
+        );
+    }
+
+    #[test]
+    fn test_cli_keygen_reconstruct() {
+        let keygen = Command::new("cargo")
+            .args([
+                "run", "--", "--format", "json", "keygen", "-t", "2", "-n", "3",
+            ])
+            .output()
+            .expect("Failed to execute command");
+        let keygen: serde_json::Value = serde_json::from_slice(&keygen.stdout).unwrap();
+        let public_key = keygen["public_key"].as_str().unwrap();
+        let share = |i: usize| keygen["participants"][i]["x_i"].as_str().unwrap();
+        let reconstruct = |ids: &[&str], shares: &[&str]| {
+            Command::new("cargo")
+                .args(["run", "--", "--format", "json", "keygen", "reconstruct"])
+                .arg("--ids")
+                .args(ids)
+                .arg("--shares")
+                .args(shares)
+                .args(["--public-key", public_key])
+                .output()
+                .expect("Failed to execute command")
+        };
+
+        let output = reconstruct(&["3", "1"], &[share(2), share(0)]);
+        assert!(output.status.success());
+        let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(value["public_key"], public_key);
+
+        let output = reconstruct(&["1"], &[share(0)]);
+        assert!(!output.status.success());
+        assert!(
+            String::from_utf8(output.stderr)
+                .unwrap()
+                .contains("does not match the public key")
+        );
+    }
+
+    #[test]
+    fn test_cli_keygen_limits() {
+        let output = Command::new("cargo")
+            .args(["run", "--", "keygen", "-t", "2", "-n", "10000000"])
+            .output()
+            .expect("Failed to execute command");
+        assert!(!output.status.success());
+        assert!(
+            String::from_utf8(output.stderr)
+                .unwrap()
+                .contains("Too many participants")
+        );
+
+        let output = Command::new("cargo")
+            .args(["run", "--", "keygen", "-t", "2", "-n", "20"])
+            .args(["--max-participants", "10"])
+            .output()
+            .expect("Failed to execute command");
+        assert!(!output.status.success());
+    }
+
+    #[test]
+    fn test_cli_locales() {
+        let verify = |lang: &[&str], env: &str| {
+            let output = Command::new("cargo")
+                .args([
+                    "run",
+                    "--",
+                    "schnorr",
+                    "verify",
+                    "--message",
+                    "rust is best",
+                    "--nonce",
+                    "032ab98218bf256c1e9a3d7a85f451f0879867fbc0923540c4cd2928d1f4b03303",
+                    "--signature",
+                    "2290a650e2d62d3f3155c52284d7db29cb0674ee5539be9340f816aca92c7262",
+                    "--public-key",
+                    "03dba6989ee4de1e4a4710fcd6fd7fc85970f30bb0efaa9dbd5c42f43476f95907",
+                    "--unframed",
+                    "--legacy-challenge",
+                ])
+                .args(lang)
+                .env("SHAMY_LANG", env)
+                .output()
+                .expect("Failed to execute command");
+            String::from_utf8(output.stdout).unwrap()
+        };
+
+        assert!(verify(&["--lang", "tr"], "").contains("İmza geçerli"));
+        assert!(verify(&["--lang", "es"], "").contains("La firma es válida"));
+        assert!(verify(&[], "tr_TR.UTF-8").contains("İmza geçerli"));
+        assert!(verify(&["--lang", "en"], "es").contains("Signature is valid"));
+    }
+
+    #[test]
+    fn test_cli_share_manifest() {
+        let dir = std::env::temp_dir().join(format!("shamy-manifest-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let share_dir = dir.to_str().unwrap();
+        let manifest = dir.join("manifest.json");
+        let manifest = manifest.to_str().unwrap();
+
+        let keygen = Command::new("cargo")
+            .args(["run", "--", "keygen", "-t", "2", "-n", "3"])
+            .args(["--share-dir", share_dir])
+            .output()
+            .expect("Failed to execute command");
+        assert!(keygen.status.success());
+        assert!(!String::from_utf8(keygen.stdout).unwrap().contains("x_i"));
+
+        let verify = |files: &[&str]| {
+            Command::new("cargo")
+                .args(["run", "--", "manifest", "verify", "--manifest", manifest])
+                .args(files)
+                .output()
+                .expect("Failed to execute command")
+        };
+        assert!(verify(&[]).status.success());
+
+        let share = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .find(|path| path.to_str().unwrap().ends_with("-2.json"))
+            .unwrap();
+        let share = share.to_str().unwrap();
+        assert!(verify(&[share]).status.success());
+
+        let tampered = std::fs::read_to_string(share)
+            .unwrap()
+            .replace("\"id\": 2", "\"id\": 3");
+        std::fs::write(share, tampered).unwrap();
+        let output = verify(&[share]);
+        assert!(!output.status.success());
+        assert!(
+            String::from_utf8(output.stdout)
+                .unwrap()
+                .contains("checksum mismatch")
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_cli_keygen_output_dir() {
+        let dir = std::env::temp_dir().join(format!("shamy-output-dir-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let keygen = Command::new("cargo")
+            .args(["run", "--", "keygen", "-t", "2", "-n", "3"])
+            .args(["--output-dir", dir.to_str().unwrap()])
+            .output()
+            .expect("Failed to execute command");
+        assert!(keygen.status.success());
+        assert!(!String::from_utf8(keygen.stdout).unwrap().contains("x_i"));
+
+        let read = |file: &str| -> serde_json::Value {
+            serde_json::from_slice(&std::fs::read(dir.join(file)).unwrap()).unwrap()
+        };
+        let public = read("public.json");
+        assert!(
+            public["participants"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .all(|p| p.get("x_i").is_none())
+        );
+        for id in 1..=3u64 {
+            let file = read(&format!("participant-{id}.json"));
+            assert_eq!(file["public_key"], public["public_key"]);
+            assert_eq!(file["commitments"], public["commitments"]);
+            for p in file["participants"].as_array().unwrap() {
+                assert_eq!(p.get("x_i").is_some(), p["id"] == id);
+            }
+        }
+
+        // each file only opens its own share
+        let export = |id: &str| {
+            let file = dir.join("participant-2.json");
+            Command::new("cargo")
+                .args(["run", "--", "pem", "export", "--keygen-file"])
+                .args([file.to_str().unwrap(), "--id", id])
+                .output()
+                .expect("Failed to execute command")
+        };
+        assert!(export("2").status.success());
+        assert!(!export("1").status.success());
+
+        // existing files are never overwritten
+        let again = Command::new("cargo")
+            .args(["run", "--", "keygen", "-t", "2", "-n", "3"])
+            .args(["--output-dir", dir.to_str().unwrap()])
+            .output()
+            .expect("Failed to execute command");
+        assert!(!again.status.success());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_cli_aggregation_proof() {
+        let proof = std::env::temp_dir().join(format!("shamy-proof-{}.json", std::process::id()));
+        let proof = proof.to_str().unwrap();
+
+        let keygen = Command::new("cargo")
+            .args([
+                "run", "--", "--format", "json", "keygen", "-t", "2", "-n", "3",
+            ])
+            .output()
+            .expect("Failed to execute command");
+        assert!(keygen.status.success());
+        let keygen: serde_json::Value = serde_json::from_slice(&keygen.stdout).unwrap();
+        let public_key = keygen["public_key"].as_str().unwrap();
+        let participant =
+            |i: usize, key: &str| keygen["participants"][i][key].as_str().unwrap().to_string();
+
+        let mut child = Command::new("cargo")
+            .args(["run", "--", "ceremony", "sign", "--proof", proof])
+            .args(["--app-id", "dao", "--purpose", "proposal"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("Failed to execute command");
+        let input = format!(
+            "rust is best\n{}\n1\n{}\n3\n{}\n\n",
+            public_key,
+            participant(0, "x_i"),
+            participant(2, "x_i")
+        );
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(input.as_bytes())
+            .unwrap();
+        assert!(child.wait_with_output().unwrap().status.success());
+
+        let verify = |shares: &[String]| {
+            Command::new("cargo")
+                .args(["run", "--", "schnorr", "verify-proof", "--proof", proof])
+                .args(["--public-key", public_key, "--ids", "1", "2", "3"])
+                .args(["--app-id", "dao", "--purpose", "proposal"])
+                .arg("--public-shares")
+                .args(shares)
+                .output()
+                .expect("Failed to execute command")
+        };
+        let shares = (0..3).map(|i| participant(i, "X_i")).collect::<Vec<_>>();
+        let output = verify(&shares);
+        assert!(output.status.success());
+        assert!(
+            String::from_utf8(output.stdout)
+                .unwrap()
+                .contains("participants [1, 3]")
+        );
+
+        // the proof must match the shares from keygen
+        let swapped = [shares[2].clone(), shares[1].clone(), shares[0].clone()];
+        assert!(!verify(&swapped).status.success());
+
+        std::fs::remove_file(proof).unwrap();
+    }
+
+    #[test]
+    fn test_qr_error_correction() {
+        use crate::qr::{bch_remainder, reed_solomon};
+
+        // "HELLO WORLD" as a 1-M code, from the ISO/IEC 18004 walkthrough
+        let data = [
+            32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236, 17, 236, 17,
+        ];
+        assert_eq!(
+            reed_solomon(&data, 10),
+            [196, 35, 39, 119, 235, 215, 231, 226, 93, 23]
+        );
+
+        // format info of level M with mask 5, version info of version 7
+        assert_eq!(
+            (5 << 10 | bch_remainder(5, 10, 0x537)) ^ 0x5412,
+            0b100000011001110
+        );
+        assert_eq!(7 << 12 | bch_remainder(7, 12, 0x1f25), 0b000111110010010100);
+    }
+
+    #[test]
+    fn test_cli_sign_message_file() {
+        let run = |args: &[&str], stdin: &[u8]| {
+            let mut child = Command::new("cargo")
+                .args(["run", "--", "--format", "json"])
+                .args(args)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .spawn()
+                .expect("Failed to execute command");
+            child.stdin.take().unwrap().write_all(stdin).unwrap();
+            let output = child.wait_with_output().unwrap();
+            // 5 is a signature that doesn't verify
+            assert!(matches!(output.status.code(), Some(0 | 5)), "{args:?}");
+            serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+        };
+        let field = |value: &serde_json::Value, key: &str| value[key].as_str().unwrap().to_string();
+
+        // large and not UTF-8
+        let payload = (0..=255u8).cycle().take(100_000).collect::<Vec<_>>();
+        let path = std::env::temp_dir().join("shamy-cli-test-message.bin");
+        std::fs::write(&path, &payload).unwrap();
+        let file = path.to_str().unwrap();
+
+        let keygen = run(&["keygen", "-t", "2", "-n", "2"], b"");
+        let public_key = field(&keygen, "public_key");
+        let share = |i: usize| field(&keygen["participants"][i], "x_i");
+
+        let sign = |prehash: &[&str]| {
+            let nonces = [0, 1].map(|_| run(&["schnorr", "nonce", "generate"], b""));
+            let mut args = vec!["schnorr", "challenge", "--message-file", file, "--unframed"];
+            args.extend(["--ids", "1", "--ids", "2", "--public-key", &public_key]);
+            let points = nonces.each_ref().map(|n| field(n, "nonce_point"));
+            args.extend(["--nonces", &points[0], "--nonces", &points[1]]);
+            args.extend(prehash);
+            let challenge = run(&args, b"");
+            let c = field(&challenge, "challenge");
+
+            let partials = [0, 1].map(|i| {
+                let (id, r) = ((i + 1).to_string(), field(&nonces[i], "nonce"));
+                let args = ["schnorr", "sign", "--challange", &c, "--id", &id];
+                let partial = run(
+                    &[&args[..], &["--share", &share(i), "--nonce", &r]].concat(),
+                    b"",
+                );
+                field(&partial, "s_i")
+            });
+            let R = field(&challenge, "nonce");
+            let mut args = vec![
+                "schnorr", "combine", "--ids", "1", "--ids", "2", "--nonce", &R,
+            ];
+            args.extend(["--signatures", &partials[0], "--signatures", &partials[1]]);
+            let signature = run(&args, b"");
+            (R, field(&signature, "signature"))
+        };
+        let verify = |(R, s): &(String, String), message: &[&str], stdin: &[u8]| {
+            let mut args = vec![
+                "schnorr",
+                "verify",
+                "--unframed",
+                "--public-key",
+                &public_key,
+            ];
+            args.extend(["--nonce", R, "--signature", s]);
+            args.extend(message);
+            run(&args, stdin)["valid"].as_bool().unwrap()
+        };
+
+        let signature = sign(&[]);
+        assert!(verify(&signature, &["--message-file", file], b""));
+        assert!(verify(&signature, &["--message-file", "-"], &payload));
+        assert!(!verify(&signature, &["--message-file", "-"], &payload[1..]));
+        assert!(!verify(&signature, &["--message", "rust is best"], b""));
+
+        // the digest is streamed, the signature only verifies as prehashed
+        let prehashed = sign(&["--prehash", "sha256"]);
+        let args = ["--message-file", "-", "--prehash", "sha256"];
+        assert!(verify(&prehashed, &args, &payload));
+        assert!(!verify(&prehashed, &["--message-file", file], b""));
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_cli_combine_qr() {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "--",
+                "schnorr",
+                "combine",
+                "--nonce",
+                "031cb8610733456b7f163fb088a127118ddfe10689af097eb7646c96c025b8e5ae",
+                "--ids",
+                "1",
+                "--ids",
+                "2",
+                "--signatures",
+                "4ea64f5d0b0a68762d143eb45b6e00366923dc76d4fbc9830176b42223677016",
+                "--signatures",
+                "983f3626eb6cb6dddf7c9eada612b64ba7558c35db80cee908469d50b2b9441f",
+                "--qr",
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        // a version 8 code is 49 modules wide, plus the quiet zone on both sides
+        let qr = stdout.lines().skip_while(|l| *l != "R || s").skip(1);
+        assert_eq!(qr.clone().count(), (49 + 8usize).div_ceil(2));
+        assert!(qr.clone().all(|l| l.chars().count() == 49 + 8));
+    }
+
+    #[test]
+    fn test_cli_verify_compact_signature() {
+        let verify = |signature: &str| {
+            Command::new("cargo")
+                .args([
+                    "run",
+                    "--",
+                    "schnorr",
+                    "verify",
+                    "--message",
+                    "rust is best",
+                    "--signature",
+                    signature,
+                    "--public-key",
+                    "03dba6989ee4de1e4a4710fcd6fd7fc85970f30bb0efaa9dbd5c42f43476f95907",
+                    "--unframed",
+                    "--legacy-challenge",
+                ])
+                .output()
+                .expect("Failed to execute command")
+        };
+
+        let output = verify(
+            "032ab98218bf256c1e9a3d7a85f451f0879867fbc0923540c4cd2928d1f4b03303\
+             2290a650e2d62d3f3155c52284d7db29cb0674ee5539be9340f816aca92c7262",
+        );
+        assert!(output.status.success());
+        assert!(String::from_utf8_lossy(&output.stdout).contains("Signature is valid"));
+
+        // R has odd Y, so reading x(R) back as even gives the wrong point
+        let output = verify(
+            "2ab98218bf256c1e9a3d7a85f451f0879867fbc0923540c4cd2928d1f4b03303\
+             2290a650e2d62d3f3155c52284d7db29cb0674ee5539be9340f816aca92c7262",
+        );
+        assert_eq!(output.status.code(), Some(5));
+        assert!(String::from_utf8_lossy(&output.stdout).contains("Signature is invalid"));
+
+        let output = verify("2290a650e2d62d3f3155c52284d7db29cb0674ee5539be9340f816aca92c7262");
+        assert!(!output.status.success());
+    }
+
+    #[test]
+    fn test_cli_vectors_match_committed_files() {
+        let dir = std::env::temp_dir().join(format!("shamy-vectors-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        for (seed, t, n, name) in [
+            ("01", "2", "3", "schnorr-2-of-3.json"),
+            ("02", "3", "5", "schnorr-3-of-5.json"),
+        ] {
+            let committed = format!("../../vectors/{name}");
+            let generated = dir.join(name);
+            let seed = format!("{seed:0>64}");
+            let output = Command::new("cargo")
+                .args([
+                    "run", "--", "vectors", "generate", "--seed", &seed, "-t", t, "-n", n,
+                ])
+                .args([
+                    "-m",
+                    "shamy test vector",
+                    "--app-id",
+                    "shamy",
+                    "--purpose",
+                    "test-vectors",
+                ])
+                .arg("--output")
+                .arg(&generated)
+                .output()
+                .expect("Failed to execute command");
+            assert!(output.status.success());
+            assert_eq!(
+                std::fs::read(&generated).unwrap(),
+                std::fs::read(&committed).unwrap()
+            );
+
+            let output = Command::new("cargo")
+                .args(["run", "--", "vectors", "verify", "--file", &committed])
+                .output()
+                .expect("Failed to execute command");
+            assert!(output.status.success());
+        }
+
+        // a tampered challenge is reported by name
+        let committed = std::fs::read_to_string("../../vectors/schnorr-2-of-3.json").unwrap();
+        let mut vector: serde_json::Value = serde_json::from_str(&committed).unwrap();
+        vector["challenge"] = serde_json::json!("00".repeat(31) + "01");
+        let tampered = dir.join("tampered.json");
+        std::fs::write(&tampered, vector.to_string()).unwrap();
+        let output = Command::new("cargo")
+            .args(["run", "--", "vectors", "verify", "--file"])
+            .arg(&tampered)
+            .output()
+            .expect("Failed to execute command");
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("challenge"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_cli_audit_keygen_file() {
+        let path = std::env::temp_dir().join(format!("shamy-audit-{}.json", std::process::id()));
+        let output = Command::new("cargo")
+            .args([
+                "run", "--", "--format", "json", "keygen", "-t", "2", "-n", "3", "--output",
+            ])
+            .arg(&path)
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success());
+
+        let audit = |path: &std::path::Path| {
+            Command::new("cargo")
+                .args(["run", "--", "--format", "json", "audit", "--keygen-file"])
+                .arg(path)
+                .output()
+                .expect("Failed to execute command")
+        };
+        let output = audit(&path);
+        assert!(output.status.success());
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(report["valid"], true);
+        assert_eq!(report["checks"].as_array().unwrap().len(), 6);
+
+        // swap the public shares of two participants
+        let mut keygen: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let first = keygen["participants"][0]["X_i"].clone();
+        keygen["participants"][0]["X_i"] = keygen["participants"][1]["X_i"].clone();
+        keygen["participants"][1]["X_i"] = first;
+        std::fs::write(&path, keygen.to_string()).unwrap();
+
+        let output = audit(&path);
+        assert!(!output.status.success());
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(report["valid"], false);
+        let failed = report["checks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|check| check["ok"] == false)
+            .map(|check| {
+                (
+                    check["check"].as_str().unwrap(),
+                    check["id"].as_u64().unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(failed, [("public_share", 1), ("public_share", 2)]);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_cli_sign_with_keygen_file() {
+        let path = std::env::temp_dir().join(format!("shamy-keygen-{}.json", std::process::id()));
+        let path_arg = path.to_str().unwrap();
+        let run = |args: &[&str]| {
+            let output = Command::new("cargo")
+                .args(["run", "--", "--format", "json"])
+                .args(args)
+                .output()
+                .expect("Failed to execute command");
+            assert!(output.status.success(), "{args:?}");
+            serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+        };
+
+        let printed = run(&["keygen", "-t", "2", "-n", "3", "--output", path_arg]);
+        let file: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(file["version"], 1);
+        assert!(file["created_at"].as_u64().unwrap() > 0);
+        assert_eq!(file["threshold"], 2);
+        assert_eq!(file["num_shares"], 3);
+        assert_eq!(file["public_key"], printed["public_key"]);
+        assert_eq!(file["commitments"], printed["commitments"]);
+        assert_eq!(file["participants"], printed["participants"]);
+
+        // the signing flow with every key taken from the file
+        let framing = ["--app-id", "shamy-tests", "--purpose", "keygen-file"];
+        let nonces = [1, 3].map(|_| run(&["schnorr", "nonce", "generate"]));
+        let challenge = run(&[
+            &[
+                "schnorr",
+                "challenge",
+                "-m",
+                "hello",
+                "--keygen-file",
+                path_arg,
+            ][..],
+            &["--ids", "1", "3", "--nonces"],
+            &[
+                nonces[0]["nonce_point"].as_str().unwrap(),
+                nonces[1]["nonce_point"].as_str().unwrap(),
+            ],
+            &framing,
+        ]
+        .concat());
+        let partials = [1, 3].iter().zip(&nonces).map(|(id, nonce)| {
+            run(&[
+                "schnorr",
+                "sign",
+                "--keygen-file",
+                path_arg,
+                "--id",
+                &id.to_string(),
+                "--nonce",
+                nonce["nonce"].as_str().unwrap(),
+                "--challange",
+                challenge["challenge"].as_str().unwrap(),
+            ])["s_i"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        });
+        let signature = run(&[
+            &["schnorr", "combine", "--ids", "1", "3", "--nonce"][..],
+            &[challenge["nonce"].as_str().unwrap(), "--signatures"],
+            &partials
+                .collect::<Vec<_>>()
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>(),
+        ]
+        .concat());
+        let verified = run(&[
+            &[
+                "schnorr",
+                "verify",
+                "-m",
+                "hello",
+                "--keygen-file",
+                path_arg,
+            ][..],
+            &["--signature", signature["compact"].as_str().unwrap()],
+            &framing,
+        ]
+        .concat());
+        assert_eq!(verified["valid"], true);
+
+        // a share the file doesn't have
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "--",
+                "schnorr",
+                "sign",
+                "--keygen-file",
+                path_arg,
+                "--id",
+                "7",
+            ])
+            .args(["--nonce", nonces[0]["nonce"].as_str().unwrap()])
+            .args(["--challange", challenge["challenge"].as_str().unwrap()])
+            .output()
+            .expect("Failed to execute command");
+        assert!(!output.status.success());
+        assert!(
+            String::from_utf8(output.stderr)
+                .unwrap()
+                .contains("Keygen file does not contain share 7")
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_cli_nonce_pool() {
+        let dir = std::env::temp_dir().join(format!("shamy-nonce-pool-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let keygen = dir.join("keygen.json");
+        let pool = dir.join("pool.json");
+        let (keygen_arg, pool_arg) = (keygen.to_str().unwrap(), pool.to_str().unwrap());
+        let run = |args: &[&str]| {
+            Command::new("cargo")
+                .args(["run", "-q", "--", "--format", "json"])
+                .args(args)
+                .output()
+                .expect("Failed to execute command")
+        };
+        let json = |output: std::process::Output| {
+            assert!(output.status.success());
+            serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+        };
+
+        json(run(&[
+            "keygen", "-t", "2", "-n", "3", "--output", keygen_arg,
+        ]));
+        let generated = json(run(&[
+            "schnorr", "nonce", "generate", "--count", "3", "--output", pool_arg,
+        ]));
+        assert_eq!(generated["nonce_points"].as_array().unwrap().len(), 3);
+        assert_eq!(generated["nonce_points"][2]["index"], 2);
+        assert_eq!(generated["remaining"], 3);
+        assert!(!std::fs::read_to_string(&pool).unwrap().is_empty());
+
+        let sign = |index: &str| {
+            run(&[
+                "schnorr",
+                "sign",
+                "--keygen-file",
+                keygen_arg,
+                "--id",
+                "1",
+                "--nonce-pool",
+                pool_arg,
+                "--nonce-index",
+                index,
+                "--challange",
+                "0000000000000000000000000000000000000000000000000000000000000001",
+            ])
+        };
+        json(sign("1"));
+        let pool_file: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&pool).unwrap()).unwrap();
+        assert_eq!(pool_file["nonces"].as_array().unwrap().len(), 2);
+
+        // a used index and one never generated
+        for (index, message) in [("1", "already used"), ("9", "unknown")] {
+            let output = sign(index);
+            assert_eq!(output.status.code(), Some(6));
+            let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+            assert!(
+                error["error"]["message"]
+                    .as_str()
+                    .unwrap()
+                    .contains(message)
+            );
+        }
+
+        // more nonces continue after the highest index
+        let generated = json(run(&["schnorr", "nonce", "generate", "-o", pool_arg]));
+        assert_eq!(generated["nonce_points"][0]["index"], 3);
+        assert_eq!(generated["remaining"], 3);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_cli_nonce_registry() {
+        let path = std::env::temp_dir().join(format!("shamy-registry-{}.txt", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let sign = |challenge: &str| {
+            Command::new("cargo")
+                .args(["run", "-q", "--", "schnorr", "sign", "--id", "1"])
+                .args(["--share", &"11".repeat(32), "--nonce", &"22".repeat(32)])
+                .args(["--challange", challenge, "--nonce-registry"])
+                .arg(&path)
+                .output()
+                .expect("Failed to execute command")
+        };
+
+        assert!(sign(&"33".repeat(32)).status.success());
+        let output = sign(&"44".repeat(32));
+        assert_eq!(output.status.code(), Some(6));
+        assert!(String::from_utf8_lossy(&output.stderr).contains("another challenge"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 1);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_cli_repair() {
+        let dir = std::env::temp_dir().join(format!("shamy-repair-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let keygen = dir.join("keygen.json");
+        let (dir_arg, keygen_arg) = (dir.to_str().unwrap(), keygen.to_str().unwrap());
+        let run = |args: &[&str]| {
+            let output = Command::new("cargo")
+                .args(["run", "-q", "--", "--format", "json"])
+                .args(args)
+                .output()
+                .expect("Failed to execute command");
+            assert!(output.status.success(), "{args:?}");
+            serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+        };
+        let files = |value: serde_json::Value| -> Vec<String> {
+            serde_json::from_value(value["files"].clone()).unwrap()
+        };
+
+        let keygen_output = run(&["keygen", "-t", "2", "-n", "3", "--output", keygen_arg]);
+        let rounds = |command: &str, helpers: &[&str]| {
+            for id in ["1", "3"] {
+                let written = files(run(&[
+                    &[command, "round1", "--id", id, "--keygen-file", keygen_arg][..],
+                    helpers,
+                    &["--output-dir", dir_arg],
+                ]
+                .concat()));
+                assert_eq!(written.len(), 2);
+            }
+            let sums = ["1", "3"].map(|id| {
+                let packages = [
+                    dir.join(format!("{command}-round1-1-to-{id}.json")),
+                    dir.join(format!("{command}-round1-3-to-{id}.json")),
+                ];
+                let round2 = match command {
+                    "enroll" => vec!["round2", "--id", id, "--keygen-file", keygen_arg],
+                    _ => vec!["round2", "--id", id],
+                };
+                files(run(&[
+                    &[command][..],
+                    &round2,
+                    helpers,
+                    &["--output-dir", dir_arg, "--packages"],
+                    &[packages[0].to_str().unwrap(), packages[1].to_str().unwrap()],
+                ]
+                .concat()))
+                .remove(0)
+            });
+            run(&[
+                &[command, "finalize", "--keygen-file", keygen_arg][..],
+                helpers,
+                &["--packages", &sums[0], &sums[1]],
+            ]
+            .concat())
+        };
+
+        let repaired = rounds("repair", &["--helpers", "1", "3", "--lost", "2"]);
+        assert_eq!(repaired["x_i"], keygen_output["participants"][1]["x_i"]);
+        assert_eq!(repaired["X_i"], keygen_output["participants"][1]["X_i"]);
+
+        // a fourth participant on the same key, its share on the group polynomial
+        let enrolled = rounds("enroll", &["--helpers", "1", "3", "--new-id", "4"]);
+        assert_eq!(enrolled["id"], 4);
+        let commitments = keygen_output["commitments"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| shamy_core::util::hex_to_pp(c.as_str().unwrap()).unwrap())
+            .collect::<Vec<_>>();
+        let x_i = shamy_core::util::hex_to_scalar(enrolled["x_i"].as_str().unwrap()).unwrap();
+        assert!(shamy_core::vss::verify_share(4, x_i, &commitments));
+        assert_eq!(
+            enrolled["X_i"],
+            shamy_core::util::pp_to_hex(&shamy_core::vss::public_share(4, &commitments))
+        );
+
+        // an id from the keygen file is a repair, not an enrollment
+        let output = Command::new("cargo")
+            .args(["run", "-q", "--", "enroll", "round1", "--id", "1"])
+            .args([
+                "--keygen-file",
+                keygen_arg,
+                "--helpers",
+                "1",
+                "3",
+                "--new-id",
+                "2",
+            ])
+            .args(["--output-dir", dir_arg])
+            .output()
+            .expect("Failed to execute command");
+        assert_eq!(output.status.code(), Some(6));
+        assert!(String::from_utf8_lossy(&output.stderr).contains("id 2"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_cli_revoke() {
+        let dir = std::env::temp_dir().join(format!("shamy-revoke-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let [keygen, refreshed, record] =
+            ["keygen.json", "refreshed.json", "revocation.json"].map(|name| dir.join(name));
+        let [keygen, refreshed, record] =
+            [&keygen, &refreshed, &record].map(|path| path.to_str().unwrap());
+        let run = |args: &[&str]| {
+            Command::new("cargo")
+                .args(["run", "-q", "--", "--format", "json"])
+                .args(args)
+                .output()
+                .expect("Failed to execute command")
+        };
+        let json = |output: std::process::Output| {
+            assert!(output.status.success());
+            serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+        };
+        let framing = ["--app-id", "shamy-tests", "--purpose", "revocation"];
+
+        let keygen_output = json(run(&["keygen", "-t", "2", "-n", "3", "--output", keygen]));
+        let revoked = json(run(&[
+            &["revoke", "--keygen-file", keygen, "--id", "2"][..],
+            &["--output", refreshed, "--record", record],
+            &framing,
+        ]
+        .concat()));
+        assert_eq!(revoked["participants"], serde_json::json!([1, 3]));
+        assert_eq!(revoked["public_key"], keygen_output["public_key"]);
+        assert!(run(&["audit", "--keygen-file", refreshed]).status.success());
+
+        let verified = json(run(&[
+            &["revoke", "verify", "--record", record, "--public-key"][..],
+            &[keygen_output["public_key"].as_str().unwrap()],
+            &framing,
+        ]
+        .concat()));
+        assert_eq!(verified["id"], 2);
+        assert_eq!(verified["X_i"], keygen_output["participants"][1]["X_i"]);
+        let output = run(&["revoke", "verify", "--record", record, "--unframed"]);
+        assert_eq!(output.status.code(), Some(5));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_cli_schnorr_sign_file() {
+        let dir = std::env::temp_dir().join(format!("shamy-sign-file-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("release.tar");
+        std::fs::write(&path, b"release contents").unwrap();
+
+        let keygen = Command::new("cargo")
+            .args([
+                "run", "--", "--format", "json", "keygen", "-t", "2", "-n", "3",
+            ])
+            .output()
+            .expect("Failed to execute command");
+        let keygen: serde_json::Value = serde_json::from_slice(&keygen.stdout).unwrap();
+        let public_key = keygen["public_key"].as_str().unwrap();
+        let share = |i: usize| keygen["participants"][i]["x_i"].as_str().unwrap();
+
+        let mut child = Command::new("cargo")
+            .args([
+                "run",
+                "-q",
+                "--",
+                "--format",
+                "json",
+                "schnorr",
+                "sign-file",
+            ])
+            .arg(&path)
+            .args(["--public-key", public_key, "--unframed"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("Failed to execute command");
+        let input = format!("1\n{}\n3\n{}\n\n", share(0), share(2));
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(input.as_bytes())
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success());
+        let signed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(signed["ids"], serde_json::json!([1, 3]));
+        assert_eq!(signed["hash"], "sha256");
+        assert!(dir.join("release.tar.shamy.sig").exists());
+
+        let verify = |public_key: &str| {
+            Command::new("cargo")
+                .args([
+                    "run",
+                    "-q",
+                    "--",
+                    "--format",
+                    "json",
+                    "schnorr",
+                    "verify-file",
+                ])
+                .arg(&path)
+                .args(["--public-key", public_key, "--unframed"])
+                .output()
+                .expect("Failed to execute command")
+        };
+        let output = verify(public_key);
+        assert!(output.status.success());
+        let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(value["valid"], true);
+        assert_eq!(value["fingerprint"], signed["fingerprint"]);
+
+        // another key
+        let output = verify(keygen["participants"][0]["X_i"].as_str().unwrap());
+        assert_eq!(output.status.code(), Some(5));
+
+        // a signature combined elsewhere is checked before it is written
+        let copy = dir.join("copy.sig");
+        let attach = |signature: &str| {
+            Command::new("cargo")
+                .args(["run", "-q", "--", "schnorr", "sign-file"])
+                .arg(&path)
+                .args(["--public-key", public_key, "--unframed", "--signature"])
+                .arg(signature)
+                .arg("--output")
+                .arg(&copy)
+                .output()
+                .expect("Failed to execute command")
+        };
+        assert!(
+            attach(signed["signature"].as_str().unwrap())
+                .status
+                .success()
+        );
+        let mut forged = signed["signature"].as_str().unwrap().to_string();
+        forged.replace_range(forged.len() - 2.., "00");
+        assert_eq!(attach(&forged).status.code(), Some(5));
+
+        std::fs::write(&path, b"release contents, patched").unwrap();
+        let output = verify(public_key);
+        assert_eq!(output.status.code(), Some(5));
+        let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(value["valid"], false);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_cli_pem() {
+        let dir = std::env::temp_dir().join(format!("shamy-pem-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let keygen_file = dir.join("keygen.json");
+        let keygen = Command::new("cargo")
+            .args([
+                "run", "-q", "--", "--format", "json", "keygen", "-t", "2", "-n", "3",
+            ])
+            .arg("--output")
+            .arg(&keygen_file)
+            .output()
+            .expect("Failed to execute command");
+        assert!(keygen.status.success());
+        let keygen: serde_json::Value = serde_json::from_slice(&keygen.stdout).unwrap();
+
+        let run = |args: &[&str]| {
+            let output = Command::new("cargo")
+                .args(["run", "-q", "--", "--format", "json", "pem"])
+                .args(args)
+                .output()
+                .expect("Failed to execute command");
+            assert!(output.status.success());
+            serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+        };
+        let keygen_arg = keygen_file.to_str().unwrap();
+
+        let group = run(&["export", "--keygen-file", keygen_arg]);
+        assert_eq!(group["label"], "SHAMY GROUP KEY");
+        assert!(group["pem"].as_str().unwrap().contains("Threshold: 2\n"));
+        let spki = run(&["export", "--keygen-file", keygen_arg, "--spki"]);
+        assert!(
+            spki["pem"]
+                .as_str()
+                .unwrap()
+                .starts_with("-----BEGIN PUBLIC KEY-----\n")
+        );
+
+        let share_path = dir.join("share-2.pem");
+        run(&[
+            "export",
+            "--keygen-file",
+            keygen_arg,
+            "--id",
+            "2",
+            "--output",
+            share_path.to_str().unwrap(),
+        ]);
+        let share = run(&["import", share_path.to_str().unwrap()]);
+        assert_eq!(share["id"], 2);
+        assert_eq!(share["threshold"], 2);
+        assert_eq!(share["x_i"], keygen["participants"][1]["x_i"]);
+        assert_eq!(share["public_key"], keygen["public_key"]);
+
+        let tampered = dir.join("tampered.pem");
+        let pem = std::fs::read_to_string(&share_path).unwrap();
+        std::fs::write(&tampered, pem.replace("Id: 2", "Id: 3")).unwrap();
+        let output = Command::new("cargo")
+            .args(["run", "-q", "--", "pem", "import"])
+            .arg(&tampered)
+            .output()
+            .expect("Failed to execute command");
+        assert!(!output.status.success());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_cli_encrypted_shares() {
+        let dir = std::env::temp_dir().join(format!("shamy-recipient-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let run = |args: &[&str]| {
+            Command::new("cargo")
+                .args(["run", "-q", "--", "--format", "json"])
+                .args(args)
+                .output()
+                .expect("Failed to execute command")
+        };
+        let json = |output: std::process::Output| {
+            assert!(output.status.success());
+            serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+        };
+
+        let key_files = (1..=3)
+            .map(|i| dir.join(format!("recipient-{i}.json")))
+            .collect::<Vec<_>>();
+        let recipients = key_files
+            .iter()
+            .map(|path| {
+                let key = json(run(&[
+                    "recipient",
+                    "generate",
+                    "-o",
+                    path.to_str().unwrap(),
+                ]));
+                assert!(key.get("secret_key").is_none());
+                key["public_key"].as_str().unwrap().to_string()
+            })
+            .collect::<Vec<_>>();
+
+        let mut args = vec!["keygen", "-t", "2", "-n", "3", "--encrypt-to"];
+        args.extend(recipients.iter().map(String::as_str));
+        let keygen = json(run(&args));
+        let participant = &keygen["participants"][1];
+        assert!(participant.get("x_i").is_none());
+        let share = participant["encrypted_share"].as_str().unwrap();
+
+        let decrypted = json(run(&[
+            "recipient",
+            "decrypt",
+            "--key-file",
+            key_files[1].to_str().unwrap(),
+            "--share",
+            share,
+        ]));
+        assert_eq!(decrypted["id"], 2);
+        assert_eq!(decrypted["X_i"], participant["X_i"]);
+
+        // only the intended recipient can open it
+        let wrong_key = run(&[
+            "recipient",
+            "decrypt",
+            "--key-file",
+            key_files[0].to_str().unwrap(),
+            "--share",
+            share,
+        ]);
+        assert!(!wrong_key.status.success());
+
+        // one recipient per share
+        let too_few = run(&[
+            "keygen",
+            "-t",
+            "2",
+            "-n",
+            "3",
+            "--encrypt-to",
+            &recipients[0],
+        ]);
+        assert_eq!(too_few.status.code(), Some(3));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_cli_sealed_participant_files() {
+        let dir = std::env::temp_dir().join(format!("shamy-sealed-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let out = dir.join("keys");
+        let run = |args: &[&str], passphrase: &str| {
+            Command::new("cargo")
+                .args(["run", "-q", "--", "--format", "json"])
+                .args(args)
+                .env("SHAMY_PASSPHRASE", passphrase)
+                .output()
+                .expect("Failed to execute command")
+        };
+
+        let key_file = dir.join("recipient.json");
+        let key = run(
+            &["recipient", "generate", "-o", key_file.to_str().unwrap()],
+            "",
+        );
+        let key: serde_json::Value = serde_json::from_slice(&key.stdout).unwrap();
+        let recipient = format!("2={}", key["public_key"].as_str().unwrap());
+
+        // passphrases only seal participant files
+        let keygen = run(&["keygen", "-t", "2", "-n", "3", "--passphrase"], "secret");
+        assert!(!keygen.status.success());
+        let keygen = |recipient: &str| {
+            run(
+                &[
+                    "keygen",
+                    "-t",
+                    "2",
+                    "-n",
+                    "3",
+                    "--output-dir",
+                    out.to_str().unwrap(),
+                    "--encrypt-to",
+                    recipient,
+                    "--passphrase",
+                ],
+                "secret",
+            )
+        };
+        assert!(!keygen("4=02aa").status.success());
+        assert!(keygen(&recipient).status.success());
+
+        // no share is left readable on disk
+        for entry in std::fs::read_dir(&out).unwrap() {
+            let data = std::fs::read_to_string(entry.unwrap().path()).unwrap();
+            assert!(!data.contains("\"x_i\""));
+        }
+
+        // share 1 opens with its passphrase
+        let export = |id: &str, passphrase: &str| {
+            let file = out.join(format!("participant-{id}.json"));
+            let file = file.to_str().unwrap();
+            run(
+                &["pem", "export", "--keygen-file", file, "--id", id],
+                passphrase,
+            )
+        };
+        assert!(export("1", "secret").status.success());
+        assert!(!export("1", "wrong").status.success());
+        assert!(!export("2", "secret").status.success());
+
+        // share 2 opens with the recipient key
+        let decrypted = run(
+            &[
+                "recipient",
+                "decrypt",
+                "--key-file",
+                key_file.to_str().unwrap(),
+                "--keygen-file",
+                out.join("participant-2.json").to_str().unwrap(),
+            ],
+            "",
+        );
+        assert!(decrypted.status.success());
+        let decrypted: serde_json::Value = serde_json::from_slice(&decrypted.stdout).unwrap();
+        assert_eq!(decrypted["id"], 2);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_cli_signer_serve() {
+        let dir = std::env::temp_dir().join(format!("shamy-signer-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("shares.json");
+
+        let keygen = Command::new("cargo")
+            .args([
+                "run", "--", "--format", "json", "keygen", "-t", "2", "-n", "2",
+            ])
+            .arg("--keystore")
+            .arg(&path)
+            .env("SHAMY_PASSPHRASE", "secret")
+            .output()
+            .expect("Failed to execute command");
+        assert!(keygen.status.success());
+        let keygen: serde_json::Value = serde_json::from_slice(&keygen.stdout).unwrap();
+        let public_key = keygen["public_key"].as_str().unwrap();
+
+        let serve = |passphrase: &str, rule: &[&str]| {
+            Command::new("cargo")
+                .args(["run", "--", "signer", "serve", "-i", "1", "--keystore"])
+                .arg(&path)
+                .args(["-p", public_key, "--coordinator", "127.0.0.1:7000"])
+                .args(["--app-id", "treasury", "--purpose", "withdrawal"])
+                .args(rule)
+                .env("SHAMY_PASSPHRASE", passphrase)
+                .output()
+                .expect("Failed to execute command")
+        };
+
+        // the share is decrypted before the daemon listens
+        let output = serve("wrong", &["--allow-prefix", "pay "]);
+        assert_eq!(output.status.code(), Some(5));
+        // a digest that is not 32 bytes
+        let output = serve("secret", &["--allow-sha256", "abcd"]);
+        assert_eq!(output.status.code(), Some(3));
+        // at least one rule, or --allow-any
+        let output = serve("secret", &[]);
+        assert!(!output.status.success());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_cli_keystore_copy() {
+        let dir = std::env::temp_dir().join(format!("shamy-keystore-copy-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (from, to) = (dir.join("from.json"), dir.join("to.json"));
+        let (from, to) = (from.to_str().unwrap(), to.to_str().unwrap());
+        let run = |args: &[&str], passphrase: &str| {
+            Command::new("cargo")
+                .args(["run", "--", "keystore"])
+                .args(args)
+                .env("SHAMY_PASSPHRASE", passphrase)
+                .output()
+                .expect("Failed to execute command")
+        };
+        let share = "cdc2e81d4d252008dbebafcf38b3cdf912fed03f3b9d2e0d656ed00dfd3965c0";
+
+        assert!(run(&["init", "--path", from], "").status.success());
+        for (id, passphrase) in [("1", "first"), ("2", "second")] {
+            let import = run(
+                &["import", "--path", from, "--id", id, "--share", share],
+                passphrase,
+            );
+            assert!(import.status.success());
+        }
+
+        // `to` is created, the shares stay encrypted under their passphrases
+        let copy = run(&["copy", "--from", from, "--to", to, "--ids", "2"], "");
+        assert!(copy.status.success());
+        let export = run(&["export", "--path", to, "--id", "2"], "second");
+        assert!(String::from_utf8(export.stdout).unwrap().contains(share));
+        assert!(
+            !run(&["export", "--path", to, "--id", "1"], "first")
+                .status
+                .success()
+        );
+
+        let again = run(&["copy", "--from", from, "--to", to], "");
+        assert_eq!(again.status.code(), Some(3));
+        assert!(
+            String::from_utf8(again.stderr)
+                .unwrap()
+                .contains("already contains share 2")
+        );
+
+        // the default build has no keychain support
+        let keyring = run(&["copy", "--from", from, "--to", "keyring:shamy-test"], "");
+        assert_eq!(keyring.status.code(), Some(3));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(feature = "keyring")]
+    #[test]
+    fn test_keyring_mock_store() {
+        use crate::keyring::platform;
+        use keyring::mock::{self, MockCredential};
+
+        keyring::set_default_credential_builder(mock::default_credential_builder());
+        let entry = platform::entry("shamy-test").unwrap();
+        assert!(platform::read(&entry).unwrap().is_none());
+
+        let keystore = br#"{"version":1,"shares":[]}"#;
+        platform::write(&entry, keystore).unwrap();
+        assert_eq!(platform::read(&entry).unwrap().unwrap(), keystore);
+        platform::write(&entry, b"{}").unwrap();
+        assert_eq!(platform::read(&entry).unwrap().unwrap(), b"{}");
+
+        // failures of the store are errors, not a missing keystore
+        let credential: &MockCredential = entry.get_credential().downcast_ref().unwrap();
+        credential.set_error(keyring::Error::NoStorageAccess("locked".into()));
+        assert!(platform::read(&entry).is_err());
+        credential.set_error(keyring::Error::TooLong("secret".to_string(), 2560));
+        assert!(platform::write(&entry, keystore).is_err());
+        assert_eq!(platform::read(&entry).unwrap().unwrap(), b"{}");
+    }
+
+    #[test]
+    fn test_cli_keygen_trivial_threshold() {
+        let keygen = |extra: &[&str]| {
+            Command::new("cargo")
+                .args([
+                    "run", "-q", "--", "--format", "json", "keygen", "-t", "1", "-n", "3",
+                ])
+                .args(extra)
+                .output()
+                .expect("Failed to execute command")
+        };
+
+        let refused = keygen(&[]);
+        assert_eq!(refused.status.code(), Some(6));
+        assert!(
+            String::from_utf8(refused.stderr)
+                .unwrap()
+                .contains("1-of-n must be allowed explicitly")
+        );
+
+        let output = keygen(&["--allow-trivial-threshold"]);
+        assert!(output.status.success());
+        let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let shares = value["participants"].as_array().unwrap();
+        assert_eq!(shares.len(), 3);
+        assert!(shares.iter().all(|p| p["x_i"] == shares[0]["x_i"]));
+    }
+
+    #[test]
+    fn test_cli_exit_codes() {
+        let run = |args: &[&str]| {
+            let output = Command::new("cargo")
+                .args(["run", "-q", "--", "--format", "json"])
+                .args(args)
+                .output()
+                .expect("Failed to execute command");
+            let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+            (output.status.code(), error["error"]["kind"].clone())
+        };
+
+        // bad hex, a missing file and a rejected threshold each have their own code
+        assert_eq!(
+            run(&[
+                "schnorr", "sign", "-c", "zz", "-s", "01", "-i", "1", "-n", "01"
+            ]),
+            (Some(3), serde_json::json!("invalid_input"))
+        );
+        assert_eq!(
+            run(&["audit", "--keygen-file", "/nonexistent/keygen.json"]),
+            (Some(4), serde_json::json!("io"))
+        );
+        assert_eq!(
+            run(&["keygen", "-t", "5", "-n", "3"]),
+            (Some(6), serde_json::json!("protocol"))
+        );
+
+        // text errors are a single line, without a panic message
+        let output = Command::new("cargo")
+            .args(["run", "-q", "--", "keygen", "-t", "5", "-n", "3"])
+            .output()
+            .expect("Failed to execute command");
+        assert_eq!(output.status.code(), Some(6));
+        assert_eq!(
+            String::from_utf8(output.stderr).unwrap(),
+            "Error: Threshold 5 exceeds the 3 participants, no quorum could ever sign\n"
+        );
+    }
+}
@@ -0,0 +1,301 @@
+use crate::error::{CliError, fail};
+use crate::i18n::Msg;
+use crate::output::Output;
+use crate::parser::{DkgCommands, OutputFormat};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::json;
+use shamy_core::{
+    Error, Limits,
+    dkg::{self, Round1Package, Round1Secret, Round2Package},
+    proofs::SchnorrProof,
+    util::{hex_to_pp, hex_to_scalar, pp_to_hex, scalar_to_hex},
+};
+use std::{
+    fmt::Write as _,
+    fs::{self, File, OpenOptions},
+    io::BufWriter,
+    path::{Path, PathBuf},
+};
+
+#[derive(Serialize, Deserialize)]
+struct Round1SecretFile {
+    id: u64,
+    threshold: usize,
+    num_shares: usize,
+    coefficients: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Round1PackageFile {
+    id: u64,
+    commitments: Vec<String>,
+    proof_r: String,
+    proof_z: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Round2PackageFile {
+    sender: u64,
+    receiver: u64,
+    share: String,
+}
+
+impl From<&Round1Secret> for Round1SecretFile {
+    fn from(secret: &Round1Secret) -> Self {
+        Self {
+            id: secret.id,
+            threshold: secret.threshold,
+            num_shares: secret.num_shares,
+            coefficients: secret.coefficients.iter().map(scalar_to_hex).collect(),
+        }
+    }
+}
+
+impl TryFrom<Round1SecretFile> for Round1Secret {
+    type Error = Error;
+
+    fn try_from(file: Round1SecretFile) -> Result<Self, Error> {
+        Ok(Self {
+            id: file.id,
+            threshold: file.threshold,
+            num_shares: file.num_shares,
+            coefficients: file
+                .coefficients
+                .iter()
+                .map(|c| hex_to_scalar(c))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl From<&Round1Package> for Round1PackageFile {
+    fn from(package: &Round1Package) -> Self {
+        Self {
+            id: package.id,
+            commitments: package.commitments.iter().map(pp_to_hex).collect(),
+            proof_r: pp_to_hex(&package.proof.R),
+            proof_z: scalar_to_hex(&package.proof.z),
+        }
+    }
+}
+
+impl TryFrom<Round1PackageFile> for Round1Package {
+    type Error = Error;
+
+    fn try_from(file: Round1PackageFile) -> Result<Self, Error> {
+        Ok(Self {
+            id: file.id,
+            commitments: file
+                .commitments
+                .iter()
+                .map(|c| hex_to_pp(c))
+                .collect::<Result<_, _>>()?,
+            proof: SchnorrProof {
+                R: hex_to_pp(&file.proof_r)?,
+                z: hex_to_scalar(&file.proof_z)?,
+            },
+        })
+    }
+}
+
+impl From<&Round2Package> for Round2PackageFile {
+    fn from(package: &Round2Package) -> Self {
+        Self {
+            sender: package.sender,
+            receiver: package.receiver,
+            share: scalar_to_hex(&package.share),
+        }
+    }
+}
+
+impl TryFrom<Round2PackageFile> for Round2Package {
+    type Error = Error;
+
+    fn try_from(file: Round2PackageFile) -> Result<Self, Error> {
+        Ok(Self {
+            sender: file.sender,
+            receiver: file.receiver,
+            share: hex_to_scalar(&file.share)?,
+        })
+    }
+}
+
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> T {
+    let file = File::open(path)
+        .unwrap_or_else(|e| fail(CliError::io(Msg::CannotRead(path, &e.to_string()))));
+    serde_json::from_reader(file)
+        .unwrap_or_else(|e| fail(CliError::input(Msg::CannotRead(path, &e.to_string()))))
+}
+
+pub fn write_json<T: Serialize>(path: &Path, value: &T) -> PathBuf {
+    write_json_to(path, value, File::create(path))
+}
+
+/// like `write_json`, readable by the owner only (0600 on unix), for state
+/// that holds secret polynomials or shares.
+pub fn write_secret_json<T: Serialize>(path: &Path, value: &T) -> PathBuf {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let file = options.open(path).and_then(|file| {
+        // an existing file keeps its mode on open
+        #[cfg(unix)]
+        file.set_permissions(std::os::unix::fs::PermissionsExt::from_mode(0o600))?;
+        Ok(file)
+    });
+    write_json_to(path, value, file)
+}
+
+fn write_json_to<T: Serialize>(path: &Path, value: &T, file: std::io::Result<File>) -> PathBuf {
+    let cannot_write = |e: &dyn std::error::Error| -> ! {
+        fail(CliError::io(Msg::CannotWrite(path, &e.to_string())))
+    };
+    let file = file.unwrap_or_else(|e| cannot_write(&e));
+    serde_json::to_writer_pretty(BufWriter::new(file), value).unwrap_or_else(|e| cannot_write(&e));
+    path.to_path_buf()
+}
+
+pub fn create_dir(dir: &Path) {
+    fs::create_dir_all(dir)
+        .unwrap_or_else(|e| fail(CliError::io(Msg::CannotWrite(dir, &e.to_string()))));
+}
+
+pub fn written_files(paths: &[PathBuf]) -> Output {
+    let text = paths
+        .iter()
+        .map(|path| Msg::Wrote(path).to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    Output::new(text, json!({ "files": paths }))
+}
+
+fn read_secret(path: &Path) -> Round1Secret {
+    read_json::<Round1SecretFile>(path)
+        .try_into()
+        .unwrap_or_else(|e: Error| fail(e))
+}
+
+fn read_round1_packages(paths: &[PathBuf]) -> Vec<Round1Package> {
+    paths
+        .iter()
+        .map(|path| {
+            read_json::<Round1PackageFile>(path)
+                .try_into()
+                .unwrap_or_else(|e: Error| fail(e))
+        })
+        .collect()
+}
+
+fn read_round2_packages(paths: &[PathBuf]) -> Vec<Round2Package> {
+    paths
+        .iter()
+        .map(|path| {
+            read_json::<Round2PackageFile>(path)
+                .try_into()
+                .unwrap_or_else(|e: Error| fail(e))
+        })
+        .collect()
+}
+
+pub fn run(command: DkgCommands, format: OutputFormat, limits: &Limits) {
+    match command {
+        DkgCommands::Round1 {
+            id,
+            threshold,
+            num_shares,
+            output_dir,
+        } => {
+            let (secret, package) =
+                dkg::round1_with_limits(id, threshold as usize, num_shares as usize, limits)
+                    .unwrap_or_else(|e| fail(e));
+
+            create_dir(&output_dir);
+            let files = [
+                write_secret_json(
+                    &output_dir.join(format!("dkg-round1-secret-{}.json", id)),
+                    &Round1SecretFile::from(&secret),
+                ),
+                write_json(
+                    &output_dir.join(format!("dkg-round1-{}.json", id)),
+                    &Round1PackageFile::from(&package),
+                ),
+            ];
+            written_files(&files).print(format);
+        }
+        DkgCommands::Round2 {
+            secret,
+            packages,
+            output_dir,
+        } => {
+            let secret = read_secret(&secret);
+            let round1_packages = read_round1_packages(&packages);
+            let round2_packages =
+                dkg::round2(&secret, &round1_packages).unwrap_or_else(|e| fail(e));
+
+            create_dir(&output_dir);
+            let files = round2_packages
+                .iter()
+                .map(|package| {
+                    write_json(
+                        &output_dir.join(format!(
+                            "dkg-round2-{}-to-{}.json",
+                            package.sender, package.receiver
+                        )),
+                        &Round2PackageFile::from(package),
+                    )
+                })
+                .collect::<Vec<_>>();
+            written_files(&files).print(format);
+        }
+        DkgCommands::Finalize {
+            secret,
+            round1,
+            round2,
+            output: output_file,
+        } => {
+            let secret = read_secret(&secret);
+            let round1_packages = read_round1_packages(&round1);
+            let round2_packages = read_round2_packages(&round2);
+            let dkg_output = dkg::finalize(&secret, &round1_packages, &round2_packages)
+                .unwrap_or_else(|e| fail(e));
+
+            let participant = &dkg_output.participant;
+            let mut text = String::new();
+            writeln!(text, "{}", Msg::ParticipantHeader(participant.id)).unwrap();
+            writeln!(text, "x_i = {}", scalar_to_hex(&participant.x_i)).unwrap();
+            writeln!(text, "X_i = {}\n", pp_to_hex(&participant.X_i)).unwrap();
+            write!(
+                text,
+                "{}",
+                Msg::PublicKey(&pp_to_hex(&dkg_output.public_key))
+            )
+            .unwrap();
+            for (i, commitment) in dkg_output.commitments.iter().enumerate() {
+                write!(text, "\n{}", Msg::Commitment(i, &pp_to_hex(commitment))).unwrap();
+            }
+
+            let output = Output::new(
+                text,
+                json!({
+                    "id": participant.id,
+                    "x_i": scalar_to_hex(&participant.x_i),
+                    "X_i": pp_to_hex(&participant.X_i),
+                    "public_key": pp_to_hex(&dkg_output.public_key),
+                    "commitments": dkg_output
+                        .commitments
+                        .iter()
+                        .map(pp_to_hex)
+                        .collect::<Vec<_>>(),
+                }),
+            );
+
+            output.print(format);
+            if let Some(path) = output_file {
+                fs::write(&path, output.render(format) + "\n").unwrap_or_else(|e| {
+                    fail(CliError::io(Msg::CannotWrite(&path, &e.to_string())))
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use crate::i18n::Msg;
+use crate::parser::OutputFormat;
+use serde_json::json;
+use shamy_core::Error;
+use std::{any::Any, fmt::Display, panic};
+
+//--------------------------------------------------------------------
+// Errors and exit codes
+//--------------------------------------------------------------------
+//
+//   0  success
+//   1  internal error (a bug)
+//   2  usage error (reported by clap)
+//   3  invalid input: malformed hex, points, scalars or files
+//   4  i/o: a file can't be read or written
+//   5  verification failed: signature, proof, share, audit, manifest
+//   6  rejected by the protocol: thresholds, ids, packages, limits
+//
+// commands stop with `fail`, which unwinds to `main` carrying a `CliError`.
+// `main` prints it to stderr (as `{"error": {...}}` with `--format json`)
+// and exits with the code of its kind.
+//
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    Internal,
+    InvalidInput,
+    Io,
+    Verification,
+    Protocol,
+}
+
+impl ErrorKind {
+    pub fn code(self) -> i32 {
+        match self {
+            ErrorKind::Internal => 1,
+            ErrorKind::InvalidInput => 3,
+            ErrorKind::Io => 4,
+            ErrorKind::Verification => 5,
+            ErrorKind::Protocol => 6,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ErrorKind::Internal => "internal",
+            ErrorKind::InvalidInput => "invalid_input",
+            ErrorKind::Io => "io",
+            ErrorKind::Verification => "verification",
+            ErrorKind::Protocol => "protocol",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CliError {
+    pub kind: ErrorKind,
+    pub message: String,
+}
+
+impl CliError {
+    pub fn new(kind: ErrorKind, message: impl Display) -> Self {
+        Self {
+            kind,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn input(message: impl Display) -> Self {
+        Self::new(ErrorKind::InvalidInput, message)
+    }
+
+    pub fn io(message: impl Display) -> Self {
+        Self::new(ErrorKind::Io, message)
+    }
+
+    pub fn verification(message: impl Display) -> Self {
+        Self::new(ErrorKind::Verification, message)
+    }
+
+    /// the error behind a caught panic, anything but a `CliError` is a bug.
+    pub fn from_panic(payload: Box<dyn Any + Send>) -> Self {
+        match payload.downcast::<CliError>() {
+            Ok(error) => *error,
+            Err(payload) => {
+                let message = match payload.downcast_ref::<&str>() {
+                    Some(message) => message.to_string(),
+                    None => payload
+                        .downcast_ref::<String>()
+                        .cloned()
+                        .unwrap_or_default(),
+                };
+                Self::new(ErrorKind::Internal, message)
+            }
+        }
+    }
+
+    pub fn report(&self, format: OutputFormat) {
+        match format {
+            OutputFormat::Text => eprintln!("{}", Msg::Error(&self.message)),
+            OutputFormat::Json => eprintln!(
+                "{}",
+                json!({
+                    "error": {
+                        "kind": self.kind.name(),
+                        "code": self.kind.code(),
+                        "message": self.message,
+                    }
+                })
+            ),
+        }
+    }
+}
+
+impl From<Error> for CliError {
+    fn from(error: Error) -> Self {
+        let kind = match &error {
+            Error::InvalidHex(_)
+            | Error::InvalidPoint(_)
+            | Error::IdentityPoint
+            | Error::InvalidScalarLength(_)
+            | Error::InvalidScalar
+            | Error::InvalidEncoding(_)
+            | Error::InvalidChildIndex(_)
+            | Error::InvalidTweak => ErrorKind::InvalidInput,
+            Error::InvalidShare { .. }
+            | Error::InvalidSignature
+            | Error::InvalidPartialSignature { .. }
+            | Error::InvalidAggregationProof(_)
+            | Error::InvalidVrfProof { .. }
+            | Error::DecryptionFailed
+            | Error::Misbehavior(_)
+            | Error::InvalidProofOfPossession { .. }
+            | Error::BackupMismatch
+            | Error::VectorMismatch(_) => ErrorKind::Verification,
+            Error::Io(_) => ErrorKind::Io,
+            _ => ErrorKind::Protocol,
+        };
+        Self::new(kind, error)
+    }
+}
+
+/// stop the command with `error`, see the exit codes above.
+pub fn fail(error: impl Into<CliError>) -> ! {
+    panic::panic_any(error.into())
+}
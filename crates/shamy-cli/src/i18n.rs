@@ -0,0 +1,848 @@
+#![allow(non_snake_case)]
+
+use clap::ValueEnum;
+use std::{fmt, path::Path, sync::OnceLock};
+
+/// languages of the message catalog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Locale {
+    En,
+    Tr,
+    Es,
+}
+
+static LOCALE: OnceLock<Locale> = OnceLock::new();
+
+/// pick the locale once at startup: `--lang`, then `SHAMY_LANG`,
+/// then the usual POSIX variables, english otherwise.
+pub fn init(lang: Option<Locale>) {
+    let locale = lang.unwrap_or_else(|| {
+        ["SHAMY_LANG", "LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Locale::from_tag(&value))
+            .unwrap_or(Locale::En)
+    });
+    let _ = LOCALE.set(locale);
+}
+
+pub fn locale() -> Locale {
+    *LOCALE.get().unwrap_or(&Locale::En)
+}
+
+impl Locale {
+    /// `tr`, `tr_TR.UTF-8`, `es-ES`, ...
+    fn from_tag(tag: &str) -> Option<Self> {
+        match tag.get(..2)?.to_ascii_lowercase().as_str() {
+            "en" => Some(Locale::En),
+            "tr" => Some(Locale::Tr),
+            "es" => Some(Locale::Es),
+            _ => None,
+        }
+    }
+}
+
+/// user-facing CLI strings. hex values, math notation (x_i, R, c, ...) and
+/// errors coming from `shamy_core` are the same in every locale.
+pub enum Msg<'a> {
+    // keygen
+    ParticipantHeader(u64),
+    PublicKey(&'a str),
+    XOnlyPublicKey(&'a str),
+    Commitment(usize, &'a str),
+    SecretKey(&'a str),
+    IdsSharesMismatch(usize, usize),
+    ReconstructedKeyMismatch,
+    // schnorr
+    PartialSignature(&'a str),
+    NonceValid,
+    Error(&'a str),
+    SignatureValid,
+    SignatureInvalid,
+    Challenge(&'a str),
+    InterpolatedSignature(&'a str),
+    CompactSignature(&'a str),
+    InvalidProof(&'a str),
+    QuorumVerified(&'a [u64]),
+    // files
+    Wrote(&'a Path),
+    AlreadyExists(&'a Path),
+    CannotRead(&'a Path, &'a str),
+    CannotWrite(&'a Path, &'a str),
+    // keystore
+    CannotReadKeystore(&'a str),
+    InvalidKeystore(&'a str),
+    UnsupportedKeystoreVersion(u8),
+    CannotWriteKeystore(&'a str),
+    ShareAlreadyStored(u64),
+    EncryptionFailed,
+    KeyDerivationFailed(&'a str),
+    InvalidKdfParams(&'a str),
+    ShareNotStored(u64),
+    WrongPassphrase(u64),
+    ShareMismatch(u64),
+    PassphrasePrompt(u64),
+    PassphrasePromptFailed(u64, &'a str),
+    EmptyPassphrase(u64),
+    CreatedKeystore(&'a Path),
+    ImportedShare(u64, &'a Path),
+    // ceremony
+    PromptMessage,
+    PromptPublicKey,
+    PromptShareIds,
+    PromptParticipantId,
+    PromptShare(u64),
+    SharesIn(&'a Path, &'a [u64]),
+    NoSharesSelected,
+    InvalidShareId(&'a str),
+    InvalidParticipantId(&'a str),
+    ShareSelectedTwice(u64),
+    UnexpectedEndOfInput,
+    CeremonySigningWith(&'a [u64]),
+    CeremonyNonce(u64, &'a str),
+    CeremonyChallenge(&'a str),
+    CeremonyPartial(u64, &'a str),
+    CeremonyCombined(&'a str),
+    CeremonyInvalid,
+    CeremonyVerified,
+    CeremonyResult(&'a str, &'a str),
+    CeremonyPublicShare(u64, &'a str),
+    // manifest
+    InvalidManifest(&'a str),
+    ManifestKey(&'a str),
+    ManifestShareOk(u64),
+    ManifestShareMissing,
+    ManifestShareNotListed,
+    ManifestChecksumMismatch,
+    ManifestWrongShare,
+    ManifestVerified(usize),
+    ManifestFailed(usize, usize),
+    // test vectors
+    InvalidSeed,
+    InvalidVector(&'a str),
+    UnsupportedVectorVersion(u8),
+    VectorVerified(&'a Path),
+    // audit
+    InvalidKeygenFile(&'a str),
+    AuditKey(&'a str),
+    AuditPublicKeyOk,
+    AuditPublicKeyMismatch,
+    AuditCommitmentCount(usize, usize),
+    AuditShareCount(usize, usize),
+    AuditInvalidId(u64),
+    AuditPublicShareMismatch(u64),
+    AuditShareMismatch(u64),
+    AuditParticipantOk(u64),
+    AuditPassed(usize),
+    AuditFailed(usize, usize),
+
+    UnsupportedKeygenFileVersion(u8),
+    ShareNotInKeygenFile(u64),
+
+    InvalidNoncePool(&'a str),
+    UnsupportedNoncePoolVersion(u8),
+    NoncePoolSize(&'a Path, usize),
+
+    Revoked(u64),
+    RevocationValid(u64),
+    RevocationInvalid,
+    InvalidRevocationRecord(&'a str),
+    FileSigned(&'a Path, &'a str),
+    FileSignatureValid(&'a Path, &'a str),
+    FileModified(&'a Path),
+    SidecarKeyMismatch(&'a str),
+    InvalidSidecar(&'a str),
+    RecipientCountMismatch(usize, usize),
+    InvalidRecipientKeyFile(&'a str),
+    SignerListening(u64, &'a str),
+    SignedRequest(&'a str),
+    SessionFailed(&'a str),
+    InvalidDigest(&'a str),
+    CannotListen(&'a str, &'a str),
+    KeyringError(&'a str),
+    NotInKeyring(&'a str),
+    KeyringNotBuilt,
+    CopiedShares(&'a [u64], &'a Path, &'a Path),
+    InvalidRecipient(&'a str),
+    ShareEncryptedToRecipient(u64),
+    NoRecipientShare,
+    PartialSignatureValid(u64),
+    PartialSignatureInvalid(u64),
+}
+
+impl fmt::Display for Msg<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match locale() {
+            Locale::En => en(self, f),
+            Locale::Tr => tr(self, f),
+            Locale::Es => es(self, f),
+        }
+    }
+}
+
+fn en(msg: &Msg, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match msg {
+        Msg::ParticipantHeader(id) => write!(f, "[Participant ID:{}]", id),
+        Msg::PublicKey(X) => write!(f, "Public key X = {}", X),
+        Msg::XOnlyPublicKey(X) => write!(f, "X-only public key = {}", X),
+        Msg::Commitment(i, C) => write!(f, "Commitment {} = {}", i, C),
+        Msg::SecretKey(x) => write!(f, "Secret key x = {}", x),
+        Msg::IdsSharesMismatch(ids, shares) => {
+            write!(f, "Got {} ids but {} shares", ids, shares)
+        }
+        Msg::ReconstructedKeyMismatch => write!(
+            f,
+            "Reconstructed key does not match the public key, not enough shares?"
+        ),
+        Msg::PartialSignature(s_i) => write!(f, "Signature: {} ", s_i),
+        Msg::NonceValid => write!(f, "Nonce is valid"),
+        Msg::Error(e) => write!(f, "Error: {}", e),
+        Msg::SignatureValid => write!(f, "🔒✅ Signature is valid"),
+        Msg::SignatureInvalid => write!(f, "🔒❌ Signature is invalid"),
+        Msg::Challenge(c) => write!(f, "Challenge: {}", c),
+        Msg::InterpolatedSignature(s) => write!(f, "Interpolated signature: {}", s),
+        Msg::CompactSignature(sig) => write!(f, "Compact signature (R || s): {}", sig),
+        Msg::InvalidProof(e) => write!(f, "Invalid aggregation proof: {}", e),
+        Msg::QuorumVerified(ids) => {
+            write!(f, "🔒✅ Signature was produced by participants {:?}", ids)
+        }
+        Msg::Wrote(path) => write!(f, "Wrote {}", path.display()),
+        Msg::AlreadyExists(path) => write!(f, "{} already exists", path.display()),
+        Msg::CannotRead(path, e) => write!(f, "Cannot read {}: {}", path.display(), e),
+        Msg::CannotWrite(path, e) => write!(f, "Cannot write {}: {}", path.display(), e),
+        Msg::CannotReadKeystore(e) => write!(f, "Cannot read keystore: {}", e),
+        Msg::InvalidKeystore(e) => write!(f, "Invalid keystore: {}", e),
+        Msg::UnsupportedKeystoreVersion(v) => write!(f, "Unsupported keystore version {}", v),
+        Msg::CannotWriteKeystore(e) => write!(f, "Cannot write keystore: {}", e),
+        Msg::ShareAlreadyStored(id) => write!(f, "Keystore already contains share {}", id),
+        Msg::EncryptionFailed => write!(f, "Encryption failed"),
+        Msg::KeyDerivationFailed(e) => write!(f, "Key derivation failed: {}", e),
+        Msg::InvalidKdfParams(e) => write!(f, "Invalid KDF parameters: {}", e),
+        Msg::ShareNotStored(id) => write!(f, "Keystore does not contain share {}", id),
+        Msg::WrongPassphrase(id) => write!(f, "Wrong passphrase for share {}", id),
+        Msg::ShareMismatch(id) => write!(f, "Share {} does not match its public share", id),
+        Msg::PassphrasePrompt(id) => write!(f, "Passphrase for share {}: ", id),
+        Msg::PassphrasePromptFailed(id, e) => write!(
+            f,
+            "Cannot read the passphrase for share {} (set SHAMY_PASSPHRASE without a terminal): {}",
+            id, e
+        ),
+        Msg::EmptyPassphrase(id) => write!(f, "Passphrase for share {} must not be empty", id),
+        Msg::CreatedKeystore(path) => write!(f, "Created keystore {}", path.display()),
+        Msg::ImportedShare(id, path) => {
+            write!(f, "Imported share {} into {}", id, path.display())
+        }
+        Msg::PromptMessage => write!(f, "Message to sign: "),
+        Msg::PromptPublicKey => write!(f, "Public key X: "),
+        Msg::PromptShareIds => write!(f, "Share ids to sign with: "),
+        Msg::PromptParticipantId => write!(f, "Participant id (empty to finish): "),
+        Msg::PromptShare(id) => write!(f, "Share x_i of participant {}: ", id),
+        Msg::SharesIn(path, ids) => write!(f, "Shares in {}: {:?}", path.display(), ids),
+        Msg::NoSharesSelected => write!(f, "No shares selected"),
+        Msg::InvalidShareId(id) => write!(f, "Invalid share id {}", id),
+        Msg::InvalidParticipantId(id) => write!(f, "Invalid participant id {}", id),
+        Msg::ShareSelectedTwice(id) => write!(f, "Share {} selected twice", id),
+        Msg::UnexpectedEndOfInput => write!(f, "Unexpected end of input"),
+        Msg::CeremonySigningWith(ids) => write!(f, "[1/6] Signing with shares {:?}", ids),
+        Msg::CeremonyNonce(id, R_i) => write!(f, "[2/6] Nonce R_{} = {}", id, R_i),
+        Msg::CeremonyChallenge(c) => write!(f, "[3/6] Challenge c = {}", c),
+        Msg::CeremonyPartial(id, s_i) => write!(f, "[4/6] Partial signature s_{} = {}", id, s_i),
+        Msg::CeremonyCombined(s) => write!(f, "[5/6] Combined signature s = {}", s),
+        Msg::CeremonyInvalid => write!(
+            f,
+            "Signature does not verify against the public key, are the shares enough and from the same key?"
+        ),
+        Msg::CeremonyVerified => write!(f, "[6/6] Signature verified"),
+        Msg::CeremonyResult(R, s) => write!(
+            f,
+            "Nonce R: {}\nSignature: {}\n🔒✅ Signature is valid",
+            R, s
+        ),
+        Msg::CeremonyPublicShare(id, X_i) => write!(f, "Public share X_{} = {}", id, X_i),
+        Msg::InvalidManifest(e) => write!(f, "Invalid manifest: {}", e),
+        Msg::ManifestKey(fp) => write!(f, "Manifest for key {}", fp),
+        Msg::ManifestShareOk(id) => write!(f, "share {} matches the manifest", id),
+        Msg::ManifestShareMissing => write!(f, "file is missing"),
+        Msg::ManifestShareNotListed => write!(f, "file is not listed in the manifest"),
+        Msg::ManifestChecksumMismatch => {
+            write!(f, "checksum mismatch, the file was modified or corrupted")
+        }
+        Msg::ManifestWrongShare => write!(f, "contents do not match the manifest entry"),
+        Msg::ManifestVerified(n) => write!(f, "All {} share files match the manifest", n),
+        Msg::ManifestFailed(failed, n) => {
+            write!(f, "{} of {} share files failed verification", failed, n)
+        }
+        Msg::InvalidSeed => write!(f, "The seed must be 32 bytes of hex"),
+        Msg::InvalidVector(e) => write!(f, "Invalid test vector: {}", e),
+        Msg::UnsupportedVectorVersion(v) => write!(f, "Unsupported test vector version {}", v),
+        Msg::VectorVerified(path) => {
+            write!(f, "✅ Every value in {} recomputes", path.display())
+        }
+        Msg::InvalidKeygenFile(e) => write!(f, "Invalid keygen file: {}", e),
+        Msg::AuditKey(fp) => write!(f, "Audit of key {}", fp),
+        Msg::AuditPublicKeyOk => write!(f, "public key equals commitment C_0"),
+        Msg::AuditPublicKeyMismatch => write!(f, "public key differs from commitment C_0"),
+        Msg::AuditCommitmentCount(actual, t) => {
+            write!(f, "{} commitments for threshold {}", actual, t)
+        }
+        Msg::AuditShareCount(actual, n) => write!(f, "{} participants of {}", actual, n),
+        Msg::AuditInvalidId(id) => write!(f, "participant id {} is zero or repeated", id),
+        Msg::AuditPublicShareMismatch(id) => write!(
+            f,
+            "public share of participant {} does not match the commitments",
+            id
+        ),
+        Msg::AuditShareMismatch(id) => write!(
+            f,
+            "share of participant {} does not match the commitments",
+            id
+        ),
+        Msg::AuditParticipantOk(id) => write!(f, "participant {} matches the commitments", id),
+        Msg::AuditPassed(n) => write!(f, "All {} checks passed", n),
+        Msg::AuditFailed(failed, n) => write!(f, "{} of {} checks failed", failed, n),
+        Msg::UnsupportedKeygenFileVersion(v) => write!(f, "Unsupported keygen file version {}", v),
+        Msg::ShareNotInKeygenFile(id) => write!(f, "Keygen file does not contain share {}", id),
+        Msg::InvalidNoncePool(e) => write!(f, "Invalid nonce pool: {}", e),
+        Msg::UnsupportedNoncePoolVersion(v) => write!(f, "Unsupported nonce pool version {}", v),
+        Msg::NoncePoolSize(path, n) => write!(f, "{} holds {} unused nonces", path.display(), n),
+        Msg::Revoked(id) => write!(
+            f,
+            "Participant {} revoked, the other shares were refreshed",
+            id
+        ),
+        Msg::RevocationValid(id) => write!(f, "✅ Participant {} was revoked by the group", id),
+        Msg::RevocationInvalid => write!(f, "❌ Revocation record is invalid"),
+        Msg::InvalidRevocationRecord(e) => write!(f, "Invalid revocation record: {}", e),
+        Msg::FileSigned(path, key) => write!(f, "✅ Signed {} with key {}", path.display(), key),
+        Msg::FileSignatureValid(path, key) => {
+            write!(f, "✅ {} is signed by key {}", path.display(), key)
+        }
+        Msg::FileModified(path) => write!(f, "❌ {} changed since it was signed", path.display()),
+        Msg::SidecarKeyMismatch(key) => write!(f, "❌ Signed by key {}, not the given key", key),
+        Msg::InvalidSidecar(e) => write!(f, "Invalid signature file: {}", e),
+        Msg::RecipientCountMismatch(got, n) => {
+            write!(f, "Got {} recipient keys for {} shares", got, n)
+        }
+        Msg::InvalidRecipientKeyFile(e) => write!(f, "Invalid recipient key file: {}", e),
+        Msg::SignerListening(id, addr) => write!(f, "Signer {} listening on {}", id, addr),
+        Msg::SignedRequest(digest) => write!(f, "✅ Signed message {}", digest),
+        Msg::SessionFailed(e) => write!(f, "❌ Session failed: {}", e),
+        Msg::InvalidDigest(e) => write!(f, "Invalid SHA-256 digest: {}", e),
+        Msg::CannotListen(addr, e) => write!(f, "Cannot listen on {}: {}", addr, e),
+        Msg::KeyringError(e) => write!(f, "OS keychain error: {}", e),
+        Msg::NotInKeyring(name) => write!(f, "No keystore {} in the OS keychain", name),
+        Msg::KeyringNotBuilt => write!(
+            f,
+            "This build has no OS keychain support, rebuild with --features keyring"
+        ),
+        Msg::CopiedShares(ids, from, to) => write!(
+            f,
+            "Copied shares {:?} from {} to {}",
+            ids,
+            from.display(),
+            to.display()
+        ),
+        Msg::InvalidRecipient(entry) => write!(
+            f,
+            "Invalid recipient {}, expected a public key or <id>=<public key>",
+            entry
+        ),
+        Msg::ShareEncryptedToRecipient(id) => write!(
+            f,
+            "Share {} is encrypted to a recipient key, open it with `recipient decrypt`",
+            id
+        ),
+        Msg::NoRecipientShare => {
+            write!(f, "Keygen file holds no share encrypted to a recipient key")
+        }
+        Msg::PartialSignatureValid(id) => {
+            write!(f, "✅ Partial signature of participant {} is valid", id)
+        }
+        Msg::PartialSignatureInvalid(id) => {
+            write!(f, "❌ Partial signature of participant {} is invalid", id)
+        }
+    }
+}
+
+fn tr(msg: &Msg, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match msg {
+        Msg::ParticipantHeader(id) => write!(f, "[Katılımcı ID:{}]", id),
+        Msg::PublicKey(X) => write!(f, "Açık anahtar X = {}", X),
+        Msg::XOnlyPublicKey(X) => write!(f, "Yalnızca x açık anahtar = {}", X),
+        Msg::Commitment(i, C) => write!(f, "Taahhüt {} = {}", i, C),
+        Msg::SecretKey(x) => write!(f, "Gizli anahtar x = {}", x),
+        Msg::IdsSharesMismatch(ids, shares) => {
+            write!(f, "{} kimlik ama {} pay verildi", ids, shares)
+        }
+        Msg::ReconstructedKeyMismatch => write!(
+            f,
+            "Yeniden oluşturulan anahtar açık anahtarla eşleşmiyor, paylar yetersiz mi?"
+        ),
+        Msg::PartialSignature(s_i) => write!(f, "İmza: {} ", s_i),
+        Msg::NonceValid => write!(f, "Nonce geçerli"),
+        Msg::Error(e) => write!(f, "Hata: {}", e),
+        Msg::SignatureValid => write!(f, "🔒✅ İmza geçerli"),
+        Msg::SignatureInvalid => write!(f, "🔒❌ İmza geçersiz"),
+        Msg::Challenge(c) => write!(f, "Meydan okuma: {}", c),
+        Msg::InterpolatedSignature(s) => write!(f, "Enterpolasyonla birleştirilmiş imza: {}", s),
+        Msg::CompactSignature(sig) => write!(f, "Kompakt imza (R || s): {}", sig),
+        Msg::InvalidProof(e) => write!(f, "Geçersiz birleştirme kanıtı: {}", e),
+        Msg::QuorumVerified(ids) => {
+            write!(
+                f,
+                "🔒✅ İmza {:?} numaralı katılımcılar tarafından üretildi",
+                ids
+            )
+        }
+        Msg::Wrote(path) => write!(f, "{} yazıldı", path.display()),
+        Msg::AlreadyExists(path) => write!(f, "{} zaten var", path.display()),
+        Msg::CannotRead(path, e) => write!(f, "{} okunamadı: {}", path.display(), e),
+        Msg::CannotWrite(path, e) => write!(f, "{} yazılamadı: {}", path.display(), e),
+        Msg::CannotReadKeystore(e) => write!(f, "Anahtar deposu okunamadı: {}", e),
+        Msg::InvalidKeystore(e) => write!(f, "Geçersiz anahtar deposu: {}", e),
+        Msg::UnsupportedKeystoreVersion(v) => {
+            write!(f, "Desteklenmeyen anahtar deposu sürümü {}", v)
+        }
+        Msg::CannotWriteKeystore(e) => write!(f, "Anahtar deposu yazılamadı: {}", e),
+        Msg::ShareAlreadyStored(id) => {
+            write!(f, "Anahtar deposunda {} numaralı pay zaten var", id)
+        }
+        Msg::EncryptionFailed => write!(f, "Şifreleme başarısız"),
+        Msg::KeyDerivationFailed(e) => write!(f, "Anahtar türetme başarısız: {}", e),
+        Msg::InvalidKdfParams(e) => write!(f, "Geçersiz KDF parametreleri: {}", e),
+        Msg::ShareNotStored(id) => write!(f, "Anahtar deposunda {} numaralı pay yok", id),
+        Msg::WrongPassphrase(id) => write!(f, "{} numaralı pay için parola yanlış", id),
+        Msg::ShareMismatch(id) => write!(f, "{} numaralı pay açık payıyla eşleşmiyor", id),
+        Msg::PassphrasePrompt(id) => write!(f, "{} numaralı pay için parola: ", id),
+        Msg::PassphrasePromptFailed(id, e) => write!(
+            f,
+            "{} numaralı pay için parola okunamadı (terminal yoksa SHAMY_PASSPHRASE ayarlayın): {}",
+            id, e
+        ),
+        Msg::EmptyPassphrase(id) => write!(f, "{} numaralı pay için parola boş olamaz", id),
+        Msg::CreatedKeystore(path) => write!(f, "Anahtar deposu oluşturuldu: {}", path.display()),
+        Msg::ImportedShare(id, path) => write!(
+            f,
+            "{} numaralı pay {} deposuna aktarıldı",
+            id,
+            path.display()
+        ),
+        Msg::PromptMessage => write!(f, "İmzalanacak mesaj: "),
+        Msg::PromptPublicKey => write!(f, "Açık anahtar X: "),
+        Msg::PromptShareIds => write!(f, "İmzalayacak pay numaraları: "),
+        Msg::PromptParticipantId => write!(f, "Katılımcı numarası (bitirmek için boş bırakın): "),
+        Msg::PromptShare(id) => write!(f, "{} numaralı katılımcının payı x_i: ", id),
+        Msg::SharesIn(path, ids) => write!(f, "{} içindeki paylar: {:?}", path.display(), ids),
+        Msg::NoSharesSelected => write!(f, "Hiç pay seçilmedi"),
+        Msg::InvalidShareId(id) => write!(f, "Geçersiz pay numarası {}", id),
+        Msg::InvalidParticipantId(id) => write!(f, "Geçersiz katılımcı numarası {}", id),
+        Msg::ShareSelectedTwice(id) => write!(f, "{} numaralı pay iki kez seçildi", id),
+        Msg::UnexpectedEndOfInput => write!(f, "Beklenmeyen girdi sonu"),
+        Msg::CeremonySigningWith(ids) => write!(f, "[1/6] İmzalayan paylar {:?}", ids),
+        Msg::CeremonyNonce(id, R_i) => write!(f, "[2/6] Nonce R_{} = {}", id, R_i),
+        Msg::CeremonyChallenge(c) => write!(f, "[3/6] Meydan okuma c = {}", c),
+        Msg::CeremonyPartial(id, s_i) => write!(f, "[4/6] Kısmi imza s_{} = {}", id, s_i),
+        Msg::CeremonyCombined(s) => write!(f, "[5/6] Birleştirilmiş imza s = {}", s),
+        Msg::CeremonyInvalid => write!(
+            f,
+            "İmza açık anahtarla doğrulanmıyor, paylar yeterli ve aynı anahtardan mı?"
+        ),
+        Msg::CeremonyVerified => write!(f, "[6/6] İmza doğrulandı"),
+        Msg::CeremonyResult(R, s) => {
+            write!(f, "Nonce R: {}\nİmza: {}\n🔒✅ İmza geçerli", R, s)
+        }
+        Msg::CeremonyPublicShare(id, X_i) => write!(f, "Açık pay X_{} = {}", id, X_i),
+        Msg::InvalidManifest(e) => write!(f, "Geçersiz bildirim dosyası: {}", e),
+        Msg::ManifestKey(fp) => write!(f, "{} anahtarının bildirim dosyası", fp),
+        Msg::ManifestShareOk(id) => write!(f, "{} numaralı pay bildirimle eşleşiyor", id),
+        Msg::ManifestShareMissing => write!(f, "dosya eksik"),
+        Msg::ManifestShareNotListed => write!(f, "dosya bildirimde listelenmiyor"),
+        Msg::ManifestChecksumMismatch => {
+            write!(
+                f,
+                "sağlama toplamı eşleşmiyor, dosya değiştirilmiş veya bozulmuş"
+            )
+        }
+        Msg::ManifestWrongShare => write!(f, "içerik bildirim kaydıyla eşleşmiyor"),
+        Msg::ManifestVerified(n) => write!(f, "{} pay dosyasının tümü bildirimle eşleşiyor", n),
+        Msg::ManifestFailed(failed, n) => {
+            write!(f, "{} pay dosyasından {} tanesi doğrulanamadı", n, failed)
+        }
+        Msg::InvalidSeed => write!(f, "Tohum 32 baytlık hex olmalı"),
+        Msg::InvalidVector(e) => write!(f, "Geçersiz test vektörü: {}", e),
+        Msg::UnsupportedVectorVersion(v) => write!(f, "Desteklenmeyen test vektörü sürümü {}", v),
+        Msg::VectorVerified(path) => {
+            write!(
+                f,
+                "✅ {} içindeki tüm değerler yeniden hesaplandı",
+                path.display()
+            )
+        }
+        Msg::InvalidKeygenFile(e) => write!(f, "Geçersiz anahtar üretim dosyası: {}", e),
+        Msg::AuditKey(fp) => write!(f, "{} anahtarının denetimi", fp),
+        Msg::AuditPublicKeyOk => write!(f, "açık anahtar C_0 taahhüdüne eşit"),
+        Msg::AuditPublicKeyMismatch => write!(f, "açık anahtar C_0 taahhüdünden farklı"),
+        Msg::AuditCommitmentCount(actual, t) => write!(f, "{} eşiği için {} taahhüt", t, actual),
+        Msg::AuditShareCount(actual, n) => write!(f, "{} katılımcıdan {}", n, actual),
+        Msg::AuditInvalidId(id) => write!(f, "katılımcı kimliği {} sıfır ya da tekrarlı", id),
+        Msg::AuditPublicShareMismatch(id) => write!(
+            f,
+            "{} numaralı katılımcının açık payı taahhütlerle eşleşmiyor",
+            id
+        ),
+        Msg::AuditShareMismatch(id) => write!(
+            f,
+            "{} numaralı katılımcının payı taahhütlerle eşleşmiyor",
+            id
+        ),
+        Msg::AuditParticipantOk(id) => {
+            write!(f, "{} numaralı katılımcı taahhütlerle eşleşiyor", id)
+        }
+        Msg::AuditPassed(n) => write!(f, "{} denetimin tümü geçti", n),
+        Msg::AuditFailed(failed, n) => write!(f, "{} denetimden {} tanesi başarısız", n, failed),
+        Msg::UnsupportedKeygenFileVersion(v) => {
+            write!(f, "Desteklenmeyen anahtar üretim dosyası sürümü {}", v)
+        }
+        Msg::ShareNotInKeygenFile(id) => {
+            write!(f, "Anahtar üretim dosyasında {} numaralı pay yok", id)
+        }
+        Msg::InvalidNoncePool(e) => write!(f, "Geçersiz nonce havuzu: {}", e),
+        Msg::UnsupportedNoncePoolVersion(v) => {
+            write!(f, "Desteklenmeyen nonce havuzu sürümü {}", v)
+        }
+        Msg::NoncePoolSize(path, n) => {
+            write!(f, "{} içinde {} kullanılmamış nonce var", path.display(), n)
+        }
+        Msg::Revoked(id) => write!(f, "Katılımcı {} iptal edildi, diğer paylar yenilendi", id),
+        Msg::RevocationValid(id) => write!(f, "✅ Katılımcı {} grup tarafından iptal edildi", id),
+        Msg::RevocationInvalid => write!(f, "❌ İptal kaydı geçersiz"),
+        Msg::InvalidRevocationRecord(e) => write!(f, "Geçersiz iptal kaydı: {}", e),
+        Msg::FileSigned(path, key) => {
+            write!(f, "✅ {} {} anahtarıyla imzalandı", path.display(), key)
+        }
+        Msg::FileSignatureValid(path, key) => {
+            write!(f, "✅ {} {} anahtarıyla imzalanmış", path.display(), key)
+        }
+        Msg::FileModified(path) => write!(f, "❌ {} imzalandıktan sonra değişmiş", path.display()),
+        Msg::SidecarKeyMismatch(key) => {
+            write!(
+                f,
+                "❌ Verilen anahtarla değil, {} anahtarıyla imzalanmış",
+                key
+            )
+        }
+        Msg::InvalidSidecar(e) => write!(f, "Geçersiz imza dosyası: {}", e),
+        Msg::RecipientCountMismatch(got, n) => {
+            write!(f, "{} pay için {} alıcı anahtarı verildi", n, got)
+        }
+        Msg::InvalidRecipientKeyFile(e) => write!(f, "Geçersiz alıcı anahtar dosyası: {}", e),
+        Msg::SignerListening(id, addr) => write!(f, "İmzacı {} {} adresini dinliyor", id, addr),
+        Msg::SignedRequest(digest) => write!(f, "✅ {} mesajı imzalandı", digest),
+        Msg::SessionFailed(e) => write!(f, "❌ Oturum başarısız: {}", e),
+        Msg::InvalidDigest(e) => write!(f, "Geçersiz SHA-256 özeti: {}", e),
+        Msg::CannotListen(addr, e) => write!(f, "{} dinlenemiyor: {}", addr, e),
+        Msg::KeyringError(e) => write!(f, "İşletim sistemi anahtar zinciri hatası: {}", e),
+        Msg::NotInKeyring(name) => {
+            write!(
+                f,
+                "İşletim sistemi anahtar zincirinde {} anahtar deposu yok",
+                name
+            )
+        }
+        Msg::KeyringNotBuilt => write!(
+            f,
+            "Bu derlemede anahtar zinciri desteği yok, --features keyring ile yeniden derleyin"
+        ),
+        Msg::CopiedShares(ids, from, to) => write!(
+            f,
+            "{:?} payları {} deposundan {} deposuna kopyalandı",
+            ids,
+            from.display(),
+            to.display()
+        ),
+        Msg::InvalidRecipient(entry) => write!(
+            f,
+            "Geçersiz alıcı {}, bir açık anahtar ya da <id>=<açık anahtar> bekleniyordu",
+            entry
+        ),
+        Msg::ShareEncryptedToRecipient(id) => write!(
+            f,
+            "Pay {} bir alıcı anahtarına şifreli, `recipient decrypt` ile açın",
+            id
+        ),
+        Msg::NoRecipientShare => {
+            write!(f, "Anahtar üretim dosyasında alıcıya şifreli pay yok")
+        }
+        Msg::PartialSignatureValid(id) => {
+            write!(f, "✅ {} numaralı katılımcının kısmi imzası geçerli", id)
+        }
+        Msg::PartialSignatureInvalid(id) => {
+            write!(f, "❌ {} numaralı katılımcının kısmi imzası geçersiz", id)
+        }
+    }
+}
+
+fn es(msg: &Msg, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match msg {
+        Msg::ParticipantHeader(id) => write!(f, "[ID de participante:{}]", id),
+        Msg::PublicKey(X) => write!(f, "Clave pública X = {}", X),
+        Msg::XOnlyPublicKey(X) => write!(f, "Clave pública solo x = {}", X),
+        Msg::Commitment(i, C) => write!(f, "Compromiso {} = {}", i, C),
+        Msg::SecretKey(x) => write!(f, "Clave secreta x = {}", x),
+        Msg::IdsSharesMismatch(ids, shares) => {
+            write!(f, "Se recibieron {} ids pero {} fragmentos", ids, shares)
+        }
+        Msg::ReconstructedKeyMismatch => write!(
+            f,
+            "La clave reconstruida no coincide con la clave pública, ¿faltan fragmentos?"
+        ),
+        Msg::PartialSignature(s_i) => write!(f, "Firma: {} ", s_i),
+        Msg::NonceValid => write!(f, "El nonce es válido"),
+        Msg::Error(e) => write!(f, "Error: {}", e),
+        Msg::SignatureValid => write!(f, "🔒✅ La firma es válida"),
+        Msg::SignatureInvalid => write!(f, "🔒❌ La firma no es válida"),
+        Msg::Challenge(c) => write!(f, "Desafío: {}", c),
+        Msg::InterpolatedSignature(s) => write!(f, "Firma interpolada: {}", s),
+        Msg::CompactSignature(sig) => write!(f, "Firma compacta (R || s): {}", sig),
+        Msg::InvalidProof(e) => write!(f, "Prueba de agregación no válida: {}", e),
+        Msg::QuorumVerified(ids) => {
+            write!(
+                f,
+                "🔒✅ La firma fue producida por los participantes {:?}",
+                ids
+            )
+        }
+        Msg::Wrote(path) => write!(f, "Escrito {}", path.display()),
+        Msg::AlreadyExists(path) => write!(f, "{} ya existe", path.display()),
+        Msg::CannotRead(path, e) => write!(f, "No se puede leer {}: {}", path.display(), e),
+        Msg::CannotWrite(path, e) => write!(f, "No se puede escribir {}: {}", path.display(), e),
+        Msg::CannotReadKeystore(e) => {
+            write!(f, "No se puede leer el almacén de claves: {}", e)
+        }
+        Msg::InvalidKeystore(e) => write!(f, "Almacén de claves no válido: {}", e),
+        Msg::UnsupportedKeystoreVersion(v) => {
+            write!(f, "Versión de almacén de claves no soportada: {}", v)
+        }
+        Msg::CannotWriteKeystore(e) => {
+            write!(f, "No se puede escribir el almacén de claves: {}", e)
+        }
+        Msg::ShareAlreadyStored(id) => {
+            write!(f, "El almacén de claves ya contiene el fragmento {}", id)
+        }
+        Msg::EncryptionFailed => write!(f, "Falló el cifrado"),
+        Msg::KeyDerivationFailed(e) => write!(f, "Falló la derivación de la clave: {}", e),
+        Msg::InvalidKdfParams(e) => write!(f, "Parámetros KDF no válidos: {}", e),
+        Msg::ShareNotStored(id) => {
+            write!(f, "El almacén de claves no contiene el fragmento {}", id)
+        }
+        Msg::WrongPassphrase(id) => {
+            write!(f, "Frase de contraseña incorrecta para el fragmento {}", id)
+        }
+        Msg::ShareMismatch(id) => {
+            write!(f, "El fragmento {} no coincide con su parte pública", id)
+        }
+        Msg::PassphrasePrompt(id) => write!(f, "Frase de contraseña del fragmento {}: ", id),
+        Msg::PassphrasePromptFailed(id, e) => write!(
+            f,
+            "No se puede leer la frase de contraseña del fragmento {} (sin terminal, defina SHAMY_PASSPHRASE): {}",
+            id, e
+        ),
+        Msg::EmptyPassphrase(id) => write!(
+            f,
+            "La frase de contraseña del fragmento {} no puede estar vacía",
+            id
+        ),
+        Msg::CreatedKeystore(path) => write!(f, "Almacén de claves creado: {}", path.display()),
+        Msg::ImportedShare(id, path) => {
+            write!(f, "Fragmento {} importado en {}", id, path.display())
+        }
+        Msg::PromptMessage => write!(f, "Mensaje a firmar: "),
+        Msg::PromptPublicKey => write!(f, "Clave pública X: "),
+        Msg::PromptShareIds => write!(f, "Ids de los fragmentos para firmar: "),
+        Msg::PromptParticipantId => write!(f, "Id de participante (vacío para terminar): "),
+        Msg::PromptShare(id) => write!(f, "Fragmento x_i del participante {}: ", id),
+        Msg::SharesIn(path, ids) => write!(f, "Fragmentos en {}: {:?}", path.display(), ids),
+        Msg::NoSharesSelected => write!(f, "No se seleccionó ningún fragmento"),
+        Msg::InvalidShareId(id) => write!(f, "Id de fragmento no válido: {}", id),
+        Msg::InvalidParticipantId(id) => write!(f, "Id de participante no válido: {}", id),
+        Msg::ShareSelectedTwice(id) => write!(f, "Fragmento {} seleccionado dos veces", id),
+        Msg::UnexpectedEndOfInput => write!(f, "Fin de entrada inesperado"),
+        Msg::CeremonySigningWith(ids) => write!(f, "[1/6] Firmando con los fragmentos {:?}", ids),
+        Msg::CeremonyNonce(id, R_i) => write!(f, "[2/6] Nonce R_{} = {}", id, R_i),
+        Msg::CeremonyChallenge(c) => write!(f, "[3/6] Desafío c = {}", c),
+        Msg::CeremonyPartial(id, s_i) => write!(f, "[4/6] Firma parcial s_{} = {}", id, s_i),
+        Msg::CeremonyCombined(s) => write!(f, "[5/6] Firma combinada s = {}", s),
+        Msg::CeremonyInvalid => write!(
+            f,
+            "La firma no se verifica con la clave pública, ¿son suficientes fragmentos y de la misma clave?"
+        ),
+        Msg::CeremonyVerified => write!(f, "[6/6] Firma verificada"),
+        Msg::CeremonyResult(R, s) => {
+            write!(f, "Nonce R: {}\nFirma: {}\n🔒✅ La firma es válida", R, s)
+        }
+        Msg::CeremonyPublicShare(id, X_i) => write!(f, "Fragmento público X_{} = {}", id, X_i),
+        Msg::InvalidManifest(e) => write!(f, "Manifiesto no válido: {}", e),
+        Msg::ManifestKey(fp) => write!(f, "Manifiesto de la clave {}", fp),
+        Msg::ManifestShareOk(id) => write!(f, "el fragmento {} coincide con el manifiesto", id),
+        Msg::ManifestShareMissing => write!(f, "falta el archivo"),
+        Msg::ManifestShareNotListed => write!(f, "el archivo no figura en el manifiesto"),
+        Msg::ManifestChecksumMismatch => write!(
+            f,
+            "la suma de verificación no coincide, el archivo fue modificado o está dañado"
+        ),
+        Msg::ManifestWrongShare => {
+            write!(f, "el contenido no coincide con la entrada del manifiesto")
+        }
+        Msg::ManifestVerified(n) => {
+            write!(
+                f,
+                "Los {} archivos de fragmentos coinciden con el manifiesto",
+                n
+            )
+        }
+        Msg::ManifestFailed(failed, n) => write!(
+            f,
+            "{} de {} archivos de fragmentos no superaron la verificación",
+            failed, n
+        ),
+        Msg::InvalidSeed => write!(f, "La semilla debe ser de 32 bytes en hex"),
+        Msg::InvalidVector(e) => write!(f, "Vector de prueba no válido: {}", e),
+        Msg::UnsupportedVectorVersion(v) => {
+            write!(f, "Versión de vector de prueba no soportada {}", v)
+        }
+        Msg::VectorVerified(path) => {
+            write!(
+                f,
+                "✅ Todos los valores de {} se recalculan",
+                path.display()
+            )
+        }
+        Msg::InvalidKeygenFile(e) => write!(f, "Archivo de generación de claves no válido: {}", e),
+        Msg::AuditKey(fp) => write!(f, "Auditoría de la clave {}", fp),
+        Msg::AuditPublicKeyOk => write!(f, "la clave pública es igual al compromiso C_0"),
+        Msg::AuditPublicKeyMismatch => write!(f, "la clave pública difiere del compromiso C_0"),
+        Msg::AuditCommitmentCount(actual, t) => {
+            write!(f, "{} compromisos para el umbral {}", actual, t)
+        }
+        Msg::AuditShareCount(actual, n) => write!(f, "{} participantes de {}", actual, n),
+        Msg::AuditInvalidId(id) => {
+            write!(f, "el id de participante {} es cero o está repetido", id)
+        }
+        Msg::AuditPublicShareMismatch(id) => write!(
+            f,
+            "el fragmento público del participante {} no coincide con los compromisos",
+            id
+        ),
+        Msg::AuditShareMismatch(id) => write!(
+            f,
+            "el fragmento del participante {} no coincide con los compromisos",
+            id
+        ),
+        Msg::AuditParticipantOk(id) => {
+            write!(f, "el participante {} coincide con los compromisos", id)
+        }
+        Msg::AuditPassed(n) => write!(f, "Las {} comprobaciones pasaron", n),
+        Msg::AuditFailed(failed, n) => write!(f, "{} de {} comprobaciones fallaron", failed, n),
+        Msg::UnsupportedKeygenFileVersion(v) => write!(
+            f,
+            "Versión de archivo de generación de claves no compatible {}",
+            v
+        ),
+        Msg::ShareNotInKeygenFile(id) => write!(
+            f,
+            "El archivo de generación de claves no contiene la parte {}",
+            id
+        ),
+        Msg::InvalidNoncePool(e) => write!(f, "Grupo de nonces no válido: {}", e),
+        Msg::UnsupportedNoncePoolVersion(v) => {
+            write!(f, "Versión de grupo de nonces no compatible {}", v)
+        }
+        Msg::NoncePoolSize(path, n) => {
+            write!(f, "{} contiene {} nonces sin usar", path.display(), n)
+        }
+        Msg::Revoked(id) => write!(
+            f,
+            "Participante {} revocado, las demás partes se renovaron",
+            id
+        ),
+        Msg::RevocationValid(id) => write!(f, "✅ El grupo revocó al participante {}", id),
+        Msg::RevocationInvalid => write!(f, "❌ El registro de revocación no es válido"),
+        Msg::InvalidRevocationRecord(e) => write!(f, "Registro de revocación no válido: {}", e),
+        Msg::FileSigned(path, key) => {
+            write!(f, "✅ {} firmado con la clave {}", path.display(), key)
+        }
+        Msg::FileSignatureValid(path, key) => {
+            write!(f, "✅ {} está firmado por la clave {}", path.display(), key)
+        }
+        Msg::FileModified(path) => {
+            write!(f, "❌ {} ha cambiado desde que se firmó", path.display())
+        }
+        Msg::SidecarKeyMismatch(key) => {
+            write!(
+                f,
+                "❌ Firmado por la clave {}, no por la clave indicada",
+                key
+            )
+        }
+        Msg::InvalidSidecar(e) => write!(f, "Archivo de firma no válido: {}", e),
+        Msg::RecipientCountMismatch(got, n) => {
+            write!(
+                f,
+                "Se dieron {} claves de destinatario para {} partes",
+                got, n
+            )
+        }
+        Msg::InvalidRecipientKeyFile(e) => {
+            write!(f, "Archivo de clave de destinatario no válido: {}", e)
+        }
+        Msg::SignerListening(id, addr) => write!(f, "Firmante {} escuchando en {}", id, addr),
+        Msg::SignedRequest(digest) => write!(f, "✅ Mensaje {} firmado", digest),
+        Msg::SessionFailed(e) => write!(f, "❌ La sesión falló: {}", e),
+        Msg::InvalidDigest(e) => write!(f, "Resumen SHA-256 no válido: {}", e),
+        Msg::CannotListen(addr, e) => write!(f, "No se puede escuchar en {}: {}", addr, e),
+        Msg::KeyringError(e) => write!(f, "Error del llavero del sistema: {}", e),
+        Msg::NotInKeyring(name) => {
+            write!(
+                f,
+                "No hay almacén de claves {} en el llavero del sistema",
+                name
+            )
+        }
+        Msg::KeyringNotBuilt => write!(
+            f,
+            "Esta compilación no admite el llavero del sistema, recompile con --features keyring"
+        ),
+        Msg::CopiedShares(ids, from, to) => write!(
+            f,
+            "Fragmentos {:?} copiados de {} a {}",
+            ids,
+            from.display(),
+            to.display()
+        ),
+        Msg::InvalidRecipient(entry) => write!(
+            f,
+            "Destinatario {} no válido, se esperaba una clave pública o <id>=<clave pública>",
+            entry
+        ),
+        Msg::ShareEncryptedToRecipient(id) => write!(
+            f,
+            "La parte {} está cifrada para una clave de destinatario, ábrala con `recipient decrypt`",
+            id
+        ),
+        Msg::NoRecipientShare => write!(
+            f,
+            "El archivo de generación de claves no tiene partes cifradas para un destinatario"
+        ),
+        Msg::PartialSignatureValid(id) => {
+            write!(f, "✅ La firma parcial del participante {} es válida", id)
+        }
+        Msg::PartialSignatureInvalid(id) => {
+            write!(
+                f,
+                "❌ La firma parcial del participante {} no es válida",
+                id
+            )
+        }
+    }
+}
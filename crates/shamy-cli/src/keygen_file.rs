@@ -0,0 +1,272 @@
+#![allow(non_snake_case)]
+
+use crate::error::{CliError, fail};
+use crate::i18n::Msg;
+use crate::keystore::{Keystore, read_passphrase};
+use crate::manifest;
+use crate::parser::KeyArgs;
+use k256::ProjectivePoint;
+use serde::{Deserialize, Serialize};
+use shamy_core::{
+    shamir::KeygenOutput,
+    threshold::Participant,
+    util::{hex_to_pp, hex_to_scalar, pp_to_hex, scalar_to_hex},
+};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const KEYGEN_FILE_VERSION: u8 = 1;
+pub const PUBLIC_FILE: &str = "public.json";
+
+/// one participant, `x_i` is left out when the shares went to a keystore
+/// or share files instead.
+#[derive(Serialize, Deserialize)]
+pub struct ParticipantRecord {
+    pub id: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub x_i: Option<String>,
+    /// x_i encrypted, written in its place by `keygen --output-dir`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sealed: Option<SealedShare>,
+    pub X_i: String,
+}
+
+/// a share that never touches the disk in the clear.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "scheme", rename_all = "snake_case")]
+pub enum SealedShare {
+    /// a keystore holding only this share, opened with the participant's passphrase.
+    Passphrase { keystore: Keystore },
+    /// hex `EncryptedShare` to the participant's recipient key, see `recipient decrypt`.
+    Recipient { share: String },
+}
+
+/// keygen result as written by `keygen --output`, every point and scalar in hex.
+#[derive(Serialize, Deserialize)]
+pub struct KeygenFile {
+    pub version: u8,
+    /// seconds since the unix epoch
+    pub created_at: u64,
+    pub threshold: usize,
+    pub num_shares: usize,
+    pub public_key: String,
+    pub commitments: Vec<String>,
+    pub participants: Vec<ParticipantRecord>,
+}
+
+impl KeygenFile {
+    pub fn new(keygen_output: &KeygenOutput, threshold: usize, with_shares: bool) -> Self {
+        Self {
+            version: KEYGEN_FILE_VERSION,
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            threshold,
+            num_shares: keygen_output.participants.len(),
+            public_key: pp_to_hex(&keygen_output.public_key),
+            commitments: keygen_output.commitments.iter().map(pp_to_hex).collect(),
+            participants: keygen_output
+                .participants
+                .iter()
+                .map(|p| ParticipantRecord {
+                    id: p.id,
+                    x_i: with_shares.then(|| scalar_to_hex(&p.x_i)),
+                    sealed: None,
+                    X_i: pp_to_hex(&p.X_i),
+                })
+                .collect(),
+        }
+    }
+
+    /// like `new` without shares, except for the share of participant `id`,
+    /// which is `sealed` if given and in the clear otherwise.
+    pub fn for_participant(
+        keygen_output: &KeygenOutput,
+        threshold: usize,
+        id: u64,
+        mut sealed: Option<SealedShare>,
+    ) -> Self {
+        let mut file = Self::new(keygen_output, threshold, false);
+        for (record, participant) in file
+            .participants
+            .iter_mut()
+            .zip(&keygen_output.participants)
+        {
+            if participant.id == id {
+                match sealed.take() {
+                    Some(sealed) => record.sealed = Some(sealed),
+                    None => record.x_i = Some(scalar_to_hex(&participant.x_i)),
+                }
+            }
+        }
+
+        file
+    }
+
+    pub fn load(path: &Path) -> Result<Self, CliError> {
+        let data =
+            fs::read(path).map_err(|e| CliError::io(Msg::CannotRead(path, &e.to_string())))?;
+        let file: KeygenFile = serde_json::from_slice(&data)
+            .map_err(|e| CliError::input(Msg::InvalidKeygenFile(&e.to_string())))?;
+        if file.version != KEYGEN_FILE_VERSION {
+            return Err(CliError::input(Msg::UnsupportedKeygenFileVersion(
+                file.version,
+            )));
+        }
+
+        Ok(file)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), CliError> {
+        fs::write(path, self.to_json())
+            .map_err(|e| CliError::io(Msg::CannotWrite(path, &e.to_string())))
+    }
+
+    /// the share sealed to a recipient key, as written for one participant.
+    pub fn recipient_share(&self) -> Result<&str, CliError> {
+        self.participants
+            .iter()
+            .find_map(|p| match &p.sealed {
+                Some(SealedShare::Recipient { share }) => Some(share.as_str()),
+                _ => None,
+            })
+            .ok_or_else(|| CliError::input(Msg::NoRecipientShare))
+    }
+
+    fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap() + "\n"
+    }
+
+    pub fn public_key(&self) -> Result<ProjectivePoint, CliError> {
+        hex_to_pp(&self.public_key)
+            .map_err(|e| CliError::input(Msg::InvalidKeygenFile(&e.to_string())))
+    }
+
+    /// `(id, X_i)` of every participant, in file order.
+    pub fn public_shares(&self) -> Result<Vec<(u64, ProjectivePoint)>, CliError> {
+        self.participants
+            .iter()
+            .map(|p| {
+                let X_i = hex_to_pp(&p.X_i)
+                    .map_err(|e| CliError::input(Msg::InvalidKeygenFile(&e.to_string())))?;
+                Ok((p.id, X_i))
+            })
+            .collect()
+    }
+
+    /// the whole keygen result, every share must be in the file.
+    pub fn keygen_output(&self) -> Result<KeygenOutput, CliError> {
+        Ok(KeygenOutput {
+            participants: self
+                .participants
+                .iter()
+                .map(|p| self.participant(p.id))
+                .collect::<Result<_, _>>()?,
+            public_key: self.public_key()?,
+            commitments: self
+                .commitments
+                .iter()
+                .map(|c| hex_to_pp(c))
+                .collect::<Result<_, _>>()
+                .map_err(|e| CliError::input(Msg::InvalidKeygenFile(&e.to_string())))?,
+        })
+    }
+
+    /// the share of participant `id`, checked against its public share.
+    /// a share sealed to a passphrase asks for it like a keystore does.
+    pub fn participant(&self, id: u64) -> Result<Participant, CliError> {
+        let record = self
+            .participants
+            .iter()
+            .find(|p| p.id == id)
+            .ok_or_else(|| CliError::input(Msg::ShareNotInKeygenFile(id)))?;
+        let x_i = match (&record.x_i, &record.sealed) {
+            (Some(x_i), _) => hex_to_scalar(x_i)
+                .map_err(|e| CliError::input(Msg::InvalidKeygenFile(&e.to_string())))?,
+            (None, Some(SealedShare::Passphrase { keystore })) => {
+                keystore.decrypt(id, &read_passphrase(id))?.x_i
+            }
+            (None, Some(SealedShare::Recipient { .. })) => {
+                return Err(CliError::input(Msg::ShareEncryptedToRecipient(id)));
+            }
+            (None, None) => return Err(CliError::input(Msg::ShareNotInKeygenFile(id))),
+        };
+
+        let participant = Participant::from_secret(id, x_i);
+        if pp_to_hex(&participant.X_i) != record.X_i {
+            return Err(CliError::verification(Msg::ShareMismatch(id)));
+        }
+        Ok(participant)
+    }
+}
+
+/// deterministic name of the keygen file holding only share `id`.
+pub fn participant_file_name(id: u64) -> String {
+    format!("participant-{id}.json")
+}
+
+/// write one keygen file per participant, each holding only that participant's
+/// share, plus `public.json` without any share into `dir`. returns the written paths.
+/// shares in `sealed` are written encrypted, the others in the clear.
+/// refuses to overwrite anything, like `keygen --share-dir`.
+pub fn write_participant_files(
+    dir: &Path,
+    keygen_output: &KeygenOutput,
+    threshold: usize,
+    mut sealed: BTreeMap<u64, SealedShare>,
+) -> Result<Vec<PathBuf>, CliError> {
+    fs::create_dir_all(dir).map_err(|e| CliError::io(Msg::CannotWrite(dir, &e.to_string())))?;
+
+    let mut files = Vec::new();
+    for participant in &keygen_output.participants {
+        let file = KeygenFile::for_participant(
+            keygen_output,
+            threshold,
+            participant.id,
+            sealed.remove(&participant.id),
+        );
+        let path = dir.join(participant_file_name(participant.id));
+        files.push(manifest::create(&path, file.to_json().as_bytes())?);
+    }
+    let public = KeygenFile::new(keygen_output, threshold, false);
+    files.push(manifest::create(
+        &dir.join(PUBLIC_FILE),
+        public.to_json().as_bytes(),
+    )?);
+
+    Ok(files)
+}
+
+/// share `id` from `--share` or from `--keygen-file`.
+pub fn load_participant(
+    id: u64,
+    share: Option<String>,
+    keygen_file: Option<PathBuf>,
+) -> Participant {
+    match (share, keygen_file) {
+        (Some(share), _) => {
+            Participant::from_secret(id, hex_to_scalar(&share).unwrap_or_else(|e| fail(e)))
+        }
+        (None, Some(path)) => KeygenFile::load(&path)
+            .and_then(|file| file.participant(id))
+            .unwrap_or_else(|e| fail(e)),
+        (None, None) => unreachable!("clap requires --share or --keygen-file"),
+    }
+}
+
+impl KeyArgs {
+    /// the group key from `--public-key` or from `--keygen-file`.
+    pub fn load(&self) -> ProjectivePoint {
+        match (&self.public_key, &self.keygen_file) {
+            (Some(public_key), _) => crate::parse_public_key(public_key),
+            (None, Some(path)) => KeygenFile::load(path)
+                .and_then(|file| file.public_key())
+                .unwrap_or_else(|e| fail(e)),
+            (None, None) => unreachable!("clap requires --public-key or --keygen-file"),
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use crate::error::CliError;
+use crate::i18n::Msg;
+
+//--------------------------------------------------------------------
+// OS keychain storage
+//--------------------------------------------------------------------
+//
+// a keystore named `keyring:<name>` lives in the OS keychain instead of
+// a file, as one generic password (service "shamy", account <name>),
+// through the `keyring` crate:
+//
+//   macOS     login keychain, Security framework
+//   Windows   Credential Manager
+//   Linux     Secret Service (GNOME Keyring, KWallet) over D-Bus
+//
+// the entry holds the same JSON as a keystore file, every share in it
+// still encrypted under its own passphrase. the keychain adds the OS
+// login on top and keeps the records out of backups of the file system.
+//
+// ⚠️ Credential Manager caps an entry at 2560 bytes, about 7 shares.
+//
+// builds without the `keyring` feature, or for another OS (where the
+// crate would fall back to its non-persistent mock store), reject
+// `keyring:` keystores.
+//
+
+const SUPPORTED: bool = cfg!(all(
+    feature = "keyring",
+    any(
+        target_os = "macos",
+        windows,
+        target_os = "linux",
+        target_os = "freebsd"
+    )
+));
+#[cfg(feature = "keyring")]
+const SERVICE: &str = "shamy";
+
+/// the keystore `name`, or None if the keychain has no such entry.
+pub fn read(name: &str) -> Result<Option<Vec<u8>>, CliError> {
+    if !SUPPORTED {
+        return Err(CliError::input(Msg::KeyringNotBuilt));
+    }
+    platform::entry(name)
+        .and_then(|entry| platform::read(&entry))
+        .map_err(|e| CliError::io(Msg::KeyringError(&e.to_string())))
+}
+
+/// create or replace the keystore `name`.
+pub fn write(name: &str, data: &[u8]) -> Result<(), CliError> {
+    if !SUPPORTED {
+        return Err(CliError::input(Msg::KeyringNotBuilt));
+    }
+    platform::entry(name)
+        .and_then(|entry| platform::write(&entry, data))
+        .map_err(|e| CliError::io(Msg::KeyringError(&e.to_string())))
+}
+
+#[cfg(not(feature = "keyring"))]
+mod platform {
+    // unreachable, `read` and `write` check SUPPORTED first
+
+    pub struct Entry;
+
+    pub fn entry(_: &str) -> Result<Entry, String> {
+        unreachable!()
+    }
+
+    pub fn read(_: &Entry) -> Result<Option<Vec<u8>>, String> {
+        unreachable!()
+    }
+
+    pub fn write(_: &Entry, _: &[u8]) -> Result<(), String> {
+        unreachable!()
+    }
+}
+
+#[cfg(feature = "keyring")]
+pub(crate) mod platform {
+    use super::SERVICE;
+    use keyring::{Entry, Error};
+
+    pub fn entry(name: &str) -> Result<Entry, Error> {
+        Entry::new(SERVICE, name)
+    }
+
+    pub fn read(entry: &Entry) -> Result<Option<Vec<u8>>, Error> {
+        match entry.get_secret() {
+            Ok(data) => Ok(Some(data)),
+            Err(Error::NoEntry) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// replaces an existing entry with the same name.
+    pub fn write(entry: &Entry, data: &[u8]) -> Result<(), Error> {
+        entry.set_secret(data)
+    }
+}
@@ -0,0 +1,316 @@
+use crate::error::{CliError, ErrorKind, fail};
+use crate::i18n::Msg;
+use crate::keyring;
+use crate::output::Output;
+use crate::parser::{KeystoreCommands, OutputFormat};
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::{
+    ChaCha20Poly1305, Key, KeyInit, Nonce,
+    aead::{Aead, AeadCore, OsRng, Payload},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use shamy_core::{
+    threshold::Participant,
+    util::{hex_to_pp, hex_to_scalar, pp_to_hex, scalar_to_hex},
+};
+use std::{fs, path::Path};
+
+const KEYSTORE_VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+/// `keyring:<name>` in place of a path names a keystore in the OS keychain.
+const KEYRING_PREFIX: &str = "keyring:";
+
+/// argon2id cost parameters, stored per share so they can be raised later
+/// without breaking existing keystores.
+#[derive(Clone, Serialize, Deserialize)]
+struct KdfParams {
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self {
+            m_cost: Params::DEFAULT_M_COST,
+            t_cost: Params::DEFAULT_T_COST,
+            p_cost: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+/// a share encrypted with ChaCha20-Poly1305 under an argon2id derived key.
+/// id and X_i are authenticated as associated data.
+#[derive(Clone, Serialize, Deserialize)]
+struct EncryptedShare {
+    id: u64,
+    X_i: String,
+    kdf: KdfParams,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Keystore {
+    version: u8,
+    shares: Vec<EncryptedShare>,
+}
+
+impl Keystore {
+    pub fn new() -> Self {
+        Self {
+            version: KEYSTORE_VERSION,
+            shares: Vec::new(),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, CliError> {
+        let data = match keyring_name(path) {
+            Some(name) => {
+                keyring::read(name)?.ok_or_else(|| CliError::io(Msg::NotInKeyring(name)))?
+            }
+            None => {
+                fs::read(path).map_err(|e| CliError::io(Msg::CannotReadKeystore(&e.to_string())))?
+            }
+        };
+        let keystore: Keystore = serde_json::from_slice(&data)
+            .map_err(|e| CliError::input(Msg::InvalidKeystore(&e.to_string())))?;
+        if keystore.version != KEYSTORE_VERSION {
+            return Err(CliError::input(Msg::UnsupportedKeystoreVersion(
+                keystore.version,
+            )));
+        }
+
+        Ok(keystore)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), CliError> {
+        match keyring_name(path) {
+            Some(name) => keyring::write(name, &serde_json::to_vec(self).unwrap()),
+            None => fs::write(path, serde_json::to_vec_pretty(self).unwrap())
+                .map_err(|e| CliError::io(Msg::CannotWriteKeystore(&e.to_string()))),
+        }
+    }
+
+    pub fn exists(path: &Path) -> Result<bool, CliError> {
+        match keyring_name(path) {
+            Some(name) => Ok(keyring::read(name)?.is_some()),
+            None => Ok(path.exists()),
+        }
+    }
+
+    pub fn ids(&self) -> Vec<u64> {
+        self.shares.iter().map(|s| s.id).collect()
+    }
+
+    /// encrypt the share of `participant` under `passphrase` and add it.
+    pub fn insert(&mut self, participant: &Participant, passphrase: &str) -> Result<(), CliError> {
+        if self.shares.iter().any(|s| s.id == participant.id) {
+            return Err(CliError::input(Msg::ShareAlreadyStored(participant.id)));
+        }
+
+        let kdf = KdfParams::default();
+        let salt: [u8; SALT_LEN] = rand::random();
+        let key = derive_key(passphrase, &salt, &kdf)?;
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let X_i = pp_to_hex(&participant.X_i);
+
+        let ciphertext = ChaCha20Poly1305::new(&key)
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: &participant.x_i.to_bytes(),
+                    aad: &associated_data(participant.id, &X_i),
+                },
+            )
+            .map_err(|_| CliError::new(ErrorKind::Internal, Msg::EncryptionFailed))?;
+
+        self.shares.push(EncryptedShare {
+            id: participant.id,
+            X_i,
+            kdf,
+            salt: hex::encode(salt),
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+        });
+
+        Ok(())
+    }
+
+    /// copy the still encrypted shares `ids` (all if empty) from `other`.
+    pub fn copy_from(&mut self, other: &Keystore, ids: &[u64]) -> Result<Vec<u64>, CliError> {
+        let ids = if ids.is_empty() {
+            other.ids()
+        } else {
+            ids.to_vec()
+        };
+        for &id in &ids {
+            let share = other
+                .shares
+                .iter()
+                .find(|s| s.id == id)
+                .ok_or(CliError::input(Msg::ShareNotStored(id)))?;
+            if self.shares.iter().any(|s| s.id == id) {
+                return Err(CliError::input(Msg::ShareAlreadyStored(id)));
+            }
+            self.shares.push(share.clone());
+        }
+
+        Ok(ids)
+    }
+
+    /// decrypt share `id`, failing if the passphrase is wrong or the record was tampered with.
+    pub fn decrypt(&self, id: u64, passphrase: &str) -> Result<Participant, CliError> {
+        let share = self
+            .shares
+            .iter()
+            .find(|s| s.id == id)
+            .ok_or(CliError::input(Msg::ShareNotStored(id)))?;
+
+        let invalid = |e: String| CliError::input(Msg::InvalidKeystore(&e));
+        let salt = hex::decode(&share.salt).map_err(|e| invalid(format!("salt: {}", e)))?;
+        let nonce = hex::decode(&share.nonce).map_err(|e| invalid(format!("nonce: {}", e)))?;
+        let ciphertext =
+            hex::decode(&share.ciphertext).map_err(|e| invalid(format!("ciphertext: {}", e)))?;
+        if nonce.len() != 12 {
+            return Err(invalid("nonce length".to_string()));
+        }
+
+        let key = derive_key(passphrase, &salt, &share.kdf)?;
+        let plaintext = ChaCha20Poly1305::new(&key)
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: &associated_data(share.id, &share.X_i),
+                },
+            )
+            .map_err(|_| CliError::verification(Msg::WrongPassphrase(id)))?;
+
+        let x_i = hex_to_scalar(&hex::encode(plaintext))?;
+        let participant = Participant::from_secret(id, x_i);
+        if participant.X_i != hex_to_pp(&share.X_i)? {
+            return Err(CliError::verification(Msg::ShareMismatch(id)));
+        }
+
+        Ok(participant)
+    }
+}
+
+fn keyring_name(path: &Path) -> Option<&str> {
+    path.to_str()?.strip_prefix(KEYRING_PREFIX)
+}
+
+fn associated_data(id: u64, X_i: &str) -> Vec<u8> {
+    let mut aad = id.to_be_bytes().to_vec();
+    aad.extend_from_slice(X_i.as_bytes());
+    aad
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], kdf: &KdfParams) -> Result<Key, CliError> {
+    let params = Params::new(kdf.m_cost, kdf.t_cost, kdf.p_cost, Some(32))
+        .map_err(|e| CliError::input(Msg::InvalidKdfParams(&e.to_string())))?;
+    let mut key = Key::default();
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| {
+            CliError::new(
+                ErrorKind::Internal,
+                Msg::KeyDerivationFailed(&e.to_string()),
+            )
+        })?;
+
+    Ok(key)
+}
+
+/// passphrase for share `id`, taken from `SHAMY_PASSPHRASE_<id>`,
+/// then `SHAMY_PASSPHRASE`, then an interactive prompt.
+pub fn read_passphrase(id: u64) -> String {
+    let passphrase = std::env::var(format!("SHAMY_PASSPHRASE_{}", id))
+        .or_else(|_| std::env::var("SHAMY_PASSPHRASE"))
+        .unwrap_or_else(|_| {
+            rpassword::prompt_password(Msg::PassphrasePrompt(id).to_string()).unwrap_or_else(|e| {
+                fail(CliError::io(Msg::PassphrasePromptFailed(
+                    id,
+                    &e.to_string(),
+                )))
+            })
+        });
+    if passphrase.is_empty() {
+        fail(CliError::input(Msg::EmptyPassphrase(id)));
+    }
+
+    passphrase
+}
+
+pub fn run(command: KeystoreCommands, format: OutputFormat) {
+    match command {
+        KeystoreCommands::Init { path } => {
+            if Keystore::exists(&path).unwrap_or_else(|e| fail(e)) {
+                fail(CliError::io(Msg::AlreadyExists(&path)));
+            }
+            Keystore::new().save(&path).unwrap_or_else(|e| fail(e));
+
+            Output::new(
+                Msg::CreatedKeystore(&path).to_string(),
+                json!({ "path": path }),
+            )
+            .print(format);
+        }
+        KeystoreCommands::Import { path, id, share } => {
+            let mut keystore = Keystore::load(&path).unwrap_or_else(|e| fail(e));
+            let x_i = hex_to_scalar(&share).unwrap_or_else(|e| fail(e));
+            let participant = Participant::from_secret(id, x_i);
+
+            keystore
+                .insert(&participant, &read_passphrase(id))
+                .unwrap_or_else(|e| fail(e));
+            keystore.save(&path).unwrap_or_else(|e| fail(e));
+
+            Output::new(
+                Msg::ImportedShare(id, &path).to_string(),
+                json!({ "id": id, "X_i": pp_to_hex(&participant.X_i), "ids": keystore.ids() }),
+            )
+            .print(format);
+        }
+        KeystoreCommands::Export { path, id } => {
+            let keystore = Keystore::load(&path).unwrap_or_else(|e| fail(e));
+            let participant = keystore
+                .decrypt(id, &read_passphrase(id))
+                .unwrap_or_else(|e| fail(e));
+
+            Output::new(
+                format!(
+                    "{}\nx_i = {}\nX_i = {}",
+                    Msg::ParticipantHeader(id),
+                    scalar_to_hex(&participant.x_i),
+                    pp_to_hex(&participant.X_i)
+                ),
+                json!({
+                    "id": id,
+                    "x_i": scalar_to_hex(&participant.x_i),
+                    "X_i": pp_to_hex(&participant.X_i),
+                }),
+            )
+            .print(format);
+        }
+        KeystoreCommands::Copy { from, to, ids } => {
+            let source = Keystore::load(&from).unwrap_or_else(|e| fail(e));
+            let mut target = if Keystore::exists(&to).unwrap_or_else(|e| fail(e)) {
+                Keystore::load(&to).unwrap_or_else(|e| fail(e))
+            } else {
+                Keystore::new()
+            };
+            let copied = target.copy_from(&source, &ids).unwrap_or_else(|e| fail(e));
+            target.save(&to).unwrap_or_else(|e| fail(e));
+
+            Output::new(
+                Msg::CopiedShares(&copied, &from, &to).to_string(),
+                json!({ "from": from, "to": to, "copied": copied, "ids": target.ids() }),
+            )
+            .print(format);
+        }
+    }
+}
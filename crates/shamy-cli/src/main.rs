@@ -0,0 +1,650 @@
+#![allow(non_snake_case)]
+
+mod audit;
+mod ceremony;
+mod cli_tests;
+mod dkg;
+mod error;
+mod i18n;
+mod keygen_file;
+mod keyring;
+mod keystore;
+mod manifest;
+mod message;
+mod nonce_pool;
+mod output;
+mod parser;
+mod pem;
+mod proof;
+mod qr;
+mod recipient;
+mod repair;
+mod revoke;
+mod sign_file;
+mod signer;
+mod vectors;
+
+use error::{CliError, ErrorKind, fail};
+use i18n::Msg;
+use k256::ProjectivePoint;
+use keygen_file::{KeygenFile, SealedShare};
+use keystore::{Keystore, read_passphrase};
+use output::Output;
+use parser::*;
+use serde_json::json;
+use shamy_core::{
+    backup::{ShareBackup, recover_secret},
+    nonces::NonceRegistry,
+    schnorr::{SchnorrSignature, compute_nonce_point, generate_nonce},
+    shamir::{encrypt_share_for, reconstruct_secret, shamir_keygen_with_limits},
+    threshold::{
+        PartialSignature, Participant, SignerSet, aggregate_nonce_with_signers,
+        finalize_signature_with_signers, partial_sign,
+    },
+    util::{
+        hex_to_pp, hex_to_pp_x_only, hex_to_scalar, pp_to_hex, pp_to_hex_x_only, scalar_to_hex,
+        share_to_mnemonic,
+    },
+};
+use std::{collections::BTreeMap, fmt::Write as _, panic, process};
+
+fn main() {
+    let cli = parser::Cli::parse();
+    let format = cli.format;
+    i18n::init(cli.lang);
+
+    // errors are reported by `main` below, not by the default panic message
+    panic::set_hook(Box::new(|_| {}));
+    if let Err(payload) = panic::catch_unwind(move || run(cli)) {
+        let error = CliError::from_panic(payload);
+        error.report(format);
+        process::exit(error.kind.code());
+    }
+}
+
+fn run(cli: parser::Cli) {
+    let limits = cli.limits();
+
+    match cli.command {
+        Some(parser::Commands::Keygen {
+            command:
+                Some(KeygenCommands::Reconstruct {
+                    ids,
+                    shares,
+                    public_key,
+                }),
+            ..
+        }) => {
+            if ids.len() != shares.len() {
+                fail(CliError::input(Msg::IdsSharesMismatch(
+                    ids.len(),
+                    shares.len(),
+                )));
+            }
+            let shares = ids
+                .iter()
+                .zip(&shares)
+                .map(|(id, x_i)| (*id, hex_to_scalar(x_i).unwrap_or_else(|e| fail(e))))
+                .collect::<Vec<_>>();
+            let secret = reconstruct_secret(&shares).unwrap_or_else(|e| fail(e));
+            let X = ProjectivePoint::GENERATOR * secret;
+
+            // too few or inconsistent shares give a valid looking but wrong key
+            if let Some(public_key) = public_key {
+                let expected = hex_to_pp(&public_key).unwrap_or_else(|e| fail(e));
+                if X != expected {
+                    fail(CliError::verification(Msg::ReconstructedKeyMismatch));
+                }
+            }
+
+            Output::new(
+                format!(
+                    "{}\n{}",
+                    Msg::SecretKey(&scalar_to_hex(&secret)),
+                    Msg::PublicKey(&pp_to_hex(&X))
+                ),
+                json!({ "secret_key": scalar_to_hex(&secret), "public_key": pp_to_hex(&X) }),
+            )
+            .print(cli.format);
+        }
+        Some(parser::Commands::Keygen {
+            command: Some(KeygenCommands::Restore { backups }),
+            ..
+        }) => {
+            let backups = backups
+                .iter()
+                .map(|words| ShareBackup::from_words(words).unwrap_or_else(|e| fail(e)))
+                .collect::<Vec<_>>();
+            // the backups carry the threshold and key id, so this can't silently be wrong
+            let secret = recover_secret(&backups).unwrap_or_else(|e| fail(e));
+            let X = ProjectivePoint::GENERATOR * secret;
+
+            Output::new(
+                format!(
+                    "{}\n{}",
+                    Msg::SecretKey(&scalar_to_hex(&secret)),
+                    Msg::PublicKey(&pp_to_hex(&X))
+                ),
+                json!({ "secret_key": scalar_to_hex(&secret), "public_key": pp_to_hex(&X) }),
+            )
+            .print(cli.format);
+        }
+        Some(parser::Commands::Keygen {
+            command: None,
+            threshold,
+            num_shares,
+            output: output_file,
+            keystore: keystore_path,
+            share_dir,
+            output_dir,
+            mnemonic,
+            backup,
+            qr,
+            x_only,
+            encrypt_to,
+            passphrase,
+        }) => {
+            // both are required unless a subcommand is given
+            let (threshold, num_shares) = (threshold.unwrap(), num_shares.unwrap());
+            let mut keygen_output =
+                shamir_keygen_with_limits(num_shares as usize, threshold as usize, &limits)
+                    .unwrap_or_else(|e| fail(e));
+            if x_only {
+                keygen_output.normalize_even_y();
+            }
+
+            // shares only leave this process encrypted when a keystore is requested
+            if let Some(path) = &keystore_path {
+                let mut keystore = Keystore::new();
+                for participant in &keygen_output.participants {
+                    keystore
+                        .insert(participant, &read_passphrase(participant.id))
+                        .unwrap_or_else(|e| fail(e));
+                }
+                keystore.save(path).unwrap_or_else(|e| fail(e));
+            }
+            let share_files = share_dir.map(|dir| {
+                manifest::write_shares(&dir, &keygen_output, threshold as usize)
+                    .unwrap_or_else(|e| fail(e))
+            });
+            let encrypted_shares = match encrypt_to.is_empty() {
+                true => None,
+                false => Some(encrypt_shares(
+                    &encrypt_to,
+                    &keygen_output.participants,
+                    passphrase,
+                )),
+            };
+            let participant_files = output_dir.map(|dir| {
+                let mut sealed = BTreeMap::new();
+                for participant in &keygen_output.participants {
+                    let id = participant.id;
+                    if let Some(share) = encrypted_shares.as_ref().and_then(|e| e.get(&id)) {
+                        sealed.insert(
+                            id,
+                            SealedShare::Recipient {
+                                share: share.clone(),
+                            },
+                        );
+                    } else if passphrase {
+                        let mut keystore = Keystore::new();
+                        keystore
+                            .insert(participant, &read_passphrase(id))
+                            .unwrap_or_else(|e| fail(e));
+                        sealed.insert(id, SealedShare::Passphrase { keystore });
+                    }
+                }
+                keygen_file::write_participant_files(
+                    &dir,
+                    &keygen_output,
+                    threshold as usize,
+                    sealed,
+                )
+                .unwrap_or_else(|e| fail(e))
+            });
+            let reveal_shares = keystore_path.is_none()
+                && share_files.is_none()
+                && participant_files.is_none()
+                && encrypted_shares.is_none();
+            let share_backup = |participant: &Participant| {
+                ShareBackup::new(participant, &keygen_output.public_key, threshold as usize)
+                    .unwrap_or_else(|e| fail(e))
+                    .to_words()
+            };
+
+            let mut text = String::new();
+            for (i, participant) in keygen_output.participants.iter().enumerate() {
+                writeln!(text, "{}", Msg::ParticipantHeader(i as u64)).unwrap();
+                if reveal_shares {
+                    writeln!(text, "x_i = {}", scalar_to_hex(&participant.x_i)).unwrap();
+                    if mnemonic {
+                        writeln!(text, "words = {}", share_to_mnemonic(&participant.x_i)).unwrap();
+                    }
+                    if backup {
+                        writeln!(text, "backup = {}", share_backup(participant)).unwrap();
+                    }
+                }
+                if let Some(encrypted) = encrypted_shares
+                    .as_ref()
+                    .and_then(|e| e.get(&participant.id))
+                {
+                    writeln!(text, "encrypted = {}", encrypted).unwrap();
+                }
+                writeln!(text, "X_i = {}\n", pp_to_hex(&participant.X_i)).unwrap();
+            }
+
+            write!(
+                text,
+                "{}",
+                Msg::PublicKey(&pp_to_hex(&keygen_output.public_key))
+            )
+            .unwrap();
+            if x_only {
+                let X = pp_to_hex_x_only(&keygen_output.public_key);
+                write!(text, "\n{}", Msg::XOnlyPublicKey(&X)).unwrap();
+            }
+            for (i, commitment) in keygen_output.commitments.iter().enumerate() {
+                write!(text, "\n{}", Msg::Commitment(i, &pp_to_hex(commitment))).unwrap();
+            }
+            for path in share_files.iter().chain(&participant_files).flatten() {
+                write!(text, "\n{}", Msg::Wrote(path)).unwrap();
+            }
+
+            let mut json = json!({
+                "threshold": threshold,
+                "num_shares": num_shares,
+                "participants": keygen_output
+                    .participants
+                    .iter()
+                    .map(|p| match reveal_shares {
+                        true => {
+                            let mut share = json!({
+                                "id": p.id,
+                                "x_i": scalar_to_hex(&p.x_i),
+                                "X_i": pp_to_hex(&p.X_i),
+                            });
+                            if mnemonic {
+                                share["mnemonic"] = json!(share_to_mnemonic(&p.x_i));
+                            }
+                            if backup {
+                                share["backup"] = json!(share_backup(p));
+                            }
+                            share
+                        }
+                        false => {
+                            let mut share = json!({ "id": p.id, "X_i": pp_to_hex(&p.X_i) });
+                            if let Some(encrypted) =
+                                encrypted_shares.as_ref().and_then(|e| e.get(&p.id))
+                            {
+                                share["encrypted_share"] = json!(encrypted);
+                            }
+                            share
+                        }
+                    })
+                    .collect::<Vec<_>>(),
+                "public_key": pp_to_hex(&keygen_output.public_key),
+                "commitments": keygen_output
+                    .commitments
+                    .iter()
+                    .map(pp_to_hex)
+                    .collect::<Vec<_>>(),
+            });
+            if x_only {
+                json["public_key_x_only"] = json!(pp_to_hex_x_only(&keygen_output.public_key));
+            }
+            if let Some(files) = share_files.as_ref().or(participant_files.as_ref()) {
+                json["files"] = json!(files);
+            }
+
+            let mut output = Output::new(text, json);
+            if qr {
+                for participant in &keygen_output.participants {
+                    output = output.with_qr(
+                        format!("x_{}", participant.id),
+                        &scalar_to_hex(&participant.x_i),
+                    );
+                }
+            }
+
+            output.print(cli.format);
+            if let Some(path) = output_file {
+                KeygenFile::new(&keygen_output, threshold as usize, reveal_shares)
+                    .save(&path)
+                    .unwrap_or_else(|e| fail(e));
+            }
+        }
+        Some(parser::Commands::Schnorr { command }) => match command {
+            SchnorrCommands::Sign {
+                challange,
+                share,
+                keygen_file,
+                id,
+                nonce,
+                nonce_pool,
+                nonce_index,
+                nonce_registry,
+                qr,
+            } => {
+                let nonce = match (nonce, nonce_pool, nonce_index) {
+                    (Some(nonce), _, _) => hex_to_scalar(&nonce).unwrap_or_else(|e| fail(e)),
+                    (None, Some(path), Some(index)) => nonce_pool::take(&path, id, index),
+                    _ => unreachable!("clap requires --nonce or --nonce-pool and --nonce-index"),
+                };
+                let challange = hex_to_scalar(&challange).unwrap_or_else(|e| fail(e));
+
+                let participant = keygen_file::load_participant(id, share, keygen_file);
+                let signature = match nonce_registry {
+                    Some(path) => NonceRegistry::open(path)
+                        .and_then(|mut registry| {
+                            registry.partial_sign(&participant, &nonce, &challange)
+                        })
+                        .unwrap_or_else(|e| fail(e)),
+                    None => partial_sign(&participant, &nonce, &challange),
+                };
+
+                let mut output = Output::new(
+                    Msg::PartialSignature(&scalar_to_hex(&signature.s_i)).to_string(),
+                    json!({ "id": signature.id, "s_i": scalar_to_hex(&signature.s_i) }),
+                );
+                if qr {
+                    output = output.with_qr(
+                        format!("s_{}", signature.id),
+                        &scalar_to_hex(&signature.s_i),
+                    );
+                }
+                output.print(cli.format);
+            }
+            SchnorrCommands::Nonce { command } => match command {
+                NonceCommands::Generate {
+                    output: Some(path),
+                    count,
+                    ..
+                } => nonce_pool::generate(&path, count).print(cli.format),
+                NonceCommands::Generate { qr, .. } => {
+                    let r = generate_nonce();
+                    let R = compute_nonce_point(&r);
+                    let mut output = Output::new(
+                        format!(
+                            "r(nonce): {}\nR(G * r): {}",
+                            scalar_to_hex(&r),
+                            pp_to_hex(&R)
+                        ),
+                        json!({ "nonce": scalar_to_hex(&r), "nonce_point": pp_to_hex(&R) }),
+                    );
+                    // only the public R, the nonce r never leaves this device
+                    if qr {
+                        output = output.with_qr("R", &pp_to_hex(&R));
+                    }
+                    output.print(cli.format);
+                }
+                NonceCommands::Verify { nonce } => match hex_to_scalar(&nonce) {
+                    Ok(_) => Output::new(Msg::NonceValid.to_string(), json!({ "valid": true })),
+                    Err(e) => Output::new(
+                        Msg::Error(&e.to_string()).to_string(),
+                        json!({ "valid": false, "error": e.to_string() }),
+                    ),
+                }
+                .print(cli.format),
+            },
+            SchnorrCommands::Verify {
+                message,
+                signature,
+                key,
+                nonce,
+                framing,
+            } => {
+                let public_key = key.load();
+
+                let signature = match nonce {
+                    Some(nonce) => SchnorrSignature::from_hex_parts(&nonce, &signature)
+                        .unwrap_or_else(|e| fail(e)),
+                    None => hex::decode(&signature)
+                        .map_err(CliError::input)
+                        .and_then(|bytes| Ok(SchnorrSignature::from_bytes(&bytes)?))
+                        .unwrap_or_else(|e| fail(e)),
+                };
+                let valid = message
+                    .load()
+                    .verify(&signature, &public_key, &framing.config());
+                let text = match valid {
+                    true => Msg::SignatureValid,
+                    false => Msg::SignatureInvalid,
+                };
+                Output::new(text.to_string(), json!({ "valid": valid })).print(cli.format);
+                if !valid {
+                    process::exit(ErrorKind::Verification.code());
+                }
+            }
+            SchnorrCommands::SignFile {
+                path,
+                key,
+                keystore,
+                signature,
+                output,
+                framing,
+            } => sign_file::sign(
+                &path,
+                &key,
+                keystore.as_deref(),
+                signature.as_deref(),
+                output.as_deref(),
+                &framing,
+            )
+            .print(cli.format),
+            SchnorrCommands::VerifyFile {
+                path,
+                signature,
+                key,
+                framing,
+            } => sign_file::verify(&path, signature.as_deref(), &key, &framing, cli.format),
+            SchnorrCommands::VerifyProof {
+                proof: path,
+                key,
+                ids,
+                public_shares,
+                framing,
+            } => {
+                let public_shares = match &key.keygen_file {
+                    Some(path) => KeygenFile::load(path)
+                        .and_then(|file| file.public_shares())
+                        .unwrap_or_else(|e| fail(e)),
+                    None => {
+                        if ids.len() != public_shares.len() {
+                            fail(CliError::input(Msg::IdsSharesMismatch(
+                                ids.len(),
+                                public_shares.len(),
+                            )));
+                        }
+                        ids.iter()
+                            .zip(&public_shares)
+                            .map(|(id, X_i)| (*id, hex_to_pp(X_i).unwrap_or_else(|e| fail(e))))
+                            .collect()
+                    }
+                };
+                proof::verify(&path, &key.load(), &public_shares, &framing.config())
+                    .print(cli.format)
+            }
+            SchnorrCommands::Challenge {
+                message,
+                ids,
+                nonces,
+                key,
+                framing,
+            } => {
+                let nonce_pairs = ids
+                    .clone()
+                    .into_iter()
+                    .zip(nonces)
+                    .map(|(id, nonce)| (id, hex_to_pp(&nonce).unwrap_or_else(|e| fail(e))))
+                    .collect::<Vec<_>>();
+                let signers = SignerSet::new(&ids, ids.len()).unwrap_or_else(|e| fail(e));
+                let R = aggregate_nonce_with_signers(&nonce_pairs, &signers)
+                    .unwrap_or_else(|e| fail(e));
+                let c = message.load().challenge(&framing.config(), &R, &key.load());
+
+                Output::new(
+                    Msg::Challenge(&scalar_to_hex(&c)).to_string(),
+                    json!({ "challenge": scalar_to_hex(&c), "nonce": pp_to_hex(&R) }),
+                )
+                .print(cli.format);
+            }
+            SchnorrCommands::Combine {
+                ids,
+                signatures,
+                nonce,
+                qr,
+            } => {
+                let nonce = hex_to_pp(&nonce).unwrap_or_else(|e| fail(e));
+                let signers = SignerSet::new(&ids, ids.len()).unwrap_or_else(|e| fail(e));
+                let partial_signatures = signatures
+                    .iter()
+                    .zip(ids)
+                    .map(|(s, id)| {
+                        PartialSignature::new(id, hex_to_scalar(s).unwrap_or_else(|e| fail(e)))
+                    })
+                    .collect::<Vec<_>>();
+                let signature =
+                    finalize_signature_with_signers(&partial_signatures, nonce, &signers)
+                        .unwrap_or_else(|e| fail(e));
+                let compact = hex::encode(signature.to_bytes().unwrap_or_else(|e| fail(e)));
+                let mut output = Output::new(
+                    format!(
+                        "{}\n{}",
+                        Msg::InterpolatedSignature(&scalar_to_hex(&signature.s)),
+                        Msg::CompactSignature(&compact)
+                    ),
+                    json!({
+                        "nonce": pp_to_hex(&signature.R),
+                        "signature": scalar_to_hex(&signature.s),
+                        "compact": compact,
+                    }),
+                );
+                if qr {
+                    output = output.with_qr("R || s", &compact);
+                }
+                output.print(cli.format);
+            }
+            SchnorrCommands::PartialVerify {
+                signature,
+                id,
+                public_share,
+                keygen_file,
+                nonce,
+                challange,
+            } => {
+                let X_i = match (public_share, keygen_file) {
+                    (Some(X_i), _) => hex_to_pp(&X_i).unwrap_or_else(|e| fail(e)),
+                    (None, Some(path)) => KeygenFile::load(&path)
+                        .and_then(|file| file.public_shares())
+                        .unwrap_or_else(|e| fail(e))
+                        .into_iter()
+                        .find_map(|(i, X_i)| (i == id).then_some(X_i))
+                        .unwrap_or_else(|| fail(CliError::input(Msg::ShareNotInKeygenFile(id)))),
+                    (None, None) => unreachable!("clap requires --public-share or --keygen-file"),
+                };
+                let partial = PartialSignature {
+                    id,
+                    s_i: hex_to_scalar(&signature).unwrap_or_else(|e| fail(e)),
+                    R_i: Some(hex_to_pp(&nonce).unwrap_or_else(|e| fail(e))),
+                    X_i: Some(X_i),
+                };
+                let challange = hex_to_scalar(&challange).unwrap_or_else(|e| fail(e));
+
+                let valid = partial.verify(&challange).is_ok();
+                let text = match valid {
+                    true => Msg::PartialSignatureValid(id),
+                    false => Msg::PartialSignatureInvalid(id),
+                };
+                Output::new(text.to_string(), json!({ "id": id, "valid": valid }))
+                    .print(cli.format);
+                if !valid {
+                    process::exit(ErrorKind::Verification.code());
+                }
+            }
+        },
+        Some(parser::Commands::Dkg { command }) => dkg::run(command, cli.format, &limits),
+        Some(parser::Commands::Repair { command }) => repair::run(command, cli.format),
+        Some(parser::Commands::Enroll { command }) => repair::run_enroll(command, cli.format),
+        Some(parser::Commands::Revoke {
+            command: Some(command),
+            ..
+        }) => revoke::verify(command, cli.format),
+        Some(parser::Commands::Revoke {
+            command: None,
+            keygen_file,
+            id,
+            output,
+            record,
+            framing,
+        }) => revoke::run(
+            &keygen_file.unwrap(),
+            id.unwrap(),
+            &output.unwrap(),
+            &record.unwrap(),
+            &framing.config(),
+        )
+        .print(cli.format),
+        Some(parser::Commands::Keystore { command }) => keystore::run(command, cli.format),
+        Some(parser::Commands::Ceremony { command }) => ceremony::run(command, cli.format),
+        Some(parser::Commands::Manifest { command }) => manifest::run(command, cli.format),
+        Some(parser::Commands::Vectors { command }) => vectors::run(command, cli.format),
+        Some(parser::Commands::Pem { command }) => pem::run(command, cli.format),
+        Some(parser::Commands::Recipient { command }) => recipient::run(command, cli.format),
+        Some(parser::Commands::Signer { command }) => signer::run(command, cli.format),
+        Some(parser::Commands::Audit { keygen_file }) => audit::run(&keygen_file, cli.format),
+        _ => unreachable!(),
+    }
+}
+
+/// a SEC1 public key, or a 32-byte x-only one lifted to even Y.
+/// hex `EncryptedShare` per participant id. recipients are `<id>=<key>`, or plain
+/// keys taken in id order. every share needs a recipient unless `partial`.
+fn encrypt_shares(
+    recipients: &[String],
+    participants: &[Participant],
+    partial: bool,
+) -> BTreeMap<u64, String> {
+    let count_mismatch = || {
+        CliError::input(Msg::RecipientCountMismatch(
+            recipients.len(),
+            participants.len(),
+        ))
+    };
+    let mut keys = BTreeMap::new();
+    for (i, recipient) in recipients.iter().enumerate() {
+        let (id, key) = match recipient.split_once('=') {
+            Some((id, key)) => match id.parse::<u64>() {
+                Ok(id) if participants.iter().any(|p| p.id == id) => (id, key),
+                _ => fail(CliError::input(Msg::InvalidRecipient(recipient))),
+            },
+            None => match participants.get(i) {
+                Some(participant) => (participant.id, recipient.as_str()),
+                None => fail(count_mismatch()),
+            },
+        };
+        let key = hex_to_pp(key).unwrap_or_else(|e| fail(e));
+        if keys.insert(id, key).is_some() {
+            fail(CliError::input(Msg::InvalidRecipient(recipient)));
+        }
+    }
+    if !partial && keys.len() != participants.len() {
+        fail(count_mismatch());
+    }
+
+    participants
+        .iter()
+        .filter_map(|participant| {
+            let recipient = keys.get(&participant.id)?;
+            let encrypted = encrypt_share_for(recipient, participant).unwrap_or_else(|e| fail(e));
+            Some((participant.id, hex::encode(encrypted.to_bytes())))
+        })
+        .collect()
+}
+
+fn parse_public_key(hex: &str) -> ProjectivePoint {
+    match hex.len() {
+        64 => hex_to_pp_x_only(hex),
+        _ => hex_to_pp(hex),
+    }
+    .unwrap_or_else(|e| fail(e))
+}
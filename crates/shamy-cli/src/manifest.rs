@@ -0,0 +1,272 @@
+#![allow(non_snake_case)]
+
+use crate::error::{CliError, fail};
+use crate::i18n::Msg;
+use crate::output::Output;
+use crate::parser::{ManifestCommands, OutputFormat};
+use k256::ProjectivePoint;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use shamy_core::{
+    shamir::KeygenOutput,
+    threshold::Participant,
+    util::{fingerprint, hex_to_scalar, pp_to_hex, scalar_to_hex},
+};
+use std::{
+    fmt::Write as _,
+    fs,
+    path::{Path, PathBuf},
+};
+
+//--------------------------------------------------------------------
+// Share distribution
+//--------------------------------------------------------------------
+//
+// keygen --share-dir writes
+//
+//   share-<key fingerprint>-<id>.json   one per participant, x_i in clear
+//   manifest.json                       file → sha256, id, fingerprint of X_i
+//
+// file names only depend on the key and the id, so a courier can check
+// the label on a USB stick against the manifest without opening the file.
+//
+// ⚠️ the manifest is only as trustworthy as the channel it came over,
+//    compare its key fingerprint with the dealer (e.g. read it out on a call).
+//
+
+const MANIFEST_VERSION: u8 = 1;
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// everything a participant needs to sign, written to its own file.
+#[derive(Serialize, Deserialize)]
+struct ShareFile {
+    version: u8,
+    id: u64,
+    threshold: usize,
+    num_shares: usize,
+    x_i: String,
+    X_i: String,
+    public_key: String,
+    commitments: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ManifestEntry {
+    file: String,
+    id: u64,
+    sha256: String,
+    /// fingerprint of X_i
+    fingerprint: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    version: u8,
+    public_key: String,
+    fingerprint: String,
+    threshold: usize,
+    num_shares: usize,
+    shares: Vec<ManifestEntry>,
+}
+
+/// deterministic name of the file holding share `id` of `public_key`.
+pub fn share_file_name(public_key: &ProjectivePoint, id: u64) -> String {
+    format!("share-{}-{}.json", fingerprint(public_key), id)
+}
+
+/// write one file per share plus the manifest into `dir`, returns the written paths.
+/// refuses to overwrite anything so an earlier key can't be clobbered by accident.
+pub fn write_shares(
+    dir: &Path,
+    keygen_output: &KeygenOutput,
+    threshold: usize,
+) -> Result<Vec<PathBuf>, CliError> {
+    fs::create_dir_all(dir).map_err(|e| CliError::io(Msg::CannotWrite(dir, &e.to_string())))?;
+
+    let public_key = pp_to_hex(&keygen_output.public_key);
+    let commitments = keygen_output
+        .commitments
+        .iter()
+        .map(pp_to_hex)
+        .collect::<Vec<_>>();
+
+    let mut files = Vec::new();
+    let mut entries = Vec::new();
+    for participant in &keygen_output.participants {
+        let file = share_file_name(&keygen_output.public_key, participant.id);
+        let share = ShareFile {
+            version: MANIFEST_VERSION,
+            id: participant.id,
+            threshold,
+            num_shares: keygen_output.participants.len(),
+            x_i: scalar_to_hex(&participant.x_i),
+            X_i: pp_to_hex(&participant.X_i),
+            public_key: public_key.clone(),
+            commitments: commitments.clone(),
+        };
+        let data = serde_json::to_vec_pretty(&share).unwrap();
+
+        files.push(create(&dir.join(&file), &data)?);
+        entries.push(ManifestEntry {
+            file,
+            id: participant.id,
+            sha256: hex::encode(Sha256::digest(&data)),
+            fingerprint: fingerprint(&participant.X_i),
+        });
+    }
+
+    let manifest = Manifest {
+        version: MANIFEST_VERSION,
+        public_key,
+        fingerprint: fingerprint(&keygen_output.public_key),
+        threshold,
+        num_shares: keygen_output.participants.len(),
+        shares: entries,
+    };
+    let data = serde_json::to_vec_pretty(&manifest).unwrap();
+    files.push(create(&dir.join(MANIFEST_FILE), &data)?);
+
+    Ok(files)
+}
+
+/// write `data` to a new file at `path`, never replacing an existing one.
+pub fn create(path: &Path, data: &[u8]) -> Result<PathBuf, CliError> {
+    if path.exists() {
+        return Err(CliError::io(Msg::AlreadyExists(path)));
+    }
+    fs::write(path, data).map_err(|e| CliError::io(Msg::CannotWrite(path, &e.to_string())))?;
+
+    Ok(path.to_path_buf())
+}
+
+/// outcome of checking one share file.
+enum Status {
+    Ok(u64),
+    Missing,
+    NotListed,
+    ChecksumMismatch,
+    WrongShare,
+}
+
+impl Status {
+    fn code(&self) -> &'static str {
+        match self {
+            Status::Ok(_) => "ok",
+            Status::Missing => "missing",
+            Status::NotListed => "not_listed",
+            Status::ChecksumMismatch => "checksum_mismatch",
+            Status::WrongShare => "wrong_share",
+        }
+    }
+
+    fn message(&self) -> Msg<'static> {
+        match self {
+            Status::Ok(id) => Msg::ManifestShareOk(*id),
+            Status::Missing => Msg::ManifestShareMissing,
+            Status::NotListed => Msg::ManifestShareNotListed,
+            Status::ChecksumMismatch => Msg::ManifestChecksumMismatch,
+            Status::WrongShare => Msg::ManifestWrongShare,
+        }
+    }
+}
+
+fn check(manifest: &Manifest, path: &Path) -> Status {
+    let name = path.file_name().and_then(|name| name.to_str());
+    let Some(entry) = manifest
+        .shares
+        .iter()
+        .find(|e| Some(e.file.as_str()) == name)
+    else {
+        return Status::NotListed;
+    };
+    let Ok(data) = fs::read(path) else {
+        return Status::Missing;
+    };
+    if hex::encode(Sha256::digest(&data)) != entry.sha256 {
+        return Status::ChecksumMismatch;
+    }
+
+    // the checksum matched, so this only fails for an inconsistent manifest
+    let consistent = || -> Option<bool> {
+        let share: ShareFile = serde_json::from_slice(&data).ok()?;
+        let participant = Participant::from_secret(share.id, hex_to_scalar(&share.x_i).ok()?);
+        Some(
+            share.id == entry.id
+                && share.public_key == manifest.public_key
+                && pp_to_hex(&participant.X_i) == share.X_i
+                && fingerprint(&participant.X_i) == entry.fingerprint,
+        )
+    };
+    match consistent() {
+        Some(true) => Status::Ok(entry.id),
+        _ => Status::WrongShare,
+    }
+}
+
+pub fn run(command: ManifestCommands, format: OutputFormat) {
+    match command {
+        ManifestCommands::Verify {
+            manifest: manifest_path,
+            files,
+        } => {
+            let data = fs::read(&manifest_path).unwrap_or_else(|e| {
+                fail(CliError::io(Msg::CannotRead(
+                    &manifest_path,
+                    &e.to_string(),
+                )))
+            });
+            let manifest: Manifest = serde_json::from_slice(&data)
+                .unwrap_or_else(|e| fail(CliError::input(Msg::InvalidManifest(&e.to_string()))));
+            if manifest.version != MANIFEST_VERSION {
+                fail(CliError::input(Msg::InvalidManifest(&format!(
+                    "version {}",
+                    manifest.version
+                ))));
+            }
+
+            // without explicit files, expect every share next to the manifest
+            let files = match files.is_empty() {
+                true => {
+                    let dir = manifest_path.parent().unwrap_or(Path::new(""));
+                    manifest.shares.iter().map(|e| dir.join(&e.file)).collect()
+                }
+                false => files,
+            };
+
+            let mut text = format!("{}\n", Msg::ManifestKey(&manifest.fingerprint));
+            let mut results = Vec::new();
+            let mut failed = 0;
+            for path in &files {
+                let status = check(&manifest, path);
+                let ok = matches!(status, Status::Ok(_));
+                if !ok {
+                    failed += 1;
+                }
+                let mark = if ok { "✅" } else { "❌" };
+                writeln!(text, "{} {}: {}", mark, path.display(), status.message()).unwrap();
+                results.push(json!({ "file": path, "status": status.code() }));
+            }
+            if failed == 0 {
+                write!(text, "{}", Msg::ManifestVerified(files.len())).unwrap();
+            }
+
+            Output::new(
+                text.trim_end(),
+                json!({
+                    "public_key": manifest.public_key,
+                    "fingerprint": manifest.fingerprint,
+                    "files": results,
+                    "valid": failed == 0,
+                }),
+            )
+            .print(format);
+            if failed > 0 {
+                fail(CliError::verification(Msg::ManifestFailed(
+                    failed,
+                    files.len(),
+                )));
+            }
+        }
+    }
+}
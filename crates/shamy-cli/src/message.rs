@@ -0,0 +1,80 @@
+#![allow(non_snake_case)]
+
+use crate::error::{CliError, fail};
+use crate::i18n::Msg;
+use crate::parser::{MessageArgs, Prehash};
+use k256::{ProjectivePoint, Scalar};
+use sha2::{Digest, Sha256};
+use shamy_core::schnorr::{SchnorrSignature, SigningConfig};
+use std::{
+    fs::File,
+    io::{self, Read},
+    path::Path,
+};
+
+/// the bytes being signed, or their digest with `--prehash`.
+pub enum Message {
+    Raw(Vec<u8>),
+    Prehashed([u8; 32]),
+}
+
+impl MessageArgs {
+    /// read the message from the argument, a file or stdin. with `--prehash`
+    /// the input is streamed into the hash instead of being held in memory.
+    pub fn load(&self) -> Message {
+        let (mut reader, path): (Box<dyn Read>, &Path) = match (&self.message, &self.message_file) {
+            (Some(message), _) => (Box::new(message.as_bytes()), Path::new("--message")),
+            (None, Some(path)) if path.as_os_str() == "-" => (Box::new(io::stdin().lock()), path),
+            (None, Some(path)) => {
+                (
+                    Box::new(File::open(path).unwrap_or_else(|e| {
+                        fail(CliError::io(Msg::CannotRead(path, &e.to_string())))
+                    })),
+                    path,
+                )
+            }
+            (None, None) => unreachable!("clap requires --message or --message-file"),
+        };
+        let fail =
+            |e: io::Error| -> ! { fail(CliError::io(Msg::CannotRead(path, &e.to_string()))) };
+
+        match self.prehash {
+            Some(Prehash::Sha256) => {
+                let mut hasher = Sha256::new();
+                io::copy(&mut reader, &mut hasher).unwrap_or_else(|e| fail(e));
+                Message::Prehashed(hasher.finalize().into())
+            }
+            None => {
+                let mut msg = Vec::new();
+                reader.read_to_end(&mut msg).unwrap_or_else(|e| fail(e));
+                Message::Raw(msg)
+            }
+        }
+    }
+}
+
+impl Message {
+    pub fn challenge(
+        &self,
+        config: &SigningConfig,
+        R: &ProjectivePoint,
+        X: &ProjectivePoint,
+    ) -> Scalar {
+        match self {
+            Message::Raw(msg) => config.challenge(R, X, msg),
+            Message::Prehashed(digest) => config.challenge_prehashed(R, X, digest),
+        }
+    }
+
+    pub fn verify(
+        &self,
+        signature: &SchnorrSignature,
+        X: &ProjectivePoint,
+        config: &SigningConfig,
+    ) -> bool {
+        match self {
+            Message::Raw(msg) => signature.verify_with_config(msg, X, config),
+            Message::Prehashed(digest) => signature.verify_prehashed(digest, X, config),
+        }
+    }
+}
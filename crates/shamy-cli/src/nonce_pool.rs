@@ -0,0 +1,127 @@
+#![allow(non_snake_case)]
+
+use crate::error::{CliError, fail};
+use crate::i18n::Msg;
+use crate::output::Output;
+use k256::Scalar;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use shamy_core::{
+    Error,
+    schnorr::{compute_nonce_point, generate_nonce},
+    util::{hex_to_scalar, pp_to_hex, scalar_to_hex},
+};
+use std::{fmt::Write as _, fs, path::Path};
+
+//--------------------------------------------------------------------
+// Nonce pools
+//--------------------------------------------------------------------
+//
+// `nonce generate --count N --output pool.json` appends N nonces to a
+// pool file and prints only their points R[k], to be handed out ahead of
+// the signing sessions. `schnorr sign --nonce-pool pool.json --nonce-index k`
+// deletes r[k] from the file before signing, so an index signs once.
+//
+// ⚠️ the file holds secret nonces, keep it next to the share.
+// ⚠️ these are single pre-committed nonces, only sign for a coordinator
+//    that doesn't run sessions concurrently (see `shamy_core::frost` for
+//    nonce pairs bound to the message).
+//
+
+const NONCE_POOL_VERSION: u8 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct NonceEntry {
+    index: u64,
+    nonce: String,
+    nonce_point: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NoncePoolFile {
+    version: u8,
+    /// index of the next generated nonce, every lower one was handed out.
+    next_index: u64,
+    nonces: Vec<NonceEntry>,
+}
+
+impl NoncePoolFile {
+    fn load(path: &Path) -> Result<Self, CliError> {
+        let data =
+            fs::read(path).map_err(|e| CliError::io(Msg::CannotRead(path, &e.to_string())))?;
+        let pool: NoncePoolFile = serde_json::from_slice(&data)
+            .map_err(|e| CliError::input(Msg::InvalidNoncePool(&e.to_string())))?;
+        if pool.version != NONCE_POOL_VERSION {
+            return Err(CliError::input(Msg::UnsupportedNoncePoolVersion(
+                pool.version,
+            )));
+        }
+
+        Ok(pool)
+    }
+
+    fn save(&self, path: &Path) -> Result<(), CliError> {
+        let data = serde_json::to_string_pretty(self).unwrap() + "\n";
+        fs::write(path, data).map_err(|e| CliError::io(Msg::CannotWrite(path, &e.to_string())))
+    }
+}
+
+/// append `count` fresh nonces to the pool in `path`, creating it if needed.
+pub fn generate(path: &Path, count: usize) -> Output {
+    let mut pool = match path.exists() {
+        true => NoncePoolFile::load(path).unwrap_or_else(|e| fail(e)),
+        false => NoncePoolFile {
+            version: NONCE_POOL_VERSION,
+            next_index: 0,
+            nonces: Vec::new(),
+        },
+    };
+
+    let mut points = Vec::with_capacity(count);
+    for _ in 0..count {
+        let r = generate_nonce();
+        let entry = NonceEntry {
+            index: pool.next_index,
+            nonce: scalar_to_hex(&r),
+            nonce_point: pp_to_hex(&compute_nonce_point(&r)),
+        };
+        points.push((entry.index, entry.nonce_point.clone()));
+        pool.nonces.push(entry);
+        pool.next_index += 1;
+    }
+    pool.save(path).unwrap_or_else(|e| fail(e));
+
+    let mut text = String::new();
+    for (index, R) in &points {
+        writeln!(text, "R[{}] = {}", index, R).unwrap();
+    }
+    write!(text, "{}", Msg::NoncePoolSize(path, pool.nonces.len())).unwrap();
+    Output::new(
+        text,
+        json!({
+            "file": path,
+            "nonce_points": points
+                .iter()
+                .map(|(index, R)| json!({ "index": index, "nonce_point": R }))
+                .collect::<Vec<_>>(),
+            "remaining": pool.nonces.len(),
+        }),
+    )
+}
+
+/// remove nonce `index` of signer `id` from the pool in `path` and return it.
+/// the file is rewritten before the nonce is returned.
+pub fn take(path: &Path, id: u64, index: u64) -> Scalar {
+    let mut pool = NoncePoolFile::load(path).unwrap_or_else(|e| fail(e));
+    let Some(position) = pool.nonces.iter().position(|entry| entry.index == index) else {
+        fail(match index < pool.next_index {
+            true => Error::NonceReused { id, index },
+            false => Error::UnknownNonce { id, index },
+        })
+    };
+
+    let entry = pool.nonces.remove(position);
+    pool.save(path).unwrap_or_else(|e| fail(e));
+    hex_to_scalar(&entry.nonce)
+        .unwrap_or_else(|e| fail(CliError::input(Msg::InvalidNoncePool(&e.to_string()))))
+}
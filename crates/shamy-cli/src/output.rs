@@ -0,0 +1,45 @@
+use crate::error::{CliError, fail};
+use crate::parser::OutputFormat;
+use crate::qr::QrCode;
+use serde_json::Value;
+
+/// result of a command, renderable as free-form text or as JSON.
+pub struct Output {
+    text: String,
+    json: Value,
+    qr_codes: Vec<(String, QrCode)>,
+}
+
+impl Output {
+    pub fn new(text: impl Into<String>, json: Value) -> Self {
+        Self {
+            text: text.into(),
+            json,
+            qr_codes: Vec::new(),
+        }
+    }
+
+    /// append `payload` as a QR code under `label` to the text output.
+    pub fn with_qr(mut self, label: impl Into<String>, payload: &str) -> Self {
+        let qr = QrCode::encode(payload.as_bytes()).unwrap_or_else(|e| fail(CliError::input(e)));
+        self.qr_codes.push((label.into(), qr));
+        self
+    }
+
+    pub fn render(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Text => {
+                let mut text = self.text.clone();
+                for (label, qr) in &self.qr_codes {
+                    text += &format!("\n\n{label}\n{}", qr.render());
+                }
+                text
+            }
+            OutputFormat::Json => serde_json::to_string_pretty(&self.json).unwrap(),
+        }
+    }
+
+    pub fn print(&self, format: OutputFormat) {
+        println!("{}", self.render(format));
+    }
+}
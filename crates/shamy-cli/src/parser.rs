@@ -0,0 +1,901 @@
+use crate::i18n::Locale;
+use clap::{Args, Subcommand, ValueEnum};
+use shamy_core::{
+    Limits,
+    schnorr::{SessionId, SigningConfig},
+};
+use std::{net::SocketAddr, path::PathBuf};
+
+pub use clap::Parser;
+
+#[derive(Parser)]
+#[command(arg_required_else_help = true)]
+#[command(version, about, long_about = None)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+
+    #[arg(short, long)]
+    pub verbose: bool,
+
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+
+    #[arg(help = "Language of messages, defaults to SHAMY_LANG or LANG")]
+    #[arg(long, global = true, value_enum)]
+    pub lang: Option<Locale>,
+
+    #[arg(help = "Largest committee accepted by keygen and dkg")]
+    #[arg(long, global = true, default_value_t = Limits::DEFAULT_MAX_PARTICIPANTS)]
+    pub max_participants: usize,
+
+    #[arg(help = "Largest threshold accepted by keygen and dkg")]
+    #[arg(long, global = true, default_value_t = Limits::DEFAULT_MAX_THRESHOLD)]
+    pub max_threshold: usize,
+
+    #[arg(help = "Accept threshold 1 in keygen and dkg, every share then signs alone")]
+    #[arg(long, global = true)]
+    pub allow_trivial_threshold: bool,
+}
+
+impl Cli {
+    pub fn limits(&self) -> Limits {
+        Limits::default()
+            .with_max_participants(self.max_participants)
+            .with_max_threshold(self.max_threshold)
+            .with_trivial_threshold(self.allow_trivial_threshold)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Subcommand)]
+pub enum Commands {
+    #[command(subcommand_negates_reqs = true, args_conflicts_with_subcommands = true)]
+    Keygen {
+        #[command(subcommand)]
+        command: Option<KeygenCommands>,
+
+        #[arg(short, long, required = true)]
+        threshold: Option<u32>,
+
+        #[arg(short, long, required = true)]
+        num_shares: Option<u32>,
+
+        #[arg(help = "Also write the result to this JSON keygen file")]
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        #[arg(help = "Encrypt every share into this keystore instead of printing it")]
+        #[arg(short, long)]
+        keystore: Option<PathBuf>,
+
+        #[arg(help = "Write one file per share and a checksum manifest into this directory")]
+        #[arg(long, conflicts_with = "keystore")]
+        share_dir: Option<PathBuf>,
+
+        #[arg(
+            help = "Write one keygen file per participant and a public summary into this directory"
+        )]
+        #[arg(long, conflicts_with_all = ["keystore", "share_dir"])]
+        output_dir: Option<PathBuf>,
+
+        #[arg(help = "Also print every share as a 24-word BIP-39 phrase for paper backups")]
+        #[arg(long, conflicts_with_all = ["keystore", "share_dir", "output_dir"])]
+        mnemonic: bool,
+
+        #[arg(help = "Also print every share as a 32-word backup with the key id and threshold")]
+        #[arg(long, conflicts_with_all = ["keystore", "share_dir", "output_dir"])]
+        backup: bool,
+
+        #[arg(help = "Also print every share as a QR code, for moving it to an air-gapped device")]
+        #[arg(long, conflicts_with_all = ["keystore", "share_dir", "output_dir"])]
+        qr: bool,
+
+        #[arg(help = "Flip the key to even Y and print its 32-byte x-only form (BIP-340)")]
+        #[arg(long)]
+        x_only: bool,
+
+        #[arg(
+            help = "Encrypt share i to the i-th recipient public key, or share <id> to <id>=<public key>, instead of printing it"
+        )]
+        #[arg(long, num_args = 1.., conflicts_with_all = ["keystore", "share_dir", "mnemonic", "backup", "qr"])]
+        encrypt_to: Vec<String>,
+
+        #[arg(
+            help = "Encrypt every share in --output-dir without a recipient key under its participant's passphrase"
+        )]
+        #[arg(long, requires = "output_dir")]
+        passphrase: bool,
+    },
+    Schnorr {
+        #[command(subcommand)]
+        command: SchnorrCommands,
+    },
+    Dkg {
+        #[command(subcommand)]
+        command: DkgCommands,
+    },
+    /// Recompute a lost share with t other participants
+    Repair {
+        #[command(subcommand)]
+        command: RepairCommands,
+    },
+    /// Issue a share for a new id with t participants, the group key stays the same
+    Enroll {
+        #[command(subcommand)]
+        command: EnrollCommands,
+    },
+    /// Remove a participant, refresh the other shares and sign a revocation record
+    #[command(subcommand_negates_reqs = true, args_conflicts_with_subcommands = true)]
+    Revoke {
+        #[command(subcommand)]
+        command: Option<RevokeCommands>,
+
+        #[arg(help = "Keygen file holding every share")]
+        #[arg(long, required = true)]
+        keygen_file: Option<PathBuf>,
+
+        #[arg(help = "Id of the participant to revoke")]
+        #[arg(short, long, required = true)]
+        id: Option<u64>,
+
+        #[arg(help = "Write the refreshed keygen file here")]
+        #[arg(short, long, required = true)]
+        output: Option<PathBuf>,
+
+        #[arg(help = "Write the signed revocation record here")]
+        #[arg(long, required = true)]
+        record: Option<PathBuf>,
+
+        #[command(flatten)]
+        framing: FramingArgs,
+    },
+    Keystore {
+        #[command(subcommand)]
+        command: KeystoreCommands,
+    },
+    Ceremony {
+        #[command(subcommand)]
+        command: CeremonyCommands,
+    },
+    Manifest {
+        #[command(subcommand)]
+        command: ManifestCommands,
+    },
+    /// Recheck every share of a stored keygen result against its commitments
+    Audit {
+        #[arg(help = "Keygen file written by `keygen --output`")]
+        #[arg(long)]
+        keygen_file: PathBuf,
+    },
+    /// Known-answer test vectors for cross-implementation testing
+    Vectors {
+        #[command(subcommand)]
+        command: VectorsCommands,
+    },
+    /// Move group keys and shares as PEM blocks
+    Pem {
+        #[command(subcommand)]
+        command: PemCommands,
+    },
+    /// Long-term keys for receiving shares encrypted by `keygen --encrypt-to`
+    Recipient {
+        #[command(subcommand)]
+        command: RecipientCommands,
+    },
+    Signer {
+        #[command(subcommand)]
+        command: SignerCommands,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum KeygenCommands {
+    /// Recover the secret key from t shares, e.g. to import it into a single-signer wallet
+    Reconstruct {
+        #[arg(help = "Ids of participants (same order as shares)")]
+        #[arg(short, long, value_parser, num_args = 1.., value_delimiter = ' ')]
+        ids: Vec<u64>,
+
+        #[arg(help = "Shares of participants (same order as ids)")]
+        #[arg(short, long, value_parser, num_args = 1.., value_delimiter = ' ')]
+        shares: Vec<String>,
+
+        #[arg(help = "Fail unless the reconstructed key matches this public key")]
+        #[arg(short, long)]
+        public_key: Option<String>,
+    },
+    /// Recover the secret key from t share backups written by `keygen --backup`
+    Restore {
+        #[arg(help = "A 32-word share backup, repeat for every share")]
+        #[arg(short, long = "words", required = true)]
+        backups: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SchnorrCommands {
+    Sign {
+        #[arg(short, long)]
+        challange: String,
+
+        #[arg(short, long, required_unless_present = "keygen_file")]
+        share: Option<String>,
+
+        #[arg(help = "Take share --id from this keygen file")]
+        #[arg(long, conflicts_with = "share")]
+        keygen_file: Option<PathBuf>,
+
+        #[arg(short, long)]
+        id: u64,
+
+        #[arg(short, long, required_unless_present = "nonce_pool")]
+        nonce: Option<String>,
+
+        #[arg(help = "Take nonce --nonce-index from this pool and delete it from the file")]
+        #[arg(long, conflicts_with = "nonce", requires = "nonce_index")]
+        nonce_pool: Option<PathBuf>,
+
+        #[arg(long, requires = "nonce_pool")]
+        nonce_index: Option<u64>,
+
+        #[arg(help = "Record the nonce in this file and refuse one signed with before")]
+        #[arg(long)]
+        nonce_registry: Option<PathBuf>,
+
+        #[arg(help = "Also print the result as a QR code, for moving it to an air-gapped device")]
+        #[arg(long)]
+        qr: bool,
+    },
+    Verify {
+        #[command(flatten)]
+        message: MessageArgs,
+
+        #[arg(
+            help = "s, or the compact signature R || s (65 or 64 bytes) when --nonce is omitted"
+        )]
+        #[arg(short, long)]
+        signature: String,
+
+        #[command(flatten)]
+        key: KeyArgs,
+
+        #[arg(short, long)]
+        nonce: Option<String>,
+
+        #[command(flatten)]
+        framing: FramingArgs,
+    },
+    Combine {
+        #[arg(short, long, value_parser, num_args = 1.., value_delimiter = ' ')]
+        ids: Vec<u64>,
+
+        #[arg(short, long, value_parser, num_args = 1.., value_delimiter = ' ')]
+        signatures: Vec<String>,
+
+        #[arg(short, long)]
+        nonce: String,
+
+        #[arg(help = "Also print the result as a QR code, for moving it to an air-gapped device")]
+        #[arg(long)]
+        qr: bool,
+    },
+    Nonce {
+        #[command(subcommand)]
+        command: NonceCommands,
+    },
+    /// Check one signer's partial signature, s_i*G == R_i + c*X_i
+    PartialVerify {
+        #[arg(help = "Partial signature s_i")]
+        #[arg(short, long)]
+        signature: String,
+
+        #[arg(short, long)]
+        id: u64,
+
+        #[arg(help = "Public share X_i of the signer")]
+        #[arg(long, required_unless_present = "keygen_file")]
+        public_share: Option<String>,
+
+        #[arg(help = "Take the public share of --id from this keygen file")]
+        #[arg(long, conflicts_with = "public_share")]
+        keygen_file: Option<PathBuf>,
+
+        #[arg(help = "Nonce point R_i of the signer")]
+        #[arg(short, long)]
+        nonce: String,
+
+        #[arg(short, long)]
+        challange: String,
+    },
+    /// Check an aggregation proof and print which quorum produced the signature
+    VerifyProof {
+        #[arg(long)]
+        proof: PathBuf,
+
+        #[command(flatten)]
+        key: KeyArgs,
+
+        #[arg(help = "Ids of participants (same order as public shares)")]
+        #[arg(short, long, value_parser, num_args = 1.., value_delimiter = ' ')]
+        #[arg(conflicts_with = "keygen_file")]
+        ids: Vec<u64>,
+
+        #[arg(help = "Public shares X_i from keygen (same order as ids)")]
+        #[arg(long, value_parser, num_args = 1.., value_delimiter = ' ')]
+        #[arg(conflicts_with = "keygen_file")]
+        public_shares: Vec<String>,
+
+        #[command(flatten)]
+        framing: FramingArgs,
+    },
+    Challenge {
+        #[command(flatten)]
+        message: MessageArgs,
+
+        #[arg(help = "Ids of participants (same order as nonces)")]
+        #[arg(short, long, value_parser, num_args = 1.., value_delimiter = ' ')]
+        ids: Vec<u64>,
+
+        #[arg(help = "Nonces of participants (same order as ids)")]
+        #[arg(short, long, value_parser, num_args = 1.., value_delimiter = ' ')]
+        nonces: Vec<String>,
+
+        #[command(flatten)]
+        key: KeyArgs,
+
+        #[command(flatten)]
+        framing: FramingArgs,
+    },
+    /// Sign the SHA-256 digest of a file and write a detached .shamy.sig file next to it
+    SignFile {
+        path: PathBuf,
+
+        #[command(flatten)]
+        key: KeyArgs,
+
+        #[arg(help = "Take the shares from this keystore instead of prompting for them")]
+        #[arg(short, long)]
+        keystore: Option<PathBuf>,
+
+        #[arg(help = "Compact signature R || s combined elsewhere, written instead of signing")]
+        #[arg(short, long, conflicts_with = "keystore")]
+        signature: Option<String>,
+
+        #[arg(help = "Signature file, defaults to <path>.shamy.sig")]
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        #[command(flatten)]
+        framing: FramingArgs,
+    },
+    /// Check a file against its detached .shamy.sig signature
+    VerifyFile {
+        path: PathBuf,
+
+        #[arg(help = "Signature file, defaults to <path>.shamy.sig")]
+        #[arg(short, long)]
+        signature: Option<PathBuf>,
+
+        #[command(flatten)]
+        key: KeyArgs,
+
+        #[command(flatten)]
+        framing: FramingArgs,
+    },
+}
+
+#[derive(Args)]
+pub struct FramingArgs {
+    #[arg(help = "Application the message belongs to, bound into the challenge")]
+    #[arg(long, required_unless_present = "unframed", requires = "purpose")]
+    pub app_id: Option<String>,
+
+    #[arg(help = "Purpose of the message within the application")]
+    #[arg(long, required_unless_present = "unframed", requires = "app_id")]
+    pub purpose: Option<String>,
+
+    #[arg(help = "Hash the raw message into the challenge (no cross-protocol safety)")]
+    #[arg(long, conflicts_with_all = ["app_id", "purpose"])]
+    pub unframed: bool,
+
+    #[arg(help = "Use the pre-v1 challenge H(R || X || m), for signatures made before it")]
+    #[arg(long)]
+    pub legacy_challenge: bool,
+
+    #[arg(help = "Use Keccak-256 over the EIP-191 message, for verification in EVM contracts")]
+    #[arg(long, conflicts_with = "legacy_challenge")]
+    pub ethereum_challenge: bool,
+
+    #[arg(
+        help = "32-byte hex session id bound into the challenge, so partials can't move between sessions"
+    )]
+    #[arg(long, value_parser = parse_session_id, conflicts_with_all = ["legacy_challenge", "ethereum_challenge"])]
+    pub session_id: Option<SessionId>,
+}
+
+/// the group key, given directly or read from a keygen file.
+#[derive(Args)]
+pub struct KeyArgs {
+    #[arg(help = "Compressed, uncompressed or 32-byte x-only public key")]
+    #[arg(short, long, required_unless_present = "keygen_file")]
+    pub public_key: Option<String>,
+
+    #[arg(help = "Take the public key (and public shares) from this keygen file")]
+    #[arg(long, conflicts_with = "public_key")]
+    pub keygen_file: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct MessageArgs {
+    #[arg(help = "Message as a UTF-8 string")]
+    #[arg(short, long, required_unless_present = "message_file")]
+    pub message: Option<String>,
+
+    #[arg(help = "Read the message bytes from this file, or from stdin with -")]
+    #[arg(long, conflicts_with = "message")]
+    pub message_file: Option<PathBuf>,
+
+    #[arg(
+        help = "Sign the digest of the message with the prehashed challenge, for large payloads"
+    )]
+    #[arg(long, value_enum)]
+    pub prehash: Option<Prehash>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Prehash {
+    Sha256,
+}
+
+impl FramingArgs {
+    pub fn config(&self) -> SigningConfig {
+        let mut config = match (&self.app_id, &self.purpose) {
+            (Some(app_id), Some(purpose)) => SigningConfig::new(app_id, purpose),
+            _ => SigningConfig::unframed(),
+        };
+        if let Some(session_id) = self.session_id {
+            config = config.with_session(session_id);
+        }
+        if self.legacy_challenge {
+            config.legacy_challenge()
+        } else if self.ethereum_challenge {
+            config.ethereum_challenge()
+        } else {
+            config
+        }
+    }
+}
+
+fn parse_session_id(hex: &str) -> Result<SessionId, String> {
+    SessionId::from_hex(hex).map_err(|e| e.to_string())
+}
+
+#[derive(Subcommand)]
+pub enum NonceCommands {
+    Generate {
+        #[arg(
+            help = "Also print the nonce point as a QR code, for moving it to an air-gapped device"
+        )]
+        #[arg(long, conflicts_with = "output")]
+        qr: bool,
+
+        #[arg(help = "Number of nonces to add to the pool")]
+        #[arg(long, default_value_t = 1, requires = "output")]
+        count: usize,
+
+        #[arg(help = "Append the nonces to this pool file and print only their points")]
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    Verify {
+        nonce: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DkgCommands {
+    Round1 {
+        #[arg(short, long)]
+        id: u64,
+
+        #[arg(short, long)]
+        threshold: u32,
+
+        #[arg(short, long)]
+        num_shares: u32,
+
+        #[arg(help = "Directory for the secret state and the package to broadcast")]
+        #[arg(short, long, default_value = ".")]
+        output_dir: PathBuf,
+    },
+    Round2 {
+        #[arg(help = "Secret state written by round1")]
+        #[arg(short, long)]
+        secret: PathBuf,
+
+        #[arg(help = "Round 1 packages of all participants")]
+        #[arg(short, long, value_parser, num_args = 1.., value_delimiter = ' ')]
+        packages: Vec<PathBuf>,
+
+        #[arg(help = "Directory for the packages to send to each participant")]
+        #[arg(short, long, default_value = ".")]
+        output_dir: PathBuf,
+    },
+    Finalize {
+        #[arg(help = "Secret state written by round1")]
+        #[arg(short, long)]
+        secret: PathBuf,
+
+        #[arg(help = "Round 1 packages of all participants")]
+        #[arg(long, value_parser, num_args = 1.., value_delimiter = ' ')]
+        round1: Vec<PathBuf>,
+
+        #[arg(help = "Round 2 packages addressed to this participant")]
+        #[arg(long, value_parser, num_args = 1.., value_delimiter = ' ')]
+        round2: Vec<PathBuf>,
+
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RepairCommands {
+    Round1 {
+        #[arg(short, long)]
+        id: u64,
+
+        #[arg(short, long, required_unless_present = "keygen_file")]
+        share: Option<String>,
+
+        #[arg(help = "Take share --id from this keygen file")]
+        #[arg(long, conflicts_with = "share")]
+        keygen_file: Option<PathBuf>,
+
+        #[arg(help = "Ids of the t participants helping with the repair")]
+        #[arg(long, value_parser, num_args = 1.., value_delimiter = ' ')]
+        helpers: Vec<u64>,
+
+        #[arg(help = "Id of the participant who lost their share")]
+        #[arg(long)]
+        lost: u64,
+
+        #[arg(help = "Directory for the packages to send to each helper")]
+        #[arg(short, long, default_value = ".")]
+        output_dir: PathBuf,
+    },
+    Round2 {
+        #[arg(short, long)]
+        id: u64,
+
+        #[arg(long, value_parser, num_args = 1.., value_delimiter = ' ')]
+        helpers: Vec<u64>,
+
+        #[arg(long)]
+        lost: u64,
+
+        #[arg(help = "Round 1 packages addressed to this helper")]
+        #[arg(short, long, value_parser, num_args = 1.., value_delimiter = ' ')]
+        packages: Vec<PathBuf>,
+
+        #[arg(help = "Directory for the package to send to the lost participant")]
+        #[arg(short, long, default_value = ".")]
+        output_dir: PathBuf,
+    },
+    Finalize {
+        #[arg(long, value_parser, num_args = 1.., value_delimiter = ' ')]
+        helpers: Vec<u64>,
+
+        #[arg(long)]
+        lost: u64,
+
+        #[arg(help = "Round 2 packages of all helpers")]
+        #[arg(short, long, value_parser, num_args = 1.., value_delimiter = ' ')]
+        packages: Vec<PathBuf>,
+
+        #[arg(help = "Group commitments to check the share against")]
+        #[arg(long, value_parser, num_args = 1.., value_delimiter = ' ')]
+        #[arg(required_unless_present = "keygen_file")]
+        commitments: Vec<String>,
+
+        #[arg(help = "Take the group commitments from this keygen file")]
+        #[arg(long, conflicts_with = "commitments")]
+        keygen_file: Option<PathBuf>,
+
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum EnrollCommands {
+    Round1 {
+        #[arg(short, long)]
+        id: u64,
+
+        #[arg(help = "Share of this helper, taken from --keygen-file if left out")]
+        #[arg(short, long)]
+        share: Option<String>,
+
+        #[arg(help = "Keygen file listing the participants who already hold a share")]
+        #[arg(long)]
+        keygen_file: PathBuf,
+
+        #[arg(help = "Ids of the t participants issuing the new share")]
+        #[arg(long, value_parser, num_args = 1.., value_delimiter = ' ')]
+        helpers: Vec<u64>,
+
+        #[arg(help = "Id of the new participant, which nobody may hold yet")]
+        #[arg(long)]
+        new_id: u64,
+
+        #[arg(help = "Directory for the packages to send to each helper")]
+        #[arg(short, long, default_value = ".")]
+        output_dir: PathBuf,
+    },
+    Round2 {
+        #[arg(short, long)]
+        id: u64,
+
+        #[arg(help = "Keygen file listing the participants who already hold a share")]
+        #[arg(long)]
+        keygen_file: PathBuf,
+
+        #[arg(long, value_parser, num_args = 1.., value_delimiter = ' ')]
+        helpers: Vec<u64>,
+
+        #[arg(long)]
+        new_id: u64,
+
+        #[arg(help = "Round 1 packages addressed to this helper")]
+        #[arg(short, long, value_parser, num_args = 1.., value_delimiter = ' ')]
+        packages: Vec<PathBuf>,
+
+        #[arg(help = "Directory for the package to send to the new participant")]
+        #[arg(short, long, default_value = ".")]
+        output_dir: PathBuf,
+    },
+    Finalize {
+        #[arg(help = "Keygen file with the group commitments and the participants")]
+        #[arg(long)]
+        keygen_file: PathBuf,
+
+        #[arg(long, value_parser, num_args = 1.., value_delimiter = ' ')]
+        helpers: Vec<u64>,
+
+        #[arg(long)]
+        new_id: u64,
+
+        #[arg(help = "Round 2 packages of all helpers")]
+        #[arg(short, long, value_parser, num_args = 1.., value_delimiter = ' ')]
+        packages: Vec<PathBuf>,
+
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RevokeCommands {
+    /// Check the group's signature over a revocation record
+    Verify {
+        #[arg(long)]
+        record: PathBuf,
+
+        #[arg(help = "Also require the record to be signed by this public key")]
+        #[arg(short, long)]
+        public_key: Option<String>,
+
+        #[command(flatten)]
+        framing: FramingArgs,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum KeystoreCommands {
+    Init {
+        #[arg(help = "Keystore file, or keyring:<name> for the OS keychain")]
+        #[arg(short, long)]
+        path: PathBuf,
+    },
+    Import {
+        #[arg(short, long)]
+        path: PathBuf,
+
+        #[arg(short, long)]
+        id: u64,
+
+        #[arg(short, long)]
+        share: String,
+    },
+    Export {
+        #[arg(short, long)]
+        path: PathBuf,
+
+        #[arg(short, long)]
+        id: u64,
+    },
+    /// Copy encrypted shares between keystores, e.g. from a file into the OS keychain
+    Copy {
+        #[arg(long)]
+        from: PathBuf,
+
+        #[arg(help = "Created if missing, a file path or keyring:<name>")]
+        #[arg(long)]
+        to: PathBuf,
+
+        #[arg(help = "Shares to copy, all of them if none are given")]
+        #[arg(long)]
+        ids: Vec<u64>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CeremonyCommands {
+    /// Sign a message with locally held shares, prompting for anything not given
+    Sign {
+        #[arg(short, long)]
+        message: Option<String>,
+
+        #[arg(short, long)]
+        public_key: Option<String>,
+
+        #[arg(help = "Take the public key from this keygen file")]
+        #[arg(long, conflicts_with = "public_key")]
+        keygen_file: Option<PathBuf>,
+
+        #[arg(help = "Take the shares from this keystore instead of prompting for them")]
+        #[arg(short, long)]
+        keystore: Option<PathBuf>,
+
+        #[arg(help = "Also write an aggregation proof naming the quorum that signed")]
+        #[arg(long)]
+        proof: Option<PathBuf>,
+
+        #[command(flatten)]
+        framing: FramingArgs,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ManifestCommands {
+    /// Check share files against the manifest written by keygen --share-dir
+    Verify {
+        #[arg(short, long)]
+        manifest: PathBuf,
+
+        #[arg(help = "Share files to check, defaults to every share listed next to the manifest")]
+        files: Vec<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PemCommands {
+    /// Write the group key, or the share of --id, of a keygen file as PEM
+    Export {
+        #[arg(long)]
+        keygen_file: PathBuf,
+
+        #[arg(help = "Export this participant's share instead of the group key")]
+        #[arg(short, long)]
+        id: Option<u64>,
+
+        #[arg(help = "Plain PUBLIC KEY block without threshold headers, for OpenSSL")]
+        #[arg(long, conflicts_with = "id")]
+        spki: bool,
+
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Decode a group key, share or OpenSSL public key PEM
+    Import {
+        #[arg(help = "PEM file, - reads stdin")]
+        path: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RecipientCommands {
+    /// Create a key pair, send the public key to the dealer
+    Generate {
+        #[arg(help = "Write the key pair to this file instead of printing the secret key")]
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Open a share encrypted to this recipient
+    Decrypt {
+        #[arg(help = "Key file written by `recipient generate --output`")]
+        #[arg(long)]
+        key_file: PathBuf,
+
+        #[arg(help = "Encrypted share in hex, as printed by keygen")]
+        #[arg(long, required_unless_present = "keygen_file")]
+        share: Option<String>,
+
+        #[arg(help = "Participant file written by `keygen --output-dir --encrypt-to`")]
+        #[arg(long, conflicts_with = "share")]
+        keygen_file: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SignerCommands {
+    /// Hold a share in memory and answer the coordinator's signing sessions
+    Serve {
+        #[arg(help = "Keystore holding the share, the passphrase is read once at startup")]
+        #[arg(long)]
+        keystore: PathBuf,
+
+        #[arg(short, long)]
+        id: u64,
+
+        #[command(flatten)]
+        key: KeyArgs,
+
+        #[arg(help = "Address the coordinator sends its messages to")]
+        #[arg(long, default_value = "127.0.0.1:7001")]
+        listen: String,
+
+        #[arg(help = "Address of the coordinator")]
+        #[arg(long)]
+        coordinator: SocketAddr,
+
+        #[arg(help = "Participant id the coordinator sends as")]
+        #[arg(long, default_value_t = 0)]
+        coordinator_id: u64,
+
+        #[arg(help = "Sign messages starting with this text, may be repeated")]
+        #[arg(long, required_unless_present_any = ["allow_sha256", "allow_any"])]
+        allow_prefix: Vec<String>,
+
+        #[arg(help = "Sign the message with this SHA-256 digest (hex), may be repeated")]
+        #[arg(long)]
+        allow_sha256: Vec<String>,
+
+        #[arg(help = "Sign every request, only for a coordinator you fully trust")]
+        #[arg(long, conflicts_with_all = ["allow_prefix", "allow_sha256"])]
+        allow_any: bool,
+
+        #[command(flatten)]
+        framing: FramingArgs,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum VectorsCommands {
+    /// Write a keygen and signing round derived entirely from a seed
+    Generate {
+        #[arg(help = "32-byte hex seed, every random value is derived from it")]
+        #[arg(long)]
+        seed: String,
+
+        #[arg(short, long)]
+        threshold: usize,
+
+        #[arg(short, long)]
+        num_shares: usize,
+
+        #[arg(short, long)]
+        message: String,
+
+        #[arg(long)]
+        app_id: String,
+
+        #[arg(long)]
+        purpose: String,
+
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Recompute every derived value of a vector and compare
+    Verify {
+        #[arg(short, long)]
+        file: PathBuf,
+    },
+}
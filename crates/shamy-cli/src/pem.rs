@@ -0,0 +1,129 @@
+#![allow(non_snake_case)]
+
+use crate::error::{CliError, fail};
+use crate::i18n::Msg;
+use crate::keygen_file::KeygenFile;
+use crate::output::Output;
+use crate::parser::{OutputFormat, PemCommands};
+use serde_json::json;
+use shamy_core::{
+    pem::{
+        GROUP_KEY_LABEL, PUBLIC_KEY_LABEL, Pem, SHARE_LABEL, group_key_from_pem, group_key_to_pem,
+        public_key_to_pem, share_from_pem, share_to_pem,
+    },
+    util::{fingerprint, pp_to_hex, scalar_to_hex},
+};
+use std::{
+    fmt::Write as _,
+    fs,
+    io::{self, Read},
+    path::Path,
+};
+
+pub fn run(command: PemCommands, format: OutputFormat) {
+    match command {
+        PemCommands::Export {
+            keygen_file,
+            id,
+            spki,
+            output,
+        } => export(&keygen_file, id, spki, output.as_deref()).print(format),
+        PemCommands::Import { path } => import(&path).print(format),
+    }
+}
+
+/// the group key, or the share of `id`, of a keygen file as PEM.
+fn export(keygen_file: &Path, id: Option<u64>, spki: bool, output: Option<&Path>) -> Output {
+    let file = KeygenFile::load(keygen_file).unwrap_or_else(|e| fail(e));
+    let public_key = file.public_key().unwrap_or_else(|e| fail(e));
+    let (label, pem) = match (id, spki) {
+        (Some(id), _) => {
+            let participant = file.participant(id).unwrap_or_else(|e| fail(e));
+            (
+                SHARE_LABEL,
+                share_to_pem(&participant, &public_key, file.threshold),
+            )
+        }
+        (None, true) => (PUBLIC_KEY_LABEL, public_key_to_pem(&public_key)),
+        (None, false) => (
+            GROUP_KEY_LABEL,
+            group_key_to_pem(&public_key, file.threshold, file.num_shares),
+        ),
+    };
+
+    let text = match output {
+        Some(path) => {
+            fs::write(path, &pem)
+                .unwrap_or_else(|e| fail(CliError::io(Msg::CannotWrite(path, &e.to_string()))));
+            Msg::Wrote(path).to_string()
+        }
+        None => pem.trim_end().to_string(),
+    };
+    Output::new(
+        text,
+        json!({
+            "label": label,
+            "pem": pem,
+            "file": output,
+        }),
+    )
+}
+
+/// decode a PEM block written by `export` or OpenSSL, `-` reads stdin.
+fn import(path: &Path) -> Output {
+    let text = match path.as_os_str() == "-" {
+        true => {
+            let mut text = String::new();
+            io::stdin()
+                .read_to_string(&mut text)
+                .map(|_| text)
+                .map_err(|e| e.to_string())
+        }
+        false => fs::read_to_string(path).map_err(|e| e.to_string()),
+    }
+    .unwrap_or_else(|e| fail(CliError::io(Msg::CannotRead(path, &e))));
+    let label = Pem::decode(&text).unwrap_or_else(|e| fail(e)).label;
+
+    let mut out = String::new();
+    if label == SHARE_LABEL {
+        let share = share_from_pem(&text).unwrap_or_else(|e| fail(e));
+        let participant = &share.participant;
+        writeln!(out, "{}", Msg::ParticipantHeader(participant.id)).unwrap();
+        writeln!(out, "x_i = {}", scalar_to_hex(&participant.x_i)).unwrap();
+        writeln!(out, "X_i = {}", pp_to_hex(&participant.X_i)).unwrap();
+        writeln!(out, "t = {}", share.threshold).unwrap();
+        write!(out, "{}", Msg::PublicKey(&pp_to_hex(&share.public_key))).unwrap();
+
+        return Output::new(
+            out,
+            json!({
+                "label": label,
+                "id": participant.id,
+                "x_i": scalar_to_hex(&participant.x_i),
+                "X_i": pp_to_hex(&participant.X_i),
+                "threshold": share.threshold,
+                "public_key": pp_to_hex(&share.public_key),
+                "fingerprint": fingerprint(&share.public_key),
+            }),
+        );
+    }
+
+    let key = group_key_from_pem(&text).unwrap_or_else(|e| fail(e));
+    if let Some(threshold) = key.threshold {
+        writeln!(out, "t = {}", threshold).unwrap();
+    }
+    if let Some(participants) = key.participants {
+        writeln!(out, "n = {}", participants).unwrap();
+    }
+    write!(out, "{}", Msg::PublicKey(&pp_to_hex(&key.public_key))).unwrap();
+    Output::new(
+        out,
+        json!({
+            "label": label,
+            "threshold": key.threshold,
+            "participants": key.participants,
+            "public_key": pp_to_hex(&key.public_key),
+            "fingerprint": fingerprint(&key.public_key),
+        }),
+    )
+}
@@ -0,0 +1,103 @@
+#![allow(non_snake_case)]
+
+use crate::error::{CliError, fail};
+use crate::i18n::Msg;
+use crate::output::Output;
+use k256::ProjectivePoint;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use shamy_core::{
+    schnorr::{SchnorrSignature, SigningConfig},
+    threshold::{AggregationProof, SignerContribution},
+    util::{hex_to_pp, hex_to_scalar, pp_to_hex, scalar_to_hex},
+};
+use std::{fs, path::Path};
+
+#[derive(Serialize, Deserialize)]
+struct ContributionFile {
+    id: u64,
+    R_i: String,
+    s_i: String,
+    lambda: String,
+}
+
+/// aggregation proof as written by `ceremony sign --proof`.
+#[derive(Serialize, Deserialize)]
+struct ProofFile {
+    message: String,
+    public_key: String,
+    nonce: String,
+    signature: String,
+    signers: Vec<ContributionFile>,
+}
+
+pub fn save(path: &Path, message: &str, public_key: &str, proof: &AggregationProof) {
+    let file = ProofFile {
+        message: message.to_string(),
+        public_key: public_key.to_string(),
+        nonce: pp_to_hex(&proof.signature.R),
+        signature: scalar_to_hex(&proof.signature.s),
+        signers: proof
+            .signers
+            .iter()
+            .map(|signer| ContributionFile {
+                id: signer.id,
+                R_i: pp_to_hex(&signer.R_i),
+                s_i: scalar_to_hex(&signer.s_i),
+                lambda: scalar_to_hex(&signer.lambda),
+            })
+            .collect(),
+    };
+    let data = serde_json::to_vec_pretty(&file).unwrap();
+    fs::write(path, data)
+        .unwrap_or_else(|e| fail(CliError::io(Msg::CannotWrite(path, &e.to_string()))));
+}
+
+fn load(path: &Path) -> Result<(ProofFile, AggregationProof), CliError> {
+    let data = fs::read(path).map_err(|e| CliError::io(Msg::CannotRead(path, &e.to_string())))?;
+    let file: ProofFile = serde_json::from_slice(&data)
+        .map_err(|e| CliError::input(Msg::InvalidProof(&e.to_string())))?;
+
+    let invalid = |e: shamy_core::Error| CliError::input(Msg::InvalidProof(&e.to_string()));
+    let signers = file
+        .signers
+        .iter()
+        .map(|signer| {
+            Ok(SignerContribution {
+                id: signer.id,
+                R_i: hex_to_pp(&signer.R_i).map_err(invalid)?,
+                s_i: hex_to_scalar(&signer.s_i).map_err(invalid)?,
+                lambda: hex_to_scalar(&signer.lambda).map_err(invalid)?,
+            })
+        })
+        .collect::<Result<Vec<_>, CliError>>()?;
+    let signature =
+        SchnorrSignature::from_hex_parts(&file.nonce, &file.signature).map_err(invalid)?;
+
+    Ok((file, AggregationProof { signature, signers }))
+}
+
+/// check the proof in `path` against the expected key and the public shares
+/// from keygen, and report which quorum signed.
+pub fn verify(
+    path: &Path,
+    X: &ProjectivePoint,
+    public_shares: &[(u64, ProjectivePoint)],
+    config: &SigningConfig,
+) -> Output {
+    let (file, proof) = load(path).unwrap_or_else(|e| fail(e));
+    proof
+        .verify(file.message.as_bytes(), X, public_shares, config)
+        .unwrap_or_else(|e| fail(CliError::verification(Msg::InvalidProof(&e.to_string()))));
+
+    let quorum = proof.quorum();
+    Output::new(
+        Msg::QuorumVerified(&quorum).to_string(),
+        json!({
+            "message": file.message,
+            "public_key": pp_to_hex(X),
+            "quorum": quorum,
+            "valid": true,
+        }),
+    )
+}
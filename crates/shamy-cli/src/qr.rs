@@ -0,0 +1,401 @@
+//--------------------------------------------------------------------
+// QR codes (ISO/IEC 18004), byte mode, error correction level M
+//--------------------------------------------------------------------
+//
+//   bits      = 0100 || len || data || 0000 || pad to a byte
+//   codewords = bits || EC 11 EC 11 ...              (up to the capacity)
+//   blocks    = codewords split per version, each followed by its
+//               Reed-Solomon EC codewords over GF(256), x^8+x^4+x^3+x^2+1
+//   matrix    = function patterns, then the interleaved blocks in the
+//               zigzag order, then the mask with the lowest penalty
+//
+// versions 1 to 10 are enough for everything the CLI prints, the longest
+// is a signature (R || s, 130 hex characters) in a version 8 code.
+//
+
+/// (EC codewords per block, [(blocks, data codewords per block)]) at level M.
+const VERSIONS_M: [(usize, &[(usize, usize)]); 10] = [
+    (10, &[(1, 16)]),
+    (16, &[(1, 28)]),
+    (26, &[(1, 44)]),
+    (18, &[(2, 32)]),
+    (24, &[(2, 43)]),
+    (16, &[(4, 27)]),
+    (18, &[(4, 31)]),
+    (22, &[(2, 38), (2, 39)]),
+    (22, &[(3, 36), (2, 37)]),
+    (26, &[(4, 43), (1, 44)]),
+];
+
+/// centers of the alignment patterns, per version.
+const ALIGNMENT: [&[usize]; 10] = [
+    &[],
+    &[6, 18],
+    &[6, 22],
+    &[6, 26],
+    &[6, 30],
+    &[6, 34],
+    &[6, 22, 38],
+    &[6, 24, 42],
+    &[6, 26, 46],
+    &[6, 28, 50],
+];
+
+const QUIET_ZONE: usize = 4;
+
+/// a square of dark (true) and light modules.
+pub struct QrCode {
+    size: usize,
+    modules: Vec<bool>,
+    function: Vec<bool>,
+}
+
+impl QrCode {
+    /// smallest code holding `data`, fails when it doesn't fit in version 10.
+    pub fn encode(data: &[u8]) -> Result<Self, String> {
+        let version = (1..=VERSIONS_M.len())
+            .find(|v| data.len() <= capacity(*v))
+            .ok_or(format!(
+                "{} bytes don't fit in a QR code, at most {} do",
+                data.len(),
+                capacity(VERSIONS_M.len())
+            ))?;
+
+        let mut qr = Self {
+            size: 17 + 4 * version,
+            modules: vec![false; (17 + 4 * version).pow(2)],
+            function: vec![false; (17 + 4 * version).pow(2)],
+        };
+        qr.draw_function_patterns(version);
+        qr.draw_codewords(&add_error_correction(
+            version,
+            &data_codewords(version, data),
+        ));
+
+        let mask = (0..8)
+            .min_by_key(|mask| {
+                qr.apply_mask(*mask);
+                qr.draw_format(*mask);
+                let penalty = qr.penalty();
+                qr.apply_mask(*mask);
+                penalty
+            })
+            .unwrap();
+        qr.apply_mask(mask);
+        qr.draw_format(mask);
+
+        Ok(qr)
+    }
+
+    /// whether the module at column `x`, row `y` is dark.
+    pub fn module(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+
+    /// two rows per line with half blocks, light modules drawn filled so
+    /// the code reads correctly on a dark terminal background.
+    pub fn render(&self) -> String {
+        let light = |x: usize, y: usize| {
+            x < QUIET_ZONE
+                || y < QUIET_ZONE
+                || x >= self.size + QUIET_ZONE
+                || y >= self.size + QUIET_ZONE
+                || !self.module(x - QUIET_ZONE, y - QUIET_ZONE)
+        };
+        let width = self.size + 2 * QUIET_ZONE;
+
+        (0..width)
+            .step_by(2)
+            .map(|y| {
+                (0..width)
+                    .map(|x| match (light(x, y), y + 1 == width || light(x, y + 1)) {
+                        (true, true) => '█',
+                        (true, false) => '▀',
+                        (false, true) => '▄',
+                        (false, false) => ' ',
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        self.modules[y * self.size + x] = dark;
+        self.function[y * self.size + x] = true;
+    }
+
+    fn draw_function_patterns(&mut self, version: usize) {
+        let size = self.size;
+        for i in 0..size {
+            self.set_function(6, i, i % 2 == 0);
+            self.set_function(i, 6, i % 2 == 0);
+        }
+
+        // finders with their separators
+        for (cx, cy) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+            for dy in -4i32..=4 {
+                for dx in -4i32..=4 {
+                    let (x, y) = (cx as i32 + dx, cy as i32 + dy);
+                    if (0..size as i32).contains(&x) && (0..size as i32).contains(&y) {
+                        let dist = dx.abs().max(dy.abs());
+                        self.set_function(x as usize, y as usize, dist != 2 && dist != 4);
+                    }
+                }
+            }
+        }
+
+        let centers = ALIGNMENT[version - 1];
+        for (i, cx) in centers.iter().enumerate() {
+            for (j, cy) in centers.iter().enumerate() {
+                // the three corners taken by the finders
+                let last = centers.len() - 1;
+                if [(0, 0), (0, last), (last, 0)].contains(&(i, j)) {
+                    continue;
+                }
+                for dy in -2i32..=2 {
+                    for dx in -2i32..=2 {
+                        self.set_function(
+                            (*cx as i32 + dx) as usize,
+                            (*cy as i32 + dy) as usize,
+                            dx.abs().max(dy.abs()) != 1,
+                        );
+                    }
+                }
+            }
+        }
+
+        // reserve the format areas, drawn for real once the mask is known
+        self.draw_format(0);
+
+        if version >= 7 {
+            let bits = (version << 12 | bch_remainder(version, 12, 0x1f25)) as u32;
+            for i in 0..18 {
+                let dark = (bits >> i) & 1 == 1;
+                let (a, b) = (size - 11 + i % 3, i / 3);
+                self.set_function(a, b, dark);
+                self.set_function(b, a, dark);
+            }
+        }
+    }
+
+    fn draw_format(&mut self, mask: usize) {
+        // level M is 00
+        let data = mask;
+        let bits = ((data << 10 | bch_remainder(data, 10, 0x537)) ^ 0x5412) as u32;
+        let bit = |i: usize| (bits >> i) & 1 == 1;
+        let size = self.size;
+
+        for i in 0..=5 {
+            self.set_function(8, i, bit(i));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for i in 9..15 {
+            self.set_function(14 - i, 8, bit(i));
+        }
+
+        for i in 0..8 {
+            self.set_function(size - 1 - i, 8, bit(i));
+        }
+        for i in 8..15 {
+            self.set_function(8, size - 15 + i, bit(i));
+        }
+        self.set_function(8, size - 8, true);
+    }
+
+    /// zigzag over column pairs from the right, skipping the timing column.
+    fn draw_codewords(&mut self, codewords: &[u8]) {
+        let size = self.size;
+        let mut i = 0;
+        let mut right = size - 1;
+        loop {
+            if right == 6 {
+                right = 5;
+            }
+            for vert in 0..size {
+                for j in 0..2 {
+                    let x = right - j;
+                    let upward = (right + 1) & 2 == 0;
+                    let y = if upward { size - 1 - vert } else { vert };
+                    if !self.function[y * size + x] && i < codewords.len() * 8 {
+                        self.modules[y * size + x] = (codewords[i / 8] >> (7 - i % 8)) & 1 == 1;
+                        i += 1;
+                    }
+                }
+            }
+            if right < 2 {
+                break;
+            }
+            right -= 2;
+        }
+    }
+
+    /// xor the data modules with a mask pattern, applying it twice undoes it.
+    fn apply_mask(&mut self, mask: usize) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let flip = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                if flip && !self.function[y * self.size + x] {
+                    self.modules[y * self.size + x] ^= true;
+                }
+            }
+        }
+    }
+
+    fn penalty(&self) -> usize {
+        let size = self.size;
+        let lines = (0..size)
+            .map(|y| (0..size).map(|x| self.module(x, y)).collect::<Vec<_>>())
+            .chain((0..size).map(|x| (0..size).map(|y| self.module(x, y)).collect()));
+
+        let mut penalty = 0;
+        for line in lines {
+            // runs of five or more
+            for run in line.chunk_by(|a, b| a == b).filter(|run| run.len() >= 5) {
+                penalty += run.len() - 2;
+            }
+            // finder lookalikes, 1:1:3:1:1 next to four light modules
+            let finder = [true, false, true, true, true, false, true];
+            for window in line.windows(11) {
+                if (window[..7] == finder && window[7..].iter().all(|m| !m))
+                    || (window[..4].iter().all(|m| !m) && window[4..] == finder)
+                {
+                    penalty += 40;
+                }
+            }
+        }
+
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let dark = self.module(x, y);
+                if [(x + 1, y), (x, y + 1), (x + 1, y + 1)]
+                    .iter()
+                    .all(|(x, y)| self.module(*x, *y) == dark)
+                {
+                    penalty += 3;
+                }
+            }
+        }
+
+        // distance of the dark share from 50%, in steps of 5%
+        let dark = self.modules.iter().filter(|m| **m).count();
+        let percent = dark * 100 / self.modules.len();
+        penalty + percent.abs_diff(50) / 5 * 10
+    }
+}
+
+/// bytes of data a version holds in byte mode.
+fn capacity(version: usize) -> usize {
+    let count_bits = if version < 10 { 8 } else { 16 };
+    (num_data_codewords(version) * 8 - 4 - count_bits) / 8
+}
+
+fn num_data_codewords(version: usize) -> usize {
+    let (_, groups) = VERSIONS_M[version - 1];
+    groups.iter().map(|(blocks, len)| blocks * len).sum()
+}
+
+fn data_codewords(version: usize, data: &[u8]) -> Vec<u8> {
+    let count_bits = if version < 10 { 8 } else { 16 };
+    let capacity = num_data_codewords(version) * 8;
+
+    let mut bits = Vec::with_capacity(capacity);
+    let mut push =
+        |value: usize, n: usize| bits.extend((0..n).rev().map(|i| (value >> i) & 1 == 1));
+    push(0b0100, 4);
+    push(data.len(), count_bits);
+    for byte in data {
+        push(*byte as usize, 8);
+    }
+    let terminator = 4.min(capacity - bits.len());
+    bits.extend(vec![false; terminator]);
+    bits.extend(vec![false; (8 - bits.len() % 8) % 8]);
+
+    let mut codewords = bits
+        .chunks(8)
+        .map(|byte| byte.iter().fold(0u8, |acc, bit| acc << 1 | *bit as u8))
+        .collect::<Vec<_>>();
+    for pad in [0xec, 0x11].into_iter().cycle() {
+        if codewords.len() == capacity / 8 {
+            break;
+        }
+        codewords.push(pad);
+    }
+    codewords
+}
+
+/// split into blocks, add the EC codewords and interleave.
+pub fn add_error_correction(version: usize, data: &[u8]) -> Vec<u8> {
+    let (ec_len, groups) = VERSIONS_M[version - 1];
+    let mut blocks = Vec::new();
+    let mut rest = data;
+    for (count, len) in groups {
+        for _ in 0..*count {
+            let (block, tail) = rest.split_at(*len);
+            blocks.push((block, reed_solomon(block, ec_len)));
+            rest = tail;
+        }
+    }
+
+    let longest = blocks.iter().map(|(block, _)| block.len()).max().unwrap();
+    let mut out = Vec::new();
+    for i in 0..longest {
+        out.extend(blocks.iter().filter_map(|(block, _)| block.get(i)));
+    }
+    for i in 0..ec_len {
+        out.extend(blocks.iter().map(|(_, ec)| ec[i]));
+    }
+    out
+}
+
+/// remainder of data(x) * x^ec_len divided by the generator of degree ec_len.
+pub fn reed_solomon(data: &[u8], ec_len: usize) -> Vec<u8> {
+    // generator = (x - α^0)(x - α^1)...(x - α^(ec_len-1)), highest term dropped
+    let mut generator = vec![0u8; ec_len];
+    generator[ec_len - 1] = 1;
+    let mut root = 1u8;
+    for _ in 0..ec_len {
+        for j in 0..ec_len {
+            generator[j] = gf_mul(generator[j], root) ^ generator.get(j + 1).copied().unwrap_or(0);
+        }
+        root = gf_mul(root, 2);
+    }
+
+    let mut remainder = vec![0u8; ec_len];
+    for byte in data {
+        let factor = byte ^ remainder.remove(0);
+        remainder.push(0);
+        for (r, g) in remainder.iter_mut().zip(&generator) {
+            *r ^= gf_mul(*g, factor);
+        }
+    }
+    remainder
+}
+
+fn gf_mul(a: u8, b: u8) -> u8 {
+    (0..8).rev().fold(0u8, |acc, i| {
+        let acc = (acc << 1) ^ if acc & 0x80 != 0 { 0x1d } else { 0 };
+        acc ^ if (b >> i) & 1 == 1 { a } else { 0 }
+    })
+}
+
+/// remainder of data * x^bits mod the BCH generator `poly`.
+pub fn bch_remainder(data: usize, bits: usize, poly: usize) -> usize {
+    let degree = poly.ilog2();
+    let mut value = data << bits;
+    while value != 0 && value.ilog2() >= degree {
+        value ^= poly << (value.ilog2() - degree);
+    }
+    value
+}
@@ -0,0 +1,117 @@
+#![allow(non_snake_case)]
+
+use crate::error::{CliError, fail};
+use crate::i18n::Msg;
+use crate::keygen_file::KeygenFile;
+use crate::output::Output;
+use crate::parser::{OutputFormat, RecipientCommands};
+use k256::{ProjectivePoint, Scalar, elliptic_curve::Field};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use shamy_core::{
+    shamir::{EncryptedShare, decrypt_share},
+    util::{hex_to_pp, hex_to_scalar, pp_to_hex, scalar_to_hex},
+};
+use std::{fmt::Write as _, fs, path::Path};
+
+const RECIPIENT_KEY_VERSION: u8 = 1;
+
+/// long-term key a participant receives encrypted shares with,
+/// as written by `recipient generate`.
+#[derive(Serialize, Deserialize)]
+pub struct RecipientKey {
+    pub version: u8,
+    pub secret_key: String,
+    pub public_key: String,
+}
+
+impl RecipientKey {
+    pub fn load(path: &Path) -> Result<(Scalar, ProjectivePoint), CliError> {
+        let data =
+            fs::read(path).map_err(|e| CliError::io(Msg::CannotRead(path, &e.to_string())))?;
+        let invalid = |e: &str| CliError::input(Msg::InvalidRecipientKeyFile(e));
+        let file: RecipientKey =
+            serde_json::from_slice(&data).map_err(|e| invalid(&e.to_string()))?;
+        if file.version != RECIPIENT_KEY_VERSION {
+            return Err(invalid(&format!("unsupported version {}", file.version)));
+        }
+        let secret_key = hex_to_scalar(&file.secret_key)?;
+        let public_key = hex_to_pp(&file.public_key)?;
+        // a hand-edited file would otherwise decrypt nothing with no hint why
+        if ProjectivePoint::GENERATOR * secret_key != public_key {
+            return Err(invalid("public key does not match the secret key"));
+        }
+
+        Ok((secret_key, public_key))
+    }
+}
+
+pub fn run(command: RecipientCommands, format: OutputFormat) {
+    match command {
+        RecipientCommands::Generate { output } => generate(output.as_deref()).print(format),
+        RecipientCommands::Decrypt {
+            key_file,
+            share,
+            keygen_file,
+        } => {
+            let share = match (share, keygen_file) {
+                (Some(share), _) => share,
+                (None, Some(path)) => KeygenFile::load(&path)
+                    .and_then(|file| file.recipient_share().map(str::to_string))
+                    .unwrap_or_else(|e| fail(e)),
+                (None, None) => unreachable!("clap requires --share or --keygen-file"),
+            };
+            decrypt(&key_file, &share).print(format)
+        }
+    }
+}
+
+/// a fresh key pair, the public key goes to the dealer for `keygen --encrypt-to`.
+fn generate(output: Option<&Path>) -> Output {
+    let secret_key = Scalar::random(&mut k256::elliptic_curve::rand_core::OsRng);
+    let public_key = pp_to_hex(&(ProjectivePoint::GENERATOR * secret_key));
+
+    let mut text = Msg::PublicKey(&public_key).to_string();
+    let mut json = json!({ "public_key": public_key, "file": output });
+    match output {
+        Some(path) => {
+            let file = RecipientKey {
+                version: RECIPIENT_KEY_VERSION,
+                secret_key: scalar_to_hex(&secret_key),
+                public_key,
+            };
+            fs::write(path, serde_json::to_vec_pretty(&file).unwrap())
+                .unwrap_or_else(|e| fail(CliError::io(Msg::CannotWrite(path, &e.to_string()))));
+            write!(text, "\n{}", Msg::Wrote(path)).unwrap();
+        }
+        None => {
+            write!(text, "\n{}", Msg::SecretKey(&scalar_to_hex(&secret_key))).unwrap();
+            json["secret_key"] = json!(scalar_to_hex(&secret_key));
+        }
+    }
+    Output::new(text, json)
+}
+
+/// open a share from `keygen --encrypt-to` with the recipient key file.
+fn decrypt(key_file: &Path, share: &str) -> Output {
+    let (secret_key, _) = RecipientKey::load(key_file).unwrap_or_else(|e| fail(e));
+    let encrypted = hex::decode(share)
+        .map_err(|e| shamy_core::Error::InvalidEncoding(e.to_string()))
+        .and_then(|bytes| EncryptedShare::from_bytes(&bytes))
+        .unwrap_or_else(|e| fail(e));
+    let participant = decrypt_share(&secret_key, &encrypted).unwrap_or_else(|e| fail(e));
+
+    Output::new(
+        format!(
+            "{}\nx_i = {}\nX_i = {}",
+            Msg::ParticipantHeader(participant.id),
+            scalar_to_hex(&participant.x_i),
+            pp_to_hex(&participant.X_i)
+        ),
+        json!({
+            "id": participant.id,
+            "x_i": scalar_to_hex(&participant.x_i),
+            "X_i": pp_to_hex(&participant.X_i),
+        }),
+    )
+}
@@ -0,0 +1,271 @@
+#![allow(non_snake_case)]
+
+use crate::dkg::{create_dir, read_json, write_json, written_files};
+use crate::error::{CliError, fail};
+use crate::i18n::Msg;
+use crate::keygen_file::{KeygenFile, load_participant};
+use crate::output::Output;
+use crate::parser::{EnrollCommands, OutputFormat, RepairCommands};
+use k256::ProjectivePoint;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use shamy_core::{
+    Error,
+    repair::{self, RepairPackage, RepairSum},
+    threshold::Participant,
+    util::{hex_to_pp, hex_to_scalar, pp_to_hex, scalar_to_hex},
+};
+use std::{
+    fmt::Write as _,
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Serialize, Deserialize)]
+struct RepairPackageFile {
+    sender: u64,
+    receiver: u64,
+    delta: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RepairSumFile {
+    sender: u64,
+    sigma: String,
+}
+
+impl From<&RepairPackage> for RepairPackageFile {
+    fn from(package: &RepairPackage) -> Self {
+        Self {
+            sender: package.sender,
+            receiver: package.receiver,
+            delta: scalar_to_hex(&package.delta),
+        }
+    }
+}
+
+impl TryFrom<RepairPackageFile> for RepairPackage {
+    type Error = Error;
+
+    fn try_from(file: RepairPackageFile) -> Result<Self, Error> {
+        Ok(Self {
+            sender: file.sender,
+            receiver: file.receiver,
+            delta: hex_to_scalar(&file.delta)?,
+        })
+    }
+}
+
+impl From<&RepairSum> for RepairSumFile {
+    fn from(sum: &RepairSum) -> Self {
+        Self {
+            sender: sum.sender,
+            sigma: scalar_to_hex(&sum.sigma),
+        }
+    }
+}
+
+impl TryFrom<RepairSumFile> for RepairSum {
+    type Error = Error;
+
+    fn try_from(file: RepairSumFile) -> Result<Self, Error> {
+        Ok(Self {
+            sender: file.sender,
+            sigma: hex_to_scalar(&file.sigma)?,
+        })
+    }
+}
+
+fn read_files<F: serde::de::DeserializeOwned + TryInto<T, Error = Error>, T>(
+    paths: &[PathBuf],
+) -> Vec<T> {
+    paths
+        .iter()
+        .map(|path| {
+            read_json::<F>(path)
+                .try_into()
+                .unwrap_or_else(|e: Error| fail(e))
+        })
+        .collect()
+}
+
+pub fn run(command: RepairCommands, format: OutputFormat) {
+    match command {
+        RepairCommands::Round1 {
+            id,
+            share,
+            keygen_file,
+            helpers,
+            lost,
+            output_dir,
+        } => {
+            let helper = load_participant(id, share, keygen_file);
+            let packages =
+                repair::repair_round1(&helper, &helpers, lost).unwrap_or_else(|e| fail(e));
+            write_round1("repair", &packages, &output_dir).print(format);
+        }
+        RepairCommands::Round2 {
+            id,
+            helpers,
+            lost,
+            packages,
+            output_dir,
+        } => {
+            let packages = read_files::<RepairPackageFile, _>(&packages);
+            let sum =
+                repair::repair_round2(id, &helpers, lost, &packages).unwrap_or_else(|e| fail(e));
+            write_round2("repair", id, lost, &sum, &output_dir).print(format);
+        }
+        RepairCommands::Finalize {
+            helpers,
+            lost,
+            packages,
+            commitments,
+            keygen_file,
+            output: output_file,
+        } => {
+            let sums = read_files::<RepairSumFile, _>(&packages);
+            let commitments = match keygen_file {
+                Some(path) => {
+                    KeygenFile::load(&path)
+                        .unwrap_or_else(|e| fail(e))
+                        .commitments
+                }
+                None => commitments,
+            };
+            let participant =
+                repair::repair_finalize(lost, &helpers, &sums, &parse_commitments(&commitments))
+                    .unwrap_or_else(|e| fail(e));
+            print_participant(&participant, format, output_file);
+        }
+    }
+}
+
+/// the repair rounds at an id the keygen file doesn't list yet.
+pub fn run_enroll(command: EnrollCommands, format: OutputFormat) {
+    match command {
+        EnrollCommands::Round1 {
+            id,
+            share,
+            keygen_file,
+            helpers,
+            new_id,
+            output_dir,
+        } => {
+            let enrolled = enrolled_ids(&keygen_file);
+            let helper = load_participant(id, share, Some(keygen_file));
+            let packages = repair::enroll_round1(&helper, &helpers, new_id, &enrolled)
+                .unwrap_or_else(|e| fail(e));
+            write_round1("enroll", &packages, &output_dir).print(format);
+        }
+        EnrollCommands::Round2 {
+            id,
+            keygen_file,
+            helpers,
+            new_id,
+            packages,
+            output_dir,
+        } => {
+            let packages = read_files::<RepairPackageFile, _>(&packages);
+            let sum =
+                repair::enroll_round2(id, &helpers, new_id, &enrolled_ids(&keygen_file), &packages)
+                    .unwrap_or_else(|e| fail(e));
+            write_round2("enroll", id, new_id, &sum, &output_dir).print(format);
+        }
+        EnrollCommands::Finalize {
+            keygen_file,
+            helpers,
+            new_id,
+            packages,
+            output: output_file,
+        } => {
+            let sums = read_files::<RepairSumFile, _>(&packages);
+            let file = KeygenFile::load(&keygen_file).unwrap_or_else(|e| fail(e));
+            let enrolled = file.participants.iter().map(|p| p.id).collect::<Vec<_>>();
+            let participant = repair::enroll_finalize(
+                new_id,
+                &helpers,
+                &sums,
+                &parse_commitments(&file.commitments),
+                &enrolled,
+            )
+            .unwrap_or_else(|e| fail(e));
+            print_participant(&participant, format, output_file);
+        }
+    }
+}
+
+/// ids of the participants in the keygen file, who hold a share already.
+fn enrolled_ids(path: &Path) -> Vec<u64> {
+    KeygenFile::load(path)
+        .unwrap_or_else(|e| fail(e))
+        .participants
+        .iter()
+        .map(|p| p.id)
+        .collect()
+}
+
+fn parse_commitments(commitments: &[String]) -> Vec<ProjectivePoint> {
+    commitments
+        .iter()
+        .map(|c| hex_to_pp(c))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap_or_else(|e| fail(e))
+}
+
+fn write_round1(prefix: &str, packages: &[RepairPackage], output_dir: &Path) -> Output {
+    create_dir(output_dir);
+    let files = packages
+        .iter()
+        .map(|package| {
+            write_json(
+                &output_dir.join(format!(
+                    "{prefix}-round1-{}-to-{}.json",
+                    package.sender, package.receiver
+                )),
+                &RepairPackageFile::from(package),
+            )
+        })
+        .collect::<Vec<_>>();
+    written_files(&files)
+}
+
+fn write_round2(
+    prefix: &str,
+    id: u64,
+    receiver: u64,
+    sum: &RepairSum,
+    output_dir: &Path,
+) -> Output {
+    create_dir(output_dir);
+    let file = write_json(
+        &output_dir.join(format!("{prefix}-round2-{id}-to-{receiver}.json")),
+        &RepairSumFile::from(sum),
+    );
+    written_files(&[file])
+}
+
+fn print_participant(
+    participant: &Participant,
+    format: OutputFormat,
+    output_file: Option<PathBuf>,
+) {
+    let mut text = String::new();
+    writeln!(text, "{}", Msg::ParticipantHeader(participant.id)).unwrap();
+    writeln!(text, "x_i = {}", scalar_to_hex(&participant.x_i)).unwrap();
+    write!(text, "X_i = {}", pp_to_hex(&participant.X_i)).unwrap();
+    let output = Output::new(
+        text,
+        json!({
+            "id": participant.id,
+            "x_i": scalar_to_hex(&participant.x_i),
+            "X_i": pp_to_hex(&participant.X_i),
+        }),
+    );
+
+    output.print(format);
+    if let Some(path) = output_file {
+        fs::write(&path, output.render(format) + "\n")
+            .unwrap_or_else(|e| fail(CliError::io(Msg::CannotWrite(&path, &e.to_string()))));
+    }
+}
@@ -0,0 +1,138 @@
+#![allow(non_snake_case)]
+
+use crate::error::{CliError, ErrorKind, fail};
+use crate::i18n::Msg;
+use crate::keygen_file::KeygenFile;
+use crate::output::Output;
+use crate::parser::{OutputFormat, RevokeCommands};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use shamy_core::{
+    schnorr::{SchnorrSignature, SigningConfig},
+    threshold::{RevocationRecord, revoke_participant},
+    util::{hex_to_pp, pp_to_hex, scalar_to_hex},
+};
+use std::{fmt::Write as _, fs, path::Path, process};
+
+const RECORD_VERSION: u8 = 1;
+
+/// `RevocationRecord` as written by `revoke --record`, points and scalars in hex.
+#[derive(Serialize, Deserialize)]
+struct RevocationRecordFile {
+    version: u8,
+    id: u64,
+    X_i: String,
+    public_key: String,
+    commitments: Vec<String>,
+    R: String,
+    s: String,
+}
+
+impl From<&RevocationRecord> for RevocationRecordFile {
+    fn from(record: &RevocationRecord) -> Self {
+        Self {
+            version: RECORD_VERSION,
+            id: record.id,
+            X_i: pp_to_hex(&record.X_i),
+            public_key: pp_to_hex(&record.public_key),
+            commitments: record.commitments.iter().map(pp_to_hex).collect(),
+            R: pp_to_hex(&record.signature.R),
+            s: scalar_to_hex(&record.signature.s),
+        }
+    }
+}
+
+impl RevocationRecordFile {
+    fn load(path: &Path) -> Result<RevocationRecord, CliError> {
+        let data =
+            fs::read(path).map_err(|e| CliError::io(Msg::CannotRead(path, &e.to_string())))?;
+        let invalid =
+            |e: &dyn ToString| CliError::input(Msg::InvalidRevocationRecord(&e.to_string()));
+        let file: RevocationRecordFile = serde_json::from_slice(&data).map_err(|e| invalid(&e))?;
+        if file.version != RECORD_VERSION {
+            return Err(invalid(&format!("unsupported version {}", file.version)));
+        }
+
+        let record = || -> Result<RevocationRecord, shamy_core::Error> {
+            Ok(RevocationRecord {
+                id: file.id,
+                X_i: hex_to_pp(&file.X_i)?,
+                public_key: hex_to_pp(&file.public_key)?,
+                commitments: file
+                    .commitments
+                    .iter()
+                    .map(|c| hex_to_pp(c))
+                    .collect::<Result<_, _>>()?,
+                signature: SchnorrSignature::from_hex_parts(&file.R, &file.s)?,
+            })
+        };
+        record().map_err(|e| invalid(&e))
+    }
+}
+
+/// revoke participant `id` of the group in `keygen_file`, write the
+/// refreshed group to `output` and the signed record to `record_path`.
+pub fn run(
+    keygen_file: &Path,
+    id: u64,
+    output: &Path,
+    record_path: &Path,
+    config: &SigningConfig,
+) -> Output {
+    let file = KeygenFile::load(keygen_file).unwrap_or_else(|e| fail(e));
+    let keygen_output = file.keygen_output().unwrap_or_else(|e| fail(e));
+    let revocation = revoke_participant(&keygen_output, id, config).unwrap_or_else(|e| fail(e));
+
+    KeygenFile::new(&revocation.keygen_output, file.threshold, true)
+        .save(output)
+        .unwrap_or_else(|e| fail(e));
+    let record = serde_json::to_string_pretty(&RevocationRecordFile::from(&revocation.record))
+        .unwrap()
+        + "\n";
+    fs::write(record_path, record)
+        .unwrap_or_else(|e| fail(CliError::io(Msg::CannotWrite(record_path, &e.to_string()))));
+
+    let mut text = String::new();
+    writeln!(text, "{}", Msg::Revoked(id)).unwrap();
+    writeln!(text, "{}", Msg::Wrote(output)).unwrap();
+    write!(text, "{}", Msg::Wrote(record_path)).unwrap();
+    Output::new(
+        text,
+        json!({
+            "id": id,
+            "public_key": pp_to_hex(&revocation.record.public_key),
+            "participants": revocation
+                .keygen_output
+                .participants
+                .iter()
+                .map(|p| p.id)
+                .collect::<Vec<_>>(),
+            "files": [output, record_path],
+        }),
+    )
+}
+
+pub fn verify(command: RevokeCommands, format: OutputFormat) {
+    let RevokeCommands::Verify {
+        record,
+        public_key,
+        framing,
+    } = command;
+    let record = RevocationRecordFile::load(&record).unwrap_or_else(|e| fail(e));
+
+    let expected_key = public_key.map(|key| crate::parse_public_key(&key));
+    let valid =
+        record.verify(&framing.config()) && expected_key.is_none_or(|key| key == record.public_key);
+    let text = match valid {
+        true => Msg::RevocationValid(record.id),
+        false => Msg::RevocationInvalid,
+    };
+    Output::new(
+        text.to_string(),
+        json!({ "id": record.id, "X_i": pp_to_hex(&record.X_i), "valid": valid }),
+    )
+    .print(format);
+    if !valid {
+        process::exit(ErrorKind::Verification.code());
+    }
+}
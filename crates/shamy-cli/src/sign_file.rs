@@ -0,0 +1,205 @@
+#![allow(non_snake_case)]
+
+use crate::ceremony;
+use crate::error::{CliError, ErrorKind, fail};
+use crate::i18n::Msg;
+use crate::message::Message;
+use crate::output::Output;
+use crate::parser::{FramingArgs, KeyArgs, OutputFormat};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use shamy_core::{
+    schnorr::SchnorrSignature,
+    util::{fingerprint, hex_to_pp, pp_to_hex},
+};
+use std::{
+    fmt::Write as _,
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+    process,
+};
+
+const SIDECAR_VERSION: u8 = 1;
+const SIDECAR_EXTENSION: &str = ".shamy.sig";
+const HASH_ALGORITHM: &str = "sha256";
+
+/// detached signature of a file, written next to it as `<file>.shamy.sig`.
+/// the signature is over the SHA-256 digest of the contents (prehashed).
+#[derive(Serialize, Deserialize)]
+struct Sidecar {
+    version: u8,
+    /// name of the signed file, for humans, it isn't checked.
+    file: String,
+    hash: String,
+    digest: String,
+    public_key: String,
+    fingerprint: String,
+    /// compact signature R || s.
+    signature: String,
+}
+
+impl Sidecar {
+    fn load(path: &Path) -> Result<Self, CliError> {
+        let data =
+            fs::read(path).map_err(|e| CliError::io(Msg::CannotRead(path, &e.to_string())))?;
+        let invalid = |e: &dyn ToString| CliError::input(Msg::InvalidSidecar(&e.to_string()));
+        let sidecar: Sidecar = serde_json::from_slice(&data).map_err(|e| invalid(&e))?;
+        if sidecar.version != SIDECAR_VERSION {
+            return Err(invalid(&format!("unsupported version {}", sidecar.version)));
+        }
+        if sidecar.hash != HASH_ALGORITHM {
+            return Err(invalid(&format!("unsupported hash {}", sidecar.hash)));
+        }
+
+        Ok(sidecar)
+    }
+
+    fn signature(&self) -> Result<SchnorrSignature, CliError> {
+        hex::decode(&self.signature)
+            .map_err(|e| CliError::input(Msg::InvalidSidecar(&e.to_string())))
+            .and_then(|bytes| Ok(SchnorrSignature::from_bytes(&bytes)?))
+    }
+}
+
+/// `<path>.shamy.sig`, where the sidecar of `path` goes by default.
+fn sidecar_path(path: &Path) -> PathBuf {
+    let mut sidecar = path.as_os_str().to_owned();
+    sidecar.push(SIDECAR_EXTENSION);
+    PathBuf::from(sidecar)
+}
+
+/// SHA-256 of the file contents, streamed.
+fn file_digest(path: &Path) -> [u8; 32] {
+    let fail = |e: io::Error| -> ! { fail(CliError::io(Msg::CannotRead(path, &e.to_string()))) };
+    let mut file = File::open(path).unwrap_or_else(|e| fail(e));
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher).unwrap_or_else(|e| fail(e));
+
+    hasher.finalize().into()
+}
+
+/// sign the digest of `path` with locally held shares, or take a compact
+/// `signature` combined elsewhere, and write the sidecar.
+pub fn sign(
+    path: &Path,
+    key: &KeyArgs,
+    keystore: Option<&Path>,
+    signature: Option<&str>,
+    output: Option<&Path>,
+    framing: &FramingArgs,
+) -> Output {
+    let public_key = key.load();
+    let digest = file_digest(path);
+    let message = Message::Prehashed(digest);
+    let config = framing.config();
+
+    let (signature, ids) = match signature {
+        Some(signature) => {
+            let signature = hex::decode(signature)
+                .map_err(CliError::input)
+                .and_then(|bytes| Ok(SchnorrSignature::from_bytes(&bytes)?))
+                .unwrap_or_else(|e| fail(e));
+            if !message.verify(&signature, &public_key, &config) {
+                fail(CliError::verification(Msg::SignatureInvalid));
+            }
+            (signature, None)
+        }
+        None => {
+            let proof = ceremony::quorum_sign(&message, &public_key, keystore, &config);
+            let ids = proof.nonces().iter().map(|(id, _)| *id).collect::<Vec<_>>();
+            (proof.signature, Some(ids))
+        }
+    };
+
+    let sidecar = Sidecar {
+        version: SIDECAR_VERSION,
+        file: path
+            .file_name()
+            .unwrap_or(path.as_os_str())
+            .to_string_lossy()
+            .into_owned(),
+        hash: HASH_ALGORITHM.to_string(),
+        digest: hex::encode(digest),
+        public_key: pp_to_hex(&public_key),
+        fingerprint: fingerprint(&public_key),
+        signature: hex::encode(signature.to_bytes().unwrap_or_else(|e| fail(e))),
+    };
+    let sidecar_path = output.map_or_else(|| sidecar_path(path), Path::to_path_buf);
+    fs::write(
+        &sidecar_path,
+        serde_json::to_string_pretty(&sidecar).unwrap() + "\n",
+    )
+    .unwrap_or_else(|e| {
+        fail(CliError::io(Msg::CannotWrite(
+            &sidecar_path,
+            &e.to_string(),
+        )))
+    });
+
+    let mut text = String::new();
+    writeln!(text, "{}", Msg::FileSigned(path, &sidecar.fingerprint)).unwrap();
+    write!(text, "{}", Msg::Wrote(&sidecar_path)).unwrap();
+    Output::new(
+        text,
+        json!({
+            "file": path,
+            "signature_file": sidecar_path,
+            "hash": sidecar.hash,
+            "digest": sidecar.digest,
+            "public_key": sidecar.public_key,
+            "fingerprint": sidecar.fingerprint,
+            "signature": sidecar.signature,
+            "ids": ids,
+        }),
+    )
+}
+
+/// check `path` against its sidecar: same digest, signed by `key` and a
+/// valid signature. exits with the verification code otherwise.
+pub fn verify(
+    path: &Path,
+    signature: Option<&Path>,
+    key: &KeyArgs,
+    framing: &FramingArgs,
+    format: OutputFormat,
+) {
+    let sidecar_path = signature.map_or_else(|| sidecar_path(path), Path::to_path_buf);
+    let sidecar = Sidecar::load(&sidecar_path).unwrap_or_else(|e| fail(e));
+    let signer = hex_to_pp(&sidecar.public_key)
+        .map_err(|e| CliError::input(Msg::InvalidSidecar(&e.to_string())))
+        .unwrap_or_else(|e| fail(e));
+    if fingerprint(&signer) != sidecar.fingerprint {
+        fail(CliError::input(Msg::InvalidSidecar(
+            "fingerprint does not match the public key",
+        )));
+    }
+    let signature = sidecar.signature().unwrap_or_else(|e| fail(e));
+
+    let public_key = key.load();
+    let digest = file_digest(path);
+    let text = if hex::encode(digest) != sidecar.digest {
+        Msg::FileModified(path)
+    } else if signer != public_key {
+        Msg::SidecarKeyMismatch(&sidecar.fingerprint)
+    } else if !Message::Prehashed(digest).verify(&signature, &public_key, &framing.config()) {
+        Msg::SignatureInvalid
+    } else {
+        Msg::FileSignatureValid(path, &sidecar.fingerprint)
+    };
+    let valid = matches!(text, Msg::FileSignatureValid(..));
+
+    Output::new(
+        text.to_string(),
+        json!({
+            "file": path,
+            "fingerprint": sidecar.fingerprint,
+            "valid": valid,
+        }),
+    )
+    .print(format);
+    if !valid {
+        process::exit(ErrorKind::Verification.code());
+    }
+}
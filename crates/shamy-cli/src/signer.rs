@@ -0,0 +1,99 @@
+use crate::error::{CliError, fail};
+use crate::i18n::Msg;
+use crate::keystore::{Keystore, read_passphrase};
+use crate::output::Output;
+use crate::parser::{OutputFormat, SignerCommands};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use shamy_net::{
+    signer::{SignerDaemon, SignerPolicy},
+    transport::TcpTransport,
+};
+
+pub fn run(command: SignerCommands, format: OutputFormat) {
+    match command {
+        SignerCommands::Serve {
+            keystore,
+            id,
+            key,
+            listen,
+            coordinator,
+            coordinator_id,
+            allow_prefix,
+            allow_sha256,
+            allow_any,
+            framing,
+        } => {
+            // decrypt before listening, a wrong passphrase should fail right away
+            let participant = Keystore::load(&keystore)
+                .and_then(|keystore| keystore.decrypt(id, &read_passphrase(id)))
+                .unwrap_or_else(|e| fail(e));
+            let public_key = key.load();
+            let policy = match allow_any {
+                true => SignerPolicy::allow_any(),
+                false => policy(&allow_prefix, &allow_sha256),
+            };
+
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap();
+            runtime.block_on(async {
+                let mut transport = TcpTransport::bind(id, &listen).await.unwrap_or_else(|e| {
+                    fail(CliError::io(Msg::CannotListen(&listen, &e.to_string())))
+                });
+                transport.add_peer(coordinator_id, coordinator);
+                let address = transport.local_addr().to_string();
+                Output::new(
+                    Msg::SignerListening(id, &address).to_string(),
+                    json!({ "event": "listening", "id": id, "address": address }),
+                )
+                .print(format);
+
+                let mut daemon = SignerDaemon::new(
+                    transport,
+                    coordinator_id,
+                    participant,
+                    public_key,
+                    framing.config(),
+                    policy,
+                );
+                daemon
+                    .run(|result| match result {
+                        Ok(message) => {
+                            let digest = hex::encode(Sha256::digest(&message));
+                            Output::new(
+                                Msg::SignedRequest(&digest).to_string(),
+                                json!({ "event": "signed", "sha256": digest }),
+                            )
+                            .print(format)
+                        }
+                        Err(e) => Output::new(
+                            Msg::SessionFailed(&e.to_string()).to_string(),
+                            json!({ "event": "failed", "error": e.to_string() }),
+                        )
+                        .print(format),
+                    })
+                    .await
+            })
+        }
+    }
+}
+
+/// deny-all policy with the `--allow-prefix` and `--allow-sha256` rules.
+fn policy(prefixes: &[String], digests: &[String]) -> SignerPolicy {
+    let policy = prefixes
+        .iter()
+        .fold(SignerPolicy::deny_all(), |policy, prefix| {
+            policy.allow_prefix(prefix.as_bytes())
+        });
+    digests.iter().fold(policy, |policy, digest| {
+        let digest = hex::decode(digest)
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| {
+                <[u8; 32]>::try_from(bytes).map_err(|_| "expected 32 bytes".to_string())
+            })
+            .unwrap_or_else(|e| fail(CliError::input(Msg::InvalidDigest(&e))));
+        policy.allow_sha256(digest)
+    })
+}
@@ -0,0 +1,214 @@
+#![allow(non_snake_case)]
+
+use crate::error::{CliError, fail};
+use crate::i18n::Msg;
+use crate::output::Output;
+use crate::parser::{OutputFormat, VectorsCommands};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use shamy_core::{
+    schnorr::SchnorrSignature,
+    threshold::{PartialSignature, Participant},
+    util::{hex_to_pp, hex_to_scalar, pp_to_hex, scalar_to_hex},
+    vectors::{NonceVector, SigningVector, generate},
+};
+use std::{fs, path::Path};
+
+const VECTOR_VERSION: u8 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct ParticipantEntry {
+    id: u64,
+    x_i: String,
+    X_i: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NonceEntry {
+    id: u64,
+    r_i: String,
+    R_i: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PartialEntry {
+    id: u64,
+    s_i: String,
+}
+
+/// a known-answer vector, every byte string in hex.
+#[derive(Serialize, Deserialize)]
+struct VectorFile {
+    version: u8,
+    seed: String,
+    threshold: usize,
+    app_id: String,
+    purpose: String,
+    message: String,
+    public_key: String,
+    commitments: Vec<String>,
+    participants: Vec<ParticipantEntry>,
+    nonces: Vec<NonceEntry>,
+    R: String,
+    challenge: String,
+    partial_signatures: Vec<PartialEntry>,
+    /// compact R || s
+    signature: String,
+}
+
+pub fn run(command: VectorsCommands, format: OutputFormat) {
+    match command {
+        VectorsCommands::Generate {
+            seed,
+            threshold,
+            num_shares,
+            message,
+            app_id,
+            purpose,
+            output,
+        } => {
+            let seed = hex::decode(&seed)
+                .ok()
+                .and_then(|seed| seed.try_into().ok())
+                .unwrap_or_else(|| fail(CliError::input(Msg::InvalidSeed)));
+            let vector = generate(
+                seed,
+                num_shares,
+                threshold,
+                &app_id,
+                &purpose,
+                message.as_bytes(),
+            )
+            .unwrap_or_else(|e| fail(e));
+            let data = serde_json::to_string_pretty(&to_file(&vector)).unwrap() + "\n";
+
+            match output {
+                Some(path) => {
+                    fs::write(&path, &data).unwrap_or_else(|e| {
+                        fail(CliError::io(Msg::CannotWrite(&path, &e.to_string())))
+                    });
+                    Output::new(Msg::Wrote(&path).to_string(), json!({ "file": path }))
+                }
+                None => Output::new(
+                    data.trim_end().to_string(),
+                    serde_json::from_str(&data).unwrap(),
+                ),
+            }
+            .print(format);
+        }
+        VectorsCommands::Verify { file } => {
+            let vector = load(&file).unwrap_or_else(|e| fail(e));
+            vector.verify().unwrap_or_else(|e| {
+                fail(CliError::verification(Msg::InvalidVector(&e.to_string())))
+            });
+            Output::new(
+                Msg::VectorVerified(&file).to_string(),
+                json!({ "file": file, "valid": true }),
+            )
+            .print(format);
+        }
+    }
+}
+
+fn to_file(vector: &SigningVector) -> VectorFile {
+    VectorFile {
+        version: VECTOR_VERSION,
+        seed: hex::encode(vector.seed),
+        threshold: vector.threshold,
+        app_id: vector.app_id.clone(),
+        purpose: vector.purpose.clone(),
+        message: hex::encode(&vector.message),
+        public_key: pp_to_hex(&vector.public_key),
+        commitments: vector.commitments.iter().map(pp_to_hex).collect(),
+        participants: vector
+            .participants
+            .iter()
+            .map(|p| ParticipantEntry {
+                id: p.id,
+                x_i: scalar_to_hex(&p.x_i),
+                X_i: pp_to_hex(&p.X_i),
+            })
+            .collect(),
+        nonces: vector
+            .nonces
+            .iter()
+            .map(|nonce| NonceEntry {
+                id: nonce.id,
+                r_i: scalar_to_hex(&nonce.r_i),
+                R_i: pp_to_hex(&nonce.R_i),
+            })
+            .collect(),
+        R: pp_to_hex(&vector.R),
+        challenge: scalar_to_hex(&vector.challenge),
+        partial_signatures: vector
+            .partial_signatures
+            .iter()
+            .map(|partial| PartialEntry {
+                id: partial.id,
+                s_i: scalar_to_hex(&partial.s_i),
+            })
+            .collect(),
+        signature: hex::encode(vector.signature.to_bytes().unwrap_or_else(|e| fail(e))),
+    }
+}
+
+fn load(path: &Path) -> Result<SigningVector, CliError> {
+    let data = fs::read(path).map_err(|e| CliError::io(Msg::CannotRead(path, &e.to_string())))?;
+    let file: VectorFile = serde_json::from_slice(&data)
+        .map_err(|e| CliError::input(Msg::InvalidVector(&e.to_string())))?;
+    if file.version != VECTOR_VERSION {
+        return Err(CliError::input(Msg::UnsupportedVectorVersion(file.version)));
+    }
+
+    let invalid = |e: String| CliError::input(Msg::InvalidVector(&e));
+    let point = |hex: &str| hex_to_pp(hex).map_err(|e| invalid(e.to_string()));
+    let scalar = |hex: &str| hex_to_scalar(hex).map_err(|e| invalid(e.to_string()));
+    let bytes = |hex: &str| hex::decode(hex).map_err(|e| invalid(e.to_string()));
+
+    Ok(SigningVector {
+        seed: bytes(&file.seed)?
+            .try_into()
+            .map_err(|_| CliError::input(Msg::InvalidSeed))?,
+        threshold: file.threshold,
+        app_id: file.app_id,
+        purpose: file.purpose,
+        message: bytes(&file.message)?,
+        public_key: point(&file.public_key)?,
+        commitments: file
+            .commitments
+            .iter()
+            .map(|C| point(C))
+            .collect::<Result<_, _>>()?,
+        participants: file
+            .participants
+            .iter()
+            .map(|p| {
+                Ok(Participant {
+                    id: p.id,
+                    x_i: scalar(&p.x_i)?,
+                    X_i: point(&p.X_i)?,
+                })
+            })
+            .collect::<Result<_, CliError>>()?,
+        nonces: file
+            .nonces
+            .iter()
+            .map(|nonce| {
+                Ok(NonceVector {
+                    id: nonce.id,
+                    r_i: scalar(&nonce.r_i)?,
+                    R_i: point(&nonce.R_i)?,
+                })
+            })
+            .collect::<Result<_, CliError>>()?,
+        R: point(&file.R)?,
+        challenge: scalar(&file.challenge)?,
+        partial_signatures: file
+            .partial_signatures
+            .iter()
+            .map(|partial| Ok(PartialSignature::new(partial.id, scalar(&partial.s_i)?)))
+            .collect::<Result<_, CliError>>()?,
+        signature: SchnorrSignature::from_bytes(&bytes(&file.signature)?)
+            .map_err(|e| invalid(e.to_string()))?,
+    })
+}
@@ -0,0 +1,155 @@
+#![allow(non_snake_case)]
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use k256::{
+    ProjectivePoint, Scalar,
+    elliptic_curve::{Field, rand_core::OsRng},
+};
+use shamy_core::hazmat::multiscalar_mul;
+use shamy_core::schnorr::{
+    KeyPair, SchnorrSignature, SigningConfig, compute_nonce_point, generate_nonce, verify_batch,
+};
+use shamy_core::shamir::shamir_keygen;
+use shamy_core::threshold::{
+    SignerSet, aggregate_nonce_with_signers, aggregate_public_key, finalize_signature_with_signers,
+    partial_sign,
+};
+use std::hint::black_box;
+
+const SIZES: [(usize, usize); 4] = [(3, 2), (10, 7), (50, 34), (100, 67)];
+
+fn keygen(c: &mut Criterion) {
+    let mut group = c.benchmark_group("keygen");
+    for (n, t) in SIZES {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{t}-of-{n}")),
+            &(n, t),
+            |b, &(n, t)| b.iter(|| shamir_keygen(n, t).unwrap()),
+        );
+    }
+    group.finish();
+}
+
+fn lagrange_aggregation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lagrange_aggregation");
+    for (n, t) in SIZES {
+        let keygen_output = shamir_keygen(n, t).unwrap();
+        let ids = keygen_output.participants[..t]
+            .iter()
+            .map(|p| p.id)
+            .collect::<Vec<_>>();
+        let signer_set = SignerSet::new(&ids, t).unwrap();
+        let public_shares = keygen_output.participants[..t]
+            .iter()
+            .map(|p| (p.id, p.X_i))
+            .collect::<Vec<_>>();
+
+        group.bench_function(BenchmarkId::new("public_key", t), |b| {
+            b.iter(|| aggregate_public_key(black_box(&public_shares)).unwrap())
+        });
+        group.bench_function(BenchmarkId::new("nonce", t), |b| {
+            b.iter(|| aggregate_nonce_with_signers(black_box(&public_shares), &signer_set).unwrap())
+        });
+    }
+    group.finish();
+}
+
+/// the README's aggregation speedup: one Straus pass against one scalar
+/// multiplication per term.
+fn multiscalar(c: &mut Criterion) {
+    let mut group = c.benchmark_group("multiscalar_mul");
+    for size in [50, 100, 200] {
+        let terms = (0..size)
+            .map(|_| {
+                let k = Scalar::random(&mut OsRng);
+                (ProjectivePoint::GENERATOR * k, Scalar::random(&mut OsRng))
+            })
+            .collect::<Vec<_>>();
+        group.bench_with_input(BenchmarkId::new("straus", size), &terms, |b, terms| {
+            b.iter(|| multiscalar_mul(black_box(terms)))
+        });
+        group.bench_with_input(BenchmarkId::new("naive", size), &terms, |b, terms| {
+            b.iter(|| {
+                black_box(terms)
+                    .iter()
+                    .map(|(P, k)| P * k)
+                    .sum::<ProjectivePoint>()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn signing(c: &mut Criterion) {
+    let (n, t) = (10, 7);
+    let keygen_output = shamir_keygen(n, t).unwrap();
+    let signers = &keygen_output.participants[..t];
+    let ids = signers.iter().map(|p| p.id).collect::<Vec<_>>();
+    let signer_set = SignerSet::new(&ids, t).unwrap();
+    let nonces = signers.iter().map(|_| generate_nonce()).collect::<Vec<_>>();
+    let R = aggregate_nonce_with_signers(
+        &ids.iter()
+            .zip(&nonces)
+            .map(|(id, r_i)| (*id, compute_nonce_point(r_i)))
+            .collect::<Vec<_>>(),
+        &signer_set,
+    )
+    .unwrap();
+    let config = SigningConfig::new("shamy-bench", "signing");
+    let challenge = config.challenge(&R, &keygen_output.public_key, b"bench");
+    let partials = signers
+        .iter()
+        .zip(&nonces)
+        .map(|(p, r_i)| partial_sign(p, r_i, &challenge))
+        .collect::<Vec<_>>();
+
+    c.bench_function("challenge", |b| {
+        b.iter(|| config.challenge(black_box(&R), &keygen_output.public_key, b"bench"))
+    });
+    c.bench_function("partial_sign", |b| {
+        b.iter(|| partial_sign(black_box(&signers[0]), &nonces[0], &challenge))
+    });
+    c.bench_function("finalize_signature_with_signers/7", |b| {
+        b.iter(|| finalize_signature_with_signers(black_box(&partials), R, &signer_set).unwrap())
+    });
+}
+
+fn verification(c: &mut Criterion) {
+    let config = SigningConfig::new("shamy-bench", "verification");
+    let key_pair = KeyPair::generate();
+    let signature = key_pair.sign(b"bench", &config);
+    let X = key_pair.public_key();
+    c.bench_function("verify", |b| {
+        b.iter(|| black_box(&signature).verify_with_config(b"bench", &X, &config))
+    });
+
+    let mut group = c.benchmark_group("verify_batch");
+    for size in [16, 128] {
+        let signatures = (0..size)
+            .map(|_| {
+                let key_pair = KeyPair::generate();
+                (
+                    &b"bench"[..],
+                    key_pair.public_key(),
+                    key_pair.sign(b"bench", &config),
+                )
+            })
+            .collect::<Vec<(&[u8], ProjectivePoint, SchnorrSignature)>>();
+        group.bench_with_input(
+            BenchmarkId::from_parameter(size),
+            &signatures,
+            |b, signatures| b.iter(|| verify_batch(black_box(signatures), &config)),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    keygen,
+    lagrange_aggregation,
+    multiscalar,
+    signing,
+    verification
+);
+criterion_main!(benches);
@@ -2,19 +2,21 @@
 
 use std::collections::HashMap;
 
-use shamy::schnorr;
-use shamy::shamir;
-use shamy::threshold::{self, Participant};
+use shamy_core::schnorr;
+use shamy_core::shamir;
+use shamy_core::threshold::{self, Participant};
 
 fn main() {
     let n = 5;
     let t = 5;
-    let keygen_output = shamir::shamir_keygen(n, t);
+    let keygen_output = shamir::shamir_keygen(n, t).unwrap();
 
     let msg = b"rust is best";
+    let config = schnorr::SigningConfig::new("shamy-examples", "demo");
 
     let signers: Vec<Participant> = keygen_output.participants.iter().take(t).copied().collect();
     let ids = signers.iter().map(|p| p.id).collect::<Vec<_>>();
+    let signer_set = threshold::SignerSet::new(&ids, t).unwrap();
 
     let mut nonces = HashMap::new();
     let mut nonce_pairs = Vec::new();
@@ -24,9 +26,9 @@ fn main() {
         nonces.insert(p.id, r_i);
         nonce_pairs.push((p.id, R_i));
     }
-    let R = threshold::aggregate_nonce(&nonce_pairs, &ids);
+    let R = threshold::aggregate_nonce_with_signers(&nonce_pairs, &signer_set).unwrap();
 
-    let c = schnorr::compute_challenge(&R, &keygen_output.public_key, msg);
+    let c = config.challenge(&R, &keygen_output.public_key, msg);
 
     let partial_signatures = signers
         .iter()
@@ -36,9 +38,10 @@ fn main() {
         })
         .collect::<Vec<_>>();
 
-    let signature = threshold::finalize_signature_lagrange(&partial_signatures, R);
+    let signature =
+        threshold::finalize_signature_with_signers(&partial_signatures, R, &signer_set).unwrap();
 
-    match signature.verify(msg, &keygen_output.public_key) {
+    match signature.verify_with_config(msg, &keygen_output.public_key, &config) {
         true => println!("success ✅"),
         false => println!("something bad happened ❌"),
     }
@@ -1,13 +1,13 @@
 #![allow(non_snake_case)]
 
 use rand::seq::IndexedRandom;
-use shamy::shamir;
-use shamy::vss::verify_share;
+use shamy_core::shamir;
+use shamy_core::vss::verify_share;
 
 fn main() {
     let n = 3;
     let t = 2;
-    let keygen_output = shamir::shamir_keygen(n, t);
+    let keygen_output = shamir::shamir_keygen(n, t).unwrap();
 
     let mut rng = rand::rng();
     let random_participant = keygen_output.participants.choose(&mut rng).unwrap();
@@ -0,0 +1,113 @@
+#ifndef SHAMY_H
+#define SHAMY_H
+
+/* generated by cbindgen from src/ffi.rs, do not edit */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#define SHAMY_POINT_LEN 33
+
+#define SHAMY_SCALAR_LEN 32
+
+#define SHAMY_SIGNATURE_LEN (SHAMY_POINT_LEN + SHAMY_SCALAR_LEN)
+
+typedef enum ShamyStatus {
+  SHAMY_STATUS_OK = 0,
+  /**
+   * a required pointer is NULL.
+   */
+  SHAMY_STATUS_NULL_POINTER = 1,
+  /**
+   * a length, threshold, id or string is invalid.
+   */
+  SHAMY_STATUS_INVALID_ARGUMENT = 2,
+  /**
+   * bytes do not encode a point on secp256k1.
+   */
+  SHAMY_STATUS_INVALID_POINT = 3,
+  /**
+   * bytes do not encode a scalar modulo the group order.
+   */
+  SHAMY_STATUS_INVALID_SCALAR = 4,
+  /**
+   * the signature does not verify.
+   */
+  SHAMY_STATUS_INVALID_SIGNATURE = 5,
+} ShamyStatus;
+
+/**
+ * static description of a status code.
+ */
+const char *shamy_status_message(enum ShamyStatus status);
+
+/**
+ * t-of-n trusted dealer keygen.
+ * writes X to `public_key` (33 bytes), x_1..x_n to `shares` (n * 32 bytes)
+ * and X_1..X_n to `public_shares` (n * 33 bytes).
+ */
+enum ShamyStatus shamy_keygen(size_t n,
+                              size_t t,
+                              uint8_t *public_key,
+                              uint8_t *shares,
+                              uint8_t *public_shares);
+
+/**
+ * fresh nonce r_i (32 bytes) and its commitment R_i = r_i*G (33 bytes).
+ * ⚠️ use the secret for exactly one partial signature.
+ */
+enum ShamyStatus shamy_generate_nonce(uint8_t *secret, uint8_t *commitment);
+
+/**
+ * R = Σ λ_i*R_i over `len` signers, `nonces` holds len * 33 bytes.
+ */
+enum ShamyStatus shamy_aggregate_nonce(const uint64_t *ids,
+                                       const uint8_t *nonces,
+                                       size_t len,
+                                       uint8_t *nonce);
+
+/**
+ * c = H(R, X, frame(m)), written to `challenge` (32 bytes).
+ */
+enum ShamyStatus shamy_challenge(const uint8_t *nonce,
+                                 const uint8_t *public_key,
+                                 const uint8_t *message,
+                                 size_t message_len,
+                                 const char *app_id,
+                                 const char *purpose,
+                                 uint8_t *challenge);
+
+/**
+ * s_i = r_i + c*x_i, written to `partial` (32 bytes).
+ */
+enum ShamyStatus shamy_partial_sign(uint64_t id,
+                                    const uint8_t *share,
+                                    const uint8_t *nonce,
+                                    const uint8_t *challenge,
+                                    uint8_t *partial);
+
+/**
+ * combine `len` partial signatures (len * 32 bytes) under the aggregate
+ * nonce R into a signature (65 bytes).
+ */
+enum ShamyStatus shamy_aggregate(const uint64_t *ids,
+                                 const uint8_t *partials,
+                                 size_t len,
+                                 const uint8_t *nonce,
+                                 uint8_t *signature);
+
+/**
+ * `SHAMY_STATUS_OK` if the signature (65 bytes) is valid for the message and
+ * public key, `SHAMY_STATUS_INVALID_SIGNATURE` otherwise.
+ */
+enum ShamyStatus shamy_verify(const uint8_t *message,
+                              size_t message_len,
+                              const uint8_t *signature,
+                              const uint8_t *public_key,
+                              const char *app_id,
+                              const char *purpose);
+
+#endif  /* SHAMY_H */
@@ -0,0 +1,125 @@
+#![allow(non_snake_case)]
+
+use crate::error::Error;
+use crate::schnorr::SessionId;
+use crate::threshold::PartialSignature;
+use k256::{ProjectivePoint, Scalar};
+#[cfg(feature = "std")]
+use {
+    crate::schnorr::{compute_nonce_point, generate_nonce_with_rng},
+    crate::threshold::{Participant, partial_sign},
+    alloc::collections::BTreeMap,
+    k256::elliptic_curve::rand_core::{CryptoRngCore, OsRng},
+    std::sync::Mutex,
+};
+
+//--------------------------------------------------------------------
+// Share backends
+//--------------------------------------------------------------------
+//
+// `threshold::partial_sign` needs x_i and r_i in memory. a backend keeps
+// both wherever the share lives (process memory, an HSM, a remote KMS)
+// and only hands out public values:
+//
+//   commit(session)            r_i random, kept inside, returns R_i = r_i*G
+//   partial_sign(c, session)   s_i = r_i + c*x_i, then r_i is forgotten
+//   discard(session)           forget r_i of an aborted session
+//
+// a backend holds at most one nonce per session: a second commit fails
+// with `Error::DuplicateSession`, a partial without a commit (or a second
+// one) with `Error::UnknownSession`. so no nonce is used twice.
+//
+// the methods take &self, backends guard their state themselves, like a
+// device handle or a KMS client does.
+//
+// ⚠️ a backend signs whatever challenge it is given, deciding what to
+//    sign is up to the caller (see `shamy_net::signer::SignerPolicy`).
+//
+
+/// holds one participant's share and signs without exposing it.
+pub trait ShareBackend {
+    /// participant id of the share.
+    fn id(&self) -> u64;
+
+    /// X_i = x_i*G.
+    fn public_share(&self) -> ProjectivePoint;
+
+    /// generate the nonce of `session` and return R_i.
+    fn commit(&self, session: &SessionId) -> Result<ProjectivePoint, Error>;
+
+    /// s_i = r_i + c*x_i with the nonce of `session`, which is consumed.
+    fn partial_sign(&self, c: &Scalar, session: &SessionId) -> Result<PartialSignature, Error>;
+
+    /// drop the nonce of `session` without signing.
+    fn discard(&self, session: &SessionId) -> Result<(), Error>;
+}
+
+/// the default backend: x_i and the open nonces in process memory,
+/// nonces drawn from `R`.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct MemoryBackend<R = OsRng> {
+    participant: Participant,
+    nonces: Mutex<BTreeMap<SessionId, Scalar>>,
+    rng: Mutex<R>,
+}
+
+#[cfg(feature = "std")]
+impl MemoryBackend {
+    pub fn new(participant: Participant) -> Self {
+        Self::with_rng(participant, OsRng)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: CryptoRngCore> MemoryBackend<R> {
+    /// like `new`, drawing every nonce from `rng`.
+    pub fn with_rng(participant: Participant, rng: R) -> Self {
+        Self {
+            participant,
+            nonces: Mutex::new(BTreeMap::new()),
+            rng: Mutex::new(rng),
+        }
+    }
+
+    /// number of sessions committed to and not yet signed or discarded.
+    pub fn open_sessions(&self) -> usize {
+        self.nonces.lock().unwrap().len()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: CryptoRngCore> ShareBackend for MemoryBackend<R> {
+    fn id(&self) -> u64 {
+        self.participant.id
+    }
+
+    fn public_share(&self) -> ProjectivePoint {
+        self.participant.X_i
+    }
+
+    fn commit(&self, session: &SessionId) -> Result<ProjectivePoint, Error> {
+        let mut nonces = self.nonces.lock().unwrap();
+        if nonces.contains_key(session) {
+            return Err(Error::DuplicateSession(*session));
+        }
+        let r_i = generate_nonce_with_rng(&mut *self.rng.lock().unwrap());
+        nonces.insert(*session, r_i);
+        Ok(compute_nonce_point(&r_i))
+    }
+
+    fn partial_sign(&self, c: &Scalar, session: &SessionId) -> Result<PartialSignature, Error> {
+        let r_i = self
+            .nonces
+            .lock()
+            .unwrap()
+            .remove(session)
+            .ok_or(Error::UnknownSession(*session))?;
+        Ok(partial_sign(&self.participant, &r_i, c))
+    }
+
+    fn discard(&self, session: &SessionId) -> Result<(), Error> {
+        self.nonces.lock().unwrap().remove(session);
+        Ok(())
+    }
+}
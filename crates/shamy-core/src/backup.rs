@@ -0,0 +1,224 @@
+#![allow(non_snake_case)]
+
+use crate::error::Error;
+use crate::hash::tagged_hash;
+use crate::shamir::reconstruct_secret;
+use crate::threshold::Participant;
+use crate::util::bip39_wordlist;
+use alloc::{string::String, vec::Vec};
+use k256::{
+    ProjectivePoint, Scalar,
+    elliptic_curve::{PrimeField, sec1::ToEncodedPoint},
+};
+use sha2::{Digest, Sha256};
+
+const CHECKSUM_TAG: &[u8] = b"shamy/backup/checksum/v1";
+const VERSION: u64 = 0;
+const BACKUP_WORDS: usize = 32;
+const PAYLOAD_BITS: usize = 322;
+
+//--------------------------------------------------------------------
+// Share backup phrases (SLIP-39 style)
+//--------------------------------------------------------------------
+//
+// 32 words of the BIP-39 list, 11 bits each, 352 bits in total
+//
+//   version   (4)    0
+//   key id    (30)   first 30 bits of SHA-256(compressed group key)
+//   threshold (16)   t
+//   id        (16)   participant id
+//   share     (256)  x_i
+//   checksum  (30)   first 30 bits of tagged_hash(tag, all of the above)
+//
+// like SLIP-39, every phrase says which key it belongs to and how many
+// phrases are needed, so a set of backups can be checked and combined
+// without any other record of the ceremony.
+//
+// ⚠️ not wire compatible with SLIP-39: SLIP-39 splits a master secret
+// byte-wise over GF(256), while shamy shares are evaluations of a
+// polynomial mod q, and the encoding uses the BIP-39 list instead of the
+// SLIP-39 one. the layout is documented above for other implementations.
+//
+
+/// a share with the metadata needed to recover the key from backups alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShareBackup {
+    pub key_id: u32,
+    pub threshold: u16,
+    pub id: u16,
+    pub x_i: Scalar,
+}
+
+/// first 30 bits of SHA-256 over the compressed key, see `util::fingerprint`.
+pub fn key_id(public_key: &ProjectivePoint) -> u32 {
+    let digest = Sha256::digest(public_key.to_affine().to_encoded_point(true).as_bytes());
+    u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]]) >> 2
+}
+
+impl ShareBackup {
+    /// backup of `participant`'s share of `public_key` with threshold `t`.
+    pub fn new(
+        participant: &Participant,
+        public_key: &ProjectivePoint,
+        t: usize,
+    ) -> Result<Self, Error> {
+        if t < 2 {
+            return Err(Error::ThresholdTooSmall {
+                threshold: t,
+                min: 2,
+            });
+        }
+        // the backup stores t in 16 bits
+        let threshold = u16::try_from(t).map_err(|_| Error::ThresholdTooLarge {
+            threshold: t,
+            max: u16::MAX as usize,
+        })?;
+        let id = u16::try_from(participant.id)
+            .ok()
+            .filter(|id| *id != 0)
+            .ok_or(Error::InvalidParticipantId {
+                id: participant.id,
+                num_shares: u16::MAX as usize,
+            })?;
+
+        Ok(Self {
+            key_id: key_id(public_key),
+            threshold,
+            id,
+            x_i: participant.x_i,
+        })
+    }
+
+    /// the 32-word phrase.
+    pub fn to_words(&self) -> String {
+        let mut bits = BitBuffer::default();
+        bits.push(VERSION, 4);
+        bits.push(u64::from(self.key_id), 30);
+        bits.push(u64::from(self.threshold), 16);
+        bits.push(u64::from(self.id), 16);
+        for byte in self.x_i.to_bytes() {
+            bits.push(u64::from(byte), 8);
+        }
+        bits.push(checksum(&bits.bytes(PAYLOAD_BITS)), 30);
+
+        let words = bip39_wordlist();
+        (0..BACKUP_WORDS)
+            .map(|i| words[bits.read(i * 11, 11) as usize])
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// parse a phrase written by `to_words`, checking the checksum.
+    pub fn from_words(phrase: &str) -> Result<Self, Error> {
+        let words = bip39_wordlist();
+        let phrase = phrase.split_whitespace().collect::<Vec<_>>();
+        if phrase.len() != BACKUP_WORDS {
+            return Err(Error::InvalidEncoding(alloc::format!(
+                "expected {BACKUP_WORDS} words, got {}",
+                phrase.len()
+            )));
+        }
+
+        let mut bits = BitBuffer::default();
+        for word in phrase {
+            let index = words
+                .binary_search(&word.to_lowercase().as_str())
+                .map_err(|_| Error::InvalidEncoding(alloc::format!("unknown word {word:?}")))?;
+            bits.push(index as u64, 11);
+        }
+
+        if checksum(&bits.bytes(PAYLOAD_BITS)) != bits.read(PAYLOAD_BITS, 30) {
+            return Err(Error::InvalidEncoding("checksum mismatch".into()));
+        }
+        if bits.read(0, 4) != VERSION {
+            return Err(Error::InvalidEncoding("unknown backup version".into()));
+        }
+
+        let mut x_i = [0u8; 32];
+        for (i, byte) in x_i.iter_mut().enumerate() {
+            *byte = bits.read(66 + i * 8, 8) as u8;
+        }
+        let x_i = Scalar::from_repr(x_i.into())
+            .into_option()
+            .ok_or(Error::InvalidScalar)?;
+
+        Ok(Self {
+            key_id: bits.read(4, 30) as u32,
+            threshold: bits.read(34, 16) as u16,
+            id: bits.read(50, 16) as u16,
+            x_i,
+        })
+    }
+
+    /// the backed up share as a participant, X_i is recomputed.
+    pub fn participant(&self) -> Participant {
+        Participant::from_secret(u64::from(self.id), self.x_i)
+    }
+}
+
+/// recover the group secret from at least t backups of the same key.
+/// unlike `reconstruct_secret`, too few or mixed shares are an error, and
+/// the result is checked against the key id.
+pub fn recover_secret(backups: &[ShareBackup]) -> Result<Scalar, Error> {
+    let first = backups.first().ok_or(Error::NoShares)?;
+    if backups
+        .iter()
+        .any(|b| b.key_id != first.key_id || b.threshold != first.threshold)
+    {
+        return Err(Error::BackupMismatch);
+    }
+    if backups.len() < first.threshold as usize {
+        return Err(Error::NotEnoughShares {
+            threshold: first.threshold as usize,
+            actual: backups.len(),
+        });
+    }
+
+    let shares = backups
+        .iter()
+        .map(|b| (u64::from(b.id), b.x_i))
+        .collect::<Vec<_>>();
+    let secret = reconstruct_secret(&shares)?;
+    if key_id(&(ProjectivePoint::GENERATOR * secret)) != first.key_id {
+        return Err(Error::BackupMismatch);
+    }
+
+    Ok(secret)
+}
+
+fn checksum(payload: &[u8]) -> u64 {
+    let digest = tagged_hash(CHECKSUM_TAG, payload);
+    u64::from(u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]]) >> 2)
+}
+
+/// big-endian bit string.
+#[derive(Default)]
+struct BitBuffer {
+    bits: Vec<bool>,
+}
+
+impl BitBuffer {
+    fn push(&mut self, value: u64, n: usize) {
+        self.bits
+            .extend((0..n).rev().map(|i| (value >> i) & 1 == 1));
+    }
+
+    fn read(&self, start: usize, n: usize) -> u64 {
+        self.bits[start..start + n]
+            .iter()
+            .fold(0, |acc, bit| (acc << 1) | u64::from(*bit))
+    }
+
+    /// the first `len` bits packed into bytes, the last one zero padded.
+    fn bytes(&self, len: usize) -> Vec<u8> {
+        self.bits[..len]
+            .chunks(8)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .enumerate()
+                    .fold(0u8, |acc, (i, bit)| acc | (u8::from(*bit) << (7 - i)))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,130 @@
+#![allow(non_snake_case)]
+
+use crate::error::Error;
+use crate::hazmat::{self, LagrangeWeights};
+use crate::schnorr::SchnorrSignature;
+use crate::threshold::{PartialSignature, Participant};
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use k256::elliptic_curve::rand_core::OsRng;
+use k256::{
+    ProjectivePoint, Scalar,
+    elliptic_curve::{Field, rand_core::CryptoRngCore},
+};
+
+//--------------------------------------------------------------------
+// Additive blinding
+//--------------------------------------------------------------------
+//
+// a share or partial signature passes a semi-trusted aggregator with a
+// random offset rᵢ added. only the blinder knows rᵢ, everyone may see
+// Rᵢ = rᵢ*G:
+//
+//   xᵢ' = xᵢ + rᵢ           Xᵢ' = Xᵢ + Rᵢ
+//   sᵢ' = sᵢ + rᵢ
+//
+// weighted sums over a set S keep the offsets apart from the values:
+//
+//   Σ λᵢ·sᵢ' = s + ρ         ρ = Σ λᵢ·rᵢ      (blinder removes ρ)
+//   Σ λᵢ·Xᵢ' = X + P         P = Σ λᵢ·Rᵢ = ρ*G (public tracking)
+//
+// so the aggregator can check s'*G == R + c*X + P and combine without
+// ever holding a usable share or signature.
+//
+// ⚠️ draw a fresh rᵢ for every value. one r on two values gives away
+//    their difference, on a share and its partial it gives away rᵢ.
+//
+
+/// the offset rᵢ blinding participant `id`'s values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Blinding {
+    pub id: u64,
+    pub r: Scalar,
+}
+
+impl Blinding {
+    #[cfg(feature = "std")]
+    pub fn random(id: u64) -> Self {
+        Self::random_with_rng(id, &mut OsRng)
+    }
+
+    /// like `random`, drawing rᵢ from `rng`.
+    pub fn random_with_rng(id: u64, rng: &mut impl CryptoRngCore) -> Self {
+        Self {
+            id,
+            r: Scalar::random(rng),
+        }
+    }
+
+    /// Rᵢ = rᵢ*G, safe to publish.
+    pub fn offset(&self) -> ProjectivePoint {
+        ProjectivePoint::GENERATOR * self.r
+    }
+}
+
+/// xᵢ' = xᵢ + r, Xᵢ' = Xᵢ + r*G.
+pub fn blind_share(share: &Participant, r: &Scalar) -> Participant {
+    Participant {
+        id: share.id,
+        x_i: share.x_i + r,
+        X_i: share.X_i + ProjectivePoint::GENERATOR * r,
+    }
+}
+
+/// undo `blind_share` with the same r.
+pub fn unblind_share(blinded: &Participant, r: &Scalar) -> Participant {
+    blind_share(blinded, &-r)
+}
+
+/// sᵢ' = sᵢ + r. a carried Rᵢ moves to Rᵢ + r*G, so the blinded
+/// partial still passes `PartialSignature::verify`.
+pub fn blind_partial(partial: &PartialSignature, r: &Scalar) -> PartialSignature {
+    PartialSignature {
+        s_i: partial.s_i + r,
+        R_i: partial.R_i.map(|R_i| R_i + ProjectivePoint::GENERATOR * r),
+        ..*partial
+    }
+}
+
+/// undo `blind_partial` with the same r.
+pub fn unblind_partial(blinded: &PartialSignature, r: &Scalar) -> PartialSignature {
+    blind_partial(blinded, &-r)
+}
+
+/// Xᵢ' = Xᵢ + Rᵢ, the public share a blinded share must match.
+pub fn blinded_public_share(X_i: &ProjectivePoint, offset: &ProjectivePoint) -> ProjectivePoint {
+    X_i + offset
+}
+
+/// ρ = Σ λᵢ·rᵢ, the offset left in a sum of values blinded with `blindings`.
+pub fn aggregate_offset(blindings: &[Blinding]) -> Result<Scalar, Error> {
+    let ids = blindings.iter().map(|b| b.id).collect::<Vec<_>>();
+    let weights = LagrangeWeights::new(&ids)?;
+
+    Ok(blindings
+        .iter()
+        .map(|b| weights.weight(b.id).unwrap() * b.r)
+        .sum())
+}
+
+/// P = Σ λᵢ·Rᵢ = ρ*G from the published offsets `(id, Rᵢ)`.
+pub fn aggregate_offset_point(
+    offsets: &[(u64, ProjectivePoint)],
+) -> Result<ProjectivePoint, Error> {
+    let ids = offsets.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+    let weights = LagrangeWeights::new(&ids)?;
+    let terms = offsets
+        .iter()
+        .map(|(id, R_i)| (*R_i, weights.weight(*id).unwrap()))
+        .collect::<Vec<_>>();
+
+    Ok(hazmat::multiscalar_mul(&terms))
+}
+
+/// s = s' − ρ for a signature combined from blinded partials.
+pub fn unblind_signature(blinded: &SchnorrSignature, rho: &Scalar) -> SchnorrSignature {
+    SchnorrSignature {
+        R: blinded.R,
+        s: blinded.s - rho,
+    }
+}
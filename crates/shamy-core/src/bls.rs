@@ -0,0 +1,222 @@
+#![allow(non_snake_case)]
+
+use crate::error::Error;
+use crate::hazmat;
+use crate::limits::Limits;
+use crate::schnorr::SigningConfig;
+use alloc::vec::Vec;
+use bls12_381::{
+    G1Affine, G1Projective, G2Affine, G2Projective, Scalar,
+    hash_to_curve::{ExpandMsgXmd, HashToCurve},
+    pairing,
+};
+use k256::elliptic_curve::rand_core::CryptoRngCore;
+#[cfg(feature = "std")]
+use k256::elliptic_curve::rand_core::OsRng;
+
+//--------------------------------------------------------------------
+// Threshold BLS (BLS12-381, minimal signature size)
+//--------------------------------------------------------------------
+//
+// signatures live in G1 (48 bytes), keys in G2 (96 bytes).
+//
+//   H(m)    hash to G1, IETF suite BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_NUL_
+//   σ_i   = x_i*H(m)                      (partial signature)
+//   σ     = Σ λ_i*σ_i = x*H(m)            (Lagrange in the exponent)
+//
+//   verify: e(σ, g2) == e(H(m), X)
+//
+// unlike Schnorr there are no nonces: signing is deterministic, a single
+// round, and any t partials combine to the same σ.
+//
+// ⚠️ BLS12-381 has its own scalar field, shares from `shamir` (secp256k1)
+// can't be reused here. keys come from `keygen` below.
+//
+
+/// domain separation tag of the IETF minimal-signature-size suite.
+pub const DST: &[u8] = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_NUL_";
+
+/// share of participant `id`: x_i = f(i), X_i = x_i*g2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlsShare {
+    pub id: u64,
+    pub x_i: Scalar,
+    pub X_i: G2Projective,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlsKeygenOutput {
+    pub shares: Vec<BlsShare>,
+    pub public_key: G2Projective,
+}
+
+/// t-of-n trusted dealer keygen over the BLS12-381 scalar field.
+#[cfg(feature = "std")]
+pub fn keygen(n: usize, t: usize) -> Result<BlsKeygenOutput, Error> {
+    keygen_with_rng(n, t, &Limits::default(), &mut OsRng)
+}
+
+/// like `keygen`, with explicit limits and drawing the polynomial from `rng`.
+pub fn keygen_with_rng(
+    n: usize,
+    t: usize,
+    limits: &Limits,
+    rng: &mut impl CryptoRngCore,
+) -> Result<BlsKeygenOutput, Error> {
+    limits.check(t, n)?;
+
+    let coefficients = (0..t).map(|_| random_scalar(rng)).collect::<Vec<_>>();
+    let shares = (1..=n as u64)
+        .map(|id| {
+            let x = Scalar::from(id);
+            let x_i = coefficients
+                .iter()
+                .rev()
+                .fold(Scalar::zero(), |acc, c| acc * x + c);
+            BlsShare {
+                id,
+                x_i,
+                X_i: G2Projective::generator() * x_i,
+            }
+        })
+        .collect();
+
+    Ok(BlsKeygenOutput {
+        shares,
+        public_key: G2Projective::generator() * coefficients[0],
+    })
+}
+
+/// H(frame(m)) in G1.
+pub fn hash_to_g1(message: &[u8], config: &SigningConfig) -> G1Projective {
+    <G1Projective as HashToCurve<ExpandMsgXmd<sha2_09::Sha256>>>::hash_to_curve(
+        config.frame_message(message),
+        DST,
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlsPartialSignature {
+    pub id: u64,
+    pub sigma_i: G1Projective,
+}
+
+/// σ_i = x_i*H(m).
+pub fn partial_sign(
+    share: &BlsShare,
+    message: &[u8],
+    config: &SigningConfig,
+) -> BlsPartialSignature {
+    BlsPartialSignature {
+        id: share.id,
+        sigma_i: hash_to_g1(message, config) * share.x_i,
+    }
+}
+
+/// check e(σ_i, g2) == e(H(m), X_i), so a bad partial can be blamed on its
+/// signer before combining.
+pub fn verify_partial(
+    partial: &BlsPartialSignature,
+    X_i: &G2Projective,
+    message: &[u8],
+    config: &SigningConfig,
+) -> bool {
+    verify_pairing(&partial.sigma_i, &hash_to_g1(message, config), X_i)
+}
+
+/// σ = Σ λ_i*σ_i over at least t partials with distinct ids.
+pub fn combine(partials: &[BlsPartialSignature]) -> Result<BlsSignature, Error> {
+    let ids = partials.iter().map(|p| p.id).collect::<Vec<_>>();
+    hazmat::validate_ids(&ids)?;
+
+    Ok(BlsSignature(
+        partials.iter().fold(G1Projective::identity(), |acc, p| {
+            acc + p.sigma_i * lagrange_coefficient(p.id, &ids)
+        }),
+    ))
+}
+
+/// combined BLS signature σ in G1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlsSignature(pub G1Projective);
+
+impl BlsSignature {
+    pub fn verify(
+        &self,
+        message: &[u8],
+        public_key: &G2Projective,
+        config: &SigningConfig,
+    ) -> bool {
+        verify_pairing(&self.0, &hash_to_g1(message, config), public_key)
+    }
+
+    /// σ = Σ σ_j, one signature over the signatures of several groups.
+    pub fn aggregate(signatures: &[BlsSignature]) -> BlsSignature {
+        BlsSignature(signatures.iter().map(|s| s.0).sum())
+    }
+
+    /// check an aggregate against `(message, public key)` pairs:
+    /// e(σ, g2) == Π e(H(m_j), X_j).
+    /// ⚠️ messages must be distinct, otherwise rogue keys can forge.
+    pub fn verify_aggregate(
+        &self,
+        signed: &[(&[u8], G2Projective)],
+        config: &SigningConfig,
+    ) -> bool {
+        for (i, (message, _)) in signed.iter().enumerate() {
+            if signed[..i].iter().any(|(other, _)| other == message) {
+                return false;
+            }
+        }
+
+        let lhs = pairing(&G1Affine::from(self.0), &G2Affine::generator());
+        let rhs = signed
+            .iter()
+            .map(|(message, X)| {
+                pairing(
+                    &G1Affine::from(hash_to_g1(message, config)),
+                    &G2Affine::from(X),
+                )
+            })
+            .sum();
+
+        lhs == rhs
+    }
+
+    /// 48 byte compressed encoding.
+    pub fn to_bytes(&self) -> [u8; 48] {
+        G1Affine::from(self.0).to_compressed()
+    }
+
+    pub fn from_bytes(bytes: &[u8; 48]) -> Result<Self, Error> {
+        G1Affine::from_compressed(bytes)
+            .into_option()
+            .map(|sigma| BlsSignature(sigma.into()))
+            .ok_or(Error::InvalidPoint("not a G1 point".into()))
+    }
+}
+
+fn verify_pairing(sigma: &G1Projective, H: &G1Projective, X: &G2Projective) -> bool {
+    pairing(&G1Affine::from(sigma), &G2Affine::generator())
+        == pairing(&G1Affine::from(H), &G2Affine::from(X))
+}
+
+fn random_scalar(rng: &mut impl CryptoRngCore) -> Scalar {
+    let mut bytes = [0u8; 64];
+    rng.fill_bytes(&mut bytes);
+    Scalar::from_bytes_wide(&bytes)
+}
+
+/// λ_i at 0 over the BLS12-381 scalar field, see `hazmat::lagrange_coefficient`.
+fn lagrange_coefficient(id_i: u64, ids: &[u64]) -> Scalar {
+    let x_i = Scalar::from(id_i);
+    let (num, den) = ids.iter().filter(|id_j| **id_j != id_i).fold(
+        (Scalar::one(), Scalar::one()),
+        |(num, den), id_j| {
+            let x_j = Scalar::from(*id_j);
+            (num * x_j, den * (x_j - x_i))
+        },
+    );
+
+    num * den.invert().unwrap()
+}
@@ -0,0 +1,251 @@
+#![allow(non_snake_case)]
+
+use crate::error::Error;
+use crate::frost::{NonceCommitment, nonce_points_with_config};
+use crate::schnorr::{SchnorrSignature, SessionId, SigningConfig};
+use crate::threshold::{PartialSignature, SessionPhase, SigningSession};
+use crate::util::ensure_not_identity;
+use alloc::{collections::BTreeMap, vec::Vec};
+#[cfg(feature = "std")]
+use k256::elliptic_curve::rand_core::OsRng;
+use k256::{ProjectivePoint, Scalar, elliptic_curve::rand_core::CryptoRngCore};
+
+//--------------------------------------------------------------------
+// Multi-session coordinator
+//--------------------------------------------------------------------
+//
+// one `SigningSession` per message, keyed by a random `SessionId` that is
+// also bound into its challenge:
+//
+//   start_batch([m_1 .. m_k])     ─► [sid_1 .. sid_k]
+//   add_commitment(sid, (D_i, E_i)) per session and signer
+//   challenges(sids)           ─►  [(R, c) ..]
+//   add_partial(sid, s_i)           per session and signer
+//   collect(sids)              ─►  [(R, s) ..]
+//
+// sessions share nothing but the key and the config. a message for one
+// session can't change another, and a partial for one session fails in
+// any other because the challenges differ.
+//
+// signers commit FROST nonce pairs, not single nonces (see frost.rs):
+//
+//   R_i = D_i + ρ_i*E_i,   ρ_i = H(X, m, B, i)
+//
+// with single nonces a co-signer could pick its R_i after seeing the
+// honest ones in k parallel sessions and forge a signature (ROS). ρ_i
+// depends on every commitment of the session, so a late R_i moves the
+// honest nonces as well. signers sign with `NoncePool::sign` over
+// `commitments(sid)` and `config.with_session(sid)`.
+//
+// ⚠️ every session needs its own nonce pairs from every signer, never
+//    commit one pair to several sessions.
+//
+
+/// coordinator side of many concurrent signing sessions under one key.
+/// in-process only, see `shamy-net` for a coordinator with a shared store.
+#[derive(Debug, Clone)]
+pub struct Coordinator {
+    public_key: ProjectivePoint,
+    config: SigningConfig,
+    sessions: BTreeMap<SessionId, SigningSession>,
+    commitments: BTreeMap<SessionId, BTreeMap<u64, NonceCommitment>>,
+}
+
+impl Coordinator {
+    /// the session id goes into the challenge, so `config` needs the v1 or merlin challenge.
+    pub fn new(public_key: ProjectivePoint, config: SigningConfig) -> Result<Self, Error> {
+        config
+            .clone()
+            .with_session(SessionId([0; 32]))
+            .check_bindings()?;
+
+        Ok(Self {
+            public_key,
+            config,
+            sessions: BTreeMap::new(),
+            commitments: BTreeMap::new(),
+        })
+    }
+
+    /// start a session for `message` with a random id.
+    #[cfg(feature = "std")]
+    pub fn start(&mut self, message: &[u8], signer_ids: &[u64]) -> Result<SessionId, Error> {
+        self.start_with_rng(message, signer_ids, &mut OsRng)
+    }
+
+    /// like `start`, drawing the session id from `rng`.
+    pub fn start_with_rng(
+        &mut self,
+        message: &[u8],
+        signer_ids: &[u64],
+        rng: &mut impl CryptoRngCore,
+    ) -> Result<SessionId, Error> {
+        let id = SessionId::random_with_rng(rng);
+        self.start_with_id(id, message, signer_ids)?;
+        Ok(id)
+    }
+
+    /// start a session under an id agreed on elsewhere.
+    pub fn start_with_id(
+        &mut self,
+        id: SessionId,
+        message: &[u8],
+        signer_ids: &[u64],
+    ) -> Result<(), Error> {
+        if self.sessions.contains_key(&id) {
+            return Err(Error::DuplicateSession(id));
+        }
+        let config = self.config.clone().with_session(id);
+        let session = SigningSession::new(self.public_key, message, signer_ids, config)?;
+        self.sessions.insert(id, session);
+
+        Ok(())
+    }
+
+    /// start one session per message, all with the same signers.
+    /// nothing is started if one of them fails.
+    #[cfg(feature = "std")]
+    pub fn start_batch(
+        &mut self,
+        messages: &[&[u8]],
+        signer_ids: &[u64],
+    ) -> Result<Vec<SessionId>, Error> {
+        self.start_batch_with_rng(messages, signer_ids, &mut OsRng)
+    }
+
+    /// like `start_batch`, drawing the session ids from `rng`.
+    pub fn start_batch_with_rng(
+        &mut self,
+        messages: &[&[u8]],
+        signer_ids: &[u64],
+        rng: &mut impl CryptoRngCore,
+    ) -> Result<Vec<SessionId>, Error> {
+        let mut batch = BTreeMap::new();
+        let mut ids = Vec::with_capacity(messages.len());
+        for message in messages {
+            let id = SessionId::random_with_rng(rng);
+            if self.sessions.contains_key(&id) || batch.contains_key(&id) {
+                return Err(Error::DuplicateSession(id));
+            }
+            let config = self.config.clone().with_session(id);
+            batch.insert(
+                id,
+                SigningSession::new(self.public_key, message, signer_ids, config)?,
+            );
+            ids.push(id);
+        }
+        self.sessions.append(&mut batch);
+
+        Ok(ids)
+    }
+
+    /// ids of the running and finalized sessions.
+    pub fn sessions(&self) -> Vec<SessionId> {
+        self.sessions.keys().copied().collect()
+    }
+
+    pub fn session(&self, id: &SessionId) -> Result<&SigningSession, Error> {
+        self.sessions.get(id).ok_or(Error::UnknownSession(*id))
+    }
+
+    /// drop a session, e.g. once its signature was delivered.
+    pub fn remove(&mut self, id: &SessionId) -> Option<SigningSession> {
+        self.commitments.remove(id);
+        self.sessions.remove(id)
+    }
+
+    /// record the nonce pair commitment of `commitment.id`. once every
+    /// signer's is in, R_i = D_i + ρ_i*E_i go to the session and the
+    /// challenge is computed. nothing is recorded unless this returns Ok,
+    /// so a rejected commitment can be resent.
+    pub fn add_commitment(
+        &mut self,
+        session: &SessionId,
+        commitment: NonceCommitment,
+    ) -> Result<(), Error> {
+        let signing = self
+            .sessions
+            .get(session)
+            .ok_or(Error::UnknownSession(*session))?;
+        let id = commitment.id;
+        if !signing.signer_ids().contains(&id) {
+            return Err(Error::UnexpectedPackage { round: 1, id });
+        }
+        let mut received = self.commitments.get(session).cloned().unwrap_or_default();
+        if received.contains_key(&id) {
+            return Err(Error::DuplicatePackage { round: 1, id });
+        }
+        ensure_not_identity(&commitment.D)?;
+        ensure_not_identity(&commitment.E)?;
+        received.insert(id, commitment);
+
+        if received.len() == signing.signer_ids().len() {
+            let commitments = received.values().copied().collect::<Vec<_>>();
+            let config = self.config.clone().with_session(*session);
+            let nonces = nonce_points_with_config(
+                &commitments,
+                &self.public_key,
+                signing.message(),
+                &config,
+            );
+            let mut signing = signing.clone();
+            for (id, R_i) in nonces {
+                signing.add_nonce(id, R_i)?;
+            }
+            self.sessions.insert(*session, signing);
+        }
+        self.commitments.insert(*session, received);
+
+        Ok(())
+    }
+
+    /// the nonce pair commitments of `session` received so far, in id order.
+    /// every signer signs over the complete list.
+    pub fn commitments(&self, session: &SessionId) -> Result<Vec<NonceCommitment>, Error> {
+        self.session(session)?;
+        Ok(self
+            .commitments
+            .get(session)
+            .map(|received| received.values().copied().collect())
+            .unwrap_or_default())
+    }
+
+    /// aggregate nonce R and challenge c of every session in `sessions`.
+    pub fn challenges(
+        &self,
+        sessions: &[SessionId],
+    ) -> Result<Vec<(ProjectivePoint, Scalar)>, Error> {
+        sessions
+            .iter()
+            .map(|id| self.session(id)?.challenge())
+            .collect()
+    }
+
+    pub fn add_partial(
+        &mut self,
+        session: &SessionId,
+        partial: PartialSignature,
+    ) -> Result<(), Error> {
+        self.session_mut(session)?.add_partial(partial)
+    }
+
+    /// finalize every session in `sessions` that isn't yet and return all
+    /// signatures, in the order of `sessions`. stops at the first session
+    /// that can't be finalized, the ones before it stay finalized.
+    pub fn collect(&mut self, sessions: &[SessionId]) -> Result<Vec<SchnorrSignature>, Error> {
+        sessions
+            .iter()
+            .map(|id| {
+                let session = self.session_mut(id)?;
+                match session.phase() {
+                    SessionPhase::Finalized => Ok(session.signature().unwrap()),
+                    _ => session.finalize(),
+                }
+            })
+            .collect()
+    }
+
+    fn session_mut(&mut self, id: &SessionId) -> Result<&mut SigningSession, Error> {
+        self.sessions.get_mut(id).ok_or(Error::UnknownSession(*id))
+    }
+}
@@ -0,0 +1,271 @@
+#![allow(non_snake_case)]
+
+use crate::error::Error;
+use crate::hazmat::{eval_polynomial, random_polynomial_with_rng};
+use crate::limits::Limits;
+use crate::proofs::SchnorrProof;
+use crate::shamir::{EncryptedShare, decrypt_scalar, encrypt_scalar};
+use crate::threshold::Participant;
+use crate::vss::{calculate_commitment, validate_dealing, verify_share};
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use k256::elliptic_curve::rand_core::OsRng;
+use k256::{
+    ProjectivePoint, Scalar,
+    elliptic_curve::{Field, rand_core::CryptoRngCore},
+};
+
+//--------------------------------------------------------------------
+// Distributed key generation (Pedersen / Feldman)
+//--------------------------------------------------------------------
+//
+// Every participant i acts as a dealer for its own random polynomial
+//   fᵢ(z) = aᵢ₀ + aᵢ₁z + ... + aᵢ₍ₜ₋₁₎zᵗ⁻¹
+//
+// [ROUND 1] broadcast Cᵢₖ = aᵢₖ·G           (Feldman commitments)
+// [ROUND 2] send fᵢ(j) privately to every j ≠ i
+// [FINAL]   verify every fᵢ(j) against Cᵢ and sum them up
+//
+//   xⱼ = Σᵢ fᵢ(j)        (share of the implicit f = Σᵢ fᵢ)
+//   X  = Σᵢ Cᵢ₀          (group public key, nobody knows f(0))
+//   Cₖ = Σᵢ Cᵢₖ          (group commitments)
+//
+// round 1 also carries a proof of knowledge of aᵢ₀ for Cᵢ₀, otherwise the
+// last participant could pick Cᵢ₀ = x*G - Σ Cⱼ₀ and own the group key.
+//
+
+/// secret state a participant keeps between round 1 and finalize.
+#[derive(Debug, Clone)]
+pub struct Round1Secret {
+    pub id: u64,
+    pub threshold: usize,
+    pub num_shares: usize,
+    pub coefficients: Vec<Scalar>,
+}
+
+/// public round 1 message, broadcast to every other participant.
+#[derive(Debug, Clone)]
+pub struct Round1Package {
+    pub id: u64,
+    pub commitments: Vec<ProjectivePoint>,
+    /// proof of possession of aᵢ₀, the secret behind `commitments[0]`.
+    pub proof: SchnorrProof,
+}
+
+/// private round 2 message carrying fᵢ(j) from `sender` to `receiver`.
+#[derive(Debug, Clone, Copy)]
+pub struct Round2Package {
+    pub sender: u64,
+    pub receiver: u64,
+    pub share: Scalar,
+}
+
+impl Round2Package {
+    /// encrypt to the long-term key of the receiver, for sending over an
+    /// untrusted channel. see `shamir::encrypt_share_for`.
+    #[cfg(feature = "std")]
+    pub fn encrypt_for(&self, recipient: &ProjectivePoint) -> Result<EncryptedShare, Error> {
+        self.encrypt_for_with_rng(recipient, &mut OsRng)
+    }
+
+    /// like `encrypt_for`, drawing the ephemeral key from `rng`.
+    pub fn encrypt_for_with_rng(
+        &self,
+        recipient: &ProjectivePoint,
+        rng: &mut impl CryptoRngCore,
+    ) -> Result<EncryptedShare, Error> {
+        encrypt_scalar(recipient, self.sender, self.receiver, &self.share, rng)
+    }
+
+    /// decrypt a package with the receiver's long-term secret key.
+    /// `finalize` still checks the share against the sender's commitments.
+    pub fn decrypt(secret_key: &Scalar, encrypted: &EncryptedShare) -> Result<Self, Error> {
+        Ok(Self {
+            sender: encrypted.sender,
+            receiver: encrypted.receiver,
+            share: decrypt_scalar(secret_key, encrypted)?,
+        })
+    }
+}
+
+/// result of a successful ceremony from the point of view of one participant.
+#[derive(Debug, Clone)]
+pub struct DkgOutput {
+    pub participant: Participant,
+    pub public_key: ProjectivePoint,
+    pub commitments: Vec<ProjectivePoint>,
+}
+
+/// start the ceremony as participant `id` of a t-of-n group.
+/// the secret must be kept locally, the package is broadcast.
+#[cfg(feature = "std")]
+pub fn round1(id: u64, t: usize, n: usize) -> Result<(Round1Secret, Round1Package), Error> {
+    round1_with_limits(id, t, n, &Limits::default())
+}
+
+/// like `round1`, with explicit limits on the committee size.
+#[cfg(feature = "std")]
+pub fn round1_with_limits(
+    id: u64,
+    t: usize,
+    n: usize,
+    limits: &Limits,
+) -> Result<(Round1Secret, Round1Package), Error> {
+    round1_with_rng(id, t, n, limits, &mut OsRng)
+}
+
+/// like `round1_with_limits`, drawing the polynomial and the proof nonce from `rng`.
+pub fn round1_with_rng(
+    id: u64,
+    t: usize,
+    n: usize,
+    limits: &Limits,
+    rng: &mut impl CryptoRngCore,
+) -> Result<(Round1Secret, Round1Package), Error> {
+    limits.check(t, n)?;
+    if id == 0 || id > n as u64 {
+        return Err(Error::InvalidParticipantId { id, num_shares: n });
+    }
+
+    let secret = Scalar::random(&mut *rng);
+    let coefficients = random_polynomial_with_rng(secret, t, &mut *rng);
+    let commitments = coefficients
+        .iter()
+        .map(|c| calculate_commitment(*c))
+        .collect::<Vec<_>>();
+    let proof =
+        SchnorrProof::prove_with_rng(&coefficients[0], &commitments[0], &pop_context(id), rng);
+
+    let secret = Round1Secret {
+        id,
+        threshold: t,
+        num_shares: n,
+        coefficients,
+    };
+    let package = Round1Package {
+        id,
+        commitments,
+        proof,
+    };
+
+    Ok((secret, package))
+}
+
+/// check the round 1 packages of all other participants and
+/// compute the private share fᵢ(j) for each of them.
+pub fn round2(
+    secret: &Round1Secret,
+    round1_packages: &[Round1Package],
+) -> Result<Vec<Round2Package>, Error> {
+    let others = collect_round1_packages(secret, round1_packages)?;
+
+    let packages = others
+        .iter()
+        .map(|package| Round2Package {
+            sender: secret.id,
+            receiver: package.id,
+            share: eval_polynomial(&secret.coefficients, package.id),
+        })
+        .collect();
+
+    Ok(packages)
+}
+
+/// verify every share received in round 2 against the sender's commitments
+/// and derive the long-term share, the group public key and commitments.
+pub fn finalize(
+    secret: &Round1Secret,
+    round1_packages: &[Round1Package],
+    round2_packages: &[Round2Package],
+) -> Result<DkgOutput, Error> {
+    let others = collect_round1_packages(secret, round1_packages)?;
+
+    let mut x_i = eval_polynomial(&secret.coefficients, secret.id);
+    let mut commitments: Vec<ProjectivePoint> = secret
+        .coefficients
+        .iter()
+        .map(|c| calculate_commitment(*c))
+        .collect();
+
+    for package in &others {
+        let received: Vec<&Round2Package> = round2_packages
+            .iter()
+            .filter(|p| p.sender == package.id && p.receiver == secret.id)
+            .collect();
+        let share = match received.as_slice() {
+            [share] => share.share,
+            [] => {
+                return Err(Error::MissingPackage {
+                    round: 2,
+                    id: package.id,
+                });
+            }
+            _ => {
+                return Err(Error::DuplicatePackage {
+                    round: 2,
+                    id: package.id,
+                });
+            }
+        };
+
+        if !verify_share(secret.id, share, &package.commitments) {
+            return Err(Error::InvalidShare { id: package.id });
+        }
+
+        x_i += share;
+        for (acc, C_k) in commitments.iter_mut().zip(&package.commitments) {
+            *acc += C_k;
+        }
+    }
+
+    Ok(DkgOutput {
+        participant: Participant::from_secret(secret.id, x_i),
+        public_key: commitments[0],
+        commitments,
+    })
+}
+
+/// returns the packages of every participant except `secret.id`, ordered by id,
+/// after checking that each of them is present exactly once and well formed.
+fn collect_round1_packages<'a>(
+    secret: &Round1Secret,
+    round1_packages: &'a [Round1Package],
+) -> Result<Vec<&'a Round1Package>, Error> {
+    let mut others = Vec::with_capacity(secret.num_shares - 1);
+
+    for id in (1..=secret.num_shares as u64).filter(|id| *id != secret.id) {
+        let received: Vec<&Round1Package> = round1_packages.iter().filter(|p| p.id == id).collect();
+        let package = match received.as_slice() {
+            [package] => *package,
+            [] => return Err(Error::MissingPackage { round: 1, id }),
+            _ => return Err(Error::DuplicatePackage { round: 1, id }),
+        };
+
+        validate_dealing(id, &package.commitments, secret.threshold)?;
+        if !package
+            .proof
+            .verify(&package.commitments[0], &pop_context(id))
+        {
+            return Err(Error::InvalidProofOfPossession { id });
+        }
+        others.push(package);
+    }
+
+    if let Some(package) = round1_packages
+        .iter()
+        .find(|p| p.id == 0 || p.id > secret.num_shares as u64)
+    {
+        return Err(Error::UnexpectedPackage {
+            round: 1,
+            id: package.id,
+        });
+    }
+
+    Ok(others)
+}
+
+fn pop_context(id: u64) -> Vec<u8> {
+    let mut context = b"shamy/dkg/pop".to_vec();
+    context.extend_from_slice(&id.to_be_bytes());
+    context
+}
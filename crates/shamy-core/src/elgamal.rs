@@ -0,0 +1,128 @@
+#![allow(non_snake_case)]
+
+use crate::error::Error;
+use crate::hash::tagged_hasher;
+use crate::hazmat;
+use crate::threshold::Participant;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use k256::elliptic_curve::rand_core::OsRng;
+use k256::{
+    ProjectivePoint, Scalar,
+    elliptic_curve::{Field, rand_core::CryptoRngCore, sec1::ToEncodedPoint},
+};
+use sha2::Digest;
+
+const KEYSTREAM_TAG: &[u8] = b"shamy/elgamal/keystream/v1";
+const MAC_TAG: &[u8] = b"shamy/elgamal/mac/v1";
+
+//--------------------------------------------------------------------
+// Threshold ElGamal (hashed EC-ElGamal)
+//--------------------------------------------------------------------
+//
+// anyone encrypts to the group key X = x*G
+//
+//   r ←$ Zq
+//   C1   = r*G
+//   K    = r*X
+//   body = m ⊕ H(K || 0) || H(K || 1) || ...
+//   tag  = H(K || C1 || body)
+//
+// decryption without reconstructing x
+//
+//   D_i  = x_i*C1                          (decryption share)
+//   K    = Σ λ_i*D_i = x*r*G = r*X
+//
+// the tag catches a wrong K, i.e. a bad share or fewer than t of them.
+//
+// ⚠️ shares are not proven correct yet, a bad one is only noticed as a
+// failed tag and can't be blamed on its sender.
+//
+
+/// hashed EC-ElGamal ciphertext under the group key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ciphertext {
+    pub C1: ProjectivePoint,
+    pub body: Vec<u8>,
+    pub tag: [u8; 32],
+}
+
+/// encrypt `msg` to the group key `X`.
+#[cfg(feature = "std")]
+pub fn encrypt(X: &ProjectivePoint, msg: &[u8]) -> Ciphertext {
+    encrypt_with_rng(X, msg, &mut OsRng)
+}
+
+/// like `encrypt`, drawing the ephemeral key from `rng`.
+pub fn encrypt_with_rng(
+    X: &ProjectivePoint,
+    msg: &[u8],
+    rng: &mut impl CryptoRngCore,
+) -> Ciphertext {
+    let r = Scalar::random(rng);
+    let C1 = ProjectivePoint::GENERATOR * r;
+    let K = *X * r;
+
+    let body = apply_keystream(&K, msg);
+    let tag = mac(&K, &C1, &body);
+
+    Ciphertext { C1, body, tag }
+}
+
+/// decryption share D_i of one participant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecryptionShare {
+    pub id: u64,
+    pub D_i: ProjectivePoint,
+}
+
+/// D_i = x_i*C1.
+pub fn partial_decrypt(participant: &Participant, ciphertext: &Ciphertext) -> DecryptionShare {
+    DecryptionShare {
+        id: participant.id,
+        D_i: ciphertext.C1 * participant.x_i,
+    }
+}
+
+/// K = Σ λ_i*D_i over at least t shares, then recover the message.
+pub fn combine_decryptions(
+    ciphertext: &Ciphertext,
+    shares: &[DecryptionShare],
+) -> Result<Vec<u8>, Error> {
+    let ids = shares.iter().map(|s| s.id).collect::<Vec<_>>();
+    hazmat::validate_ids(&ids)?;
+
+    let K = shares.iter().fold(ProjectivePoint::IDENTITY, |acc, s| {
+        acc + s.D_i * hazmat::lagrange_coefficient(s.id, &ids)
+    });
+    if mac(&K, &ciphertext.C1, &ciphertext.body) != ciphertext.tag {
+        return Err(Error::DecryptionFailed);
+    }
+
+    Ok(apply_keystream(&K, &ciphertext.body))
+}
+
+fn apply_keystream(K: &ProjectivePoint, data: &[u8]) -> Vec<u8> {
+    data.chunks(32)
+        .enumerate()
+        .flat_map(|(counter, chunk)| {
+            let mut hasher = tagged_hasher(KEYSTREAM_TAG);
+            hasher.update(K.to_encoded_point(true).as_bytes());
+            hasher.update((counter as u64).to_be_bytes());
+            let block = hasher.finalize();
+            chunk
+                .iter()
+                .zip(block)
+                .map(|(byte, key)| byte ^ key)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn mac(K: &ProjectivePoint, C1: &ProjectivePoint, body: &[u8]) -> [u8; 32] {
+    let mut hasher = tagged_hasher(MAC_TAG);
+    hasher.update(K.to_encoded_point(true).as_bytes());
+    hasher.update(C1.to_encoded_point(true).as_bytes());
+    hasher.update(body);
+    hasher.finalize().into()
+}
@@ -0,0 +1,305 @@
+use crate::schnorr::SessionId;
+use crate::threshold::CulpritReport;
+use alloc::string::String;
+use core::fmt;
+
+/// errors returned by shamy.
+/// new variants may be added in minor releases, so match with a wildcard arm.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// the input is not valid hex.
+    InvalidHex(String),
+    /// the bytes do not encode a point on secp256k1.
+    InvalidPoint(String),
+    /// the point at infinity where a key, nonce or commitment is expected.
+    IdentityPoint,
+    /// a scalar encoding has the wrong length.
+    InvalidScalarLength(usize),
+    /// a scalar encoding is not reduced modulo the group order.
+    InvalidScalar,
+    /// n = 0, there is no one to give a share to.
+    NoParticipants,
+    /// t below 2, or below 1 with `Limits::allow_trivial_threshold`.
+    ThresholdTooSmall { threshold: usize, min: usize },
+    /// t > n, no quorum could ever sign.
+    ThresholdExceedsParticipants { threshold: usize, num_shares: usize },
+    /// n is above the configured `Limits::max_participants`.
+    TooManyParticipants { num_shares: usize, max: usize },
+    /// t is above the configured `Limits::max_threshold`.
+    ThresholdTooLarge { threshold: usize, max: usize },
+    /// a participant id is outside of 1..=n.
+    InvalidParticipantId { id: u64, num_shares: usize },
+    /// the same participant id appears more than once.
+    DuplicateParticipantId(u64),
+    /// an operation needs at least one share.
+    NoShares,
+    /// no message was received from a participant in the given round.
+    MissingPackage { round: u8, id: u64 },
+    /// more than one message was received from a participant in the given round.
+    DuplicatePackage { round: u8, id: u64 },
+    /// a message was received from a participant that is not part of the group.
+    UnexpectedPackage { round: u8, id: u64 },
+    /// a participant sent the wrong number of commitments.
+    InvalidCommitmentCount {
+        id: u64,
+        expected: usize,
+        actual: usize,
+    },
+    /// a commitment vector does not have one point per coefficient.
+    CommitmentCount { expected: usize, actual: usize },
+    /// commitment C_index is the point at infinity.
+    IdentityCommitment { index: usize },
+    /// a share sent by a participant does not match its commitments.
+    InvalidShare { id: u64 },
+    /// the group public key is not the first commitment C₀.
+    PublicKeyMismatch,
+    /// a participant's public share Xᵢ is not xᵢ*G.
+    PublicShareMismatch { id: u64 },
+    /// a protocol step was attempted in the wrong state.
+    OutOfOrder { expected: String, actual: String },
+    /// the combined signature does not verify.
+    InvalidSignature,
+    /// a partial signature does not verify against the signer's public share.
+    InvalidPartialSignature { id: u64 },
+    /// a partial signature without the nonce point or public share to verify it.
+    MissingPartialCommitments { id: u64 },
+    /// an aggregation proof is inconsistent with its signature.
+    InvalidAggregationProof(String),
+    /// a key aggregation needs at least one public key.
+    NoPublicKeys,
+    /// the key is not part of the signing group.
+    UnknownPublicKey,
+    /// the wrong number of partial signatures was given.
+    InvalidPartialCount { expected: usize, actual: usize },
+    /// a partial VRF proof does not verify against the participant's public share.
+    InvalidVrfProof { id: u64 },
+    /// the decryption shares don't recover the ciphertext's key.
+    DecryptionFailed,
+    /// the child index is hardened or yields an invalid key, try the next one.
+    InvalidChildIndex(u32),
+    /// a key tweak is out of range or cancels out the key.
+    InvalidTweak,
+    /// some signers sent partials that don't verify.
+    Misbehavior(CulpritReport),
+    /// a participant's proof of possession is missing or doesn't verify.
+    InvalidProofOfPossession { id: u64 },
+    /// a text or binary encoding is malformed or fails its checksum.
+    InvalidEncoding(String),
+    /// fewer shares than the threshold recorded in their backups.
+    NotEnoughShares { threshold: usize, actual: usize },
+    /// a signer set smaller than the threshold.
+    NotEnoughSigners { threshold: usize, actual: usize },
+    /// share backups of different keys or thresholds were mixed.
+    BackupMismatch,
+    /// a half-aggregate was given the wrong number of messages.
+    InvalidAggregateLength { expected: usize, actual: usize },
+    /// a test vector field doesn't match its recomputed value.
+    VectorMismatch(String),
+    /// the nonce pair was not generated by this pool or its commitment differs.
+    UnknownNonce { id: u64, index: u64 },
+    /// the nonce pair was already used for a signature.
+    NonceReused { id: u64, index: u64 },
+    /// the participant already signed with this nonce, for the same or another challenge.
+    NonceAlreadyUsed { id: u64, same_challenge: bool },
+    /// reading or writing persisted state failed.
+    Io(String),
+    /// a transcript or session id needs the v1 or merlin challenge, the others can't carry it.
+    UnsupportedChallenge,
+    /// the transcript entry doesn't chain to the ones before it.
+    TranscriptMismatch { index: usize },
+    /// misbehavior evidence whose message satisfies the relation it is blamed for.
+    InvalidEvidence { id: u64 },
+    /// no session with this id is running.
+    UnknownSession(SessionId),
+    /// a session with this id is already running.
+    DuplicateSession(SessionId),
+    /// no identity key is registered for the participant.
+    UnknownIdentity(u64),
+    /// a round message whose identity signature doesn't verify.
+    InvalidMessageSignature { round: u8, id: u64 },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidHex(e) => write!(f, "Invalid hex string: {}", e),
+            Error::InvalidPoint(e) => write!(f, "Invalid encoded point: {}", e),
+            Error::IdentityPoint => write!(f, "Unexpected point at infinity"),
+            Error::InvalidScalarLength(len) => {
+                write!(f, "Invalid scalar length: expected 32 bytes, got {}", len)
+            }
+            Error::InvalidScalar => write!(f, "Invalid scalar"),
+            Error::NoParticipants => {
+                write!(
+                    f,
+                    "No participants: the number of shares must be at least 1"
+                )
+            }
+            Error::ThresholdTooSmall {
+                threshold: 1,
+                min: 2,
+            } => write!(
+                f,
+                "Threshold 1 would let every share sign alone, 1-of-n must be allowed explicitly"
+            ),
+            Error::ThresholdTooSmall { threshold, min } => {
+                write!(f, "Threshold {} is below the minimum of {}", threshold, min)
+            }
+            Error::ThresholdExceedsParticipants {
+                threshold,
+                num_shares,
+            } => write!(
+                f,
+                "Threshold {} exceeds the {} participants, no quorum could ever sign",
+                threshold, num_shares
+            ),
+            Error::TooManyParticipants { num_shares, max } => write!(
+                f,
+                "Too many participants: {} exceeds the limit of {}",
+                num_shares, max
+            ),
+            Error::ThresholdTooLarge { threshold, max } => {
+                write!(f, "Threshold {} exceeds the limit of {}", threshold, max)
+            }
+            Error::InvalidParticipantId { id, num_shares } => write!(
+                f,
+                "Participant id {} is out of range 1..={}",
+                id, num_shares
+            ),
+            Error::DuplicateParticipantId(id) => {
+                write!(f, "Participant id {} appears more than once", id)
+            }
+            Error::NoShares => write!(f, "No shares given"),
+            Error::MissingPackage { round, id } => {
+                write!(f, "Missing round {} package from {}", round, id)
+            }
+            Error::DuplicatePackage { round, id } => {
+                write!(f, "Duplicate round {} package from {}", round, id)
+            }
+            Error::UnexpectedPackage { round, id } => {
+                write!(f, "Unexpected round {} package from {}", round, id)
+            }
+            Error::CommitmentCount { expected, actual } => {
+                write!(f, "Expected {} commitments, got {}", expected, actual)
+            }
+            Error::IdentityCommitment { index } => {
+                write!(f, "Commitment C_{} is the point at infinity", index)
+            }
+            Error::InvalidCommitmentCount {
+                id,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Participant {} sent {} commitments, expected {}",
+                id, actual, expected
+            ),
+            Error::InvalidShare { id } => write!(
+                f,
+                "Share from participant {} does not match its commitments",
+                id
+            ),
+            Error::PublicKeyMismatch => {
+                write!(f, "Public key is not the first commitment")
+            }
+            Error::PublicShareMismatch { id } => {
+                write!(
+                    f,
+                    "Public share of participant {} does not match its share",
+                    id
+                )
+            }
+            Error::OutOfOrder { expected, actual } => write!(
+                f,
+                "Out of order: expected state {}, but in state {}",
+                expected, actual
+            ),
+            Error::InvalidSignature => write!(f, "Combined signature is invalid"),
+            Error::InvalidPartialSignature { id } => {
+                write!(f, "Partial signature of participant {} is invalid", id)
+            }
+            Error::MissingPartialCommitments { id } => write!(
+                f,
+                "Partial signature of participant {} carries no nonce point or public share",
+                id
+            ),
+            Error::InvalidAggregationProof(e) => write!(f, "Invalid aggregation proof: {}", e),
+            Error::NoPublicKeys => write!(f, "No public keys to aggregate"),
+            Error::UnknownPublicKey => write!(f, "Public key is not part of the signing group"),
+            Error::InvalidPartialCount { expected, actual } => write!(
+                f,
+                "Expected {} partial signatures, got {}",
+                expected, actual
+            ),
+            Error::InvalidVrfProof { id } => {
+                write!(f, "VRF proof of participant {} is invalid", id)
+            }
+            Error::DecryptionFailed => write!(f, "Decryption failed"),
+            Error::InvalidChildIndex(index) => write!(f, "Can't derive child {}", index),
+            Error::InvalidTweak => write!(f, "Invalid key tweak"),
+            Error::Misbehavior(report) => {
+                write!(f, "Misbehaving participants: {:?}", report.ids())
+            }
+            Error::InvalidProofOfPossession { id } => {
+                write!(f, "Proof of possession of participant {} is invalid", id)
+            }
+            Error::InvalidEncoding(reason) => write!(f, "Invalid encoding: {}", reason),
+            Error::NotEnoughShares { threshold, actual } => {
+                write!(f, "Need {} shares, got {}", threshold, actual)
+            }
+            Error::NotEnoughSigners { threshold, actual } => {
+                write!(f, "Need {} signers, got {}", threshold, actual)
+            }
+            Error::BackupMismatch => write!(f, "Share backups belong to different keys"),
+            Error::InvalidAggregateLength { expected, actual } => write!(
+                f,
+                "Half-aggregate holds {} signatures, got {} messages",
+                expected, actual
+            ),
+            Error::VectorMismatch(field) => {
+                write!(f, "Test vector field {} does not match", field)
+            }
+            Error::UnknownNonce { id, index } => {
+                write!(f, "Nonce {} of participant {} is unknown", index, id)
+            }
+            Error::NonceReused { id, index } => {
+                write!(f, "Nonce {} of participant {} was already used", index, id)
+            }
+            Error::NonceAlreadyUsed { id, same_challenge } => write!(
+                f,
+                "Participant {} already signed {} challenge with this nonce",
+                id,
+                match same_challenge {
+                    true => "this",
+                    false => "another",
+                }
+            ),
+            Error::Io(e) => write!(f, "I/O error: {}", e),
+            Error::UnsupportedChallenge => {
+                write!(
+                    f,
+                    "Only the v1 or merlin challenge can be bound to a transcript or session"
+                )
+            }
+            Error::TranscriptMismatch { index } => {
+                write!(f, "Transcript entry {} doesn't match its hash", index)
+            }
+            Error::InvalidEvidence { id } => {
+                write!(f, "Evidence doesn't show misbehavior of participant {}", id)
+            }
+            Error::UnknownSession(id) => write!(f, "Unknown session {}", id.to_hex()),
+            Error::DuplicateSession(id) => write!(f, "Session {} already exists", id.to_hex()),
+            Error::UnknownIdentity(id) => {
+                write!(f, "No identity key registered for participant {}", id)
+            }
+            Error::InvalidMessageSignature { round, id } => write!(
+                f,
+                "Round {} message from {} has an invalid identity signature",
+                round, id
+            ),
+        }
+    }
+}
+
+impl core::error::Error for Error {}
@@ -0,0 +1,281 @@
+#![allow(non_snake_case)]
+
+use crate::error::Error;
+use crate::schnorr::SigningConfig;
+use crate::threshold::PartialSignature;
+use crate::util::{PointEncoding, bytes_to_pp};
+use crate::vss::verify_share;
+use alloc::{format, string::ToString, vec::Vec};
+use k256::{FieldBytes, ProjectivePoint, Scalar, elliptic_curve::PrimeField};
+
+const EVIDENCE_MAGIC: &[u8; 4] = b"SHEV";
+const EVIDENCE_VERSION: u8 = 1;
+const POINT_LEN: usize = 33;
+
+//--------------------------------------------------------------------
+// Misbehavior evidence
+//--------------------------------------------------------------------
+//
+// a culprit report names who broke a ceremony. evidence also carries the
+// message they sent and the values it was checked against, so anyone can
+// recheck the broken relation without the session:
+//
+//   partial:  s_i*G != R_i + c*X_i
+//   share:    s_ij*G != Σₖ C_ik·jᵏ
+//
+// `verify` succeeds only if the relation really fails, so honest
+// participants can't be blamed with made-up values that happen to hold.
+//
+// binary encoding:
+//
+//   "SHEV" || version || id || kind || fields
+//   partial (kind 1): R || R_i || X_i || c || s_i
+//   share   (kind 2): receiver || s_ij || count || C_i0 .. C_i(t-1)
+//
+// ids are 8-byte and count 4-byte big-endian, points compressed.
+//
+// ⚠️ the values are only as trustworthy as their source. R_i, X_i and the
+//    commitments must come from the broadcast rounds and the keygen output,
+//    and without signed messages the accused can deny having sent s_i.
+// ⚠️ share evidence reveals s_ij, the share the dealer sent to j. it is
+//    invalid, but publish it only to parties that may see it.
+//
+
+/// the message a participant sent and what it should have satisfied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::large_enum_variant)]
+#[non_exhaustive]
+pub enum OffendingMessage {
+    /// partial signature with s_i*G != R_i + c*X_i.
+    Partial {
+        /// aggregate nonce of the session, to recheck c.
+        R: ProjectivePoint,
+        R_i: ProjectivePoint,
+        X_i: ProjectivePoint,
+        c: Scalar,
+        s_i: Scalar,
+    },
+    /// dealer share with s_ij*G != Σₖ C_ik·jᵏ.
+    Share {
+        receiver: u64,
+        share: Scalar,
+        commitments: Vec<ProjectivePoint>,
+    },
+}
+
+/// self-contained proof that participant `id` sent an invalid message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MisbehaviorEvidence {
+    pub id: u64,
+    pub message: OffendingMessage,
+}
+
+impl MisbehaviorEvidence {
+    /// evidence against the sender of `partial`.
+    pub fn partial(
+        partial: &PartialSignature,
+        R: ProjectivePoint,
+        R_i: ProjectivePoint,
+        X_i: ProjectivePoint,
+        c: Scalar,
+    ) -> Self {
+        Self {
+            id: partial.id,
+            message: OffendingMessage::Partial {
+                R,
+                R_i,
+                X_i,
+                c,
+                s_i: partial.s_i,
+            },
+        }
+    }
+
+    /// evidence against `dealer` for the share it sent to `receiver`.
+    pub fn share(
+        dealer: u64,
+        receiver: u64,
+        share: Scalar,
+        commitments: &[ProjectivePoint],
+    ) -> Self {
+        Self {
+            id: dealer,
+            message: OffendingMessage::Share {
+                receiver,
+                share,
+                commitments: commitments.to_vec(),
+            },
+        }
+    }
+
+    /// the relation the message should have satisfied.
+    pub fn relation(&self) -> &'static str {
+        match self.message {
+            OffendingMessage::Partial { .. } => "s_i*G == R_i + c*X_i",
+            OffendingMessage::Share { .. } => "s_ij*G == sum(C_ik * j^k)",
+        }
+    }
+
+    /// whether the relation fails, i.e. the evidence shows misbehavior.
+    /// fails with `Error::InvalidEvidence` if the message was valid.
+    pub fn verify(&self) -> Result<(), Error> {
+        let holds = match &self.message {
+            OffendingMessage::Partial {
+                R_i, X_i, c, s_i, ..
+            } => ProjectivePoint::GENERATOR * s_i == *R_i + *X_i * c,
+            OffendingMessage::Share {
+                receiver,
+                share,
+                commitments,
+            } => commitments.is_empty() || verify_share(*receiver, *share, commitments),
+        };
+
+        match holds {
+            true => Err(Error::InvalidEvidence { id: self.id }),
+            false => Ok(()),
+        }
+    }
+
+    /// whether the c of partial evidence is the session challenge for
+    /// `message` under `public_key`. share evidence has no challenge.
+    pub fn check_challenge(
+        &self,
+        public_key: &ProjectivePoint,
+        message: &[u8],
+        config: &SigningConfig,
+    ) -> bool {
+        match &self.message {
+            OffendingMessage::Partial { R, c, .. } => {
+                config.challenge(R, public_key, message) == *c
+            }
+            OffendingMessage::Share { .. } => false,
+        }
+    }
+
+    /// versioned binary encoding, see the layout above.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let point = |P: &ProjectivePoint| PointEncoding::Compressed.encode(P);
+        let mut out = EVIDENCE_MAGIC.to_vec();
+        out.push(EVIDENCE_VERSION);
+        out.extend(self.id.to_be_bytes());
+        match &self.message {
+            OffendingMessage::Partial {
+                R,
+                R_i,
+                X_i,
+                c,
+                s_i,
+            } => {
+                out.push(1);
+                for P in [R, R_i, X_i] {
+                    out.extend_from_slice(point(P).as_bytes());
+                }
+                out.extend(c.to_bytes());
+                out.extend(s_i.to_bytes());
+            }
+            OffendingMessage::Share {
+                receiver,
+                share,
+                commitments,
+            } => {
+                out.push(2);
+                out.extend(receiver.to_be_bytes());
+                out.extend(share.to_bytes());
+                out.extend((commitments.len() as u32).to_be_bytes());
+                for C in commitments {
+                    out.extend_from_slice(point(C).as_bytes());
+                }
+            }
+        }
+
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let invalid = |reason: &str| Error::InvalidEncoding(reason.to_string());
+        let rest = bytes
+            .strip_prefix(EVIDENCE_MAGIC)
+            .ok_or(invalid("not misbehavior evidence"))?;
+        let (&version, mut rest) = rest.split_first().ok_or(invalid("truncated"))?;
+        if version != EVIDENCE_VERSION {
+            return Err(Error::InvalidEncoding(format!(
+                "unsupported evidence version {version}"
+            )));
+        }
+
+        let id = u64::from_be_bytes(take(&mut rest, 8)?.try_into().unwrap());
+        let message = match take(&mut rest, 1)?[0] {
+            1 => OffendingMessage::Partial {
+                R: bytes_to_pp(take(&mut rest, POINT_LEN)?)?,
+                R_i: bytes_to_pp(take(&mut rest, POINT_LEN)?)?,
+                X_i: bytes_to_pp(take(&mut rest, POINT_LEN)?)?,
+                c: scalar(take(&mut rest, 32)?)?,
+                s_i: scalar(take(&mut rest, 32)?)?,
+            },
+            2 => {
+                let receiver = u64::from_be_bytes(take(&mut rest, 8)?.try_into().unwrap());
+                let share = scalar(take(&mut rest, 32)?)?;
+                let count = u32::from_be_bytes(take(&mut rest, 4)?.try_into().unwrap()) as usize;
+                let commitments = take(&mut rest, count.saturating_mul(POINT_LEN))?
+                    .chunks(POINT_LEN)
+                    .map(bytes_to_pp)
+                    .collect::<Result<Vec<_>, _>>()?;
+                OffendingMessage::Share {
+                    receiver,
+                    share,
+                    commitments,
+                }
+            }
+            kind => {
+                return Err(Error::InvalidEncoding(format!(
+                    "unknown evidence kind {kind}"
+                )));
+            }
+        };
+        if !rest.is_empty() {
+            return Err(invalid("trailing data"));
+        }
+
+        Ok(Self { id, message })
+    }
+}
+
+/// evidence against every signer whose partial fails s_i*G == R_i + c*X_i.
+/// partials without a nonce or public share are skipped, there is nothing
+/// to check them against.
+pub fn partial_evidence(
+    partials: &[PartialSignature],
+    nonces: &[(u64, ProjectivePoint)],
+    public_shares: &[(u64, ProjectivePoint)],
+    R: ProjectivePoint,
+    c: Scalar,
+) -> Vec<MisbehaviorEvidence> {
+    let find = |points: &[(u64, ProjectivePoint)], id: u64| {
+        points.iter().find(|(i, _)| *i == id).map(|(_, P)| *P)
+    };
+
+    partials
+        .iter()
+        .filter_map(|partial| {
+            let R_i = find(nonces, partial.id)?;
+            let X_i = find(public_shares, partial.id)?;
+            let evidence = MisbehaviorEvidence::partial(partial, R, R_i, X_i, c);
+            evidence.verify().is_ok().then_some(evidence)
+        })
+        .collect()
+}
+
+fn take<'a>(rest: &mut &'a [u8], len: usize) -> Result<&'a [u8], Error> {
+    if rest.len() < len {
+        return Err(Error::InvalidEncoding("truncated".to_string()));
+    }
+    let (head, tail) = rest.split_at(len);
+    *rest = tail;
+    Ok(head)
+}
+
+fn scalar(bytes: &[u8]) -> Result<Scalar, Error> {
+    Scalar::from_repr(*FieldBytes::from_slice(bytes))
+        .into_option()
+        .ok_or(Error::InvalidScalar)
+}
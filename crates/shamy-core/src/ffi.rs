@@ -0,0 +1,323 @@
+//! C ABI for embedding the threshold signer, see `include/shamy.h`.
+//!
+//! all values are passed as fixed size byte buffers owned by the caller:
+//!
+//!   points      33 byte compressed SEC1
+//!   scalars     32 byte big endian
+//!   signatures  65 bytes, R || s
+//!
+//! framing: `app_id` and `purpose` are NUL terminated UTF-8 strings. pass
+//! NULL for both to hash the raw message (see `SigningConfig::unframed`).
+//!
+//! ⚠️ ignore the output buffers unless `SHAMY_STATUS_OK` is returned.
+
+#![allow(non_snake_case)]
+#![allow(clippy::missing_safety_doc)]
+
+use crate::error::Error;
+use crate::limits::Limits;
+use crate::schnorr::{self, SchnorrSignature, SigningConfig};
+use crate::shamir::shamir_keygen_with_limits;
+use crate::threshold::{self, PartialSignature, Participant, SignerSet};
+use crate::util::bytes_to_pp;
+use k256::{
+    ProjectivePoint, Scalar,
+    elliptic_curve::{PrimeField, sec1::ToEncodedPoint},
+};
+use std::ffi::{CStr, c_char};
+use std::slice;
+
+pub const SHAMY_POINT_LEN: usize = 33;
+pub const SHAMY_SCALAR_LEN: usize = 32;
+pub const SHAMY_SIGNATURE_LEN: usize = SHAMY_POINT_LEN + SHAMY_SCALAR_LEN;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShamyStatus {
+    Ok = 0,
+    /// a required pointer is NULL.
+    NullPointer = 1,
+    /// a length, threshold, id or string is invalid.
+    InvalidArgument = 2,
+    /// bytes do not encode a point on secp256k1.
+    InvalidPoint = 3,
+    /// bytes do not encode a scalar modulo the group order.
+    InvalidScalar = 4,
+    /// the signature does not verify.
+    InvalidSignature = 5,
+}
+
+impl From<Error> for ShamyStatus {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::InvalidHex(_) | Error::InvalidPoint(_) | Error::IdentityPoint => {
+                ShamyStatus::InvalidPoint
+            }
+            Error::InvalidScalarLength(_) | Error::InvalidScalar => ShamyStatus::InvalidScalar,
+            Error::InvalidSignature | Error::InvalidPartialSignature { .. } => {
+                ShamyStatus::InvalidSignature
+            }
+            _ => ShamyStatus::InvalidArgument,
+        }
+    }
+}
+
+/// static description of a status code.
+#[unsafe(no_mangle)]
+pub extern "C" fn shamy_status_message(status: ShamyStatus) -> *const c_char {
+    let message: &'static CStr = match status {
+        ShamyStatus::Ok => c"ok",
+        ShamyStatus::NullPointer => c"null pointer",
+        ShamyStatus::InvalidArgument => c"invalid argument",
+        ShamyStatus::InvalidPoint => c"invalid point",
+        ShamyStatus::InvalidScalar => c"invalid scalar",
+        ShamyStatus::InvalidSignature => c"invalid signature",
+    };
+
+    message.as_ptr()
+}
+
+/// t-of-n trusted dealer keygen.
+/// writes X to `public_key` (33 bytes), x_1..x_n to `shares` (n * 32 bytes)
+/// and X_1..X_n to `public_shares` (n * 33 bytes).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn shamy_keygen(
+    n: usize,
+    t: usize,
+    public_key: *mut u8,
+    shares: *mut u8,
+    public_shares: *mut u8,
+) -> ShamyStatus {
+    status(|| {
+        let output = shamir_keygen_with_limits(n, t, &Limits::default())?;
+        let public_key = unsafe { output_buf(public_key, SHAMY_POINT_LEN)? };
+        let shares = unsafe { output_buf(shares, n * SHAMY_SCALAR_LEN)? };
+        let public_shares = unsafe { output_buf(public_shares, n * SHAMY_POINT_LEN)? };
+
+        public_key.copy_from_slice(&point_to_bytes(&output.public_key)?);
+        for (i, participant) in output.participants.iter().enumerate() {
+            shares[i * SHAMY_SCALAR_LEN..(i + 1) * SHAMY_SCALAR_LEN]
+                .copy_from_slice(&participant.x_i.to_bytes());
+            public_shares[i * SHAMY_POINT_LEN..(i + 1) * SHAMY_POINT_LEN]
+                .copy_from_slice(&point_to_bytes(&participant.X_i)?);
+        }
+
+        Ok(())
+    })
+}
+
+/// fresh nonce r_i (32 bytes) and its commitment R_i = r_i*G (33 bytes).
+/// ⚠️ use the secret for exactly one partial signature.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn shamy_generate_nonce(secret: *mut u8, commitment: *mut u8) -> ShamyStatus {
+    status(|| {
+        let secret = unsafe { output_buf(secret, SHAMY_SCALAR_LEN)? };
+        let commitment = unsafe { output_buf(commitment, SHAMY_POINT_LEN)? };
+
+        let r = schnorr::generate_nonce();
+        secret.copy_from_slice(&r.to_bytes());
+        commitment.copy_from_slice(&point_to_bytes(&schnorr::compute_nonce_point(&r))?);
+
+        Ok(())
+    })
+}
+
+/// R = Σ λ_i*R_i over `len` signers, `nonces` holds len * 33 bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn shamy_aggregate_nonce(
+    ids: *const u64,
+    nonces: *const u8,
+    len: usize,
+    nonce: *mut u8,
+) -> ShamyStatus {
+    status(|| {
+        let ids = unsafe { input_slice(ids, len)? };
+        let nonces = unsafe { input_slice(nonces, len * SHAMY_POINT_LEN)? }
+            .chunks(SHAMY_POINT_LEN)
+            .zip(ids)
+            .map(|(R_i, id)| Ok((*id, point_from_bytes(R_i)?)))
+            .collect::<Result<Vec<_>, ShamyStatus>>()?;
+        let nonce = unsafe { output_buf(nonce, SHAMY_POINT_LEN)? };
+
+        let signers = SignerSet::new(ids, ids.len())?;
+        nonce.copy_from_slice(&point_to_bytes(&threshold::aggregate_nonce_with_signers(
+            &nonces, &signers,
+        )?)?);
+
+        Ok(())
+    })
+}
+
+/// c = H(R, X, frame(m)), written to `challenge` (32 bytes).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn shamy_challenge(
+    nonce: *const u8,
+    public_key: *const u8,
+    message: *const u8,
+    message_len: usize,
+    app_id: *const c_char,
+    purpose: *const c_char,
+    challenge: *mut u8,
+) -> ShamyStatus {
+    status(|| {
+        let R = point_from_bytes(unsafe { input_slice(nonce, SHAMY_POINT_LEN)? })?;
+        let X = point_from_bytes(unsafe { input_slice(public_key, SHAMY_POINT_LEN)? })?;
+        let message = unsafe { input_slice(message, message_len)? };
+        let config = unsafe { config(app_id, purpose)? };
+        let challenge = unsafe { output_buf(challenge, SHAMY_SCALAR_LEN)? };
+
+        challenge.copy_from_slice(&config.challenge(&R, &X, message).to_bytes());
+
+        Ok(())
+    })
+}
+
+/// s_i = r_i + c*x_i, written to `partial` (32 bytes).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn shamy_partial_sign(
+    id: u64,
+    share: *const u8,
+    nonce: *const u8,
+    challenge: *const u8,
+    partial: *mut u8,
+) -> ShamyStatus {
+    status(|| {
+        let x_i = scalar_from_bytes(unsafe { input_slice(share, SHAMY_SCALAR_LEN)? })?;
+        let r_i = scalar_from_bytes(unsafe { input_slice(nonce, SHAMY_SCALAR_LEN)? })?;
+        let c = scalar_from_bytes(unsafe { input_slice(challenge, SHAMY_SCALAR_LEN)? })?;
+        let partial = unsafe { output_buf(partial, SHAMY_SCALAR_LEN)? };
+
+        let participant = Participant::from_secret(id, x_i);
+        partial.copy_from_slice(
+            &threshold::partial_sign(&participant, &r_i, &c)
+                .s_i
+                .to_bytes(),
+        );
+
+        Ok(())
+    })
+}
+
+/// combine `len` partial signatures (len * 32 bytes) under the aggregate
+/// nonce R into a signature (65 bytes).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn shamy_aggregate(
+    ids: *const u64,
+    partials: *const u8,
+    len: usize,
+    nonce: *const u8,
+    signature: *mut u8,
+) -> ShamyStatus {
+    status(|| {
+        let ids = unsafe { input_slice(ids, len)? };
+        let partials = unsafe { input_slice(partials, len * SHAMY_SCALAR_LEN)? }
+            .chunks(SHAMY_SCALAR_LEN)
+            .zip(ids)
+            .map(|(s_i, id)| Ok(PartialSignature::new(*id, scalar_from_bytes(s_i)?)))
+            .collect::<Result<Vec<_>, ShamyStatus>>()?;
+        let R = point_from_bytes(unsafe { input_slice(nonce, SHAMY_POINT_LEN)? })?;
+        let signature = unsafe { output_buf(signature, SHAMY_SIGNATURE_LEN)? };
+
+        let signers = SignerSet::new(ids, ids.len())?;
+        let combined = threshold::finalize_signature_with_signers(&partials, R, &signers)?;
+        signature[..SHAMY_POINT_LEN].copy_from_slice(&point_to_bytes(&combined.R)?);
+        signature[SHAMY_POINT_LEN..].copy_from_slice(&combined.s.to_bytes());
+
+        Ok(())
+    })
+}
+
+/// `SHAMY_STATUS_OK` if the signature (65 bytes) is valid for the message and
+/// public key, `SHAMY_STATUS_INVALID_SIGNATURE` otherwise.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn shamy_verify(
+    message: *const u8,
+    message_len: usize,
+    signature: *const u8,
+    public_key: *const u8,
+    app_id: *const c_char,
+    purpose: *const c_char,
+) -> ShamyStatus {
+    status(|| {
+        let message = unsafe { input_slice(message, message_len)? };
+        let signature = unsafe { input_slice(signature, SHAMY_SIGNATURE_LEN)? };
+        let signature = SchnorrSignature::from_parts(
+            &signature[..SHAMY_POINT_LEN],
+            &signature[SHAMY_POINT_LEN..],
+        )?;
+        let X = point_from_bytes(unsafe { input_slice(public_key, SHAMY_POINT_LEN)? })?;
+        let config = unsafe { config(app_id, purpose)? };
+
+        if !signature.verify_with_config(message, &X, &config) {
+            return Err(ShamyStatus::InvalidSignature);
+        }
+
+        Ok(())
+    })
+}
+
+fn status(f: impl FnOnce() -> Result<(), ShamyStatus>) -> ShamyStatus {
+    match f() {
+        Ok(()) => ShamyStatus::Ok,
+        Err(status) => status,
+    }
+}
+
+unsafe fn input_slice<'a, T>(ptr: *const T, len: usize) -> Result<&'a [T], ShamyStatus> {
+    if len == 0 {
+        return Ok(&[]);
+    }
+    if ptr.is_null() {
+        return Err(ShamyStatus::NullPointer);
+    }
+
+    Ok(unsafe { slice::from_raw_parts(ptr, len) })
+}
+
+unsafe fn output_buf<'a>(ptr: *mut u8, len: usize) -> Result<&'a mut [u8], ShamyStatus> {
+    if ptr.is_null() {
+        return Err(ShamyStatus::NullPointer);
+    }
+
+    Ok(unsafe { slice::from_raw_parts_mut(ptr, len) })
+}
+
+unsafe fn config(
+    app_id: *const c_char,
+    purpose: *const c_char,
+) -> Result<SigningConfig, ShamyStatus> {
+    let string = |ptr: *const c_char| {
+        unsafe { CStr::from_ptr(ptr) }
+            .to_str()
+            .map_err(|_| ShamyStatus::InvalidArgument)
+    };
+
+    match (app_id.is_null(), purpose.is_null()) {
+        (true, true) => Ok(SigningConfig::unframed()),
+        (false, false) => Ok(SigningConfig::new(string(app_id)?, string(purpose)?)),
+        _ => Err(ShamyStatus::NullPointer),
+    }
+}
+
+/// the identity has no 33 byte encoding, e.g. an aggregate nonce of
+/// crafted commitments that cancel out.
+fn point_to_bytes(point: &ProjectivePoint) -> Result<[u8; SHAMY_POINT_LEN], ShamyStatus> {
+    let encoded = point.to_affine().to_encoded_point(true);
+    encoded
+        .as_bytes()
+        .try_into()
+        .map_err(|_| ShamyStatus::InvalidPoint)
+}
+
+fn point_from_bytes(bytes: &[u8]) -> Result<ProjectivePoint, ShamyStatus> {
+    Ok(bytes_to_pp(bytes)?)
+}
+
+fn scalar_from_bytes(bytes: &[u8]) -> Result<Scalar, ShamyStatus> {
+    let mut repr = [0u8; SHAMY_SCALAR_LEN];
+    repr.copy_from_slice(bytes);
+
+    Scalar::from_repr(repr.into())
+        .into_option()
+        .ok_or(ShamyStatus::InvalidScalar)
+}
@@ -0,0 +1,295 @@
+#![allow(non_snake_case)]
+
+use crate::error::Error;
+#[cfg(feature = "merlin")]
+use crate::hash::transcript_scalar;
+use crate::hash::{finalize_scalar, scalar_hasher};
+#[cfg(feature = "merlin")]
+use crate::schnorr::ChallengeMode;
+use crate::schnorr::{SigningConfig, compute_nonce_point, generate_nonce_with_rng};
+use crate::threshold::{
+    PartialSignature, Participant, SignerSet, aggregate_nonce_with_signers, partial_sign,
+};
+use crate::util::PointEncoding;
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::fmt;
+#[cfg(feature = "std")]
+use k256::elliptic_curve::rand_core::OsRng;
+use k256::{ProjectivePoint, Scalar, elliptic_curve::rand_core::CryptoRngCore};
+use sha2::Digest;
+
+const BINDING_TAG: &[u8] = b"shamy/frost/binding/v1";
+#[cfg(feature = "merlin")]
+const MERLIN_BINDING_LABEL: &[u8] = b"shamy/frost/binding/merlin/v1";
+
+//--------------------------------------------------------------------
+// Nonce preprocessing (FROST)
+//--------------------------------------------------------------------
+//
+// offline, every signer fills a pool with nonce pairs and publishes
+//
+//   (id, index, D = d*G, E = e*G)
+//
+// online, the coordinator picks one unused commitment per signer for a
+// message and sends the list B to every signer:
+//
+//   ρ_i = hash_to_scalar(tag, X || len(m) || m || B || i)    binding factor
+//   R_i = D_i + ρ_i*E_i,    r_i = d_i + ρ_i*e_i
+//   R   = Σ λ_i*R_i,        c = config.challenge(R, X, m)
+//   s_i = r_i + c*x_i                                        as in threshold.rs
+//
+// ρ_i binds every nonce to the message and to the whole signer set, so a
+// coordinator choosing the message after seeing the commitments can't
+// combine sessions into a forgery (ROS), unlike single pre-committed nonces.
+//
+// under `SigningConfig::merlin_challenge` ρ_i is drawn from a merlin
+// transcript over the same fields (plus the context) instead, see
+// `binding_factor_with_config`.
+//
+// ⚠️ a pair is deleted from the pool before the partial is returned, an
+//    index is never signed with twice. persist the pool after every `sign`.
+//
+
+/// public commitment to one nonce pair of a signer's pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NonceCommitment {
+    pub id: u64,
+    pub index: u64,
+    pub D: ProjectivePoint,
+    pub E: ProjectivePoint,
+}
+
+/// signer side: secret nonce pairs for future sessions, each usable once.
+pub struct NoncePool {
+    id: u64,
+    next_index: u64,
+    nonces: BTreeMap<u64, (Scalar, Scalar)>,
+}
+
+impl fmt::Debug for NoncePool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NoncePool")
+            .field("id", &self.id)
+            .field("next_index", &self.next_index)
+            .field("remaining", &self.nonces.len())
+            .finish()
+    }
+}
+
+impl NoncePool {
+    pub fn new(id: u64) -> Self {
+        Self {
+            id,
+            next_index: 0,
+            nonces: BTreeMap::new(),
+        }
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// generate `count` nonce pairs and return their commitments.
+    #[cfg(feature = "std")]
+    pub fn generate(&mut self, count: usize) -> Vec<NonceCommitment> {
+        self.generate_with_rng(count, &mut OsRng)
+    }
+
+    /// like `generate`, drawing the nonces from `rng`.
+    pub fn generate_with_rng(
+        &mut self,
+        count: usize,
+        rng: &mut impl CryptoRngCore,
+    ) -> Vec<NonceCommitment> {
+        (0..count)
+            .map(|_| {
+                let (d, e) = (generate_nonce_with_rng(rng), generate_nonce_with_rng(rng));
+                let index = self.next_index;
+                self.nonces.insert(index, (d, e));
+                self.next_index += 1;
+
+                NonceCommitment {
+                    id: self.id,
+                    index,
+                    D: compute_nonce_point(&d),
+                    E: compute_nonce_point(&e),
+                }
+            })
+            .collect()
+    }
+
+    /// commitments of the nonces not used yet, in index order.
+    pub fn commitments(&self) -> Vec<NonceCommitment> {
+        self.nonces
+            .iter()
+            .map(|(index, (d, e))| NonceCommitment {
+                id: self.id,
+                index: *index,
+                D: compute_nonce_point(d),
+                E: compute_nonce_point(e),
+            })
+            .collect()
+    }
+
+    /// number of nonce pairs not used yet.
+    pub fn remaining(&self) -> usize {
+        self.nonces.len()
+    }
+
+    /// sign `message` with the pair this signer has in `commitments`, which
+    /// is then deleted. fails with `Error::NonceReused` for a pair that was
+    /// already used and `Error::UnknownNonce` for one this pool never made.
+    pub fn sign(
+        &mut self,
+        participant: &Participant,
+        commitments: &[NonceCommitment],
+        public_key: &ProjectivePoint,
+        message: &[u8],
+        config: &SigningConfig,
+    ) -> Result<PartialSignature, Error> {
+        let own = commitments
+            .iter()
+            .find(|commitment| commitment.id == self.id)
+            .ok_or(Error::MissingPackage {
+                round: 1,
+                id: self.id,
+            })?;
+        let (index, id) = (own.index, self.id);
+        let (d, e) = match self.nonces.get(&index) {
+            Some(pair) => *pair,
+            None if index < self.next_index => return Err(Error::NonceReused { id, index }),
+            None => return Err(Error::UnknownNonce { id, index }),
+        };
+        if compute_nonce_point(&d) != own.D || compute_nonce_point(&e) != own.E {
+            return Err(Error::UnknownNonce { id, index });
+        }
+
+        // computed before the pair is gone, a bad signer set doesn't burn it
+        let R = group_nonce_with_config(commitments, public_key, message, config)?;
+        self.nonces.remove(&index);
+
+        let r_i = d + e * binding_factor_with_config(id, commitments, public_key, message, config);
+        let c = config.challenge(&R, public_key, message);
+        Ok(partial_sign(participant, &r_i, &c))
+    }
+}
+
+/// ρ_i of signer `id` for the commitment list `commitments`.
+pub fn binding_factor(
+    id: u64,
+    commitments: &[NonceCommitment],
+    public_key: &ProjectivePoint,
+    message: &[u8],
+) -> Scalar {
+    let encode = |point: &ProjectivePoint| PointEncoding::Compressed.encode(point);
+
+    let mut hasher = scalar_hasher(BINDING_TAG);
+    hasher.update(encode(public_key).as_bytes());
+    hasher.update((message.len() as u64).to_be_bytes());
+    hasher.update(message);
+    let mut sorted = commitments.to_vec();
+    sorted.sort_by_key(|commitment| commitment.id);
+    for commitment in &sorted {
+        hasher.update(commitment.id.to_be_bytes());
+        hasher.update(encode(&commitment.D).as_bytes());
+        hasher.update(encode(&commitment.E).as_bytes());
+    }
+    hasher.update(id.to_be_bytes());
+    finalize_scalar(hasher)
+}
+
+/// like `binding_factor`, drawn from a merlin transcript when `config`
+/// uses the merlin challenge. every other config gives `binding_factor`.
+pub fn binding_factor_with_config(
+    id: u64,
+    commitments: &[NonceCommitment],
+    public_key: &ProjectivePoint,
+    message: &[u8],
+    config: &SigningConfig,
+) -> Scalar {
+    #[cfg(feature = "merlin")]
+    if let ChallengeMode::Merlin { context } = config.challenge_mode() {
+        return merlin_binding_factor(id, commitments, public_key, message, context);
+    }
+    #[cfg(not(feature = "merlin"))]
+    let _ = config;
+
+    binding_factor(id, commitments, public_key, message)
+}
+
+#[cfg(feature = "merlin")]
+fn merlin_binding_factor(
+    id: u64,
+    commitments: &[NonceCommitment],
+    public_key: &ProjectivePoint,
+    message: &[u8],
+    context: &[u8],
+) -> Scalar {
+    let encode = |point: &ProjectivePoint| PointEncoding::Compressed.encode(point);
+
+    let mut transcript = merlin::Transcript::new(MERLIN_BINDING_LABEL);
+    transcript.append_message(b"context", context);
+    transcript.append_message(b"X", encode(public_key).as_bytes());
+    transcript.append_message(b"message", message);
+    let mut sorted = commitments.to_vec();
+    sorted.sort_by_key(|commitment| commitment.id);
+    transcript.append_u64(b"signers", sorted.len() as u64);
+    for commitment in &sorted {
+        transcript.append_u64(b"signer", commitment.id);
+        transcript.append_message(b"D", encode(&commitment.D).as_bytes());
+        transcript.append_message(b"E", encode(&commitment.E).as_bytes());
+    }
+    transcript.append_u64(b"id", id);
+    transcript_scalar(&mut transcript, b"binding")
+}
+
+/// R_i = D_i + ρ_i*E_i of every signer in `commitments`.
+pub fn nonce_points(
+    commitments: &[NonceCommitment],
+    public_key: &ProjectivePoint,
+    message: &[u8],
+) -> Vec<(u64, ProjectivePoint)> {
+    nonce_points_with_config(commitments, public_key, message, &SigningConfig::unframed())
+}
+
+/// like `nonce_points`, with ρ_i from `binding_factor_with_config`.
+pub fn nonce_points_with_config(
+    commitments: &[NonceCommitment],
+    public_key: &ProjectivePoint,
+    message: &[u8],
+    config: &SigningConfig,
+) -> Vec<(u64, ProjectivePoint)> {
+    commitments
+        .iter()
+        .map(|commitment| {
+            let rho =
+                binding_factor_with_config(commitment.id, commitments, public_key, message, config);
+            (commitment.id, commitment.D + commitment.E * rho)
+        })
+        .collect()
+}
+
+/// R = Σ λ_i*R_i over the signers in `commitments`, for the challenge and
+/// `finalize_signature_with_signers` over the same `SignerSet`.
+pub fn group_nonce(
+    commitments: &[NonceCommitment],
+    public_key: &ProjectivePoint,
+    message: &[u8],
+) -> Result<ProjectivePoint, Error> {
+    group_nonce_with_config(commitments, public_key, message, &SigningConfig::unframed())
+}
+
+/// like `group_nonce`, with ρ_i from `binding_factor_with_config`. the
+/// coordinator must pass the config the signers sign with.
+pub fn group_nonce_with_config(
+    commitments: &[NonceCommitment],
+    public_key: &ProjectivePoint,
+    message: &[u8],
+    config: &SigningConfig,
+) -> Result<ProjectivePoint, Error> {
+    let ids = commitments.iter().map(|c| c.id).collect::<Vec<_>>();
+    aggregate_nonce_with_signers(
+        &nonce_points_with_config(commitments, public_key, message, config),
+        &SignerSet::new(&ids, ids.len())?,
+    )
+}
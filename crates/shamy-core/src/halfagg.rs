@@ -0,0 +1,141 @@
+#![allow(non_snake_case)]
+
+use crate::error::Error;
+use crate::hash::{finalize_scalar, scalar_hasher};
+use crate::schnorr::{SchnorrSignature, SigningConfig};
+use crate::util::{PointEncoding, bytes_to_pp};
+use alloc::{string::ToString, vec::Vec};
+use k256::{FieldBytes, ProjectivePoint, Scalar, elliptic_curve::PrimeField};
+use sha2::{Digest, Sha512};
+
+const COEFFICIENT_TAG: &[u8] = b"shamy/halfagg/coefficient/v1";
+
+//--------------------------------------------------------------------
+// Half-aggregation of Schnorr signatures
+//--------------------------------------------------------------------
+//
+// n signatures (R_i, s_i) by keys X_i over messages m_i become
+//
+//   (R_1, ..., R_n, s),   s = Σ z_i*s_i
+//
+//   z_1 = 1
+//   z_i = hash_to_scalar(tag, R_1 || X_1 || len(m_1) || m_1 || ... || R_i || X_i || len(m_i) || m_i)
+//
+// and one check covers all of them
+//
+//   s*G == Σ z_i*(R_i + c_i*X_i),   c_i = config.challenge(R_i, X_i, m_i)
+//
+// 33n + 32 bytes instead of 65n. z_i only depends on the first i entries,
+// so more signatures can be folded into an aggregate later.
+//
+// ⚠️ aggregation doesn't check the inputs, a single bad signature makes the
+// whole aggregate fail. it also can't be split back into signatures.
+//
+
+/// half-aggregate of n signatures, the nonces of each and one scalar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HalfAggregate {
+    pub R: Vec<ProjectivePoint>,
+    pub s: Scalar,
+}
+
+/// aggregate `(message, public key, signature)` into one `HalfAggregate`.
+pub fn half_aggregate(signatures: &[(&[u8], ProjectivePoint, SchnorrSignature)]) -> HalfAggregate {
+    let mut aggregate = HalfAggregate {
+        R: Vec::new(),
+        s: Scalar::ZERO,
+    };
+    aggregate
+        .extend(&[], signatures)
+        .expect("an empty aggregate has no previous entries");
+    aggregate
+}
+
+impl HalfAggregate {
+    /// fold more signatures into the aggregate. `previous` are the
+    /// `(message, public key)` already aggregated, in order.
+    pub fn extend(
+        &mut self,
+        previous: &[(&[u8], ProjectivePoint)],
+        signatures: &[(&[u8], ProjectivePoint, SchnorrSignature)],
+    ) -> Result<(), Error> {
+        if previous.len() != self.R.len() {
+            return Err(Error::InvalidAggregateLength {
+                expected: self.R.len(),
+                actual: previous.len(),
+            });
+        }
+
+        let mut hasher = scalar_hasher(COEFFICIENT_TAG);
+        for (R, (msg, X)) in self.R.iter().zip(previous) {
+            absorb(&mut hasher, R, X, msg);
+        }
+        for (msg, X, signature) in signatures {
+            absorb(&mut hasher, &signature.R, X, msg);
+            self.s += coefficient(&hasher, self.R.len()) * signature.s;
+            self.R.push(signature.R);
+        }
+
+        Ok(())
+    }
+
+    /// verify the aggregate against the `(message, public key)` of every
+    /// signature in it, in the order they were aggregated.
+    pub fn verify(&self, messages: &[(&[u8], ProjectivePoint)], config: &SigningConfig) -> bool {
+        if messages.len() != self.R.len() {
+            return false;
+        }
+
+        let mut hasher = scalar_hasher(COEFFICIENT_TAG);
+        let mut rhs = ProjectivePoint::IDENTITY;
+        for (i, (R, (msg, X))) in self.R.iter().zip(messages).enumerate() {
+            absorb(&mut hasher, R, X, msg);
+            let c = config.challenge(R, X, msg);
+            rhs += (*R + *X * c) * coefficient(&hasher, i);
+        }
+
+        ProjectivePoint::GENERATOR * self.s == rhs
+    }
+
+    /// R_1 || ... || R_n || s, with every R_i compressed.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = self
+            .R
+            .iter()
+            .flat_map(|R| PointEncoding::Compressed.encode(R).as_bytes().to_vec())
+            .collect::<Vec<_>>();
+        out.extend(self.s.to_bytes());
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < 32 || !(bytes.len() - 32).is_multiple_of(33) {
+            return Err(Error::InvalidEncoding(
+                "a half-aggregate is 33n + 32 bytes".to_string(),
+            ));
+        }
+        let (R, s) = bytes.split_at(bytes.len() - 32);
+
+        Ok(Self {
+            R: R.chunks(33).map(bytes_to_pp).collect::<Result<_, _>>()?,
+            s: Scalar::from_repr(*FieldBytes::from_slice(s))
+                .into_option()
+                .ok_or(Error::InvalidScalar)?,
+        })
+    }
+}
+
+fn absorb(hasher: &mut Sha512, R: &ProjectivePoint, X: &ProjectivePoint, msg: &[u8]) {
+    hasher.update(PointEncoding::Compressed.encode(R).as_bytes());
+    hasher.update(PointEncoding::Compressed.encode(X).as_bytes());
+    hasher.update((msg.len() as u64).to_be_bytes());
+    hasher.update(msg);
+}
+
+/// z_i over the entries absorbed so far, z_1 = 1.
+fn coefficient(hasher: &Sha512, index: usize) -> Scalar {
+    match index {
+        0 => Scalar::ONE,
+        _ => finalize_scalar(hasher.clone()),
+    }
+}
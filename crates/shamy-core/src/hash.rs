@@ -0,0 +1,86 @@
+use k256::{
+    Scalar,
+    elliptic_curve::{bigint::U512, ops::Reduce},
+};
+use sha2::{Digest, Sha256, Sha512};
+
+//--------------------------------------------------------------------
+// Tagged hashes (BIP-340)
+//--------------------------------------------------------------------
+//
+//   tagged_hash(tag, data) = SHA-256(SHA-256(tag) || SHA-256(tag) || data)
+//
+// each of the crate's hashes (challenges, nonce coefficients, proofs, ...)
+// starts from its own tag, so an output of one can never be mistaken for
+// an output of another. the 64-byte prefix is one SHA-256 block, which
+// means the state after it could be precomputed per tag.
+//
+// tags are `shamy/<module>/<name>/v1`, bump the version whenever the
+// encoding of `data` changes.
+//
+// hashing to a scalar uses SHA-512 in the same way and reduces all 64
+// bytes mod q. a 32-byte hash mod q would be off by ~2^-128 from uniform,
+// the 512-bit reduction leaves a bias of ~2^-256.
+//
+//   hash_to_scalar(tag, data) = SHA-512(SHA-512(tag) || SHA-512(tag) || data) mod q
+//
+
+/// SHA-256 already fed with the tag prefix, for hashing several fields.
+pub fn tagged_hasher(tag: &[u8]) -> Sha256 {
+    let tag_hash = Sha256::digest(tag);
+    let mut hasher = Sha256::new();
+    hasher.update(tag_hash);
+    hasher.update(tag_hash);
+    hasher
+}
+
+/// SHA-256(SHA-256(tag) || SHA-256(tag) || data).
+pub fn tagged_hash(tag: &[u8], data: &[u8]) -> [u8; 32] {
+    let mut hasher = tagged_hasher(tag);
+    hasher.update(data);
+    hasher.finalize().into()
+}
+
+/// SHA-512 already fed with the tag prefix, finish with `finalize_scalar`.
+pub fn scalar_hasher(tag: &[u8]) -> Sha512 {
+    let tag_hash = Sha512::digest(tag);
+    let mut hasher = Sha512::new();
+    hasher.update(tag_hash);
+    hasher.update(tag_hash);
+    hasher
+}
+
+/// wide reduction of the 64-byte digest mod q.
+pub fn finalize_scalar(hasher: Sha512) -> Scalar {
+    <Scalar as Reduce<U512>>::reduce_bytes(&hasher.finalize())
+}
+
+/// SHA-512(SHA-512(tag) || SHA-512(tag) || data) mod q.
+pub fn hash_to_scalar(tag: &[u8], data: &[u8]) -> Scalar {
+    let mut hasher = scalar_hasher(tag);
+    hasher.update(data);
+    finalize_scalar(hasher)
+}
+
+//--------------------------------------------------------------------
+// Merlin transcripts
+//--------------------------------------------------------------------
+//
+// with the `merlin` feature a scalar can also be drawn from a merlin
+// transcript (STROBE-128 over Keccak-f[1600]). every field is absorbed
+// under its own label with its length, so no hand-rolled prefixes are
+// needed to keep two encodings apart:
+//
+//   t.append_message(label₁, field₁) … t.append_message(labelₖ, fieldₖ)
+//   scalar = t.challenge_bytes(label, 64) mod q
+//
+// the 64 bytes are reduced like `finalize_scalar` does.
+//
+
+/// draw a scalar from `transcript` under `label`.
+#[cfg(feature = "merlin")]
+pub fn transcript_scalar(transcript: &mut merlin::Transcript, label: &'static [u8]) -> Scalar {
+    let mut wide = [0u8; 64];
+    transcript.challenge_bytes(label, &mut wide);
+    <Scalar as Reduce<U512>>::reduce_bytes(&wide.into())
+}
@@ -0,0 +1,221 @@
+//! low-level building blocks.
+//!
+//! ⚠️ these functions expose raw polynomial coefficients and interpolation
+//! weights. apart from `validate_ids` and `LagrangeWeights::new` they perform
+//! no validation and misusing them (e.g. leaking a coefficient or
+//! interpolating over duplicate ids) breaks the security of the scheme.
+//! prefer the high-level APIs in `shamir`, `dkg` and `threshold`.
+
+use crate::error::Error;
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec,
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use k256::elliptic_curve::rand_core::OsRng;
+use k256::{
+    ProjectivePoint, Scalar,
+    elliptic_curve::{Field, ops::LinearCombinationExt, rand_core::CryptoRngCore},
+};
+
+/// generate a random polynomial of degree t-1.
+/// a_0 = secret, a_1, ..., a_{t-1} = random scalars
+#[cfg(feature = "std")]
+pub fn random_polynomial(secret: Scalar, t: usize) -> Vec<Scalar> {
+    random_polynomial_with_rng(secret, t, &mut OsRng)
+}
+
+/// like `random_polynomial`, drawing the coefficients from `rng`.
+pub fn random_polynomial_with_rng(
+    secret: Scalar,
+    t: usize,
+    rng: &mut impl CryptoRngCore,
+) -> Vec<Scalar> {
+    let mut coeffs = vec![secret];
+    for _ in 1..t {
+        coeffs.push(Scalar::random(&mut *rng));
+    }
+
+    coeffs
+}
+
+/// evaluate the polynomial at x = id.
+pub fn eval_polynomial(coeffs: &[Scalar], id: u64) -> Scalar {
+    eval_polynomial_at(coeffs, &Scalar::from(id))
+}
+
+/// evaluate the polynomial at any x, e.g. a hash-derived abscissa.
+/// ⚠️ f(0) is the secret, never hand out a share at x = 0.
+pub fn eval_polynomial_at(coeffs: &[Scalar], x: &Scalar) -> Scalar {
+    let mut acc = Scalar::ZERO;
+    for &c in coeffs.iter().rev() {
+        // horners rule
+        acc = acc * x + c;
+    }
+
+    acc
+}
+
+//--------------------------------------------------------------------
+// λᵢ  (Lagrange weight at z = 0)
+//--------------------------------------------------------------------
+//
+// General form
+//   λⱼ(z₀) = ∏_{k∈T, k≠iⱼ} (z₀ − k)/(iⱼ − k) (mod q)
+//   f(z₀)  = Σ_{j∈T} λⱼ(z₀) · f(iⱼ)          (mod q)
+//
+// For Schnorr we need f(0) ⇢ secret key, so take z₀ = 0:
+//   λᵢ = ∏_{j∈S, j≠i}  j / (j − i)            (mod q)
+//
+// Multiplying each share f(i) by its λᵢ lets us recover f(0)
+// (or any other linear expression that involves f(0)).
+// https://en.wikipedia.org/wiki/Polynomial_interpolation
+//
+// ⚠️ `ids` must be distinct, duplicates silently give a wrong weight.
+//
+pub fn lagrange_coefficient(id_i: u64, ids: &[u64]) -> Scalar {
+    let id_i_scalar = Scalar::from(id_i);
+    let mut num = Scalar::ONE;
+    let mut den = Scalar::ONE;
+
+    for &id_j in ids {
+        if id_j == id_i {
+            continue;
+        }
+        let id_j_scalar = Scalar::from(id_j);
+        num *= id_j_scalar;
+        den *= id_j_scalar - id_i_scalar;
+    }
+
+    num * den.invert().unwrap()
+}
+
+/// λᵢ(x), the weight of f(id_i) in f(x) for the set `ids`.
+/// `lagrange_coefficient` is the case x = 0, `shamir::interpolate_at`
+/// the validated sum over all ids.
+///
+/// ⚠️ `ids` must be distinct, duplicates silently give a wrong weight.
+pub fn lagrange_coefficient_at(id_i: u64, ids: &[u64], x: u64) -> Scalar {
+    let (id_i_scalar, x) = (Scalar::from(id_i), Scalar::from(x));
+    let mut num = Scalar::ONE;
+    let mut den = Scalar::ONE;
+
+    for &id_j in ids {
+        if id_j == id_i {
+            continue;
+        }
+        let id_j_scalar = Scalar::from(id_j);
+        num *= x - id_j_scalar;
+        den *= id_i_scalar - id_j_scalar;
+    }
+
+    num * den.invert().unwrap()
+}
+
+/// check that a signer set is non-empty and its ids are distinct and non-zero.
+pub fn validate_ids(ids: &[u64]) -> Result<(), Error> {
+    if ids.is_empty() {
+        return Err(Error::NoShares);
+    }
+    let mut seen = BTreeSet::new();
+    for id in ids {
+        if *id == 0 {
+            return Err(Error::InvalidParticipantId {
+                id: 0,
+                num_shares: ids.len(),
+            });
+        }
+        if !seen.insert(*id) {
+            return Err(Error::DuplicateParticipantId(*id));
+        }
+    }
+
+    Ok(())
+}
+
+//--------------------------------------------------------------------
+// λᵢ for a whole signer set
+//--------------------------------------------------------------------
+//
+// same weights as `lagrange_coefficient`, computed once per set. the
+// denominators dᵢ = ∏ (j − i) are inverted together (Montgomery's trick):
+//
+//   pᵢ    = d₁·d₂·…·dᵢ                      (prefix products)
+//   inv   = 1/pₜ                            (the only inversion)
+//   1/dᵢ  = inv·pᵢ₋₁,  inv ← inv·dᵢ         (walking back from t)
+//
+// so a fixed quorum pays one inversion instead of t per aggregation.
+//
+
+/// Lagrange weights at z = 0 of a fixed signer set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LagrangeWeights {
+    ids: Vec<u64>,
+    weights: BTreeMap<u64, Scalar>,
+}
+
+impl LagrangeWeights {
+    /// weights of the signer set `ids`, which must be non-empty, distinct and non-zero.
+    pub fn new(ids: &[u64]) -> Result<Self, Error> {
+        validate_ids(ids)?;
+
+        let (nums, dens): (Vec<Scalar>, Vec<Scalar>) = ids
+            .iter()
+            .map(|&id_i| {
+                let x_i = Scalar::from(id_i);
+                ids.iter().filter(|&&id_j| id_j != id_i).fold(
+                    (Scalar::ONE, Scalar::ONE),
+                    |(num, den), &id_j| {
+                        let x_j = Scalar::from(id_j);
+                        (num * x_j, den * (x_j - x_i))
+                    },
+                )
+            })
+            .unzip();
+
+        let mut prefix = Vec::with_capacity(dens.len());
+        let mut acc = Scalar::ONE;
+        for den in &dens {
+            prefix.push(acc);
+            acc *= den;
+        }
+        let mut inv = acc.invert().unwrap();
+        let mut weights = BTreeMap::new();
+        for i in (0..ids.len()).rev() {
+            weights.insert(ids[i], nums[i] * inv * prefix[i]);
+            inv *= dens[i];
+        }
+
+        Ok(Self {
+            ids: ids.to_vec(),
+            weights,
+        })
+    }
+
+    /// the signer set, in the order given to `new`.
+    pub fn ids(&self) -> &[u64] {
+        &self.ids
+    }
+
+    /// λᵢ of `id`, `None` if it's not in the set.
+    pub fn weight(&self, id: u64) -> Option<Scalar> {
+        self.weights.get(&id).copied()
+    }
+}
+
+//--------------------------------------------------------------------
+// Multi-scalar multiplication
+//--------------------------------------------------------------------
+//
+//   Σ kᵢ·Pᵢ
+//
+// Straus: every Pᵢ gets a small lookup table, then all terms share one
+// chain of doublings (4 per 4-bit window). t separate scalar mults pay
+// ~256 doublings each, here they are paid once for the whole sum.
+//
+
+/// Σ kᵢ·Pᵢ in one pass, much faster than summing `P * k` for large sets.
+pub fn multiscalar_mul(points_and_scalars: &[(ProjectivePoint, Scalar)]) -> ProjectivePoint {
+    ProjectivePoint::lincomb_ext(points_and_scalars)
+}
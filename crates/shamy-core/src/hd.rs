@@ -0,0 +1,106 @@
+#![allow(non_snake_case)]
+
+use crate::error::Error;
+use crate::threshold::Participant;
+use hmac::{Hmac, Mac};
+use k256::{
+    ProjectivePoint, Scalar,
+    elliptic_curve::{PrimeField, sec1::ToEncodedPoint},
+};
+use sha2::Sha512;
+
+/// first hardened index, those need the secret key and can't be derived here.
+pub const HARDENED: u32 = 1 << 31;
+
+//--------------------------------------------------------------------
+// Non-hardened HD derivation (BIP-32 CKDpub)
+//--------------------------------------------------------------------
+//
+//   I      = HMAC-SHA512(chain_code, X || index)
+//   tweak  = I[..32]
+//   X'     = X + tweak*G
+//   chain' = I[32..]
+//
+// adding a constant to f(0) shifts every share by the same constant,
+//   f'(x) = f(x) + tweak  =>  x_i' = x_i + tweak,  X_i' = X_i + tweak*G
+// so the existing shares sign for any derived key, no new keygen needed.
+//
+// ⚠️ the tweak is public: anyone holding the chain code and one derived
+// secret learns the group secret. keep derived secrets inside the shares.
+//
+
+/// a key derived from the group key, with the total tweak from the root.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DerivedKey {
+    pub public_key: ProjectivePoint,
+    pub chain_code: [u8; 32],
+    pub tweak: Scalar,
+}
+
+impl DerivedKey {
+    /// the group key itself, the start of every path.
+    pub fn root(public_key: ProjectivePoint, chain_code: [u8; 32]) -> Self {
+        Self {
+            public_key,
+            chain_code,
+            tweak: Scalar::ZERO,
+        }
+    }
+
+    /// CKDpub, one level down.
+    pub fn child(&self, index: u32) -> Result<Self, Error> {
+        let (public_key, chain_code, tweak) =
+            derive_child(&self.public_key, &self.chain_code, index)?;
+        Ok(Self {
+            public_key,
+            chain_code,
+            tweak: self.tweak + tweak,
+        })
+    }
+
+    /// follow `path` from this key, e.g. `&[0, 7]` for .../0/7.
+    pub fn derive_path(&self, path: &[u32]) -> Result<Self, Error> {
+        path.iter().try_fold(*self, |key, index| key.child(*index))
+    }
+
+    /// the participant's share of the derived key, x_i + tweak.
+    pub fn tweak_participant(&self, participant: &Participant) -> Participant {
+        Participant::from_secret(participant.id, participant.x_i + self.tweak)
+    }
+
+    /// a public share of the derived key, X_i + tweak*G.
+    pub fn tweak_public_share(&self, X_i: &ProjectivePoint) -> ProjectivePoint {
+        *X_i + ProjectivePoint::GENERATOR * self.tweak
+    }
+}
+
+/// derive `(child key, child chain code, tweak)` at a non-hardened `index`.
+pub fn derive_child(
+    public_key: &ProjectivePoint,
+    chain_code: &[u8; 32],
+    index: u32,
+) -> Result<(ProjectivePoint, [u8; 32], Scalar), Error> {
+    if index >= HARDENED {
+        return Err(Error::InvalidChildIndex(index));
+    }
+
+    let mut mac = Hmac::<Sha512>::new_from_slice(chain_code).expect("HMAC takes any key length");
+    mac.update(public_key.to_encoded_point(true).as_bytes());
+    mac.update(&index.to_be_bytes());
+    let I = mac.finalize().into_bytes();
+
+    let mut tweak_bytes = [0u8; 32];
+    tweak_bytes.copy_from_slice(&I[..32]);
+    let mut child_chain_code = [0u8; 32];
+    child_chain_code.copy_from_slice(&I[32..]);
+
+    // BIP-32: tweak ≥ n or an identity child is invalid, move on to index + 1
+    let tweak = Option::<Scalar>::from(Scalar::from_repr(tweak_bytes.into()))
+        .ok_or(Error::InvalidChildIndex(index))?;
+    let child = *public_key + ProjectivePoint::GENERATOR * tweak;
+    if child == ProjectivePoint::IDENTITY {
+        return Err(Error::InvalidChildIndex(index));
+    }
+
+    Ok((child, child_chain_code, tweak))
+}
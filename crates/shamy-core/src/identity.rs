@@ -0,0 +1,282 @@
+#![allow(non_snake_case)]
+
+use crate::error::Error;
+use crate::hash::tagged_hasher;
+use crate::proofs::SchnorrProof;
+use crate::util::{PointEncoding, bytes_to_pp, ensure_not_identity};
+use alloc::{collections::BTreeMap, format, string::ToString, vec::Vec};
+#[cfg(feature = "std")]
+use k256::elliptic_curve::rand_core::OsRng;
+use k256::{
+    FieldBytes, ProjectivePoint, Scalar,
+    elliptic_curve::{Field, PrimeField, rand_core::CryptoRngCore},
+};
+use sha2::Digest;
+
+const MESSAGE_TAG: &[u8] = b"shamy/identity/message/v1";
+const SIGNED_MESSAGE_MAGIC: &[u8; 4] = b"SHSM";
+const SIGNED_MESSAGE_VERSION: u8 = 1;
+const POINT_LEN: usize = 33;
+
+/// receiver of a message that goes to every participant.
+pub const BROADCAST: u64 = 0;
+
+//--------------------------------------------------------------------
+// Authenticated round messages
+//--------------------------------------------------------------------
+//
+// every participant has a long-term identity key s, S = s*G, registered
+// with the others out of band. it is independent of the share x_i, so
+// it survives refreshes and also covers DKG, where no share exists yet.
+//
+// each round message (nonce commitment, partial signature, DKG package,
+// ...) is signed over everything that places it in the protocol:
+//
+//   m = H(message, len(context) || context || round || sender
+//                  || receiver || len(payload) || payload)
+//   σ = SchnorrProof::prove(s, S, m)       (a Schnorr signature on m)
+//
+// receiver is `BROADCAST` for messages to everyone. context names the
+// ceremony, e.g. a session id, so a message can't be replayed into
+// another ceremony, round or to another receiver.
+//
+// binary encoding:
+//
+//   "SHSM" || version || round || sender || receiver || len || payload || σ.R || σ.z
+//
+// ids are 8-byte and len 4-byte big-endian, R is compressed.
+//
+// ⚠️ a registry is only as good as the way its keys were exchanged,
+//    compare fingerprints over a channel the network can't touch.
+// ⚠️ the same signed message can be delivered twice within a round,
+//    receivers must still reject duplicates.
+//
+
+/// long-term signing key of a participant.
+#[derive(Clone, PartialEq, Eq)]
+pub struct IdentityKey {
+    secret: Scalar,
+    public: ProjectivePoint,
+}
+
+impl core::fmt::Debug for IdentityKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("IdentityKey")
+            .field("public", &self.public)
+            .finish_non_exhaustive()
+    }
+}
+
+impl IdentityKey {
+    #[cfg(feature = "std")]
+    pub fn generate() -> Self {
+        Self::generate_with_rng(&mut OsRng)
+    }
+
+    /// like `generate`, drawing the key from `rng`.
+    pub fn generate_with_rng(rng: &mut impl CryptoRngCore) -> Self {
+        let secret = Scalar::random(rng);
+        Self::from_secret(secret).expect("a random scalar is not zero")
+    }
+
+    pub fn from_secret(secret: Scalar) -> Result<Self, Error> {
+        if secret == Scalar::ZERO {
+            return Err(Error::InvalidScalar);
+        }
+        Ok(Self {
+            secret,
+            public: ProjectivePoint::GENERATOR * secret,
+        })
+    }
+
+    pub fn secret(&self) -> &Scalar {
+        &self.secret
+    }
+
+    pub fn public_key(&self) -> ProjectivePoint {
+        self.public
+    }
+
+    /// sign `payload` as the round message from `sender` to `receiver`.
+    #[cfg(feature = "std")]
+    pub fn sign(
+        &self,
+        context: &[u8],
+        round: u8,
+        sender: u64,
+        receiver: u64,
+        payload: &[u8],
+    ) -> SignedMessage {
+        self.sign_with_rng(context, round, sender, receiver, payload, &mut OsRng)
+    }
+
+    /// like `sign`, drawing the signature nonce from `rng`.
+    pub fn sign_with_rng(
+        &self,
+        context: &[u8],
+        round: u8,
+        sender: u64,
+        receiver: u64,
+        payload: &[u8],
+        rng: &mut impl CryptoRngCore,
+    ) -> SignedMessage {
+        let digest = message_digest(context, round, sender, receiver, payload);
+        SignedMessage {
+            round,
+            sender,
+            receiver,
+            payload: payload.to_vec(),
+            signature: SchnorrProof::prove_with_rng(&self.secret, &self.public, &digest, rng),
+        }
+    }
+}
+
+/// a round message with the identity signature of its sender.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedMessage {
+    pub round: u8,
+    pub sender: u64,
+    /// `BROADCAST` for a message to every participant.
+    pub receiver: u64,
+    pub payload: Vec<u8>,
+    pub signature: SchnorrProof,
+}
+
+impl SignedMessage {
+    /// whether the signature verifies for the sender key `S` in `context`.
+    pub fn verify(&self, context: &[u8], S: &ProjectivePoint) -> bool {
+        let digest = message_digest(
+            context,
+            self.round,
+            self.sender,
+            self.receiver,
+            &self.payload,
+        );
+        self.signature.verify(S, &digest)
+    }
+
+    /// versioned binary encoding, see the layout above.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = SIGNED_MESSAGE_MAGIC.to_vec();
+        out.push(SIGNED_MESSAGE_VERSION);
+        out.push(self.round);
+        out.extend(self.sender.to_be_bytes());
+        out.extend(self.receiver.to_be_bytes());
+        out.extend((self.payload.len() as u32).to_be_bytes());
+        out.extend_from_slice(&self.payload);
+        out.extend_from_slice(
+            PointEncoding::Compressed
+                .encode(&self.signature.R)
+                .as_bytes(),
+        );
+        out.extend(self.signature.z.to_bytes());
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let invalid = |reason: &str| Error::InvalidEncoding(reason.to_string());
+        let mut rest = bytes
+            .strip_prefix(SIGNED_MESSAGE_MAGIC)
+            .ok_or(invalid("not a signed message"))?;
+        let version = take(&mut rest, 1)?[0];
+        if version != SIGNED_MESSAGE_VERSION {
+            return Err(Error::InvalidEncoding(format!(
+                "unsupported signed message version {version}"
+            )));
+        }
+
+        let round = take(&mut rest, 1)?[0];
+        let sender = u64::from_be_bytes(take(&mut rest, 8)?.try_into().unwrap());
+        let receiver = u64::from_be_bytes(take(&mut rest, 8)?.try_into().unwrap());
+        let len = u32::from_be_bytes(take(&mut rest, 4)?.try_into().unwrap()) as usize;
+        let payload = take(&mut rest, len)?.to_vec();
+        let R = bytes_to_pp(take(&mut rest, POINT_LEN)?)?;
+        let z = Scalar::from_repr(*FieldBytes::from_slice(take(&mut rest, 32)?))
+            .into_option()
+            .ok_or(Error::InvalidScalar)?;
+        if !rest.is_empty() {
+            return Err(invalid("trailing data"));
+        }
+
+        Ok(Self {
+            round,
+            sender,
+            receiver,
+            payload,
+            signature: SchnorrProof { R, z },
+        })
+    }
+}
+
+/// identity keys of the participants of a ceremony, by participant id.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IdentityRegistry {
+    keys: BTreeMap<u64, ProjectivePoint>,
+}
+
+impl IdentityRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// register the identity key of `id`, replacing an earlier one.
+    pub fn insert(&mut self, id: u64, S: ProjectivePoint) -> Result<(), Error> {
+        ensure_not_identity(&S)?;
+        self.keys.insert(id, S);
+        Ok(())
+    }
+
+    pub fn get(&self, id: u64) -> Option<&ProjectivePoint> {
+        self.keys.get(&id)
+    }
+
+    pub fn ids(&self) -> Vec<u64> {
+        self.keys.keys().copied().collect()
+    }
+
+    /// check the signature of `message` against the key registered for
+    /// its sender and return the payload.
+    pub fn verify<'a>(
+        &self,
+        context: &[u8],
+        message: &'a SignedMessage,
+    ) -> Result<&'a [u8], Error> {
+        let S = self
+            .get(message.sender)
+            .ok_or(Error::UnknownIdentity(message.sender))?;
+        match message.verify(context, S) {
+            true => Ok(&message.payload),
+            false => Err(Error::InvalidMessageSignature {
+                round: message.round,
+                id: message.sender,
+            }),
+        }
+    }
+}
+
+fn message_digest(
+    context: &[u8],
+    round: u8,
+    sender: u64,
+    receiver: u64,
+    payload: &[u8],
+) -> [u8; 32] {
+    let mut hasher = tagged_hasher(MESSAGE_TAG);
+    hasher.update((context.len() as u32).to_be_bytes());
+    hasher.update(context);
+    hasher.update([round]);
+    hasher.update(sender.to_be_bytes());
+    hasher.update(receiver.to_be_bytes());
+    hasher.update((payload.len() as u32).to_be_bytes());
+    hasher.update(payload);
+    hasher.finalize().into()
+}
+
+fn take<'a>(bytes: &mut &'a [u8], len: usize) -> Result<&'a [u8], Error> {
+    if bytes.len() < len {
+        return Err(Error::InvalidEncoding("truncated".to_string()));
+    }
+    let (head, tail) = bytes.split_at(len);
+    *bytes = tail;
+    Ok(head)
+}
@@ -1,9 +1,49 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod backend;
+pub mod backup;
+pub mod blinding;
+#[cfg(feature = "bls")]
+pub mod bls;
+pub mod coordinator;
+pub mod dkg;
+pub mod elgamal;
+pub mod error;
+pub mod evidence;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 pub mod frost;
+pub mod halfagg;
+pub mod hash;
+pub mod hazmat;
+pub mod hd;
+pub mod identity;
+pub mod limits;
+pub mod musig;
+pub mod nonces;
+pub mod pem;
+pub mod proofs;
+pub mod repair;
+pub mod reshare;
+pub mod rfc9591;
+pub mod robust;
 pub mod schnorr;
 pub mod shamir;
+pub mod taproot;
 pub mod threshold;
+pub mod transcript;
 pub mod util;
+#[cfg(feature = "test-vectors")]
+pub mod vectors;
+pub mod vrf;
 pub mod vss;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use error::Error;
+pub use limits::Limits;
 
 /*
 Schnorr Signature Scheme
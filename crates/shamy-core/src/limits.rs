@@ -0,0 +1,84 @@
+use crate::error::Error;
+
+/// upper bounds on committee parameters, so that pathological inputs
+/// (n = 10⁷, ...) fail with an error instead of exhausting memory.
+/// keygen and DKG hold O(n) shares or packages and O(t) coefficients.
+/// start from `Limits::default()` and adjust with the `with_*` methods.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Limits {
+    pub max_participants: usize,
+    pub max_threshold: usize,
+    /// accept t = 1: a constant polynomial, every share is the secret
+    /// itself and signs alone. n copies of one key, off by default.
+    pub allow_trivial_threshold: bool,
+}
+
+impl Limits {
+    pub const DEFAULT_MAX_PARTICIPANTS: usize = 10_000;
+    pub const DEFAULT_MAX_THRESHOLD: usize = 1_000;
+
+    pub fn with_max_participants(mut self, max_participants: usize) -> Self {
+        self.max_participants = max_participants;
+        self
+    }
+
+    pub fn with_max_threshold(mut self, max_threshold: usize) -> Self {
+        self.max_threshold = max_threshold;
+        self
+    }
+
+    /// accept t = 1, see `allow_trivial_threshold`.
+    pub fn with_trivial_threshold(mut self, allow: bool) -> Self {
+        self.allow_trivial_threshold = allow;
+        self
+    }
+
+    /// check that t-of-n is well formed and within the limits.
+    pub fn check(&self, t: usize, n: usize) -> Result<(), Error> {
+        if n > self.max_participants {
+            return Err(Error::TooManyParticipants {
+                num_shares: n,
+                max: self.max_participants,
+            });
+        }
+        self.check_threshold(t, n as u64)
+    }
+
+    /// like `check`, without bounding n. for callers that never hold
+    /// all n shares at once, e.g. `shamir::Dealer`.
+    pub fn check_threshold(&self, t: usize, n: u64) -> Result<(), Error> {
+        if n == 0 {
+            return Err(Error::NoParticipants);
+        }
+        if t > self.max_threshold {
+            return Err(Error::ThresholdTooLarge {
+                threshold: t,
+                max: self.max_threshold,
+            });
+        }
+        let min = if self.allow_trivial_threshold { 1 } else { 2 };
+        if t < min {
+            return Err(Error::ThresholdTooSmall { threshold: t, min });
+        }
+        if t as u64 > n {
+            return Err(Error::ThresholdExceedsParticipants {
+                threshold: t,
+                // t > n, so n is below t and fits a usize
+                num_shares: n as usize,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_participants: Self::DEFAULT_MAX_PARTICIPANTS,
+            max_threshold: Self::DEFAULT_MAX_THRESHOLD,
+            allow_trivial_threshold: false,
+        }
+    }
+}
@@ -0,0 +1,252 @@
+#![allow(non_snake_case)]
+
+use crate::error::Error;
+use crate::hash::{finalize_scalar, scalar_hasher, tagged_hasher};
+use crate::schnorr::{SchnorrSignature, SigningConfig};
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use k256::elliptic_curve::rand_core::OsRng;
+use k256::{
+    ProjectivePoint, Scalar,
+    elliptic_curve::{Field, rand_core::CryptoRngCore, sec1::ToEncodedPoint},
+};
+use sha2::Digest;
+
+const KEYAGG_LIST_TAG: &[u8] = b"shamy/musig2/keyagg-list/v1";
+const KEYAGG_COEF_TAG: &[u8] = b"shamy/musig2/keyagg-coef/v1";
+const NONCE_COEF_TAG: &[u8] = b"shamy/musig2/nonce-coef/v1";
+
+//--------------------------------------------------------------------
+// MuSig2 (n-of-n multisignatures)
+//--------------------------------------------------------------------
+//
+// every signer has an independent key pair (x_i, X_i), no dealer or DKG.
+//
+// key aggregation
+//   L   = H(X_1 || ... || X_n)
+//   a_i = H(L || X_i)
+//   X   = Σ a_i*X_i
+//
+// signing, two nonces per signer
+//   R_i1 = r_i1*G, R_i2 = r_i2*G            (broadcast)
+//   R_1  = Σ R_i1, R_2 = Σ R_i2
+//   b    = H(R_1 || R_2 || X || m)
+//   R    = R_1 + b*R_2
+//   c    = H(R, X, frame(m))                (same challenge as `schnorr`)
+//   s_i  = r_i1 + b*r_i2 + c*a_i*x_i
+//   s    = Σ s_i
+//
+// (R, s) is a plain Schnorr signature under X:
+//   s*G = R_1 + b*R_2 + c*Σ a_i*X_i = R + c*X
+//
+// the coefficients a_i stop rogue-key attacks (X_n = x*G - Σ X_i), the
+// second nonce makes signing sessions safe to run concurrently.
+//
+// ⚠️ all n signers must take part, this is not a threshold scheme.
+//
+
+/// aggregated public key of a MuSig2 group, in signer order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyAggContext {
+    public_keys: Vec<ProjectivePoint>,
+    coefficients: Vec<Scalar>,
+    aggregate_key: ProjectivePoint,
+}
+
+impl KeyAggContext {
+    /// aggregate the keys of all signers. the order matters, every signer
+    /// must use the same list.
+    pub fn new(public_keys: &[ProjectivePoint]) -> Result<Self, Error> {
+        if public_keys.is_empty() {
+            return Err(Error::NoPublicKeys);
+        }
+
+        let mut hasher = tagged_hasher(KEYAGG_LIST_TAG);
+        for X_i in public_keys {
+            hasher.update(X_i.to_encoded_point(true).as_bytes());
+        }
+        let L = hasher.finalize();
+
+        let coefficients = public_keys
+            .iter()
+            .map(|X_i| {
+                let mut hasher = scalar_hasher(KEYAGG_COEF_TAG);
+                hasher.update(L);
+                hasher.update(X_i.to_encoded_point(true).as_bytes());
+                finalize_scalar(hasher)
+            })
+            .collect::<Vec<_>>();
+
+        let aggregate_key = public_keys
+            .iter()
+            .zip(&coefficients)
+            .fold(ProjectivePoint::IDENTITY, |acc, (X_i, a_i)| {
+                acc + *X_i * a_i
+            });
+        if aggregate_key == ProjectivePoint::IDENTITY {
+            return Err(Error::InvalidPoint("aggregate key is the identity".into()));
+        }
+
+        Ok(Self {
+            public_keys: public_keys.to_vec(),
+            coefficients,
+            aggregate_key,
+        })
+    }
+
+    /// X = Σ a_i*X_i, verifies the final signature.
+    pub fn aggregate_key(&self) -> ProjectivePoint {
+        self.aggregate_key
+    }
+
+    pub fn public_keys(&self) -> &[ProjectivePoint] {
+        &self.public_keys
+    }
+
+    /// a_i of the signer with key `X_i`.
+    pub fn coefficient(&self, X_i: &ProjectivePoint) -> Result<Scalar, Error> {
+        self.public_keys
+            .iter()
+            .position(|X| X == X_i)
+            .map(|i| self.coefficients[i])
+            .ok_or(Error::UnknownPublicKey)
+    }
+}
+
+/// secret nonce pair (r_i1, r_i2) of one signer for one session.
+/// not `Clone`: `Session::sign` consumes it so it can't be used twice.
+pub struct SecretNonce {
+    r1: Scalar,
+    r2: Scalar,
+}
+
+impl SecretNonce {
+    #[cfg(feature = "std")]
+    pub fn generate() -> Self {
+        Self::generate_with_rng(&mut OsRng)
+    }
+
+    /// ⚠️ `rng` must be a CSPRNG, a predictable nonce leaks the secret key.
+    pub fn generate_with_rng(rng: &mut impl CryptoRngCore) -> Self {
+        Self {
+            r1: Scalar::random(&mut *rng),
+            r2: Scalar::random(rng),
+        }
+    }
+
+    /// (R_i1, R_i2) to broadcast to the other signers.
+    pub fn public_nonce(&self) -> PublicNonce {
+        PublicNonce {
+            R1: ProjectivePoint::GENERATOR * self.r1,
+            R2: ProjectivePoint::GENERATOR * self.r2,
+        }
+    }
+}
+
+/// public nonce pair, of one signer or aggregated over all of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PublicNonce {
+    pub R1: ProjectivePoint,
+    pub R2: ProjectivePoint,
+}
+
+/// (Σ R_i1, Σ R_i2) over the public nonces of all signers.
+pub fn aggregate_nonces(nonces: &[PublicNonce]) -> PublicNonce {
+    nonces.iter().fold(
+        PublicNonce {
+            R1: ProjectivePoint::IDENTITY,
+            R2: ProjectivePoint::IDENTITY,
+        },
+        |acc, nonce| PublicNonce {
+            R1: acc.R1 + nonce.R1,
+            R2: acc.R2 + nonce.R2,
+        },
+    )
+}
+
+/// values every signer derives for one message: b, R and c.
+#[derive(Debug, Clone)]
+pub struct Session {
+    key_agg: KeyAggContext,
+    b: Scalar,
+    R: ProjectivePoint,
+    c: Scalar,
+}
+
+impl Session {
+    pub fn new(
+        key_agg: &KeyAggContext,
+        aggregate_nonce: &PublicNonce,
+        message: &[u8],
+        config: &SigningConfig,
+    ) -> Result<Self, Error> {
+        let X = key_agg.aggregate_key;
+        let framed = config.frame_message(message);
+
+        let mut hasher = scalar_hasher(NONCE_COEF_TAG);
+        hasher.update(aggregate_nonce.R1.to_encoded_point(true).as_bytes());
+        hasher.update(aggregate_nonce.R2.to_encoded_point(true).as_bytes());
+        hasher.update(X.to_encoded_point(true).as_bytes());
+        hasher.update(&framed);
+        let b = finalize_scalar(hasher);
+
+        // only reachable if a signer picks its nonces after seeing the others
+        let R = aggregate_nonce.R1 + aggregate_nonce.R2 * b;
+        if R == ProjectivePoint::IDENTITY {
+            return Err(Error::InvalidPoint(
+                "aggregate nonce is the identity".into(),
+            ));
+        }
+
+        Ok(Self {
+            key_agg: key_agg.clone(),
+            b,
+            R,
+            c: config.challenge(&R, &X, message),
+        })
+    }
+
+    /// R = R_1 + b*R_2.
+    pub fn nonce(&self) -> ProjectivePoint {
+        self.R
+    }
+
+    pub fn challenge(&self) -> Scalar {
+        self.c
+    }
+
+    /// s_i = r_i1 + b*r_i2 + c*a_i*x_i.
+    pub fn sign(&self, x_i: &Scalar, nonce: SecretNonce) -> Result<Scalar, Error> {
+        let a_i = self
+            .key_agg
+            .coefficient(&(ProjectivePoint::GENERATOR * x_i))?;
+
+        Ok(nonce.r1 + self.b * nonce.r2 + self.c * a_i * x_i)
+    }
+
+    /// check s_i*G == R_i1 + b*R_i2 + c*a_i*X_i, so a bad partial can be
+    /// blamed on its signer before aggregation.
+    pub fn verify_partial(&self, X_i: &ProjectivePoint, nonce: &PublicNonce, s_i: &Scalar) -> bool {
+        let Ok(a_i) = self.key_agg.coefficient(X_i) else {
+            return false;
+        };
+
+        ProjectivePoint::GENERATOR * s_i == nonce.R1 + nonce.R2 * self.b + *X_i * (self.c * a_i)
+    }
+
+    /// s = Σ s_i, one partial per signer.
+    pub fn aggregate(&self, partials: &[Scalar]) -> Result<SchnorrSignature, Error> {
+        if partials.len() != self.key_agg.public_keys.len() {
+            return Err(Error::InvalidPartialCount {
+                expected: self.key_agg.public_keys.len(),
+                actual: partials.len(),
+            });
+        }
+
+        SchnorrSignature {
+            R: self.R,
+            s: partials.iter().sum(),
+        }
+        .normalize()
+    }
+}
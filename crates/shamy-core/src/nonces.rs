@@ -0,0 +1,142 @@
+#![allow(non_snake_case)]
+
+use crate::error::Error;
+use crate::schnorr::compute_nonce_point;
+use crate::threshold::{PartialSignature, Participant, partial_sign};
+use crate::util::pp_to_hex;
+use alloc::{collections::BTreeMap, string::String};
+use k256::Scalar;
+#[cfg(feature = "std")]
+use {
+    crate::util::{hex_to_pp, hex_to_scalar, scalar_to_hex},
+    alloc::{format, string::ToString},
+    std::{
+        fs::{self, OpenOptions},
+        io::{ErrorKind, Write},
+        path::PathBuf,
+    },
+};
+
+//--------------------------------------------------------------------
+// Nonce reuse guard
+//--------------------------------------------------------------------
+//
+// two partials with one nonce and different challenges leak the share:
+//
+//   s_1 = r + c_1*x_i,   s_2 = r + c_2*x_i
+//   x_i = (s_1 - s_2) / (c_1 - c_2)
+//
+// `partial_sign` is a bare formula and can't know what it signed before.
+// `NonceRegistry::partial_sign` records R_i = r_i*G of every nonce it signs
+// with and refuses a nonce it has seen, whatever the challenge.
+//
+// only public values are stored, one line per signature:
+//
+//   <id> <R_i> <c>
+//
+// ⚠️ a file-backed registry appends and syncs the line before the partial
+//    is returned, so a crash can burn a nonce but never reuse one.
+//
+
+/// consumed nonces of one or more signers, in memory or backed by a file.
+#[derive(Debug, Default)]
+pub struct NonceRegistry {
+    /// (id, R_i in hex) -> challenge signed with it
+    used: BTreeMap<(u64, String), Scalar>,
+    #[cfg(feature = "std")]
+    path: Option<PathBuf>,
+}
+
+impl NonceRegistry {
+    /// empty in-memory registry, forgotten when dropped.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// registry persisted in `path`, created on the first signature.
+    #[cfg(feature = "std")]
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, Error> {
+        let path = path.into();
+        let mut registry = Self {
+            used: BTreeMap::new(),
+            path: None,
+        };
+        let data = match fs::read_to_string(&path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(Error::Io(e.to_string())),
+        };
+        for (number, line) in data.lines().enumerate() {
+            let invalid = || Error::InvalidEncoding(format!("nonce registry line {}", number + 1));
+            let mut fields = line.split_whitespace();
+            let (Some(id), Some(R_i), Some(c), None) =
+                (fields.next(), fields.next(), fields.next(), fields.next())
+            else {
+                return Err(invalid());
+            };
+            let id = id.parse().map_err(|_| invalid())?;
+            let R_i = hex_to_pp(R_i).map_err(|_| invalid())?;
+            let c = hex_to_scalar(c).map_err(|_| invalid())?;
+            registry.used.insert((id, pp_to_hex(&R_i)), c);
+        }
+
+        registry.path = Some(path);
+        Ok(registry)
+    }
+
+    /// whether participant `id` already signed with nonce `r_i`.
+    pub fn is_used(&self, id: u64, r_i: &Scalar) -> bool {
+        self.used
+            .contains_key(&(id, pp_to_hex(&compute_nonce_point(r_i))))
+    }
+
+    /// number of recorded signatures.
+    pub fn len(&self) -> usize {
+        self.used.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.used.is_empty()
+    }
+
+    /// `threshold::partial_sign` that records `r_i` first. fails with
+    /// `Error::NonceAlreadyUsed` if the participant signed with it before.
+    pub fn partial_sign(
+        &mut self,
+        participant: &Participant,
+        r_i: &Scalar,
+        c: &Scalar,
+    ) -> Result<PartialSignature, Error> {
+        let key = (participant.id, pp_to_hex(&compute_nonce_point(r_i)));
+        if let Some(previous) = self.used.get(&key) {
+            return Err(Error::NonceAlreadyUsed {
+                id: participant.id,
+                same_challenge: previous == c,
+            });
+        }
+
+        self.persist(&key, c)?;
+        self.used.insert(key, *c);
+        Ok(partial_sign(participant, r_i, c))
+    }
+
+    #[cfg(feature = "std")]
+    fn persist(&self, (id, R_i): &(u64, String), c: &Scalar) -> Result<(), Error> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let io = |e: std::io::Error| Error::Io(e.to_string());
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(io)?;
+        writeln!(file, "{} {} {}", id, R_i, scalar_to_hex(c)).map_err(io)?;
+        file.sync_all().map_err(io)
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn persist(&self, _: &(u64, String), _: &Scalar) -> Result<(), Error> {
+        Ok(())
+    }
+}
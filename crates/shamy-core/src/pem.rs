@@ -0,0 +1,351 @@
+#![allow(non_snake_case)]
+
+use crate::error::Error;
+use crate::threshold::Participant;
+use crate::util::{PointEncoding, bytes_to_pp, fingerprint, pp_to_hex};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use k256::{FieldBytes, ProjectivePoint, Scalar, elliptic_curve::PrimeField};
+
+/// label of a plain SubjectPublicKeyInfo, what OpenSSL reads and writes.
+pub const PUBLIC_KEY_LABEL: &str = "PUBLIC KEY";
+pub const GROUP_KEY_LABEL: &str = "SHAMY GROUP KEY";
+pub const SHARE_LABEL: &str = "SHAMY SHARE";
+
+const SHARE_VERSION: u8 = 1;
+const SHARE_LEN: usize = 1 + 8 + 4 + 33 + 32;
+const LINE_LEN: usize = 64;
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// DER prefix of a SubjectPublicKeyInfo with id-ecPublicKey on secp256k1,
+/// up to the BIT STRING holding the 65-byte uncompressed point.
+const SPKI_UNCOMPRESSED: &[u8] = &[
+    0x30, 0x56, 0x30, 0x10, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x05, 0x2b,
+    0x81, 0x04, 0x00, 0x0a, 0x03, 0x42, 0x00,
+];
+/// the same for the 33-byte compressed point.
+const SPKI_COMPRESSED: &[u8] = &[
+    0x30, 0x36, 0x30, 0x10, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x05, 0x2b,
+    0x81, 0x04, 0x00, 0x0a, 0x03, 0x22, 0x00,
+];
+
+//--------------------------------------------------------------------
+// PEM containers
+//--------------------------------------------------------------------
+//
+//   -----BEGIN <label>-----
+//   Name: value                  optional headers (RFC 1421 style)
+//
+//   base64(body), 64 characters per line
+//   -----END <label>-----
+//
+// PUBLIC KEY       body is the DER SubjectPublicKeyInfo of the group key
+//                  (id-ecPublicKey, secp256k1), readable by OpenSSL
+// SHAMY GROUP KEY  same body, with Threshold, Participants and
+//                  Fingerprint headers
+// SHAMY SHARE      body is version || id || t || X || x_i, with Id,
+//                  Threshold, Public-Key and Fingerprint headers
+//
+// ids are 8-byte and t 4-byte big-endian, X is compressed.
+//
+// headers are for people and tools that don't parse the body. they are
+// not authenticated, so every header that repeats the body must match it
+// on decode, and Threshold/Participants of a group key are advisory.
+//
+// ⚠️ a share PEM holds x_i in the clear, treat it like the share itself.
+//
+
+/// a decoded PEM block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pem {
+    pub label: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Pem {
+    pub fn new(label: &str, body: &[u8]) -> Self {
+        Self {
+            label: label.to_string(),
+            headers: Vec::new(),
+            body: body.to_vec(),
+        }
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// value of the first header called `name`.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn encode(&self) -> String {
+        let mut out = format!("-----BEGIN {}-----\n", self.label);
+        for (name, value) in &self.headers {
+            out.push_str(&format!("{name}: {value}\n"));
+        }
+        if !self.headers.is_empty() {
+            out.push('\n');
+        }
+        let encoded = base64_encode(&self.body);
+        for line in encoded.as_bytes().chunks(LINE_LEN) {
+            out.push_str(core::str::from_utf8(line).unwrap());
+            out.push('\n');
+        }
+        out.push_str(&format!("-----END {}-----\n", self.label));
+        out
+    }
+
+    /// parse the first PEM block in `text`, ignoring anything around it.
+    pub fn decode(text: &str) -> Result<Self, Error> {
+        let invalid = |reason: &str| Error::InvalidEncoding(reason.to_string());
+        let mut lines = text
+            .lines()
+            .map(str::trim)
+            .skip_while(|line| !line.starts_with("-----BEGIN "));
+        let label = lines
+            .next()
+            .and_then(|line| line.strip_prefix("-----BEGIN "))
+            .and_then(|line| line.strip_suffix("-----"))
+            .ok_or(invalid("no PEM block"))?
+            .to_string();
+        let end = format!("-----END {label}-----");
+
+        let mut headers = Vec::new();
+        let mut body = String::new();
+        let mut closed = false;
+        for line in lines.by_ref() {
+            if line == end {
+                closed = true;
+                break;
+            }
+            match line.split_once(": ") {
+                Some((name, value)) if body.is_empty() => {
+                    headers.push((name.to_string(), value.to_string()));
+                }
+                _ => body.push_str(line),
+            }
+        }
+        if !closed {
+            return Err(invalid("PEM block is not closed"));
+        }
+
+        Ok(Self {
+            label,
+            headers,
+            body: base64_decode(&body)?,
+        })
+    }
+
+    /// fail unless the header `name`, if present, is `expected`.
+    fn check_header(&self, name: &str, expected: &str) -> Result<(), Error> {
+        match self.header(name) {
+            Some(value) if value != expected => Err(Error::InvalidEncoding(format!(
+                "header {name} does not match the body"
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// group key with the metadata of a `SHAMY GROUP KEY` block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArmoredGroupKey {
+    pub public_key: ProjectivePoint,
+    /// None for a plain `PUBLIC KEY` block.
+    pub threshold: Option<usize>,
+    pub participants: Option<usize>,
+}
+
+/// a share with the group it belongs to, from a `SHAMY SHARE` block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArmoredShare {
+    pub participant: Participant,
+    pub public_key: ProjectivePoint,
+    pub threshold: usize,
+}
+
+/// DER SubjectPublicKeyInfo of `public_key`, uncompressed as OpenSSL writes it.
+pub fn public_key_to_spki(public_key: &ProjectivePoint) -> Vec<u8> {
+    let mut der = SPKI_UNCOMPRESSED.to_vec();
+    der.extend_from_slice(PointEncoding::Uncompressed.encode(public_key).as_bytes());
+    der
+}
+
+/// parse a secp256k1 SubjectPublicKeyInfo with either point form.
+pub fn spki_to_public_key(der: &[u8]) -> Result<ProjectivePoint, Error> {
+    let point = der
+        .strip_prefix(SPKI_UNCOMPRESSED)
+        .filter(|point| point.len() == 65)
+        .or_else(|| {
+            der.strip_prefix(SPKI_COMPRESSED)
+                .filter(|point| point.len() == 33)
+        })
+        .ok_or_else(|| {
+            Error::InvalidEncoding("not a secp256k1 SubjectPublicKeyInfo".to_string())
+        })?;
+    bytes_to_pp(point)
+}
+
+/// plain `PUBLIC KEY` block, for OpenSSL and other single-key tools.
+pub fn public_key_to_pem(public_key: &ProjectivePoint) -> String {
+    Pem::new(PUBLIC_KEY_LABEL, &public_key_to_spki(public_key)).encode()
+}
+
+/// `SHAMY GROUP KEY` block of a `threshold`-of-`participants` group.
+pub fn group_key_to_pem(
+    public_key: &ProjectivePoint,
+    threshold: usize,
+    participants: usize,
+) -> String {
+    Pem::new(GROUP_KEY_LABEL, &public_key_to_spki(public_key))
+        .with_header("Threshold", &threshold.to_string())
+        .with_header("Participants", &participants.to_string())
+        .with_header("Fingerprint", &fingerprint(public_key))
+        .encode()
+}
+
+/// read a `SHAMY GROUP KEY` or a plain `PUBLIC KEY` block.
+pub fn group_key_from_pem(text: &str) -> Result<ArmoredGroupKey, Error> {
+    let pem = Pem::decode(text)?;
+    if pem.label != GROUP_KEY_LABEL && pem.label != PUBLIC_KEY_LABEL {
+        return Err(Error::InvalidEncoding(format!(
+            "expected a public key, got {}",
+            pem.label
+        )));
+    }
+    let public_key = spki_to_public_key(&pem.body)?;
+    pem.check_header("Fingerprint", &fingerprint(&public_key))?;
+    let number = |name: &str| {
+        pem.header(name)
+            .map(|value| {
+                value
+                    .parse::<usize>()
+                    .map_err(|_| Error::InvalidEncoding(format!("header {name} is not a number")))
+            })
+            .transpose()
+    };
+
+    Ok(ArmoredGroupKey {
+        public_key,
+        threshold: number("Threshold")?,
+        participants: number("Participants")?,
+    })
+}
+
+/// `SHAMY SHARE` block of `participant` in a `threshold`-of-n group with key `public_key`.
+pub fn share_to_pem(
+    participant: &Participant,
+    public_key: &ProjectivePoint,
+    threshold: usize,
+) -> String {
+    let compressed = PointEncoding::Compressed.encode(public_key);
+    let mut body = Vec::with_capacity(SHARE_LEN);
+    body.push(SHARE_VERSION);
+    body.extend(participant.id.to_be_bytes());
+    body.extend((threshold as u32).to_be_bytes());
+    body.extend_from_slice(compressed.as_bytes());
+    body.extend(participant.x_i.to_bytes());
+
+    Pem::new(SHARE_LABEL, &body)
+        .with_header("Id", &participant.id.to_string())
+        .with_header("Threshold", &threshold.to_string())
+        .with_header("Public-Key", &pp_to_hex(public_key))
+        .with_header("Fingerprint", &fingerprint(public_key))
+        .encode()
+}
+
+pub fn share_from_pem(text: &str) -> Result<ArmoredShare, Error> {
+    let invalid = |reason: &str| Error::InvalidEncoding(reason.to_string());
+    let pem = Pem::decode(text)?;
+    if pem.label != SHARE_LABEL {
+        return Err(Error::InvalidEncoding(format!(
+            "expected a share, got {}",
+            pem.label
+        )));
+    }
+    let body = pem.body.as_slice();
+    if body.len() != SHARE_LEN {
+        return Err(invalid("share body has the wrong length"));
+    }
+    if body[0] != SHARE_VERSION {
+        return Err(Error::InvalidEncoding(format!(
+            "unsupported share version {}",
+            body[0]
+        )));
+    }
+
+    let id = u64::from_be_bytes(body[1..9].try_into().unwrap());
+    let threshold = u32::from_be_bytes(body[9..13].try_into().unwrap()) as usize;
+    let public_key = bytes_to_pp(&body[13..46])?;
+    let x_i = Scalar::from_repr(*FieldBytes::from_slice(&body[46..]))
+        .into_option()
+        .ok_or(Error::InvalidScalar)?;
+    if id == 0 || x_i == Scalar::ZERO {
+        return Err(invalid("share is zero"));
+    }
+    pem.check_header("Id", &id.to_string())?;
+    pem.check_header("Threshold", &threshold.to_string())?;
+    pem.check_header("Public-Key", &pp_to_hex(&public_key))?;
+    pem.check_header("Fingerprint", &fingerprint(&public_key))?;
+
+    Ok(ArmoredShare {
+        participant: Participant::from_secret(id, x_i),
+        public_key,
+        threshold,
+    })
+}
+
+fn base64_encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            match i <= chunk.len() {
+                true => out.push(BASE64[(n >> (18 - 6 * i) & 0x3f) as usize] as char),
+                false => out.push('='),
+            }
+        }
+    }
+    out
+}
+
+fn base64_decode(text: &str) -> Result<Vec<u8>, Error> {
+    let invalid = || Error::InvalidEncoding("invalid base64".to_string());
+    let bytes = text.as_bytes();
+    if !bytes.len().is_multiple_of(4) {
+        return Err(invalid());
+    }
+
+    let mut out = Vec::with_capacity(bytes.len() / 4 * 3);
+    for (k, chunk) in bytes.chunks(4).enumerate() {
+        let padding = chunk.iter().rev().take_while(|c| **c == b'=').count();
+        if padding > 2 || (padding > 0 && k + 1 != bytes.len() / 4) {
+            return Err(invalid());
+        }
+        let mut n = 0u32;
+        for (i, c) in chunk[..4 - padding].iter().enumerate() {
+            let value = BASE64.iter().position(|b| b == c).ok_or_else(invalid)? as u32;
+            n |= value << (18 - 6 * i);
+        }
+        let len = 3 - padding;
+        // the bits past the last byte must be zero
+        if n & (0xff_ffff >> (8 * len)) != 0 {
+            return Err(invalid());
+        }
+        out.extend_from_slice(&n.to_be_bytes()[1..1 + len]);
+    }
+    Ok(out)
+}
@@ -0,0 +1,248 @@
+#![allow(non_snake_case)]
+
+use crate::error::Error;
+use crate::hash::{finalize_scalar, scalar_hasher};
+use crate::threshold::Participant;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use k256::elliptic_curve::rand_core::OsRng;
+use k256::{
+    ProjectivePoint, Scalar,
+    elliptic_curve::{Field, rand_core::CryptoRngCore, sec1::ToEncodedPoint},
+};
+use sha2::Digest;
+
+const SCHNORR_PROOF_TAG: &[u8] = b"shamy/proofs/schnorr-pok/v1";
+const POP_CONTEXT: &[u8] = b"shamy/proofs/pop";
+const DLEQ_TAG: &[u8] = b"shamy/proofs/dleq/v1";
+
+//--------------------------------------------------------------------
+// Schnorr proof of knowledge (Fiat–Shamir)
+//--------------------------------------------------------------------
+//
+// proves knowledge of x such that P = x*G without revealing x
+//
+//   prover:   k ←$ Zq,  R = k*G
+//             e = H(tag || ctx || R || P)
+//             z = k + e*x
+//
+//   verifier: z*G == R + e*P
+//
+// `ctx` binds the proof to its use (participant id, fresh coordinator
+// challenge, ...) so a proof can't be replayed in another setting.
+//
+
+/// non-interactive proof of knowledge of the discrete log of a point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SchnorrProof {
+    pub R: ProjectivePoint,
+    pub z: Scalar,
+}
+
+impl SchnorrProof {
+    /// prove knowledge of `x` where `P = x*G`.
+    #[cfg(feature = "std")]
+    pub fn prove(x: &Scalar, P: &ProjectivePoint, context: &[u8]) -> Self {
+        Self::prove_with_rng(x, P, context, &mut OsRng)
+    }
+
+    /// like `prove`, drawing the proof nonce from `rng`.
+    pub fn prove_with_rng(
+        x: &Scalar,
+        P: &ProjectivePoint,
+        context: &[u8],
+        rng: &mut impl CryptoRngCore,
+    ) -> Self {
+        let k = Scalar::random(rng);
+        let R = ProjectivePoint::GENERATOR * k;
+        let e = proof_challenge(&R, P, context);
+
+        Self { R, z: k + e * x }
+    }
+
+    /// verify the proof for the public point `P` and the same `context`.
+    pub fn verify(&self, P: &ProjectivePoint, context: &[u8]) -> bool {
+        let e = proof_challenge(&self.R, P, context);
+        ProjectivePoint::GENERATOR * self.z == self.R + (*P * e)
+    }
+}
+
+/// proof of possession of a public share Xᵢ, bound to the participant id.
+/// a rogue Xᵢ = x*G - Σ Xⱼ can't come with one, as nobody knows its secret.
+///
+/// the `_with_context` variants also bind the proof to a ceremony, e.g.
+/// the group key after a DKG or an enrollment, so a proof seen in one
+/// group can't be replayed to register the same Xᵢ in another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProofOfPossession {
+    pub id: u64,
+    pub proof: SchnorrProof,
+}
+
+impl ProofOfPossession {
+    #[cfg(feature = "std")]
+    pub fn prove(participant: &Participant) -> Self {
+        Self::prove_with_rng(participant, &mut OsRng)
+    }
+
+    /// like `prove`, drawing the proof nonce from `rng`.
+    pub fn prove_with_rng(participant: &Participant, rng: &mut impl CryptoRngCore) -> Self {
+        Self::prove_with_context_and_rng(participant, &[], rng)
+    }
+
+    /// like `prove`, bound to `context`.
+    #[cfg(feature = "std")]
+    pub fn prove_with_context(participant: &Participant, context: &[u8]) -> Self {
+        Self::prove_with_context_and_rng(participant, context, &mut OsRng)
+    }
+
+    /// like `prove_with_context`, drawing the proof nonce from `rng`.
+    pub fn prove_with_context_and_rng(
+        participant: &Participant,
+        context: &[u8],
+        rng: &mut impl CryptoRngCore,
+    ) -> Self {
+        Self {
+            id: participant.id,
+            proof: SchnorrProof::prove_with_rng(
+                &participant.x_i,
+                &participant.X_i,
+                &pop_context(participant.id, context),
+                rng,
+            ),
+        }
+    }
+
+    /// verify against the public share Xᵢ claimed for `self.id`.
+    pub fn verify(&self, X_i: &ProjectivePoint) -> bool {
+        self.verify_with_context(X_i, &[])
+    }
+
+    /// like `verify`, for a proof bound to `context`.
+    pub fn verify_with_context(&self, X_i: &ProjectivePoint, context: &[u8]) -> bool {
+        self.proof.verify(X_i, &pop_context(self.id, context))
+    }
+}
+
+/// check a proof of possession for every `(id, Xᵢ)`, `proofs` in any order.
+pub fn verify_public_shares(
+    public_shares: &[(u64, ProjectivePoint)],
+    proofs: &[ProofOfPossession],
+) -> Result<(), Error> {
+    verify_public_shares_with_context(public_shares, proofs, &[])
+}
+
+/// like `verify_public_shares`, for proofs bound to `context`.
+pub fn verify_public_shares_with_context(
+    public_shares: &[(u64, ProjectivePoint)],
+    proofs: &[ProofOfPossession],
+    context: &[u8],
+) -> Result<(), Error> {
+    for (id, X_i) in public_shares {
+        let valid = proofs
+            .iter()
+            .find(|proof| proof.id == *id)
+            .is_some_and(|proof| proof.verify_with_context(X_i, context));
+        if !valid {
+            return Err(Error::InvalidProofOfPossession { id: *id });
+        }
+    }
+
+    Ok(())
+}
+
+fn pop_context(id: u64, context: &[u8]) -> Vec<u8> {
+    let mut bound = POP_CONTEXT.to_vec();
+    bound.extend_from_slice(&id.to_be_bytes());
+    bound.extend_from_slice(context);
+    bound
+}
+
+fn proof_challenge(R: &ProjectivePoint, P: &ProjectivePoint, context: &[u8]) -> Scalar {
+    let mut hasher = scalar_hasher(SCHNORR_PROOF_TAG);
+    hasher.update((context.len() as u64).to_be_bytes());
+    hasher.update(context);
+    hasher.update(R.to_encoded_point(true).as_bytes());
+    hasher.update(P.to_encoded_point(true).as_bytes());
+
+    finalize_scalar(hasher)
+}
+
+//--------------------------------------------------------------------
+// DLEQ proof (Chaum–Pedersen, Fiat–Shamir)
+//--------------------------------------------------------------------
+//
+// proves log_G(A) == log_H(B), i.e. A = x*G and B = x*H for one x,
+// without revealing x
+//
+//   prover:   k ←$ Zq,  U = k*G,  V = k*H
+//             c = H(tag || ctx || G || A || H || B || U || V)
+//             z = k + c*x
+//
+//   verifier: U = z*G - c*A,  V = z*H - c*B
+//             c == H(tag || ctx || G || A || H || B || U || V)
+//
+// the proof is (c, z), U and V are recomputed. used for PVSS, decryption
+// shares D = x*C checked against X = x*G, and nonces derived from a
+// committed key.
+//
+// ⚠️ the statement is only meaningful if nobody knows log_G(H), take H
+//    from `vrf::hash_to_point` or another hash to curve.
+//
+
+/// non-interactive proof that `A = x*G` and `B = x*H` share the same x.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DleqProof {
+    pub c: Scalar,
+    pub z: Scalar,
+}
+
+/// prove that `x*G` and `x*H` have the same discrete log `x`.
+#[cfg(feature = "std")]
+pub fn prove(x: &Scalar, G: &ProjectivePoint, H: &ProjectivePoint, context: &[u8]) -> DleqProof {
+    prove_with_rng(x, G, H, context, &mut OsRng)
+}
+
+/// like `prove`, drawing the proof nonce from `rng`.
+pub fn prove_with_rng(
+    x: &Scalar,
+    G: &ProjectivePoint,
+    H: &ProjectivePoint,
+    context: &[u8],
+    rng: &mut impl CryptoRngCore,
+) -> DleqProof {
+    let k = Scalar::random(rng);
+    let c = dleq_challenge(context, [G, &(*G * x), H, &(*H * x), &(*G * k), &(*H * k)]);
+
+    DleqProof { c, z: k + c * x }
+}
+
+/// check `proof` for `A = x*G`, `B = x*H` and the same `context`.
+/// bases at the identity prove nothing and are rejected.
+pub fn verify(
+    proof: &DleqProof,
+    G: &ProjectivePoint,
+    A: &ProjectivePoint,
+    H: &ProjectivePoint,
+    B: &ProjectivePoint,
+    context: &[u8],
+) -> bool {
+    if *G == ProjectivePoint::IDENTITY || *H == ProjectivePoint::IDENTITY {
+        return false;
+    }
+    let U = *G * proof.z - *A * proof.c;
+    let V = *H * proof.z - *B * proof.c;
+
+    dleq_challenge(context, [G, A, H, B, &U, &V]) == proof.c
+}
+
+fn dleq_challenge(context: &[u8], points: [&ProjectivePoint; 6]) -> Scalar {
+    let mut hasher = scalar_hasher(DLEQ_TAG);
+    hasher.update((context.len() as u64).to_be_bytes());
+    hasher.update(context);
+    for P in points {
+        hasher.update(P.to_encoded_point(true).as_bytes());
+    }
+
+    finalize_scalar(hasher)
+}
@@ -0,0 +1,274 @@
+#![allow(non_snake_case)]
+
+use crate::error::Error;
+use crate::hazmat::{lagrange_coefficient_at, validate_ids};
+use crate::threshold::Participant;
+use crate::vss::{validate_commitments, verify_share};
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use k256::elliptic_curve::rand_core::OsRng;
+use k256::{
+    ProjectivePoint, Scalar,
+    elliptic_curve::{Field, rand_core::CryptoRngCore},
+};
+
+//--------------------------------------------------------------------
+// Share repair
+//--------------------------------------------------------------------
+//
+// t helpers H recompute the share of participant r, who lost it:
+//
+//   x_r = f(r) = Σ_{i∈H} λᵢ(r)·xᵢ
+//
+// [ROUND 1] helper i splits δᵢ = λᵢ(r)·xᵢ into random δᵢⱼ with Σⱼ δᵢⱼ = δᵢ
+//           and sends δᵢⱼ privately to every helper j (itself included)
+// [ROUND 2] helper j sends σⱼ = Σᵢ δᵢⱼ privately to r
+// [FINAL]   r computes x_r = Σⱼ σⱼ and checks it against the commitments
+//
+// a single δᵢⱼ or σⱼ is uniformly random, so no helper learns another
+// helper's δᵢ, and r only learns x_r. the group secret is never formed.
+//
+// ⚠️ round 1 and round 2 messages are secret, send them over private
+//    channels only. r alone must see the σⱼ.
+//
+
+/// private round 1 message carrying δᵢⱼ from helper `sender` to helper `receiver`.
+#[derive(Debug, Clone, Copy)]
+pub struct RepairPackage {
+    pub sender: u64,
+    pub receiver: u64,
+    pub delta: Scalar,
+}
+
+/// private round 2 message carrying σⱼ from helper `sender` to the lost participant.
+#[derive(Debug, Clone, Copy)]
+pub struct RepairSum {
+    pub sender: u64,
+    pub sigma: Scalar,
+}
+
+/// round 1 of `helper`, one of `helpers`, to repair the share of `lost`.
+/// returns one package for every helper, including `helper` itself.
+#[cfg(feature = "std")]
+pub fn repair_round1(
+    helper: &Participant,
+    helpers: &[u64],
+    lost: u64,
+) -> Result<Vec<RepairPackage>, Error> {
+    repair_round1_with_rng(helper, helpers, lost, &mut OsRng)
+}
+
+/// like `repair_round1`, drawing the random split from `rng`.
+pub fn repair_round1_with_rng(
+    helper: &Participant,
+    helpers: &[u64],
+    lost: u64,
+    rng: &mut impl CryptoRngCore,
+) -> Result<Vec<RepairPackage>, Error> {
+    validate_helpers(helpers, lost)?;
+    if !helpers.contains(&helper.id) {
+        return Err(Error::UnexpectedPackage {
+            round: 1,
+            id: helper.id,
+        });
+    }
+
+    let delta = lagrange_coefficient_at(helper.id, helpers, lost) * helper.x_i;
+    let mut rest = delta;
+    let mut packages = Vec::with_capacity(helpers.len());
+    for (k, &receiver) in helpers.iter().enumerate() {
+        let delta = match k + 1 == helpers.len() {
+            true => rest,
+            false => Scalar::random(&mut *rng),
+        };
+        rest -= delta;
+        packages.push(RepairPackage {
+            sender: helper.id,
+            receiver,
+            delta,
+        });
+    }
+
+    Ok(packages)
+}
+
+/// round 2 of helper `id`: sum the δᵢⱼ every helper sent to it.
+pub fn repair_round2(
+    id: u64,
+    helpers: &[u64],
+    lost: u64,
+    packages: &[RepairPackage],
+) -> Result<RepairSum, Error> {
+    validate_helpers(helpers, lost)?;
+    if let Some(package) = packages
+        .iter()
+        .find(|p| p.receiver != id || !helpers.contains(&p.sender))
+    {
+        return Err(Error::UnexpectedPackage {
+            round: 1,
+            id: package.sender,
+        });
+    }
+
+    let mut sigma = Scalar::ZERO;
+    for &sender in helpers {
+        let received = packages
+            .iter()
+            .filter(|p| p.sender == sender)
+            .collect::<Vec<_>>();
+        match received.as_slice() {
+            [package] => sigma += package.delta,
+            [] => {
+                return Err(Error::MissingPackage {
+                    round: 1,
+                    id: sender,
+                });
+            }
+            _ => {
+                return Err(Error::DuplicatePackage {
+                    round: 1,
+                    id: sender,
+                });
+            }
+        }
+    }
+
+    Ok(RepairSum { sender: id, sigma })
+}
+
+/// final step of the lost participant: add up the σⱼ of all helpers and
+/// check the share against the group commitments.
+pub fn repair_finalize(
+    lost: u64,
+    helpers: &[u64],
+    sums: &[RepairSum],
+    commitments: &[ProjectivePoint],
+) -> Result<Participant, Error> {
+    validate_helpers(helpers, lost)?;
+    validate_commitments(commitments, commitments.len())?;
+    if helpers.len() < commitments.len() {
+        return Err(Error::NotEnoughShares {
+            threshold: commitments.len(),
+            actual: helpers.len(),
+        });
+    }
+    if let Some(sum) = sums.iter().find(|s| !helpers.contains(&s.sender)) {
+        return Err(Error::UnexpectedPackage {
+            round: 2,
+            id: sum.sender,
+        });
+    }
+
+    let mut x_r = Scalar::ZERO;
+    for &sender in helpers {
+        let received = sums
+            .iter()
+            .filter(|s| s.sender == sender)
+            .collect::<Vec<_>>();
+        match received.as_slice() {
+            [sum] => x_r += sum.sigma,
+            [] => {
+                return Err(Error::MissingPackage {
+                    round: 2,
+                    id: sender,
+                });
+            }
+            _ => {
+                return Err(Error::DuplicatePackage {
+                    round: 2,
+                    id: sender,
+                });
+            }
+        }
+    }
+
+    if !verify_share(lost, x_r, commitments) {
+        return Err(Error::InvalidShare { id: lost });
+    }
+    Ok(Participant::from_secret(lost, x_r))
+}
+
+//--------------------------------------------------------------------
+// Enrollment
+//--------------------------------------------------------------------
+//
+// the same rounds at an id that never had a share give a new participant
+// x_new = f(new) on the existing polynomial, so the group grows without a
+// dealer and the public key and commitments stay the same:
+//
+//   X_new = Σₖ Cₖ·newᵏ
+//
+// the math can't tell an enrollment from a repair, so the enroll_* rounds
+// take the ids that already hold a share and refuse to issue one of them
+// again. each party checks with the roster it knows.
+//
+// ⚠️ the threshold doesn't change, t of the grown group can still sign.
+//
+
+#[cfg(feature = "std")]
+pub fn enroll_round1(
+    helper: &Participant,
+    helpers: &[u64],
+    new_id: u64,
+    enrolled: &[u64],
+) -> Result<Vec<RepairPackage>, Error> {
+    enroll_round1_with_rng(helper, helpers, new_id, enrolled, &mut OsRng)
+}
+
+/// `repair_round1_with_rng` at `new_id`, which must not be in `enrolled`.
+pub fn enroll_round1_with_rng(
+    helper: &Participant,
+    helpers: &[u64],
+    new_id: u64,
+    enrolled: &[u64],
+    rng: &mut impl CryptoRngCore,
+) -> Result<Vec<RepairPackage>, Error> {
+    validate_new_id(new_id, enrolled)?;
+    repair_round1_with_rng(helper, helpers, new_id, rng)
+}
+
+/// `repair_round2` at `new_id`, which must not be in `enrolled`.
+pub fn enroll_round2(
+    id: u64,
+    helpers: &[u64],
+    new_id: u64,
+    enrolled: &[u64],
+    packages: &[RepairPackage],
+) -> Result<RepairSum, Error> {
+    validate_new_id(new_id, enrolled)?;
+    repair_round2(id, helpers, new_id, packages)
+}
+
+/// `repair_finalize` at `new_id`, which must not be in `enrolled`.
+pub fn enroll_finalize(
+    new_id: u64,
+    helpers: &[u64],
+    sums: &[RepairSum],
+    commitments: &[ProjectivePoint],
+    enrolled: &[u64],
+) -> Result<Participant, Error> {
+    validate_new_id(new_id, enrolled)?;
+    repair_finalize(new_id, helpers, sums, commitments)
+}
+
+fn validate_new_id(new_id: u64, enrolled: &[u64]) -> Result<(), Error> {
+    match enrolled.contains(&new_id) {
+        true => Err(Error::DuplicateParticipantId(new_id)),
+        false => Ok(()),
+    }
+}
+
+/// distinct non-zero helpers, without the participant being repaired.
+fn validate_helpers(helpers: &[u64], lost: u64) -> Result<(), Error> {
+    validate_ids(helpers)?;
+    if lost == 0 {
+        return Err(Error::InvalidParticipantId {
+            id: 0,
+            num_shares: helpers.len(),
+        });
+    }
+    match helpers.contains(&lost) {
+        true => Err(Error::DuplicateParticipantId(lost)),
+        false => Ok(()),
+    }
+}
@@ -0,0 +1,186 @@
+#![allow(non_snake_case)]
+
+use crate::dkg::DkgOutput;
+use crate::error::Error;
+use crate::hazmat::{self, lagrange_coefficient, validate_ids};
+use crate::limits::Limits;
+use crate::threshold::Participant;
+use crate::vss::{calculate_commitment, public_share, validate_dealing, verify_share};
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use k256::elliptic_curve::rand_core::OsRng;
+use k256::{ProjectivePoint, Scalar, elliptic_curve::rand_core::CryptoRngCore};
+
+//--------------------------------------------------------------------
+// Resharing (t-of-n → t'-of-n')
+//--------------------------------------------------------------------
+//
+// t old participants S (the dealers) move the key to n' new ids with a
+// new threshold t'. every dealer i shares its weighted share with a fresh
+// polynomial of degree t'-1:
+//
+//   hᵢ(0) = λᵢ·xᵢ     (λᵢ over S, so Σᵢ hᵢ(0) = f(0))
+//
+// [ROUND 1] broadcast Hᵢₖ = aᵢₖ·G, send hᵢ(j) privately to every new j
+// [FINAL]   new participant j checks every hᵢ(j) against Hᵢ and every
+//           Hᵢ₀ against the old public share, λᵢ·Xᵢ, then
+//
+//   xⱼ' = Σᵢ hᵢ(j),   Cₖ' = Σᵢ Hᵢₖ,   X = C₀' = C₀
+//
+// the group key doesn't change, only the shares, the threshold and the
+// set of ids. old and new shares don't combine.
+//
+// ⚠️ delete the old shares once every new participant has finalized,
+//    t of them still hold the key.
+//
+
+/// public round 1 message of a dealer, broadcast to every new participant.
+#[derive(Debug, Clone)]
+pub struct ReshareCommitment {
+    pub sender: u64,
+    /// Hᵢₖ, t' commitments to the dealer's new polynomial.
+    pub commitments: Vec<ProjectivePoint>,
+}
+
+/// private round 1 message carrying hᵢ(j) from dealer `sender` to new participant `receiver`.
+#[derive(Debug, Clone, Copy)]
+pub struct ResharePackage {
+    pub sender: u64,
+    pub receiver: u64,
+    pub share: Scalar,
+}
+
+/// round 1 of old participant `dealer`, one of `dealers`, for a
+/// `new_threshold`-of-`new_ids` group.
+#[cfg(feature = "std")]
+pub fn reshare_round1(
+    dealer: &Participant,
+    dealers: &[u64],
+    new_threshold: usize,
+    new_ids: &[u64],
+) -> Result<(ReshareCommitment, Vec<ResharePackage>), Error> {
+    reshare_round1_with_rng(dealer, dealers, new_threshold, new_ids, &mut OsRng)
+}
+
+/// like `reshare_round1`, drawing the polynomial from `rng`.
+pub fn reshare_round1_with_rng(
+    dealer: &Participant,
+    dealers: &[u64],
+    new_threshold: usize,
+    new_ids: &[u64],
+    rng: &mut impl CryptoRngCore,
+) -> Result<(ReshareCommitment, Vec<ResharePackage>), Error> {
+    validate_ids(dealers)?;
+    validate_new_group(new_threshold, new_ids)?;
+    if !dealers.contains(&dealer.id) {
+        return Err(Error::UnexpectedPackage {
+            round: 1,
+            id: dealer.id,
+        });
+    }
+
+    let weighted = lagrange_coefficient(dealer.id, dealers) * dealer.x_i;
+    let coefficients = hazmat::random_polynomial_with_rng(weighted, new_threshold, rng);
+    let packages = new_ids
+        .iter()
+        .map(|&receiver| ResharePackage {
+            sender: dealer.id,
+            receiver,
+            share: hazmat::eval_polynomial(&coefficients, receiver),
+        })
+        .collect();
+
+    Ok((
+        ReshareCommitment {
+            sender: dealer.id,
+            commitments: coefficients
+                .iter()
+                .map(|a| calculate_commitment(*a))
+                .collect(),
+        },
+        packages,
+    ))
+}
+
+/// new participant `id`, one of `new_ids`: check what every dealer sent against the old
+/// commitments and derive the new share and commitments.
+pub fn reshare_finalize(
+    id: u64,
+    dealers: &[u64],
+    new_threshold: usize,
+    new_ids: &[u64],
+    old_commitments: &[ProjectivePoint],
+    commitments: &[ReshareCommitment],
+    packages: &[ResharePackage],
+) -> Result<DkgOutput, Error> {
+    validate_ids(dealers)?;
+    validate_new_group(new_threshold, new_ids)?;
+    if !new_ids.contains(&id) {
+        return Err(Error::InvalidParticipantId {
+            id,
+            num_shares: new_ids.len(),
+        });
+    }
+    if dealers.len() < old_commitments.len() {
+        return Err(Error::NotEnoughShares {
+            threshold: old_commitments.len(),
+            actual: dealers.len(),
+        });
+    }
+    if let Some(unexpected) = commitments
+        .iter()
+        .map(|c| c.sender)
+        .chain(packages.iter().map(|p| p.sender))
+        .find(|sender| !dealers.contains(sender))
+    {
+        return Err(Error::UnexpectedPackage {
+            round: 1,
+            id: unexpected,
+        });
+    }
+
+    let mut x_j = Scalar::ZERO;
+    let mut new_commitments = alloc::vec![ProjectivePoint::IDENTITY; new_threshold];
+    for &dealer in dealers {
+        let commitment = exactly_one(dealer, commitments.iter().filter(|c| c.sender == dealer))?;
+        let package = exactly_one(
+            dealer,
+            packages
+                .iter()
+                .filter(|p| p.sender == dealer && p.receiver == id),
+        )?;
+
+        validate_dealing(dealer, &commitment.commitments, new_threshold)?;
+        // the dealer must pass on its own share, not any secret
+        let X_i = public_share(dealer, old_commitments);
+        if commitment.commitments[0] != X_i * lagrange_coefficient(dealer, dealers)
+            || !verify_share(id, package.share, &commitment.commitments)
+        {
+            return Err(Error::InvalidShare { id: dealer });
+        }
+
+        x_j += package.share;
+        for (acc, H_k) in new_commitments.iter_mut().zip(&commitment.commitments) {
+            *acc += H_k;
+        }
+    }
+
+    Ok(DkgOutput {
+        participant: Participant::from_secret(id, x_j),
+        public_key: new_commitments[0],
+        commitments: new_commitments,
+    })
+}
+
+fn exactly_one<'a, T>(id: u64, mut received: impl Iterator<Item = &'a T>) -> Result<&'a T, Error> {
+    match (received.next(), received.next()) {
+        (Some(item), None) => Ok(item),
+        (None, _) => Err(Error::MissingPackage { round: 1, id }),
+        (Some(_), Some(_)) => Err(Error::DuplicatePackage { round: 1, id }),
+    }
+}
+
+fn validate_new_group(new_threshold: usize, new_ids: &[u64]) -> Result<(), Error> {
+    validate_ids(new_ids)?;
+    Limits::default().check(new_threshold, new_ids.len())
+}
@@ -0,0 +1,524 @@
+#![allow(non_snake_case)]
+
+use crate::error::Error;
+use crate::hazmat::{lagrange_coefficient, validate_ids};
+use crate::schnorr::SchnorrSignature;
+use crate::threshold::{CulpritReport, Misbehavior, Participant};
+use crate::util::{PointEncoding, bytes_to_pp, ensure_not_identity};
+use alloc::{format, string::ToString, vec::Vec};
+use core::fmt;
+#[cfg(feature = "std")]
+use k256::elliptic_curve::rand_core::OsRng;
+use k256::{
+    FieldBytes, ProjectivePoint, Scalar,
+    elliptic_curve::{PrimeField, bigint::U512, ops::Reduce, rand_core::CryptoRngCore},
+};
+use sha2::{Digest, Sha256};
+
+/// contextString of the FROST(secp256k1, SHA-256) ciphersuite.
+pub const CONTEXT_STRING: &[u8] = b"FROST-secp256k1-SHA256-v1";
+
+//--------------------------------------------------------------------
+// RFC 9591 interop (FROST(secp256k1, SHA-256))
+//--------------------------------------------------------------------
+//
+// the ciphersuite of `frost-secp256k1`, for quorums that mix shamy with
+// other RFC 9591 implementations. the shares are the same, x_i = f(i)
+// with i the id as a scalar, and so is the group key. what differs from
+// `frost.rs` is the hashing and where λ_i goes:
+//
+//   H1..H3(m) = hash_to_field(m, contextString || "rho" | "chal" | "nonce")
+//   H4(m)     = SHA-256(contextString || "msg" || m)
+//   H5(m)     = SHA-256(contextString || "com" || m)
+//
+//   [ROUND 1] d_i = H3(32 random bytes || x_i), e_i likewise
+//             publish (D_i, E_i) = (d_i*G, e_i*G)
+//   [ROUND 2] B    = Σ (i || D_i || E_i), sorted by i
+//             ρ_i  = H1(X || H4(m) || H5(B) || i)
+//             R    = Σ (D_i + ρ_i*E_i)
+//             c    = H2(R || X || m)
+//             z_i  = d_i + ρ_i*e_i + λ_i*x_i*c
+//   [FINAL]   z    = Σ z_i,  signature (R, z)
+//
+// ids are 32-byte big-endian scalars, points 33-byte compressed and
+// scalars 32-byte big-endian, the encodings `frost-secp256k1` reads with
+// `deserialize`. hash_to_field is expand_message_xmd (RFC 9380) with
+// SHA-256 to 48 bytes, reduced mod q.
+//
+// signatures verify with `verify` here, not with `SigningConfig`, the
+// challenge has no tag, context or framing.
+//
+// ⚠️ shares from `frost.rs` or `threshold.rs` partials are not z_i,
+//    every signer of a mixed quorum has to run these rounds.
+// ⚠️ a `SigningNonces` is consumed by `sign`, never keep a copy.
+//
+
+/// id as an RFC 9591 identifier, a 32-byte big-endian scalar.
+pub fn serialize_identifier(id: u64) -> [u8; 32] {
+    Scalar::from(id).to_bytes().into()
+}
+
+/// parse an identifier back into an id. fails for identifiers that
+/// aren't a small integer, like the ones `Identifier::derive` makes.
+pub fn deserialize_identifier(bytes: &[u8]) -> Result<u64, Error> {
+    let scalar = deserialize_scalar(bytes)?;
+    let bytes = scalar.to_bytes();
+    if bytes[..24].iter().any(|b| *b != 0) || scalar == Scalar::ZERO {
+        return Err(Error::InvalidEncoding(
+            "identifier is not a u64 id".to_string(),
+        ));
+    }
+
+    Ok(u64::from_be_bytes(bytes[24..].try_into().unwrap()))
+}
+
+/// SerializeElement, 33-byte compressed. the identity has no encoding.
+pub fn serialize_element(point: &ProjectivePoint) -> Result<[u8; 33], Error> {
+    ensure_not_identity(point)?;
+    Ok(PointEncoding::Compressed
+        .encode(point)
+        .as_bytes()
+        .try_into()
+        .unwrap())
+}
+
+pub fn deserialize_element(bytes: &[u8]) -> Result<ProjectivePoint, Error> {
+    if bytes.len() != 33 {
+        return Err(Error::InvalidEncoding(format!(
+            "an element is 33 bytes, got {}",
+            bytes.len()
+        )));
+    }
+    bytes_to_pp(bytes)
+}
+
+/// SerializeScalar, 32-byte big-endian.
+pub fn serialize_scalar(scalar: &Scalar) -> [u8; 32] {
+    scalar.to_bytes().into()
+}
+
+/// rejects non-canonical scalars, i.e. ≥ q.
+pub fn deserialize_scalar(bytes: &[u8]) -> Result<Scalar, Error> {
+    if bytes.len() != 32 {
+        return Err(Error::InvalidEncoding(format!(
+            "a scalar is 32 bytes, got {}",
+            bytes.len()
+        )));
+    }
+    Scalar::from_repr(*FieldBytes::from_slice(bytes))
+        .into_option()
+        .ok_or(Error::InvalidScalar)
+}
+
+/// the fields of a `frost-secp256k1` `KeyPackage`, in RFC 9591 encodings.
+#[derive(Clone, PartialEq, Eq)]
+pub struct KeyPackage {
+    pub identifier: [u8; 32],
+    pub signing_share: [u8; 32],
+    pub verifying_share: [u8; 33],
+    pub verifying_key: [u8; 33],
+    pub min_signers: u16,
+}
+
+impl fmt::Debug for KeyPackage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyPackage")
+            .field("identifier", &hex::encode(self.identifier))
+            .field("verifying_share", &hex::encode(self.verifying_share))
+            .field("verifying_key", &hex::encode(self.verifying_key))
+            .field("min_signers", &self.min_signers)
+            .finish_non_exhaustive()
+    }
+}
+
+impl KeyPackage {
+    /// key package of `participant` in a `threshold`-of-n group with key `public_key`.
+    pub fn new(
+        participant: &Participant,
+        public_key: &ProjectivePoint,
+        threshold: usize,
+    ) -> Result<Self, Error> {
+        let min_signers = u16::try_from(threshold)
+            .map_err(|_| Error::InvalidEncoding("threshold exceeds u16".to_string()))?;
+
+        Ok(Self {
+            identifier: serialize_identifier(participant.id),
+            signing_share: serialize_scalar(&participant.x_i),
+            verifying_share: serialize_element(&participant.X_i)?,
+            verifying_key: serialize_element(public_key)?,
+            min_signers,
+        })
+    }
+
+    /// the participant, checking the verifying share against the signing share.
+    pub fn participant(&self) -> Result<Participant, Error> {
+        let id = deserialize_identifier(&self.identifier)?;
+        let participant = Participant::from_secret(id, deserialize_scalar(&self.signing_share)?);
+        if serialize_element(&participant.X_i)? != self.verifying_share {
+            return Err(Error::InvalidShare { id });
+        }
+
+        Ok(participant)
+    }
+
+    pub fn public_key(&self) -> Result<ProjectivePoint, Error> {
+        deserialize_element(&self.verifying_key)
+    }
+}
+
+/// secret hiding and binding nonce of one signing session.
+pub struct SigningNonces {
+    hiding: Scalar,
+    binding: Scalar,
+}
+
+impl fmt::Debug for SigningNonces {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SigningNonces").finish_non_exhaustive()
+    }
+}
+
+impl SigningNonces {
+    /// nonce_generate of both nonces from the given randomness.
+    /// ⚠️ for known-answer tests, use `commit` otherwise.
+    pub fn from_randomness(
+        hiding_randomness: &[u8; 32],
+        binding_randomness: &[u8; 32],
+        secret: &Scalar,
+    ) -> Self {
+        Self {
+            hiding: nonce_generate(hiding_randomness, secret),
+            binding: nonce_generate(binding_randomness, secret),
+        }
+    }
+
+    /// the public commitments of signer `id` to these nonces.
+    pub fn commitments(&self, id: u64) -> SigningCommitments {
+        SigningCommitments {
+            id,
+            hiding: ProjectivePoint::GENERATOR * self.hiding,
+            binding: ProjectivePoint::GENERATOR * self.binding,
+        }
+    }
+}
+
+/// round 1 message of signer `id`, (D_i, E_i).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SigningCommitments {
+    pub id: u64,
+    pub hiding: ProjectivePoint,
+    pub binding: ProjectivePoint,
+}
+
+impl SigningCommitments {
+    /// D_i || E_i, both compressed.
+    pub fn to_bytes(&self) -> Result<[u8; 66], Error> {
+        let mut out = [0u8; 66];
+        out[..33].copy_from_slice(&serialize_element(&self.hiding)?);
+        out[33..].copy_from_slice(&serialize_element(&self.binding)?);
+        Ok(out)
+    }
+
+    pub fn from_bytes(id: u64, bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != 66 {
+            return Err(Error::InvalidEncoding(format!(
+                "commitments are 66 bytes, got {}",
+                bytes.len()
+            )));
+        }
+
+        Ok(Self {
+            id,
+            hiding: deserialize_element(&bytes[..33])?,
+            binding: deserialize_element(&bytes[33..])?,
+        })
+    }
+}
+
+/// round 2 message of signer `id`, z_i.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignatureShare {
+    pub id: u64,
+    pub z: Scalar,
+}
+
+/// round 1: fresh nonces for `participant` and the commitments to publish.
+#[cfg(feature = "std")]
+pub fn commit(participant: &Participant) -> (SigningNonces, SigningCommitments) {
+    commit_with_rng(participant, &mut OsRng)
+}
+
+/// like `commit`, drawing the randomness from `rng`.
+pub fn commit_with_rng(
+    participant: &Participant,
+    rng: &mut impl CryptoRngCore,
+) -> (SigningNonces, SigningCommitments) {
+    let (mut hiding, mut binding) = ([0u8; 32], [0u8; 32]);
+    rng.fill_bytes(&mut hiding);
+    rng.fill_bytes(&mut binding);
+    let nonces = SigningNonces::from_randomness(&hiding, &binding, &participant.x_i);
+    let commitments = nonces.commitments(participant.id);
+
+    (nonces, commitments)
+}
+
+/// ρ_i of every signer in `commitments`, sorted by id.
+pub fn binding_factors(
+    commitments: &[SigningCommitments],
+    public_key: &ProjectivePoint,
+    message: &[u8],
+) -> Result<Vec<(u64, Scalar)>, Error> {
+    let commitments = sorted(commitments)?;
+
+    let mut encoded = Vec::with_capacity(commitments.len() * 98);
+    for commitment in &commitments {
+        encoded.extend(serialize_identifier(commitment.id));
+        encoded.extend(commitment.to_bytes()?);
+    }
+    let mut prefix = serialize_element(public_key)?.to_vec();
+    prefix.extend(H4(message));
+    prefix.extend(H5(&encoded));
+
+    Ok(commitments
+        .iter()
+        .map(|commitment| {
+            let mut input = prefix.clone();
+            input.extend(serialize_identifier(commitment.id));
+            (commitment.id, H1(&input))
+        })
+        .collect())
+}
+
+/// R = Σ (D_i + ρ_i*E_i).
+pub fn group_commitment(
+    commitments: &[SigningCommitments],
+    public_key: &ProjectivePoint,
+    message: &[u8],
+) -> Result<ProjectivePoint, Error> {
+    let factors = binding_factors(commitments, public_key, message)?;
+    let R = sorted(commitments)?
+        .iter()
+        .zip(&factors)
+        .map(|(commitment, (_, rho))| commitment.hiding + commitment.binding * rho)
+        .sum();
+    ensure_not_identity(&R)?;
+
+    Ok(R)
+}
+
+/// c = H2(R || X || m).
+pub fn challenge(
+    R: &ProjectivePoint,
+    public_key: &ProjectivePoint,
+    message: &[u8],
+) -> Result<Scalar, Error> {
+    let mut input = serialize_element(R)?.to_vec();
+    input.extend(serialize_element(public_key)?);
+    input.extend_from_slice(message);
+
+    Ok(H2(&input))
+}
+
+/// round 2: z_i of `participant` over `message`. `commitments` are the ones
+/// of every signer, including this one, which must match `nonces`.
+pub fn sign(
+    participant: &Participant,
+    nonces: SigningNonces,
+    commitments: &[SigningCommitments],
+    public_key: &ProjectivePoint,
+    message: &[u8],
+) -> Result<SignatureShare, Error> {
+    let id = participant.id;
+    if !commitments.contains(&nonces.commitments(id)) {
+        return Err(Error::MissingPackage { round: 1, id });
+    }
+
+    let (rho, lambda, c, _) = signer_terms(id, commitments, public_key, message)?;
+    Ok(SignatureShare {
+        id,
+        z: nonces.hiding + nonces.binding * rho + lambda * participant.x_i * c,
+    })
+}
+
+/// z_i*G == D_i + ρ_i*E_i + λ_i*c*X_i for the share of signer `share.id`
+/// with public share `X_i`.
+pub fn verify_signature_share(
+    share: &SignatureShare,
+    X_i: &ProjectivePoint,
+    commitments: &[SigningCommitments],
+    public_key: &ProjectivePoint,
+    message: &[u8],
+) -> bool {
+    match signer_terms(share.id, commitments, public_key, message) {
+        Ok((rho, lambda, c, commitment)) => {
+            ProjectivePoint::GENERATOR * share.z
+                == commitment.hiding + commitment.binding * rho + *X_i * (lambda * c)
+        }
+        Err(_) => false,
+    }
+}
+
+/// z = Σ z_i over one share per signer in `commitments`. if (R, z) doesn't
+/// verify, every share is checked against `public_shares` and the call
+/// fails with `Error::Misbehavior` naming the bad signers.
+pub fn aggregate(
+    commitments: &[SigningCommitments],
+    shares: &[SignatureShare],
+    public_shares: &[(u64, ProjectivePoint)],
+    public_key: &ProjectivePoint,
+    message: &[u8],
+) -> Result<SchnorrSignature, Error> {
+    let ids = sorted(commitments)?
+        .iter()
+        .map(|c| c.id)
+        .collect::<Vec<_>>();
+    let mut share_ids = shares.iter().map(|s| s.id).collect::<Vec<_>>();
+    share_ids.sort_unstable();
+    if share_ids != ids {
+        return Err(Error::InvalidEncoding(
+            "need exactly one share per commitment".to_string(),
+        ));
+    }
+
+    let signature = SchnorrSignature {
+        R: group_commitment(commitments, public_key, message)?,
+        s: shares.iter().map(|share| share.z).sum(),
+    }
+    .normalize()?;
+    if verify(&signature, public_key, message) {
+        return Ok(signature);
+    }
+
+    let culprits = shares
+        .iter()
+        .filter_map(|share| {
+            let misbehavior = match public_shares.iter().find(|(id, _)| *id == share.id) {
+                None => Misbehavior::UnknownSigner,
+                Some((_, X_i))
+                    if !verify_signature_share(share, X_i, commitments, public_key, message) =>
+                {
+                    Misbehavior::InvalidPartial
+                }
+                _ => return None,
+            };
+            Some((share.id, misbehavior))
+        })
+        .collect::<Vec<_>>();
+    match culprits.is_empty() {
+        true => Err(Error::InvalidSignature),
+        false => Err(Error::Misbehavior(CulpritReport { culprits })),
+    }
+}
+
+/// z*G == R + c*X with the RFC 9591 challenge, what `VerifyingKey::verify`
+/// of `frost-secp256k1` checks. the signature encodes as `to_bytes`.
+pub fn verify(signature: &SchnorrSignature, public_key: &ProjectivePoint, message: &[u8]) -> bool {
+    match challenge(&signature.R, public_key, message) {
+        Ok(c) => ProjectivePoint::GENERATOR * signature.s == signature.R + *public_key * c,
+        Err(_) => false,
+    }
+}
+
+/// ρ_i, λ_i, c and the commitments of signer `id`.
+fn signer_terms(
+    id: u64,
+    commitments: &[SigningCommitments],
+    public_key: &ProjectivePoint,
+    message: &[u8],
+) -> Result<(Scalar, Scalar, Scalar, SigningCommitments), Error> {
+    let commitment = *commitments
+        .iter()
+        .find(|c| c.id == id)
+        .ok_or(Error::MissingPackage { round: 1, id })?;
+    let factors = binding_factors(commitments, public_key, message)?;
+    let rho = factors.iter().find(|(i, _)| *i == id).unwrap().1;
+    let ids = factors.iter().map(|(i, _)| *i).collect::<Vec<_>>();
+    let R = group_commitment(commitments, public_key, message)?;
+
+    Ok((
+        rho,
+        lagrange_coefficient(id, &ids),
+        challenge(&R, public_key, message)?,
+        commitment,
+    ))
+}
+
+fn sorted(commitments: &[SigningCommitments]) -> Result<Vec<SigningCommitments>, Error> {
+    let mut sorted = commitments.to_vec();
+    sorted.sort_by_key(|c| c.id);
+    validate_ids(&sorted.iter().map(|c| c.id).collect::<Vec<_>>())?;
+    Ok(sorted)
+}
+
+fn nonce_generate(randomness: &[u8; 32], secret: &Scalar) -> Scalar {
+    let mut input = randomness.to_vec();
+    input.extend(serialize_scalar(secret));
+    H3(&input)
+}
+
+fn H1(m: &[u8]) -> Scalar {
+    hash_to_field(m, b"rho")
+}
+
+fn H2(m: &[u8]) -> Scalar {
+    hash_to_field(m, b"chal")
+}
+
+fn H3(m: &[u8]) -> Scalar {
+    hash_to_field(m, b"nonce")
+}
+
+fn H4(m: &[u8]) -> [u8; 32] {
+    Sha256::new()
+        .chain_update(CONTEXT_STRING)
+        .chain_update(b"msg")
+        .chain_update(m)
+        .finalize()
+        .into()
+}
+
+fn H5(m: &[u8]) -> [u8; 32] {
+    Sha256::new()
+        .chain_update(CONTEXT_STRING)
+        .chain_update(b"com")
+        .chain_update(m)
+        .finalize()
+        .into()
+}
+
+/// hash_to_field with DST = contextString || label: 48 bytes of
+/// expand_message_xmd, reduced mod q.
+fn hash_to_field(m: &[u8], label: &[u8]) -> Scalar {
+    let mut wide = [0u8; 64];
+    expand_message_xmd(m, &[CONTEXT_STRING, label].concat(), &mut wide[16..]);
+    <Scalar as Reduce<U512>>::reduce_bytes(&wide.into())
+}
+
+/// expand_message_xmd of RFC 9380 with SHA-256, for up to 255 blocks.
+fn expand_message_xmd(msg: &[u8], dst: &[u8], out: &mut [u8]) {
+    let dst_len = [dst.len() as u8];
+    let b_0 = Sha256::new()
+        .chain_update([0u8; 64])
+        .chain_update(msg)
+        .chain_update((out.len() as u16).to_be_bytes())
+        .chain_update([0u8])
+        .chain_update(dst)
+        .chain_update(dst_len)
+        .finalize();
+
+    let mut b_i = [0u8; 32];
+    for (i, chunk) in out.chunks_mut(32).enumerate() {
+        let mut input = b_0;
+        for (x, b) in input.iter_mut().zip(b_i) {
+            *x ^= b;
+        }
+        b_i = Sha256::new()
+            .chain_update(input)
+            .chain_update([i as u8 + 1])
+            .chain_update(dst)
+            .chain_update(dst_len)
+            .finalize()
+            .into();
+        chunk.copy_from_slice(&b_i[..chunk.len()]);
+    }
+}
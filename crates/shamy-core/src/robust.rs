@@ -0,0 +1,241 @@
+#![allow(non_snake_case)]
+
+use crate::error::Error;
+use crate::hazmat::{LagrangeWeights, validate_ids};
+use crate::schnorr::{SchnorrSignature, SigningConfig};
+use crate::threshold::{
+    CulpritReport, Misbehavior, PartialSignature, SessionPhase, aggregate_nonce_with_weights,
+    finalize_signature_with_weights,
+};
+use crate::util::ensure_not_identity;
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    format, vec,
+    vec::Vec,
+};
+use k256::{ProjectivePoint, Scalar};
+
+//--------------------------------------------------------------------
+// Robust signing
+//--------------------------------------------------------------------
+//
+// a `SigningSession` fixes its t signers before the challenge, so one
+// signer that never answers aborts it. the challenge depends on R and R on
+// the signer set, so a partial can't be moved to another set, and signing
+// one nonce twice leaks the share. instead every attempt gets fresh nonces:
+//
+//   1. any number of signers offer a nonce Rᵢ (`add_nonce`)
+//   2. once t nonces are ready, the first t form an attempt with its own
+//      λᵢ, R = Σ λᵢ·Rᵢ and c (`SigningAttempt`), the nonces are used up
+//   3. a signer that answers with a valid sᵢ offers a new nonce for the
+//      next attempt, one that sends an invalid sᵢ is excluded for good
+//   4. the first attempt with t valid partials gives the signature
+//
+// signers that don't answer stay in their stalled attempt and never get
+// into a new one, the ones that did answer form the next attempts with
+// the spare signers. several attempts can run at the same time.
+//
+// ⚠️ a signer must answer each attempt with the nonce it offered for it
+//    and offer a new one afterwards, never reuse one across attempts.
+//
+
+/// one try at a signature by exactly t signers, sent to each of them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SigningAttempt {
+    pub attempt: usize,
+    pub signer_ids: Vec<u64>,
+    pub R: ProjectivePoint,
+    pub c: Scalar,
+}
+
+#[derive(Debug, Clone)]
+struct Attempt {
+    public: SigningAttempt,
+    weights: LagrangeWeights,
+    nonces: BTreeMap<u64, ProjectivePoint>,
+    partials: BTreeMap<u64, Scalar>,
+}
+
+/// coordinator side of a signing session over more than t available signers.
+#[derive(Debug, Clone)]
+pub struct RobustSession {
+    public_key: ProjectivePoint,
+    message: Vec<u8>,
+    threshold: usize,
+    config: SigningConfig,
+    public_shares: BTreeMap<u64, ProjectivePoint>,
+    /// offered nonces not in an attempt yet, in order of arrival.
+    ready: Vec<(u64, ProjectivePoint)>,
+    attempts: Vec<Attempt>,
+    excluded: BTreeSet<u64>,
+    signature: Option<SchnorrSignature>,
+}
+
+impl RobustSession {
+    /// `public_shares` are (id, Xᵢ) of every participant that may sign,
+    /// from the keygen output.
+    pub fn new(
+        public_key: ProjectivePoint,
+        message: &[u8],
+        threshold: usize,
+        public_shares: &[(u64, ProjectivePoint)],
+        config: SigningConfig,
+    ) -> Result<Self, Error> {
+        config.check_bindings()?;
+        validate_ids(&public_shares.iter().map(|(id, _)| *id).collect::<Vec<_>>())?;
+        if threshold == 0 || public_shares.len() < threshold {
+            return Err(Error::NotEnoughShares {
+                threshold,
+                actual: public_shares.len(),
+            });
+        }
+
+        Ok(Self {
+            public_key,
+            message: message.to_vec(),
+            threshold,
+            config,
+            public_shares: public_shares.iter().copied().collect(),
+            ready: Vec::new(),
+            attempts: Vec::new(),
+            excluded: BTreeSet::new(),
+            signature: None,
+        })
+    }
+
+    pub fn phase(&self) -> SessionPhase {
+        match (self.signature, self.attempts.is_empty()) {
+            (Some(_), _) => SessionPhase::Finalized,
+            (None, true) => SessionPhase::CollectingNonces,
+            (None, false) => SessionPhase::CollectingPartials,
+        }
+    }
+
+    /// the signature, once an attempt completed.
+    pub fn signature(&self) -> Option<SchnorrSignature> {
+        self.signature
+    }
+
+    /// signers excluded for an invalid partial or by `exclude`.
+    pub fn excluded(&self) -> Vec<u64> {
+        self.excluded.iter().copied().collect()
+    }
+
+    /// signers asked in an open attempt that haven't answered yet.
+    pub fn pending(&self) -> Vec<u64> {
+        self.attempts
+            .iter()
+            .flat_map(|a| a.nonces.keys().filter(|id| !a.partials.contains_key(id)))
+            .copied()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// record a fresh nonce Rᵢ of signer `id`. returns the attempt to send
+    /// to its signers once t nonces are ready.
+    pub fn add_nonce(
+        &mut self,
+        id: u64,
+        R_i: ProjectivePoint,
+    ) -> Result<Option<SigningAttempt>, Error> {
+        self.expect_open()?;
+        if !self.public_shares.contains_key(&id) || self.excluded.contains(&id) {
+            return Err(Error::UnexpectedPackage { round: 1, id });
+        }
+        if self.ready.iter().any(|(ready, _)| *ready == id) {
+            return Err(Error::DuplicatePackage { round: 1, id });
+        }
+        ensure_not_identity(&R_i)?;
+        self.ready.push((id, R_i));
+
+        if self.ready.len() < self.threshold {
+            return Ok(None);
+        }
+        let nonces = self.ready.drain(..self.threshold).collect::<Vec<_>>();
+        let signer_ids = nonces.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+        let weights = LagrangeWeights::new(&signer_ids)?;
+        let R = aggregate_nonce_with_weights(&nonces, &weights)?;
+        let c = self.config.challenge(&R, &self.public_key, &self.message);
+        let public = SigningAttempt {
+            attempt: self.attempts.len(),
+            signer_ids,
+            R,
+            c,
+        };
+        self.attempts.push(Attempt {
+            public: public.clone(),
+            weights,
+            nonces: nonces.into_iter().collect(),
+            partials: BTreeMap::new(),
+        });
+
+        Ok(Some(public))
+    }
+
+    /// record the answer of a signer to `attempt`. returns the signature
+    /// once the attempt has t valid partials. an invalid partial fails with
+    /// `Error::Misbehavior` and excludes its sender from later attempts.
+    pub fn add_partial(
+        &mut self,
+        attempt: usize,
+        partial: PartialSignature,
+    ) -> Result<Option<SchnorrSignature>, Error> {
+        self.expect_open()?;
+        let id = partial.id;
+        let Some(current) = self.attempts.get_mut(attempt) else {
+            return Err(Error::UnexpectedPackage { round: 2, id });
+        };
+        let Some(R_i) = current.nonces.get(&id) else {
+            return Err(Error::UnexpectedPackage { round: 2, id });
+        };
+        if current.partials.contains_key(&id) {
+            return Err(Error::DuplicatePackage { round: 2, id });
+        }
+
+        let X_i = self.public_shares[&id];
+        if ProjectivePoint::GENERATOR * partial.s_i != *R_i + X_i * current.public.c {
+            self.exclude(id);
+            return Err(Error::Misbehavior(CulpritReport {
+                culprits: vec![(id, Misbehavior::InvalidPartial)],
+            }));
+        }
+        current.partials.insert(id, partial.s_i);
+        if current.partials.len() < self.threshold {
+            return Ok(None);
+        }
+
+        let partials = current
+            .partials
+            .iter()
+            .map(|(id, s_i)| PartialSignature::new(*id, *s_i))
+            .collect::<Vec<_>>();
+        let signature =
+            finalize_signature_with_weights(&partials, current.public.R, &current.weights)?;
+        if ProjectivePoint::GENERATOR * signature.s
+            != current.public.R + self.public_key * current.public.c
+        {
+            return Err(Error::InvalidSignature);
+        }
+        self.signature = Some(signature);
+
+        Ok(Some(signature))
+    }
+
+    /// keep signer `id` out of every later attempt, e.g. after a timeout.
+    /// its offered nonce, if any, is dropped.
+    pub fn exclude(&mut self, id: u64) {
+        self.excluded.insert(id);
+        self.ready.retain(|(ready, _)| *ready != id);
+    }
+
+    fn expect_open(&self) -> Result<(), Error> {
+        match self.signature {
+            None => Ok(()),
+            Some(_) => Err(Error::OutOfOrder {
+                expected: format!("{:?}", SessionPhase::CollectingPartials),
+                actual: format!("{:?}", SessionPhase::Finalized),
+            }),
+        }
+    }
+}
@@ -0,0 +1,854 @@
+#![allow(non_snake_case)]
+
+use crate::error::Error;
+#[cfg(feature = "merlin")]
+use crate::hash::transcript_scalar;
+use crate::hash::{finalize_scalar, scalar_hasher, tagged_hasher};
+use crate::util::{PointEncoding, bytes_to_pp, ensure_not_identity, has_even_y, pp_to_x_only};
+use alloc::{
+    borrow::Cow,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use hex::FromHex;
+#[cfg(feature = "std")]
+use k256::elliptic_curve::rand_core::OsRng;
+use k256::{
+    FieldBytes, ProjectivePoint, Scalar, U256,
+    elliptic_curve::{
+        Field, PrimeField, ops::Reduce, point::AffineCoordinates, rand_core::CryptoRngCore,
+        sec1::ToEncodedPoint,
+    },
+};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use sha2::{Digest, Sha256};
+
+/// protocol tag of the versioned challenge.
+pub const CHALLENGE_TAG: &[u8] = b"shamy/schnorr/challenge/v1";
+/// protocol tag of the challenge over a prehashed message.
+pub const PREHASHED_CHALLENGE_TAG: &[u8] = b"shamy/schnorr/challenge-prehashed/v1";
+const FRAME_TAG: &[u8] = b"shamy/schnorr/frame/v1";
+const NONCE_TAG: &[u8] = b"shamy/schnorr/nonce/v1";
+const SIGNER_SET_TAG: &[u8] = b"shamy/schnorr/signer-set/v1";
+const BIP340_CHALLENGE_TAG: &[u8] = b"BIP0340/challenge";
+/// protocol label of the merlin challenge transcript.
+#[cfg(feature = "merlin")]
+pub const MERLIN_CHALLENGE_LABEL: &[u8] = b"shamy/schnorr/challenge/merlin/v1";
+/// protocol label of the merlin transcript over a prehashed message.
+#[cfg(feature = "merlin")]
+pub const MERLIN_PREHASHED_CHALLENGE_LABEL: &[u8] = b"shamy/schnorr/challenge-prehashed/merlin/v1";
+/// group and hash the challenge is defined over.
+pub const CIPHERSUITE_ID: &[u8] = b"secp256k1-sha256";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SchnorrSignature {
+    pub R: ProjectivePoint, // r*G
+    pub s: Scalar,          // r + c*x
+}
+
+impl SchnorrSignature {
+    /// verify the Schnorr signature against the public key X, with the
+    /// legacy challenge over the raw `msg`.
+    /// ⚠️ no domain separation, see `SigningConfig::legacy_challenge`.
+    #[deprecated(note = "no domain separation, use `verify_with_config` with \
+                `SigningConfig::unframed().legacy_challenge()` for old signatures")]
+    pub fn verify(&self, msg: &[u8], X: &ProjectivePoint) -> bool {
+        let c = compute_challenge(&self.R, X, msg);
+        self.check(X, &c, &SigningConfig::unframed().legacy_challenge())
+    }
+
+    /// verify the signature of a message framed according to `config`.
+    pub fn verify_with_config(
+        &self,
+        msg: &[u8],
+        X: &ProjectivePoint,
+        config: &SigningConfig,
+    ) -> bool {
+        let c = config.challenge(&self.R, X, msg);
+        self.check(X, &c, config)
+    }
+
+    /// verify a signature made over `digest = prehash(m)`.
+    pub fn verify_prehashed(
+        &self,
+        digest: &[u8; 32],
+        X: &ProjectivePoint,
+        config: &SigningConfig,
+    ) -> bool {
+        let c = config.challenge_prehashed(&self.R, X, digest);
+        self.check(X, &c, config)
+    }
+
+    /// s*G == R + c*X. a BIP-340 verifier only sees x(R) and x(X) and
+    /// lifts both to even Y, so R must have even Y and X counts as ±X.
+    /// R at infinity never verifies.
+    fn check(&self, X: &ProjectivePoint, c: &Scalar, config: &SigningConfig) -> bool {
+        if self.R == ProjectivePoint::IDENTITY {
+            return false;
+        }
+        let X = match config.challenge_mode() {
+            ChallengeMode::Bip340 if !has_even_y(&self.R) => return false,
+            ChallengeMode::Bip340 if !has_even_y(X) => -*X,
+            _ => *X,
+        };
+        ProjectivePoint::GENERATOR * self.s == self.R + (X * c)
+    }
+
+    /// the canonical form of the signature: R in affine coordinates, so
+    /// equal signatures are equal field by field, and `Error::IdentityPoint`
+    /// for R at infinity. aggregation returns normalized signatures.
+    pub fn normalize(&self) -> Result<Self, Error> {
+        ensure_not_identity(&self.R)?;
+        Ok(Self {
+            R: self.R.to_affine().into(),
+            s: self.s,
+        })
+    }
+
+    /// strict decoding of R and s given apart, e.g. from a wire message.
+    /// R must be a 33 byte compressed point other than infinity and s a
+    /// 32 byte big-endian scalar below q, so every signature has exactly
+    /// one encoding.
+    pub fn from_parts(R: &[u8], s: &[u8]) -> Result<Self, Error> {
+        if R.len() != 33 {
+            return Err(Error::InvalidEncoding(alloc::format!(
+                "a signature nonce is a 33 byte compressed point, got {} bytes",
+                R.len()
+            )));
+        }
+        if s.len() != 32 {
+            return Err(Error::InvalidScalarLength(s.len()));
+        }
+        let s = Scalar::from_repr(*FieldBytes::from_slice(s))
+            .into_option()
+            .ok_or(Error::InvalidScalar)?;
+
+        Ok(Self {
+            R: bytes_to_pp(R)?,
+            s,
+        })
+    }
+
+    /// like `from_parts` over hex, R and s as printed by the CLI.
+    pub fn from_hex_parts(R: &str, s: &str) -> Result<Self, Error> {
+        let decode = |hex: &str| Vec::from_hex(hex).map_err(|e| Error::InvalidHex(e.to_string()));
+        Self::from_parts(&decode(R)?, &decode(s)?)
+    }
+
+    /// 65 byte compact encoding, compressed R || s.
+    /// an R at infinity has no 33 byte encoding and fails like in `normalize`.
+    pub fn to_bytes(&self) -> Result<[u8; 65], Error> {
+        ensure_not_identity(&self.R)?;
+        let mut out = [0u8; 65];
+        out[..33].copy_from_slice(PointEncoding::Compressed.encode(&self.R).as_bytes());
+        out[33..].copy_from_slice(&self.s.to_bytes());
+        Ok(out)
+    }
+
+    /// 64 byte compact encoding, x(R) || s.
+    /// the parity of R is dropped and read back as even, so this fails
+    /// for a signature whose R has odd Y.
+    pub fn to_bytes_x_only(&self) -> Result<[u8; 64], Error> {
+        ensure_not_identity(&self.R)?;
+        let R = self.R.to_affine();
+        if bool::from(R.y_is_odd()) {
+            return Err(Error::InvalidEncoding(
+                "nonce point has odd Y, use the 65 byte encoding".to_string(),
+            ));
+        }
+        let mut out = [0u8; 64];
+        out[..32].copy_from_slice(&R.x());
+        out[32..].copy_from_slice(&self.s.to_bytes());
+        Ok(out)
+    }
+
+    /// parse either compact encoding, told apart by length. decoding is
+    /// strict like `from_parts`, an s at or above q is `Error::InvalidScalar`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        match bytes.len() {
+            65 => Self::from_parts(&bytes[..33], &bytes[33..]),
+            64 => Self::from_parts(&[&[0x02], &bytes[..32]].concat(), &bytes[32..]),
+            len => Err(Error::InvalidEncoding(alloc::format!(
+                "a signature is 64 or 65 bytes, got {len}"
+            ))),
+        }
+    }
+}
+
+/// verify many `(message, public key, signature)` under the same `config`.
+/// with the `parallel` feature the signatures are checked on all cores.
+pub fn verify_batch(
+    signatures: &[(&[u8], ProjectivePoint, SchnorrSignature)],
+    config: &SigningConfig,
+) -> bool {
+    let valid = |(msg, X, signature): &(&[u8], ProjectivePoint, SchnorrSignature)| {
+        signature.verify_with_config(msg, X, config)
+    };
+
+    #[cfg(feature = "parallel")]
+    return signatures.par_iter().all(valid);
+    #[cfg(not(feature = "parallel"))]
+    signatures.iter().all(valid)
+}
+
+/// how a message is turned into the bytes that enter the challenge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MessageFraming {
+    /// m' = tagged_hash(frame tag, len(app_id) || app_id || len(purpose) || purpose || m)
+    /// lengths are 4-byte big-endian.
+    Canonical { app_id: String, purpose: String },
+    /// m' = m, only for interop with signers that do not frame messages.
+    Unframed,
+}
+
+/// how the challenge scalar is hashed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ChallengeMode {
+    /// domain separated, see `compute_challenge_v1`.
+    V1 { context: Vec<u8> },
+    /// c = SHA-256(R || X || m), only to verify signatures made before v1.
+    Legacy,
+    /// c = Keccak-256(R || X || eip191(m)) mod q, see `compute_challenge_ethereum`.
+    #[cfg(feature = "ethereum")]
+    Ethereum,
+    /// c = tagged_hash("BIP0340/challenge", x(R) || x(X) || m) mod q,
+    /// see `compute_challenge_bip340`.
+    Bip340,
+    /// drawn from a merlin transcript, see `compute_challenge_merlin`.
+    #[cfg(feature = "merlin")]
+    Merlin { context: Vec<u8> },
+}
+
+/// random identifier of one signing session, bound into the v1 challenge so
+/// a partial signature can't be replayed or spliced into another session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SessionId(pub [u8; 32]);
+
+impl SessionId {
+    #[cfg(feature = "std")]
+    pub fn random() -> Self {
+        Self::random_with_rng(&mut OsRng)
+    }
+
+    pub fn random_with_rng(rng: &mut impl CryptoRngCore) -> Self {
+        let mut id = [0u8; 32];
+        rng.fill_bytes(&mut id);
+        Self(id)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0)
+    }
+
+    pub fn from_hex(hex: &str) -> Result<Self, Error> {
+        let raw = Vec::from_hex(hex).map_err(|e| Error::InvalidHex(e.to_string()))?;
+        raw.try_into().map(Self).map_err(|raw: Vec<u8>| {
+            Error::InvalidEncoding(format!("session id is {} bytes, expected 32", raw.len()))
+        })
+    }
+}
+
+/// signing parameters shared by every participant of a ceremony.
+///
+/// framing binds each signature to an application and a purpose so that
+/// a signature produced for one protocol (e.g. a Bitcoin spend) can never
+/// be replayed as valid for another (e.g. an internal approval).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SigningConfig {
+    framing: MessageFraming,
+    challenge: ChallengeMode,
+    point_encoding: PointEncoding,
+    session: Option<SessionId>,
+    signer_set: Option<[u8; 32]>,
+}
+
+impl SigningConfig {
+    /// canonical framing for the given application and purpose.
+    pub fn new(app_id: &str, purpose: &str) -> Self {
+        Self {
+            framing: MessageFraming::Canonical {
+                app_id: app_id.to_string(),
+                purpose: purpose.to_string(),
+            },
+            challenge: ChallengeMode::V1 {
+                context: Vec::new(),
+            },
+            point_encoding: PointEncoding::Compressed,
+            session: None,
+            signer_set: None,
+        }
+    }
+
+    /// explicitly opt out of message framing.
+    pub fn unframed() -> Self {
+        Self {
+            framing: MessageFraming::Unframed,
+            challenge: ChallengeMode::V1 {
+                context: Vec::new(),
+            },
+            point_encoding: PointEncoding::Compressed,
+            session: None,
+            signer_set: None,
+        }
+    }
+
+    /// bind an application context string into the v1 challenge, or
+    /// into the merlin transcript if that is the challenge in use.
+    pub fn with_context(mut self, context: &[u8]) -> Self {
+        let context = context.to_vec();
+        self.challenge = match self.challenge {
+            #[cfg(feature = "merlin")]
+            ChallengeMode::Merlin { .. } => ChallengeMode::Merlin { context },
+            _ => ChallengeMode::V1 { context },
+        };
+        self
+    }
+
+    /// fall back to the pre-v1 challenge, for signatures made before it.
+    /// ⚠️ no domain separation, a challenge can collide with another protocol's hash.
+    pub fn legacy_challenge(mut self) -> Self {
+        self.challenge = ChallengeMode::Legacy;
+        self
+    }
+
+    /// hash the challenge with Keccak-256 over the EIP-191 personal message,
+    /// so that EVM contracts can verify the signature.
+    /// ⚠️ no domain separation beyond the EIP-191 prefix, frame the message
+    /// or pick `unframed` only if the contract expects the raw message.
+    #[cfg(feature = "ethereum")]
+    pub fn ethereum_challenge(mut self) -> Self {
+        self.challenge = ChallengeMode::Ethereum;
+        self
+    }
+
+    /// hash the challenge like BIP-340, so Bitcoin nodes accept the signature
+    /// under the x-only key. use it `unframed` over the 32-byte sighash.
+    /// the threshold flow then has to agree on even Y, see `threshold::EvenY`.
+    /// ⚠️ no domain separation and no session id or signer set.
+    pub fn bip340_challenge(mut self) -> Self {
+        self.challenge = ChallengeMode::Bip340;
+        self
+    }
+
+    /// draw the challenge from a merlin transcript instead of hashing a
+    /// concatenation, keeping the v1 context. carries the session id and
+    /// signer set like v1, but the two are never interchangeable.
+    #[cfg(feature = "merlin")]
+    pub fn merlin_challenge(mut self) -> Self {
+        let context = self.context().to_vec();
+        self.challenge = ChallengeMode::Merlin { context };
+        self
+    }
+
+    /// how R and X are encoded in the v1, merlin and ethereum challenges, compressed by default.
+    /// the legacy challenge always hashes them uncompressed, the BIP-340 one x-only.
+    pub fn with_point_encoding(mut self, point_encoding: PointEncoding) -> Self {
+        self.point_encoding = point_encoding;
+        self
+    }
+
+    /// bind the session id into the v1 or merlin challenge, see `SessionId`.
+    /// ⚠️ the legacy and ethereum challenges can't carry it, sessions refuse
+    ///    a config that has both.
+    pub fn with_session(mut self, session: SessionId) -> Self {
+        self.session = Some(session);
+        self
+    }
+
+    pub fn session(&self) -> Option<SessionId> {
+        self.session
+    }
+
+    /// bind the quorum into the v1 or merlin challenge: the (id, Rᵢ) of every signer,
+    /// hashed sorted by id, so the partials only combine for this quorum.
+    /// the verifier needs the same set, e.g. from the aggregation proof.
+    pub fn with_signer_set(mut self, nonces: &[(u64, ProjectivePoint)]) -> Self {
+        let mut nonces = nonces.to_vec();
+        nonces.sort_by_key(|(id, _)| *id);
+        let mut hasher = tagged_hasher(SIGNER_SET_TAG);
+        hasher.update((nonces.len() as u32).to_be_bytes());
+        for (id, R_i) in &nonces {
+            hasher.update(id.to_be_bytes());
+            hasher.update(PointEncoding::Compressed.encode(R_i).as_bytes());
+        }
+        self.signer_set = Some(hasher.finalize().into());
+        self
+    }
+
+    /// digest of the signer set bound by `with_signer_set`.
+    pub fn signer_set(&self) -> Option<[u8; 32]> {
+        self.signer_set
+    }
+
+    /// fail with `Error::UnsupportedChallenge` if a session id or signer
+    /// set is bound but the challenge can't carry it.
+    pub fn check_bindings(&self) -> Result<(), Error> {
+        match self.bindings().is_empty() || self.supports_bindings() {
+            true => Ok(()),
+            false => Err(Error::UnsupportedChallenge),
+        }
+    }
+
+    /// whether the challenge carries a context, session id and signer
+    /// set, true for the v1 and merlin challenges.
+    pub fn supports_bindings(&self) -> bool {
+        match &self.challenge {
+            ChallengeMode::V1 { .. } => true,
+            #[cfg(feature = "merlin")]
+            ChallengeMode::Merlin { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// session id and signer set as hashed after the context, both
+    /// present (empty if unset) as soon as one of them is.
+    fn bindings(&self) -> Vec<&[u8]> {
+        if self.session.is_none() && self.signer_set.is_none() {
+            return Vec::new();
+        }
+        [
+            self.session.as_ref().map(|id| id.0.as_slice()),
+            self.signer_set.as_ref().map(|digest| digest.as_slice()),
+        ]
+        .map(|field| field.unwrap_or_default())
+        .to_vec()
+    }
+
+    pub fn framing(&self) -> &MessageFraming {
+        &self.framing
+    }
+
+    pub fn challenge_mode(&self) -> &ChallengeMode {
+        &self.challenge
+    }
+
+    /// the context bound by `with_context`, empty for the legacy and
+    /// ethereum challenges.
+    pub fn context(&self) -> &[u8] {
+        match &self.challenge {
+            ChallengeMode::V1 { context } => context,
+            #[cfg(feature = "merlin")]
+            ChallengeMode::Merlin { context } => context,
+            _ => &[],
+        }
+    }
+
+    pub fn point_encoding(&self) -> PointEncoding {
+        self.point_encoding
+    }
+
+    /// bytes that are hashed into the challenge in place of `msg`.
+    pub fn frame_message<'a>(&self, msg: &'a [u8]) -> Cow<'a, [u8]> {
+        match &self.framing {
+            MessageFraming::Canonical { app_id, purpose } => {
+                let mut hasher = tagged_hasher(FRAME_TAG);
+                for field in [app_id.as_bytes(), purpose.as_bytes()] {
+                    hasher.update((field.len() as u32).to_be_bytes());
+                    hasher.update(field);
+                }
+                hasher.update(msg);
+                Cow::Owned(hasher.finalize().to_vec())
+            }
+            MessageFraming::Unframed => Cow::Borrowed(msg),
+        }
+    }
+
+    /// compute the challenge c = H(R, X, frame(m)).
+    pub fn challenge(&self, R: &ProjectivePoint, X: &ProjectivePoint, msg: &[u8]) -> Scalar {
+        let msg = self.frame_message(msg);
+        match &self.challenge {
+            ChallengeMode::V1 { context } => versioned_challenge(
+                CHALLENGE_TAG,
+                R,
+                X,
+                &msg,
+                context,
+                &self.bindings(),
+                self.point_encoding,
+            ),
+            ChallengeMode::Legacy => compute_challenge(R, X, &msg),
+            ChallengeMode::Bip340 => compute_challenge_bip340(R, X, &msg),
+            #[cfg(feature = "ethereum")]
+            ChallengeMode::Ethereum => ethereum_challenge(R, X, &msg, self.point_encoding),
+            #[cfg(feature = "merlin")]
+            ChallengeMode::Merlin { .. } => {
+                merlin_challenge(MERLIN_CHALLENGE_LABEL, R, X, &msg, self)
+            }
+        }
+    }
+
+    /// compute the challenge over a message digest, see `prehash`.
+    /// the legacy mode has no prehashed variant, this is always the
+    /// versioned challenge. the ethereum mode signs the digest as a
+    /// 32-byte personal message, like wallets do for `bytes32` hashes.
+    pub fn challenge_prehashed(
+        &self,
+        R: &ProjectivePoint,
+        X: &ProjectivePoint,
+        digest: &[u8; 32],
+    ) -> Scalar {
+        let (context, bindings) = match &self.challenge {
+            ChallengeMode::V1 { context } => (context.as_slice(), self.bindings()),
+            ChallengeMode::Legacy => (&[][..], Vec::new()),
+            ChallengeMode::Bip340 => {
+                return compute_challenge_bip340(R, X, &self.frame_message(digest));
+            }
+            #[cfg(feature = "ethereum")]
+            ChallengeMode::Ethereum => {
+                let msg = self.frame_message(digest);
+                return ethereum_challenge(R, X, &msg, self.point_encoding);
+            }
+            #[cfg(feature = "merlin")]
+            ChallengeMode::Merlin { .. } => {
+                let msg = self.frame_message(digest);
+                return merlin_challenge(MERLIN_PREHASHED_CHALLENGE_LABEL, R, X, &msg, self);
+            }
+        };
+        versioned_challenge(
+            PREHASHED_CHALLENGE_TAG,
+            R,
+            X,
+            &self.frame_message(digest),
+            context,
+            &bindings,
+            self.point_encoding,
+        )
+    }
+}
+
+/// a single-party Schnorr key, for plain signatures without a group.
+#[derive(Clone)]
+pub struct KeyPair {
+    secret: Scalar,
+    public_key: ProjectivePoint,
+}
+
+impl core::fmt::Debug for KeyPair {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("KeyPair")
+            .field("public_key", &self.public_key)
+            .finish_non_exhaustive()
+    }
+}
+
+impl KeyPair {
+    /// random key pair.
+    #[cfg(feature = "std")]
+    pub fn generate() -> Self {
+        Self::generate_with_rng(&mut OsRng)
+    }
+
+    /// like `generate`, drawing the secret from `rng`.
+    pub fn generate_with_rng(rng: &mut impl CryptoRngCore) -> Self {
+        // a zero scalar has probability ~2^-256, from_secret can't fail here
+        Self::from_secret(Scalar::random(rng)).unwrap()
+    }
+
+    /// key pair of an existing secret x, rejecting zero.
+    pub fn from_secret(secret: Scalar) -> Result<Self, Error> {
+        if bool::from(secret.is_zero()) {
+            return Err(Error::InvalidScalar);
+        }
+        Ok(Self {
+            secret,
+            public_key: ProjectivePoint::GENERATOR * secret,
+        })
+    }
+
+    /// X = x*G.
+    pub fn public_key(&self) -> ProjectivePoint {
+        self.public_key
+    }
+
+    /// sign `msg` framed according to `config`, verify with
+    /// `SchnorrSignature::verify_with_config`.
+    #[cfg(feature = "std")]
+    pub fn sign(&self, msg: &[u8], config: &SigningConfig) -> SchnorrSignature {
+        self.sign_with_rng(msg, config, &mut OsRng)
+    }
+
+    /// like `sign`, drawing the nonce randomness from `rng`.
+    ///
+    ///   r = hash_to_scalar(nonce tag, x || X || aux || m),  aux ←$ 32 bytes
+    ///
+    /// the nonce is hedged, a broken `rng` still gives a different r per
+    /// message instead of leaking x.
+    pub fn sign_with_rng(
+        &self,
+        msg: &[u8],
+        config: &SigningConfig,
+        rng: &mut impl CryptoRngCore,
+    ) -> SchnorrSignature {
+        let mut aux = [0u8; 32];
+        rng.fill_bytes(&mut aux);
+
+        let mut hasher = scalar_hasher(NONCE_TAG);
+        hasher.update(self.secret.to_bytes());
+        hasher.update(
+            PointEncoding::Compressed
+                .encode(&self.public_key)
+                .as_bytes(),
+        );
+        hasher.update(aux);
+        hasher.update(msg);
+        let mut r = finalize_scalar(hasher);
+
+        let mut R = compute_nonce_point(&r);
+        let mut secret = self.secret;
+        // BIP-340 verifies against R and X with even Y
+        if config.challenge == ChallengeMode::Bip340 {
+            if !has_even_y(&R) {
+                (r, R) = (-r, -R);
+            }
+            if !has_even_y(&self.public_key) {
+                secret = -secret;
+            }
+        }
+        let c = config.challenge(&R, &self.public_key, msg);
+        SchnorrSignature {
+            R,
+            s: r + c * secret,
+        }
+    }
+}
+
+/// SHA-256(m), the digest signed by the prehashed API.
+/// large payloads can be streamed through any SHA-256 implementation instead.
+pub fn prehash(msg: &[u8]) -> [u8; 32] {
+    Sha256::digest(msg).into()
+}
+
+/// generate a random nonce for signing.
+#[cfg(feature = "std")]
+pub fn generate_nonce() -> Scalar {
+    generate_nonce_with_rng(&mut OsRng)
+}
+
+/// like `generate_nonce`, drawing from `rng`.
+/// ⚠️ `rng` must be a CSPRNG, a predictable nonce leaks the signing share.
+pub fn generate_nonce_with_rng(rng: &mut impl CryptoRngCore) -> Scalar {
+    Scalar::random(rng)
+}
+
+/// compute the nonce point R = r*G from a nonce scalar r.
+pub fn compute_nonce_point(r: &Scalar) -> ProjectivePoint {
+    ProjectivePoint::GENERATOR * r
+}
+
+/// compute the challenge c = H(R, X, m) over an already framed message, where:
+/// - R is the nonce point
+/// - X is the public key
+/// - m is the message
+/// - H is SHA-256
+///
+/// this is the legacy challenge, with no domain separation.
+/// prefer `SigningConfig::challenge`, which frames the message first.
+pub fn compute_challenge(R: &ProjectivePoint, X: &ProjectivePoint, msg: &[u8]) -> Scalar {
+    let mut hasher = Sha256::new();
+    let R_enc = R.to_encoded_point(false);
+    let X_enc = X.to_encoded_point(false);
+    hasher.update(R_enc.as_bytes());
+    hasher.update(X_enc.as_bytes());
+    hasher.update(msg);
+
+    // same value as before for every hash below q, without panicking above it
+    <Scalar as Reduce<U256>>::reduce_bytes(&hasher.finalize())
+}
+
+/// compute the BIP-340 challenge over an already framed message,
+///
+///   c = tagged_hash("BIP0340/challenge", x(R) || x(X) || m) mod q
+///
+/// R and X are taken as their x coordinates, whatever the parity of Y.
+pub fn compute_challenge_bip340(R: &ProjectivePoint, X: &ProjectivePoint, msg: &[u8]) -> Scalar {
+    let mut hasher = tagged_hasher(BIP340_CHALLENGE_TAG);
+    hasher.update(pp_to_x_only(R));
+    hasher.update(pp_to_x_only(X));
+    hasher.update(msg);
+
+    <Scalar as Reduce<U256>>::reduce_bytes(&hasher.finalize())
+}
+
+/// compute the versioned challenge over an already framed message,
+///
+///   c = hash_to_scalar(tag, ciphersuite || context || R || X || m)
+///
+/// where ciphersuite and context are each prefixed with their 4-byte
+/// big-endian length, and R, X are compressed. `SigningConfig::with_point_encoding`
+/// picks the uncompressed form instead. `SigningConfig::with_session` and
+/// `with_signer_set` add the session id and the signer set digest as two
+/// more length-prefixed fields after the context, an unset one empty.
+pub fn compute_challenge_v1(
+    R: &ProjectivePoint,
+    X: &ProjectivePoint,
+    msg: &[u8],
+    context: &[u8],
+) -> Scalar {
+    versioned_challenge(
+        CHALLENGE_TAG,
+        R,
+        X,
+        msg,
+        context,
+        &[],
+        PointEncoding::Compressed,
+    )
+}
+
+/// compute the challenge over an already framed message digest, encoded
+/// like `compute_challenge_v1` with an empty context but under its own tag.
+/// the separate tag keeps a signature over `digest` from also being a
+/// signature over the 32-byte message `digest`.
+pub fn compute_challenge_prehashed(
+    R: &ProjectivePoint,
+    X: &ProjectivePoint,
+    digest: &[u8; 32],
+) -> Scalar {
+    versioned_challenge(
+        PREHASHED_CHALLENGE_TAG,
+        R,
+        X,
+        digest,
+        &[],
+        &[],
+        PointEncoding::Compressed,
+    )
+}
+
+fn versioned_challenge(
+    tag: &[u8],
+    R: &ProjectivePoint,
+    X: &ProjectivePoint,
+    msg: &[u8],
+    context: &[u8],
+    bindings: &[&[u8]],
+    point_encoding: PointEncoding,
+) -> Scalar {
+    let mut hasher = scalar_hasher(tag);
+    for field in [CIPHERSUITE_ID, context]
+        .into_iter()
+        .chain(bindings.iter().copied())
+    {
+        hasher.update((field.len() as u32).to_be_bytes());
+        hasher.update(field);
+    }
+    hasher.update(point_encoding.encode(R).as_bytes());
+    hasher.update(point_encoding.encode(X).as_bytes());
+    hasher.update(msg);
+
+    finalize_scalar(hasher)
+}
+
+//--------------------------------------------------------------------
+// Merlin challenge
+//--------------------------------------------------------------------
+//
+//   t = Transcript(label)
+//   t ← ("ciphersuite", ciphersuite)  ("context", context)
+//   t ← ("session", sid)              if bound
+//   t ← ("signer-set", digest)        if bound
+//   t ← ("R", R)  ("X", X)  ("message", m)
+//   c = t.challenge_bytes("challenge", 64) mod q
+//
+// merlin frames every field with its label and length, so the optional
+// bindings are simply left out instead of being hashed as empty fields.
+// the prehashed challenge starts from its own transcript label.
+//
+
+/// compute the merlin challenge over an already framed message, with no
+/// session or signer set bound and R, X compressed.
+#[cfg(feature = "merlin")]
+pub fn compute_challenge_merlin(
+    R: &ProjectivePoint,
+    X: &ProjectivePoint,
+    msg: &[u8],
+    context: &[u8],
+) -> Scalar {
+    let config = SigningConfig::unframed()
+        .merlin_challenge()
+        .with_context(context);
+    merlin_challenge(MERLIN_CHALLENGE_LABEL, R, X, msg, &config)
+}
+
+/// the merlin challenge over an already framed message, with the
+/// context, bindings and point encoding of `config`.
+#[cfg(feature = "merlin")]
+fn merlin_challenge(
+    label: &'static [u8],
+    R: &ProjectivePoint,
+    X: &ProjectivePoint,
+    msg: &[u8],
+    config: &SigningConfig,
+) -> Scalar {
+    let encode = |point: &ProjectivePoint| config.point_encoding.encode(point);
+
+    let mut transcript = merlin::Transcript::new(label);
+    transcript.append_message(b"ciphersuite", CIPHERSUITE_ID);
+    transcript.append_message(b"context", config.context());
+    if let Some(session) = &config.session {
+        transcript.append_message(b"session", session.as_bytes());
+    }
+    if let Some(digest) = &config.signer_set {
+        transcript.append_message(b"signer-set", digest);
+    }
+    transcript.append_message(b"R", encode(R).as_bytes());
+    transcript.append_message(b"X", encode(X).as_bytes());
+    transcript.append_message(b"message", msg);
+
+    transcript_scalar(&mut transcript, b"challenge")
+}
+
+//--------------------------------------------------------------------
+// Ethereum challenge (Keccak-256, EIP-191)
+//--------------------------------------------------------------------
+//
+//   h = Keccak-256("\x19Ethereum Signed Message:\n" || len(m) || m)
+//   c = Keccak-256(R || X || h) mod q
+//
+// len(m) is the byte length in ASCII decimal, R and X are compressed
+// (33 bytes) unless the config asks for uncompressed points. in Solidity
+//
+//   c = uint256(keccak256(abi.encodePacked(R, X, toEthSignedMessageHash(m)))) % Q
+//
+// and the contract checks s*G == R + c*X, e.g. through the ecrecover trick.
+//
+
+/// Keccak-256 of the EIP-191 personal message `msg`.
+#[cfg(feature = "ethereum")]
+pub fn eip191_hash(msg: &[u8]) -> [u8; 32] {
+    let mut hasher = sha3::Keccak256::new();
+    hasher.update(b"\x19Ethereum Signed Message:\n");
+    hasher.update(msg.len().to_string().as_bytes());
+    hasher.update(msg);
+    hasher.finalize().into()
+}
+
+/// compute the ethereum challenge over an already framed message, with
+/// R and X compressed.
+#[cfg(feature = "ethereum")]
+pub fn compute_challenge_ethereum(R: &ProjectivePoint, X: &ProjectivePoint, msg: &[u8]) -> Scalar {
+    ethereum_challenge(R, X, msg, PointEncoding::Compressed)
+}
+
+#[cfg(feature = "ethereum")]
+fn ethereum_challenge(
+    R: &ProjectivePoint,
+    X: &ProjectivePoint,
+    msg: &[u8],
+    point_encoding: PointEncoding,
+) -> Scalar {
+    let mut hasher = sha3::Keccak256::new();
+    hasher.update(point_encoding.encode(R).as_bytes());
+    hasher.update(point_encoding.encode(X).as_bytes());
+    hasher.update(eip191_hash(msg));
+
+    <Scalar as Reduce<U256>>::reduce_bytes(&hasher.finalize())
+}
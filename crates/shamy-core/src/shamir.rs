@@ -0,0 +1,565 @@
+#![allow(non_snake_case)]
+
+use crate::error::Error;
+use crate::hash::tagged_hasher;
+use crate::hazmat;
+use crate::limits::Limits;
+use crate::threshold::*;
+use crate::util::{PointEncoding, bytes_to_pp, has_even_y};
+use crate::vss::{calculate_commitment, verify_shares};
+use alloc::{string::ToString, vec::Vec};
+#[cfg(feature = "std")]
+use k256::elliptic_curve::rand_core::OsRng;
+use k256::{
+    FieldBytes, ProjectivePoint, Scalar,
+    elliptic_curve::{Field, PrimeField, rand_core::CryptoRngCore, subtle::ConstantTimeEq},
+};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use sha2::Digest;
+
+pub struct KeygenOutput {
+    pub participants: Vec<Participant>,
+    pub public_key: ProjectivePoint,
+    pub commitments: Vec<ProjectivePoint>,
+}
+
+//--------------------------------------------------------------------
+// Binary encoding of a keygen result
+//--------------------------------------------------------------------
+//
+//   magic "SHKG" || version (1 byte)
+//   || len || X                        (compressed, 33 bytes)
+//   || len || C_0 || ... || C_{t-1}    (compressed, 33 bytes each)
+//   || len || id || x_i || X_i || ...  (8 + 32 + 33 bytes per participant)
+//
+// len is the byte length of the field, 4-byte big-endian. a reader that
+// doesn't know the version refuses the file instead of guessing.
+//
+// ⚠️ the encoding holds every share in the clear, store it like the secret.
+//
+
+const KEYGEN_MAGIC: &[u8; 4] = b"SHKG";
+const KEYGEN_VERSION: u8 = 1;
+const POINT_LEN: usize = 33;
+const RECORD_LEN: usize = 8 + 32 + POINT_LEN;
+
+impl KeygenOutput {
+    /// versioned binary encoding, see the layout above.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = KEYGEN_MAGIC.to_vec();
+        out.push(KEYGEN_VERSION);
+
+        let mut field = |bytes: Vec<u8>| {
+            out.extend((bytes.len() as u32).to_be_bytes());
+            out.extend(bytes);
+        };
+        field(encode_point(&self.public_key));
+        field(self.commitments.iter().flat_map(encode_point).collect());
+        field(
+            self.participants
+                .iter()
+                .flat_map(|p| {
+                    let mut record = p.id.to_be_bytes().to_vec();
+                    record.extend(p.x_i.to_bytes());
+                    record.extend(encode_point(&p.X_i));
+                    record
+                })
+                .collect(),
+        );
+
+        out
+    }
+
+    /// parse `to_bytes`, rejecting unknown versions, truncated or trailing
+    /// data, invalid points and scalars, and bad participant ids.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let invalid = |reason: &str| Error::InvalidEncoding(reason.to_string());
+        let rest = bytes
+            .strip_prefix(KEYGEN_MAGIC)
+            .ok_or(invalid("not a keygen output"))?;
+        let (&version, mut rest) = rest.split_first().ok_or(invalid("truncated"))?;
+        if version != KEYGEN_VERSION {
+            return Err(Error::InvalidEncoding(alloc::format!(
+                "unsupported keygen output version {version}"
+            )));
+        }
+
+        let mut field = || -> Result<&[u8], Error> {
+            if rest.len() < 4 {
+                return Err(invalid("truncated"));
+            }
+            let (len, tail) = rest.split_at(4);
+            let len = u32::from_be_bytes(len.try_into().unwrap()) as usize;
+            if tail.len() < len {
+                return Err(invalid("truncated"));
+            }
+            let (field, tail) = tail.split_at(len);
+            rest = tail;
+            Ok(field)
+        };
+        let public_key = field()?;
+        let commitments = field()?;
+        let participants = field()?;
+        if !rest.is_empty() {
+            return Err(invalid("trailing data"));
+        }
+
+        if public_key.len() != POINT_LEN {
+            return Err(invalid("public key is not a compressed point"));
+        }
+        let public_key = bytes_to_pp(public_key)?;
+        if commitments.is_empty() || commitments.len() % POINT_LEN != 0 {
+            return Err(invalid("commitments are not compressed points"));
+        }
+        let commitments = commitments
+            .chunks(POINT_LEN)
+            .map(bytes_to_pp)
+            .collect::<Result<Vec<_>, _>>()?;
+        if commitments[0] != public_key {
+            return Err(invalid("public key is not the first commitment"));
+        }
+
+        if participants.len() % RECORD_LEN != 0 {
+            return Err(invalid("participant records have the wrong length"));
+        }
+        let participants = participants
+            .chunks(RECORD_LEN)
+            .map(|record| {
+                let (id, record) = record.split_at(8);
+                let (x_i, X_i) = record.split_at(32);
+                Ok(Participant {
+                    id: u64::from_be_bytes(id.try_into().unwrap()),
+                    x_i: Scalar::from_repr(*FieldBytes::from_slice(x_i))
+                        .into_option()
+                        .ok_or(Error::InvalidScalar)?,
+                    X_i: bytes_to_pp(X_i)?,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        hazmat::validate_ids(&participants.iter().map(|p| p.id).collect::<Vec<_>>())?;
+
+        Ok(Self {
+            participants,
+            public_key,
+            commitments,
+        })
+    }
+}
+
+impl KeygenOutput {
+    /// flip the group key to even Y for x-only (BIP-340) use, negating
+    /// every share and commitment with it. returns whether it was flipped.
+    pub fn normalize_even_y(&mut self) -> bool {
+        if has_even_y(&self.public_key) {
+            return false;
+        }
+        self.public_key = -self.public_key;
+        for commitment in &mut self.commitments {
+            *commitment = -*commitment;
+        }
+        for participant in &mut self.participants {
+            participant.x_i = -participant.x_i;
+            participant.X_i = -participant.X_i;
+        }
+        true
+    }
+
+    /// check the output is self-consistent: X = C₀, ids distinct and
+    /// non-zero, Xᵢ = xᵢ*G and every xᵢ on the committed polynomial.
+    pub fn verify(&self) -> Result<(), Error> {
+        if self.commitments.first() != Some(&self.public_key) {
+            return Err(Error::PublicKeyMismatch);
+        }
+        hazmat::validate_ids(&self.participants.iter().map(|p| p.id).collect::<Vec<_>>())?;
+        if let Some(p) = self
+            .participants
+            .iter()
+            .find(|p| ProjectivePoint::GENERATOR * p.x_i != p.X_i)
+        {
+            return Err(Error::PublicShareMismatch { id: p.id });
+        }
+
+        let shares = self
+            .participants
+            .iter()
+            .map(|p| (p.id, p.x_i))
+            .collect::<Vec<_>>();
+        verify_shares(&shares, &self.commitments)
+    }
+}
+
+fn encode_point(point: &ProjectivePoint) -> Vec<u8> {
+    PointEncoding::Compressed.encode(point).as_bytes().to_vec()
+}
+
+#[cfg(feature = "std")]
+#[deprecated(note = "moved to `shamy_core::hazmat::random_polynomial`")]
+pub fn random_polynomial(secret: Scalar, t: usize) -> Vec<Scalar> {
+    hazmat::random_polynomial(secret, t)
+}
+
+#[deprecated(note = "moved to `shamy_core::hazmat::eval_polynomial`")]
+pub fn eval_polynomial(coeffs: &[Scalar], id: u64) -> Scalar {
+    hazmat::eval_polynomial(coeffs, id)
+}
+
+/// Create n Shamir shares for threshold t.
+/// Returns (participants, public_key, commitments).
+/// Fails if t-of-n is invalid or above the default `Limits`, see `Limits::check`.
+#[cfg(feature = "std")]
+pub fn shamir_keygen(n: usize, t: usize) -> Result<KeygenOutput, Error> {
+    shamir_keygen_with_limits(n, t, &Limits::default())
+}
+
+/// like `shamir_keygen`, with explicit limits.
+#[cfg(feature = "std")]
+pub fn shamir_keygen_with_limits(
+    n: usize,
+    t: usize,
+    limits: &Limits,
+) -> Result<KeygenOutput, Error> {
+    shamir_keygen_with_rng(n, t, limits, &mut OsRng)
+}
+
+/// like `shamir_keygen_with_limits`, drawing the secret and coefficients from `rng`.
+pub fn shamir_keygen_with_rng(
+    n: usize,
+    t: usize,
+    limits: &Limits,
+    rng: &mut impl CryptoRngCore,
+) -> Result<KeygenOutput, Error> {
+    limits.check(t, n)?;
+    let dealer = Dealer::new_with_rng(n as u64, t, limits, rng)?;
+
+    #[cfg(feature = "parallel")]
+    let participants = (1..=n as u64)
+        .into_par_iter()
+        .map(|id| dealer.share(id).unwrap())
+        .collect();
+    #[cfg(not(feature = "parallel"))]
+    let participants = dealer.shares().collect();
+
+    Ok(KeygenOutput {
+        participants,
+        public_key: dealer.public_key,
+        commitments: dealer.commitments,
+    })
+}
+
+/// like `shamir_keygen_with_limits`, for the participants `ids` instead of 1..=n.
+/// ids can be any distinct non-zero values, e.g. derived from identities.
+#[cfg(feature = "std")]
+pub fn shamir_keygen_with_ids(
+    ids: &[u64],
+    t: usize,
+    limits: &Limits,
+) -> Result<KeygenOutput, Error> {
+    shamir_keygen_with_ids_and_rng(ids, t, limits, &mut OsRng)
+}
+
+/// like `shamir_keygen_with_ids`, drawing the secret and coefficients from `rng`.
+pub fn shamir_keygen_with_ids_and_rng(
+    ids: &[u64],
+    t: usize,
+    limits: &Limits,
+    rng: &mut impl CryptoRngCore,
+) -> Result<KeygenOutput, Error> {
+    limits.check(t, ids.len())?;
+    hazmat::validate_ids(ids)?;
+    let dealer = Dealer::new_with_rng(ids.len() as u64, t, limits, rng)?;
+
+    #[cfg(feature = "parallel")]
+    let participants = ids.par_iter().map(|id| dealer.evaluate(*id)).collect();
+    #[cfg(not(feature = "parallel"))]
+    let participants = ids.iter().map(|id| dealer.evaluate(*id)).collect();
+
+    Ok(KeygenOutput {
+        participants,
+        public_key: dealer.public_key,
+        commitments: dealer.commitments,
+    })
+}
+
+/// deals shares one at a time, for committees too big to hold in memory.
+/// only the t coefficients are kept, so n is not bound by `Limits::max_participants`.
+pub struct Dealer {
+    num_shares: u64,
+    coefficients: Vec<Scalar>,
+    public_key: ProjectivePoint,
+    commitments: Vec<ProjectivePoint>,
+}
+
+impl Dealer {
+    #[cfg(feature = "std")]
+    pub fn new(n: u64, t: usize, limits: &Limits) -> Result<Self, Error> {
+        Self::new_with_rng(n, t, limits, &mut OsRng)
+    }
+
+    pub fn new_with_rng(
+        n: u64,
+        t: usize,
+        limits: &Limits,
+        rng: &mut impl CryptoRngCore,
+    ) -> Result<Self, Error> {
+        limits.check_threshold(t, n)?;
+
+        let secret = Scalar::random(&mut *rng);
+        let coefficients = hazmat::random_polynomial_with_rng(secret, t, rng);
+        let commitments = coefficients
+            .iter()
+            .map(|c| calculate_commitment(*c))
+            .collect::<Vec<_>>();
+
+        Ok(Self {
+            num_shares: n,
+            coefficients,
+            public_key: commitments[0],
+            commitments,
+        })
+    }
+
+    pub fn public_key(&self) -> ProjectivePoint {
+        self.public_key
+    }
+
+    pub fn commitments(&self) -> &[ProjectivePoint] {
+        &self.commitments
+    }
+
+    pub fn num_shares(&self) -> u64 {
+        self.num_shares
+    }
+
+    /// share of participant `id` in 1..=n.
+    pub fn share(&self, id: u64) -> Result<Participant, Error> {
+        if id == 0 || id > self.num_shares {
+            return Err(Error::InvalidParticipantId {
+                id,
+                num_shares: self.num_shares as usize,
+            });
+        }
+
+        Ok(self.evaluate(id))
+    }
+
+    /// lazily computed shares of participants 1..=n.
+    pub fn shares(&self) -> impl Iterator<Item = Participant> + '_ {
+        (1..=self.num_shares).map(|id| self.evaluate(id))
+    }
+
+    fn evaluate(&self, id: u64) -> Participant {
+        Participant::from_secret(id, hazmat::eval_polynomial(&self.coefficients, id))
+    }
+}
+
+/// Recover the secret f(0) from shares `(id, x_i)`.
+/// ⚠️ with fewer than t shares this silently returns a wrong secret, check
+/// the result against the public key.
+pub fn reconstruct_secret(shares: &[(u64, Scalar)]) -> Result<Scalar, Error> {
+    interpolate_at(0, shares)
+}
+
+/// f(x0) of the polynomial through `points` `(id, f(id))`, e.g. the share
+/// of a new participant x0 (enrollment) or of a lost one (repair).
+/// ⚠️ the same caveat as `reconstruct_secret`: with fewer than t points
+/// the result is a wrong value, not an error.
+pub fn interpolate_at(x0: u64, points: &[(u64, Scalar)]) -> Result<Scalar, Error> {
+    let ids = points.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+    hazmat::validate_ids(&ids)?;
+
+    Ok(points.iter().fold(Scalar::ZERO, |acc, (id, y)| {
+        acc + hazmat::lagrange_coefficient_at(*id, &ids, x0) * y
+    }))
+}
+
+//--------------------------------------------------------------------
+// Encrypted share distribution (ECIES)
+//--------------------------------------------------------------------
+//
+// a share for participant j is encrypted to j's long-term key P = p*G,
+// so a dealer or a DKG participant can publish it on any channel:
+//
+//   k ←$ Zq,  R = k*G,  K = k*P              (j computes K = p*R)
+//   body = x ⊕ H(share-key, K || R || P)
+//   tag  = H(share-mac, K || sender || receiver || R || body)
+//
+// sender is 0 for a trusted dealer. the tag binds both ids, so a share
+// can't be replayed to another receiver or passed off as another sender's.
+//
+// binary encoding:
+//
+//   "SHES" || version || sender || receiver || R || body || tag
+//
+// ids are 8-byte big-endian, R is compressed.
+//
+// ⚠️ anyone who knows P can encrypt, decryption doesn't authenticate the
+//    sender. check the share against the commitments (`vss::verify_share`).
+//
+
+const SHARE_KEY_TAG: &[u8] = b"shamy/shamir/share-key/v1";
+const SHARE_MAC_TAG: &[u8] = b"shamy/shamir/share-mac/v1";
+const ENCRYPTED_SHARE_MAGIC: &[u8; 4] = b"SHES";
+const ENCRYPTED_SHARE_VERSION: u8 = 1;
+const ENCRYPTED_SHARE_LEN: usize = 4 + 1 + 8 + 8 + POINT_LEN + 32 + 32;
+
+/// a share encrypted to the long-term key of its receiver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncryptedShare {
+    /// 0 for a trusted dealer, the DKG participant otherwise.
+    pub sender: u64,
+    pub receiver: u64,
+    /// ephemeral key k*G.
+    pub R: ProjectivePoint,
+    pub body: [u8; 32],
+    pub tag: [u8; 32],
+}
+
+impl EncryptedShare {
+    /// versioned binary encoding, see the layout above.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = ENCRYPTED_SHARE_MAGIC.to_vec();
+        out.push(ENCRYPTED_SHARE_VERSION);
+        out.extend(self.sender.to_be_bytes());
+        out.extend(self.receiver.to_be_bytes());
+        out.extend_from_slice(PointEncoding::Compressed.encode(&self.R).as_bytes());
+        out.extend(self.body);
+        out.extend(self.tag);
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let invalid = |reason: &str| Error::InvalidEncoding(reason.to_string());
+        let rest = bytes
+            .strip_prefix(ENCRYPTED_SHARE_MAGIC)
+            .ok_or(invalid("not an encrypted share"))?;
+        if bytes.len() != ENCRYPTED_SHARE_LEN {
+            return Err(invalid("encrypted share has the wrong length"));
+        }
+        if rest[0] != ENCRYPTED_SHARE_VERSION {
+            return Err(Error::InvalidEncoding(alloc::format!(
+                "unsupported encrypted share version {}",
+                rest[0]
+            )));
+        }
+
+        Ok(Self {
+            sender: u64::from_be_bytes(rest[1..9].try_into().unwrap()),
+            receiver: u64::from_be_bytes(rest[9..17].try_into().unwrap()),
+            R: bytes_to_pp(&rest[17..17 + POINT_LEN])?,
+            body: rest[17 + POINT_LEN..17 + POINT_LEN + 32]
+                .try_into()
+                .unwrap(),
+            tag: rest[17 + POINT_LEN + 32..].try_into().unwrap(),
+        })
+    }
+}
+
+/// encrypt the dealer share `share` to its receiver's long-term key `recipient`.
+#[cfg(feature = "std")]
+pub fn encrypt_share_for(
+    recipient: &ProjectivePoint,
+    share: &Participant,
+) -> Result<EncryptedShare, Error> {
+    encrypt_share_for_with_rng(recipient, share, &mut OsRng)
+}
+
+/// like `encrypt_share_for`, drawing the ephemeral key from `rng`.
+pub fn encrypt_share_for_with_rng(
+    recipient: &ProjectivePoint,
+    share: &Participant,
+    rng: &mut impl CryptoRngCore,
+) -> Result<EncryptedShare, Error> {
+    encrypt_scalar(recipient, 0, share.id, &share.x_i, rng)
+}
+
+/// decrypt a dealer share with the receiver's long-term secret key.
+/// fails with `Error::DecryptionFailed` for a wrong key or a modified share.
+pub fn decrypt_share(
+    secret_key: &Scalar,
+    encrypted: &EncryptedShare,
+) -> Result<Participant, Error> {
+    let x_i = decrypt_scalar(secret_key, encrypted)?;
+    Ok(Participant::from_secret(encrypted.receiver, x_i))
+}
+
+pub(crate) fn encrypt_scalar(
+    recipient: &ProjectivePoint,
+    sender: u64,
+    receiver: u64,
+    value: &Scalar,
+    rng: &mut impl CryptoRngCore,
+) -> Result<EncryptedShare, Error> {
+    crate::util::ensure_not_identity(recipient)?;
+    let k = Scalar::random(rng);
+    let R = ProjectivePoint::GENERATOR * k;
+    let K = *recipient * k;
+
+    let mut body: [u8; 32] = value.to_bytes().into();
+    for (byte, key) in body.iter_mut().zip(share_key(&K, &R, recipient)) {
+        *byte ^= key;
+    }
+    let tag = share_mac(&K, sender, receiver, &R, &body);
+
+    Ok(EncryptedShare {
+        sender,
+        receiver,
+        R,
+        body,
+        tag,
+    })
+}
+
+pub(crate) fn decrypt_scalar(
+    secret_key: &Scalar,
+    encrypted: &EncryptedShare,
+) -> Result<Scalar, Error> {
+    // R at infinity makes K the identity, a key anyone can compute
+    if encrypted.R == ProjectivePoint::IDENTITY {
+        return Err(Error::DecryptionFailed);
+    }
+    let P = ProjectivePoint::GENERATOR * secret_key;
+    let K = encrypted.R * secret_key;
+    let tag = share_mac(
+        &K,
+        encrypted.sender,
+        encrypted.receiver,
+        &encrypted.R,
+        &encrypted.body,
+    );
+    if !bool::from(tag.ct_eq(&encrypted.tag)) {
+        return Err(Error::DecryptionFailed);
+    }
+
+    let mut value = encrypted.body;
+    for (byte, key) in value.iter_mut().zip(share_key(&K, &encrypted.R, &P)) {
+        *byte ^= key;
+    }
+    Scalar::from_repr(*FieldBytes::from_slice(&value))
+        .into_option()
+        .ok_or(Error::DecryptionFailed)
+}
+
+fn share_key(K: &ProjectivePoint, R: &ProjectivePoint, P: &ProjectivePoint) -> [u8; 32] {
+    let mut hasher = tagged_hasher(SHARE_KEY_TAG);
+    for point in [K, R, P] {
+        hasher.update(PointEncoding::Compressed.encode(point).as_bytes());
+    }
+    hasher.finalize().into()
+}
+
+fn share_mac(
+    K: &ProjectivePoint,
+    sender: u64,
+    receiver: u64,
+    R: &ProjectivePoint,
+    body: &[u8; 32],
+) -> [u8; 32] {
+    let mut hasher = tagged_hasher(SHARE_MAC_TAG);
+    hasher.update(PointEncoding::Compressed.encode(K).as_bytes());
+    hasher.update(sender.to_be_bytes());
+    hasher.update(receiver.to_be_bytes());
+    hasher.update(PointEncoding::Compressed.encode(R).as_bytes());
+    hasher.update(body);
+    hasher.finalize().into()
+}
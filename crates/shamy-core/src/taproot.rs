@@ -0,0 +1,103 @@
+#![allow(non_snake_case)]
+
+use crate::error::Error;
+use crate::hash::tagged_hasher;
+use crate::threshold::Participant;
+use crate::util::segwit_encode;
+use alloc::string::String;
+use k256::{
+    ProjectivePoint, Scalar,
+    elliptic_curve::{PrimeField, point::AffineCoordinates},
+};
+use sha2::Digest;
+
+//--------------------------------------------------------------------
+// Taproot output key (BIP-341)
+//--------------------------------------------------------------------
+//
+//   P  = internal key with even Y         (the group key, negated if odd)
+//   t  = H_TapTweak(x(P) || merkle_root)  (no merkle root for key path only)
+//   Q  = P + t*G                          (output key, goes in the P2TR output)
+//
+// shares follow the same shift as the key:
+//   x_i' = ±x_i + t,  X_i' = ±X_i + t*G   (minus when the group key had odd Y)
+//
+// the group then signs for Q with its usual protocol.
+//
+// ⚠️ a P2TR key path spend needs a BIP-340 signature under x(Q). sign with
+// `SigningConfig::bip340_challenge`, whose flow flips the tweaked shares
+// when Q has odd Y and the nonces when R has (`threshold::EvenY`).
+//
+
+/// the BIP-341 tweak of a group key, and how to apply it to the shares.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaprootTweak {
+    /// Q = P + t*G, with whatever parity it ends up with.
+    pub output_key: ProjectivePoint,
+    /// t.
+    pub tweak: Scalar,
+    /// the group key had odd Y and was negated to get P.
+    pub negate_internal: bool,
+}
+
+/// tweak the group key `internal_key` for a P2TR output committing to
+/// `merkle_root`, or to no script tree when it is `None`.
+pub fn taproot_tweak(
+    internal_key: &ProjectivePoint,
+    merkle_root: Option<&[u8; 32]>,
+) -> Result<TaprootTweak, Error> {
+    let affine = internal_key.to_affine();
+    let negate_internal = bool::from(affine.y_is_odd());
+    let P = if negate_internal {
+        -*internal_key
+    } else {
+        *internal_key
+    };
+
+    let mut hasher = tagged_hasher(b"TapTweak");
+    hasher.update(affine.x());
+    if let Some(root) = merkle_root {
+        hasher.update(root);
+    }
+    let tweak =
+        Option::<Scalar>::from(Scalar::from_repr(hasher.finalize())).ok_or(Error::InvalidTweak)?;
+
+    let output_key = P + ProjectivePoint::GENERATOR * tweak;
+    if output_key == ProjectivePoint::IDENTITY {
+        return Err(Error::InvalidTweak);
+    }
+
+    Ok(TaprootTweak {
+        output_key,
+        tweak,
+        negate_internal,
+    })
+}
+
+impl TaprootTweak {
+    /// x-only encoding of Q for the witness program.
+    pub fn output_key_x_only(&self) -> [u8; 32] {
+        self.output_key.to_affine().x().into()
+    }
+
+    /// bech32m P2TR address of Q, e.g. `address("bc")` for mainnet.
+    pub fn address(&self, hrp: &str) -> Result<String, Error> {
+        segwit_encode(hrp, 1, &self.output_key_x_only())
+    }
+
+    /// the participant's share of Q, ±x_i + t.
+    pub fn tweak_participant(&self, participant: &Participant) -> Participant {
+        let x_i = if self.negate_internal {
+            -participant.x_i
+        } else {
+            participant.x_i
+        };
+        Participant::from_secret(participant.id, x_i + self.tweak)
+    }
+
+    /// a public share of Q, ±X_i + t*G.
+    pub fn tweak_public_share(&self, X_i: &ProjectivePoint) -> ProjectivePoint {
+        let X_i = if self.negate_internal { -*X_i } else { *X_i };
+        X_i + ProjectivePoint::GENERATOR * self.tweak
+    }
+}
@@ -0,0 +1,1274 @@
+#![allow(non_snake_case)]
+
+use crate::error::Error;
+use crate::evidence::{MisbehaviorEvidence, partial_evidence};
+use crate::hazmat::{self, LagrangeWeights};
+use crate::schnorr::*;
+use crate::shamir::KeygenOutput;
+use crate::transcript::Transcript;
+use crate::util::{PointEncoding, ensure_not_identity, has_even_y};
+use crate::vss::calculate_commitment;
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    format,
+    string::ToString,
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use k256::elliptic_curve::rand_core::OsRng;
+use k256::{ProjectivePoint, Scalar, elliptic_curve::rand_core::CryptoRngCore};
+
+/// Participant in the threshold Schnorr signature scheme.
+/// Each participant has:
+/// - A unique ID (used for Shamir's secret sharing)
+/// - A long-term secret key (x_i)
+/// - A public key share (X_i = x_i*G)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Participant {
+    pub id: u64,
+    pub x_i: Scalar,
+    pub X_i: ProjectivePoint,
+}
+
+impl Participant {
+    pub fn from_secret(id: u64, x_i: Scalar) -> Self {
+        let X_i = ProjectivePoint::GENERATOR * x_i;
+        Self { id, x_i, X_i }
+    }
+}
+
+/// sᵢ of participant `id`. `R_i` and `X_i` are the nonce point and the
+/// public share it was made with, so a receiver can check it on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartialSignature {
+    pub id: u64,
+    pub s_i: Scalar,
+    pub R_i: Option<ProjectivePoint>,
+    pub X_i: Option<ProjectivePoint>,
+}
+
+impl PartialSignature {
+    /// a bare sᵢ, without the points to verify it.
+    pub fn new(id: u64, s_i: Scalar) -> Self {
+        Self {
+            id,
+            s_i,
+            R_i: None,
+            X_i: None,
+        }
+    }
+
+    /// sᵢ*G == Rᵢ + c*Xᵢ with the carried points.
+    /// ⚠️ Xᵢ is only claimed by the sender, compare it with the public
+    ///    share on record before trusting the result.
+    pub fn verify(&self, c: &Scalar) -> Result<(), Error> {
+        let (Some(R_i), Some(X_i)) = (self.R_i, self.X_i) else {
+            return Err(Error::MissingPartialCommitments { id: self.id });
+        };
+        match ProjectivePoint::GENERATOR * self.s_i == R_i + X_i * c {
+            true => Ok(()),
+            false => Err(Error::InvalidPartialSignature { id: self.id }),
+        }
+    }
+}
+
+//--------------------------------------------------------------------
+// Signer sets
+//--------------------------------------------------------------------
+//
+// R and s must be weighted over the same quorum S:
+//
+//   R = Σ_{i∈S} λᵢ·Rᵢ      s = Σ_{i∈S} λᵢ·sᵢ
+//
+// taking S from whatever nonces or partials arrived lets one missing or
+// extra signer shift every λᵢ, and the signature comes out invalid with
+// no error. a `SignerSet` fixes S up front:
+//
+//   |S| ≥ t,  ids distinct and non-zero
+//
+// and aggregation over it fails if a member sent nothing.
+//
+
+/// a quorum of at least t distinct, non-zero signer ids, with its λᵢ.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignerSet {
+    threshold: usize,
+    weights: LagrangeWeights,
+}
+
+impl SignerSet {
+    pub fn new(ids: &[u64], t: usize) -> Result<Self, Error> {
+        let weights = LagrangeWeights::new(ids)?;
+        if ids.len() < t {
+            return Err(Error::NotEnoughSigners {
+                threshold: t,
+                actual: ids.len(),
+            });
+        }
+
+        Ok(Self {
+            threshold: t,
+            weights,
+        })
+    }
+
+    /// the signer ids, in the order given to `new`.
+    pub fn ids(&self) -> &[u64] {
+        self.weights.ids()
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    pub fn contains(&self, id: u64) -> bool {
+        self.weights.weight(id).is_some()
+    }
+
+    pub fn weights(&self) -> &LagrangeWeights {
+        &self.weights
+    }
+
+    /// `received` in `round` holds every member of the set, each once.
+    fn check_complete(&self, round: u8, received: &[u64]) -> Result<(), Error> {
+        check_unique(received.iter().copied())?;
+        match self.ids().iter().find(|id| !received.contains(id)) {
+            Some(&id) => Err(Error::MissingPackage { round, id }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// aggregate the public key from a set of participants.
+/// X = Σ λᵢ·Xᵢ where λᵢ is the Lagrange coefficient
+pub fn aggregate_public_key(
+    public_keys: &[(u64, ProjectivePoint)],
+) -> Result<ProjectivePoint, Error> {
+    let ids: Vec<u64> = public_keys.iter().map(|(id, _)| *id).collect();
+    aggregate_public_key_with_weights(public_keys, &LagrangeWeights::new(&ids)?)
+}
+
+/// like `aggregate_public_key` with weights precomputed for the signer set.
+/// keys of participants outside of the set are skipped.
+pub fn aggregate_public_key_with_weights(
+    public_keys: &[(u64, ProjectivePoint)],
+    weights: &LagrangeWeights,
+) -> Result<ProjectivePoint, Error> {
+    interpolate_points(public_keys, weights)
+}
+
+/// R = Σ λᵢ·Rᵢ over `signers`, each of which needs a nonce.
+/// nonces of participants outside of the set are skipped.
+pub fn aggregate_nonce_with_signers(
+    nonces: &[(u64, ProjectivePoint)],
+    signers: &SignerSet,
+) -> Result<ProjectivePoint, Error> {
+    signers.check_complete(1, &nonces.iter().map(|(id, _)| *id).collect::<Vec<_>>())?;
+    aggregate_nonce_with_weights(nonces, signers.weights())
+}
+
+#[deprecated(note = "fixes no threshold, use `aggregate_nonce_with_signers` with a `SignerSet`")]
+pub fn aggregate_nonce(
+    nonces: &[(u64, ProjectivePoint)],
+    ids: &[u64],
+) -> Result<ProjectivePoint, Error> {
+    aggregate_nonce_with_signers(nonces, &SignerSet::new(ids, ids.len())?)
+}
+
+/// like `aggregate_nonce_with_signers` with weights precomputed for the
+/// signer set. nonces of participants outside of the set are skipped.
+pub fn aggregate_nonce_with_weights(
+    nonces: &[(u64, ProjectivePoint)],
+    weights: &LagrangeWeights,
+) -> Result<ProjectivePoint, Error> {
+    interpolate_points(nonces, weights)
+}
+
+fn interpolate_points(
+    points: &[(u64, ProjectivePoint)],
+    weights: &LagrangeWeights,
+) -> Result<ProjectivePoint, Error> {
+    check_unique(points.iter().map(|(id, _)| *id))?;
+    let terms = points
+        .iter()
+        .filter_map(|(id, P)| weights.weight(*id).map(|lambda| (*P, lambda)))
+        .collect::<Vec<_>>();
+    for (P, _) in &terms {
+        ensure_not_identity(P)?;
+    }
+
+    // cancelling inputs are only possible when someone picked theirs after
+    // seeing the others
+    let P = hazmat::multiscalar_mul(&terms);
+    ensure_not_identity(&P)?;
+    Ok(P)
+}
+
+/// a second value from the same participant would be counted twice.
+fn check_unique(ids: impl Iterator<Item = u64>) -> Result<(), Error> {
+    let mut seen = BTreeSet::new();
+    for id in ids {
+        if !seen.insert(id) {
+            return Err(Error::DuplicateParticipantId(id));
+        }
+    }
+
+    Ok(())
+}
+
+#[deprecated(note = "moved to `shamy_core::hazmat::lagrange_coefficient`")]
+pub fn lagrange_coefficient(id_i: u64, ids: &[u64]) -> Scalar {
+    hazmat::lagrange_coefficient(id_i, ids)
+}
+
+/// compute a partial signature s_i = r_i + c·x_i where:
+/// - r_i is the participant's nonce
+/// - c is the challenge
+/// - x_i is the participant's secret key
+///
+/// nothing stops a second call with the same nonce, which leaks x_i.
+/// `nonces::NonceRegistry::partial_sign` refuses a nonce it has seen.
+/// `backend::ShareBackend` signs without x_i or r_i in memory.
+pub fn partial_sign(participant: &Participant, r_i: &Scalar, c: &Scalar) -> PartialSignature {
+    PartialSignature {
+        id: participant.id,
+        s_i: r_i + (participant.x_i * c),
+        R_i: Some(compute_nonce_point(r_i)),
+        X_i: Some(participant.X_i),
+    }
+}
+
+//--------------------------------------------------------------------
+// Even-Y nonces (BIP-340)
+//--------------------------------------------------------------------
+//
+// a BIP-340 signature only carries x(R) and is checked against x(X), the
+// verifier lifts both to the point with even Y. once R = Σ λᵢ·Rᵢ is known
+// every signer flips its values to match:
+//
+//   rᵢ' = -rᵢ  if y(R) is odd         Σ λᵢ·rᵢ'·G = -R      (even Y)
+//   xᵢ' = -xᵢ  if y(X) is odd         Σ λᵢ·xᵢ'·G = -X      (even Y)
+//   sᵢ  = rᵢ' + c·xᵢ'                 c over x(R), x(X)
+//
+// R and X are public, so the coordinator and every signer reach the same
+// flags without another round. a key from `KeygenOutput::normalize_even_y`
+// never needs the second flip.
+//
+// ⚠️ without the flips the signature still passes s*G == R + c*X but no
+//    BIP-340 verifier accepts it.
+//
+
+/// the flips every signer applies in a BIP-340 session, agreed from R and X.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EvenY {
+    /// R has odd Y, every rᵢ is negated.
+    pub negate_nonce: bool,
+    /// X has odd Y, every xᵢ is negated.
+    pub negate_key: bool,
+}
+
+impl EvenY {
+    /// flags for the aggregate nonce `R` and the group key `X`.
+    pub fn new(R: &ProjectivePoint, X: &ProjectivePoint) -> Self {
+        Self {
+            negate_nonce: !has_even_y(R),
+            negate_key: !has_even_y(X),
+        }
+    }
+
+    /// ±R, or ±Rᵢ for a signer's nonce point.
+    pub fn nonce(&self, R: &ProjectivePoint) -> ProjectivePoint {
+        match self.negate_nonce {
+            true => -*R,
+            false => *R,
+        }
+    }
+
+    /// ±X, or ±Xᵢ for a public share.
+    pub fn key(&self, X: &ProjectivePoint) -> ProjectivePoint {
+        match self.negate_key {
+            true => -*X,
+            false => *X,
+        }
+    }
+}
+
+/// like `partial_sign` with rᵢ and xᵢ flipped by `even_y`. the partial
+/// carries the flipped Rᵢ and Xᵢ, so it still passes `PartialSignature::verify`.
+pub fn partial_sign_even_y(
+    participant: &Participant,
+    r_i: &Scalar,
+    c: &Scalar,
+    even_y: &EvenY,
+) -> PartialSignature {
+    let r_i = match even_y.negate_nonce {
+        true => -r_i,
+        false => *r_i,
+    };
+    let participant = match even_y.negate_key {
+        true => Participant {
+            x_i: -participant.x_i,
+            X_i: -participant.X_i,
+            ..*participant
+        },
+        false => *participant,
+    };
+
+    partial_sign(&participant, &r_i, c)
+}
+
+//--------------------------------------------------------------------
+// Aggregate partial signatures
+//--------------------------------------------------------------------
+// Combine t partial Schnorr signatures into one.
+//
+//   R = Σ (λ_i * R_i)
+//   s = Σ λ_i * s_i
+//
+// Each partial response is affine in its share:
+//     sᵢ = rᵢ + c · f(i)
+//
+// Because of that linearity,
+//     s = Σ λᵢ sᵢ = r + c · f(0)   where r = Σ λᵢ rᵢ, and f(0) is the private key
+//
+// every member of `signers` needs a partial, the set R was aggregated over.
+// partials of participants outside of the set are skipped.
+//
+pub fn finalize_signature_with_signers(
+    partials: &[PartialSignature],
+    R: ProjectivePoint,
+    signers: &SignerSet,
+) -> Result<SchnorrSignature, Error> {
+    signers.check_complete(2, &partials.iter().map(|p| p.id).collect::<Vec<_>>())?;
+    finalize_signature_with_weights(partials, R, signers.weights())
+}
+
+/// weighs over the ids of `partials`, so a missing partial goes unnoticed.
+#[deprecated(
+    note = "use `finalize_signature_with_signers` over the `SignerSet` R was aggregated with"
+)]
+pub fn finalize_signature_lagrange(
+    partials: &[PartialSignature],
+    R: ProjectivePoint,
+) -> Result<SchnorrSignature, Error> {
+    let ids = partials.iter().map(|p| p.id).collect::<Vec<_>>();
+    finalize_signature_with_signers(partials, R, &SignerSet::new(&ids, ids.len())?)
+}
+
+/// like `finalize_signature_with_signers` with weights precomputed for the
+/// signer set. partials of participants outside of the set are skipped.
+pub fn finalize_signature_with_weights(
+    partials: &[PartialSignature],
+    R: ProjectivePoint,
+    weights: &LagrangeWeights,
+) -> Result<SchnorrSignature, Error> {
+    check_unique(partials.iter().map(|p| p.id))?;
+    let s = partials
+        .iter()
+        .filter_map(|p| weights.weight(p.id).map(|lambda| lambda * p.s_i))
+        .sum();
+
+    SchnorrSignature { R, s }.normalize()
+}
+
+//--------------------------------------------------------------------
+// Identifiable aborts
+//--------------------------------------------------------------------
+//
+// a bad s_i makes (R, s) invalid without saying whose it was. checking
+// every partial on its own before combining names the culprits:
+//
+//   s_i*G == R_i + c*X_i
+//
+// R_i and X_i have to come from the nonce round and the keygen output,
+// never from the signer being checked.
+//
+
+/// why a participant was blamed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Misbehavior {
+    /// s_i*G != R_i + c*X_i.
+    InvalidPartial,
+    /// sent a partial without a nonce commitment.
+    MissingNonce,
+    /// not a participant of the key, there is no X_i for it.
+    UnknownSigner,
+}
+
+/// participants that broke an aggregation, in the order of their partials.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CulpritReport {
+    pub culprits: Vec<(u64, Misbehavior)>,
+}
+
+impl CulpritReport {
+    pub fn ids(&self) -> Vec<u64> {
+        self.culprits.iter().map(|(id, _)| *id).collect()
+    }
+}
+
+/// like `finalize_signature_with_signers`, but checks every partial against the
+/// signer's nonce commitment and public share first. R is aggregated from
+/// the commitments of the signers in `partials`.
+/// fails with `Error::Misbehavior` naming every bad signer.
+pub fn finalize_signature_identifiable(
+    partials: &[PartialSignature],
+    nonces: &[(u64, ProjectivePoint)],
+    public_shares: &[(u64, ProjectivePoint)],
+    c: &Scalar,
+) -> Result<SchnorrSignature, Error> {
+    let find = |points: &[(u64, ProjectivePoint)], id: u64| {
+        points.iter().find(|(i, _)| *i == id).map(|(_, P)| *P)
+    };
+
+    let culprits = partials
+        .iter()
+        .filter_map(|partial| {
+            let misbehavior = match (find(public_shares, partial.id), find(nonces, partial.id)) {
+                (None, _) => Misbehavior::UnknownSigner,
+                (_, None) => Misbehavior::MissingNonce,
+                (Some(X_i), Some(R_i))
+                    if ProjectivePoint::GENERATOR * partial.s_i != R_i + X_i * c =>
+                {
+                    Misbehavior::InvalidPartial
+                }
+                _ => return None,
+            };
+            Some((partial.id, misbehavior))
+        })
+        .collect::<Vec<_>>();
+    if !culprits.is_empty() {
+        return Err(Error::Misbehavior(CulpritReport { culprits }));
+    }
+
+    let ids = partials.iter().map(|p| p.id).collect::<Vec<_>>();
+    let signers = SignerSet::new(&ids, ids.len())?;
+    let R = aggregate_nonce_with_signers(
+        &ids.iter()
+            .map(|id| (*id, find(nonces, *id).unwrap()))
+            .collect::<Vec<_>>(),
+        &signers,
+    )?;
+
+    finalize_signature_with_signers(partials, R, &signers)
+}
+
+//--------------------------------------------------------------------
+// Aggregation proof
+//--------------------------------------------------------------------
+//
+// a valid (R, s) only shows that *some* quorum signed. the proof keeps
+// every signer's contribution so an auditor holding the public shares
+// Xᵢ can check which quorum it was:
+//
+//   λᵢ       = Lagrange coefficient of i in the signer set
+//   R        = Σ λᵢ Rᵢ
+//   s        = Σ λᵢ sᵢ
+//   X        = Σ λᵢ Xᵢ            the quorum's shares belong to the key
+//   sᵢ·G     = Rᵢ + c·Xᵢ          each signer answered the challenge
+//
+// ⚠️ only as good as the public shares it's checked against, take them
+//    from the keygen output, not from whoever hands over the proof.
+//
+
+/// one signer's share of an aggregated signature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignerContribution {
+    pub id: u64,
+    pub R_i: ProjectivePoint,
+    pub s_i: Scalar,
+    pub lambda: Scalar,
+}
+
+/// a signature together with the contributions of the quorum that produced it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AggregationProof {
+    pub signature: SchnorrSignature,
+    pub signers: Vec<SignerContribution>,
+}
+
+impl AggregationProof {
+    /// collect the contributions, `nonces` and `partials` in any order.
+    pub fn new(
+        signature: SchnorrSignature,
+        nonces: &[(u64, ProjectivePoint)],
+        partials: &[PartialSignature],
+    ) -> Result<Self, Error> {
+        let ids = partials.iter().map(|p| p.id).collect::<Vec<_>>();
+        hazmat::validate_ids(&ids)?;
+        let signers = partials
+            .iter()
+            .map(|partial| {
+                let (_, R_i) = nonces.iter().find(|(id, _)| *id == partial.id).ok_or(
+                    Error::MissingPackage {
+                        round: 1,
+                        id: partial.id,
+                    },
+                )?;
+                Ok(SignerContribution {
+                    id: partial.id,
+                    R_i: *R_i,
+                    s_i: partial.s_i,
+                    lambda: hazmat::lagrange_coefficient(partial.id, &ids),
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(Self { signature, signers })
+    }
+
+    /// (id, Rᵢ) of the signers, e.g. for `SigningConfig::with_signer_set`.
+    pub fn nonces(&self) -> Vec<(u64, ProjectivePoint)> {
+        self.signers.iter().map(|s| (s.id, s.R_i)).collect()
+    }
+
+    /// ids of the signers, in the order of the proof.
+    pub fn quorum(&self) -> Vec<u64> {
+        self.signers.iter().map(|s| s.id).collect()
+    }
+
+    /// check the signature and that exactly the quorum's shares produced it.
+    /// `public_shares` are (id, Xᵢ) of all participants, or at least the quorum.
+    pub fn verify(
+        &self,
+        message: &[u8],
+        public_key: &ProjectivePoint,
+        public_shares: &[(u64, ProjectivePoint)],
+        config: &SigningConfig,
+    ) -> Result<(), Error> {
+        let ids = self.quorum();
+        hazmat::validate_ids(&ids)?;
+        if !self
+            .signature
+            .verify_with_config(message, public_key, config)
+        {
+            return Err(Error::InvalidSignature);
+        }
+
+        let inconsistent = |what: &str| Err(Error::InvalidAggregationProof(what.to_string()));
+        let (mut R, mut s, mut X) = (
+            ProjectivePoint::IDENTITY,
+            Scalar::ZERO,
+            ProjectivePoint::IDENTITY,
+        );
+        let c = config.challenge(&self.signature.R, public_key, message);
+        // the BIP-340 signers of an odd-Y key signed with -xᵢ, see `EvenY`
+        let even_y = match config.challenge_mode() {
+            ChallengeMode::Bip340 => EvenY::new(&self.signature.R, public_key),
+            _ => EvenY::default(),
+        };
+        for signer in &self.signers {
+            if signer.lambda != hazmat::lagrange_coefficient(signer.id, &ids) {
+                return inconsistent(&format!("wrong coefficient for signer {}", signer.id));
+            }
+            let (_, X_i) = public_shares
+                .iter()
+                .find(|(id, _)| *id == signer.id)
+                .ok_or(Error::InvalidAggregationProof(format!(
+                    "no public share for signer {}",
+                    signer.id
+                )))?;
+            let X_i = even_y.key(X_i);
+            if ProjectivePoint::GENERATOR * signer.s_i != signer.R_i + X_i * c {
+                return Err(Error::InvalidPartialSignature { id: signer.id });
+            }
+
+            R += signer.R_i * signer.lambda;
+            s += signer.s_i * signer.lambda;
+            X += X_i * signer.lambda;
+        }
+
+        if R != self.signature.R || s != self.signature.s {
+            return inconsistent("contributions don't add up to the signature");
+        }
+        if X != even_y.key(public_key) {
+            return inconsistent("public shares of the quorum don't match the public key");
+        }
+
+        Ok(())
+    }
+}
+
+//--------------------------------------------------------------------
+// Signing session state machines
+//--------------------------------------------------------------------
+//
+//   coordinator (SigningSession)          signer (SignerState)
+//
+//   CollectingNonces   ◄── R_i ────────── commit()
+//         │ all R_i
+//         ▼
+//   CollectingPartials ─── R, c ────────► sign(c)
+//                      ◄── s_i ─────────
+//         │ all s_i
+//         ▼
+//   Finalized ─► (R, s)
+//
+// messages are numbered like protocol rounds in errors:
+// round 1 = nonce commitments, round 2 = partial signatures.
+//
+
+/// where a `SigningSession` is in the protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionPhase {
+    CollectingNonces,
+    CollectingPartials,
+    Finalized,
+}
+
+/// coordinator side of one threshold signing session.
+/// rejects messages from unknown signers, duplicates and out of order messages.
+#[derive(Debug, Clone)]
+pub struct SigningSession {
+    public_key: ProjectivePoint,
+    message: Vec<u8>,
+    prehashed: bool,
+    signer_ids: Vec<u64>,
+    weights: LagrangeWeights,
+    config: SigningConfig,
+    nonces: BTreeMap<u64, ProjectivePoint>,
+    partials: BTreeMap<u64, Scalar>,
+    challenge: Option<(ProjectivePoint, Scalar)>,
+    even_y: Option<EvenY>,
+    signature: Option<SchnorrSignature>,
+    phase: SessionPhase,
+    transcript: Option<Transcript>,
+    bind_signers: bool,
+}
+
+impl SigningSession {
+    pub fn new(
+        public_key: ProjectivePoint,
+        message: &[u8],
+        signer_ids: &[u64],
+        config: SigningConfig,
+    ) -> Result<Self, Error> {
+        config.check_bindings()?;
+        let weights = LagrangeWeights::new(signer_ids)?;
+
+        Ok(Self {
+            public_key,
+            message: message.to_vec(),
+            prehashed: false,
+            signer_ids: signer_ids.to_vec(),
+            weights,
+            config,
+            nonces: BTreeMap::new(),
+            partials: BTreeMap::new(),
+            challenge: None,
+            even_y: None,
+            signature: None,
+            phase: SessionPhase::CollectingNonces,
+            transcript: None,
+            bind_signers: false,
+        })
+    }
+
+    /// like `new`, signing `digest = schnorr::prehash(m)` instead of the
+    /// message itself, see `SigningConfig::challenge_prehashed`.
+    pub fn new_prehashed(
+        public_key: ProjectivePoint,
+        digest: &[u8; 32],
+        signer_ids: &[u64],
+        config: SigningConfig,
+    ) -> Result<Self, Error> {
+        let mut session = Self::new(public_key, digest, signer_ids, config)?;
+        session.prehashed = true;
+        Ok(session)
+    }
+
+    /// record every message of the session in a `Transcript` for
+    /// `context` and bind its digest into the challenge. the signature
+    /// then verifies under `Transcript::bound_config`, not `config`.
+    /// needs the v1 or merlin challenge.
+    pub fn with_transcript(mut self, context: &[u8]) -> Result<Self, Error> {
+        if !self.config.supports_bindings() {
+            return Err(Error::UnsupportedChallenge);
+        }
+
+        let mut transcript = Transcript::new(context);
+        transcript.append_point(0, 0, "public_key", &self.public_key);
+        let label = match self.prehashed {
+            true => "digest",
+            false => "message",
+        };
+        transcript.append(0, 0, label, &self.message);
+        let ids = self
+            .signer_ids
+            .iter()
+            .flat_map(|id| id.to_be_bytes())
+            .collect::<Vec<_>>();
+        transcript.append(0, 0, "signer_ids", &ids);
+        self.transcript = Some(transcript);
+        Ok(self)
+    }
+
+    /// hash the signer ids and nonce commitments into the challenge, see
+    /// `SigningConfig::with_signer_set`. the signature then verifies under
+    /// `config.with_signer_set(&aggregation_proof.nonces())`. needs the v1
+    /// or merlin challenge.
+    pub fn bind_signers(mut self) -> Result<Self, Error> {
+        if !self.config.supports_bindings() {
+            return Err(Error::UnsupportedChallenge);
+        }
+        self.bind_signers = true;
+        Ok(self)
+    }
+
+    pub fn phase(&self) -> SessionPhase {
+        self.phase
+    }
+
+    /// the signature, once finalized.
+    pub fn signature(&self) -> Option<SchnorrSignature> {
+        self.signature
+    }
+
+    /// the session transcript, if started `with_transcript`.
+    pub fn transcript(&self) -> Option<&Transcript> {
+        self.transcript.as_ref()
+    }
+
+    pub fn signer_ids(&self) -> &[u64] {
+        &self.signer_ids
+    }
+
+    /// the message being signed, the digest for a prehashed session.
+    pub fn message(&self) -> &[u8] {
+        &self.message
+    }
+
+    /// the session id bound into the challenge, if the config has one.
+    pub fn session_id(&self) -> Option<SessionId> {
+        self.config.session()
+    }
+
+    /// record the nonce commitment Rᵢ of signer `id`.
+    /// the challenge is computed once the last one arrives. the session
+    /// only changes when this returns Ok, a rejected nonce can be resent.
+    pub fn add_nonce(&mut self, id: u64, R_i: ProjectivePoint) -> Result<(), Error> {
+        self.expect_phase(SessionPhase::CollectingNonces)?;
+        self.check_signer(1, id)?;
+        if self.nonces.contains_key(&id) {
+            return Err(Error::DuplicatePackage { round: 1, id });
+        }
+        ensure_not_identity(&R_i)?;
+
+        let mut nonces = self.nonces.clone();
+        nonces.insert(id, R_i);
+        let mut transcript = self.transcript.clone();
+        if let Some(transcript) = &mut transcript {
+            transcript.append_point(1, id, "nonce", &R_i);
+        }
+        let challenge = match nonces.len() == self.signer_ids.len() {
+            true => Some(self.compute_challenge(&nonces, &mut transcript)?),
+            false => None,
+        };
+
+        self.nonces = nonces;
+        self.transcript = transcript;
+        if let Some((R, c, even_y)) = challenge {
+            self.even_y = even_y;
+            self.challenge = Some((R, c));
+            self.phase = SessionPhase::CollectingPartials;
+        }
+
+        Ok(())
+    }
+
+    /// R, c and the even Y flags over the complete `nonces`.
+    fn compute_challenge(
+        &self,
+        nonces: &BTreeMap<u64, ProjectivePoint>,
+        transcript: &mut Option<Transcript>,
+    ) -> Result<(ProjectivePoint, Scalar, Option<EvenY>), Error> {
+        let nonces = nonces
+            .iter()
+            .map(|(id, R_i)| (*id, *R_i))
+            .collect::<Vec<_>>();
+        let mut R = aggregate_nonce_with_weights(&nonces, &self.weights)?;
+        let mut even_y = None;
+        if *self.config.challenge_mode() == ChallengeMode::Bip340 {
+            let flags = EvenY::new(&R, &self.public_key);
+            R = flags.nonce(&R);
+            even_y = Some(flags);
+        }
+        let config = match self.bind_signers {
+            true => self.config.clone().with_signer_set(&nonces),
+            false => self.config.clone(),
+        };
+        let config = match transcript {
+            Some(transcript) => transcript.bind(1, &config)?,
+            None => config,
+        };
+        let c = if self.prehashed {
+            let digest = self
+                .message
+                .as_slice()
+                .try_into()
+                .expect("digest is 32 bytes");
+            config.challenge_prehashed(&R, &self.public_key, digest)
+        } else {
+            config.challenge(&R, &self.public_key, &self.message)
+        };
+
+        Ok((R, c, even_y))
+    }
+
+    /// aggregate nonce R and challenge c to send to the signers.
+    /// under the BIP-340 challenge R already has even Y, see `even_y`.
+    pub fn challenge(&self) -> Result<(ProjectivePoint, Scalar), Error> {
+        self.challenge.ok_or_else(|| self.missing(1, &self.nonces))
+    }
+
+    /// the flips to send along with the challenge under the BIP-340
+    /// challenge, for `SignerState::sign_even_y`. `None` for the other
+    /// challenges and before all nonces are in.
+    pub fn even_y(&self) -> Option<EvenY> {
+        self.even_y
+    }
+
+    pub fn add_partial(&mut self, partial: PartialSignature) -> Result<(), Error> {
+        self.expect_phase(SessionPhase::CollectingPartials)?;
+        self.check_signer(2, partial.id)?;
+        if self.partials.contains_key(&partial.id) {
+            return Err(Error::DuplicatePackage {
+                round: 2,
+                id: partial.id,
+            });
+        }
+        self.partials.insert(partial.id, partial.s_i);
+        if let Some(transcript) = &mut self.transcript {
+            transcript.append_scalar(2, partial.id, "partial", &partial.s_i);
+        }
+
+        Ok(())
+    }
+
+    /// combine the partials once all arrived and verify the result.
+    pub fn finalize(&mut self) -> Result<SchnorrSignature, Error> {
+        self.expect_phase(SessionPhase::CollectingPartials)?;
+        if self.partials.len() != self.signer_ids.len() {
+            return Err(self.missing(2, &self.partials));
+        }
+
+        let (R, c) = self.challenge()?;
+        let partials = self
+            .partials
+            .iter()
+            .map(|(id, s_i)| PartialSignature::new(*id, *s_i))
+            .collect::<Vec<_>>();
+        let signature = finalize_signature_with_weights(&partials, R, &self.weights)?;
+        if ProjectivePoint::GENERATOR * signature.s != R + self.signing_key() * c {
+            return Err(Error::InvalidSignature);
+        }
+        if let Some(transcript) = &mut self.transcript {
+            transcript.append(3, 0, "signature", &signature.to_bytes()?);
+        }
+        self.signature = Some(signature);
+        self.phase = SessionPhase::Finalized;
+
+        Ok(signature)
+    }
+
+    /// like `finalize`, but checks every partial against the signer's public
+    /// share so a bad one fails with `Error::Misbehavior` naming its sender.
+    /// `public_shares` are (id, X_i) from the keygen output.
+    pub fn finalize_identifiable(
+        &mut self,
+        public_shares: &[(u64, ProjectivePoint)],
+    ) -> Result<SchnorrSignature, Error> {
+        self.expect_phase(SessionPhase::CollectingPartials)?;
+        if self.partials.len() != self.signer_ids.len() {
+            return Err(self.missing(2, &self.partials));
+        }
+
+        let (R, c) = self.challenge()?;
+        let partials = self
+            .partials
+            .iter()
+            .map(|(id, s_i)| PartialSignature::new(*id, *s_i))
+            .collect::<Vec<_>>();
+        let signature = finalize_signature_identifiable(
+            &partials,
+            &self.signed_nonces(),
+            &self.signed_shares(public_shares),
+            &c,
+        )?;
+        if ProjectivePoint::GENERATOR * signature.s != R + self.signing_key() * c {
+            return Err(Error::InvalidSignature);
+        }
+        if let Some(transcript) = &mut self.transcript {
+            transcript.append(3, 0, "signature", &signature.to_bytes()?);
+        }
+        self.signature = Some(signature);
+        self.phase = SessionPhase::Finalized;
+
+        Ok(signature)
+    }
+
+    /// evidence against every signer whose partial fails its check, for
+    /// third parties. `public_shares` as in `finalize_identifiable`.
+    pub fn evidence(&self, public_shares: &[(u64, ProjectivePoint)]) -> Vec<MisbehaviorEvidence> {
+        let Some((R, c)) = self.challenge else {
+            return Vec::new();
+        };
+        let partials = self
+            .partials
+            .iter()
+            .map(|(id, s_i)| PartialSignature::new(*id, *s_i))
+            .collect::<Vec<_>>();
+
+        partial_evidence(
+            &partials,
+            &self.signed_nonces(),
+            &self.signed_shares(public_shares),
+            R,
+            c,
+        )
+    }
+
+    /// contributions of every signer, for auditors. only after `finalize`.
+    pub fn aggregation_proof(&self) -> Result<AggregationProof, Error> {
+        self.expect_phase(SessionPhase::Finalized)?;
+        let partials = self
+            .signer_ids
+            .iter()
+            .map(|id| PartialSignature::new(*id, self.partials[id]))
+            .collect::<Vec<_>>();
+
+        AggregationProof::new(self.signature.unwrap(), &self.signed_nonces(), &partials)
+    }
+
+    /// X, flipped like the shares under the BIP-340 challenge.
+    fn signing_key(&self) -> ProjectivePoint {
+        self.even_y.unwrap_or_default().key(&self.public_key)
+    }
+
+    /// (id, Rᵢ) as the signers used them, flipped under the BIP-340 challenge.
+    fn signed_nonces(&self) -> Vec<(u64, ProjectivePoint)> {
+        let even_y = self.even_y.unwrap_or_default();
+        self.nonces
+            .iter()
+            .map(|(id, R_i)| (*id, even_y.nonce(R_i)))
+            .collect()
+    }
+
+    fn signed_shares(
+        &self,
+        public_shares: &[(u64, ProjectivePoint)],
+    ) -> Vec<(u64, ProjectivePoint)> {
+        let even_y = self.even_y.unwrap_or_default();
+        public_shares
+            .iter()
+            .map(|(id, X_i)| (*id, even_y.key(X_i)))
+            .collect()
+    }
+
+    fn expect_phase(&self, expected: SessionPhase) -> Result<(), Error> {
+        match self.phase == expected {
+            true => Ok(()),
+            false => Err(Error::OutOfOrder {
+                expected: format!("{:?}", expected),
+                actual: format!("{:?}", self.phase),
+            }),
+        }
+    }
+
+    fn check_signer(&self, round: u8, id: u64) -> Result<(), Error> {
+        match self.signer_ids.contains(&id) {
+            true => Ok(()),
+            false => Err(Error::UnexpectedPackage { round, id }),
+        }
+    }
+
+    fn missing<T>(&self, round: u8, received: &BTreeMap<u64, T>) -> Error {
+        let id = self
+            .signer_ids
+            .iter()
+            .find(|id| !received.contains_key(id))
+            .copied()
+            .unwrap_or_default();
+        Error::MissingPackage { round, id }
+    }
+}
+
+/// participant side of one signing session. the nonce is generated by
+/// `commit`, used once by `sign` and then dropped, so it can't be reused.
+#[derive(Debug)]
+pub struct SignerState {
+    participant: Participant,
+    nonce: Option<Scalar>,
+    phase: SignerPhase,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SignerPhase {
+    Fresh,
+    Committed,
+    Signed,
+}
+
+impl SignerState {
+    pub fn new(participant: Participant) -> Self {
+        Self {
+            participant,
+            nonce: None,
+            phase: SignerPhase::Fresh,
+        }
+    }
+
+    pub fn id(&self) -> u64 {
+        self.participant.id
+    }
+
+    /// generate the nonce and return Rᵢ for the coordinator.
+    #[cfg(feature = "std")]
+    pub fn commit(&mut self) -> Result<ProjectivePoint, Error> {
+        self.commit_with_rng(&mut OsRng)
+    }
+
+    /// like `commit`, drawing the nonce from `rng`.
+    pub fn commit_with_rng(
+        &mut self,
+        rng: &mut impl CryptoRngCore,
+    ) -> Result<ProjectivePoint, Error> {
+        self.expect_phase(SignerPhase::Fresh)?;
+        let r_i = generate_nonce_with_rng(rng);
+        self.nonce = Some(r_i);
+        self.phase = SignerPhase::Committed;
+
+        Ok(compute_nonce_point(&r_i))
+    }
+
+    /// answer the coordinator's challenge, at most once.
+    pub fn sign(&mut self, c: &Scalar) -> Result<PartialSignature, Error> {
+        self.sign_even_y(c, &EvenY::default())
+    }
+
+    /// like `sign` with the flips of a BIP-340 session, see `SigningSession::even_y`.
+    pub fn sign_even_y(&mut self, c: &Scalar, even_y: &EvenY) -> Result<PartialSignature, Error> {
+        self.expect_phase(SignerPhase::Committed)?;
+        let r_i = self.nonce.take().unwrap();
+        self.phase = SignerPhase::Signed;
+
+        Ok(partial_sign_even_y(&self.participant, &r_i, c, even_y))
+    }
+
+    fn expect_phase(&self, expected: SignerPhase) -> Result<(), Error> {
+        match self.phase == expected {
+            true => Ok(()),
+            false => Err(Error::OutOfOrder {
+                expected: format!("{:?}", expected),
+                actual: format!("{:?}", self.phase),
+            }),
+        }
+    }
+}
+
+//--------------------------------------------------------------------
+// Share refresh and revocation
+//--------------------------------------------------------------------
+//
+// a refresh adds a random polynomial with g(0) = 0 to every share:
+//
+//   xⱼ' = xⱼ + g(j),   Cₖ' = Cₖ + aₖ·G   (k ≥ 1)
+//
+// f(0), X and C₀ don't change, but old and new shares lie on different
+// polynomials and can't be combined. revoking participant r refreshes the
+// shares of everyone else and drops r, so r's share is useless on its own
+// and together with up to t-1 shares from before the refresh.
+//
+// the group then signs a revocation record with the new shares:
+//
+//   message = "shamy/threshold/revocation/v1" || r || X_r || X || C₀' || ... || Cₜ₋₁'
+//
+// anyone holding X can check who was revoked and which commitments are
+// current.
+//
+// ⚠️ the refresh is only effective once every remaining participant has
+//    deleted their old share.
+//
+
+const REVOCATION_PREFIX: &[u8] = b"shamy/threshold/revocation/v1";
+
+/// signed statement that participant `id` was removed from the group.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RevocationRecord {
+    pub id: u64,
+    /// public share of the revoked participant.
+    pub X_i: ProjectivePoint,
+    pub public_key: ProjectivePoint,
+    /// commitments after the refresh.
+    pub commitments: Vec<ProjectivePoint>,
+    pub signature: SchnorrSignature,
+}
+
+impl RevocationRecord {
+    /// bytes signed by the group.
+    pub fn message(&self) -> Vec<u8> {
+        let mut message = REVOCATION_PREFIX.to_vec();
+        message.extend_from_slice(&self.id.to_be_bytes());
+        for point in [&self.X_i, &self.public_key]
+            .into_iter()
+            .chain(&self.commitments)
+        {
+            message.extend_from_slice(PointEncoding::Compressed.encode(point).as_bytes());
+        }
+        message
+    }
+
+    /// check the group's signature over the record.
+    pub fn verify(&self, config: &SigningConfig) -> bool {
+        self.signature
+            .verify_with_config(&self.message(), &self.public_key, config)
+    }
+}
+
+/// the group without the revoked participant, with refreshed shares.
+pub struct Revocation {
+    pub keygen_output: KeygenOutput,
+    pub record: RevocationRecord,
+}
+
+/// refresh every share of `keygen_output`, keeping the group key.
+#[cfg(feature = "std")]
+pub fn refresh_shares(keygen_output: &KeygenOutput) -> KeygenOutput {
+    refresh_shares_with_rng(keygen_output, &mut OsRng)
+}
+
+/// like `refresh_shares`, drawing the polynomial from `rng`.
+pub fn refresh_shares_with_rng(
+    keygen_output: &KeygenOutput,
+    rng: &mut impl CryptoRngCore,
+) -> KeygenOutput {
+    let t = keygen_output.commitments.len();
+    let g = hazmat::random_polynomial_with_rng(Scalar::ZERO, t, rng);
+
+    KeygenOutput {
+        participants: keygen_output
+            .participants
+            .iter()
+            .map(|p| Participant::from_secret(p.id, p.x_i + hazmat::eval_polynomial(&g, p.id)))
+            .collect(),
+        public_key: keygen_output.public_key,
+        commitments: keygen_output
+            .commitments
+            .iter()
+            .zip(&g)
+            .map(|(C_k, a_k)| *C_k + calculate_commitment(*a_k))
+            .collect(),
+    }
+}
+
+/// remove participant `id`, refresh the other shares and sign the
+/// revocation record with t of them.
+#[cfg(feature = "std")]
+pub fn revoke_participant(
+    keygen_output: &KeygenOutput,
+    id: u64,
+    config: &SigningConfig,
+) -> Result<Revocation, Error> {
+    revoke_participant_with_rng(keygen_output, id, config, &mut OsRng)
+}
+
+/// like `revoke_participant`, drawing the refresh and nonces from `rng`.
+pub fn revoke_participant_with_rng(
+    keygen_output: &KeygenOutput,
+    id: u64,
+    config: &SigningConfig,
+    rng: &mut impl CryptoRngCore,
+) -> Result<Revocation, Error> {
+    let revoked = keygen_output
+        .participants
+        .iter()
+        .find(|p| p.id == id)
+        .ok_or(Error::InvalidParticipantId {
+            id,
+            num_shares: keygen_output.participants.len(),
+        })?;
+    let t = keygen_output.commitments.len();
+    if keygen_output.participants.len() - 1 < t {
+        return Err(Error::NotEnoughShares {
+            threshold: t,
+            actual: keygen_output.participants.len() - 1,
+        });
+    }
+
+    let remaining = KeygenOutput {
+        participants: keygen_output
+            .participants
+            .iter()
+            .filter(|p| p.id != id)
+            .copied()
+            .collect(),
+        public_key: keygen_output.public_key,
+        commitments: keygen_output.commitments.clone(),
+    };
+    let refreshed = refresh_shares_with_rng(&remaining, rng);
+
+    let mut record = RevocationRecord {
+        id,
+        X_i: revoked.X_i,
+        public_key: refreshed.public_key,
+        commitments: refreshed.commitments.clone(),
+        signature: SchnorrSignature {
+            R: ProjectivePoint::IDENTITY,
+            s: Scalar::ZERO,
+        },
+    };
+    record.signature = sign_with_shares(
+        &refreshed.participants[..t],
+        &refreshed.public_key,
+        &record.message(),
+        config,
+        rng,
+    )?;
+
+    Ok(Revocation {
+        keygen_output: refreshed,
+        record,
+    })
+}
+
+/// the whole signing flow for someone holding every share in `signers`.
+fn sign_with_shares(
+    signers: &[Participant],
+    public_key: &ProjectivePoint,
+    message: &[u8],
+    config: &SigningConfig,
+    rng: &mut impl CryptoRngCore,
+) -> Result<SchnorrSignature, Error> {
+    let ids = signers.iter().map(|p| p.id).collect::<Vec<_>>();
+    let signer_set = SignerSet::new(&ids, ids.len())?;
+    let nonces = signers
+        .iter()
+        .map(|_| generate_nonce_with_rng(rng))
+        .collect::<Vec<_>>();
+    let mut R = aggregate_nonce_with_signers(
+        &ids.iter()
+            .zip(&nonces)
+            .map(|(id, r_i)| (*id, compute_nonce_point(r_i)))
+            .collect::<Vec<_>>(),
+        &signer_set,
+    )?;
+    // every share is here, so the signers agree on even Y without a round
+    let even_y = match config.challenge_mode() {
+        ChallengeMode::Bip340 => EvenY::new(&R, public_key),
+        _ => EvenY::default(),
+    };
+    R = even_y.nonce(&R);
+    let c = config.challenge(&R, public_key, message);
+    let partials = signers
+        .iter()
+        .zip(&nonces)
+        .map(|(p, r_i)| partial_sign_even_y(p, r_i, &c, &even_y))
+        .collect::<Vec<_>>();
+
+    let signature = finalize_signature_with_signers(&partials, R, &signer_set)?;
+    match signature.verify_with_config(message, public_key, config) {
+        true => Ok(signature),
+        false => Err(Error::InvalidSignature),
+    }
+}
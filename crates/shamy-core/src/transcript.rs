@@ -0,0 +1,266 @@
+#![allow(non_snake_case)]
+
+use crate::error::Error;
+use crate::hash::tagged_hasher;
+use crate::schnorr::SigningConfig;
+use crate::util::PointEncoding;
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use k256::{ProjectivePoint, Scalar};
+use sha2::Digest;
+
+const GENESIS_TAG: &[u8] = b"shamy/transcript/genesis/v1";
+const ENTRY_TAG: &[u8] = b"shamy/transcript/entry/v1";
+const TRANSCRIPT_MAGIC: &[u8; 4] = b"SHTR";
+const TRANSCRIPT_VERSION: u8 = 1;
+
+/// label of the entry `bind` appends, its hash goes into the challenge.
+pub const BIND_LABEL: &str = "bind";
+
+//--------------------------------------------------------------------
+// Ceremony transcripts
+//--------------------------------------------------------------------
+//
+// every round message of a ceremony is appended to a hash chain:
+//
+//   h₀ = tagged_hash(genesis, len(context) || context)
+//   hₖ = tagged_hash(entry, hₖ₋₁ || round || sender
+//                          || len(label) || label || len(payload) || payload)
+//
+// changing, dropping or reordering any message changes every later hash.
+// `bind` appends a marker entry and puts its hash into the context of the
+// v1 (or merlin) challenge, so the final signature only verifies together with the
+// transcript up to that point. a dispute is settled by replaying the
+// entries (`Transcript::replay`) and checking the signature against
+// `bound_config`.
+//
+// binary encoding:
+//
+//   "SHTR" || version || len || context || count
+//   || (round || sender || len || label || len || payload || hash) ...
+//
+// lengths and count are 4-byte big-endian, sender is 8 bytes.
+//
+// ⚠️ payloads are stored as given, never append secret shares or nonces.
+//
+
+/// one recorded message and the chain hash after it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranscriptEntry {
+    pub round: u8,
+    /// participant id, 0 for the coordinator.
+    pub sender: u64,
+    pub label: String,
+    pub payload: Vec<u8>,
+    pub hash: [u8; 32],
+}
+
+/// append-only, hash-chained log of a keygen or signing ceremony.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transcript {
+    context: Vec<u8>,
+    entries: Vec<TranscriptEntry>,
+}
+
+impl Transcript {
+    /// empty transcript for the ceremony named by `context`, e.g. a session id.
+    pub fn new(context: &[u8]) -> Self {
+        Self {
+            context: context.to_vec(),
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn context(&self) -> &[u8] {
+        &self.context
+    }
+
+    pub fn entries(&self) -> &[TranscriptEntry] {
+        &self.entries
+    }
+
+    /// hash of the last entry, or of the context while empty.
+    pub fn digest(&self) -> [u8; 32] {
+        match self.entries.last() {
+            Some(entry) => entry.hash,
+            None => genesis(&self.context),
+        }
+    }
+
+    /// record a message and return the new digest.
+    pub fn append(&mut self, round: u8, sender: u64, label: &str, payload: &[u8]) -> [u8; 32] {
+        let hash = chain(&self.digest(), round, sender, label, payload);
+        self.entries.push(TranscriptEntry {
+            round,
+            sender,
+            label: label.to_string(),
+            payload: payload.to_vec(),
+            hash,
+        });
+        hash
+    }
+
+    /// `append` of a compressed point.
+    pub fn append_point(
+        &mut self,
+        round: u8,
+        sender: u64,
+        label: &str,
+        point: &ProjectivePoint,
+    ) -> [u8; 32] {
+        let encoded = PointEncoding::Compressed.encode(point);
+        self.append(round, sender, label, encoded.as_bytes())
+    }
+
+    /// `append` of a big-endian scalar.
+    pub fn append_scalar(
+        &mut self,
+        round: u8,
+        sender: u64,
+        label: &str,
+        scalar: &Scalar,
+    ) -> [u8; 32] {
+        self.append(round, sender, label, &scalar.to_bytes())
+    }
+
+    /// append the bind marker and return `config` with the digest in its
+    /// challenge context. only the v1 and merlin challenges have a context.
+    pub fn bind(&mut self, round: u8, config: &SigningConfig) -> Result<SigningConfig, Error> {
+        if !config.supports_bindings() {
+            return Err(Error::UnsupportedChallenge);
+        }
+        let digest = self.append(round, 0, BIND_LABEL, &[]);
+        bound(config, &digest)
+    }
+
+    /// the config `bind` returned, for checking the signature of a
+    /// replayed transcript. uses the last bind marker.
+    pub fn bound_config(&self, config: &SigningConfig) -> Result<SigningConfig, Error> {
+        let entry = self
+            .entries
+            .iter()
+            .rev()
+            .find(|entry| entry.label == BIND_LABEL && entry.sender == 0)
+            .ok_or_else(|| Error::InvalidEncoding("transcript was never bound".to_string()))?;
+        bound(config, &entry.hash)
+    }
+
+    /// rebuild a transcript from stored entries, checking every hash.
+    pub fn replay(context: &[u8], entries: &[TranscriptEntry]) -> Result<Self, Error> {
+        let mut transcript = Self::new(context);
+        for (index, entry) in entries.iter().enumerate() {
+            let hash = transcript.append(entry.round, entry.sender, &entry.label, &entry.payload);
+            if hash != entry.hash {
+                return Err(Error::TranscriptMismatch { index });
+            }
+        }
+
+        Ok(transcript)
+    }
+
+    /// versioned binary encoding, see the layout above.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = TRANSCRIPT_MAGIC.to_vec();
+        out.push(TRANSCRIPT_VERSION);
+        push_field(&mut out, &self.context);
+        out.extend((self.entries.len() as u32).to_be_bytes());
+        for entry in &self.entries {
+            out.push(entry.round);
+            out.extend(entry.sender.to_be_bytes());
+            push_field(&mut out, entry.label.as_bytes());
+            push_field(&mut out, &entry.payload);
+            out.extend(entry.hash);
+        }
+
+        out
+    }
+
+    /// parse `to_bytes` and replay the entries. fails with
+    /// `Error::TranscriptMismatch` for the first entry that was altered.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let invalid = |reason: &str| Error::InvalidEncoding(reason.to_string());
+        let rest = bytes
+            .strip_prefix(TRANSCRIPT_MAGIC)
+            .ok_or(invalid("not a transcript"))?;
+        let (&version, mut rest) = rest.split_first().ok_or(invalid("truncated"))?;
+        if version != TRANSCRIPT_VERSION {
+            return Err(Error::InvalidEncoding(format!(
+                "unsupported transcript version {version}"
+            )));
+        }
+
+        let context = field(&mut rest)?.to_vec();
+        let count = u32::from_be_bytes(take(&mut rest, 4)?.try_into().unwrap());
+        let mut entries = Vec::new();
+        for _ in 0..count {
+            let round = take(&mut rest, 1)?[0];
+            let sender = u64::from_be_bytes(take(&mut rest, 8)?.try_into().unwrap());
+            let label = String::from_utf8(field(&mut rest)?.to_vec())
+                .map_err(|_| invalid("label is not UTF-8"))?;
+            let payload = field(&mut rest)?.to_vec();
+            let hash = take(&mut rest, 32)?.try_into().unwrap();
+            entries.push(TranscriptEntry {
+                round,
+                sender,
+                label,
+                payload,
+                hash,
+            });
+        }
+        if !rest.is_empty() {
+            return Err(invalid("trailing data"));
+        }
+
+        Self::replay(&context, &entries)
+    }
+}
+
+fn genesis(context: &[u8]) -> [u8; 32] {
+    let mut hasher = tagged_hasher(GENESIS_TAG);
+    hasher.update((context.len() as u32).to_be_bytes());
+    hasher.update(context);
+    hasher.finalize().into()
+}
+
+fn chain(prev: &[u8; 32], round: u8, sender: u64, label: &str, payload: &[u8]) -> [u8; 32] {
+    let mut hasher = tagged_hasher(ENTRY_TAG);
+    hasher.update(prev);
+    hasher.update([round]);
+    hasher.update(sender.to_be_bytes());
+    hasher.update((label.len() as u32).to_be_bytes());
+    hasher.update(label.as_bytes());
+    hasher.update((payload.len() as u32).to_be_bytes());
+    hasher.update(payload);
+    hasher.finalize().into()
+}
+
+fn bound(config: &SigningConfig, digest: &[u8; 32]) -> Result<SigningConfig, Error> {
+    if !config.supports_bindings() {
+        return Err(Error::UnsupportedChallenge);
+    }
+    let mut context = config.context().to_vec();
+    context.extend_from_slice(digest);
+    Ok(config.clone().with_context(&context))
+}
+
+fn push_field(out: &mut Vec<u8>, bytes: &[u8]) {
+    out.extend((bytes.len() as u32).to_be_bytes());
+    out.extend_from_slice(bytes);
+}
+
+fn take<'a>(rest: &mut &'a [u8], len: usize) -> Result<&'a [u8], Error> {
+    if rest.len() < len {
+        return Err(Error::InvalidEncoding("truncated".to_string()));
+    }
+    let (head, tail) = rest.split_at(len);
+    *rest = tail;
+    Ok(head)
+}
+
+fn field<'a>(rest: &mut &'a [u8]) -> Result<&'a [u8], Error> {
+    let len = u32::from_be_bytes(take(rest, 4)?.try_into().unwrap()) as usize;
+    take(rest, len)
+}
@@ -0,0 +1,710 @@
+use crate::error::Error;
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use hex::{self, FromHex};
+use k256::{
+    AffinePoint, EncodedPoint, ProjectivePoint, Scalar,
+    elliptic_curve::{
+        PrimeField,
+        point::AffineCoordinates,
+        sec1::{FromEncodedPoint, ToEncodedPoint},
+    },
+};
+use sha2::{Digest, Sha256};
+
+/// SEC1 form of a point, 33 bytes compressed or 65 bytes uncompressed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PointEncoding {
+    #[default]
+    Compressed,
+    Uncompressed,
+}
+
+impl PointEncoding {
+    pub fn encode(self, point: &ProjectivePoint) -> EncodedPoint {
+        point
+            .to_affine()
+            .to_encoded_point(self == PointEncoding::Compressed)
+    }
+}
+
+/// compressed hex encoding, the form used everywhere in shamy.
+pub fn pp_to_hex(point: &ProjectivePoint) -> String {
+    pp_to_hex_compressed(point)
+}
+
+pub fn pp_to_hex_compressed(point: &ProjectivePoint) -> String {
+    hex::encode(PointEncoding::Compressed.encode(point).as_bytes())
+}
+
+pub fn pp_to_hex_uncompressed(point: &ProjectivePoint) -> String {
+    hex::encode(PointEncoding::Uncompressed.encode(point).as_bytes())
+}
+
+/// parse a SEC1 compressed or uncompressed point, rejecting the identity,
+/// any other encoding and points that aren't on the curve.
+pub fn hex_to_pp(hex: &str) -> Result<ProjectivePoint, Error> {
+    let raw = Vec::from_hex(hex).map_err(|e| Error::InvalidHex(e.to_string()))?;
+    bytes_to_pp(&raw)
+}
+
+/// like `hex_to_pp` over raw bytes.
+pub fn bytes_to_pp(bytes: &[u8]) -> Result<ProjectivePoint, Error> {
+    match (bytes.first(), bytes.len()) {
+        (Some(0x00), 1) => return Err(Error::IdentityPoint),
+        (Some(0x02 | 0x03), 33) | (Some(0x04), 65) => {}
+        _ => return Err(Error::InvalidPoint("not a canonical encoding".to_string())),
+    }
+    decode_point(bytes)
+}
+
+/// like `hex_to_pp`, but accepts the identity and every SEC1 encoding.
+/// ⚠️ for tests and fixtures only, degenerate points break the protocols.
+pub fn hex_to_pp_unchecked(hex: &str) -> Result<ProjectivePoint, Error> {
+    let raw = Vec::from_hex(hex).map_err(|e| Error::InvalidHex(e.to_string()))?;
+    decode_point(&raw)
+}
+
+/// the point at infinity where a key, nonce or commitment is expected.
+pub fn ensure_not_identity(point: &ProjectivePoint) -> Result<(), Error> {
+    if *point == ProjectivePoint::IDENTITY {
+        return Err(Error::IdentityPoint);
+    }
+
+    Ok(())
+}
+
+fn decode_point(bytes: &[u8]) -> Result<ProjectivePoint, Error> {
+    let encoded =
+        EncodedPoint::from_bytes(bytes).map_err(|e| Error::InvalidPoint(e.to_string()))?;
+    let affine = AffinePoint::from_encoded_point(&encoded)
+        .into_option()
+        .ok_or(Error::InvalidPoint("not on the curve".to_string()))?;
+
+    Ok(ProjectivePoint::from(affine))
+}
+
+pub fn scalar_to_hex(scalar: &Scalar) -> String {
+    let bytes = scalar.to_bytes();
+
+    hex::encode(bytes)
+}
+
+pub fn hex_to_scalar(hex: &str) -> Result<Scalar, Error> {
+    let raw = Vec::from_hex(hex).map_err(|e| Error::InvalidHex(e.to_string()))?;
+    if raw.len() != 32 {
+        return Err(Error::InvalidScalarLength(raw.len()));
+    }
+    let mut buf = [0u8; 32];
+    buf.copy_from_slice(&raw);
+
+    Scalar::from_repr(buf.into())
+        .into_option()
+        .ok_or(Error::InvalidScalar)
+}
+
+/// short id of a key for humans to compare, e.g. on a share label:
+/// first 8 bytes of SHA-256 over the compressed point, in hex.
+pub fn fingerprint(point: &ProjectivePoint) -> String {
+    let encoded = point.to_affine().to_encoded_point(true);
+    let digest = Sha256::digest(encoded.as_bytes());
+
+    hex::encode(&digest[..8])
+}
+
+//--------------------------------------------------------------------
+// X-only public keys (BIP-340)
+//--------------------------------------------------------------------
+//
+// a key is given by x(P) alone and always stands for the point with even Y.
+// a group key with odd Y is flipped to -P after keygen, which means the
+// whole polynomial is negated:
+//
+//   x_i' = -x_i,  X_i' = -X_i,  C_j' = -C_j
+//
+// see `KeygenOutput::normalize_even_y`.
+//
+
+/// whether the affine Y coordinate of `point` is even.
+pub fn has_even_y(point: &ProjectivePoint) -> bool {
+    !bool::from(point.to_affine().y_is_odd())
+}
+
+/// x(P) in 32 bytes, dropping the parity of Y.
+pub fn pp_to_x_only(point: &ProjectivePoint) -> [u8; 32] {
+    point.to_affine().x().into()
+}
+
+pub fn pp_to_hex_x_only(point: &ProjectivePoint) -> String {
+    hex::encode(pp_to_x_only(point))
+}
+
+/// lift a 32-byte x-only key to the point with even Y.
+pub fn x_only_to_pp(bytes: &[u8]) -> Result<ProjectivePoint, Error> {
+    if bytes.len() != 32 {
+        return Err(Error::InvalidPoint("an x-only key is 32 bytes".to_string()));
+    }
+    decode_point(&[&[0x02], bytes].concat())
+}
+
+pub fn hex_to_pp_x_only(hex: &str) -> Result<ProjectivePoint, Error> {
+    let raw = Vec::from_hex(hex).map_err(|e| Error::InvalidHex(e.to_string()))?;
+    x_only_to_pp(&raw)
+}
+
+//--------------------------------------------------------------------
+// Bech32 / Bech32m (BIP-173, BIP-350)
+//--------------------------------------------------------------------
+//
+//   hrp || "1" || data (5-bit groups) || checksum (6 groups)
+//
+// the checksum is a BCH code over the hrp and data, it catches any
+// 4 wrong characters. Bech32m only changes the final constant and is
+// required for segwit v1+ (taproot) addresses.
+//
+
+const BECH32_CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const BECH32_MAX_LEN: usize = 90;
+
+/// checksum variant of a bech32 string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Bech32Variant {
+    Bech32,
+    Bech32m,
+}
+
+impl Bech32Variant {
+    fn constant(self) -> u32 {
+        match self {
+            Bech32Variant::Bech32 => 1,
+            Bech32Variant::Bech32m => 0x2bc8_30a3,
+        }
+    }
+}
+
+/// encode `data` bytes under the human readable part `hrp`.
+pub fn bech32_encode(hrp: &str, data: &[u8], variant: Bech32Variant) -> Result<String, Error> {
+    encode_groups(hrp, &convert_bits(data, 8, 5, true)?, variant)
+}
+
+/// decode a bech32 or bech32m string into `(hrp, data bytes, variant)`.
+pub fn bech32_decode(encoded: &str) -> Result<(String, Vec<u8>, Bech32Variant), Error> {
+    let (hrp, groups, variant) = decode_groups(encoded)?;
+    Ok((hrp, convert_bits(&groups, 5, 8, false)?, variant))
+}
+
+/// segwit address of a witness program, e.g. `segwit_encode("bc", 1, &x(Q))`
+/// for the output key of a `TaprootTweak`.
+pub fn segwit_encode(hrp: &str, version: u8, program: &[u8]) -> Result<String, Error> {
+    check_witness_program(version, program)?;
+    let variant = match version {
+        0 => Bech32Variant::Bech32,
+        _ => Bech32Variant::Bech32m,
+    };
+    let mut groups = alloc::vec![version];
+    groups.extend(convert_bits(program, 8, 5, true)?);
+
+    encode_groups(hrp, &groups, variant)
+}
+
+/// `(version, program)` of a segwit address with human readable part `hrp`.
+pub fn segwit_decode(hrp: &str, address: &str) -> Result<(u8, Vec<u8>), Error> {
+    let (decoded_hrp, groups, variant) = decode_groups(address)?;
+    if decoded_hrp != hrp {
+        return Err(Error::InvalidEncoding(alloc::format!(
+            "expected hrp {hrp}, got {decoded_hrp}"
+        )));
+    }
+    let (&version, data) = groups
+        .split_first()
+        .ok_or(Error::InvalidEncoding("empty witness program".to_string()))?;
+    let expected = match version {
+        0 => Bech32Variant::Bech32,
+        _ => Bech32Variant::Bech32m,
+    };
+    if variant != expected {
+        return Err(Error::InvalidEncoding(
+            "wrong checksum variant for the witness version".to_string(),
+        ));
+    }
+    let program = convert_bits(data, 5, 8, false)?;
+    check_witness_program(version, &program)?;
+
+    Ok((version, program))
+}
+
+/// a scalar, e.g. a share, as bech32 under `hrp`.
+pub fn scalar_to_bech32(hrp: &str, scalar: &Scalar) -> Result<String, Error> {
+    bech32_encode(hrp, &scalar.to_bytes(), Bech32Variant::Bech32m)
+}
+
+/// parse a scalar written by `scalar_to_bech32`, checking the hrp.
+pub fn bech32_to_scalar(hrp: &str, encoded: &str) -> Result<Scalar, Error> {
+    let data = decode_with_hrp(hrp, encoded)?;
+    hex_to_scalar(&hex::encode(data))
+}
+
+/// a compressed point as bech32 under `hrp`.
+pub fn pp_to_bech32(hrp: &str, point: &ProjectivePoint) -> Result<String, Error> {
+    bech32_encode(
+        hrp,
+        PointEncoding::Compressed.encode(point).as_bytes(),
+        Bech32Variant::Bech32m,
+    )
+}
+
+/// parse a point written by `pp_to_bech32`, checking the hrp.
+pub fn bech32_to_pp(hrp: &str, encoded: &str) -> Result<ProjectivePoint, Error> {
+    bytes_to_pp(&decode_with_hrp(hrp, encoded)?)
+}
+
+fn decode_with_hrp(hrp: &str, encoded: &str) -> Result<Vec<u8>, Error> {
+    let (decoded_hrp, data, variant) = bech32_decode(encoded)?;
+    if decoded_hrp != hrp {
+        return Err(Error::InvalidEncoding(alloc::format!(
+            "expected hrp {hrp}, got {decoded_hrp}"
+        )));
+    }
+    if variant != Bech32Variant::Bech32m {
+        return Err(Error::InvalidEncoding(
+            "expected a bech32m checksum".to_string(),
+        ));
+    }
+
+    Ok(data)
+}
+
+fn check_witness_program(version: u8, program: &[u8]) -> Result<(), Error> {
+    let valid = match version {
+        0 => program.len() == 20 || program.len() == 32,
+        1..=16 => (2..=40).contains(&program.len()),
+        _ => false,
+    };
+    if !valid {
+        return Err(Error::InvalidEncoding(alloc::format!(
+            "invalid witness program of version {version} and {} bytes",
+            program.len()
+        )));
+    }
+
+    Ok(())
+}
+
+fn encode_groups(hrp: &str, groups: &[u8], variant: Bech32Variant) -> Result<String, Error> {
+    let hrp = hrp.to_ascii_lowercase();
+    if hrp.is_empty() || hrp.bytes().any(|c| !(33..=126).contains(&c)) {
+        return Err(Error::InvalidEncoding("invalid hrp".to_string()));
+    }
+    if hrp.len() + 1 + groups.len() + 6 > BECH32_MAX_LEN {
+        return Err(Error::InvalidEncoding(
+            "longer than 90 characters".to_string(),
+        ));
+    }
+
+    let mut values = hrp_expand(&hrp);
+    values.extend_from_slice(groups);
+    values.extend_from_slice(&[0; 6]);
+    let checksum = polymod(&values) ^ variant.constant();
+
+    let mut encoded = hrp;
+    encoded.push('1');
+    for group in groups {
+        encoded.push(BECH32_CHARSET[*group as usize] as char);
+    }
+    for i in 0..6 {
+        encoded.push(BECH32_CHARSET[((checksum >> (5 * (5 - i))) & 31) as usize] as char);
+    }
+
+    Ok(encoded)
+}
+
+fn decode_groups(encoded: &str) -> Result<(String, Vec<u8>, Bech32Variant), Error> {
+    let invalid = |reason: &str| Err(Error::InvalidEncoding(reason.to_string()));
+    if encoded.len() > BECH32_MAX_LEN {
+        return invalid("longer than 90 characters");
+    }
+    if encoded.bytes().any(|c| c.is_ascii_lowercase())
+        && encoded.bytes().any(|c| c.is_ascii_uppercase())
+    {
+        return invalid("mixed case");
+    }
+    let encoded = encoded.to_ascii_lowercase();
+    let Some(separator) = encoded.rfind('1') else {
+        return invalid("no separator");
+    };
+    let (hrp, data) = (&encoded[..separator], &encoded[separator + 1..]);
+    if hrp.is_empty() || hrp.bytes().any(|c| !(33..=126).contains(&c)) {
+        return invalid("invalid hrp");
+    }
+    if data.len() < 6 {
+        return invalid("checksum too short");
+    }
+
+    let groups = data
+        .bytes()
+        .map(|c| {
+            BECH32_CHARSET
+                .iter()
+                .position(|d| *d == c)
+                .map(|v| v as u8)
+                .ok_or(Error::InvalidEncoding(alloc::format!(
+                    "invalid character {:?}",
+                    c as char
+                )))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let mut values = hrp_expand(hrp);
+    values.extend_from_slice(&groups);
+    let variant = match polymod(&values) {
+        c if c == Bech32Variant::Bech32.constant() => Bech32Variant::Bech32,
+        c if c == Bech32Variant::Bech32m.constant() => Bech32Variant::Bech32m,
+        _ => return invalid("checksum mismatch"),
+    };
+
+    Ok((
+        hrp.to_string(),
+        groups[..groups.len() - 6].to_vec(),
+        variant,
+    ))
+}
+
+fn polymod(values: &[u8]) -> u32 {
+    const GEN: [u32; 5] = [
+        0x3b6a_57b2,
+        0x2650_8e6d,
+        0x1ea1_19fa,
+        0x3d42_33dd,
+        0x2a14_62b3,
+    ];
+    values.iter().fold(1u32, |chk, v| {
+        let top = chk >> 25;
+        let chk = ((chk & 0x01ff_ffff) << 5) ^ u32::from(*v);
+        GEN.iter()
+            .enumerate()
+            .filter(|(i, _)| (top >> i) & 1 == 1)
+            .fold(chk, |chk, (_, g)| chk ^ g)
+    })
+}
+
+fn hrp_expand(hrp: &str) -> Vec<u8> {
+    let mut values = hrp.bytes().map(|c| c >> 5).collect::<Vec<_>>();
+    values.push(0);
+    values.extend(hrp.bytes().map(|c| c & 31));
+    values
+}
+
+/// regroup bits, e.g. 8-bit bytes into 5-bit groups.
+fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Result<Vec<u8>, Error> {
+    let (mut acc, mut bits) = (0u32, 0u32);
+    let max = (1u32 << to) - 1;
+    let mut out = Vec::with_capacity(data.len() * from as usize / to as usize + 1);
+    for value in data {
+        acc = (acc << from) | u32::from(*value);
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            out.push(((acc >> bits) & max) as u8);
+        }
+    }
+    if pad {
+        if bits > 0 {
+            out.push(((acc << (to - bits)) & max) as u8);
+        }
+    } else if bits >= from || (acc << (to - bits)) & max != 0 {
+        return Err(Error::InvalidEncoding("invalid padding".to_string()));
+    }
+
+    Ok(out)
+}
+
+//--------------------------------------------------------------------
+// Base58Check
+//--------------------------------------------------------------------
+//
+//   base58(payload || SHA-256(SHA-256(payload))[..4])
+//
+// leading zero bytes become leading '1's. the alphabet leaves out 0, O,
+// I and l, which are easy to mix up when copied by hand.
+//
+
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// base58 of `payload` (version bytes included) followed by its checksum.
+pub fn base58check_encode(payload: &[u8]) -> String {
+    let mut data = payload.to_vec();
+    data.extend_from_slice(&Sha256::digest(Sha256::digest(payload))[..4]);
+
+    let zeros = data.iter().take_while(|b| **b == 0).count();
+    // base 256 -> base 58, little endian digits
+    let mut digits: Vec<u8> = Vec::new();
+    for byte in &data[zeros..] {
+        let mut carry = u32::from(*byte);
+        for digit in digits.iter_mut() {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    core::iter::repeat_n('1', zeros)
+        .chain(
+            digits
+                .iter()
+                .rev()
+                .map(|d| BASE58_ALPHABET[*d as usize] as char),
+        )
+        .collect()
+}
+
+/// payload of a Base58Check string, after checking its checksum.
+pub fn base58check_decode(encoded: &str) -> Result<Vec<u8>, Error> {
+    let zeros = encoded.bytes().take_while(|c| *c == b'1').count();
+    // base 58 -> base 256, little endian bytes
+    let mut bytes: Vec<u8> = Vec::new();
+    for c in encoded.bytes().skip(zeros) {
+        let mut carry =
+            BASE58_ALPHABET
+                .iter()
+                .position(|d| *d == c)
+                .ok_or(Error::InvalidEncoding(alloc::format!(
+                    "invalid character {:?}",
+                    c as char
+                )))? as u32;
+        for byte in bytes.iter_mut() {
+            carry += u32::from(*byte) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    let mut data = alloc::vec![0u8; zeros];
+    data.extend(bytes.iter().rev());
+
+    if data.len() < 4 {
+        return Err(Error::InvalidEncoding("checksum too short".to_string()));
+    }
+    let (payload, checksum) = data.split_at(data.len() - 4);
+    if Sha256::digest(Sha256::digest(payload))[..4] != *checksum {
+        return Err(Error::InvalidEncoding("checksum mismatch".to_string()));
+    }
+
+    Ok(payload.to_vec())
+}
+
+//--------------------------------------------------------------------
+// BIP-39 mnemonic
+//--------------------------------------------------------------------
+//
+//   bits  = share (256 bits) || SHA-256(share)[0] (8 bits)
+//   words = wordlist[bits[0..11]], wordlist[bits[11..22]], ...  (24 words)
+//
+// the phrase is the share itself, it is only a paper friendly way of
+// writing the 32 bytes down. it is not a BIP-39 seed: nothing is derived
+// from it with PBKDF2, and a passphrase has no meaning here.
+//
+
+const BIP39_WORDLIST: &str = include_str!("wordlist/english.txt");
+const MNEMONIC_WORDS: usize = 24;
+
+/// the 2048 word BIP-39 English list, sorted.
+pub(crate) fn bip39_wordlist() -> Vec<&'static str> {
+    BIP39_WORDLIST.lines().collect()
+}
+
+/// write a share as a 24-word BIP-39 phrase.
+pub fn share_to_mnemonic(share: &Scalar) -> String {
+    let words = bip39_wordlist();
+    let entropy = share.to_bytes();
+    let checksum = Sha256::digest(entropy)[0];
+
+    let (mut acc, mut bits) = (0u32, 0u32);
+    let mut phrase = Vec::with_capacity(MNEMONIC_WORDS);
+    for byte in entropy.iter().chain(core::iter::once(&checksum)) {
+        acc = (acc << 8) | u32::from(*byte);
+        bits += 8;
+        if bits >= 11 {
+            bits -= 11;
+            phrase.push(words[((acc >> bits) & 0x7ff) as usize]);
+        }
+    }
+
+    phrase.join(" ")
+}
+
+/// parse a share written by `share_to_mnemonic`, checking the checksum.
+pub fn mnemonic_to_share(mnemonic: &str) -> Result<Scalar, Error> {
+    let words = bip39_wordlist();
+    let phrase = mnemonic.split_whitespace().collect::<Vec<_>>();
+    if phrase.len() != MNEMONIC_WORDS {
+        return Err(Error::InvalidEncoding(alloc::format!(
+            "expected {MNEMONIC_WORDS} words, got {}",
+            phrase.len()
+        )));
+    }
+
+    let (mut acc, mut bits) = (0u32, 0u32);
+    let mut data = Vec::with_capacity(33);
+    for word in phrase {
+        let index = words
+            .binary_search(&word.to_lowercase().as_str())
+            .map_err(|_| Error::InvalidEncoding(alloc::format!("unknown word {word:?}")))?;
+        acc = (acc << 11) | index as u32;
+        bits += 11;
+        while bits >= 8 {
+            bits -= 8;
+            data.push((acc >> bits) as u8);
+        }
+    }
+
+    let (entropy, checksum) = data.split_at(32);
+    if Sha256::digest(entropy)[0] != checksum[0] {
+        return Err(Error::InvalidEncoding("checksum mismatch".to_string()));
+    }
+    let mut buf = [0u8; 32];
+    buf.copy_from_slice(entropy);
+
+    Scalar::from_repr(buf.into())
+        .into_option()
+        .ok_or(Error::InvalidScalar)
+}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for k256::ProjectivePoint {}
+    impl Sealed for k256::Scalar {}
+}
+
+/// hex encoding as methods on curve points.
+/// sealed: implemented for `ProjectivePoint` only.
+pub trait PointExt: sealed::Sealed {
+    fn to_hex(&self) -> String;
+}
+
+impl PointExt for ProjectivePoint {
+    fn to_hex(&self) -> String {
+        pp_to_hex(self)
+    }
+}
+
+/// hex encoding as methods on scalars.
+/// sealed: implemented for `Scalar` only.
+pub trait ScalarExt: sealed::Sealed {
+    fn to_hex(&self) -> String;
+}
+
+impl ScalarExt for Scalar {
+    fn to_hex(&self) -> String {
+        scalar_to_hex(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::schnorr::{compute_nonce_point, generate_nonce};
+
+    use super::*;
+
+    #[test]
+    fn test_pp_valid_roundtrip() {
+        let nonce = generate_nonce();
+        let nonce_point = compute_nonce_point(&nonce);
+        let hex = pp_to_hex(&nonce_point);
+        let decoded = hex_to_pp(&hex).unwrap();
+        assert_eq!(nonce_point, decoded);
+    }
+
+    #[test]
+    fn test_hex_to_pp_invalid() {
+        let hex = "invalid";
+        let decoded = hex_to_pp(hex);
+        assert!(decoded.is_err());
+    }
+
+    #[test]
+    fn test_pp_encodings() {
+        let point = compute_nonce_point(&generate_nonce());
+        let compressed = pp_to_hex_compressed(&point);
+        let uncompressed = pp_to_hex_uncompressed(&point);
+        assert_eq!(pp_to_hex(&point), compressed);
+        assert_eq!(compressed.len(), 66);
+        assert_eq!(uncompressed.len(), 130);
+        assert_eq!(&uncompressed[2..66], &compressed[2..]);
+        assert_eq!(hex_to_pp(&compressed), Ok(point));
+        assert_eq!(hex_to_pp(&uncompressed), Ok(point));
+    }
+
+    #[test]
+    fn test_hex_to_pp_strict() {
+        let point = compute_nonce_point(&generate_nonce());
+
+        assert_eq!(hex_to_pp("00"), Err(Error::IdentityPoint));
+        assert_eq!(hex_to_pp_unchecked("00"), Ok(ProjectivePoint::IDENTITY));
+
+        // x-only "compact" encoding, and a compressed point with a trailing byte
+        let x_only = format!("05{}", &pp_to_hex(&point)[2..]);
+        assert!(matches!(hex_to_pp(&x_only), Err(Error::InvalidPoint(_))));
+        assert!(matches!(
+            hex_to_pp(&format!("{}00", pp_to_hex(&point))),
+            Err(Error::InvalidPoint(_))
+        ));
+
+        // x = 5 has no point on secp256k1
+        let off_curve = format!("02{:064x}", 5);
+        assert!(matches!(hex_to_pp(&off_curve), Err(Error::InvalidPoint(_))));
+    }
+
+    #[test]
+    fn test_scalar_valid_roundtrip() {
+        let nonce = generate_nonce();
+        let hex = scalar_to_hex(&nonce);
+        let decoded = hex_to_scalar(&hex).unwrap();
+        assert_eq!(nonce, decoded);
+    }
+
+    #[test]
+    fn test_scalar_invalid_roundtrip() {
+        let hex = "invalid";
+        let decoded = hex_to_scalar(hex);
+        assert!(decoded.is_err());
+    }
+
+    #[test]
+    fn test_scalar_invalid_length() {
+        let hex = "042069";
+        let decoded = hex_to_scalar(hex);
+        assert_eq!(decoded, Err(Error::InvalidScalarLength(3)));
+    }
+
+    #[test]
+    fn test_ext_traits_match_free_functions() {
+        let nonce = generate_nonce();
+        let nonce_point = compute_nonce_point(&nonce);
+        assert_eq!(nonce.to_hex(), scalar_to_hex(&nonce));
+        assert_eq!(nonce_point.to_hex(), pp_to_hex(&nonce_point));
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let point = compute_nonce_point(&generate_nonce());
+        let other = compute_nonce_point(&generate_nonce());
+        assert_eq!(fingerprint(&point).len(), 16);
+        assert_eq!(
+            fingerprint(&point),
+            fingerprint(&hex_to_pp(&pp_to_hex(&point)).unwrap())
+        );
+        assert_ne!(fingerprint(&point), fingerprint(&other));
+    }
+}
@@ -0,0 +1,274 @@
+#![allow(non_snake_case)]
+
+use crate::error::Error;
+use crate::hash::tagged_hash;
+use crate::limits::Limits;
+use crate::schnorr::{SchnorrSignature, SigningConfig, compute_nonce_point};
+use crate::shamir::shamir_keygen_with_rng;
+use crate::threshold::{
+    PartialSignature, Participant, SignerSet, aggregate_nonce_with_signers,
+    finalize_signature_with_signers, partial_sign,
+};
+use crate::vss::verify_share;
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use k256::{
+    ProjectivePoint, Scalar,
+    elliptic_curve::{
+        Field,
+        rand_core::{CryptoRng, RngCore},
+    },
+};
+
+const DRBG_TAG: &[u8] = b"shamy/vectors/drbg/v1";
+
+//--------------------------------------------------------------------
+// Known-answer test vectors
+//--------------------------------------------------------------------
+//
+// a seed fixes every random value of a keygen and one signing round
+//
+//   block_k = tagged_hash(tag, seed || k)      (k as 8-byte big-endian)
+//
+// the randomness is drawn in order: keygen polynomial, then one nonce per
+// signer (the first t participants).
+//
+// `SigningVector::verify` only recomputes what follows from the secrets in
+// the vector (public shares, commitments, R, c, s_i, signature), so vectors
+// written by another implementation with its own randomness check as well.
+//
+// ⚠️ for tests only, anyone who knows the seed knows every share.
+//
+
+/// deterministic RNG for test vectors.
+pub struct SeededRng {
+    seed: [u8; 32],
+    counter: u64,
+}
+
+impl SeededRng {
+    pub fn new(seed: [u8; 32]) -> Self {
+        Self { seed, counter: 0 }
+    }
+}
+
+impl RngCore for SeededRng {
+    fn next_u32(&mut self) -> u32 {
+        self.next_u64() as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0u8; 8];
+        self.fill_bytes(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(32) {
+            let mut input = self.seed.to_vec();
+            input.extend(self.counter.to_be_bytes());
+            self.counter += 1;
+            let block = tagged_hash(DRBG_TAG, &input);
+            chunk.copy_from_slice(&block[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(
+        &mut self,
+        dest: &mut [u8],
+    ) -> Result<(), k256::elliptic_curve::rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl CryptoRng for SeededRng {}
+
+/// one signer's nonce r_i and R_i = r_i*G.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NonceVector {
+    pub id: u64,
+    pub r_i: Scalar,
+    pub R_i: ProjectivePoint,
+}
+
+/// a t-of-n keygen and one signing round by the first t participants.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SigningVector {
+    pub seed: [u8; 32],
+    pub threshold: usize,
+    pub app_id: String,
+    pub purpose: String,
+    pub message: Vec<u8>,
+    pub public_key: ProjectivePoint,
+    pub commitments: Vec<ProjectivePoint>,
+    pub participants: Vec<Participant>,
+    pub nonces: Vec<NonceVector>,
+    pub R: ProjectivePoint,
+    pub challenge: Scalar,
+    pub partial_signatures: Vec<PartialSignature>,
+    pub signature: SchnorrSignature,
+}
+
+/// generate the vector of a t-of-n group signing `message` under
+/// `SigningConfig::new(app_id, purpose)`, with all randomness from `seed`.
+pub fn generate(
+    seed: [u8; 32],
+    num_shares: usize,
+    threshold: usize,
+    app_id: &str,
+    purpose: &str,
+    message: &[u8],
+) -> Result<SigningVector, Error> {
+    let mut rng = SeededRng::new(seed);
+    let keygen_output =
+        shamir_keygen_with_rng(num_shares, threshold, &Limits::default(), &mut rng)?;
+
+    let signers = &keygen_output.participants[..threshold];
+    let nonces = signers
+        .iter()
+        .map(|p| {
+            let r_i = Scalar::random(&mut rng);
+            NonceVector {
+                id: p.id,
+                r_i,
+                R_i: compute_nonce_point(&r_i),
+            }
+        })
+        .collect::<Vec<_>>();
+    let round = sign(
+        signers,
+        &nonces,
+        &keygen_output.public_key,
+        &SigningConfig::new(app_id, purpose),
+        message,
+    )?;
+
+    Ok(SigningVector {
+        seed,
+        threshold,
+        app_id: app_id.to_string(),
+        purpose: purpose.to_string(),
+        message: message.to_vec(),
+        public_key: keygen_output.public_key,
+        commitments: keygen_output.commitments,
+        participants: keygen_output.participants,
+        nonces,
+        R: round.R,
+        challenge: round.challenge,
+        partial_signatures: round.partial_signatures,
+        signature: round.signature,
+    })
+}
+
+impl SigningVector {
+    /// recompute every derived value from the shares and nonces and compare.
+    /// `Error::VectorMismatch` names the first field that differs.
+    pub fn verify(&self) -> Result<(), Error> {
+        let mismatch = |field: &str| Err(Error::VectorMismatch(field.to_string()));
+
+        if self.commitments.len() != self.threshold
+            || self.commitments.first() != Some(&self.public_key)
+        {
+            return mismatch("commitments");
+        }
+        for p in &self.participants {
+            if ProjectivePoint::GENERATOR * p.x_i != p.X_i
+                || !verify_share(p.id, p.x_i, &self.commitments)
+            {
+                return mismatch("participants");
+            }
+        }
+
+        let signers = self
+            .nonces
+            .iter()
+            .map(|nonce| {
+                self.participants
+                    .iter()
+                    .find(|p| p.id == nonce.id)
+                    .copied()
+                    .ok_or(Error::VectorMismatch("nonces".to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if self
+            .nonces
+            .iter()
+            .any(|nonce| compute_nonce_point(&nonce.r_i) != nonce.R_i)
+        {
+            return mismatch("nonces");
+        }
+
+        let config = SigningConfig::new(&self.app_id, &self.purpose);
+        let round = sign(
+            &signers,
+            &self.nonces,
+            &self.public_key,
+            &config,
+            &self.message,
+        )?;
+        if round.R != self.R {
+            return mismatch("R");
+        }
+        if round.challenge != self.challenge {
+            return mismatch("challenge");
+        }
+        // the vector records (id, sᵢ), Rᵢ and Xᵢ are checked above
+        let bare = |partials: &[PartialSignature]| {
+            partials.iter().map(|p| (p.id, p.s_i)).collect::<Vec<_>>()
+        };
+        if bare(&round.partial_signatures) != bare(&self.partial_signatures) {
+            return mismatch("partial_signatures");
+        }
+        if round.signature != self.signature
+            || !round
+                .signature
+                .verify_with_config(&self.message, &self.public_key, &config)
+        {
+            return mismatch("signature");
+        }
+
+        Ok(())
+    }
+}
+
+struct Round {
+    R: ProjectivePoint,
+    challenge: Scalar,
+    partial_signatures: Vec<PartialSignature>,
+    signature: SchnorrSignature,
+}
+
+fn sign(
+    signers: &[Participant],
+    nonces: &[NonceVector],
+    public_key: &ProjectivePoint,
+    config: &SigningConfig,
+    message: &[u8],
+) -> Result<Round, Error> {
+    let ids = nonces.iter().map(|nonce| nonce.id).collect::<Vec<_>>();
+    let signer_set = SignerSet::new(&ids, ids.len())?;
+    let R = aggregate_nonce_with_signers(
+        &nonces
+            .iter()
+            .map(|nonce| (nonce.id, nonce.R_i))
+            .collect::<Vec<_>>(),
+        &signer_set,
+    )?;
+    let challenge = config.challenge(&R, public_key, message);
+    let partial_signatures = signers
+        .iter()
+        .zip(nonces)
+        .map(|(p, nonce)| partial_sign(p, &nonce.r_i, &challenge))
+        .collect::<Vec<_>>();
+    let signature = finalize_signature_with_signers(&partial_signatures, R, &signer_set)?;
+
+    Ok(Round {
+        R,
+        challenge,
+        partial_signatures,
+        signature,
+    })
+}
@@ -0,0 +1,201 @@
+#![allow(non_snake_case)]
+
+use crate::error::Error;
+use crate::hash::{finalize_scalar, scalar_hasher, tagged_hasher};
+use crate::hazmat;
+use crate::threshold::Participant;
+use alloc::vec::Vec;
+use k256::{
+    ProjectivePoint, Scalar, Secp256k1,
+    elliptic_curve::{
+        hash2curve::{ExpandMsgXmd, GroupDigest},
+        sec1::ToEncodedPoint,
+    },
+};
+use sha2::{Digest, Sha256};
+
+const HASH_TO_CURVE_DST: &[u8] = b"shamy/vrf/hash-to-curve/v1";
+const DLEQ_NONCE_TAG: &[u8] = b"shamy/vrf/dleq-nonce/v1";
+const DLEQ_CHALLENGE_TAG: &[u8] = b"shamy/vrf/dleq-challenge/v1";
+const OUTPUT_TAG: &[u8] = b"shamy/vrf/output/v1";
+
+//--------------------------------------------------------------------
+// Threshold VRF (DDH based)
+//--------------------------------------------------------------------
+//
+// built on the same shares x_i = f(i) as threshold Schnorr.
+//
+//   H     = hash_to_curve(input)
+//   Γ_i   = x_i*H                          (partial evaluation)
+//   π_i   = DLEQ proof that log_G X_i == log_H Γ_i
+//
+//   k     = H(x_i || H)                    (deterministic, like RFC 9381)
+//   c     = H(X_i || Γ_i || k*G || k*H)
+//   z     = k + c*x_i
+//
+//   verifier: z*G - c*X_i == k*G,  z*H - c*Γ_i == k*H
+//
+// combining t valid partials
+//   Γ     = Σ λ_i*Γ_i = x*H
+//   β     = H(Γ)                           (the random output)
+//
+// Γ only depends on x and the input, so every quorum ends up with the same
+// β. anyone holding the public shares can check the proof, which makes it a
+// fit for randomness beacons.
+//
+// ⚠️ fewer than t participants can't compute β, but t colluding ones can
+// predict it for any input.
+//
+
+/// H(input) on secp256k1, hash_to_curve with SSWU.
+pub fn hash_to_point(input: &[u8]) -> ProjectivePoint {
+    Secp256k1::hash_from_bytes::<ExpandMsgXmd<Sha256>>(&[input], &[HASH_TO_CURVE_DST])
+        .expect("the DST is shorter than 256 bytes")
+}
+
+/// Γ_i = x_i*H(input) with a DLEQ proof against the public share X_i.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartialVrfProof {
+    pub id: u64,
+    pub Gamma_i: ProjectivePoint,
+    pub c: Scalar,
+    pub z: Scalar,
+}
+
+impl PartialVrfProof {
+    /// check the DLEQ proof of `Gamma_i` against the public share `X_i`.
+    pub fn verify(&self, X_i: &ProjectivePoint, input: &[u8]) -> bool {
+        let H = hash_to_point(input);
+        let U = ProjectivePoint::GENERATOR * self.z - *X_i * self.c;
+        let V = H * self.z - self.Gamma_i * self.c;
+
+        dleq_challenge(X_i, &self.Gamma_i, &U, &V) == self.c
+    }
+}
+
+/// evaluate the VRF on `input` with the participant's share.
+pub fn partial_evaluate(participant: &Participant, input: &[u8]) -> PartialVrfProof {
+    let H = hash_to_point(input);
+    let Gamma_i = H * participant.x_i;
+
+    let mut hasher = scalar_hasher(DLEQ_NONCE_TAG);
+    hasher.update(participant.x_i.to_bytes());
+    hasher.update(H.to_encoded_point(true).as_bytes());
+    let k = finalize_scalar(hasher);
+
+    let c = dleq_challenge(
+        &participant.X_i,
+        &Gamma_i,
+        &(ProjectivePoint::GENERATOR * k),
+        &(H * k),
+    );
+
+    PartialVrfProof {
+        id: participant.id,
+        Gamma_i,
+        c,
+        z: k + c * participant.x_i,
+    }
+}
+
+/// combined evaluation Γ = x*H(input) with the partial proofs it was built from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VrfProof {
+    pub Gamma: ProjectivePoint,
+    pub partials: Vec<PartialVrfProof>,
+}
+
+/// verify every partial against its public share and combine them,
+/// Γ = Σ λ_i*Γ_i. `public_shares` are `(id, X_i)` pairs.
+pub fn combine(
+    input: &[u8],
+    partials: &[PartialVrfProof],
+    public_shares: &[(u64, ProjectivePoint)],
+) -> Result<VrfProof, Error> {
+    if partials.is_empty() {
+        return Err(Error::NoShares);
+    }
+    let ids = partials.iter().map(|p| p.id).collect::<Vec<_>>();
+    for (i, partial) in partials.iter().enumerate() {
+        if ids[..i].contains(&partial.id) {
+            return Err(Error::DuplicateParticipantId(partial.id));
+        }
+        let valid =
+            public_share(public_shares, partial.id).is_some_and(|X_i| partial.verify(&X_i, input));
+        if !valid {
+            return Err(Error::InvalidVrfProof { id: partial.id });
+        }
+    }
+
+    Ok(VrfProof {
+        Gamma: interpolate(partials.iter().map(|p| (p.id, p.Gamma_i)), &ids),
+        partials: partials.to_vec(),
+    })
+}
+
+impl VrfProof {
+    /// β = H(Γ), the pseudorandom output.
+    pub fn output(&self) -> [u8; 32] {
+        let mut hasher = tagged_hasher(OUTPUT_TAG);
+        hasher.update(self.Gamma.to_encoded_point(true).as_bytes());
+        hasher.finalize().into()
+    }
+
+    /// check that Γ was evaluated under the group key `X`: every partial
+    /// proof holds, the quorum's public shares interpolate to X and the
+    /// partials interpolate to Γ.
+    pub fn verify(
+        &self,
+        input: &[u8],
+        X: &ProjectivePoint,
+        public_shares: &[(u64, ProjectivePoint)],
+    ) -> bool {
+        let ids = self.partials.iter().map(|p| p.id).collect::<Vec<_>>();
+        let mut quorum_shares = Vec::with_capacity(ids.len());
+        for (i, partial) in self.partials.iter().enumerate() {
+            if partial.id == 0 || ids[..i].contains(&partial.id) {
+                return false;
+            }
+            match public_share(public_shares, partial.id) {
+                Some(X_i) if partial.verify(&X_i, input) => quorum_shares.push((partial.id, X_i)),
+                _ => return false,
+            }
+        }
+
+        !ids.is_empty()
+            && interpolate(quorum_shares, &ids) == *X
+            && interpolate(self.partials.iter().map(|p| (p.id, p.Gamma_i)), &ids) == self.Gamma
+    }
+}
+
+fn public_share(public_shares: &[(u64, ProjectivePoint)], id: u64) -> Option<ProjectivePoint> {
+    public_shares
+        .iter()
+        .find(|(share_id, _)| *share_id == id)
+        .map(|(_, X_i)| *X_i)
+}
+
+fn interpolate(
+    points: impl IntoIterator<Item = (u64, ProjectivePoint)>,
+    ids: &[u64],
+) -> ProjectivePoint {
+    points
+        .into_iter()
+        .fold(ProjectivePoint::IDENTITY, |acc, (id, P)| {
+            acc + P * hazmat::lagrange_coefficient(id, ids)
+        })
+}
+
+fn dleq_challenge(
+    X_i: &ProjectivePoint,
+    Gamma_i: &ProjectivePoint,
+    U: &ProjectivePoint,
+    V: &ProjectivePoint,
+) -> Scalar {
+    let mut hasher = scalar_hasher(DLEQ_CHALLENGE_TAG);
+    for P in [X_i, Gamma_i, U, V] {
+        hasher.update(P.to_encoded_point(true).as_bytes());
+    }
+
+    finalize_scalar(hasher)
+}
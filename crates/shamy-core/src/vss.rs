@@ -0,0 +1,170 @@
+#![allow(non_snake_case)]
+
+use crate::error::Error;
+use crate::proofs::SchnorrProof;
+use crate::threshold::Participant;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use k256::elliptic_curve::rand_core::OsRng;
+use k256::{ProjectivePoint, Scalar, elliptic_curve::rand_core::CryptoRngCore};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// calculates the commitment for a given coefficient
+pub fn calculate_commitment(c: Scalar) -> ProjectivePoint {
+    ProjectivePoint::GENERATOR * c
+}
+
+/// checks a dealer's commitments C₀..Cₜ₋₁ before any share is checked
+/// against them: exactly `t` of them and none at infinity. C₀ = O would
+/// make the group key O, any other Cⱼ = O drops the degree of f.
+/// points are on the curve by construction, decoding already rejects
+/// everything else.
+pub fn validate_commitments(commitments: &[ProjectivePoint], t: usize) -> Result<(), Error> {
+    if commitments.len() != t {
+        return Err(Error::CommitmentCount {
+            expected: t,
+            actual: commitments.len(),
+        });
+    }
+    match commitments
+        .iter()
+        .position(|C_j| *C_j == ProjectivePoint::IDENTITY)
+    {
+        Some(index) => Err(Error::IdentityCommitment { index }),
+        None => Ok(()),
+    }
+}
+
+/// `validate_commitments` for the commitments `sender` dealt in a
+/// protocol round, with the errors blaming `sender`.
+pub(crate) fn validate_dealing(
+    sender: u64,
+    commitments: &[ProjectivePoint],
+    t: usize,
+) -> Result<(), Error> {
+    validate_commitments(commitments, t).map_err(|e| match e {
+        Error::CommitmentCount { expected, actual } => Error::InvalidCommitmentCount {
+            id: sender,
+            expected,
+            actual,
+        },
+        _ => Error::InvalidShare { id: sender },
+    })
+}
+
+/// verifies a participant's share against a set of commitments using Feldman's VSS scheme.
+/// malformed commitments (none, or one at infinity) verify no share.
+pub fn verify_share(id: u64, x_i: Scalar, commitments: &[ProjectivePoint]) -> bool {
+    if commitments.is_empty() || validate_commitments(commitments, commitments.len()).is_err() {
+        return false;
+    }
+
+    /*
+     * verification:
+     *
+     * polynomial f(i) is defined as:
+     *         f(i) = a₀ + a₁i + ... + aₜiᵗ
+     *     G * f(i) = C₀ + C₁i + ... + Cₜiᵗ
+     *
+     * where:
+     *     xᵢ = f(i)      [share]
+     *     Cⱼ = aⱼG       [commitment]
+     *
+     * multiplying both sides by generator G:
+     *     xᵢG = f(i)G    [verification equation]
+     */
+
+    let lhs = ProjectivePoint::GENERATOR * x_i;
+    let rhs = public_share(id, commitments);
+
+    lhs == rhs
+}
+
+/// verify many shares `(id, xᵢ)` against the same commitments, e.g. a
+/// dealer's whole output. fails with the smallest invalid id, or with the
+/// error of `validate_commitments` if a commitment is at infinity.
+/// with the `parallel` feature the shares are checked on all cores.
+pub fn verify_shares(
+    shares: &[(u64, Scalar)],
+    commitments: &[ProjectivePoint],
+) -> Result<(), Error> {
+    validate_commitments(commitments, commitments.len())?;
+
+    #[cfg(feature = "parallel")]
+    let shares = shares.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let shares = shares.iter();
+
+    match shares
+        .filter(|(id, x_i)| !verify_share(*id, *x_i, commitments))
+        .map(|(id, _)| *id)
+        .min()
+    {
+        Some(id) => Err(Error::InvalidShare { id }),
+        None => Ok(()),
+    }
+}
+
+/// derives the public share Xᵢ = f(i)G = Σ Cⱼiʲ of participant `id` from the commitments.
+pub fn public_share(id: u64, commitments: &[ProjectivePoint]) -> ProjectivePoint {
+    public_share_at(&Scalar::from(id), commitments)
+}
+
+/// f(x)G = Σ Cⱼxʲ at any abscissa, the public side of `hazmat::eval_polynomial_at`.
+pub fn public_share_at(x: &Scalar, commitments: &[ProjectivePoint]) -> ProjectivePoint {
+    let mut x_pow = Scalar::ONE;
+
+    let mut X_i = ProjectivePoint::IDENTITY;
+    for &C_j in commitments.iter() {
+        X_i += C_j * x_pow;
+        x_pow *= x;
+    }
+
+    X_i
+}
+
+/// proof that a participant still holds a share consistent with the commitments,
+/// without revealing it. used for periodic custody/liveness attestation: the
+/// coordinator sends a fresh `challenge`, the participant answers with this proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShareAttestation {
+    pub id: u64,
+    pub proof: SchnorrProof,
+}
+
+impl ShareAttestation {
+    /// prove knowledge of xᵢ for the coordinator's `challenge`.
+    #[cfg(feature = "std")]
+    pub fn prove(participant: &Participant, challenge: &[u8]) -> Self {
+        Self::prove_with_rng(participant, challenge, &mut OsRng)
+    }
+
+    /// like `prove`, drawing the proof nonce from `rng`.
+    pub fn prove_with_rng(
+        participant: &Participant,
+        challenge: &[u8],
+        rng: &mut impl CryptoRngCore,
+    ) -> Self {
+        let context = attestation_context(participant.id, challenge);
+        Self {
+            id: participant.id,
+            proof: SchnorrProof::prove_with_rng(&participant.x_i, &participant.X_i, &context, rng),
+        }
+    }
+
+    /// verify against Xᵢ derived from the commitments, so a participant
+    /// can't attest with a key that is not their share.
+    pub fn verify(&self, commitments: &[ProjectivePoint], challenge: &[u8]) -> bool {
+        let X_i = public_share(self.id, commitments);
+        let context = attestation_context(self.id, challenge);
+        self.proof.verify(&X_i, &context)
+    }
+}
+
+fn attestation_context(id: u64, challenge: &[u8]) -> Vec<u8> {
+    let mut context = b"shamy/vss/share-attestation".to_vec();
+    context.extend_from_slice(&id.to_be_bytes());
+    context.extend_from_slice(challenge);
+    context
+}
@@ -0,0 +1,217 @@
+//! wasm-bindgen bindings for browser signers.
+//!
+//! points and scalars cross the boundary as hex strings, the same encoding
+//! the CLI prints. participant ids are `u32` so they stay plain JS numbers.
+//!
+//! randomness comes from `OsRng`, which under wasm32 is backed by
+//! `crypto.getRandomValues` (getrandom's `js` feature).
+//!
+//! ⚠️ nonces and shares live in JS memory, which cannot be zeroized.
+
+#![allow(non_snake_case)]
+
+use crate::limits::Limits;
+use crate::schnorr::{self, SchnorrSignature, SigningConfig};
+use crate::shamir::shamir_keygen_with_rng;
+use crate::threshold::{self, PartialSignature, Participant, SignerSet};
+use crate::util::{hex_to_pp, hex_to_scalar, pp_to_hex, scalar_to_hex};
+use k256::elliptic_curve::rand_core::OsRng;
+use wasm_bindgen::prelude::*;
+
+fn js_error(e: crate::Error) -> JsError {
+    JsError::new(&e.to_string())
+}
+
+fn points(hex: &[String]) -> Result<Vec<k256::ProjectivePoint>, JsError> {
+    hex.iter().map(|p| hex_to_pp(p).map_err(js_error)).collect()
+}
+
+/// message framing, see `SigningConfig`.
+#[wasm_bindgen]
+pub struct Framing {
+    config: SigningConfig,
+}
+
+#[wasm_bindgen]
+impl Framing {
+    #[wasm_bindgen(constructor)]
+    pub fn new(app_id: &str, purpose: &str) -> Self {
+        Self {
+            config: SigningConfig::new(app_id, purpose),
+        }
+    }
+
+    pub fn unframed() -> Self {
+        Self {
+            config: SigningConfig::unframed(),
+        }
+    }
+}
+
+/// output of a trusted dealer keygen, share `i` belongs to participant `i + 1`.
+#[wasm_bindgen]
+pub struct Keygen {
+    public_key: String,
+    shares: Vec<String>,
+    public_shares: Vec<String>,
+}
+
+#[wasm_bindgen]
+impl Keygen {
+    #[wasm_bindgen(getter)]
+    pub fn public_key(&self) -> String {
+        self.public_key.clone()
+    }
+
+    /// secret shares x_i.
+    #[wasm_bindgen(getter)]
+    pub fn shares(&self) -> Vec<String> {
+        self.shares.clone()
+    }
+
+    /// public shares X_i.
+    #[wasm_bindgen(getter)]
+    pub fn public_shares(&self) -> Vec<String> {
+        self.public_shares.clone()
+    }
+}
+
+/// t-of-n keygen with the default `Limits`.
+#[wasm_bindgen]
+pub fn keygen(n: usize, t: usize) -> Result<Keygen, JsError> {
+    let output = shamir_keygen_with_rng(n, t, &Limits::default(), &mut OsRng).map_err(js_error)?;
+
+    Ok(Keygen {
+        public_key: pp_to_hex(&output.public_key),
+        shares: output
+            .participants
+            .iter()
+            .map(|p| scalar_to_hex(&p.x_i))
+            .collect(),
+        public_shares: output
+            .participants
+            .iter()
+            .map(|p| pp_to_hex(&p.X_i))
+            .collect(),
+    })
+}
+
+/// nonce r_i and its commitment R_i = r_i*G.
+/// ⚠️ use the secret for exactly one partial signature.
+#[wasm_bindgen]
+pub struct Nonce {
+    secret: String,
+    commitment: String,
+}
+
+#[wasm_bindgen]
+impl Nonce {
+    #[wasm_bindgen(getter)]
+    pub fn secret(&self) -> String {
+        self.secret.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn commitment(&self) -> String {
+        self.commitment.clone()
+    }
+}
+
+#[wasm_bindgen]
+pub fn generate_nonce() -> Nonce {
+    let r = schnorr::generate_nonce_with_rng(&mut OsRng);
+
+    Nonce {
+        secret: scalar_to_hex(&r),
+        commitment: pp_to_hex(&schnorr::compute_nonce_point(&r)),
+    }
+}
+
+/// R = Σ λ_i*R_i over the nonce commitments of the signers `ids`.
+#[wasm_bindgen]
+pub fn aggregate_nonce(ids: Vec<u32>, nonces: Vec<String>) -> Result<String, JsError> {
+    if ids.len() != nonces.len() {
+        return Err(JsError::new("ids and nonces differ in length"));
+    }
+    let ids = ids.into_iter().map(u64::from).collect::<Vec<_>>();
+    let nonces = ids
+        .iter()
+        .copied()
+        .zip(points(&nonces)?)
+        .collect::<Vec<_>>();
+
+    Ok(pp_to_hex(
+        &threshold::aggregate_nonce_with_signers(
+            &nonces,
+            &SignerSet::new(&ids, ids.len()).map_err(js_error)?,
+        )
+        .map_err(js_error)?,
+    ))
+}
+
+/// c = H(R, X, frame(m)).
+#[wasm_bindgen]
+pub fn challenge(
+    nonce: &str,
+    public_key: &str,
+    message: &[u8],
+    framing: &Framing,
+) -> Result<String, JsError> {
+    let R = hex_to_pp(nonce).map_err(js_error)?;
+    let X = hex_to_pp(public_key).map_err(js_error)?;
+
+    Ok(scalar_to_hex(&framing.config.challenge(&R, &X, message)))
+}
+
+/// s_i = r_i + c*x_i.
+#[wasm_bindgen]
+pub fn partial_sign(id: u32, share: &str, nonce: &str, challenge: &str) -> Result<String, JsError> {
+    let participant = Participant::from_secret(id.into(), hex_to_scalar(share).map_err(js_error)?);
+    let r_i = hex_to_scalar(nonce).map_err(js_error)?;
+    let c = hex_to_scalar(challenge).map_err(js_error)?;
+
+    Ok(scalar_to_hex(
+        &threshold::partial_sign(&participant, &r_i, &c).s_i,
+    ))
+}
+
+/// s = Σ λ_i*s_i, returns the `s` half of the signature (R, s).
+#[wasm_bindgen]
+pub fn combine(ids: Vec<u32>, partials: Vec<String>, nonce: &str) -> Result<String, JsError> {
+    if ids.len() != partials.len() {
+        return Err(JsError::new("ids and partial signatures differ in length"));
+    }
+    let ids = ids.into_iter().map(u64::from).collect::<Vec<_>>();
+    let signers = SignerSet::new(&ids, ids.len()).map_err(js_error)?;
+    let partials = ids
+        .iter()
+        .zip(&partials)
+        .map(|(id, s_i)| {
+            Ok(PartialSignature::new(
+                *id,
+                hex_to_scalar(s_i).map_err(js_error)?,
+            ))
+        })
+        .collect::<Result<Vec<_>, JsError>>()?;
+    let R = hex_to_pp(nonce).map_err(js_error)?;
+
+    Ok(scalar_to_hex(
+        &threshold::finalize_signature_with_signers(&partials, R, &signers)
+            .map_err(js_error)?
+            .s,
+    ))
+}
+
+#[wasm_bindgen]
+pub fn verify(
+    message: &[u8],
+    nonce: &str,
+    signature: &str,
+    public_key: &str,
+    framing: &Framing,
+) -> Result<bool, JsError> {
+    let signature = SchnorrSignature::from_hex_parts(nonce, signature).map_err(js_error)?;
+    let X = hex_to_pp(public_key).map_err(js_error)?;
+
+    Ok(signature.verify_with_config(message, &X, &framing.config))
+}
@@ -0,0 +1,67 @@
+#![allow(non_snake_case)]
+
+use k256::Scalar;
+use shamy_core::Error;
+use shamy_core::backend::*;
+use shamy_core::schnorr::*;
+use shamy_core::shamir::*;
+use shamy_core::threshold::*;
+
+#[test]
+fn test_memory_backend_signs() {
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let X = keygen_output.public_key;
+    let backends = [0, 2].map(|i| MemoryBackend::new(keygen_output.participants[i]));
+    let session = SessionId::random();
+    let msg = b"signed by backends";
+
+    let nonces = backends
+        .iter()
+        .map(|b| (b.id(), b.commit(&session).unwrap()))
+        .collect::<Vec<_>>();
+    let ids = nonces.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+    let signer_set = SignerSet::new(&ids, ids.len()).unwrap();
+    let R = aggregate_nonce_with_signers(&nonces, &signer_set).unwrap();
+    let c = compute_challenge(&R, &X, msg);
+
+    let partials = backends
+        .iter()
+        .map(|b| b.partial_sign(&c, &session).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        backends[1].public_share(),
+        keygen_output.participants[2].X_i
+    );
+    assert!(
+        finalize_signature_with_signers(&partials, R, &signer_set)
+            .unwrap()
+            .verify_with_config(msg, &X, &SigningConfig::unframed().legacy_challenge())
+    );
+}
+
+#[test]
+fn test_memory_backend_uses_each_nonce_once() {
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let backend = MemoryBackend::new(keygen_output.participants[0]);
+    let (first, second) = (SessionId::random(), SessionId::random());
+    let c = Scalar::from(7u64);
+
+    backend.commit(&first).unwrap();
+    assert_eq!(backend.commit(&first), Err(Error::DuplicateSession(first)));
+    assert_eq!(
+        backend.partial_sign(&c, &second),
+        Err(Error::UnknownSession(second))
+    );
+
+    backend.partial_sign(&c, &first).unwrap();
+    assert_eq!(
+        backend.partial_sign(&c, &first),
+        Err(Error::UnknownSession(first))
+    );
+
+    backend.commit(&second).unwrap();
+    assert_eq!(backend.open_sessions(), 1);
+    backend.discard(&second).unwrap();
+    assert_eq!(backend.open_sessions(), 0);
+    assert!(backend.partial_sign(&c, &second).is_err());
+}
@@ -0,0 +1,96 @@
+use k256::{ProjectivePoint, Scalar};
+use shamy_core::backup::*;
+use shamy_core::error::Error;
+use shamy_core::shamir::shamir_keygen;
+use shamy_core::threshold::Participant;
+
+#[test]
+fn test_backup_layout() {
+    // computed independently from the documented bit layout
+    let participant = Participant::from_secret(3, Scalar::from(0x42u64));
+    let backup = ShareBackup::new(&participant, &ProjectivePoint::GENERATOR, 2).unwrap();
+    let words = backup.to_words();
+    assert_eq!(
+        words,
+        "abstract reward huge length amount about abandon abandon abandon abandon abandon \
+         abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+         abandon abandon abandon abandon abandon abandon abandon absurd exile clown attitude"
+    );
+    assert_eq!(ShareBackup::from_words(&words).unwrap(), backup);
+    assert_eq!(backup.participant().X_i, participant.X_i);
+}
+
+#[test]
+fn test_backup_recovery() {
+    let keygen = shamir_keygen(5, 3).unwrap();
+    let backups = keygen
+        .participants
+        .iter()
+        .map(|p| ShareBackup::new(p, &keygen.public_key, 3).unwrap())
+        .map(|b| ShareBackup::from_words(&b.to_words()).unwrap())
+        .collect::<Vec<_>>();
+
+    let secret = recover_secret(&backups[1..4]).unwrap();
+    assert_eq!(ProjectivePoint::GENERATOR * secret, keygen.public_key);
+
+    assert_eq!(
+        recover_secret(&backups[..2]),
+        Err(Error::NotEnoughShares {
+            threshold: 3,
+            actual: 2
+        })
+    );
+
+    // a share of another key
+    let other = shamir_keygen(5, 3).unwrap();
+    let mut mixed = backups[..2].to_vec();
+    mixed.push(ShareBackup::new(&other.participants[2], &other.public_key, 3).unwrap());
+    assert_eq!(recover_secret(&mixed), Err(Error::BackupMismatch));
+
+    // a threshold that was recorded too low recovers a wrong key
+    let low = keygen.participants[..2]
+        .iter()
+        .map(|p| ShareBackup::new(p, &keygen.public_key, 2).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(recover_secret(&low), Err(Error::BackupMismatch));
+}
+
+#[test]
+fn test_backup_rejects_bad_input() {
+    let keygen = shamir_keygen(3, 2).unwrap();
+    let words = ShareBackup::new(&keygen.participants[0], &keygen.public_key, 2)
+        .unwrap()
+        .to_words();
+
+    // any single swapped word fails the checksum
+    let phrase = words.split(' ').collect::<Vec<_>>();
+    for i in 0..phrase.len() {
+        let mut typo = phrase.clone();
+        typo[i] = if typo[i] == "zoo" { "abandon" } else { "zoo" };
+        assert!(matches!(
+            ShareBackup::from_words(&typo.join(" ")),
+            Err(Error::InvalidEncoding(_))
+        ));
+    }
+    assert!(ShareBackup::from_words(&phrase[..24].join(" ")).is_err());
+
+    let participant = Participant::from_secret(70_000, Scalar::ONE);
+    assert!(matches!(
+        ShareBackup::new(&participant, &keygen.public_key, 2),
+        Err(Error::InvalidParticipantId { id: 70_000, .. })
+    ));
+    assert!(matches!(
+        ShareBackup::new(&keygen.participants[0], &keygen.public_key, 1),
+        Err(Error::ThresholdTooSmall {
+            threshold: 1,
+            min: 2
+        })
+    ));
+    assert!(matches!(
+        ShareBackup::new(&keygen.participants[0], &keygen.public_key, 70_000),
+        Err(Error::ThresholdTooLarge {
+            threshold: 70_000,
+            max: 65_535
+        })
+    ));
+}
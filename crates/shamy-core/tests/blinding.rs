@@ -0,0 +1,94 @@
+#![allow(non_snake_case)]
+
+use k256::ProjectivePoint;
+use shamy_core::Error;
+use shamy_core::blinding::*;
+use shamy_core::schnorr::*;
+use shamy_core::shamir::*;
+use shamy_core::threshold::*;
+
+#[test]
+fn test_blind_share_roundtrip() {
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let share = keygen_output.participants[0];
+    let blinding = Blinding::random(share.id);
+
+    let blinded = blind_share(&share, &blinding.r);
+    assert_ne!(blinded.x_i, share.x_i);
+    assert_eq!(ProjectivePoint::GENERATOR * blinded.x_i, blinded.X_i);
+    // followed from public values only
+    assert_eq!(
+        blinded.X_i,
+        blinded_public_share(&share.X_i, &blinding.offset())
+    );
+    assert_eq!(unblind_share(&blinded, &blinding.r), share);
+}
+
+#[test]
+fn test_aggregator_combines_blinded_partials() {
+    let keygen_output = shamir_keygen(5, 3).unwrap();
+    let X = keygen_output.public_key;
+    let signers = [1, 3, 4].map(|i| keygen_output.participants[i]);
+    let msg = b"combined blind";
+
+    let nonces = signers.map(|p| (p.id, generate_nonce()));
+    let ids = signers.map(|p| p.id);
+    let signer_set = SignerSet::new(&ids, ids.len()).unwrap();
+    let R = aggregate_nonce_with_signers(
+        &nonces.map(|(id, r_i)| (id, compute_nonce_point(&r_i))),
+        &signer_set,
+    )
+    .unwrap();
+    let c = compute_challenge(&R, &X, msg);
+
+    let blindings = ids.map(Blinding::random);
+    let partials = signers
+        .iter()
+        .zip(&nonces)
+        .zip(&blindings)
+        .map(|((p, (_, r_i)), b)| blind_partial(&partial_sign(p, r_i, &c), &b.r))
+        .collect::<Vec<_>>();
+
+    // the aggregator's signature is not valid, but checks against the offsets
+    let blinded = finalize_signature_with_signers(&partials, R, &signer_set).unwrap();
+    assert!(!blinded.verify_with_config(msg, &X, &SigningConfig::unframed().legacy_challenge()));
+    let P = aggregate_offset_point(&blindings.map(|b| (b.id, b.offset()))).unwrap();
+    assert_eq!(ProjectivePoint::GENERATOR * blinded.s, R + X * c + P);
+    // each blinded partial still carries a consistent Rᵢ + rᵢ*G
+    assert!(partials.iter().all(|p| p.verify(&c).is_ok()));
+
+    let rho = aggregate_offset(&blindings).unwrap();
+    assert_eq!(ProjectivePoint::GENERATOR * rho, P);
+    assert!(unblind_signature(&blinded, &rho).verify_with_config(
+        msg,
+        &X,
+        &SigningConfig::unframed().legacy_challenge()
+    ));
+
+    let unblinded = partials
+        .iter()
+        .zip(&blindings)
+        .map(|(p, b)| unblind_partial(p, &b.r))
+        .collect::<Vec<_>>();
+    assert!(
+        finalize_signature_with_signers(&unblinded, R, &signer_set)
+            .unwrap()
+            .verify_with_config(msg, &X, &SigningConfig::unframed().legacy_challenge())
+    );
+}
+
+#[test]
+fn test_aggregate_offset_rejects_duplicate_ids() {
+    let blinding = Blinding::random(1);
+    assert_eq!(
+        aggregate_offset(&[blinding, blinding]),
+        Err(Error::DuplicateParticipantId(1))
+    );
+    assert_eq!(
+        aggregate_offset_point(&[(0, blinding.offset())]),
+        Err(Error::InvalidParticipantId {
+            id: 0,
+            num_shares: 1
+        })
+    );
+}
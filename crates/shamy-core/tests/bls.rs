@@ -0,0 +1,95 @@
+#![cfg(feature = "bls")]
+
+use shamy_core::Error;
+use shamy_core::bls::*;
+use shamy_core::schnorr::SigningConfig;
+
+#[test]
+fn test_bls_threshold_signing() {
+    let keys = keygen(5, 3).unwrap();
+    let config = SigningConfig::new("chain", "block");
+    let message = b"rust is best";
+
+    let partials = keys
+        .shares
+        .iter()
+        .map(|share| partial_sign(share, message, &config))
+        .collect::<Vec<_>>();
+    for (partial, share) in partials.iter().zip(&keys.shares) {
+        assert!(verify_partial(partial, &share.X_i, message, &config));
+    }
+    assert!(!verify_partial(
+        &partials[0],
+        &keys.shares[1].X_i,
+        message,
+        &config
+    ));
+
+    // any quorum yields the same signature
+    let signature = combine(&partials[..3]).unwrap();
+    assert_eq!(combine(&partials[2..]).unwrap(), signature);
+    assert!(signature.verify(message, &keys.public_key, &config));
+    assert!(!signature.verify(b"rust is worst", &keys.public_key, &config));
+    assert!(!signature.verify(message, &keys.public_key, &SigningConfig::unframed()));
+
+    // below threshold the combination is not a valid signature
+    assert!(
+        !combine(&partials[..2])
+            .unwrap()
+            .verify(message, &keys.public_key, &config)
+    );
+
+    let bytes = signature.to_bytes();
+    assert_eq!(BlsSignature::from_bytes(&bytes).unwrap(), signature);
+    assert!(BlsSignature::from_bytes(&[0xffu8; 48]).is_err());
+}
+
+#[test]
+fn test_bls_combine_rejects_bad_ids() {
+    let keys = keygen(3, 2).unwrap();
+    let config = SigningConfig::unframed();
+    let partial = partial_sign(&keys.shares[0], b"rust is best", &config);
+
+    assert_eq!(combine(&[]), Err(Error::NoShares));
+    assert_eq!(
+        combine(&[partial, partial]),
+        Err(Error::DuplicateParticipantId(1))
+    );
+    assert!(combine(&[BlsPartialSignature { id: 0, ..partial }]).is_err());
+}
+
+#[test]
+fn test_bls_aggregate_across_groups() {
+    let config = SigningConfig::new("chain", "block");
+    let groups = [keygen(3, 2).unwrap(), keygen(4, 3).unwrap()];
+    let messages: [&[u8]; 2] = [b"block 1", b"block 2"];
+
+    let signatures = groups
+        .iter()
+        .zip(messages)
+        .map(|(keys, message)| {
+            let partials = keys
+                .shares
+                .iter()
+                .map(|share| partial_sign(share, message, &config))
+                .collect::<Vec<_>>();
+            combine(&partials).unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    let aggregate = BlsSignature::aggregate(&signatures);
+    let signed = [
+        (messages[0], groups[0].public_key),
+        (messages[1], groups[1].public_key),
+    ];
+    assert!(aggregate.verify_aggregate(&signed, &config));
+
+    let swapped = [
+        (messages[0], groups[1].public_key),
+        (messages[1], groups[0].public_key),
+    ];
+    assert!(!aggregate.verify_aggregate(&swapped, &config));
+
+    let repeated = [signed[0], signed[0]];
+    assert!(!aggregate.verify_aggregate(&repeated, &config));
+}
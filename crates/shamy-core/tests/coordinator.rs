@@ -0,0 +1,170 @@
+#![allow(non_snake_case)]
+
+use k256::ProjectivePoint;
+use shamy_core::Error;
+use shamy_core::coordinator::*;
+use shamy_core::frost::*;
+use shamy_core::schnorr::*;
+use shamy_core::shamir::*;
+use shamy_core::threshold::*;
+
+fn config() -> SigningConfig {
+    SigningConfig::new("shamy-tests", "coordinator")
+}
+
+#[test]
+fn test_coordinator_signs_batch() {
+    let keygen_output = shamir_keygen(5, 3).unwrap();
+    let X = keygen_output.public_key;
+    let participants = &keygen_output.participants[..3];
+    let ids = participants.iter().map(|p| p.id).collect::<Vec<_>>();
+    let messages: [&[u8]; 3] = [b"first", b"second", b"third"];
+
+    let mut coordinator = Coordinator::new(X, config()).unwrap();
+    let sessions = coordinator.start_batch(&messages, &ids).unwrap();
+    assert_eq!(coordinator.sessions().len(), 3);
+
+    // one nonce pair per session and signer
+    let mut pools = participants
+        .iter()
+        .map(|p| NoncePool::new(p.id))
+        .collect::<Vec<_>>();
+    for session in &sessions {
+        for pool in pools.iter_mut() {
+            let commitment = pool.generate(1)[0];
+            coordinator.add_commitment(session, commitment).unwrap();
+        }
+    }
+    let challenges = coordinator.challenges(&sessions).unwrap();
+    for ((session, message), (R, _)) in sessions.iter().zip(messages).zip(&challenges) {
+        let commitments = coordinator.commitments(session).unwrap();
+        let session_config = config().with_session(*session);
+        for (participant, pool) in participants.iter().zip(pools.iter_mut()) {
+            let partial = pool
+                .sign(participant, &commitments, &X, message, &session_config)
+                .unwrap();
+            coordinator.add_partial(session, partial).unwrap();
+        }
+        assert_eq!(
+            Ok(*R),
+            group_nonce_with_config(&commitments, &X, message, &session_config)
+        );
+    }
+
+    let signatures = coordinator.collect(&sessions).unwrap();
+    for ((session, message), signature) in sessions.iter().zip(messages).zip(&signatures) {
+        assert!(signature.verify_with_config(message, &X, &config().with_session(*session)));
+        assert!(!signature.verify_with_config(message, &X, &config()));
+    }
+    // collecting again returns the same signatures
+    assert_eq!(coordinator.collect(&sessions).unwrap(), signatures);
+    assert!(coordinator.remove(&sessions[0]).is_some());
+    assert_eq!(
+        coordinator.collect(&sessions).err(),
+        Some(Error::UnknownSession(sessions[0]))
+    );
+}
+
+#[test]
+fn test_coordinator_isolates_sessions() {
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let mut coordinator = Coordinator::new(keygen_output.public_key, config()).unwrap();
+    let sessions = coordinator
+        .start_batch(&[b"same message", b"same message"], &[1, 2])
+        .unwrap();
+    assert_ne!(sessions[0], sessions[1]);
+    assert_eq!(
+        coordinator.start_with_id(sessions[0], b"other", &[1, 2]),
+        Err(Error::DuplicateSession(sessions[0]))
+    );
+
+    // signers reuse nothing, but the coordinator is sent session 0's partials for session 1
+    let participants = &keygen_output.participants[..2];
+    let mut pools = participants
+        .iter()
+        .map(|p| NoncePool::new(p.id))
+        .collect::<Vec<_>>();
+    for session in &sessions {
+        for pool in pools.iter_mut() {
+            let commitment = pool.generate(1)[0];
+            coordinator.add_commitment(session, commitment).unwrap();
+        }
+    }
+    let commitments = coordinator.commitments(&sessions[0]).unwrap();
+    let session_config = config().with_session(sessions[0]);
+    for (participant, pool) in participants.iter().zip(pools.iter_mut()) {
+        let partial = pool
+            .sign(
+                participant,
+                &commitments,
+                &keygen_output.public_key,
+                b"same message",
+                &session_config,
+            )
+            .unwrap();
+        coordinator.add_partial(&sessions[0], partial).unwrap();
+        coordinator.add_partial(&sessions[1], partial).unwrap();
+    }
+    assert!(coordinator.collect(&sessions[..1]).is_ok());
+    assert_eq!(
+        coordinator.collect(&sessions[1..]),
+        Err(Error::InvalidSignature)
+    );
+    assert_eq!(
+        coordinator.session(&sessions[1]).unwrap().phase(),
+        SessionPhase::CollectingPartials
+    );
+}
+
+#[test]
+fn test_coordinator_rejects_bad_input() {
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let X = keygen_output.public_key;
+    assert_eq!(
+        Coordinator::new(X, config().legacy_challenge()).err(),
+        Some(Error::UnsupportedChallenge)
+    );
+
+    let mut coordinator = Coordinator::new(X, config()).unwrap();
+    assert!(coordinator.start_batch(&[b"a", b"b"], &[1, 1]).is_err());
+    assert!(coordinator.sessions().is_empty());
+    let unknown = SessionId::random();
+    let commitment = NoncePool::new(1).generate(1)[0];
+    assert_eq!(
+        coordinator.add_commitment(&unknown, commitment),
+        Err(Error::UnknownSession(unknown))
+    );
+
+    // only the session's signers, once each
+    let session = coordinator.start(b"message", &[1, 2]).unwrap();
+    let outsider = NoncePool::new(3).generate(1)[0];
+    assert_eq!(
+        coordinator.add_commitment(&session, outsider),
+        Err(Error::UnexpectedPackage { round: 1, id: 3 })
+    );
+    coordinator.add_commitment(&session, commitment).unwrap();
+    let again = NoncePool::new(1).generate(1)[0];
+    assert_eq!(
+        coordinator.add_commitment(&session, again),
+        Err(Error::DuplicatePackage { round: 1, id: 1 })
+    );
+    assert_eq!(coordinator.commitments(&session).unwrap(), vec![commitment]);
+
+    // a rejected last commitment records nothing, the signer can resend
+    let mut pool = NoncePool::new(2);
+    let valid = pool.generate(1)[0];
+    let broken = NonceCommitment {
+        D: ProjectivePoint::IDENTITY,
+        ..valid
+    };
+    assert_eq!(
+        coordinator.add_commitment(&session, broken),
+        Err(Error::IdentityPoint)
+    );
+    assert_eq!(coordinator.commitments(&session).unwrap().len(), 1);
+    coordinator.add_commitment(&session, valid).unwrap();
+    assert_eq!(
+        coordinator.session(&session).unwrap().phase(),
+        SessionPhase::CollectingPartials
+    );
+}
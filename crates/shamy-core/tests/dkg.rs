@@ -0,0 +1,280 @@
+#![allow(non_snake_case)]
+
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use k256::{ProjectivePoint, Scalar};
+use shamy_core::dkg::{self, DkgOutput, Round1Package, Round1Secret, Round2Package};
+use shamy_core::proofs::{
+    ProofOfPossession, verify_public_shares, verify_public_shares_with_context,
+};
+use shamy_core::schnorr::*;
+use shamy_core::shamir::EncryptedShare;
+use shamy_core::threshold::*;
+use shamy_core::vss::{public_share, verify_share};
+use shamy_core::{Error, Limits};
+
+fn run_round1(n: usize, t: usize) -> (Vec<Round1Secret>, Vec<Round1Package>) {
+    (1..=n as u64)
+        .map(|id| dkg::round1(id, t, n).unwrap())
+        .unzip()
+}
+
+fn run_ceremony(n: usize, t: usize) -> Vec<DkgOutput> {
+    let (secrets, round1_packages) = run_round1(n, t);
+
+    let round2_packages: Vec<Round2Package> = secrets
+        .iter()
+        .flat_map(|s| dkg::round2(s, &round1_packages).unwrap())
+        .collect();
+
+    secrets
+        .iter()
+        .map(|s| dkg::finalize(s, &round1_packages, &round2_packages).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_dkg_outputs_agree() {
+    let outputs = run_ceremony(4, 3);
+
+    for output in &outputs {
+        assert_eq!(output.public_key, outputs[0].public_key);
+        assert_eq!(output.commitments, outputs[0].commitments);
+        assert!(verify_share(
+            output.participant.id,
+            output.participant.x_i,
+            &output.commitments
+        ));
+    }
+}
+
+#[test]
+fn test_dkg_threshold_signature() {
+    let outputs = run_ceremony(3, 2);
+    let public_key = outputs[0].public_key;
+    let msg = b"dealerless signing";
+
+    let signers: Vec<Participant> = outputs.iter().skip(1).map(|o| o.participant).collect();
+    let ids: Vec<u64> = signers.iter().map(|p| p.id).collect();
+    let signer_set = SignerSet::new(&ids, ids.len()).unwrap();
+
+    let nonce_pairs = signers
+        .iter()
+        .map(|p| {
+            let r_i = generate_nonce();
+            (p, r_i, compute_nonce_point(&r_i))
+        })
+        .collect::<Vec<_>>();
+    let nonces = nonce_pairs
+        .iter()
+        .map(|(p, _, R_i)| (p.id, *R_i))
+        .collect::<Vec<_>>();
+    let R = aggregate_nonce_with_signers(&nonces, &signer_set).unwrap();
+
+    let c = compute_challenge(&R, &public_key, msg);
+    let partials = nonce_pairs
+        .iter()
+        .map(|(p, r_i, _)| partial_sign(p, r_i, &c))
+        .collect::<Vec<_>>();
+
+    let signature = finalize_signature_with_signers(&partials, R, &signer_set).unwrap();
+    assert!(signature.verify_with_config(
+        msg,
+        &public_key,
+        &SigningConfig::unframed().legacy_challenge()
+    ));
+
+    let public_keys = signers.iter().map(|p| (p.id, p.X_i)).collect::<Vec<_>>();
+    assert_eq!(aggregate_public_key(&public_keys).unwrap(), public_key);
+}
+
+#[test]
+fn test_dkg_rejects_tampered_share() {
+    let (secrets, round1_packages) = run_round1(3, 2);
+
+    let mut round2_packages: Vec<Round2Package> = secrets
+        .iter()
+        .flat_map(|s| dkg::round2(s, &round1_packages).unwrap())
+        .collect();
+    let tampered = round2_packages
+        .iter_mut()
+        .find(|p| p.sender == 2 && p.receiver == 1)
+        .unwrap();
+    tampered.share += k256::Scalar::ONE;
+
+    let err = dkg::finalize(&secrets[0], &round1_packages, &round2_packages).unwrap_err();
+    assert_eq!(err, Error::InvalidShare { id: 2 });
+}
+
+#[test]
+fn test_dkg_rejects_rogue_key() {
+    let (secrets, mut round1_packages) = run_round1(3, 2);
+
+    // participant 3 cancels out the others' constant terms
+    let rogue = ProjectivePoint::GENERATOR
+        - round1_packages[0].commitments[0]
+        - round1_packages[1].commitments[0];
+    round1_packages[2].commitments[0] = rogue;
+
+    let err = dkg::round2(&secrets[0], &round1_packages).unwrap_err();
+    assert_eq!(err, Error::InvalidProofOfPossession { id: 3 });
+
+    // a proof can't be replayed under another id
+    let (secrets, mut round1_packages) = run_round1(3, 2);
+    round1_packages[1].proof = round1_packages[2].proof;
+    round1_packages[1].commitments = round1_packages[2].commitments.clone();
+    let err = dkg::round2(&secrets[0], &round1_packages).unwrap_err();
+    assert_eq!(err, Error::InvalidProofOfPossession { id: 2 });
+}
+
+#[test]
+fn test_dkg_rejects_missing_and_malformed_packages() {
+    let (secrets, mut round1_packages) = run_round1(3, 2);
+
+    let missing = dkg::round2(&secrets[0], &round1_packages[..2]).unwrap_err();
+    assert_eq!(missing, Error::MissingPackage { round: 1, id: 3 });
+
+    round1_packages[1]
+        .commitments
+        .push(ProjectivePoint::GENERATOR);
+    let malformed = dkg::round2(&secrets[0], &round1_packages).unwrap_err();
+    assert_eq!(
+        malformed,
+        Error::InvalidCommitmentCount {
+            id: 2,
+            expected: 2,
+            actual: 3
+        }
+    );
+
+    // a higher coefficient at infinity, C_0 still carries the proof
+    round1_packages[1].commitments.truncate(2);
+    round1_packages[1].commitments[1] = ProjectivePoint::IDENTITY;
+    let degenerate = dkg::round2(&secrets[0], &round1_packages).unwrap_err();
+    assert_eq!(degenerate, Error::InvalidShare { id: 2 });
+}
+
+#[test]
+fn test_dkg_round1_invalid_parameters() {
+    assert!(dkg::round1(1, 1, 3).is_err());
+    assert!(dkg::round1(1, 4, 3).is_err());
+    assert!(dkg::round1(0, 2, 3).is_err());
+    assert!(dkg::round1(4, 2, 3).is_err());
+    assert!(matches!(
+        dkg::round1(1, 2, 10_000_000),
+        Err(Error::TooManyParticipants { .. })
+    ));
+
+    let limits = Limits::default()
+        .with_max_participants(5)
+        .with_max_threshold(2);
+    assert!(dkg::round1_with_limits(1, 2, 5, &limits).is_ok());
+    assert!(matches!(
+        dkg::round1_with_limits(1, 3, 5, &limits),
+        Err(Error::ThresholdTooLarge { .. })
+    ));
+}
+
+#[test]
+fn test_dkg_trivial_threshold() {
+    assert!(dkg::round1(1, 1, 3).is_err());
+    let limits = Limits::default().with_trivial_threshold(true);
+    let (secrets, round1_packages): (Vec<_>, Vec<_>) = (1..=3)
+        .map(|id| dkg::round1_with_limits(id, 1, 3, &limits).unwrap())
+        .unzip();
+    let round2_packages = secrets
+        .iter()
+        .flat_map(|s| dkg::round2(s, &round1_packages).unwrap())
+        .collect::<Vec<_>>();
+    let outputs = secrets
+        .iter()
+        .map(|s| dkg::finalize(s, &round1_packages, &round2_packages).unwrap())
+        .collect::<Vec<_>>();
+
+    // every participant ends with the same share of the group key
+    for output in &outputs {
+        assert_eq!(output.public_key, outputs[0].public_key);
+        assert_eq!(output.participant.x_i, outputs[0].participant.x_i);
+        assert_eq!(output.participant.X_i, output.public_key);
+    }
+}
+
+#[test]
+fn test_dkg_encrypted_round2() {
+    let (secrets, round1_packages) = run_round1(3, 2);
+    let identity_keys: Vec<Scalar> = (1..=3u64).map(|id| Scalar::from(100 + id)).collect();
+    let recipient = |id: u64| ProjectivePoint::GENERATOR * identity_keys[id as usize - 1];
+
+    // packages go over the wire encrypted to the receiver's identity key
+    let wire: Vec<Vec<u8>> = secrets
+        .iter()
+        .flat_map(|s| dkg::round2(s, &round1_packages).unwrap())
+        .map(|package| {
+            package
+                .encrypt_for(&recipient(package.receiver))
+                .unwrap()
+                .to_bytes()
+        })
+        .collect();
+
+    let outputs: Vec<DkgOutput> = secrets
+        .iter()
+        .zip(&identity_keys)
+        .map(|(secret, key)| {
+            let mine: Vec<Round2Package> = wire
+                .iter()
+                .map(|bytes| EncryptedShare::from_bytes(bytes).unwrap())
+                .filter(|encrypted| encrypted.receiver == secret.id)
+                .map(|encrypted| Round2Package::decrypt(key, &encrypted).unwrap())
+                .collect();
+            dkg::finalize(secret, &round1_packages, &mine).unwrap()
+        })
+        .collect();
+    assert!(
+        outputs
+            .iter()
+            .all(|o| o.public_key == outputs[0].public_key)
+    );
+
+    // another participant's identity key can't open it
+    let for_2 = EncryptedShare::from_bytes(&wire[0]).unwrap();
+    assert_eq!(for_2.receiver, 2);
+    assert!(matches!(
+        Round2Package::decrypt(&identity_keys[0], &for_2),
+        Err(Error::DecryptionFailed)
+    ));
+}
+
+#[test]
+fn test_dkg_shares_come_with_proofs_of_possession() {
+    let outputs = run_ceremony(3, 2);
+    let group_key = outputs[0].public_key.to_encoded_point(true);
+    let proofs = outputs
+        .iter()
+        .map(|o| ProofOfPossession::prove_with_context(&o.participant, group_key.as_bytes()))
+        .collect::<Vec<_>>();
+    let public_shares = outputs
+        .iter()
+        .map(|o| {
+            (
+                o.participant.id,
+                public_share(o.participant.id, &o.commitments),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        verify_public_shares_with_context(&public_shares, &proofs, group_key.as_bytes()),
+        Ok(())
+    );
+
+    // a proof registered for this group doesn't count anywhere else
+    assert_eq!(
+        verify_public_shares(&public_shares, &proofs),
+        Err(Error::InvalidProofOfPossession { id: 1 })
+    );
+    assert!(!proofs[1].verify_with_context(&public_shares[1].1, b"other group"));
+
+    // nor for a public share whose secret the prover doesn't hold
+    let mut stolen = proofs.clone();
+    stolen[2].id = 1;
+    assert!(!stolen[2].verify_with_context(&public_shares[0].1, group_key.as_bytes()));
+}
@@ -0,0 +1,47 @@
+use shamy_core::Error;
+use shamy_core::elgamal::*;
+use shamy_core::shamir::*;
+
+#[test]
+fn test_threshold_decryption() {
+    let keygen = shamir_keygen(5, 3).unwrap();
+    let msg = b"rust is best, and this message spans more than one keystream block";
+    let ciphertext = encrypt(&keygen.public_key, msg);
+    assert_ne!(&ciphertext.body[..], &msg[..]);
+
+    let shares = keygen
+        .participants
+        .iter()
+        .map(|p| partial_decrypt(p, &ciphertext))
+        .collect::<Vec<_>>();
+
+    assert_eq!(combine_decryptions(&ciphertext, &shares[..3]).unwrap(), msg);
+    assert_eq!(combine_decryptions(&ciphertext, &shares[2..]).unwrap(), msg);
+    assert_eq!(
+        combine_decryptions(&ciphertext, &shares[..2]),
+        Err(Error::DecryptionFailed)
+    );
+    assert_eq!(
+        combine_decryptions(&ciphertext, &[shares[0], shares[0], shares[1]]),
+        Err(Error::DuplicateParticipantId(1))
+    );
+    assert_eq!(combine_decryptions(&ciphertext, &[]), Err(Error::NoShares));
+}
+
+#[test]
+fn test_tampered_ciphertext_is_rejected() {
+    let keygen = shamir_keygen(3, 2).unwrap();
+    let mut ciphertext = encrypt(&keygen.public_key, b"rust is best");
+    ciphertext.body[0] ^= 1;
+
+    let shares = keygen
+        .participants
+        .iter()
+        .take(2)
+        .map(|p| partial_decrypt(p, &ciphertext))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        combine_decryptions(&ciphertext, &shares),
+        Err(Error::DecryptionFailed)
+    );
+}
@@ -0,0 +1,163 @@
+#![allow(non_snake_case)]
+
+use k256::{ProjectivePoint, Scalar};
+use shamy_core::error::Error;
+use shamy_core::util::*;
+
+#[test]
+fn test_segwit_vectors() {
+    // BIP-173, v0 P2WPKH
+    let (version, program) =
+        segwit_decode("bc", "BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4").unwrap();
+    assert_eq!(version, 0);
+    assert_eq!(
+        hex::encode(&program),
+        "751e76e8199196d454941c45d1b3a323f1433bd6"
+    );
+    assert_eq!(
+        segwit_encode("bc", 0, &program).unwrap(),
+        "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+    );
+
+    // BIP-350, v1 P2TR
+    let address = "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0";
+    let (version, program) = segwit_decode("bc", address).unwrap();
+    assert_eq!(version, 1);
+    assert_eq!(
+        hex::encode(&program),
+        "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"
+    );
+    assert_eq!(segwit_encode("bc", 1, &program).unwrap(), address);
+
+    // v1 with a bech32 checksum, wrong network
+    assert!(
+        segwit_decode(
+            "bc",
+            "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq2qvmrp"
+        )
+        .is_err()
+    );
+    assert!(segwit_decode("tb", address).is_err());
+}
+
+#[test]
+fn test_bech32_catches_typos() {
+    let share = Scalar::from(0xdead_beef_u64);
+    let encoded = scalar_to_bech32("shamyshare", &share).unwrap();
+    assert!(encoded.starts_with("shamyshare1"));
+    assert_eq!(bech32_to_scalar("shamyshare", &encoded).unwrap(), share);
+    assert_eq!(
+        bech32_to_scalar("shamyshare", &encoded.to_uppercase()).unwrap(),
+        share
+    );
+
+    // every single-character substitution fails the checksum
+    let data_start = encoded.rfind('1').unwrap() + 1;
+    for i in data_start..encoded.len() {
+        let mut typo = encoded.clone().into_bytes();
+        typo[i] = if typo[i] == b'q' { b'p' } else { b'q' };
+        let typo = String::from_utf8(typo).unwrap();
+        assert!(matches!(
+            bech32_to_scalar("shamyshare", &typo),
+            Err(Error::InvalidEncoding(_))
+        ));
+    }
+
+    // a share can't be read back as something else
+    assert!(bech32_to_scalar("shamypub", &encoded).is_err());
+    assert!(bech32_to_pp("shamyshare", &encoded).is_err());
+
+    let X = ProjectivePoint::GENERATOR * share;
+    let encoded = pp_to_bech32("shamypub", &X).unwrap();
+    assert_eq!(bech32_to_pp("shamypub", &encoded).unwrap(), X);
+}
+
+#[test]
+fn test_base58check() {
+    assert_eq!(
+        base58check_encode(&[0u8; 21]),
+        "1111111111111111111114oLvT2"
+    );
+
+    // a P2PKH address, version byte 0x00 then the 20-byte key hash
+    let payload = base58check_decode("1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2").unwrap();
+    assert_eq!(
+        hex::encode(&payload),
+        "0077bff20c60e522dfaa3350c39b030a5d004e839a"
+    );
+    assert_eq!(
+        base58check_encode(&payload),
+        "1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2"
+    );
+
+    assert!(matches!(
+        base58check_decode("1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN3"),
+        Err(Error::InvalidEncoding(_))
+    ));
+    assert!(base58check_decode("1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN0").is_err());
+}
+
+#[test]
+fn test_share_mnemonic() {
+    // BIP-39 vectors for 256-bit entropy
+    let share = hex_to_scalar(&"7f".repeat(32)).unwrap();
+    let phrase = "legal winner thank year wave sausage worth useful legal winner thank year \
+                  wave sausage worth useful legal winner thank year wave sausage worth title";
+    assert_eq!(
+        share_to_mnemonic(&share),
+        phrase.split_whitespace().collect::<Vec<_>>().join(" ")
+    );
+    assert_eq!(mnemonic_to_share(phrase).unwrap(), share);
+    assert_eq!(
+        mnemonic_to_share(&("abandon ".repeat(23) + "ART")).unwrap(),
+        Scalar::ZERO
+    );
+
+    let share = Scalar::from(0xdead_beef_u64);
+    assert_eq!(
+        mnemonic_to_share(&share_to_mnemonic(&share)).unwrap(),
+        share
+    );
+
+    // wrong last word, unknown word, short phrase
+    let swapped = phrase.replace("title", "trust");
+    assert!(matches!(
+        mnemonic_to_share(&swapped),
+        Err(Error::InvalidEncoding(_))
+    ));
+    assert!(mnemonic_to_share(&phrase.replace("legal", "legall")).is_err());
+    assert!(mnemonic_to_share("legal winner thank year").is_err());
+
+    // a valid phrase above the group order is not a share
+    assert_eq!(
+        mnemonic_to_share(&("zoo ".repeat(23) + "vote")),
+        Err(Error::InvalidScalar)
+    );
+}
+
+#[test]
+fn test_x_only_keys() {
+    // BIP-340 test vector 0, secret key 3
+    let P = ProjectivePoint::GENERATOR * Scalar::from(3u64);
+    let x_only = "f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9";
+    assert_eq!(pp_to_hex_x_only(&P), x_only);
+    assert!(has_even_y(&P));
+    assert_eq!(hex_to_pp_x_only(x_only).unwrap(), P);
+
+    // the odd twin has the same x and lifts back to the even point
+    assert!(!has_even_y(&-P));
+    assert_eq!(pp_to_x_only(&-P), pp_to_x_only(&P));
+    assert_eq!(x_only_to_pp(&pp_to_x_only(&-P)).unwrap(), P);
+
+    assert!(matches!(
+        hex_to_pp_x_only(&pp_to_hex(&P)),
+        Err(Error::InvalidPoint(_))
+    ));
+    // x = 5 is not on the curve
+    let mut not_on_curve = [0u8; 32];
+    not_on_curve[31] = 5;
+    assert!(matches!(
+        x_only_to_pp(&not_on_curve),
+        Err(Error::InvalidPoint(_))
+    ));
+}
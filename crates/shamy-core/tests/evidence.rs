@@ -0,0 +1,101 @@
+#![allow(non_snake_case)]
+
+use k256::{ProjectivePoint, Scalar};
+use shamy_core::Error;
+use shamy_core::evidence::*;
+use shamy_core::schnorr::*;
+use shamy_core::shamir::*;
+use shamy_core::threshold::*;
+use shamy_core::vss::*;
+
+fn config() -> SigningConfig {
+    SigningConfig::new("shamy-tests", "evidence")
+}
+
+#[test]
+fn test_session_evidence_for_bad_partial() {
+    let msg = b"blame";
+    let keygen_output = shamir_keygen(5, 3).unwrap();
+    let public_shares = keygen_output
+        .participants
+        .iter()
+        .map(|p| (p.id, p.X_i))
+        .collect::<Vec<_>>();
+    let mut signers = keygen_output.participants[..3]
+        .iter()
+        .map(|p| SignerState::new(*p))
+        .collect::<Vec<_>>();
+
+    let mut session =
+        SigningSession::new(keygen_output.public_key, msg, &[1, 2, 3], config()).unwrap();
+    for signer in signers.iter_mut() {
+        session
+            .add_nonce(signer.id(), signer.commit().unwrap())
+            .unwrap();
+    }
+    let (_, c) = session.challenge().unwrap();
+    for signer in signers.iter_mut() {
+        let mut partial = signer.sign(&c).unwrap();
+        if partial.id == 2 {
+            partial.s_i += Scalar::ONE;
+        }
+        session.add_partial(partial).unwrap();
+    }
+    assert!(session.finalize_identifiable(&public_shares).is_err());
+
+    let evidence = session.evidence(&public_shares);
+    assert_eq!(evidence.len(), 1);
+    assert_eq!(evidence[0].id, 2);
+    assert_eq!(evidence[0].relation(), "s_i*G == R_i + c*X_i");
+    assert_eq!(evidence[0].verify(), Ok(()));
+    assert!(evidence[0].check_challenge(&keygen_output.public_key, msg, &config()));
+    assert!(!evidence[0].check_challenge(&keygen_output.public_key, b"other", &config()));
+
+    // a third party only needs the bytes
+    let decoded = MisbehaviorEvidence::from_bytes(&evidence[0].to_bytes()).unwrap();
+    assert_eq!(decoded, evidence[0]);
+    assert_eq!(decoded.verify(), Ok(()));
+}
+
+#[test]
+fn test_evidence_against_honest_partial_is_rejected() {
+    let participant = Participant::from_secret(4, generate_nonce());
+    let r_i = generate_nonce();
+    let R_i = compute_nonce_point(&r_i);
+    let c = generate_nonce();
+    let partial = partial_sign(&participant, &r_i, &c);
+
+    let evidence = MisbehaviorEvidence::partial(&partial, R_i, R_i, participant.X_i, c);
+    assert_eq!(evidence.verify(), Err(Error::InvalidEvidence { id: 4 }));
+    assert!(partial_evidence(&[partial], &[(4, R_i)], &[(4, participant.X_i)], R_i, c).is_empty());
+}
+
+#[test]
+fn test_share_evidence() {
+    let keygen_output = shamir_keygen(4, 2).unwrap();
+    let commitments = &keygen_output.commitments;
+    let x_3 = keygen_output.participants[2].x_i;
+
+    let honest = MisbehaviorEvidence::share(1, 3, x_3, commitments);
+    assert_eq!(honest.verify(), Err(Error::InvalidEvidence { id: 1 }));
+
+    let evidence = MisbehaviorEvidence::share(1, 3, x_3 + Scalar::ONE, commitments);
+    assert!(!verify_share(3, x_3 + Scalar::ONE, commitments));
+    assert_eq!(evidence.verify(), Ok(()));
+    assert_eq!(
+        MisbehaviorEvidence::from_bytes(&evidence.to_bytes()).unwrap(),
+        evidence
+    );
+}
+
+#[test]
+fn test_evidence_bytes_rejects_garbage() {
+    let evidence = MisbehaviorEvidence::share(1, 3, Scalar::ONE, &[ProjectivePoint::GENERATOR]);
+    let bytes = evidence.to_bytes();
+
+    assert!(MisbehaviorEvidence::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    assert!(MisbehaviorEvidence::from_bytes(&[bytes.as_slice(), &[0]].concat()).is_err());
+    let mut kind = bytes.clone();
+    kind[13] = 9;
+    assert!(MisbehaviorEvidence::from_bytes(&kind).is_err());
+}
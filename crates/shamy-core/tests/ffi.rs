@@ -0,0 +1,160 @@
+#![cfg(feature = "ffi")]
+
+use shamy_core::ffi::*;
+use std::ptr;
+
+#[test]
+fn test_ffi_threshold_signing() {
+    let mut public_key = [0u8; SHAMY_POINT_LEN];
+    let mut shares = [0u8; 3 * SHAMY_SCALAR_LEN];
+    let mut public_shares = [0u8; 3 * SHAMY_POINT_LEN];
+    let status = unsafe {
+        shamy_keygen(
+            3,
+            2,
+            public_key.as_mut_ptr(),
+            shares.as_mut_ptr(),
+            public_shares.as_mut_ptr(),
+        )
+    };
+    assert_eq!(status, ShamyStatus::Ok);
+
+    // participants 1 and 3 sign
+    let ids = [1u64, 3];
+    let share = |id: u64| &shares[(id as usize - 1) * SHAMY_SCALAR_LEN..][..SHAMY_SCALAR_LEN];
+
+    let mut secrets = [[0u8; SHAMY_SCALAR_LEN]; 2];
+    let mut commitments = [0u8; 2 * SHAMY_POINT_LEN];
+    for (i, secret) in secrets.iter_mut().enumerate() {
+        let commitment = &mut commitments[i * SHAMY_POINT_LEN..][..SHAMY_POINT_LEN];
+        let status = unsafe { shamy_generate_nonce(secret.as_mut_ptr(), commitment.as_mut_ptr()) };
+        assert_eq!(status, ShamyStatus::Ok);
+    }
+
+    let mut nonce = [0u8; SHAMY_POINT_LEN];
+    let status = unsafe {
+        shamy_aggregate_nonce(
+            ids.as_ptr(),
+            commitments.as_ptr(),
+            ids.len(),
+            nonce.as_mut_ptr(),
+        )
+    };
+    assert_eq!(status, ShamyStatus::Ok);
+
+    let message = b"rust is best";
+    let app_id = c"treasury";
+    let purpose = c"withdrawal";
+    let mut challenge = [0u8; SHAMY_SCALAR_LEN];
+    let status = unsafe {
+        shamy_challenge(
+            nonce.as_ptr(),
+            public_key.as_ptr(),
+            message.as_ptr(),
+            message.len(),
+            app_id.as_ptr(),
+            purpose.as_ptr(),
+            challenge.as_mut_ptr(),
+        )
+    };
+    assert_eq!(status, ShamyStatus::Ok);
+
+    let mut partials = [0u8; 2 * SHAMY_SCALAR_LEN];
+    for (i, id) in ids.iter().enumerate() {
+        let partial = &mut partials[i * SHAMY_SCALAR_LEN..][..SHAMY_SCALAR_LEN];
+        let status = unsafe {
+            shamy_partial_sign(
+                *id,
+                share(*id).as_ptr(),
+                secrets[i].as_ptr(),
+                challenge.as_ptr(),
+                partial.as_mut_ptr(),
+            )
+        };
+        assert_eq!(status, ShamyStatus::Ok);
+    }
+
+    let mut signature = [0u8; SHAMY_SIGNATURE_LEN];
+    let status = unsafe {
+        shamy_aggregate(
+            ids.as_ptr(),
+            partials.as_ptr(),
+            ids.len(),
+            nonce.as_ptr(),
+            signature.as_mut_ptr(),
+        )
+    };
+    assert_eq!(status, ShamyStatus::Ok);
+
+    let verify = |message: &[u8], app_id: *const _, purpose: *const _| unsafe {
+        shamy_verify(
+            message.as_ptr(),
+            message.len(),
+            signature.as_ptr(),
+            public_key.as_ptr(),
+            app_id,
+            purpose,
+        )
+    };
+    assert_eq!(
+        verify(message, app_id.as_ptr(), purpose.as_ptr()),
+        ShamyStatus::Ok
+    );
+    assert_eq!(
+        verify(b"rust is worst", app_id.as_ptr(), purpose.as_ptr()),
+        ShamyStatus::InvalidSignature
+    );
+    assert_eq!(
+        verify(message, ptr::null(), ptr::null()),
+        ShamyStatus::InvalidSignature
+    );
+    assert_eq!(
+        verify(message, app_id.as_ptr(), ptr::null()),
+        ShamyStatus::NullPointer
+    );
+}
+
+#[test]
+fn test_ffi_invalid_input() {
+    let mut public_key = [0u8; SHAMY_POINT_LEN];
+    let status = unsafe {
+        shamy_keygen(
+            3,
+            4,
+            public_key.as_mut_ptr(),
+            ptr::null_mut(),
+            ptr::null_mut(),
+        )
+    };
+    assert_eq!(status, ShamyStatus::InvalidArgument);
+
+    let status = unsafe {
+        shamy_keygen(
+            3,
+            2,
+            public_key.as_mut_ptr(),
+            ptr::null_mut(),
+            ptr::null_mut(),
+        )
+    };
+    assert_eq!(status, ShamyStatus::NullPointer);
+
+    let scalar = [0xffu8; SHAMY_SCALAR_LEN];
+    let mut partial = [0u8; SHAMY_SCALAR_LEN];
+    let status = unsafe {
+        shamy_partial_sign(
+            1,
+            scalar.as_ptr(),
+            scalar.as_ptr(),
+            scalar.as_ptr(),
+            partial.as_mut_ptr(),
+        )
+    };
+    assert_eq!(status, ShamyStatus::InvalidScalar);
+
+    let point = [5u8; SHAMY_POINT_LEN];
+    let mut nonce = [0u8; SHAMY_POINT_LEN];
+    let status =
+        unsafe { shamy_aggregate_nonce([1u64].as_ptr(), point.as_ptr(), 1, nonce.as_mut_ptr()) };
+    assert_eq!(status, ShamyStatus::InvalidPoint);
+}
@@ -0,0 +1,159 @@
+#![allow(non_snake_case)]
+
+use shamy_core::Error;
+use shamy_core::frost::*;
+use shamy_core::schnorr::*;
+use shamy_core::shamir::*;
+use shamy_core::threshold::*;
+
+fn config() -> SigningConfig {
+    SigningConfig::new("shamy-tests", "frost")
+}
+
+#[test]
+fn test_preprocessed_signing() {
+    let keygen_output = shamir_keygen(5, 3).unwrap();
+    let X = keygen_output.public_key;
+    let signers = &keygen_output.participants[1..4];
+    let ids = signers.iter().map(|p| p.id).collect::<Vec<_>>();
+    let signer_set = SignerSet::new(&ids, 3).unwrap();
+    let mut pools = signers
+        .iter()
+        .map(|p| NoncePool::new(p.id))
+        .collect::<Vec<_>>();
+
+    // offline: 4 pairs per signer
+    let published = pools
+        .iter_mut()
+        .map(|pool| pool.generate(4))
+        .collect::<Vec<_>>();
+    assert_eq!(published[0].len(), 4);
+    assert_eq!(pools[0].commitments(), published[0]);
+
+    // online: one commitment per signer and message
+    for (k, msg) in [b"first".as_slice(), b"second", b"third"]
+        .iter()
+        .enumerate()
+    {
+        let commitments = published.iter().map(|batch| batch[k]).collect::<Vec<_>>();
+        let partials = signers
+            .iter()
+            .zip(pools.iter_mut())
+            .map(|(p, pool)| pool.sign(p, &commitments, &X, msg, &config()).unwrap())
+            .collect::<Vec<_>>();
+
+        let R = group_nonce(&commitments, &X, msg).unwrap();
+        let signature = finalize_signature_with_signers(&partials, R, &signer_set).unwrap();
+        assert!(signature.verify_with_config(msg, &X, &config()));
+    }
+    assert!(pools.iter().all(|pool| pool.remaining() == 1));
+}
+
+#[test]
+fn test_nonce_pool_refuses_reuse() {
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let X = keygen_output.public_key;
+    let [p1, p2] = [keygen_output.participants[0], keygen_output.participants[1]];
+    let mut pool = NoncePool::new(p1.id);
+    let own = pool.generate(2);
+    let other = NoncePool::new(p2.id).generate(1);
+
+    let commitments = [own[0], other[0]];
+    pool.sign(&p1, &commitments, &X, b"m", &config()).unwrap();
+    assert_eq!(
+        pool.sign(&p1, &commitments, &X, b"another message", &config()),
+        Err(Error::NonceReused { id: 1, index: 0 })
+    );
+
+    // never generated, or a commitment that isn't this pool's
+    let mut unknown = own[1];
+    unknown.index = 7;
+    assert_eq!(
+        pool.sign(&p1, &[unknown, other[0]], &X, b"m", &config()),
+        Err(Error::UnknownNonce { id: 1, index: 7 })
+    );
+    let mut swapped = own[1];
+    swapped.E = own[0].E;
+    assert_eq!(
+        pool.sign(&p1, &[swapped, other[0]], &X, b"m", &config()),
+        Err(Error::UnknownNonce { id: 1, index: 1 })
+    );
+
+    // a rejected signer set doesn't use up the pair
+    assert!(
+        pool.sign(&p1, &[own[1], own[1]], &X, b"m", &config())
+            .is_err()
+    );
+    assert_eq!(
+        pool.sign(&p1, &other, &X, b"m", &config()),
+        Err(Error::MissingPackage { round: 1, id: 1 })
+    );
+    assert_eq!(pool.remaining(), 1);
+    pool.sign(&p1, &[own[1], other[0]], &X, b"m", &config())
+        .unwrap();
+    assert_eq!(pool.remaining(), 0);
+}
+
+#[test]
+fn test_binding_factor_depends_on_session() {
+    let X = shamir_keygen(3, 2).unwrap().public_key;
+    let a = NoncePool::new(1).generate(2);
+    let b = NoncePool::new(2).generate(1);
+
+    let rho = binding_factor(1, &[a[0], b[0]], &X, b"m");
+    assert_eq!(rho, binding_factor(1, &[b[0], a[0]], &X, b"m"));
+    assert_ne!(rho, binding_factor(2, &[a[0], b[0]], &X, b"m"));
+    assert_ne!(rho, binding_factor(1, &[a[0], b[0]], &X, b"m'"));
+    assert_ne!(rho, binding_factor(1, &[a[1], b[0]], &X, b"m"));
+}
+
+#[cfg(feature = "merlin")]
+#[test]
+fn test_merlin_binding_factors() {
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let X = keygen_output.public_key;
+    let signers = &keygen_output.participants[..2];
+    let ids = signers.iter().map(|p| p.id).collect::<Vec<_>>();
+    let signer_set = SignerSet::new(&ids, 2).unwrap();
+    let mut pools = signers
+        .iter()
+        .map(|p| NoncePool::new(p.id))
+        .collect::<Vec<_>>();
+    let commitments = pools
+        .iter_mut()
+        .map(|pool| pool.generate(1)[0])
+        .collect::<Vec<_>>();
+
+    // any other config keeps the SHA-512 binding factor
+    let merlin = config().merlin_challenge();
+    let rho = binding_factor_with_config(1, &commitments, &X, b"m", &merlin);
+    assert_ne!(rho, binding_factor(1, &commitments, &X, b"m"));
+    assert_eq!(
+        binding_factor_with_config(1, &commitments, &X, b"m", &config()),
+        binding_factor(1, &commitments, &X, b"m")
+    );
+    assert_ne!(
+        rho,
+        binding_factor_with_config(2, &commitments, &X, b"m", &merlin)
+    );
+    assert_ne!(
+        rho,
+        binding_factor_with_config(
+            1,
+            &commitments,
+            &X,
+            b"m",
+            &merlin.clone().with_context(b"x")
+        )
+    );
+
+    let partials = signers
+        .iter()
+        .zip(pools.iter_mut())
+        .map(|(p, pool)| pool.sign(p, &commitments, &X, b"m", &merlin).unwrap())
+        .collect::<Vec<_>>();
+    let R = group_nonce_with_config(&commitments, &X, b"m", &merlin).unwrap();
+    assert_ne!(R, group_nonce(&commitments, &X, b"m").unwrap());
+    let signature = finalize_signature_with_signers(&partials, R, &signer_set).unwrap();
+    assert!(signature.verify_with_config(b"m", &X, &merlin));
+}
@@ -0,0 +1,99 @@
+#![allow(non_snake_case)]
+
+use k256::ProjectivePoint;
+use shamy_core::Error;
+use shamy_core::halfagg::*;
+use shamy_core::schnorr::{KeyPair, SchnorrSignature, SigningConfig};
+
+fn sign_all(
+    messages: &[&'static [u8]],
+    config: &SigningConfig,
+) -> Vec<(&'static [u8], ProjectivePoint, SchnorrSignature)> {
+    messages
+        .iter()
+        .map(|msg| {
+            let key_pair = KeyPair::generate();
+            (*msg, key_pair.public_key(), key_pair.sign(msg, config))
+        })
+        .collect()
+}
+
+fn public(
+    signatures: &[(&'static [u8], ProjectivePoint, SchnorrSignature)],
+) -> Vec<(&'static [u8], ProjectivePoint)> {
+    signatures.iter().map(|(msg, X, _)| (*msg, *X)).collect()
+}
+
+#[test]
+fn test_half_aggregate() {
+    let config = SigningConfig::new("shamy-tests", "log");
+    let signatures = sign_all(&[b"entry 1", b"entry 2", b"entry 3", b"entry 4"], &config);
+    let messages = public(&signatures);
+
+    let aggregate = half_aggregate(&signatures);
+    assert!(aggregate.verify(&messages, &config));
+    assert_eq!(aggregate.to_bytes().len(), 4 * 33 + 32);
+    assert_eq!(
+        HalfAggregate::from_bytes(&aggregate.to_bytes()).unwrap(),
+        aggregate
+    );
+
+    // wrong config, reordered or missing messages
+    assert!(!aggregate.verify(&messages, &SigningConfig::unframed()));
+    let mut swapped = messages.clone();
+    swapped.swap(1, 2);
+    assert!(!aggregate.verify(&swapped, &config));
+    assert!(!aggregate.verify(&messages[..3], &config));
+
+    // one bad signature spoils the aggregate
+    let mut tampered = signatures.clone();
+    tampered[2].2.s += k256::Scalar::ONE;
+    assert!(!half_aggregate(&tampered).verify(&messages, &config));
+
+    // the empty aggregate is just s = 0
+    assert!(half_aggregate(&[]).verify(&[], &config));
+}
+
+#[test]
+fn test_incremental_half_aggregate() {
+    let config = SigningConfig::new("shamy-tests", "log");
+    let signatures = sign_all(&[b"a", b"b", b"c", b"d", b"e"], &config);
+    let messages = public(&signatures);
+
+    let mut aggregate = half_aggregate(&signatures[..2]);
+    aggregate.extend(&messages[..2], &signatures[2..4]).unwrap();
+    aggregate.extend(&messages[..4], &signatures[4..]).unwrap();
+    assert_eq!(aggregate, half_aggregate(&signatures));
+    assert!(aggregate.verify(&messages, &config));
+
+    assert_eq!(
+        aggregate.extend(&messages[..4], &signatures[..1]),
+        Err(Error::InvalidAggregateLength {
+            expected: 5,
+            actual: 4
+        })
+    );
+}
+
+#[test]
+fn test_half_aggregate_rejects_malformed_bytes() {
+    let config = SigningConfig::unframed();
+    let bytes = half_aggregate(&sign_all(&[b"x"], &config)).to_bytes();
+
+    assert!(matches!(
+        HalfAggregate::from_bytes(&bytes[1..]),
+        Err(Error::InvalidEncoding(_))
+    ));
+    let mut bad_point = bytes.clone();
+    bad_point[0] = 0x05;
+    assert!(matches!(
+        HalfAggregate::from_bytes(&bad_point),
+        Err(Error::InvalidPoint(_))
+    ));
+    let mut bad_scalar = bytes.clone();
+    bad_scalar[33..].fill(0xff);
+    assert_eq!(
+        HalfAggregate::from_bytes(&bad_scalar),
+        Err(Error::InvalidScalar)
+    );
+}
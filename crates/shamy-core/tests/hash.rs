@@ -0,0 +1,55 @@
+use sha2::{Digest, Sha256};
+use shamy_core::hash::{
+    finalize_scalar, hash_to_scalar, scalar_hasher, tagged_hash, tagged_hasher,
+};
+use shamy_core::util::scalar_to_hex;
+
+#[test]
+fn test_tagged_hash_matches_bip340() {
+    let tag = b"BIP0340/challenge";
+    let data = b"payload";
+
+    let tag_hash = Sha256::digest(tag);
+    let expected: [u8; 32] = Sha256::new()
+        .chain_update(tag_hash)
+        .chain_update(tag_hash)
+        .chain_update(data)
+        .finalize()
+        .into();
+    assert_eq!(tagged_hash(tag, data), expected);
+
+    let mut hasher = tagged_hasher(tag);
+    hasher.update(b"pay");
+    hasher.update(b"load");
+    assert_eq!(<[u8; 32]>::from(hasher.finalize()), expected);
+}
+
+#[test]
+fn test_tags_separate_outputs() {
+    assert_ne!(
+        tagged_hash(b"shamy/a/v1", b"x"),
+        tagged_hash(b"shamy/b/v1", b"x")
+    );
+    assert_ne!(
+        tagged_hash(b"shamy/a/v1", b""),
+        <[u8; 32]>::from(Sha256::digest(b"shamy/a/v1"))
+    );
+}
+
+#[test]
+fn test_hash_to_scalar_reduces_all_64_bytes() {
+    let tag = b"shamy/tests/hash-to-scalar/v1";
+
+    // SHA-512(SHA-512(tag) || SHA-512(tag) || data) as a 512-bit integer mod q
+    let c = hash_to_scalar(tag, b"payload");
+    assert_eq!(
+        scalar_to_hex(&c),
+        "9ae12a9a2826fe3e313ecbc6e1ec6fce4afd30a9a966885f77ac8b7338c14dda"
+    );
+
+    let mut hasher = scalar_hasher(tag);
+    hasher.update(b"pay");
+    hasher.update(b"load");
+    assert_eq!(finalize_scalar(hasher), c);
+    assert_ne!(hash_to_scalar(b"shamy/tests/other/v1", b"payload"), c);
+}
@@ -0,0 +1,101 @@
+#![allow(non_snake_case)]
+
+use shamy_core::Error;
+use shamy_core::hd::*;
+use shamy_core::schnorr::*;
+use shamy_core::shamir::*;
+use shamy_core::threshold::*;
+use shamy_core::util::{hex_to_pp, pp_to_hex};
+
+#[test]
+fn test_bip32_vector_2() {
+    // BIP-32 test vector 2, M -> M/0
+    let X =
+        hex_to_pp("03cbcaa9c98c877a26977d00825c956a238e8dddfbd322cce4f74b0b5bd6ace4a7").unwrap();
+    let chain_code =
+        hex::decode("60499f801b896d83179a4374aeb7822aaeaceaa0db1f85ee3e904c4defbd9689")
+            .unwrap()
+            .try_into()
+            .unwrap();
+
+    let child = DerivedKey::root(X, chain_code).child(0).unwrap();
+    assert_eq!(
+        pp_to_hex(&child.public_key),
+        "02fc9e5af0ac8d9b3cecfe2a888e2117ba3d089d8585886c9c826b6b22a98d12ea"
+    );
+    assert_eq!(
+        hex::encode(child.chain_code),
+        "f0909affaa7ee7abe5dd4e100598d4dc53cd709d5a5c2cac40e7412f232f7c9c"
+    );
+
+    assert_eq!(
+        DerivedKey::root(X, chain_code).child(HARDENED),
+        Err(Error::InvalidChildIndex(HARDENED))
+    );
+}
+
+#[test]
+fn test_threshold_signing_with_derived_key() {
+    let keygen = shamir_keygen(5, 3).unwrap();
+    let derived = DerivedKey::root(keygen.public_key, [7u8; 32])
+        .derive_path(&[0, 42])
+        .unwrap();
+    assert_eq!(
+        derived,
+        DerivedKey::root(keygen.public_key, [7u8; 32])
+            .child(0)
+            .unwrap()
+            .child(42)
+            .unwrap()
+    );
+
+    let signers = keygen
+        .participants
+        .iter()
+        .skip(2)
+        .map(|p| derived.tweak_participant(p))
+        .collect::<Vec<_>>();
+    for (signer, p) in signers.iter().zip(keygen.participants.iter().skip(2)) {
+        assert_eq!(signer.X_i, derived.tweak_public_share(&p.X_i));
+    }
+    let public_shares = signers.iter().map(|p| (p.id, p.X_i)).collect::<Vec<_>>();
+    assert_eq!(
+        aggregate_public_key(&public_shares).unwrap(),
+        derived.public_key
+    );
+
+    let ids = signers.iter().map(|p| p.id).collect::<Vec<_>>();
+    let signer_set = SignerSet::new(&ids, ids.len()).unwrap();
+    let nonces = signers
+        .iter()
+        .map(|p| (p.id, generate_nonce()))
+        .collect::<Vec<_>>();
+    let R = aggregate_nonce_with_signers(
+        &nonces
+            .iter()
+            .map(|(id, r_i)| (*id, compute_nonce_point(r_i)))
+            .collect::<Vec<_>>(),
+        &signer_set,
+    )
+    .unwrap();
+
+    let msg = b"rust is best";
+    let c = compute_challenge(&R, &derived.public_key, msg);
+    let partials = signers
+        .iter()
+        .zip(&nonces)
+        .map(|(p, (_, r_i))| partial_sign(p, r_i, &c))
+        .collect::<Vec<_>>();
+
+    let signature = finalize_signature_with_signers(&partials, R, &signer_set).unwrap();
+    assert!(signature.verify_with_config(
+        msg,
+        &derived.public_key,
+        &SigningConfig::unframed().legacy_challenge()
+    ));
+    assert!(!signature.verify_with_config(
+        msg,
+        &keygen.public_key,
+        &SigningConfig::unframed().legacy_challenge()
+    ));
+}
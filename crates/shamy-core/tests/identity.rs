@@ -0,0 +1,81 @@
+#![allow(non_snake_case)]
+
+use k256::{ProjectivePoint, Scalar};
+use shamy_core::Error;
+use shamy_core::identity::*;
+use shamy_core::threshold::*;
+
+#[test]
+fn test_signed_partial_signature() {
+    let alice = IdentityKey::generate();
+    let mut registry = IdentityRegistry::new();
+    registry.insert(1, alice.public_key()).unwrap();
+
+    let participant = Participant::from_secret(1, Scalar::from(42u64));
+    let partial = partial_sign(&participant, &Scalar::from(7u64), &Scalar::from(3u64));
+    let message = alice.sign(b"session", 4, 1, BROADCAST, &partial.s_i.to_bytes());
+
+    let decoded = SignedMessage::from_bytes(&message.to_bytes()).unwrap();
+    assert_eq!(decoded, message);
+    assert_eq!(
+        registry.verify(b"session", &decoded).unwrap(),
+        partial.s_i.to_bytes().as_slice()
+    );
+    // a different ceremony
+    assert!(matches!(
+        registry.verify(b"other session", &decoded),
+        Err(Error::InvalidMessageSignature { round: 4, id: 1 })
+    ));
+}
+
+#[test]
+fn test_signed_message_binds_every_field() {
+    let key = IdentityKey::generate();
+    let S = key.public_key();
+    let message = key.sign(b"ctx", 1, 2, 3, b"nonce commitment");
+    assert!(message.verify(b"ctx", &S));
+
+    let mut tampered = [
+        message.clone(),
+        message.clone(),
+        message.clone(),
+        message.clone(),
+    ];
+    tampered[0].round = 2;
+    tampered[1].sender = 5;
+    tampered[2].receiver = BROADCAST;
+    tampered[3].payload[0] ^= 1;
+    for message in &tampered {
+        assert!(!message.verify(b"ctx", &S));
+    }
+    assert!(!message.verify(b"ctx", &IdentityKey::generate().public_key()));
+}
+
+#[test]
+fn test_registry_rejects_unknown_senders() {
+    let key = IdentityKey::generate();
+    let mut registry = IdentityRegistry::new();
+    registry.insert(1, key.public_key()).unwrap();
+    assert!(registry.insert(2, ProjectivePoint::IDENTITY).is_err());
+    assert_eq!(registry.ids(), vec![1]);
+
+    // a valid signature, but nobody registered id 9
+    let message = key.sign(b"ctx", 1, 9, BROADCAST, b"");
+    assert!(matches!(
+        registry.verify(b"ctx", &message),
+        Err(Error::UnknownIdentity(9))
+    ));
+    assert!(IdentityKey::from_secret(Scalar::ZERO).is_err());
+}
+
+#[test]
+fn test_signed_message_encoding() {
+    let message = IdentityKey::generate().sign(b"ctx", 2, 1, 3, &[0xab; 40]);
+    let bytes = message.to_bytes();
+    assert!(bytes.starts_with(b"SHSM\x01\x02"));
+    assert!(SignedMessage::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    assert!(SignedMessage::from_bytes(&[bytes.as_slice(), &[0]].concat()).is_err());
+    let mut other_version = bytes.clone();
+    other_version[4] = 2;
+    assert!(SignedMessage::from_bytes(&other_version).is_err());
+}
@@ -0,0 +1,108 @@
+#![allow(non_snake_case)]
+
+use k256::{ProjectivePoint, Scalar};
+use shamy_core::Error;
+use shamy_core::musig::*;
+use shamy_core::schnorr::{SigningConfig, generate_nonce};
+
+fn keys(n: usize) -> Vec<(Scalar, ProjectivePoint)> {
+    (0..n)
+        .map(|_| {
+            let x_i = generate_nonce();
+            (x_i, ProjectivePoint::GENERATOR * x_i)
+        })
+        .collect()
+}
+
+#[test]
+fn test_musig_sign_verify() {
+    let keys = keys(3);
+    let public_keys = keys.iter().map(|(_, X_i)| *X_i).collect::<Vec<_>>();
+    let key_agg = KeyAggContext::new(&public_keys).unwrap();
+    let config = SigningConfig::new("treasury", "withdrawal");
+    let message = b"rust is best";
+
+    let secret_nonces = keys
+        .iter()
+        .map(|_| SecretNonce::generate())
+        .collect::<Vec<_>>();
+    let public_nonces = secret_nonces
+        .iter()
+        .map(SecretNonce::public_nonce)
+        .collect::<Vec<_>>();
+    let session = Session::new(
+        &key_agg,
+        &aggregate_nonces(&public_nonces),
+        message,
+        &config,
+    )
+    .unwrap();
+
+    let partials = keys
+        .iter()
+        .zip(secret_nonces)
+        .map(|((x_i, _), nonce)| session.sign(x_i, nonce).unwrap())
+        .collect::<Vec<_>>();
+    for ((X_i, nonce), s_i) in public_keys.iter().zip(&public_nonces).zip(&partials) {
+        assert!(session.verify_partial(X_i, nonce, s_i));
+    }
+
+    let signature = session.aggregate(&partials).unwrap();
+    assert!(signature.verify_with_config(message, &key_agg.aggregate_key(), &config));
+    assert!(!signature.verify_with_config(b"rust is worst", &key_agg.aggregate_key(), &config));
+
+    // a partial can't be replayed for another signer
+    assert!(!session.verify_partial(&public_keys[1], &public_nonces[1], &partials[0]));
+    assert_eq!(
+        session.aggregate(&partials[..2]),
+        Err(Error::InvalidPartialCount {
+            expected: 3,
+            actual: 2
+        })
+    );
+}
+
+#[test]
+fn test_musig_key_aggregation() {
+    let keys = keys(3);
+    let public_keys = keys.iter().map(|(_, X_i)| *X_i).collect::<Vec<_>>();
+    let key_agg = KeyAggContext::new(&public_keys).unwrap();
+
+    // coefficients make the aggregate differ from the plain sum
+    let sum = public_keys
+        .iter()
+        .fold(ProjectivePoint::IDENTITY, |acc, X_i| acc + X_i);
+    assert_ne!(key_agg.aggregate_key(), sum);
+
+    // the order of the keys is part of the aggregate
+    let reversed = public_keys.iter().rev().copied().collect::<Vec<_>>();
+    assert_ne!(
+        KeyAggContext::new(&reversed).unwrap().aggregate_key(),
+        key_agg.aggregate_key()
+    );
+
+    let outsider = ProjectivePoint::GENERATOR * generate_nonce();
+    assert_eq!(key_agg.coefficient(&outsider), Err(Error::UnknownPublicKey));
+    assert_eq!(KeyAggContext::new(&[]), Err(Error::NoPublicKeys));
+}
+
+#[test]
+fn test_musig_outsider_cannot_sign() {
+    let keys = keys(2);
+    let public_keys = keys.iter().map(|(_, X_i)| *X_i).collect::<Vec<_>>();
+    let key_agg = KeyAggContext::new(&public_keys).unwrap();
+
+    let nonce = SecretNonce::generate();
+    let session = Session::new(
+        &key_agg,
+        &aggregate_nonces(&[nonce.public_nonce(), SecretNonce::generate().public_nonce()]),
+        b"rust is best",
+        &SigningConfig::unframed(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        session.sign(&generate_nonce(), nonce).err(),
+        Some(Error::UnknownPublicKey)
+    );
+}
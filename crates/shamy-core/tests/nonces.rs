@@ -0,0 +1,65 @@
+use shamy_core::Error;
+use shamy_core::nonces::*;
+use shamy_core::schnorr::*;
+use shamy_core::threshold::*;
+
+#[test]
+fn test_registry_refuses_second_use() {
+    let participant = Participant::from_secret(1, generate_nonce());
+    let other = Participant::from_secret(2, generate_nonce());
+    let (r, c_1, c_2) = (generate_nonce(), generate_nonce(), generate_nonce());
+    let mut registry = NonceRegistry::new();
+
+    let partial = registry.partial_sign(&participant, &r, &c_1).unwrap();
+    assert_eq!(partial, partial_sign(&participant, &r, &c_1));
+    assert!(registry.is_used(1, &r));
+    assert_eq!(
+        registry.partial_sign(&participant, &r, &c_2),
+        Err(Error::NonceAlreadyUsed {
+            id: 1,
+            same_challenge: false,
+        })
+    );
+    assert_eq!(
+        registry.partial_sign(&participant, &r, &c_1),
+        Err(Error::NonceAlreadyUsed {
+            id: 1,
+            same_challenge: true,
+        })
+    );
+
+    // nonces are tracked per participant
+    registry.partial_sign(&other, &r, &c_2).unwrap();
+    registry
+        .partial_sign(&participant, &generate_nonce(), &c_2)
+        .unwrap();
+    assert_eq!(registry.len(), 3);
+}
+
+#[test]
+fn test_file_registry_survives_reopen() {
+    let path = std::env::temp_dir().join(format!("shamy-nonces-{}.txt", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let participant = Participant::from_secret(3, generate_nonce());
+    let (r, c) = (generate_nonce(), generate_nonce());
+
+    NonceRegistry::open(&path)
+        .unwrap()
+        .partial_sign(&participant, &r, &c)
+        .unwrap();
+    let mut registry = NonceRegistry::open(&path).unwrap();
+    assert_eq!(registry.len(), 1);
+    assert!(registry.is_used(3, &r));
+    assert!(
+        registry
+            .partial_sign(&participant, &r, &generate_nonce())
+            .is_err()
+    );
+
+    std::fs::write(&path, "3 not-a-point 00\n").unwrap();
+    assert!(matches!(
+        NonceRegistry::open(&path),
+        Err(Error::InvalidEncoding(_))
+    ));
+    std::fs::remove_file(&path).unwrap();
+}
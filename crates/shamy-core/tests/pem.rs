@@ -0,0 +1,97 @@
+#![allow(non_snake_case)]
+
+use k256::ProjectivePoint;
+use shamy_core::pem::*;
+use shamy_core::shamir::*;
+use shamy_core::util::fingerprint;
+
+// `openssl ec -pubin -pubout` of the generator, uncompressed and compressed
+const GENERATOR_PEM: &str = "-----BEGIN PUBLIC KEY-----
+MFYwEAYHKoZIzj0CAQYFK4EEAAoDQgAEeb5mfvncu6xVoGKVzocLBwKb/NstzijZ
+WfKBWxb4F5hIOtp3JqPEZV2k+/wOEQio/Re0SKaFVBmcR9CP+xDUuA==
+-----END PUBLIC KEY-----
+";
+const GENERATOR_PEM_COMPRESSED: &str = "-----BEGIN PUBLIC KEY-----
+MDYwEAYHKoZIzj0CAQYFK4EEAAoDIgACeb5mfvncu6xVoGKVzocLBwKb/NstzijZ
+WfKBWxb4F5g=
+-----END PUBLIC KEY-----
+";
+
+#[test]
+fn test_public_key_pem_matches_openssl() {
+    assert_eq!(
+        public_key_to_pem(&ProjectivePoint::GENERATOR),
+        GENERATOR_PEM
+    );
+    for pem in [GENERATOR_PEM, GENERATOR_PEM_COMPRESSED] {
+        let key = group_key_from_pem(pem).unwrap();
+        assert_eq!(key.public_key, ProjectivePoint::GENERATOR);
+        assert_eq!(key.threshold, None);
+    }
+}
+
+#[test]
+fn test_group_key_pem() {
+    let keygen_output = shamir_keygen(5, 3).unwrap();
+    let X = keygen_output.public_key;
+
+    let pem = group_key_to_pem(&X, 3, 5);
+    assert!(pem.starts_with("-----BEGIN SHAMY GROUP KEY-----\nThreshold: 3\n"));
+    assert!(pem.contains(&format!("Fingerprint: {}\n", fingerprint(&X))));
+    let key = group_key_from_pem(&pem).unwrap();
+    assert_eq!(key.public_key, X);
+    assert_eq!((key.threshold, key.participants), (Some(3), Some(5)));
+
+    // the body is a plain SubjectPublicKeyInfo
+    let decoded = Pem::decode(&pem).unwrap();
+    assert_eq!(spki_to_public_key(&decoded.body).unwrap(), X);
+
+    let other = shamir_keygen(3, 2).unwrap().public_key;
+    let forged = pem.replace(&fingerprint(&X), &fingerprint(&other));
+    assert!(group_key_from_pem(&forged).is_err());
+}
+
+#[test]
+fn test_share_pem() {
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let X = keygen_output.public_key;
+    let participant = &keygen_output.participants[1];
+
+    let pem = share_to_pem(participant, &X, 2);
+    assert!(pem.starts_with("-----BEGIN SHAMY SHARE-----\nId: 2\nThreshold: 2\n"));
+    // text around the block, e.g. from a mail, is skipped
+    let share = share_from_pem(&format!("share for id 2:\n\n{pem}\nthanks")).unwrap();
+    assert_eq!(share.participant, *participant);
+    assert_eq!((share.public_key, share.threshold), (X, 2));
+
+    // a header that disagrees with the body
+    assert!(share_from_pem(&pem.replace("Id: 2", "Id: 3")).is_err());
+    assert!(share_from_pem(&pem.replace("Threshold: 2", "Threshold: 1")).is_err());
+    // headers are optional
+    let bare = Pem {
+        headers: Vec::new(),
+        ..Pem::decode(&pem).unwrap()
+    };
+    assert_eq!(share_from_pem(&bare.encode()).unwrap(), share);
+
+    // a share is not a public key and the other way around
+    assert!(group_key_from_pem(&pem).is_err());
+    assert!(share_from_pem(&group_key_to_pem(&X, 2, 3)).is_err());
+}
+
+#[test]
+fn test_pem_rejects_malformed() {
+    let pem = public_key_to_pem(&ProjectivePoint::GENERATOR);
+    assert!(Pem::decode("").is_err());
+    assert!(Pem::decode(&pem.replace("-----END PUBLIC KEY-----\n", "")).is_err());
+    assert!(Pem::decode(&pem.replace("MFYw", "MFY*")).is_err());
+    assert!(Pem::decode(&pem.replace("uA==", "uA=")).is_err());
+    // non-zero bits after the last byte
+    assert!(Pem::decode(&pem.replace("uA==", "uB==")).is_err());
+    assert!(spki_to_public_key(&[0x30, 0x00]).is_err());
+
+    let roundtrip = Pem::new("TEST", &[0, 1, 2, 3, 4]).with_header("Comment", "a: b");
+    let decoded = Pem::decode(&roundtrip.encode()).unwrap();
+    assert_eq!(decoded, roundtrip);
+    assert_eq!(decoded.header("Comment"), Some("a: b"));
+}
@@ -0,0 +1,116 @@
+#![allow(non_snake_case)]
+
+use k256::{ProjectivePoint, Scalar};
+use shamy_core::Error;
+use shamy_core::proofs::{self, ProofOfPossession, verify_public_shares};
+use shamy_core::shamir::shamir_keygen;
+use shamy_core::vrf::hash_to_point;
+
+#[test]
+fn test_proof_of_possession() {
+    let keygen = shamir_keygen(3, 2).unwrap();
+    let public_shares = keygen
+        .participants
+        .iter()
+        .map(|p| (p.id, p.X_i))
+        .collect::<Vec<_>>();
+    let proofs = keygen
+        .participants
+        .iter()
+        .rev()
+        .map(ProofOfPossession::prove)
+        .collect::<Vec<_>>();
+    assert_eq!(verify_public_shares(&public_shares, &proofs), Ok(()));
+
+    // a proof is bound to the participant id
+    let mut replayed = proofs.clone();
+    replayed[0].id = 1;
+    replayed[2].id = 3;
+    assert_eq!(
+        verify_public_shares(&public_shares, &replayed),
+        Err(Error::InvalidProofOfPossession { id: 1 })
+    );
+
+    // nobody knows the secret of a rogue share X_3 = X - X_1 - X_2
+    let mut rogue = public_shares.clone();
+    rogue[2].1 = keygen.public_key - public_shares[0].1 - public_shares[1].1;
+    assert_eq!(
+        verify_public_shares(&rogue, &proofs),
+        Err(Error::InvalidProofOfPossession { id: 3 })
+    );
+    assert_eq!(
+        verify_public_shares(&public_shares, &proofs[..2]),
+        Err(Error::InvalidProofOfPossession { id: 1 })
+    );
+    assert!(!proofs[0].verify(&ProjectivePoint::GENERATOR));
+}
+
+#[test]
+fn test_dleq_proof() {
+    let G = ProjectivePoint::GENERATOR;
+    let H = hash_to_point(b"dleq base");
+    let x = Scalar::from(42u64);
+    let (A, B) = (G * x, H * x);
+
+    let proof = proofs::prove(&x, &G, &H, b"ctx");
+    assert!(proofs::verify(&proof, &G, &A, &H, &B, b"ctx"));
+    assert_ne!(proof, proofs::prove(&x, &G, &H, b"ctx"));
+
+    // bound to the context and to every point of the statement
+    assert!(!proofs::verify(&proof, &G, &A, &H, &B, b"other"));
+    assert!(!proofs::verify(
+        &proof,
+        &G,
+        &A,
+        &H,
+        &(H * Scalar::from(43u64)),
+        b"ctx"
+    ));
+    assert!(!proofs::verify(
+        &proof,
+        &G,
+        &A.double(),
+        &H,
+        &B.double(),
+        b"ctx"
+    ));
+    assert!(!proofs::verify(&proof, &H, &B, &G, &A, b"ctx"));
+
+    // an identity base would make any statement provable
+    let I = ProjectivePoint::IDENTITY;
+    let trivial = proofs::prove(&x, &G, &I, b"ctx");
+    assert!(!proofs::verify(&trivial, &G, &A, &I, &I, b"ctx"));
+}
+
+#[test]
+fn test_dleq_proof_for_decryption_share() {
+    // D_i = x_i*C1 is the decryption share of X_i = x_i*G
+    let keygen = shamir_keygen(3, 2).unwrap();
+    let p = keygen.participants[0];
+    let C1 = hash_to_point(b"ciphertext");
+    let D_i = C1 * p.x_i;
+
+    let proof = proofs::prove(
+        &p.x_i,
+        &ProjectivePoint::GENERATOR,
+        &C1,
+        &p.id.to_be_bytes(),
+    );
+    assert!(proofs::verify(
+        &proof,
+        &ProjectivePoint::GENERATOR,
+        &p.X_i,
+        &C1,
+        &D_i,
+        &p.id.to_be_bytes()
+    ));
+    let other = keygen.participants[1];
+    assert!(!proofs::verify(
+        &proof,
+        &ProjectivePoint::GENERATOR,
+        &other.X_i,
+        &C1,
+        &(C1 * other.x_i),
+        &p.id.to_be_bytes()
+    ));
+}
@@ -0,0 +1,157 @@
+#![allow(non_snake_case)]
+
+use shamy_core::Error;
+use shamy_core::repair::*;
+use shamy_core::schnorr::*;
+use shamy_core::shamir::*;
+use shamy_core::threshold::*;
+use shamy_core::vss::public_share;
+
+#[test]
+fn test_repair_lost_share() {
+    let keygen_output = shamir_keygen(5, 3).unwrap();
+    let lost = keygen_output.participants[1];
+    let helpers = [1, 4, 5];
+    let helper_shares = keygen_output
+        .participants
+        .iter()
+        .filter(|p| helpers.contains(&p.id))
+        .collect::<Vec<_>>();
+
+    let round1 = helper_shares
+        .iter()
+        .flat_map(|p| repair_round1(p, &helpers, lost.id).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(round1.len(), 9);
+    let sums = helpers
+        .iter()
+        .map(|&id| {
+            let received = round1
+                .iter()
+                .filter(|p| p.receiver == id)
+                .copied()
+                .collect::<Vec<_>>();
+            repair_round2(id, &helpers, lost.id, &received).unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    let repaired = repair_finalize(lost.id, &helpers, &sums, &keygen_output.commitments).unwrap();
+    assert_eq!(repaired, lost);
+
+    // a missing or tampered sum
+    assert_eq!(
+        repair_finalize(lost.id, &helpers, &sums[..2], &keygen_output.commitments),
+        Err(Error::MissingPackage { round: 2, id: 5 })
+    );
+    let mut tampered = sums.clone();
+    tampered[0].sigma += sums[1].sigma;
+    assert_eq!(
+        repair_finalize(lost.id, &helpers, &tampered, &keygen_output.commitments),
+        Err(Error::InvalidShare { id: 2 })
+    );
+}
+
+#[test]
+fn test_repair_rejects_bad_helper_sets() {
+    let keygen_output = shamir_keygen(5, 3).unwrap();
+    let helper = keygen_output.participants[0];
+
+    // fewer helpers than the threshold can't recompute the share
+    assert_eq!(
+        repair_finalize(2, &[1, 3], &[], &keygen_output.commitments),
+        Err(Error::NotEnoughShares {
+            threshold: 3,
+            actual: 2
+        })
+    );
+    assert_eq!(
+        repair_round1(&helper, &[1, 2, 3], 2).unwrap_err(),
+        Error::DuplicateParticipantId(2)
+    );
+    assert_eq!(
+        repair_round1(&helper, &[3, 4, 5], 2).unwrap_err(),
+        Error::UnexpectedPackage { round: 1, id: 1 }
+    );
+
+    // a package for someone else
+    let packages = repair_round1(&helper, &[1, 3, 4], 2).unwrap();
+    assert_eq!(
+        repair_round2(3, &[1, 3, 4], 2, &packages).unwrap_err(),
+        Error::UnexpectedPackage { round: 1, id: 1 }
+    );
+}
+
+#[test]
+fn test_enroll_new_participant() {
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let helpers = [1, 3];
+    let round1 = keygen_output
+        .participants
+        .iter()
+        .filter(|p| helpers.contains(&p.id))
+        .flat_map(|p| enroll_round1(p, &helpers, 4, &[1, 2, 3]).unwrap())
+        .collect::<Vec<_>>();
+    let sums = helpers
+        .iter()
+        .map(|&id| {
+            let received = round1
+                .iter()
+                .filter(|p| p.receiver == id)
+                .copied()
+                .collect::<Vec<_>>();
+            enroll_round2(id, &helpers, 4, &[1, 2, 3], &received).unwrap()
+        })
+        .collect::<Vec<_>>();
+    let enrolled =
+        enroll_finalize(4, &helpers, &sums, &keygen_output.commitments, &[1, 2, 3]).unwrap();
+    assert_eq!(enrolled.X_i, public_share(4, &keygen_output.commitments));
+
+    // the new share signs with an old one under the same key
+    let X = keygen_output.public_key;
+    let signers = [keygen_output.participants[1], enrolled];
+    let ids = signers.iter().map(|p| p.id).collect::<Vec<_>>();
+    let signer_set = SignerSet::new(&ids, ids.len()).unwrap();
+    let nonces = signers.map(|_| generate_nonce());
+    let R = aggregate_nonce_with_signers(
+        &signers
+            .iter()
+            .zip(&nonces)
+            .map(|(p, r_i)| (p.id, compute_nonce_point(r_i)))
+            .collect::<Vec<_>>(),
+        &signer_set,
+    )
+    .unwrap();
+    let c = compute_challenge(&R, &X, b"grown");
+    let partials = signers
+        .iter()
+        .zip(&nonces)
+        .map(|(p, r_i)| partial_sign(p, r_i, &c))
+        .collect::<Vec<_>>();
+    let signature = finalize_signature_with_signers(&partials, R, &signer_set).unwrap();
+    assert!(signature.verify_with_config(
+        b"grown",
+        &X,
+        &SigningConfig::unframed().legacy_challenge()
+    ));
+}
+
+#[test]
+fn test_enroll_rejects_held_id() {
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let helper = &keygen_output.participants[0];
+    let enrolled = [1, 2, 3];
+    assert_eq!(
+        enroll_round1(helper, &[1, 3], 2, &enrolled).unwrap_err(),
+        Error::DuplicateParticipantId(2)
+    );
+    assert_eq!(
+        enroll_round2(1, &[1, 3], 2, &enrolled, &[]).unwrap_err(),
+        Error::DuplicateParticipantId(2)
+    );
+    assert_eq!(
+        enroll_finalize(2, &[1, 3], &[], &keygen_output.commitments, &enrolled).unwrap_err(),
+        Error::DuplicateParticipantId(2)
+    );
+    // repair at the same id is fine
+    assert!(repair_round1(helper, &[1, 3], 2).is_ok());
+}
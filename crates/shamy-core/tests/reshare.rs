@@ -0,0 +1,170 @@
+#![allow(non_snake_case)]
+
+use k256::ProjectivePoint;
+use shamy_core::Error;
+use shamy_core::dkg::DkgOutput;
+use shamy_core::reshare::*;
+use shamy_core::schnorr::*;
+use shamy_core::shamir::*;
+use shamy_core::threshold::*;
+
+/// run the rounds with `dealers` of `keygen_output` for a t'-of-`new_ids` group.
+fn reshare(
+    keygen_output: &KeygenOutput,
+    dealers: &[u64],
+    new_threshold: usize,
+    new_ids: &[u64],
+) -> Vec<DkgOutput> {
+    let (commitments, packages): (Vec<_>, Vec<_>) = keygen_output
+        .participants
+        .iter()
+        .filter(|p| dealers.contains(&p.id))
+        .map(|p| reshare_round1(p, dealers, new_threshold, new_ids).unwrap())
+        .unzip();
+    let packages = packages.concat();
+
+    new_ids
+        .iter()
+        .map(|&id| {
+            reshare_finalize(
+                id,
+                dealers,
+                new_threshold,
+                new_ids,
+                &keygen_output.commitments,
+                &commitments,
+                &packages,
+            )
+            .unwrap()
+        })
+        .collect()
+}
+
+fn sign(signers: &[Participant], X: &ProjectivePoint, msg: &[u8]) -> SchnorrSignature {
+    let ids = signers.iter().map(|p| p.id).collect::<Vec<_>>();
+    let signer_set = SignerSet::new(&ids, ids.len()).unwrap();
+    let nonces = signers.iter().map(|_| generate_nonce()).collect::<Vec<_>>();
+    let R = aggregate_nonce_with_signers(
+        &signers
+            .iter()
+            .zip(&nonces)
+            .map(|(p, r_i)| (p.id, compute_nonce_point(r_i)))
+            .collect::<Vec<_>>(),
+        &signer_set,
+    )
+    .unwrap();
+    let c = compute_challenge(&R, X, msg);
+    let partials = signers
+        .iter()
+        .zip(&nonces)
+        .map(|(p, r_i)| partial_sign(p, r_i, &c))
+        .collect::<Vec<_>>();
+    finalize_signature_with_signers(&partials, R, &signer_set).unwrap()
+}
+
+#[test]
+fn test_reshare_raises_threshold() {
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let X = keygen_output.public_key;
+    let outputs = reshare(&keygen_output, &[1, 3], 3, &[1, 2, 3, 4, 5]);
+
+    for output in &outputs {
+        assert_eq!(output.public_key, X);
+        assert_eq!(output.commitments.len(), 3);
+        assert_eq!(output.commitments, outputs[0].commitments);
+    }
+    let new = outputs.iter().map(|o| o.participant).collect::<Vec<_>>();
+    assert!(
+        sign(&[new[0], new[3], new[4]], &X, b"3-of-5").verify_with_config(
+            b"3-of-5",
+            &X,
+            &SigningConfig::unframed().legacy_challenge()
+        )
+    );
+    assert!(!sign(&[new[0], new[3]], &X, b"3-of-5").verify_with_config(
+        b"3-of-5",
+        &X,
+        &SigningConfig::unframed().legacy_challenge()
+    ));
+}
+
+#[test]
+fn test_reshare_lowers_threshold() {
+    let keygen_output = shamir_keygen(5, 3).unwrap();
+    let X = keygen_output.public_key;
+    let outputs = reshare(&keygen_output, &[2, 4, 5], 2, &[10, 20, 30, 40]);
+
+    let new = outputs.iter().map(|o| o.participant).collect::<Vec<_>>();
+    assert_eq!(
+        new.iter().map(|p| p.id).collect::<Vec<_>>(),
+        [10, 20, 30, 40]
+    );
+    assert!(sign(&[new[1], new[3]], &X, b"2-of-4").verify_with_config(
+        b"2-of-4",
+        &X,
+        &SigningConfig::unframed().legacy_challenge()
+    ));
+
+    // old shares don't combine with new ones
+    let mixed = [keygen_output.participants[0], new[1]];
+    assert!(!sign(&mixed, &X, b"mixed").verify_with_config(
+        b"mixed",
+        &X,
+        &SigningConfig::unframed().legacy_challenge()
+    ));
+}
+
+#[test]
+fn test_reshare_rejects_cheating_dealer() {
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let dealers = [1, 2];
+    let new_ids = [1, 2, 3];
+    let [p1, p2] = [keygen_output.participants[0], keygen_output.participants[1]];
+    let (c1, packages1) = reshare_round1(&p1, &dealers, 2, &new_ids).unwrap();
+
+    // dealer 2 reshares a secret of its own choosing instead of its share
+    let impostor = Participant::from_secret(2, generate_nonce());
+    let (c2, packages2) = reshare_round1(&impostor, &dealers, 2, &new_ids).unwrap();
+    let finalize = |commitments: &[ReshareCommitment], packages: &[ResharePackage]| {
+        reshare_finalize(
+            3,
+            &dealers,
+            2,
+            &new_ids,
+            &keygen_output.commitments,
+            commitments,
+            packages,
+        )
+        .map(|output| output.participant)
+    };
+    let packages = [packages1.clone(), packages2].concat();
+    assert_eq!(
+        finalize(&[c1.clone(), c2], &packages).unwrap_err(),
+        Error::InvalidShare { id: 2 }
+    );
+
+    // honest dealer 2, but a package was lost or doubled
+    let (c2, packages2) = reshare_round1(&p2, &dealers, 2, &new_ids).unwrap();
+    let commitments = [c1, c2];
+    assert_eq!(
+        finalize(&commitments, &packages1).unwrap_err(),
+        Error::MissingPackage { round: 1, id: 2 }
+    );
+    let doubled = [packages1.clone(), packages2.clone(), packages2.clone()].concat();
+    assert_eq!(
+        finalize(&commitments, &doubled).unwrap_err(),
+        Error::DuplicatePackage { round: 1, id: 2 }
+    );
+    assert!(finalize(&commitments, &[packages1, packages2].concat()).is_ok());
+
+    // fewer dealers than the old threshold
+    assert_eq!(
+        reshare_finalize(3, &[1], 2, &new_ids, &keygen_output.commitments, &[], &[])
+            .map(|o| o.participant)
+            .unwrap_err(),
+        Error::NotEnoughShares {
+            threshold: 2,
+            actual: 1
+        }
+    );
+}
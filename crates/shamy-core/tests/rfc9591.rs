@@ -0,0 +1,197 @@
+#![allow(non_snake_case)]
+
+use k256::{ProjectivePoint, Scalar};
+use shamy_core::Error;
+use shamy_core::rfc9591::*;
+use shamy_core::shamir::*;
+use shamy_core::threshold::*;
+
+fn scalar(hex: &str) -> Scalar {
+    deserialize_scalar(&hex::decode(hex).unwrap()).unwrap()
+}
+
+fn bytes(hex: &str) -> [u8; 32] {
+    hex::decode(hex).unwrap().try_into().unwrap()
+}
+
+// RFC 9591, appendix E.5: FROST(secp256k1, SHA-256), signers 1 and 3 of 2-of-3
+#[test]
+fn test_rfc9591_vectors() {
+    let secret = scalar("0d004150d27c3bf2a42f312683d35fac7394b1e9e318249c1bfe7f0795a83114");
+    let a_1 = scalar("fbf85eadae3058ea14f19148bb72b45e4399c0b16028acaf0395c9b03c823579");
+    let X = ProjectivePoint::GENERATOR * secret;
+    let message = hex::decode("74657374").unwrap();
+    assert_eq!(
+        hex::encode(serialize_element(&X).unwrap()),
+        "02f37c34b66ced1fb51c34a90bdae006901f10625cc06c4f64663b0eae87d87b4f"
+    );
+
+    let p_1 = Participant::from_secret(1, secret + a_1);
+    let p_3 = Participant::from_secret(3, secret + a_1 * Scalar::from(3u64));
+    assert_eq!(
+        hex::encode(serialize_scalar(&p_1.x_i)),
+        "08f89ffe80ac94dcb920c26f3f46140bfc7f95b493f8310f5fc1ea2b01f4254c"
+    );
+    assert_eq!(
+        hex::encode(serialize_scalar(&p_3.x_i)),
+        "00e95d59dd0d46b0e303e500b62b7ccb0e555d49f5b849f5e748c071da8c0dbc"
+    );
+
+    let nonces_1 = SigningNonces::from_randomness(
+        &bytes("7ea5ed09af19f6ff21040c07ec2d2adbd35b759da5a401d4c99dd26b82391cb2"),
+        &bytes("47acab018f116020c10cb9b9abdc7ac10aae1b48ca6e36dc15acb6ec9be5cdc5"),
+        &p_1.x_i,
+    );
+    let nonces_3 = SigningNonces::from_randomness(
+        &bytes("e6cc56ccbd0502b3f6f831d91e2ebd01c4de0479e0191b66895a4ffd9b68d544"),
+        &bytes("7203d55eb82a5ca0d7d83674541ab55f6e76f1b85391d2c13706a89a064fd5b9"),
+        &p_3.x_i,
+    );
+    let commitments = [nonces_1.commitments(1), nonces_3.commitments(3)];
+    assert_eq!(
+        hex::encode(commitments[0].to_bytes().unwrap()),
+        "03c699af97d26bb4d3f05232ec5e1938c12f1e6ae97643c8f8f11c9820303f1904\
+         02fa2aaccd51b948c9dc1a325d77226e98a5a3fe65fe9ba213761a60123040a45e"
+    );
+    assert_eq!(
+        hex::encode(commitments[1].to_bytes().unwrap()),
+        "03077507ba327fc074d2793955ef3410ee3f03b82b4cdc2370f71d865beb926ef6\
+         02ad53031ddfbbacfc5fbda3d3b0c2445c8e3e99cbc4ca2db2aa283fa68525b135"
+    );
+
+    let factors = binding_factors(&commitments, &X, &message).unwrap();
+    assert_eq!(
+        hex::encode(serialize_scalar(&factors[0].1)),
+        "3e08fe561e075c653cbfd46908a10e7637c70c74f0a77d5fd45d1a750c739ec6"
+    );
+    assert_eq!(
+        hex::encode(serialize_scalar(&factors[1].1)),
+        "93f79041bb3fd266105be251adaeb5fd7f8b104fb554a4ba9a0becea48ddbfd7"
+    );
+
+    let share_1 = sign(&p_1, nonces_1, &commitments, &X, &message).unwrap();
+    let share_3 = sign(&p_3, nonces_3, &commitments, &X, &message).unwrap();
+    assert_eq!(
+        hex::encode(serialize_scalar(&share_1.z)),
+        "c4fce1775a1e141fb579944166eab0d65eefe7b98d480a569bbbfcb14f91c197"
+    );
+    assert!(verify_signature_share(
+        &share_3,
+        &p_3.X_i,
+        &commitments,
+        &X,
+        &message
+    ));
+
+    let public_shares = [(1, p_1.X_i), (3, p_3.X_i)];
+    let signature = aggregate(
+        &commitments,
+        &[share_1, share_3],
+        &public_shares,
+        &X,
+        &message,
+    )
+    .unwrap();
+    assert_eq!(
+        hex::encode(signature.to_bytes().unwrap()),
+        "0205b6d04d3774c8929413e3c76024d54149c372d57aae62574ed74319b5ea14d0\
+         c65dde8492a7471437e6c2fe3da49b90d23f642b5c6dbe7e36089f096dd97324"
+    );
+    assert!(verify(&signature, &X, &message));
+}
+
+#[test]
+fn test_rfc9591_signing_with_shamy_shares() {
+    let keygen_output = shamir_keygen(5, 3).unwrap();
+    let X = keygen_output.public_key;
+    let signers = &keygen_output.participants[1..4];
+    let msg = b"mixed quorum";
+
+    let (nonces, commitments): (Vec<_>, Vec<_>) = signers.iter().map(commit).unzip();
+    let shares = signers
+        .iter()
+        .zip(nonces)
+        .map(|(p, nonces)| sign(p, nonces, &commitments, &X, msg).unwrap())
+        .collect::<Vec<_>>();
+    let public_shares = keygen_output
+        .participants
+        .iter()
+        .map(|p| (p.id, p.X_i))
+        .collect::<Vec<_>>();
+
+    let signature = aggregate(&commitments, &shares, &public_shares, &X, msg).unwrap();
+    assert!(verify(&signature, &X, msg));
+    assert!(!verify(&signature, &X, b"another message"));
+
+    // a bad share names its signer
+    let mut tampered = shares.clone();
+    tampered[1].z += Scalar::ONE;
+    match aggregate(&commitments, &tampered, &public_shares, &X, msg) {
+        Err(Error::Misbehavior(report)) => assert_eq!(report.ids(), vec![signers[1].id]),
+        other => panic!("expected misbehavior, got {other:?}"),
+    }
+
+    // one share per commitment
+    assert!(aggregate(&commitments, &shares[..2], &public_shares, &X, msg).is_err());
+}
+
+#[test]
+fn test_rfc9591_sign_needs_own_commitment() {
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let X = keygen_output.public_key;
+    let (p_1, p_2) = (
+        &keygen_output.participants[0],
+        &keygen_output.participants[1],
+    );
+
+    let (nonces_1, _) = commit(p_1);
+    let (_, stale) = commit(p_1);
+    let (_, commitments_2) = commit(p_2);
+    assert!(matches!(
+        sign(p_1, nonces_1, &[stale, commitments_2], &X, b"msg"),
+        Err(Error::MissingPackage { round: 1, id: 1 })
+    ));
+}
+
+#[test]
+fn test_rfc9591_key_package() {
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let X = keygen_output.public_key;
+    let participant = &keygen_output.participants[2];
+
+    let package = KeyPackage::new(participant, &X, 2).unwrap();
+    assert_eq!(package.identifier, serialize_identifier(3));
+    assert_eq!(package.min_signers, 2);
+    assert_eq!(package.public_key().unwrap(), X);
+    let restored = package.participant().unwrap();
+    assert_eq!(
+        (restored.id, restored.x_i),
+        (participant.id, participant.x_i)
+    );
+
+    let mut mismatched = package.clone();
+    mismatched.verifying_share = KeyPackage::new(&keygen_output.participants[0], &X, 2)
+        .unwrap()
+        .verifying_share;
+    assert!(matches!(
+        mismatched.participant(),
+        Err(Error::InvalidShare { id: 3 })
+    ));
+}
+
+#[test]
+fn test_rfc9591_encodings() {
+    assert_eq!(
+        deserialize_identifier(&serialize_identifier(u64::MAX)).unwrap(),
+        u64::MAX
+    );
+    // a derived identifier is a full scalar
+    let mut derived = [0xab; 32];
+    derived[0] = 0x01;
+    assert!(deserialize_identifier(&derived).is_err());
+    assert!(deserialize_identifier(&[0; 32]).is_err());
+
+    assert!(serialize_element(&ProjectivePoint::IDENTITY).is_err());
+    assert!(deserialize_scalar(&[0xff; 32]).is_err());
+    assert!(SigningCommitments::from_bytes(1, &[0x02; 65]).is_err());
+}
@@ -0,0 +1,166 @@
+#![allow(non_snake_case)]
+
+use k256::{ProjectivePoint, Scalar};
+use shamy_core::Error;
+use shamy_core::robust::*;
+use shamy_core::schnorr::*;
+use shamy_core::shamir::*;
+use shamy_core::threshold::*;
+use std::collections::BTreeMap;
+
+fn config() -> SigningConfig {
+    SigningConfig::new("shamy-tests", "robust")
+}
+
+fn setup(n: usize, t: usize) -> (KeygenOutput, RobustSession, BTreeMap<u64, SignerState>) {
+    let keygen_output = shamir_keygen(n, t).unwrap();
+    let public_shares = keygen_output
+        .participants
+        .iter()
+        .map(|p| (p.id, p.X_i))
+        .collect::<Vec<_>>();
+    let session = RobustSession::new(
+        keygen_output.public_key,
+        b"robust",
+        t,
+        &public_shares,
+        config(),
+    )
+    .unwrap();
+    let signers = keygen_output
+        .participants
+        .iter()
+        .map(|p| (p.id, SignerState::new(*p)))
+        .collect();
+
+    (keygen_output, session, signers)
+}
+
+/// fresh signer state and nonce for `id`, as a signer does after answering.
+fn offer(
+    session: &mut RobustSession,
+    signers: &mut BTreeMap<u64, SignerState>,
+    keygen_output: &KeygenOutput,
+    id: u64,
+) -> Option<SigningAttempt> {
+    let participant = keygen_output.participants[id as usize - 1];
+    let signer = signers.entry(id).or_insert(SignerState::new(participant));
+    *signer = SignerState::new(participant);
+    let R_i = signer.commit().unwrap();
+    session.add_nonce(id, R_i).unwrap()
+}
+
+#[test]
+fn test_robust_session_survives_non_responder() {
+    let (keygen_output, mut session, mut signers) = setup(5, 3);
+
+    // four signers offer nonces, the first three form attempt 0
+    let mut attempt = None;
+    for id in 1..=4 {
+        attempt = offer(&mut session, &mut signers, &keygen_output, id).or(attempt);
+    }
+    let first = attempt.unwrap();
+    assert_eq!(first.signer_ids, [1, 2, 3]);
+    assert_eq!(session.phase(), SessionPhase::CollectingPartials);
+
+    // 2 never answers, 1 and 3 answer and offer fresh nonces
+    for id in [1, 3] {
+        let partial = signers.get_mut(&id).unwrap().sign(&first.c).unwrap();
+        assert_eq!(session.add_partial(first.attempt, partial).unwrap(), None);
+    }
+    assert_eq!(session.pending(), [2]);
+    assert_eq!(offer(&mut session, &mut signers, &keygen_output, 1), None);
+    let next = offer(&mut session, &mut signers, &keygen_output, 3);
+
+    let second = next.unwrap();
+    assert_eq!(second.signer_ids, [4, 1, 3]);
+    assert_ne!(second.c, first.c);
+    let mut signature = None;
+    for id in &second.signer_ids {
+        let partial = signers.get_mut(id).unwrap().sign(&second.c).unwrap();
+        signature = session.add_partial(second.attempt, partial).unwrap();
+    }
+
+    let signature = signature.unwrap();
+    assert_eq!(session.phase(), SessionPhase::Finalized);
+    assert_eq!(session.signature(), Some(signature));
+    assert!(signature.verify_with_config(b"robust", &keygen_output.public_key, &config()));
+    assert!(session.add_nonce(5, ProjectivePoint::GENERATOR).is_err());
+}
+
+#[test]
+fn test_robust_session_excludes_bad_signer() {
+    let (keygen_output, mut session, mut signers) = setup(4, 2);
+    offer(&mut session, &mut signers, &keygen_output, 1);
+    let attempt = offer(&mut session, &mut signers, &keygen_output, 2).unwrap();
+
+    let mut partial = signers.get_mut(&2).unwrap().sign(&attempt.c).unwrap();
+    partial.s_i += Scalar::ONE;
+    assert_eq!(
+        session.add_partial(attempt.attempt, partial),
+        Err(Error::Misbehavior(CulpritReport {
+            culprits: vec![(2, Misbehavior::InvalidPartial)],
+        }))
+    );
+    assert_eq!(session.excluded(), [2]);
+    assert_eq!(
+        offer_result(&mut session, 2),
+        Err(Error::UnexpectedPackage { round: 1, id: 2 })
+    );
+
+    let partial = signers.get_mut(&1).unwrap().sign(&attempt.c).unwrap();
+    session.add_partial(attempt.attempt, partial).unwrap();
+    offer(&mut session, &mut signers, &keygen_output, 1);
+    let retry = offer(&mut session, &mut signers, &keygen_output, 3).unwrap();
+    assert_eq!(retry.signer_ids, [1, 3]);
+    for id in [1, 3] {
+        let partial = signers.get_mut(&id).unwrap().sign(&retry.c).unwrap();
+        session.add_partial(retry.attempt, partial).unwrap();
+    }
+    assert!(session.signature().is_some());
+}
+
+/// a nonce from `id` without a signer behind it.
+fn offer_result(session: &mut RobustSession, id: u64) -> Result<Option<SigningAttempt>, Error> {
+    session.add_nonce(id, compute_nonce_point(&generate_nonce()))
+}
+
+#[test]
+fn test_robust_session_rejects_unexpected_messages() {
+    let (keygen_output, mut session, mut signers) = setup(3, 2);
+    let public_shares = [(1, keygen_output.participants[0].X_i)];
+    assert_eq!(
+        RobustSession::new(keygen_output.public_key, b"m", 2, &public_shares, config()).err(),
+        Some(Error::NotEnoughShares {
+            threshold: 2,
+            actual: 1,
+        })
+    );
+
+    offer(&mut session, &mut signers, &keygen_output, 1);
+    assert_eq!(
+        offer_result(&mut session, 1),
+        Err(Error::DuplicatePackage { round: 1, id: 1 })
+    );
+    assert_eq!(
+        offer_result(&mut session, 9),
+        Err(Error::UnexpectedPackage { round: 1, id: 9 })
+    );
+
+    let attempt = offer(&mut session, &mut signers, &keygen_output, 3).unwrap();
+    let partial = signers.get_mut(&3).unwrap().sign(&attempt.c).unwrap();
+    assert_eq!(
+        session.add_partial(7, partial),
+        Err(Error::UnexpectedPackage { round: 2, id: 3 })
+    );
+    session.add_partial(attempt.attempt, partial).unwrap();
+    assert_eq!(
+        session.add_partial(attempt.attempt, partial),
+        Err(Error::DuplicatePackage { round: 2, id: 3 })
+    );
+    let stranger = PartialSignature::new(2, Scalar::ONE);
+    assert_eq!(
+        session.add_partial(attempt.attempt, stranger),
+        Err(Error::UnexpectedPackage { round: 2, id: 2 })
+    );
+}
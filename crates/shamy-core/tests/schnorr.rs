@@ -0,0 +1,527 @@
+#![allow(non_snake_case)]
+
+use k256::{ProjectivePoint, Scalar};
+use shamy_core::Error;
+use shamy_core::hazmat::lagrange_coefficient;
+use shamy_core::schnorr::*;
+use shamy_core::shamir::*;
+use shamy_core::threshold::*;
+use shamy_core::util::PointEncoding;
+
+#[test]
+fn test_invalid_signature_wrong_message() {
+    let n = 3;
+    let t = 3;
+    let keygen_output = shamir_keygen(n, t).unwrap();
+
+    let correct_msg = b"Correct message";
+    let tampered_msg = b"Wrong message";
+    let ids: Vec<u64> = keygen_output.participants.iter().map(|p| p.id).collect();
+    let signer_set = SignerSet::new(&ids, t).unwrap();
+
+    let nonce_pairs = keygen_output
+        .participants
+        .iter()
+        .map(|p| {
+            let r_i = generate_nonce();
+            let R_i = compute_nonce_point(&r_i);
+            (p, r_i, R_i)
+        })
+        .collect::<Vec<_>>();
+
+    let nonces = nonce_pairs
+        .clone()
+        .into_iter()
+        .map(|(p, _, R_i)| (p.id, R_i))
+        .collect::<Vec<_>>();
+    let R = aggregate_nonce_with_signers(nonces.as_slice(), &signer_set).unwrap();
+
+    let c = compute_challenge(&R, &keygen_output.public_key, correct_msg);
+
+    let partials = nonce_pairs
+        .iter()
+        .map(|(p, r_i, _)| partial_sign(p, r_i, &c))
+        .collect::<Vec<_>>();
+
+    let sig = finalize_signature_with_signers(&partials, R, &signer_set).unwrap();
+    assert!(!sig.verify_with_config(
+        tampered_msg,
+        &keygen_output.public_key,
+        &SigningConfig::unframed().legacy_challenge()
+    ));
+}
+
+#[test]
+fn test_valid_signature_deterministic() {
+    let n = 4;
+    let t = 4;
+    let keygen_output = shamir_keygen(n, t).unwrap();
+
+    let msg = b"Repeat verification";
+    let ids: Vec<u64> = keygen_output.participants.iter().map(|p| p.id).collect();
+    let signer_set = SignerSet::new(&ids, t).unwrap();
+
+    let nonce_pairs = keygen_output
+        .participants
+        .iter()
+        .map(|p| {
+            let r_i = generate_nonce();
+            let R_i = compute_nonce_point(&r_i);
+            (p, r_i, R_i)
+        })
+        .collect::<Vec<_>>();
+
+    let R = nonce_pairs
+        .iter()
+        .fold(ProjectivePoint::IDENTITY, |acc, (p, _, R_i)| {
+            let lambda = lagrange_coefficient(p.id, &ids);
+            acc + (*R_i * lambda)
+        });
+
+    let c = compute_challenge(&R, &keygen_output.public_key, msg);
+
+    let partials = nonce_pairs
+        .iter()
+        .map(|(p, r_i, _)| partial_sign(p, r_i, &c))
+        .collect::<Vec<_>>();
+
+    let sig = finalize_signature_with_signers(&partials, R, &signer_set).unwrap();
+
+    for _ in 0..50 {
+        assert!(sig.verify_with_config(
+            msg,
+            &keygen_output.public_key,
+            &SigningConfig::unframed().legacy_challenge()
+        ));
+    }
+}
+
+#[test]
+fn test_framed_signature_is_bound_to_config() {
+    let n = 3;
+    let t = 2;
+    let keygen_output = shamir_keygen(n, t).unwrap();
+
+    let msg = b"transfer 1 BTC";
+    let config = SigningConfig::new("treasury", "withdrawal");
+    let signers = &keygen_output.participants[..t];
+    let ids: Vec<u64> = signers.iter().map(|p| p.id).collect();
+    let signer_set = SignerSet::new(&ids, t).unwrap();
+
+    let nonce_pairs = signers
+        .iter()
+        .map(|p| {
+            let r_i = generate_nonce();
+            (p, r_i, compute_nonce_point(&r_i))
+        })
+        .collect::<Vec<_>>();
+    let nonces = nonce_pairs
+        .iter()
+        .map(|(p, _, R_i)| (p.id, *R_i))
+        .collect::<Vec<_>>();
+    let R = aggregate_nonce_with_signers(&nonces, &signer_set).unwrap();
+
+    let c = config.challenge(&R, &keygen_output.public_key, msg);
+
+    let partials = nonce_pairs
+        .iter()
+        .map(|(p, r_i, _)| partial_sign(p, r_i, &c))
+        .collect::<Vec<_>>();
+
+    let sig = finalize_signature_with_signers(&partials, R, &signer_set).unwrap();
+    let X = keygen_output.public_key;
+
+    assert!(sig.verify_with_config(msg, &X, &config));
+    assert!(!sig.verify_with_config(msg, &X, &SigningConfig::unframed().legacy_challenge()));
+    assert!(!sig.verify_with_config(msg, &X, &SigningConfig::unframed()));
+    assert!(!sig.verify_with_config(msg, &X, &SigningConfig::new("treasury", "approval")));
+    assert!(!sig.verify_with_config(msg, &X, &SigningConfig::new("nostr", "withdrawal")));
+}
+
+#[test]
+fn test_framing_is_unambiguous() {
+    let msg = b"payload";
+    let a = SigningConfig::new("ab", "c")
+        .frame_message(msg)
+        .into_owned();
+    let b = SigningConfig::new("a", "bc")
+        .frame_message(msg)
+        .into_owned();
+    assert_ne!(a, b);
+
+    let unframed = SigningConfig::unframed();
+    assert_eq!(unframed.frame_message(msg).as_ref(), msg);
+}
+
+#[test]
+fn test_challenge_is_domain_separated() {
+    let msg = b"payload";
+    let x = generate_nonce();
+    let r = generate_nonce();
+    let X = ProjectivePoint::GENERATOR * x;
+    let R = compute_nonce_point(&r);
+
+    let config = SigningConfig::new("treasury", "withdrawal");
+    let c = config.challenge(&R, &X, msg);
+    let framed = config.frame_message(msg);
+    assert_eq!(c, compute_challenge_v1(&R, &X, &framed, b""));
+    assert_ne!(c, compute_challenge(&R, &X, &framed));
+
+    let sig = SchnorrSignature { R, s: r + c * x };
+    assert!(sig.verify_with_config(msg, &X, &config));
+    assert!(!sig.verify_with_config(msg, &X, &config.clone().legacy_challenge()));
+    assert!(!sig.verify_with_config(msg, &X, &config.clone().with_context(b"app")));
+
+    let with_context = config.clone().with_context(b"app");
+    let c = with_context.challenge(&R, &X, msg);
+    let sig = SchnorrSignature { R, s: r + c * x };
+    assert!(sig.verify_with_config(msg, &X, &with_context));
+    assert!(!sig.verify_with_config(msg, &X, &config));
+}
+
+#[test]
+fn test_challenge_point_encoding() {
+    let msg = b"payload";
+    let x = generate_nonce();
+    let r = generate_nonce();
+    let X = ProjectivePoint::GENERATOR * x;
+    let R = compute_nonce_point(&r);
+
+    let compressed = SigningConfig::new("treasury", "withdrawal");
+    let uncompressed = compressed
+        .clone()
+        .with_point_encoding(PointEncoding::Uncompressed);
+    assert_eq!(compressed.point_encoding(), PointEncoding::Compressed);
+    assert_ne!(
+        compressed.challenge(&R, &X, msg),
+        uncompressed.challenge(&R, &X, msg)
+    );
+
+    let c = uncompressed.challenge(&R, &X, msg);
+    let sig = SchnorrSignature { R, s: r + c * x };
+    assert!(sig.verify_with_config(msg, &X, &uncompressed));
+    assert!(!sig.verify_with_config(msg, &X, &compressed));
+}
+
+#[test]
+fn test_legacy_challenge_verifies_old_signatures() {
+    let msg = b"rust is best";
+    let x = generate_nonce();
+    let r = generate_nonce();
+    let X = ProjectivePoint::GENERATOR * x;
+    let R = compute_nonce_point(&r);
+
+    let c = compute_challenge(&R, &X, msg);
+    let sig = SchnorrSignature { R, s: r + c * x };
+    assert!(sig.verify_with_config(msg, &X, &SigningConfig::unframed().legacy_challenge()));
+
+    let legacy = SigningConfig::unframed().legacy_challenge();
+    assert_eq!(legacy.challenge_mode(), &ChallengeMode::Legacy);
+    assert!(sig.verify_with_config(msg, &X, &legacy));
+    assert!(!sig.verify_with_config(msg, &X, &SigningConfig::unframed()));
+}
+
+#[test]
+fn test_prehashed_challenge() {
+    let digest = prehash(b"a very large payload");
+    let x = generate_nonce();
+    let r = generate_nonce();
+    let X = ProjectivePoint::GENERATOR * x;
+    let R = compute_nonce_point(&r);
+
+    let unframed = SigningConfig::unframed();
+    let c = unframed.challenge_prehashed(&R, &X, &digest);
+    assert_eq!(c, compute_challenge_prehashed(&R, &X, &digest));
+    assert_ne!(c, unframed.challenge(&R, &X, &digest));
+
+    let config = SigningConfig::new("treasury", "withdrawal");
+    let c = config.challenge_prehashed(&R, &X, &digest);
+    let sig = SchnorrSignature { R, s: r + c * x };
+    assert!(sig.verify_prehashed(&digest, &X, &config));
+    assert!(!sig.verify_prehashed(&digest, &X, &unframed));
+    assert!(!sig.verify_prehashed(&prehash(b"another payload"), &X, &config));
+}
+
+#[test]
+fn test_verify_batch() {
+    let config = SigningConfig::new("shamy-tests", "batch");
+    let messages = (0..20).map(|i| format!("message {i}")).collect::<Vec<_>>();
+    let mut batch = messages
+        .iter()
+        .map(|msg| {
+            let x = generate_nonce();
+            let r = generate_nonce();
+            let X = ProjectivePoint::GENERATOR * x;
+            let R = compute_nonce_point(&r);
+            let c = config.challenge(&R, &X, msg.as_bytes());
+            (msg.as_bytes(), X, SchnorrSignature { R, s: r + c * x })
+        })
+        .collect::<Vec<_>>();
+    assert!(verify_batch(&batch, &config));
+    assert!(verify_batch(&[], &config));
+    assert!(!verify_batch(&batch, &SigningConfig::unframed()));
+
+    batch[13].0 = b"forged";
+    assert!(!verify_batch(&batch, &config));
+}
+
+#[test]
+fn test_compact_signature_encoding() {
+    let x = Scalar::from(7u64);
+    let X = ProjectivePoint::GENERATOR * x;
+    let config = SigningConfig::new("shamy-tests", "compact");
+    let sign = |r: Scalar| {
+        let R = compute_nonce_point(&r);
+        SchnorrSignature {
+            R,
+            s: r + config.challenge(&R, &X, b"msg") * x,
+        }
+    };
+
+    // G has even Y, -G odd
+    let even = sign(Scalar::ONE);
+    let odd = sign(-Scalar::ONE);
+
+    for signature in [even, odd] {
+        let bytes = signature.to_bytes().unwrap();
+        assert_eq!(
+            bytes[0],
+            PointEncoding::Compressed.encode(&signature.R).as_bytes()[0]
+        );
+        let decoded = SchnorrSignature::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, signature);
+        assert!(decoded.verify_with_config(b"msg", &X, &config));
+    }
+
+    let x_only = even.to_bytes_x_only().unwrap();
+    assert_eq!(x_only[..], even.to_bytes().unwrap()[1..]);
+    assert_eq!(SchnorrSignature::from_bytes(&x_only).unwrap(), even);
+    assert!(matches!(
+        odd.to_bytes_x_only(),
+        Err(Error::InvalidEncoding(_))
+    ));
+
+    assert!(matches!(
+        SchnorrSignature::from_bytes(&x_only[..63]),
+        Err(Error::InvalidEncoding(_))
+    ));
+    let mut overflow = even.to_bytes().unwrap();
+    overflow[33..].fill(0xff);
+    assert_eq!(
+        SchnorrSignature::from_bytes(&overflow),
+        Err(Error::InvalidScalar)
+    );
+
+    // R at infinity has no compact encoding
+    let infinity = SchnorrSignature {
+        R: ProjectivePoint::IDENTITY,
+        s: Scalar::ONE,
+    };
+    assert_eq!(infinity.to_bytes(), Err(Error::IdentityPoint));
+    assert_eq!(infinity.to_bytes_x_only(), Err(Error::IdentityPoint));
+}
+
+#[test]
+fn test_strict_signature_decoding() {
+    let key_pair = KeyPair::from_secret(Scalar::from(5u64)).unwrap();
+    let X = key_pair.public_key();
+    let config = SigningConfig::new("shamy-tests", "strict");
+    let signature = key_pair.sign(b"msg", &config);
+    let bytes = signature.to_bytes().unwrap();
+    let (R, s) = bytes.split_at(33);
+    assert_eq!(SchnorrSignature::from_parts(R, s), Ok(signature));
+    assert_eq!(
+        SchnorrSignature::from_hex_parts(&hex::encode(R), &hex::encode(s)),
+        Ok(signature)
+    );
+
+    // one encoding per signature: R compressed, s 32 bytes below q
+    let uncompressed = PointEncoding::Uncompressed.encode(&signature.R);
+    assert!(matches!(
+        SchnorrSignature::from_parts(uncompressed.as_bytes(), s),
+        Err(Error::InvalidEncoding(_))
+    ));
+    let q =
+        hex::decode("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141").unwrap();
+    assert_eq!(
+        SchnorrSignature::from_parts(R, &q),
+        Err(Error::InvalidScalar)
+    );
+    assert_eq!(
+        SchnorrSignature::from_parts(R, &[&[0u8][..], s].concat()),
+        Err(Error::InvalidScalarLength(33))
+    );
+    assert_eq!(
+        SchnorrSignature::from_parts(&[0u8; 33], s),
+        Err(Error::InvalidPoint("not a canonical encoding".to_string()))
+    );
+
+    // R at infinity never verifies, even when s*G == R + c*X holds
+    let c = config.challenge(&ProjectivePoint::IDENTITY, &X, b"msg");
+    let degenerate = SchnorrSignature {
+        R: ProjectivePoint::IDENTITY,
+        s: c * Scalar::from(5u64),
+    };
+    assert!(!degenerate.verify_with_config(b"msg", &X, &config));
+    assert_eq!(degenerate.normalize(), Err(Error::IdentityPoint));
+
+    // normalized signatures are equal field by field, like decoded ones
+    let R = ProjectivePoint::GENERATOR + ProjectivePoint::GENERATOR;
+    let signature = SchnorrSignature { R, s: Scalar::ONE };
+    let normalized = signature.normalize().unwrap();
+    assert_eq!(normalized, signature);
+    assert_eq!(
+        format!("{normalized:?}"),
+        format!(
+            "{:?}",
+            SchnorrSignature::from_bytes(&signature.to_bytes().unwrap()).unwrap()
+        )
+    );
+}
+
+#[cfg(feature = "ethereum")]
+#[test]
+fn test_ethereum_challenge() {
+    // hashMessage("Hello World") in ethers.js
+    assert_eq!(
+        hex::encode(eip191_hash(b"Hello World")),
+        "a1de988600a42c4b4ab089b619297c17d53cffae5d5120d82d8a92d0bb3b78f2"
+    );
+
+    // R = G, X = 2G, checked against an independent Keccak-256
+    let G = ProjectivePoint::GENERATOR;
+    let config = SigningConfig::unframed().ethereum_challenge();
+    let c = config.challenge(&G, &G.double(), b"Hello World");
+    assert_eq!(
+        hex::encode(c.to_bytes()),
+        "fec9a85b9997cb841b030e3f503f924b05ce35f3b05a89e45eb1db070c5a70da"
+    );
+    assert_eq!(
+        c,
+        compute_challenge_ethereum(&G, &G.double(), b"Hello World")
+    );
+
+    // threshold signature under the ethereum challenge
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let X = keygen_output.public_key;
+    let signers = &keygen_output.participants[1..];
+    let ids = signers.iter().map(|p| p.id).collect::<Vec<_>>();
+    let signer_set = SignerSet::new(&ids, ids.len()).unwrap();
+    let nonces = signers.iter().map(|_| generate_nonce()).collect::<Vec<_>>();
+    let R = aggregate_nonce_with_signers(
+        &ids.iter()
+            .zip(&nonces)
+            .map(|(id, r_i)| (*id, compute_nonce_point(r_i)))
+            .collect::<Vec<_>>(),
+        &signer_set,
+    )
+    .unwrap();
+    let c = config.challenge(&R, &X, b"mint 1 token");
+    let partials = signers
+        .iter()
+        .zip(&nonces)
+        .map(|(p, r_i)| partial_sign(p, r_i, &c))
+        .collect::<Vec<_>>();
+    let signature = finalize_signature_with_signers(&partials, R, &signer_set).unwrap();
+
+    assert!(signature.verify_with_config(b"mint 1 token", &X, &config));
+    assert!(!signature.verify_with_config(b"mint 1 token", &X, &SigningConfig::unframed()));
+    assert!(!signature.verify_with_config(b"mint 2 token", &X, &config));
+}
+
+#[cfg(feature = "merlin")]
+#[test]
+fn test_merlin_challenge() {
+    let G = ProjectivePoint::GENERATOR;
+    let (R, X) = (G, G.double());
+    let v1 = SigningConfig::new("shamy-tests", "merlin").with_context(b"ctx");
+    let config = v1.clone().merlin_challenge();
+    assert_eq!(
+        config.challenge_mode(),
+        &ChallengeMode::Merlin {
+            context: b"ctx".to_vec()
+        }
+    );
+    assert_eq!(config.clone().with_context(b"other").context(), b"other");
+
+    // a transcript, not the v1 hash, over the same fields
+    let c = config.challenge(&R, &X, b"m");
+    assert_ne!(c, v1.challenge(&R, &X, b"m"));
+    assert_eq!(
+        SigningConfig::unframed()
+            .merlin_challenge()
+            .challenge(&R, &X, b"m"),
+        compute_challenge_merlin(&R, &X, b"m", &[])
+    );
+    assert_ne!(c, config.challenge(&R, &X, b"m'"));
+    assert_ne!(c, config.clone().with_context(b"").challenge(&R, &X, b"m"));
+    assert_ne!(c, config.challenge_prehashed(&R, &X, &prehash(b"m")));
+
+    // session and signer set are bound like in v1
+    assert_eq!(config.check_bindings(), Ok(()));
+    let session = config.clone().with_session(SessionId([7; 32]));
+    assert_eq!(session.check_bindings(), Ok(()));
+    let c_session = session.challenge(&R, &X, b"m");
+    assert_ne!(c, c_session);
+    let bound = session.with_signer_set(&[(1, R), (2, X)]);
+    assert_ne!(c_session, bound.challenge(&R, &X, b"m"));
+
+    // threshold signature under the merlin challenge
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let X = keygen_output.public_key;
+    let signers = &keygen_output.participants[..2];
+    let ids = signers.iter().map(|p| p.id).collect::<Vec<_>>();
+    let signer_set = SignerSet::new(&ids, ids.len()).unwrap();
+    let nonces = signers.iter().map(|_| generate_nonce()).collect::<Vec<_>>();
+    let nonce_points = ids
+        .iter()
+        .zip(&nonces)
+        .map(|(id, r_i)| (*id, compute_nonce_point(r_i)))
+        .collect::<Vec<_>>();
+    let R = aggregate_nonce_with_signers(&nonce_points, &signer_set).unwrap();
+    let config = config.with_signer_set(&nonce_points);
+    let c = config.challenge(&R, &X, b"approve");
+    let partials = signers
+        .iter()
+        .zip(&nonces)
+        .map(|(p, r_i)| partial_sign(p, r_i, &c))
+        .collect::<Vec<_>>();
+    let signature = finalize_signature_with_signers(&partials, R, &signer_set).unwrap();
+
+    assert!(signature.verify_with_config(b"approve", &X, &config));
+    assert!(!signature.verify_with_config(b"approve", &X, &v1));
+    assert!(!signature.verify_with_config(b"approve", &X, &v1.merlin_challenge()));
+}
+
+#[test]
+fn test_key_pair() {
+    let key_pair = KeyPair::generate();
+    let X = key_pair.public_key();
+    let config = SigningConfig::new("shamy-tests", "key-pair");
+
+    let signature = key_pair.sign(b"hello", &config);
+    assert!(signature.verify_with_config(b"hello", &X, &config));
+    assert!(!signature.verify_with_config(b"hullo", &X, &config));
+    assert!(!signature.verify_with_config(b"hello", &KeyPair::generate().public_key(), &config));
+
+    // hedged nonces, signing twice gives two different signatures
+    assert_ne!(key_pair.sign(b"hello", &config), signature);
+
+    let key_pair = KeyPair::from_secret(Scalar::from(3u64)).unwrap();
+    assert_eq!(
+        key_pair.public_key(),
+        ProjectivePoint::GENERATOR * Scalar::from(3u64)
+    );
+    let signature = key_pair.sign(b"legacy", &SigningConfig::unframed().legacy_challenge());
+    assert!(signature.verify_with_config(
+        b"legacy",
+        &key_pair.public_key(),
+        &SigningConfig::unframed().legacy_challenge()
+    ));
+
+    assert_eq!(
+        KeyPair::from_secret(Scalar::ZERO).unwrap_err(),
+        Error::InvalidScalar
+    );
+    assert!(!format!("{key_pair:?}").contains("secret"));
+}
@@ -0,0 +1,508 @@
+#![allow(non_snake_case)]
+
+use k256::ProjectivePoint;
+use shamy_core::Error;
+use shamy_core::hazmat::LagrangeWeights;
+use shamy_core::schnorr::*;
+use shamy_core::shamir::*;
+use shamy_core::threshold::*;
+
+fn config() -> SigningConfig {
+    SigningConfig::new("shamy-tests", "session")
+}
+
+#[test]
+fn test_session_happy_path() {
+    let msg = b"state machine";
+    let keygen_output = shamir_keygen(5, 3).unwrap();
+    let mut signers = keygen_output.participants[1..4]
+        .iter()
+        .map(|p| SignerState::new(*p))
+        .collect::<Vec<_>>();
+    let ids = signers.iter().map(|s| s.id()).collect::<Vec<_>>();
+
+    let mut session = SigningSession::new(keygen_output.public_key, msg, &ids, config()).unwrap();
+    for signer in signers.iter_mut() {
+        assert_eq!(session.phase(), SessionPhase::CollectingNonces);
+        session
+            .add_nonce(signer.id(), signer.commit().unwrap())
+            .unwrap();
+    }
+    assert_eq!(session.phase(), SessionPhase::CollectingPartials);
+
+    let (_, c) = session.challenge().unwrap();
+    for signer in signers.iter_mut() {
+        session.add_partial(signer.sign(&c).unwrap()).unwrap();
+    }
+
+    let signature = session.finalize().unwrap();
+    assert_eq!(session.phase(), SessionPhase::Finalized);
+    assert!(signature.verify_with_config(msg, &keygen_output.public_key, &config()));
+}
+
+#[test]
+fn test_session_prehashed() {
+    let payload = vec![0x5a; 1 << 16];
+    let digest = prehash(&payload);
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let X = keygen_output.public_key;
+    let mut signers = keygen_output.participants[..2]
+        .iter()
+        .map(|p| SignerState::new(*p))
+        .collect::<Vec<_>>();
+
+    let mut session = SigningSession::new_prehashed(X, &digest, &[1, 2], config()).unwrap();
+    for signer in signers.iter_mut() {
+        session
+            .add_nonce(signer.id(), signer.commit().unwrap())
+            .unwrap();
+    }
+    let (R, c) = session.challenge().unwrap();
+    assert_eq!(c, config().challenge_prehashed(&R, &X, &digest));
+    for signer in signers.iter_mut() {
+        session.add_partial(signer.sign(&c).unwrap()).unwrap();
+    }
+
+    let signature = session.finalize().unwrap();
+    assert!(signature.verify_prehashed(&digest, &X, &config()));
+    assert!(!signature.verify_with_config(&digest, &X, &config()));
+    assert!(!signature.verify_with_config(&payload, &X, &config()));
+}
+
+#[test]
+fn test_session_rejects_out_of_order_and_duplicates() {
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let mut signers = keygen_output.participants[..2]
+        .iter()
+        .map(|p| SignerState::new(*p))
+        .collect::<Vec<_>>();
+    let mut session =
+        SigningSession::new(keygen_output.public_key, b"msg", &[1, 2], config()).unwrap();
+
+    assert_eq!(
+        session.challenge(),
+        Err(Error::MissingPackage { round: 1, id: 1 })
+    );
+    assert!(matches!(
+        session.add_partial(PartialSignature::new(1, k256::Scalar::ONE)),
+        Err(Error::OutOfOrder { .. })
+    ));
+    assert!(matches!(session.finalize(), Err(Error::OutOfOrder { .. })));
+
+    let R_1 = signers[0].commit().unwrap();
+    session.add_nonce(1, R_1).unwrap();
+    assert_eq!(
+        session.add_nonce(1, R_1),
+        Err(Error::DuplicatePackage { round: 1, id: 1 })
+    );
+    assert_eq!(
+        session.add_nonce(3, R_1),
+        Err(Error::UnexpectedPackage { round: 1, id: 3 })
+    );
+    session.add_nonce(2, signers[1].commit().unwrap()).unwrap();
+
+    let (_, c) = session.challenge().unwrap();
+    let partial = signers[0].sign(&c).unwrap();
+    session.add_partial(partial).unwrap();
+    assert_eq!(
+        session.add_partial(partial),
+        Err(Error::DuplicatePackage { round: 2, id: 1 })
+    );
+    assert_eq!(
+        session.finalize(),
+        Err(Error::MissingPackage { round: 2, id: 2 })
+    );
+}
+
+#[test]
+fn test_session_keeps_state_on_rejected_nonce() {
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let X = keygen_output.public_key;
+    let mut signers = keygen_output.participants[..2]
+        .iter()
+        .map(|p| SignerState::new(*p))
+        .collect::<Vec<_>>();
+    let mut session = SigningSession::new(X, b"msg", &[1, 2], config()).unwrap();
+
+    // a second nonce doesn't replace the first
+    let R_1 = signers[0].commit().unwrap();
+    session.add_nonce(1, R_1).unwrap();
+    assert_eq!(
+        session.add_nonce(1, ProjectivePoint::GENERATOR),
+        Err(Error::DuplicatePackage { round: 1, id: 1 })
+    );
+    assert_eq!(
+        session.add_nonce(2, ProjectivePoint::IDENTITY),
+        Err(Error::IdentityPoint)
+    );
+
+    // a last nonce that cancels R leaves the session waiting for it
+    let weights = LagrangeWeights::new(&[1, 2]).unwrap();
+    let (lambda_1, lambda_2) = (weights.weight(1).unwrap(), weights.weight(2).unwrap());
+    let cancelling = R_1 * (-lambda_1 * lambda_2.invert().unwrap());
+    assert!(session.add_nonce(2, cancelling).is_err());
+    assert_eq!(session.phase(), SessionPhase::CollectingNonces);
+
+    let R_2 = signers[1].commit().unwrap();
+    session.add_nonce(2, R_2).unwrap();
+    let (R, c) = session.challenge().unwrap();
+    assert_eq!(R, R_1 * lambda_1 + R_2 * lambda_2);
+    for signer in signers.iter_mut() {
+        session.add_partial(signer.sign(&c).unwrap()).unwrap();
+    }
+    assert!(
+        session
+            .finalize()
+            .unwrap()
+            .verify_with_config(b"msg", &X, &config())
+    );
+}
+
+#[test]
+fn test_signer_state_is_single_use() {
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let mut signer = SignerState::new(keygen_output.participants[0]);
+    let c = generate_nonce();
+
+    assert!(matches!(signer.sign(&c), Err(Error::OutOfOrder { .. })));
+    signer.commit().unwrap();
+    assert!(matches!(signer.commit(), Err(Error::OutOfOrder { .. })));
+    signer.sign(&c).unwrap();
+    assert!(matches!(signer.sign(&c), Err(Error::OutOfOrder { .. })));
+}
+
+#[test]
+fn test_session_rejects_invalid_partial_and_signer_set() {
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    assert_eq!(
+        SigningSession::new(keygen_output.public_key, b"msg", &[1, 1], config()).unwrap_err(),
+        Error::DuplicateParticipantId(1)
+    );
+
+    let mut signers = keygen_output.participants[..2]
+        .iter()
+        .map(|p| SignerState::new(*p))
+        .collect::<Vec<_>>();
+    let mut session =
+        SigningSession::new(keygen_output.public_key, b"msg", &[1, 2], config()).unwrap();
+    for signer in signers.iter_mut() {
+        session
+            .add_nonce(signer.id(), signer.commit().unwrap())
+            .unwrap();
+    }
+    let (_, c) = session.challenge().unwrap();
+    session.add_partial(signers[0].sign(&c).unwrap()).unwrap();
+    let mut forged = signers[1].sign(&c).unwrap();
+    forged.s_i += k256::Scalar::ONE;
+    session.add_partial(forged).unwrap();
+
+    assert_eq!(session.finalize(), Err(Error::InvalidSignature));
+}
+
+#[test]
+fn test_aggregation_proof() {
+    let msg = b"governance vote";
+    let keygen_output = shamir_keygen(5, 3).unwrap();
+    let public_shares = keygen_output
+        .participants
+        .iter()
+        .map(|p| (p.id, p.X_i))
+        .collect::<Vec<_>>();
+    let mut signers = [0, 2, 4]
+        .iter()
+        .map(|i| SignerState::new(keygen_output.participants[*i]))
+        .collect::<Vec<_>>();
+    let ids = signers.iter().map(|s| s.id()).collect::<Vec<_>>();
+
+    let mut session = SigningSession::new(keygen_output.public_key, msg, &ids, config()).unwrap();
+    for signer in signers.iter_mut() {
+        session
+            .add_nonce(signer.id(), signer.commit().unwrap())
+            .unwrap();
+    }
+    assert!(matches!(
+        session.aggregation_proof(),
+        Err(Error::OutOfOrder { .. })
+    ));
+    let (_, c) = session.challenge().unwrap();
+    for signer in signers.iter_mut() {
+        session.add_partial(signer.sign(&c).unwrap()).unwrap();
+    }
+    let signature = session.finalize().unwrap();
+
+    let proof = session.aggregation_proof().unwrap();
+    let X = keygen_output.public_key;
+    assert_eq!(proof.signature, signature);
+    assert_eq!(proof.quorum(), [1, 3, 5]);
+    assert_eq!(proof.verify(msg, &X, &public_shares, &config()), Ok(()));
+
+    // claiming a different quorum
+    let mut forged = proof.clone();
+    forged.signers[0].id = 2;
+    assert!(forged.verify(msg, &X, &public_shares, &config()).is_err());
+
+    // swapping nonces between signers
+    let mut forged = proof.clone();
+    forged.signers[0].R_i = proof.signers[1].R_i;
+    assert_eq!(
+        forged.verify(msg, &X, &public_shares, &config()),
+        Err(Error::InvalidPartialSignature { id: 1 })
+    );
+
+    // public shares of another key
+    let other = shamir_keygen(5, 3)
+        .unwrap()
+        .participants
+        .iter()
+        .map(|p| (p.id, p.X_i))
+        .collect::<Vec<_>>();
+    assert!(proof.verify(msg, &X, &other, &config()).is_err());
+
+    assert!(matches!(
+        proof.verify(msg, &X, &public_shares[..2], &config()),
+        Err(Error::InvalidAggregationProof(_))
+    ));
+    assert_eq!(
+        proof.verify(b"other", &X, &public_shares, &config()),
+        Err(Error::InvalidSignature)
+    );
+}
+
+#[test]
+fn test_session_identifies_bad_partials() {
+    let msg = b"state machine";
+    let keygen_output = shamir_keygen(5, 3).unwrap();
+    let public_shares = keygen_output
+        .participants
+        .iter()
+        .map(|p| (p.id, p.X_i))
+        .collect::<Vec<_>>();
+    let mut signers = keygen_output.participants[..3]
+        .iter()
+        .map(|p| SignerState::new(*p))
+        .collect::<Vec<_>>();
+    let ids = signers.iter().map(|s| s.id()).collect::<Vec<_>>();
+
+    let mut session = SigningSession::new(keygen_output.public_key, msg, &ids, config()).unwrap();
+    for signer in signers.iter_mut() {
+        session
+            .add_nonce(signer.id(), signer.commit().unwrap())
+            .unwrap();
+    }
+    let (_, c) = session.challenge().unwrap();
+    for signer in signers.iter_mut() {
+        let mut partial = signer.sign(&c).unwrap();
+        if partial.id != 1 {
+            partial.s_i += k256::Scalar::ONE;
+        }
+        session.add_partial(partial).unwrap();
+    }
+
+    let Err(Error::Misbehavior(report)) = session.finalize_identifiable(&public_shares) else {
+        panic!("bad partials must be blamed");
+    };
+    assert_eq!(report.ids(), vec![2, 3]);
+    assert!(
+        report
+            .culprits
+            .iter()
+            .all(|(_, m)| *m == Misbehavior::InvalidPartial)
+    );
+    assert_eq!(session.phase(), SessionPhase::CollectingPartials);
+}
+
+#[test]
+fn test_identifiable_aggregation_unknown_signer() {
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let public_shares = keygen_output.participants[..2]
+        .iter()
+        .map(|p| (p.id, p.X_i))
+        .collect::<Vec<_>>();
+
+    let nonces = keygen_output
+        .participants
+        .iter()
+        .map(|p| (p, generate_nonce()))
+        .collect::<Vec<_>>();
+    let commitments = nonces
+        .iter()
+        .map(|(p, r_i)| (p.id, compute_nonce_point(r_i)))
+        .collect::<Vec<_>>();
+    let c = compute_challenge(
+        &aggregate_nonce_with_signers(&commitments[..2], &SignerSet::new(&[1, 2], 2).unwrap())
+            .unwrap(),
+        &keygen_output.public_key,
+        b"rust is best",
+    );
+    let partials = nonces
+        .iter()
+        .map(|(p, r_i)| partial_sign(p, r_i, &c))
+        .collect::<Vec<_>>();
+
+    // participant 3 has no public share here, participant 2 no nonce
+    assert_eq!(
+        finalize_signature_identifiable(&partials, &commitments[..1], &public_shares, &c),
+        Err(Error::Misbehavior(CulpritReport {
+            culprits: vec![
+                (2, Misbehavior::MissingNonce),
+                (3, Misbehavior::UnknownSigner)
+            ]
+        }))
+    );
+
+    let signature =
+        finalize_signature_identifiable(&partials[..2], &commitments, &public_shares, &c).unwrap();
+    assert!(signature.verify_with_config(
+        b"rust is best",
+        &keygen_output.public_key,
+        &SigningConfig::unframed().legacy_challenge()
+    ));
+}
+
+#[test]
+fn test_session_with_custom_ids() {
+    let msg = b"custom ids";
+    let keygen_output =
+        shamir_keygen_with_ids(&[1001, 2002, 3003], 2, &shamy_core::Limits::default()).unwrap();
+    let mut signers = [2, 0].map(|i| SignerState::new(keygen_output.participants[i]));
+    let ids = signers.iter().map(|s| s.id()).collect::<Vec<_>>();
+    assert_eq!(ids, [3003, 1001]);
+
+    let mut session = SigningSession::new(keygen_output.public_key, msg, &ids, config()).unwrap();
+    for signer in signers.iter_mut() {
+        session
+            .add_nonce(signer.id(), signer.commit().unwrap())
+            .unwrap();
+    }
+    let (_, c) = session.challenge().unwrap();
+    for signer in signers.iter_mut() {
+        session.add_partial(signer.sign(&c).unwrap()).unwrap();
+    }
+
+    let signature = session.finalize().unwrap();
+    assert!(signature.verify_with_config(msg, &keygen_output.public_key, &config()));
+}
+
+#[test]
+fn test_session_id_prevents_replay() {
+    let msg = b"session ids";
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let public_shares = keygen_output
+        .participants
+        .iter()
+        .map(|p| (p.id, p.X_i))
+        .collect::<Vec<_>>();
+    let (id_a, id_b) = (SessionId::random(), SessionId::random());
+    let mut signers = keygen_output.participants[..2]
+        .iter()
+        .map(|p| SignerState::new(*p))
+        .collect::<Vec<_>>();
+    let nonces = signers
+        .iter_mut()
+        .map(|s| (s.id(), s.commit().unwrap()))
+        .collect::<Vec<_>>();
+
+    // the same nonces in two sessions give two challenges
+    let start = |id: SessionId| {
+        let config = config().with_session(id);
+        let mut session =
+            SigningSession::new(keygen_output.public_key, msg, &[1, 2], config).unwrap();
+        for (id, R_i) in &nonces {
+            session.add_nonce(*id, *R_i).unwrap();
+        }
+        session
+    };
+    let mut session_a = start(id_a);
+    let mut session_b = start(id_b);
+    assert_eq!(session_a.session_id(), Some(id_a));
+    let (_, c_a) = session_a.challenge().unwrap();
+    assert_ne!(c_a, session_b.challenge().unwrap().1);
+
+    // partials of session a don't fit session b
+    let partials = signers
+        .iter_mut()
+        .map(|s| s.sign(&c_a).unwrap())
+        .collect::<Vec<_>>();
+    for partial in &partials {
+        session_a.add_partial(*partial).unwrap();
+        session_b.add_partial(*partial).unwrap();
+    }
+    assert!(matches!(
+        session_b.finalize_identifiable(&public_shares),
+        Err(Error::Misbehavior(_))
+    ));
+    let signature = session_a.finalize().unwrap();
+    assert!(signature.verify_with_config(
+        msg,
+        &keygen_output.public_key,
+        &config().with_session(id_a)
+    ));
+    assert!(!signature.verify_with_config(msg, &keygen_output.public_key, &config()));
+}
+
+#[test]
+fn test_session_id_needs_v1_challenge() {
+    let session = SessionId::random();
+    assert_eq!(SessionId::from_hex(&session.to_hex()), Ok(session));
+    assert!(SessionId::from_hex("00ff").is_err());
+
+    let config = config().with_session(session).legacy_challenge();
+    assert!(matches!(
+        SigningSession::new(ProjectivePoint::GENERATOR, b"m", &[1, 2], config),
+        Err(Error::UnsupportedChallenge)
+    ));
+}
+
+#[test]
+fn test_session_binds_signer_set() {
+    let msg = b"quorum";
+    let keygen_output = shamir_keygen(5, 3).unwrap();
+    let public_shares = keygen_output
+        .participants
+        .iter()
+        .map(|p| (p.id, p.X_i))
+        .collect::<Vec<_>>();
+    let mut signers = keygen_output.participants[1..4]
+        .iter()
+        .map(|p| SignerState::new(*p))
+        .collect::<Vec<_>>();
+
+    let mut session = SigningSession::new(keygen_output.public_key, msg, &[2, 3, 4], config())
+        .unwrap()
+        .bind_signers()
+        .unwrap();
+    for signer in signers.iter_mut() {
+        session
+            .add_nonce(signer.id(), signer.commit().unwrap())
+            .unwrap();
+    }
+    let (_, c) = session.challenge().unwrap();
+    for signer in signers.iter_mut() {
+        session.add_partial(signer.sign(&c).unwrap()).unwrap();
+    }
+    let signature = session.finalize().unwrap();
+
+    let proof = session.aggregation_proof().unwrap();
+    let mut nonces = proof.nonces();
+    let bound = config().with_signer_set(&nonces);
+    assert!(signature.verify_with_config(msg, &keygen_output.public_key, &bound));
+    assert!(!signature.verify_with_config(msg, &keygen_output.public_key, &config()));
+    assert_eq!(
+        proof.verify(msg, &keygen_output.public_key, &public_shares, &bound),
+        Ok(())
+    );
+
+    // the digest ignores order but not membership or nonces
+    nonces.reverse();
+    assert_eq!(config().with_signer_set(&nonces), bound);
+    assert_ne!(config().with_signer_set(&nonces[..2]), bound);
+    nonces[0].1 = ProjectivePoint::GENERATOR;
+    assert_ne!(config().with_signer_set(&nonces), bound);
+
+    let legacy = config().legacy_challenge();
+    let session = SigningSession::new(keygen_output.public_key, msg, &[2, 3, 4], legacy).unwrap();
+    assert!(matches!(
+        session.bind_signers(),
+        Err(Error::UnsupportedChallenge)
+    ));
+}
@@ -0,0 +1,528 @@
+#![allow(non_snake_case)]
+
+use k256::{
+    ProjectivePoint, Scalar,
+    elliptic_curve::rand_core::{CryptoRng, RngCore},
+};
+use shamy_core::backend::{MemoryBackend, ShareBackend};
+use shamy_core::dkg;
+use shamy_core::hash::tagged_hash;
+use shamy_core::hazmat::random_polynomial_with_rng;
+use shamy_core::schnorr::{
+    SessionId, SigningConfig, compute_challenge, compute_nonce_point, generate_nonce,
+    generate_nonce_with_rng,
+};
+use shamy_core::shamir::*;
+use shamy_core::threshold::{
+    SignerSet, aggregate_nonce_with_signers, finalize_signature_with_signers, partial_sign,
+};
+use shamy_core::util::{has_even_y, pp_to_x_only};
+use shamy_core::vss::verify_share;
+use shamy_core::{Error, Limits};
+
+#[test]
+fn test_reconstruct_secret_from_any_subset() {
+    let keygen_output = shamir_keygen(5, 3).unwrap();
+    let shares = keygen_output
+        .participants
+        .iter()
+        .map(|p| (p.id, p.x_i))
+        .collect::<Vec<_>>();
+
+    for subset in [
+        &shares[..3],
+        &shares[2..],
+        &[shares[4], shares[0], shares[2]],
+    ] {
+        let secret = reconstruct_secret(subset).unwrap();
+        assert_eq!(
+            ProjectivePoint::GENERATOR * secret,
+            keygen_output.public_key
+        );
+    }
+
+    // below the threshold the result is garbage
+    let secret = reconstruct_secret(&shares[..2]).unwrap();
+    assert_ne!(
+        ProjectivePoint::GENERATOR * secret,
+        keygen_output.public_key
+    );
+}
+
+#[test]
+fn test_reconstruct_secret_rejects_bad_ids() {
+    let x = Scalar::ONE;
+    assert_eq!(reconstruct_secret(&[]), Err(Error::NoShares));
+    assert_eq!(
+        reconstruct_secret(&[(1, x), (2, x), (1, x)]),
+        Err(Error::DuplicateParticipantId(1))
+    );
+    assert_eq!(
+        reconstruct_secret(&[(0, x), (1, x)]),
+        Err(Error::InvalidParticipantId {
+            id: 0,
+            num_shares: 2
+        })
+    );
+}
+
+#[test]
+fn test_interpolate_at_recovers_any_share() {
+    let keygen_output = shamir_keygen(5, 3).unwrap();
+    let shares = keygen_output
+        .participants
+        .iter()
+        .map(|p| (p.id, p.x_i))
+        .collect::<Vec<_>>();
+
+    // shares 1, 4 and 5 determine f, so they give 2, 3 and a new id 9
+    let helpers = [shares[0], shares[3], shares[4]];
+    for (id, x_i) in &shares[1..3] {
+        assert_eq!(interpolate_at(*id, &helpers).unwrap(), *x_i);
+    }
+    let x_9 = interpolate_at(9, &helpers).unwrap();
+    assert!(verify_share(9, x_9, &keygen_output.commitments));
+    assert_eq!(interpolate_at(9, &shares[..3]).unwrap(), x_9);
+    assert_eq!(
+        interpolate_at(0, &helpers).unwrap(),
+        reconstruct_secret(&helpers).unwrap()
+    );
+    // at one of the points, its own value
+    assert_eq!(interpolate_at(4, &helpers).unwrap(), shares[3].1);
+
+    assert_eq!(
+        interpolate_at(9, &[(2, Scalar::ONE), (2, Scalar::ONE)]),
+        Err(Error::DuplicateParticipantId(2))
+    );
+}
+
+#[test]
+fn test_keygen_reports_bad_parameters() {
+    assert_eq!(shamir_keygen(0, 2).err(), Some(Error::NoParticipants));
+    assert_eq!(
+        shamir_keygen(3, 4).err(),
+        Some(Error::ThresholdExceedsParticipants {
+            threshold: 4,
+            num_shares: 3
+        })
+    );
+    assert!(matches!(
+        shamir_keygen(Limits::DEFAULT_MAX_PARTICIPANTS + 1, 2),
+        Err(Error::TooManyParticipants { .. })
+    ));
+}
+
+#[test]
+fn test_keygen_limits() {
+    let limits = Limits::default()
+        .with_max_participants(10)
+        .with_max_threshold(4);
+    assert!(shamir_keygen_with_limits(10, 4, &limits).is_ok());
+    assert!(matches!(
+        shamir_keygen_with_limits(10_000_000, 3, &limits),
+        Err(Error::TooManyParticipants {
+            num_shares: 10_000_000,
+            max: 10
+        })
+    ));
+    assert!(matches!(
+        shamir_keygen_with_limits(10, 5, &limits),
+        Err(Error::ThresholdTooLarge {
+            threshold: 5,
+            max: 4
+        })
+    ));
+    assert_eq!(
+        shamir_keygen_with_limits(3, 4, &limits).err(),
+        Some(Error::ThresholdExceedsParticipants {
+            threshold: 4,
+            num_shares: 3
+        })
+    );
+    assert_eq!(
+        shamir_keygen_with_limits(3, 1, &limits).err(),
+        Some(Error::ThresholdTooSmall {
+            threshold: 1,
+            min: 2
+        })
+    );
+    assert_eq!(
+        shamir_keygen_with_limits(0, 2, &limits).err(),
+        Some(Error::NoParticipants)
+    );
+    assert_eq!(
+        Dealer::new(0, 2, &limits).err().map(|e| e.to_string()),
+        Some("No participants: the number of shares must be at least 1".to_string())
+    );
+}
+
+#[test]
+fn test_trivial_threshold_is_opt_in() {
+    assert!(matches!(
+        shamir_keygen_with_limits(3, 1, &Limits::default()),
+        Err(Error::ThresholdTooSmall { .. })
+    ));
+    let limits = Limits::default().with_trivial_threshold(true);
+    assert_eq!(
+        shamir_keygen_with_limits(3, 0, &limits).err(),
+        Some(Error::ThresholdTooSmall {
+            threshold: 0,
+            min: 1
+        })
+    );
+
+    // a constant polynomial: every share is the secret
+    let keygen_output = shamir_keygen_with_limits(3, 1, &limits).unwrap();
+    let X = keygen_output.public_key;
+    assert_eq!(keygen_output.commitments, [X]);
+    for p in &keygen_output.participants {
+        assert_eq!(p.X_i, X);
+        assert!(verify_share(p.id, p.x_i, &keygen_output.commitments));
+    }
+
+    // any one share signs alone, and any larger set still works
+    let msg = b"one of three";
+    for signers in [&[1][..], &[3], &[1, 2, 3]] {
+        let signer_set = SignerSet::new(signers, 1).unwrap();
+        let nonces = signers
+            .iter()
+            .map(|id| (*id, generate_nonce()))
+            .collect::<Vec<_>>();
+        let points = nonces
+            .iter()
+            .map(|(id, r_i)| (*id, compute_nonce_point(r_i)))
+            .collect::<Vec<_>>();
+        let R = aggregate_nonce_with_signers(&points, &signer_set).unwrap();
+        let c = compute_challenge(&R, &X, msg);
+        let partials = nonces
+            .iter()
+            .map(|(id, r_i)| partial_sign(&keygen_output.participants[*id as usize - 1], r_i, &c))
+            .collect::<Vec<_>>();
+        assert!(
+            finalize_signature_with_signers(&partials, R, &signer_set)
+                .unwrap()
+                .verify_with_config(msg, &X, &SigningConfig::unframed().legacy_challenge())
+        );
+    }
+}
+
+#[test]
+fn test_dealer_streams_big_committees() {
+    let dealer = Dealer::new(u64::MAX, 3, &Limits::default()).unwrap();
+    let shares = dealer
+        .shares()
+        .take(3)
+        .map(|p| (p.id, p.x_i))
+        .collect::<Vec<_>>();
+
+    let secret = reconstruct_secret(&shares).unwrap();
+    assert_eq!(ProjectivePoint::GENERATOR * secret, dealer.public_key());
+
+    let last = dealer.share(u64::MAX).unwrap();
+    assert!(shamy_core::vss::verify_share(
+        last.id,
+        last.x_i,
+        dealer.commitments()
+    ));
+    assert!(dealer.share(0).is_err());
+}
+
+/// SHA-256 in counter mode, a stand-in for a seeded DRBG or a hardware RNG.
+struct TestDrbg {
+    seed: [u8; 32],
+    counter: u64,
+}
+
+impl RngCore for TestDrbg {
+    fn next_u32(&mut self) -> u32 {
+        self.next_u64() as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0u8; 8];
+        self.fill_bytes(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(32) {
+            let mut input = self.seed.to_vec();
+            input.extend(self.counter.to_be_bytes());
+            self.counter += 1;
+            let block = tagged_hash(b"shamy/tests/drbg/v1", &input);
+            chunk.copy_from_slice(&block[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(
+        &mut self,
+        dest: &mut [u8],
+    ) -> Result<(), k256::elliptic_curve::rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl CryptoRng for TestDrbg {}
+
+fn drbg(seed: u8) -> TestDrbg {
+    TestDrbg {
+        seed: [seed; 32],
+        counter: 0,
+    }
+}
+
+#[test]
+fn test_injected_rng_is_reproducible() {
+    let keygen = |seed| shamir_keygen_with_rng(5, 3, &Limits::default(), &mut drbg(seed)).unwrap();
+    let (a, b, other) = (keygen(7), keygen(7), keygen(8));
+    assert_eq!(a.public_key, b.public_key);
+    assert_eq!(a.commitments, b.commitments);
+    for (x, y) in a.participants.iter().zip(&b.participants) {
+        assert_eq!((x.id, x.x_i), (y.id, y.x_i));
+    }
+    assert_ne!(a.public_key, other.public_key);
+
+    let secret = Scalar::from(42u64);
+    assert_eq!(
+        random_polynomial_with_rng(secret, 3, &mut drbg(1)),
+        random_polynomial_with_rng(secret, 3, &mut drbg(1))
+    );
+    assert_eq!(
+        generate_nonce_with_rng(&mut drbg(1)),
+        generate_nonce_with_rng(&mut drbg(1))
+    );
+    assert_ne!(
+        generate_nonce_with_rng(&mut drbg(1)),
+        generate_nonce_with_rng(&mut drbg(2))
+    );
+}
+
+#[test]
+fn test_injected_rng_reaches_dkg_and_backends() {
+    let round1 = |seed| dkg::round1_with_rng(1, 2, 3, &Limits::default(), &mut drbg(seed)).unwrap();
+    let ((_, a), (_, b), (_, other)) = (round1(3), round1(3), round1(4));
+    assert_eq!(a.commitments, b.commitments);
+    assert_eq!(a.proof, b.proof);
+    assert_ne!(a.commitments, other.commitments);
+
+    let keygen = shamir_keygen_with_rng(3, 2, &Limits::default(), &mut drbg(5)).unwrap();
+    let commit = |seed| {
+        MemoryBackend::with_rng(keygen.participants[0], drbg(seed))
+            .commit(&SessionId([9; 32]))
+            .unwrap()
+    };
+    assert_eq!(commit(6), commit(6));
+    assert_ne!(commit(6), commit(7));
+}
+
+#[test]
+fn test_keygen_with_custom_ids() {
+    let ids = [7, 1_000_001, u64::MAX, 42];
+    let keygen = shamir_keygen_with_ids(&ids, 3, &Limits::default()).unwrap();
+    assert_eq!(
+        keygen.participants.iter().map(|p| p.id).collect::<Vec<_>>(),
+        ids
+    );
+
+    let shares = keygen
+        .participants
+        .iter()
+        .map(|p| (p.id, p.x_i))
+        .collect::<Vec<_>>();
+    for subset in [&shares[..3], &shares[1..]] {
+        let secret = reconstruct_secret(subset).unwrap();
+        assert_eq!(ProjectivePoint::GENERATOR * secret, keygen.public_key);
+    }
+
+    assert_eq!(
+        shamir_keygen_with_ids(&[3, 9, 3], 2, &Limits::default()).err(),
+        Some(Error::DuplicateParticipantId(3))
+    );
+    assert!(matches!(
+        shamir_keygen_with_ids(&[3, 0, 9], 2, &Limits::default()),
+        Err(Error::InvalidParticipantId { id: 0, .. })
+    ));
+    assert!(matches!(
+        shamir_keygen_with_ids(&[3, 9], 3, &Limits::default()),
+        Err(Error::ThresholdExceedsParticipants { .. })
+    ));
+}
+
+#[test]
+fn test_keygen_output_verify() {
+    let keygen = || shamir_keygen_with_ids(&[3, 9, 27, 81], 3, &Limits::default()).unwrap();
+    assert_eq!(keygen().verify(), Ok(()));
+
+    let mut wrong_key = keygen();
+    wrong_key.public_key = -wrong_key.public_key;
+    assert_eq!(wrong_key.verify(), Err(Error::PublicKeyMismatch));
+
+    let mut wrong_public_share = keygen();
+    wrong_public_share.participants[1].X_i = wrong_public_share.participants[0].X_i;
+    assert_eq!(
+        wrong_public_share.verify(),
+        Err(Error::PublicShareMismatch { id: 9 })
+    );
+
+    // consistent xᵢ and Xᵢ, but off the committed polynomial
+    let mut wrong_share = keygen();
+    let p = &mut wrong_share.participants[2];
+    p.x_i += Scalar::ONE;
+    p.X_i = ProjectivePoint::GENERATOR * p.x_i;
+    assert_eq!(wrong_share.verify(), Err(Error::InvalidShare { id: 27 }));
+
+    let mut duplicate = keygen();
+    duplicate.participants[3] = duplicate.participants[0];
+    assert_eq!(duplicate.verify(), Err(Error::DuplicateParticipantId(3)));
+
+    let mut flipped = keygen();
+    flipped.normalize_even_y();
+    assert_eq!(flipped.verify(), Ok(()));
+}
+
+#[test]
+fn test_keygen_output_bytes_round_trip() {
+    let keygen_output = shamir_keygen_with_ids(&[3, 9, 27, 81], 3, &Limits::default()).unwrap();
+    let bytes = keygen_output.to_bytes();
+    assert_eq!(&bytes[..5], b"SHKG\x01");
+    // header, 3 length prefixes, X, t commitments, 4 records
+    assert_eq!(bytes.len(), 5 + 3 * 4 + 33 + 3 * 33 + 4 * (8 + 32 + 33));
+
+    let decoded = KeygenOutput::from_bytes(&bytes).unwrap();
+    assert_eq!(decoded.public_key, keygen_output.public_key);
+    assert_eq!(decoded.commitments, keygen_output.commitments);
+    for (a, b) in decoded.participants.iter().zip(&keygen_output.participants) {
+        assert_eq!((a.id, a.x_i, a.X_i), (b.id, b.x_i, b.X_i));
+    }
+    assert_eq!(decoded.to_bytes(), bytes);
+}
+
+#[test]
+fn test_keygen_output_bytes_rejects_malformed_input() {
+    let bytes = shamir_keygen(3, 2).unwrap().to_bytes();
+    let invalid = |bytes: &[u8]| {
+        matches!(
+            KeygenOutput::from_bytes(bytes),
+            Err(Error::InvalidEncoding(_))
+        )
+    };
+
+    let mut bad_magic = bytes.clone();
+    bad_magic[0] = b'X';
+    assert!(invalid(&bad_magic));
+
+    let mut bad_version = bytes.clone();
+    bad_version[4] = 2;
+    assert!(invalid(&bad_version));
+
+    assert!(invalid(&bytes[..bytes.len() - 1]));
+    assert!(invalid(&bytes[..4]));
+    assert!(invalid(&[bytes.as_slice(), &[0]].concat()));
+
+    // the public key must open the commitments
+    let other = shamir_keygen(3, 2).unwrap().to_bytes();
+    let swapped = [&bytes[..5 + 4 + 33], &other[5 + 4 + 33..]].concat();
+    assert!(invalid(&swapped));
+
+    // duplicate participant ids
+    let mut duplicate = bytes.clone();
+    let first_id = bytes.len() - 3 * 73;
+    duplicate[first_id + 7] = 2;
+    assert!(matches!(
+        KeygenOutput::from_bytes(&duplicate),
+        Err(Error::DuplicateParticipantId(2))
+    ));
+}
+
+#[test]
+fn test_normalize_even_y() {
+    // keep generating until both parities have been seen
+    let mut flipped = [false; 2];
+    while !flipped.iter().all(|f| *f) {
+        let mut keygen_output = shamir_keygen(4, 3).unwrap();
+        let original = keygen_output.public_key;
+        let was_flipped = keygen_output.normalize_even_y();
+        flipped[was_flipped as usize] = true;
+
+        assert!(has_even_y(&keygen_output.public_key));
+        assert_eq!(
+            pp_to_x_only(&keygen_output.public_key),
+            pp_to_x_only(&original)
+        );
+        assert_eq!(keygen_output.public_key, keygen_output.commitments[0]);
+        assert!(!keygen_output.normalize_even_y());
+
+        let shares = keygen_output
+            .participants
+            .iter()
+            .map(|p| (p.id, p.x_i))
+            .collect::<Vec<_>>();
+        let secret = reconstruct_secret(&shares[1..]).unwrap();
+        assert_eq!(
+            ProjectivePoint::GENERATOR * secret,
+            keygen_output.public_key
+        );
+        for participant in &keygen_output.participants {
+            assert_eq!(
+                ProjectivePoint::GENERATOR * participant.x_i,
+                participant.X_i
+            );
+            assert!(verify_share(
+                participant.id,
+                participant.x_i,
+                &keygen_output.commitments
+            ));
+        }
+    }
+}
+
+#[test]
+fn test_encrypted_share_roundtrip() {
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let share = keygen_output.participants[1];
+    let p = Scalar::from(0x5eed_u64);
+    let P = ProjectivePoint::GENERATOR * p;
+
+    let encrypted = encrypt_share_for(&P, &share).unwrap();
+    assert_eq!((encrypted.sender, encrypted.receiver), (0, share.id));
+    assert_ne!(encrypted.body, <[u8; 32]>::from(share.x_i.to_bytes()));
+    assert_eq!(decrypt_share(&p, &encrypted).unwrap(), share);
+
+    let bytes = encrypted.to_bytes();
+    assert_eq!(EncryptedShare::from_bytes(&bytes).unwrap(), encrypted);
+    assert!(EncryptedShare::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    assert!(EncryptedShare::from_bytes(&[bytes.as_slice(), &[0]].concat()).is_err());
+
+    // every ephemeral key is fresh
+    assert_ne!(encrypt_share_for(&P, &share).unwrap().R, encrypted.R);
+    assert!(encrypt_share_for(&ProjectivePoint::IDENTITY, &share).is_err());
+}
+
+#[test]
+fn test_encrypted_share_rejects_wrong_key_and_tampering() {
+    let share = shamir_keygen(3, 2).unwrap().participants[0];
+    let p = Scalar::from(7u64);
+    let encrypted = encrypt_share_for(&(ProjectivePoint::GENERATOR * p), &share).unwrap();
+
+    assert!(matches!(
+        decrypt_share(&Scalar::from(8u64), &encrypted),
+        Err(Error::DecryptionFailed)
+    ));
+
+    let mut redirected = encrypted;
+    redirected.receiver = 2;
+    let mut flipped = encrypted;
+    flipped.body[0] ^= 1;
+    let mut forged_sender = encrypted;
+    forged_sender.sender = 3;
+    // with R at infinity the key is public, anyone could forge the MAC
+    let mut public_key = encrypted;
+    public_key.R = ProjectivePoint::IDENTITY;
+    for tampered in [redirected, flipped, forged_sender, public_key] {
+        assert!(matches!(
+            decrypt_share(&p, &tampered),
+            Err(Error::DecryptionFailed)
+        ));
+    }
+}
@@ -0,0 +1,64 @@
+#![allow(non_snake_case)]
+
+use k256::ProjectivePoint;
+use shamy_core::shamir::*;
+use shamy_core::taproot::*;
+use shamy_core::threshold::aggregate_public_key;
+use shamy_core::util::{hex_to_pp, scalar_to_hex};
+
+#[test]
+fn test_bip341_key_path_vector() {
+    // BIP-341 wallet test vectors, scriptPubKey[0] (no script tree)
+    let P =
+        hex_to_pp("02d6889cb081036e0faefa3a35157ad71086b123b2b144b649798b494c300a961d").unwrap();
+    let tweak = taproot_tweak(&P, None).unwrap();
+    assert_eq!(
+        scalar_to_hex(&tweak.tweak),
+        "b86e7be8f39bab32a6f2c0443abbc210f0edac0e2c53d501b36b64437d9c6c70"
+    );
+    assert_eq!(
+        hex::encode(tweak.output_key_x_only()),
+        "53a1f6e454df1aa2776a2814a721372d6258050de330b3c6d10ee8f4e0dda343"
+    );
+
+    // the odd-Y twin has the same x and the same output key
+    let odd = taproot_tweak(&-P, None).unwrap();
+    assert!(odd.negate_internal && !tweak.negate_internal);
+    assert_eq!(odd.output_key, tweak.output_key);
+}
+
+#[test]
+fn test_bip341_key_path_address() {
+    let P =
+        hex_to_pp("02d6889cb081036e0faefa3a35157ad71086b123b2b144b649798b494c300a961d").unwrap();
+    let tweak = taproot_tweak(&P, None).unwrap();
+    assert_eq!(
+        tweak.address("bc").unwrap(),
+        "bc1p2wsldez5mud2yam29q22wgfh9439spgduvct83k3pm50fcxa5dps59h4z5"
+    );
+}
+
+#[test]
+fn test_tweaked_shares_match_output_key() {
+    let keygen = shamir_keygen(5, 3).unwrap();
+    for merkle_root in [None, Some(&[9u8; 32])] {
+        let tweak = taproot_tweak(&keygen.public_key, merkle_root).unwrap();
+
+        let public_shares = keygen
+            .participants
+            .iter()
+            .take(3)
+            .map(|p| {
+                let tweaked = tweak.tweak_participant(p);
+                assert_eq!(tweaked.X_i, tweak.tweak_public_share(&p.X_i));
+                (p.id, tweaked.X_i)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            aggregate_public_key(&public_shares).unwrap(),
+            tweak.output_key
+        );
+        assert_ne!(tweak.output_key, keygen.public_key);
+        assert_ne!(tweak.output_key, ProjectivePoint::IDENTITY);
+    }
+}
@@ -0,0 +1,727 @@
+#![allow(non_snake_case)]
+
+use k256::{ProjectivePoint, Scalar};
+use rand::{rng, seq::IteratorRandom};
+use shamy_core::Error;
+use shamy_core::hazmat::{LagrangeWeights, lagrange_coefficient, multiscalar_mul};
+use shamy_core::schnorr::*;
+use shamy_core::shamir::*;
+use shamy_core::threshold::*;
+use shamy_core::util::pp_to_x_only;
+use shamy_core::vss::verify_share;
+
+#[test]
+fn test_threshold_schnorr_3_5() {
+    let mut rng = rng();
+    let n = 5;
+    let t = 3;
+    let keygen_output = shamir_keygen(n, t).unwrap();
+
+    let msg = b"Hello threshold schnorr!";
+
+    let chosen_participants: Vec<Participant> = keygen_output
+        .participants
+        .iter()
+        .choose_multiple(&mut rng, t)
+        .into_iter()
+        .copied()
+        .collect();
+
+    let ids: Vec<u64> = chosen_participants.iter().map(|p| p.id).collect();
+
+    let mut nonce_pairs = Vec::new(); // (Participant, r_i, R_i)
+    for p in &chosen_participants {
+        let r_i = generate_nonce();
+        let R_i = compute_nonce_point(&r_i);
+        nonce_pairs.push((p, r_i, R_i));
+    }
+
+    let nonces = nonce_pairs
+        .clone()
+        .into_iter()
+        .map(|(p, _, R_i)| (p.id, R_i))
+        .collect::<Vec<_>>();
+    let signer_set = SignerSet::new(&ids, t).unwrap();
+    let R = aggregate_nonce_with_signers(nonces.as_slice(), &signer_set).unwrap();
+
+    let c = compute_challenge(&R, &keygen_output.public_key, msg);
+
+    let partials = nonce_pairs
+        .iter()
+        .map(|(p, r_i, _)| partial_sign(p, r_i, &c))
+        .collect::<Vec<_>>();
+
+    let signature = finalize_signature_with_signers(&partials, R, &signer_set).unwrap();
+    assert!(signature.verify_with_config(
+        msg,
+        &keygen_output.public_key,
+        &SigningConfig::unframed().legacy_challenge()
+    ));
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_deprecated_aggregation_over_ids() {
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let signers = &keygen_output.participants[1..];
+    let nonces = signers.iter().map(|_| generate_nonce()).collect::<Vec<_>>();
+    let points = signers
+        .iter()
+        .zip(&nonces)
+        .map(|(p, r_i)| (p.id, compute_nonce_point(r_i)))
+        .collect::<Vec<_>>();
+    let signer_set = SignerSet::new(&[2, 3], 2).unwrap();
+    let R = aggregate_nonce(&points, &[2, 3]).unwrap();
+    assert_eq!(Ok(R), aggregate_nonce_with_signers(&points, &signer_set));
+    assert_eq!(
+        aggregate_nonce(&points, &[2, 2]),
+        Err(Error::DuplicateParticipantId(2))
+    );
+
+    let c = compute_challenge(&R, &keygen_output.public_key, b"old api");
+    let partials = signers
+        .iter()
+        .zip(&nonces)
+        .map(|(p, r_i)| partial_sign(p, r_i, &c))
+        .collect::<Vec<_>>();
+    let signature = finalize_signature_lagrange(&partials, R).unwrap();
+    assert_eq!(
+        Ok(signature),
+        finalize_signature_with_signers(&partials, R, &signer_set)
+    );
+    assert!(signature.verify(b"old api", &keygen_output.public_key));
+}
+
+#[test]
+fn test_threshold_schnorr_5_5_valid() {
+    let n = 5;
+    let t = 5;
+    let keygen_output = shamir_keygen(n, t).unwrap();
+
+    let msg = b"Full participation test";
+    let ids: Vec<u64> = keygen_output.participants.iter().map(|p| p.id).collect();
+
+    let nonce_pairs = keygen_output
+        .participants
+        .iter()
+        .map(|p| {
+            let r_i = generate_nonce();
+            let R_i = compute_nonce_point(&r_i);
+            (p, r_i, R_i)
+        })
+        .collect::<Vec<_>>();
+
+    let nonces = nonce_pairs
+        .clone()
+        .into_iter()
+        .map(|(p, _, R_i)| (p.id, R_i))
+        .collect::<Vec<_>>();
+    let signer_set = SignerSet::new(&ids, t).unwrap();
+    let R = aggregate_nonce_with_signers(nonces.as_slice(), &signer_set).unwrap();
+
+    let c = compute_challenge(&R, &keygen_output.public_key, msg);
+
+    let partials = nonce_pairs
+        .iter()
+        .map(|(p, r_i, _)| partial_sign(p, r_i, &c))
+        .collect::<Vec<_>>();
+
+    let sig = finalize_signature_with_signers(&partials, R, &signer_set).unwrap();
+    assert!(sig.verify_with_config(
+        msg,
+        &keygen_output.public_key,
+        &SigningConfig::unframed().legacy_challenge()
+    ));
+}
+
+#[test]
+fn test_invalid_signature_wrong_participants() {
+    let n = 5;
+    let t = 5;
+    let keygen_output = shamir_keygen(n, t).unwrap();
+
+    // threshold is 5 but only 3 participants are signing
+    let signers = &keygen_output.participants[0..3];
+    let signer_ids: Vec<u64> = signers.iter().map(|p| p.id).collect();
+
+    assert_eq!(
+        SignerSet::new(&signer_ids, t),
+        Err(Error::NotEnoughSigners {
+            threshold: 5,
+            actual: 3
+        })
+    );
+}
+
+#[test]
+fn test_signer_set_needs_every_member() {
+    let keygen_output = shamir_keygen(5, 3).unwrap();
+    let signers = &keygen_output.participants[..3];
+    let ids = signers.iter().map(|p| p.id).collect::<Vec<_>>();
+    let signer_set = SignerSet::new(&ids, 3).unwrap();
+    assert_eq!(signer_set.ids(), &ids[..]);
+    assert_eq!(signer_set.threshold(), 3);
+    assert!(signer_set.contains(2) && !signer_set.contains(4));
+    assert_eq!(
+        SignerSet::new(&[1, 2, 1], 2),
+        Err(Error::DuplicateParticipantId(1))
+    );
+
+    let nonces = signers
+        .iter()
+        .map(|p| (p, generate_nonce()))
+        .collect::<Vec<_>>();
+    let commitments = nonces
+        .iter()
+        .map(|(p, r_i)| (p.id, compute_nonce_point(r_i)))
+        .collect::<Vec<_>>();
+
+    // a signer dropping out is an error, not a differently weighted R
+    assert_eq!(
+        aggregate_nonce_with_signers(&commitments[..2], &signer_set),
+        Err(Error::MissingPackage { round: 1, id: 3 })
+    );
+    let R = aggregate_nonce_with_signers(&commitments, &signer_set).unwrap();
+
+    let c = compute_challenge(&R, &keygen_output.public_key, b"all or nothing");
+    let partials = nonces
+        .iter()
+        .map(|(p, r_i)| partial_sign(p, r_i, &c))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        finalize_signature_with_signers(&partials[1..], R, &signer_set),
+        Err(Error::MissingPackage { round: 2, id: 1 })
+    );
+    assert!(
+        finalize_signature_with_signers(&partials, R, &signer_set)
+            .unwrap()
+            .verify_with_config(
+                b"all or nothing",
+                &keygen_output.public_key,
+                &SigningConfig::unframed().legacy_challenge()
+            )
+    );
+}
+
+#[test]
+fn test_threshold_signature_equals_manual_combined_signature() {
+    let n = 5;
+    let t = 3;
+    let keygen_output = shamir_keygen(n, t).unwrap();
+
+    let mut rng = rng();
+    let chosen: Vec<Participant> = keygen_output
+        .participants
+        .iter()
+        .choose_multiple(&mut rng, t)
+        .into_iter()
+        .copied()
+        .collect();
+
+    let ids: Vec<u64> = chosen.iter().map(|p| p.id).collect();
+    let signer_set = SignerSet::new(&ids, t).unwrap();
+    let msg = b"same signature from reconstructed key";
+
+    let nonce_pairs: Vec<(Participant, Scalar, ProjectivePoint)> = chosen
+        .iter()
+        .map(|p| {
+            let r_i = generate_nonce();
+            let R_i = compute_nonce_point(&r_i);
+            (*p, r_i, R_i)
+        })
+        .collect();
+
+    let R = nonce_pairs
+        .iter()
+        .fold(ProjectivePoint::IDENTITY, |acc, (p, _, R_i)| {
+            let lambda = lagrange_coefficient(p.id, &ids);
+            acc + (*R_i * lambda)
+        });
+
+    // aggregate the secret key from the chosen participants for manual verification
+    let combined_x = chosen.iter().fold(Scalar::ZERO, |acc, p| {
+        let lambda = lagrange_coefficient(p.id, &ids);
+        acc + (lambda * p.x_i)
+    });
+    let X = ProjectivePoint::GENERATOR * combined_x;
+
+    // reconstruct nonce: r = Σ λᵢ·rᵢ
+    let combined_r = nonce_pairs.iter().fold(Scalar::ZERO, |acc, (p, r_i, _)| {
+        let lambda = lagrange_coefficient(p.id, &ids);
+        acc + (lambda * r_i)
+    });
+    let c = compute_challenge(&R, &X, msg);
+
+    // manual signature
+    let s_manual = combined_r + c * combined_x;
+    let manual_signature = SchnorrSignature { R, s: s_manual };
+
+    // threshold signature from partials
+    let partials: Vec<PartialSignature> = nonce_pairs
+        .iter()
+        .map(|(p, r_i, _)| partial_sign(p, r_i, &c))
+        .collect();
+
+    let threshold_signature = finalize_signature_with_signers(&partials, R, &signer_set).unwrap();
+
+    assert_eq!(manual_signature.R, threshold_signature.R);
+    assert_eq!(manual_signature.s, threshold_signature.s);
+}
+
+#[test]
+fn test_compare_signatures_of_different_subsets() {
+    let n = 5;
+    let t = 3;
+    let keygen_output = shamir_keygen(n, t).unwrap();
+
+    let msg = b"Hello threshold schnorr!";
+
+    let chosen_participants: Vec<Participant> =
+        keygen_output.participants.iter().take(t).copied().collect();
+
+    let ids: Vec<u64> = chosen_participants.iter().map(|p| p.id).collect();
+
+    let mut nonce_pairs = Vec::new();
+    for p in &chosen_participants {
+        let r_i = generate_nonce();
+        let R_i = compute_nonce_point(&r_i);
+        nonce_pairs.push((p, r_i, R_i));
+    }
+
+    let nonces = nonce_pairs
+        .clone()
+        .into_iter()
+        .map(|(p, _, R_i)| (p.id, R_i))
+        .collect::<Vec<_>>();
+    let signer_set = SignerSet::new(&ids, t).unwrap();
+    let R = aggregate_nonce_with_signers(nonces.as_slice(), &signer_set).unwrap();
+
+    let c = compute_challenge(&R, &keygen_output.public_key, msg);
+
+    let partials = nonce_pairs
+        .iter()
+        .map(|(p, r_i, _)| partial_sign(p, r_i, &c))
+        .collect::<Vec<_>>();
+
+    let signature = finalize_signature_with_signers(&partials, R, &signer_set).unwrap();
+    assert!(signature.verify_with_config(
+        msg,
+        &keygen_output.public_key,
+        &SigningConfig::unframed().legacy_challenge()
+    ));
+
+    // ---------------------------
+
+    let rev_chosen_participants: Vec<Participant> = keygen_output
+        .participants
+        .iter()
+        .rev()
+        .take(t)
+        .copied()
+        .collect();
+
+    let ids: Vec<u64> = rev_chosen_participants.iter().map(|p| p.id).collect();
+
+    let public_keys = rev_chosen_participants
+        .iter()
+        .map(|p| (p.id, p.X_i))
+        .collect::<Vec<_>>();
+    let rev_public_key = aggregate_public_key(&public_keys).unwrap();
+
+    assert_eq!(keygen_output.public_key, rev_public_key);
+
+    let mut nonce_pairs = Vec::new();
+    for p in &rev_chosen_participants {
+        let r_i = generate_nonce();
+        let R_i = compute_nonce_point(&r_i);
+        nonce_pairs.push((p, r_i, R_i));
+    }
+
+    let nonces = nonce_pairs
+        .clone()
+        .into_iter()
+        .map(|(p, _, R_i)| (p.id, R_i))
+        .collect::<Vec<_>>();
+    let signer_set = SignerSet::new(&ids, t).unwrap();
+    let R = aggregate_nonce_with_signers(nonces.as_slice(), &signer_set).unwrap();
+
+    let c = compute_challenge(&R, &rev_public_key, msg);
+
+    let partials = nonce_pairs
+        .iter()
+        .map(|(p, r_i, _)| partial_sign(p, r_i, &c))
+        .collect::<Vec<_>>();
+
+    let rev_signature = finalize_signature_with_signers(&partials, R, &signer_set).unwrap();
+
+    assert_ne!(signature.R, rev_signature.R);
+    assert_ne!(signature.s, rev_signature.s);
+}
+
+#[test]
+fn test_lagrange_weights_match_coefficients() {
+    for ids in [vec![1u64, 2], vec![3, 1, 5], vec![7, 2, 9, 4, 11], vec![42]] {
+        let weights = LagrangeWeights::new(&ids).unwrap();
+        assert_eq!(weights.ids(), &ids[..]);
+        for id in &ids {
+            assert_eq!(weights.weight(*id), Some(lagrange_coefficient(*id, &ids)));
+        }
+        assert_eq!(weights.weight(100), None);
+    }
+}
+
+#[test]
+fn test_aggregation_rejects_duplicate_and_zero_ids() {
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let [p1, p2, _] = keygen_output.participants[..] else {
+        unreachable!()
+    };
+    let R_1 = compute_nonce_point(&generate_nonce());
+    let R_2 = compute_nonce_point(&generate_nonce());
+
+    assert_eq!(
+        LagrangeWeights::new(&[1, 2, 1]),
+        Err(Error::DuplicateParticipantId(1))
+    );
+    assert_eq!(
+        LagrangeWeights::new(&[0, 2]),
+        Err(Error::InvalidParticipantId {
+            id: 0,
+            num_shares: 2
+        })
+    );
+    assert_eq!(LagrangeWeights::new(&[]), Err(Error::NoShares));
+    let pair = SignerSet::new(&[1, 2], 2).unwrap();
+
+    assert_eq!(
+        aggregate_nonce_with_signers(&[(1, R_1), (2, R_2), (1, R_2)], &pair),
+        Err(Error::DuplicateParticipantId(1))
+    );
+    assert_eq!(
+        aggregate_public_key(&[(1, p1.X_i), (1, p1.X_i)]),
+        Err(Error::DuplicateParticipantId(1))
+    );
+
+    let partial = PartialSignature::new(p2.id, p2.x_i);
+    assert_eq!(
+        finalize_signature_with_signers(&[partial, partial], R_1, &pair),
+        Err(Error::DuplicateParticipantId(2))
+    );
+    let weights = LagrangeWeights::new(&[1, 2]).unwrap();
+    assert_eq!(
+        finalize_signature_with_weights(&[partial, partial], R_1, &weights),
+        Err(Error::DuplicateParticipantId(2))
+    );
+}
+
+#[test]
+fn test_partial_signature_verifies_itself() {
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let p1 = keygen_output.participants[0];
+    let r_1 = generate_nonce();
+    let c = Scalar::from(11u64);
+
+    let partial = partial_sign(&p1, &r_1, &c);
+    assert_eq!(partial.R_i, Some(compute_nonce_point(&r_1)));
+    assert_eq!(partial.X_i, Some(p1.X_i));
+    assert_eq!(partial.verify(&c), Ok(()));
+    assert_eq!(
+        partial.verify(&(c + Scalar::ONE)),
+        Err(Error::InvalidPartialSignature { id: p1.id })
+    );
+
+    let claimed = PartialSignature {
+        X_i: Some(keygen_output.participants[1].X_i),
+        ..partial
+    };
+    assert_eq!(
+        claimed.verify(&c),
+        Err(Error::InvalidPartialSignature { id: p1.id })
+    );
+    assert_eq!(
+        PartialSignature::new(p1.id, partial.s_i).verify(&c),
+        Err(Error::MissingPartialCommitments { id: p1.id })
+    );
+}
+
+#[test]
+fn test_aggregation_rejects_identity_points() {
+    let R_1 = compute_nonce_point(&generate_nonce());
+    let pair = SignerSet::new(&[1, 2], 2).unwrap();
+
+    assert_eq!(
+        aggregate_nonce_with_signers(&[(1, R_1), (2, ProjectivePoint::IDENTITY)], &pair),
+        Err(Error::IdentityPoint)
+    );
+
+    // R_2 chosen after seeing R_1 so that λ_1*R_1 + λ_2*R_2 = 0
+    let (lambda_1, lambda_2) = (
+        lagrange_coefficient(1, &[1, 2]),
+        lagrange_coefficient(2, &[1, 2]),
+    );
+    let R_2 = -(R_1 * lambda_1 * lambda_2.invert().unwrap());
+    assert_eq!(
+        aggregate_nonce_with_signers(&[(1, R_1), (2, R_2)], &pair),
+        Err(Error::IdentityPoint)
+    );
+}
+
+#[test]
+fn test_threshold_schnorr_with_cached_weights() {
+    let keygen_output = shamir_keygen(5, 3).unwrap();
+    let signers = &keygen_output.participants[1..4];
+    let ids = signers.iter().map(|p| p.id).collect::<Vec<_>>();
+    let weights = LagrangeWeights::new(&ids).unwrap();
+    let signer_set = SignerSet::new(&ids, 3).unwrap();
+
+    // the same quorum signs twice with one set of weights
+    for msg in [b"first message".as_slice(), b"second message"] {
+        let nonces = signers
+            .iter()
+            .map(|p| (p, generate_nonce()))
+            .collect::<Vec<_>>();
+        let commitments = nonces
+            .iter()
+            .map(|(p, r_i)| (p.id, compute_nonce_point(r_i)))
+            .collect::<Vec<_>>();
+        let R = aggregate_nonce_with_weights(&commitments, &weights).unwrap();
+        assert_eq!(
+            R,
+            aggregate_nonce_with_signers(&commitments, &signer_set).unwrap()
+        );
+
+        let c = compute_challenge(&R, &keygen_output.public_key, msg);
+        let partials = nonces
+            .iter()
+            .map(|(p, r_i)| partial_sign(p, r_i, &c))
+            .collect::<Vec<_>>();
+        let signature = finalize_signature_with_weights(&partials, R, &weights).unwrap();
+        assert_eq!(
+            signature,
+            finalize_signature_with_signers(&partials, R, &signer_set).unwrap()
+        );
+        assert!(signature.verify_with_config(
+            msg,
+            &keygen_output.public_key,
+            &SigningConfig::unframed().legacy_challenge()
+        ));
+    }
+
+    let public_keys = signers.iter().map(|p| (p.id, p.X_i)).collect::<Vec<_>>();
+    assert_eq!(
+        aggregate_public_key_with_weights(&public_keys, &weights).unwrap(),
+        keygen_output.public_key
+    );
+}
+
+#[test]
+fn test_multiscalar_mul_matches_naive_sum() {
+    for n in [0usize, 1, 2, 17, 150] {
+        let terms = (0..n)
+            .map(|_| (compute_nonce_point(&generate_nonce()), generate_nonce()))
+            .collect::<Vec<_>>();
+        let naive = terms
+            .iter()
+            .fold(ProjectivePoint::IDENTITY, |acc, (P, k)| acc + *P * k);
+        assert_eq!(multiscalar_mul(&terms), naive);
+    }
+}
+
+#[test]
+fn test_revoke_participant() {
+    let keygen_output = shamir_keygen(5, 3).unwrap();
+    let config = SigningConfig::new("shamy-tests", "revocation");
+    let old = |id: u64| keygen_output.participants[id as usize - 1];
+
+    let revocation = revoke_participant(&keygen_output, 2, &config).unwrap();
+    let refreshed = &revocation.keygen_output;
+    assert_eq!(refreshed.public_key, keygen_output.public_key);
+    assert_eq!(
+        refreshed
+            .participants
+            .iter()
+            .map(|p| p.id)
+            .collect::<Vec<_>>(),
+        [1, 3, 4, 5]
+    );
+    for p in &refreshed.participants {
+        assert!(verify_share(p.id, p.x_i, &refreshed.commitments));
+        assert_ne!(p.x_i, old(p.id).x_i);
+    }
+    assert!(!verify_share(2, old(2).x_i, &refreshed.commitments));
+
+    // the new shares hold the same key, mixed with old ones they don't
+    let shares = |participants: &[Participant]| {
+        participants
+            .iter()
+            .map(|p| (p.id, p.x_i))
+            .collect::<Vec<_>>()
+    };
+    let secret = reconstruct_secret(&shares(&keygen_output.participants[..3])).unwrap();
+    assert_eq!(
+        reconstruct_secret(&shares(&refreshed.participants[..3])).unwrap(),
+        secret
+    );
+    let mixed = [old(2), refreshed.participants[0], refreshed.participants[1]];
+    assert_ne!(reconstruct_secret(&shares(&mixed)).unwrap(), secret);
+
+    let record = &revocation.record;
+    assert_eq!((record.id, record.X_i), (2, old(2).X_i));
+    assert_eq!(record.commitments, refreshed.commitments);
+    assert!(record.verify(&config));
+    let mut forged = record.clone();
+    forged.id = 3;
+    assert!(!forged.verify(&config));
+}
+
+#[test]
+fn test_revoke_participant_bip340() {
+    // an odd-Y key makes the signers flip their shares as well as their nonces
+    let config = SigningConfig::new("shamy-tests", "revocation").bip340_challenge();
+    for _ in 0..4 {
+        let keygen_output = shamir_keygen(4, 2).unwrap();
+        let revocation = revoke_participant(&keygen_output, 1, &config).unwrap();
+        assert!(revocation.record.verify(&config));
+    }
+}
+
+#[test]
+fn test_revoke_participant_errors() {
+    let config = SigningConfig::new("shamy-tests", "revocation");
+    assert_eq!(
+        revoke_participant(&shamir_keygen(5, 3).unwrap(), 9, &config).err(),
+        Some(Error::InvalidParticipantId {
+            id: 9,
+            num_shares: 5
+        })
+    );
+    assert_eq!(
+        revoke_participant(&shamir_keygen(3, 3).unwrap(), 1, &config).err(),
+        Some(Error::NotEnoughShares {
+            threshold: 3,
+            actual: 2
+        })
+    );
+}
+
+/// what a Bitcoin node checks: BIP-340 over x(X) and the 64-byte signature.
+fn bip340_verify(signature: &SchnorrSignature, X: &ProjectivePoint, msg: &[u8]) -> bool {
+    let Ok(bytes) = signature.to_bytes_x_only() else {
+        return false;
+    };
+    let key = k256::schnorr::VerifyingKey::from_bytes(&pp_to_x_only(X)).unwrap();
+    let signature = k256::schnorr::Signature::try_from(bytes.as_slice()).unwrap();
+    key.verify_raw(msg, &signature).is_ok()
+}
+
+#[test]
+fn test_even_y_threshold_signature_is_bip340_valid() {
+    let config = SigningConfig::unframed().bip340_challenge();
+    let msg = [7u8; 32];
+    let mut seen = Vec::new();
+
+    // fresh keys and nonces until both flips have come up both ways
+    for _ in 0..16 {
+        let keygen_output = shamir_keygen(3, 2).unwrap();
+        let X = keygen_output.public_key;
+        let signers = &keygen_output.participants[1..];
+        let ids = signers.iter().map(|p| p.id).collect::<Vec<_>>();
+        let signer_set = SignerSet::new(&ids, 2).unwrap();
+        let nonces = signers.iter().map(|_| generate_nonce()).collect::<Vec<_>>();
+        let R = aggregate_nonce_with_signers(
+            &ids.iter()
+                .zip(&nonces)
+                .map(|(id, r_i)| (*id, compute_nonce_point(r_i)))
+                .collect::<Vec<_>>(),
+            &signer_set,
+        )
+        .unwrap();
+
+        let even_y = EvenY::new(&R, &X);
+        let R = even_y.nonce(&R);
+        let c = config.challenge(&R, &X, &msg);
+        let partials = signers
+            .iter()
+            .zip(&nonces)
+            .map(|(p, r_i)| partial_sign_even_y(p, r_i, &c, &even_y))
+            .collect::<Vec<_>>();
+        assert!(partials.iter().all(|p| p.verify(&c).is_ok()));
+        let signature = finalize_signature_with_signers(&partials, R, &signer_set).unwrap();
+
+        assert!(signature.verify_with_config(&msg, &X, &config));
+        assert!(bip340_verify(&signature, &X, &msg));
+        seen.push(even_y);
+
+        // unflipped partials only add up under the textbook equation
+        if even_y.negate_nonce {
+            let partials = signers
+                .iter()
+                .zip(&nonces)
+                .map(|(p, r_i)| partial_sign(p, r_i, &c))
+                .collect::<Vec<_>>();
+            let signature = finalize_signature_with_signers(&partials, -R, &signer_set).unwrap();
+            assert!(!signature.verify_with_config(&msg, &X, &config));
+            assert!(!bip340_verify(&signature, &X, &msg));
+        }
+    }
+    for flip in [true, false] {
+        assert!(seen.iter().any(|e| e.negate_nonce == flip));
+        assert!(seen.iter().any(|e| e.negate_key == flip));
+    }
+
+    // a single-party key flips the same way
+    for _ in 0..8 {
+        let key_pair = KeyPair::generate();
+        let signature = key_pair.sign(&msg, &config);
+        assert!(bip340_verify(&signature, &key_pair.public_key(), &msg));
+    }
+}
+
+#[test]
+fn test_signing_session_negotiates_even_y() {
+    let config = SigningConfig::unframed().bip340_challenge();
+    let msg = [9u8; 32];
+    for _ in 0..8 {
+        let keygen_output = shamir_keygen(3, 2).unwrap();
+        let X = keygen_output.public_key;
+        let public_shares = keygen_output
+            .participants
+            .iter()
+            .map(|p| (p.id, p.X_i))
+            .collect::<Vec<_>>();
+        let mut signers = keygen_output.participants[..2]
+            .iter()
+            .map(|p| SignerState::new(*p))
+            .collect::<Vec<_>>();
+        let mut session = SigningSession::new(X, &msg, &[1, 2], config.clone()).unwrap();
+        assert_eq!(session.even_y(), None);
+        for signer in &mut signers {
+            session
+                .add_nonce(signer.id(), signer.commit().unwrap())
+                .unwrap();
+        }
+
+        let (R, c) = session.challenge().unwrap();
+        let even_y = session.even_y().unwrap();
+        assert!(!EvenY::new(&R, &X).negate_nonce);
+        for signer in &mut signers {
+            session
+                .add_partial(signer.sign_even_y(&c, &even_y).unwrap())
+                .unwrap();
+        }
+        let signature = session.clone().finalize().unwrap();
+        assert_eq!(session.finalize_identifiable(&public_shares), Ok(signature));
+        assert!(session.evidence(&public_shares).is_empty());
+        assert!(bip340_verify(&signature, &X, &msg));
+
+        let proof = session.aggregation_proof().unwrap();
+        assert_eq!(proof.verify(&msg, &X, &public_shares, &config), Ok(()));
+    }
+
+    // the BIP-340 challenge has no room for a session id
+    let config = config.with_session(SessionId([1; 32]));
+    assert_eq!(
+        SigningSession::new(ProjectivePoint::GENERATOR, &msg, &[1, 2], config).err(),
+        Some(Error::UnsupportedChallenge)
+    );
+}
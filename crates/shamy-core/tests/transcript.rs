@@ -0,0 +1,119 @@
+#![allow(non_snake_case)]
+
+use shamy_core::Error;
+use shamy_core::schnorr::*;
+use shamy_core::shamir::*;
+use shamy_core::threshold::*;
+use shamy_core::transcript::*;
+
+fn config() -> SigningConfig {
+    SigningConfig::new("shamy-tests", "transcript")
+}
+
+fn signed_session(msg: &[u8]) -> (KeygenOutput, SigningSession, SchnorrSignature) {
+    let keygen_output = shamir_keygen(5, 3).unwrap();
+    let mut signers = keygen_output.participants[..3]
+        .iter()
+        .map(|p| SignerState::new(*p))
+        .collect::<Vec<_>>();
+
+    let mut session = SigningSession::new(keygen_output.public_key, msg, &[1, 2, 3], config())
+        .unwrap()
+        .with_transcript(b"ceremony 1")
+        .unwrap();
+    for signer in signers.iter_mut() {
+        session
+            .add_nonce(signer.id(), signer.commit().unwrap())
+            .unwrap();
+    }
+    let (_, c) = session.challenge().unwrap();
+    for signer in signers.iter_mut() {
+        session.add_partial(signer.sign(&c).unwrap()).unwrap();
+    }
+    let signature = session.finalize().unwrap();
+
+    (keygen_output, session, signature)
+}
+
+#[test]
+fn test_signature_bound_to_transcript() {
+    let msg = b"audited";
+    let (keygen_output, session, signature) = signed_session(msg);
+    let transcript = session.transcript().unwrap();
+    let X = keygen_output.public_key;
+
+    // 3 setup entries, 3 nonces, bind, 3 partials, signature
+    assert_eq!(transcript.entries().len(), 11);
+    assert!(!signature.verify_with_config(msg, &X, &config()));
+    let bound = transcript.bound_config(&config()).unwrap();
+    assert!(signature.verify_with_config(msg, &X, &bound));
+
+    let replayed = Transcript::replay(transcript.context(), transcript.entries()).unwrap();
+    assert_eq!(&replayed, transcript);
+    assert_eq!(replayed.digest(), transcript.digest());
+}
+
+#[test]
+fn test_replay_detects_tampering() {
+    let (_, session, _) = signed_session(b"audited");
+    let transcript = session.transcript().unwrap();
+
+    let mut entries = transcript.entries().to_vec();
+    entries[4].payload[1] ^= 1;
+    assert_eq!(
+        Transcript::replay(transcript.context(), &entries),
+        Err(Error::TranscriptMismatch { index: 4 })
+    );
+
+    let mut entries = transcript.entries().to_vec();
+    entries.swap(3, 4);
+    assert_eq!(
+        Transcript::replay(transcript.context(), &entries),
+        Err(Error::TranscriptMismatch { index: 3 })
+    );
+
+    assert_eq!(
+        Transcript::replay(b"ceremony 2", transcript.entries()),
+        Err(Error::TranscriptMismatch { index: 0 })
+    );
+}
+
+#[test]
+fn test_transcript_bytes_roundtrip() {
+    let (_, session, _) = signed_session(b"audited");
+    let transcript = session.transcript().unwrap();
+    let bytes = transcript.to_bytes();
+
+    assert_eq!(&Transcript::from_bytes(&bytes).unwrap(), transcript);
+    assert!(Transcript::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+    // a flipped payload byte is caught by the replay
+    let mut tampered = bytes.clone();
+    let end = tampered.len() - 32 - 65 + 10;
+    tampered[end] ^= 1;
+    assert_eq!(
+        Transcript::from_bytes(&tampered),
+        Err(Error::TranscriptMismatch { index: 10 })
+    );
+}
+
+#[test]
+fn test_bind_needs_v1_challenge() {
+    let mut transcript = Transcript::new(b"ceremony");
+    assert_eq!(
+        transcript.bind(1, &config().legacy_challenge()),
+        Err(Error::UnsupportedChallenge)
+    );
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    assert!(
+        SigningSession::new(
+            keygen_output.public_key,
+            b"m",
+            &[1, 2],
+            config().legacy_challenge()
+        )
+        .unwrap()
+        .with_transcript(b"ceremony")
+        .is_err()
+    );
+}
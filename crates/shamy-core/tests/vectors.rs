@@ -0,0 +1,47 @@
+#![cfg(feature = "test-vectors")]
+
+use k256::Scalar;
+use shamy_core::Error;
+use shamy_core::vectors::*;
+
+#[test]
+fn test_vectors_are_deterministic() {
+    let vector = |seed| generate(seed, 5, 3, "shamy", "test-vectors", b"message").unwrap();
+    let a = vector([1; 32]);
+    assert_eq!(a, vector([1; 32]));
+    assert_ne!(a.public_key, vector([2; 32]).public_key);
+
+    assert_eq!(a.nonces.len(), 3);
+    assert_eq!(a.partial_signatures.len(), 3);
+    assert_eq!(a.verify(), Ok(()));
+}
+
+#[test]
+fn test_vector_verify_names_the_wrong_field() {
+    let vector = generate([7; 32], 3, 2, "shamy", "test-vectors", b"message").unwrap();
+    let mismatch = |vector: SigningVector| match vector.verify() {
+        Err(Error::VectorMismatch(field)) => field,
+        other => panic!("expected a mismatch, got {other:?}"),
+    };
+
+    let mut wrong_share = vector.clone();
+    wrong_share.participants[2].x_i += Scalar::ONE;
+    assert_eq!(mismatch(wrong_share), "participants");
+
+    let mut wrong_challenge = vector.clone();
+    wrong_challenge.challenge += Scalar::ONE;
+    assert_eq!(mismatch(wrong_challenge), "challenge");
+
+    let mut wrong_partial = vector.clone();
+    wrong_partial.partial_signatures[0].s_i += Scalar::ONE;
+    assert_eq!(mismatch(wrong_partial), "partial_signatures");
+
+    let mut other_message = vector.clone();
+    other_message.message = b"other".to_vec();
+    assert_eq!(mismatch(other_message), "challenge");
+
+    // the seed is informative only, another implementation's randomness is fine
+    let mut other_seed = vector;
+    other_seed.seed = [0; 32];
+    assert_eq!(other_seed.verify(), Ok(()));
+}
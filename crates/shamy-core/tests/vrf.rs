@@ -0,0 +1,86 @@
+#![allow(non_snake_case)]
+
+use k256::ProjectivePoint;
+use shamy_core::Error;
+use shamy_core::shamir::*;
+use shamy_core::vrf::*;
+
+fn public_shares(keygen: &KeygenOutput) -> Vec<(u64, ProjectivePoint)> {
+    keygen.participants.iter().map(|p| (p.id, p.X_i)).collect()
+}
+
+#[test]
+fn test_vrf_any_quorum_same_output() {
+    let keygen = shamir_keygen(5, 3).unwrap();
+    let public_shares = public_shares(&keygen);
+    let input = b"round 42";
+
+    let partials = keygen
+        .participants
+        .iter()
+        .map(|p| partial_evaluate(p, input))
+        .collect::<Vec<_>>();
+
+    let proof = combine(input, &partials[..3], &public_shares).unwrap();
+    let other = combine(input, &partials[2..], &public_shares).unwrap();
+    assert_eq!(proof.output(), other.output());
+    assert!(proof.verify(input, &keygen.public_key, &public_shares));
+    assert!(other.verify(input, &keygen.public_key, &public_shares));
+
+    // evaluation is deterministic, another input gives another output
+    assert_eq!(
+        partial_evaluate(&keygen.participants[0], input),
+        partials[0]
+    );
+    let next = keygen
+        .participants
+        .iter()
+        .take(3)
+        .map(|p| partial_evaluate(p, b"round 43"))
+        .collect::<Vec<_>>();
+    assert_ne!(
+        combine(b"round 43", &next, &public_shares)
+            .unwrap()
+            .output(),
+        proof.output()
+    );
+
+    assert!(!proof.verify(b"round 43", &keygen.public_key, &public_shares));
+}
+
+#[test]
+fn test_vrf_rejects_bad_proofs() {
+    let keygen = shamir_keygen(5, 3).unwrap();
+    let public_shares = public_shares(&keygen);
+    let input = b"round 42";
+
+    let mut partials = keygen
+        .participants
+        .iter()
+        .take(3)
+        .map(|p| partial_evaluate(p, input))
+        .collect::<Vec<_>>();
+
+    // below threshold the shares don't interpolate to the group key
+    let short = combine(input, &partials[..2], &public_shares).unwrap();
+    assert!(!short.verify(input, &keygen.public_key, &public_shares));
+
+    assert_eq!(
+        combine(input, &[partials[0], partials[0]], &public_shares),
+        Err(Error::DuplicateParticipantId(1))
+    );
+    assert_eq!(combine(input, &[], &public_shares), Err(Error::NoShares));
+
+    partials[1].Gamma_i += ProjectivePoint::GENERATOR;
+    assert_eq!(
+        combine(input, &partials, &public_shares),
+        Err(Error::InvalidVrfProof { id: 2 })
+    );
+
+    let mut proof = combine(input, &partials[..1], &public_shares).unwrap();
+    proof.partials.push(partials[1]);
+    proof
+        .partials
+        .push(partial_evaluate(&keygen.participants[2], input));
+    assert!(!proof.verify(input, &keygen.public_key, &public_shares));
+}
@@ -0,0 +1,205 @@
+use k256::ProjectivePoint;
+use k256::{
+    Scalar,
+    elliptic_curve::{Field, rand_core::OsRng},
+};
+use rand::{Rng, rng};
+use shamy_core::Error;
+use shamy_core::hash::hash_to_scalar;
+use shamy_core::hazmat::{eval_polynomial, eval_polynomial_at, random_polynomial};
+use shamy_core::shamir::shamir_keygen;
+use shamy_core::vss::calculate_commitment;
+use shamy_core::vss::{
+    ShareAttestation, public_share, public_share_at, validate_commitments, verify_share,
+    verify_shares,
+};
+
+#[test]
+fn test_verify_commitment_valid() {
+    let n = 5;
+    let t = 3;
+
+    let secret = Scalar::random(&mut OsRng);
+    let coefs = random_polynomial(secret, t);
+    let commitments = coefs
+        .iter()
+        .map(|c| calculate_commitment(*c))
+        .collect::<Vec<_>>();
+
+    let mut rng = rng();
+
+    let p_id = rng.random_range(1..=n);
+    let x_i = eval_polynomial(&coefs, p_id);
+
+    let is_valid = verify_share(p_id, x_i, &commitments);
+
+    assert!(is_valid);
+}
+
+#[test]
+fn test_shares_at_scalar_abscissas() {
+    let coefs = random_polynomial(Scalar::random(&mut OsRng), 3);
+    let commitments = coefs
+        .iter()
+        .map(|c| calculate_commitment(*c))
+        .collect::<Vec<_>>();
+
+    // the u64 form is the same point
+    assert_eq!(
+        eval_polynomial_at(&coefs, &Scalar::from(7u64)),
+        eval_polynomial(&coefs, 7)
+    );
+    assert_eq!(
+        public_share_at(&Scalar::from(7u64), &commitments),
+        public_share(7, &commitments)
+    );
+
+    // a full-width abscissa derived from an identity
+    let x = hash_to_scalar(b"shamy-tests/id", b"alice@example.com");
+    let x_i = eval_polynomial_at(&coefs, &x);
+    assert_eq!(
+        ProjectivePoint::GENERATOR * x_i,
+        public_share_at(&x, &commitments)
+    );
+    assert_ne!(
+        ProjectivePoint::GENERATOR * x_i,
+        public_share_at(&(x + Scalar::ONE), &commitments)
+    );
+}
+
+#[test]
+fn test_validate_commitments() {
+    let keygen_output = shamir_keygen(5, 3).unwrap();
+    let commitments = &keygen_output.commitments;
+    assert_eq!(validate_commitments(commitments, 3), Ok(()));
+    assert_eq!(
+        validate_commitments(commitments, 4),
+        Err(Error::CommitmentCount {
+            expected: 4,
+            actual: 3
+        })
+    );
+
+    let share = keygen_output.participants[0];
+    for index in [0, 2] {
+        let mut malformed = commitments.clone();
+        malformed[index] = ProjectivePoint::IDENTITY;
+        assert_eq!(
+            validate_commitments(&malformed, 3),
+            Err(Error::IdentityCommitment { index })
+        );
+        assert!(!verify_share(share.id, share.x_i, &malformed));
+        assert_eq!(
+            verify_shares(&[(share.id, share.x_i)], &malformed),
+            Err(Error::IdentityCommitment { index })
+        );
+    }
+
+    // x = 0 used to match an empty vector
+    assert!(!verify_share(1, Scalar::ZERO, &[]));
+}
+
+#[test]
+fn test_verify_commitment_invalid_coefs() {
+    let n = 5;
+    let t = 3;
+
+    let secret = Scalar::random(&mut OsRng);
+    let original_coefs = random_polynomial(secret, t);
+
+    let mut rng = rng();
+
+    let p_id = rng.random_range(1..=n);
+    let x_i = eval_polynomial(&original_coefs, p_id);
+
+    let wrong_coefs = random_polynomial(secret, t);
+    let wrong_commitments = wrong_coefs
+        .iter()
+        .map(|c| calculate_commitment(*c))
+        .collect::<Vec<_>>();
+
+    let is_valid = verify_share(p_id, x_i, &wrong_commitments);
+
+    assert!(!is_valid);
+}
+
+#[test]
+fn test_verify_commitment_invalid_id() {
+    let n = 5;
+    let t = 3;
+
+    let secret = Scalar::random(&mut OsRng);
+    let coefs = random_polynomial(secret, t);
+    let commitments = coefs
+        .iter()
+        .map(|c| calculate_commitment(*c))
+        .collect::<Vec<_>>();
+
+    let mut rng = rng();
+
+    let p_id = rng.random_range(1..=n);
+    let x_i = eval_polynomial(&coefs, p_id);
+
+    let wrong_id = p_id + 1;
+
+    let is_valid = verify_share(wrong_id, x_i, &commitments);
+    assert!(!is_valid);
+}
+
+#[test]
+fn test_share_attestation() {
+    let keygen_output = shamir_keygen(5, 3).unwrap();
+    let challenge = b"epoch 42";
+
+    for participant in &keygen_output.participants {
+        let attestation = ShareAttestation::prove(participant, challenge);
+        assert!(attestation.verify(&keygen_output.commitments, challenge));
+        assert_eq!(
+            public_share(participant.id, &keygen_output.commitments),
+            participant.X_i
+        );
+    }
+}
+
+#[test]
+fn test_share_attestation_rejects_stale_challenge() {
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let participant = &keygen_output.participants[0];
+
+    let attestation = ShareAttestation::prove(participant, b"epoch 41");
+    assert!(!attestation.verify(&keygen_output.commitments, b"epoch 42"));
+}
+
+#[test]
+fn test_share_attestation_rejects_foreign_share() {
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let other_output = shamir_keygen(3, 2).unwrap();
+    let challenge = b"epoch 42";
+
+    // a participant of another group can't attest for this one
+    let attestation = ShareAttestation::prove(&other_output.participants[0], challenge);
+    assert!(!attestation.verify(&keygen_output.commitments, challenge));
+
+    // nor can a participant claim someone else's id
+    let mut attestation = ShareAttestation::prove(&keygen_output.participants[0], challenge);
+    attestation.id = keygen_output.participants[1].id;
+    assert!(!attestation.verify(&keygen_output.commitments, challenge));
+}
+
+#[test]
+fn test_verify_shares_batch() {
+    let keygen_output = shamir_keygen(50, 5).unwrap();
+    let mut shares = keygen_output
+        .participants
+        .iter()
+        .map(|p| (p.id, p.x_i))
+        .collect::<Vec<_>>();
+    assert_eq!(verify_shares(&shares, &keygen_output.commitments), Ok(()));
+
+    shares[40].1 += Scalar::ONE;
+    shares[7].1 += Scalar::ONE;
+    assert_eq!(
+        verify_shares(&shares, &keygen_output.commitments),
+        Err(Error::InvalidShare { id: 8 })
+    );
+}
@@ -0,0 +1,60 @@
+fn main() {
+    #[cfg(feature = "grpc")]
+    grpc();
+}
+
+/// service stubs for `proto/shamy/v1/shamy.proto`. the messages are written
+/// by hand in `src/wire.rs`, so building doesn't need `protoc`.
+#[cfg(feature = "grpc")]
+fn grpc() {
+    use tonic_build::manual::{Builder, Method, Service};
+
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let method = |name: &str, route: &str, input: &str, output: &str| {
+        Method::builder()
+            .name(name)
+            .route_name(route)
+            .input_type(format!("crate::wire::{}", input))
+            .output_type(format!("crate::wire::{}", output))
+            .codec_path("tonic_prost::ProstCodec")
+            .build()
+    };
+
+    let coordinator = Service::builder()
+        .name("Coordinator")
+        .package("shamy.v1")
+        .method(method(
+            "create_session",
+            "CreateSession",
+            "CreateSessionRequest",
+            "CreateSessionResponse",
+        ))
+        .method(method(
+            "submit_nonce",
+            "SubmitNonce",
+            "SubmitNonceRequest",
+            "SubmitNonceResponse",
+        ))
+        .method(method(
+            "get_challenge",
+            "GetChallenge",
+            "GetChallengeRequest",
+            "Challenge",
+        ))
+        .method(method(
+            "submit_partial",
+            "SubmitPartial",
+            "SubmitPartialRequest",
+            "SubmitPartialResponse",
+        ))
+        .method(method(
+            "finalize",
+            "Finalize",
+            "FinalizeRequest",
+            "Signature",
+        ))
+        .build();
+
+    Builder::new().compile(&[coordinator]);
+}
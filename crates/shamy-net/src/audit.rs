@@ -0,0 +1,368 @@
+use crate::error::Error;
+use k256::ProjectivePoint;
+use serde::Serialize;
+use shamy_core::util::fingerprint;
+use std::{
+    collections::HashMap,
+    fs::{self, File, OpenOptions},
+    io::Write,
+    net::{ToSocketAddrs, UdpSocket},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+//--------------------------------------------------------------------
+// Audit trail
+//--------------------------------------------------------------------
+//
+// every state change of a signing session is recorded as an `AuditEvent`
+// and handed to an `AuditSink`:
+//
+//   FileSink     JSON lines, rotated by size
+//   SyslogSink   RFC 5424 over UDP or a local unix socket
+//   WebhookSink  HTTPS POST of the JSON event, e.g. into a SIEM collector
+//   AuditLog     routes events to sinks per key (by fingerprint)
+//
+// ⚠️ events carry ids, nonces and signatures but never shares or the
+//    message itself (only its SHA-256).
+//
+
+/// what happened in a signing session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum AuditKind {
+    SessionCreated {
+        message_sha256: String,
+        signer_ids: Vec<u64>,
+    },
+    TakenOver {
+        epoch: u64,
+    },
+    NonceReceived {
+        id: u64,
+        nonce: String,
+    },
+    PartialReceived {
+        id: u64,
+    },
+    Finalized {
+        nonce: String,
+        signature: String,
+    },
+    /// a message or operation was refused, e.g. an unexpected signer.
+    Rejected {
+        reason: String,
+    },
+}
+
+impl AuditKind {
+    /// snake case name, same as the `event` field of the JSON encoding.
+    pub fn name(&self) -> &'static str {
+        match self {
+            AuditKind::SessionCreated { .. } => "session_created",
+            AuditKind::TakenOver { .. } => "taken_over",
+            AuditKind::NonceReceived { .. } => "nonce_received",
+            AuditKind::PartialReceived { .. } => "partial_received",
+            AuditKind::Finalized { .. } => "finalized",
+            AuditKind::Rejected { .. } => "rejected",
+        }
+    }
+}
+
+/// one audit record, serialized as a flat JSON object.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AuditEvent {
+    /// unix time in seconds.
+    pub time: u64,
+    /// fingerprint of the group public key.
+    pub key: String,
+    pub session_id: String,
+    /// name of the coordinator instance that recorded the event.
+    pub coordinator: String,
+    #[serde(flatten)]
+    pub kind: AuditKind,
+}
+
+impl AuditEvent {
+    pub fn new(
+        public_key: &ProjectivePoint,
+        session_id: &str,
+        coordinator: &str,
+        kind: AuditKind,
+    ) -> Self {
+        Self {
+            time: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            key: fingerprint(public_key),
+            session_id: session_id.to_string(),
+            coordinator: coordinator.to_string(),
+            kind,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+}
+
+/// destination of audit events.
+pub trait AuditSink: Send + Sync {
+    fn record(&self, event: &AuditEvent) -> Result<(), Error>;
+}
+
+impl<T: AuditSink + ?Sized> AuditSink for Arc<T> {
+    fn record(&self, event: &AuditEvent) -> Result<(), Error> {
+        (**self).record(event)
+    }
+}
+
+impl<T: AuditSink + ?Sized> AuditSink for Box<T> {
+    fn record(&self, event: &AuditEvent) -> Result<(), Error> {
+        (**self).record(event)
+    }
+}
+
+/// sends every event to the sinks of its key plus the sinks for all keys.
+/// all sinks are tried, the first failure is returned.
+#[derive(Default)]
+pub struct AuditLog {
+    sinks: Vec<Box<dyn AuditSink>>,
+    key_sinks: HashMap<String, Vec<Box<dyn AuditSink>>>,
+}
+
+impl AuditLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// record events of every key in `sink`.
+    pub fn add_sink(&mut self, sink: impl AuditSink + 'static) {
+        self.sinks.push(Box::new(sink));
+    }
+
+    /// record events of `public_key` only in `sink`.
+    pub fn add_key_sink(&mut self, public_key: &ProjectivePoint, sink: impl AuditSink + 'static) {
+        self.key_sinks
+            .entry(fingerprint(public_key))
+            .or_default()
+            .push(Box::new(sink));
+    }
+}
+
+impl AuditSink for AuditLog {
+    fn record(&self, event: &AuditEvent) -> Result<(), Error> {
+        let key_sinks = self.key_sinks.get(&event.key).into_iter().flatten();
+        self.sinks
+            .iter()
+            .chain(key_sinks)
+            .map(|sink| sink.record(event))
+            .fold(Ok(()), Result::and)
+    }
+}
+
+/// appends JSON lines to `path`. once the file would grow past `max_bytes`
+/// it is renamed to `path.1` (`path.1` to `path.2`, ...) and `keep` old
+/// files are kept.
+pub struct FileSink {
+    path: PathBuf,
+    max_bytes: u64,
+    keep: usize,
+    file: Mutex<(File, u64)>,
+}
+
+impl FileSink {
+    pub fn new(path: impl Into<PathBuf>, max_bytes: u64, keep: usize) -> Result<Self, Error> {
+        let path = path.into();
+        let file = open_append(&path)?;
+        let size = file.metadata().map_err(audit_error)?.len();
+
+        Ok(Self {
+            path,
+            max_bytes,
+            keep,
+            file: Mutex::new((file, size)),
+        })
+    }
+
+    fn rotated(&self, n: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", n));
+        name.into()
+    }
+
+    fn rotate(&self) -> Result<File, Error> {
+        if self.keep == 0 {
+            fs::remove_file(&self.path).map_err(audit_error)?;
+        } else {
+            for n in (1..self.keep).rev() {
+                if self.rotated(n).exists() {
+                    fs::rename(self.rotated(n), self.rotated(n + 1)).map_err(audit_error)?;
+                }
+            }
+            fs::rename(&self.path, self.rotated(1)).map_err(audit_error)?;
+        }
+
+        open_append(&self.path)
+    }
+}
+
+impl AuditSink for FileSink {
+    fn record(&self, event: &AuditEvent) -> Result<(), Error> {
+        let line = event.to_json() + "\n";
+        let mut file = self.file.lock().unwrap();
+        let (handle, size) = &mut *file;
+
+        if *size > 0 && *size + line.len() as u64 > self.max_bytes {
+            *handle = self.rotate()?;
+            *size = 0;
+        }
+        handle.write_all(line.as_bytes()).map_err(audit_error)?;
+        handle.flush().map_err(audit_error)?;
+        *size += line.len() as u64;
+
+        Ok(())
+    }
+}
+
+fn open_append(path: &Path) -> Result<File, Error> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(audit_error)
+}
+
+/// RFC 5424 syslog messages with facility authpriv, the JSON event as message.
+/// rejections are logged as warnings, everything else as notices.
+pub struct SyslogSink {
+    socket: SyslogSocket,
+    app_name: String,
+}
+
+enum SyslogSocket {
+    Udp(UdpSocket),
+    #[cfg(unix)]
+    Unix(std::os::unix::net::UnixDatagram),
+}
+
+impl SyslogSink {
+    const FACILITY_AUTHPRIV: u8 = 10;
+    const SEVERITY_WARNING: u8 = 4;
+    const SEVERITY_NOTICE: u8 = 5;
+
+    /// send to a remote collector, e.g. `"siem.example.com:514"`.
+    pub fn udp(addr: impl ToSocketAddrs, app_name: &str) -> Result<Self, Error> {
+        let socket = UdpSocket::bind("0.0.0.0:0").map_err(audit_error)?;
+        socket.connect(addr).map_err(audit_error)?;
+
+        Ok(Self {
+            socket: SyslogSocket::Udp(socket),
+            app_name: app_name.to_string(),
+        })
+    }
+
+    /// send to the local syslog daemon, usually at `/dev/log`.
+    #[cfg(unix)]
+    pub fn unix(path: impl AsRef<Path>, app_name: &str) -> Result<Self, Error> {
+        let socket = std::os::unix::net::UnixDatagram::unbound().map_err(audit_error)?;
+        socket.connect(path).map_err(audit_error)?;
+
+        Ok(Self {
+            socket: SyslogSocket::Unix(socket),
+            app_name: app_name.to_string(),
+        })
+    }
+
+    /// `<PRI>1 TIMESTAMP HOSTNAME APP-NAME PROCID MSGID SD MSG`, with
+    /// timestamp and hostname left to the collector.
+    pub fn format(&self, event: &AuditEvent) -> String {
+        let severity = match event.kind {
+            AuditKind::Rejected { .. } => Self::SEVERITY_WARNING,
+            _ => Self::SEVERITY_NOTICE,
+        };
+        format!(
+            "<{}>1 - - {} {} {} - {}",
+            Self::FACILITY_AUTHPRIV * 8 + severity,
+            self.app_name,
+            std::process::id(),
+            event.kind.name(),
+            event.to_json()
+        )
+    }
+}
+
+impl AuditSink for SyslogSink {
+    fn record(&self, event: &AuditEvent) -> Result<(), Error> {
+        let message = self.format(event);
+        match &self.socket {
+            SyslogSocket::Udp(socket) => socket.send(message.as_bytes()),
+            #[cfg(unix)]
+            SyslogSocket::Unix(socket) => socket.send(message.as_bytes()),
+        }
+        .map(|_| ())
+        .map_err(audit_error)
+    }
+}
+
+/// POSTs every event as JSON to a webhook, any non-2xx answer is an error.
+pub struct WebhookSink {
+    url: String,
+    headers: Vec<(String, String)>,
+    agent: ureq::Agent,
+}
+
+impl WebhookSink {
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// `url` must be https, events would otherwise leave the host in the clear.
+    pub fn new(url: &str) -> Result<Self, Error> {
+        if !url.starts_with("https://") {
+            return Err(Error::Audit(format!("Webhook {} is not https", url)));
+        }
+        Ok(Self::insecure(url))
+    }
+
+    /// like `new` but also accepts plain http, for a collector on localhost.
+    pub fn insecure(url: &str) -> Self {
+        let agent = ureq::Agent::config_builder()
+            .timeout_global(Some(Self::DEFAULT_TIMEOUT))
+            .build()
+            .into();
+
+        Self {
+            url: url.to_string(),
+            headers: Vec::new(),
+            agent,
+        }
+    }
+
+    /// extra header sent with every request, e.g. an authorization token.
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+impl AuditSink for WebhookSink {
+    fn record(&self, event: &AuditEvent) -> Result<(), Error> {
+        let mut request = self
+            .agent
+            .post(&self.url)
+            .header("Content-Type", "application/json");
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+
+        request
+            .send(event.to_json())
+            .map(|_| ())
+            .map_err(|e| Error::Audit(format!("Webhook {}: {}", self.url, e)))
+    }
+}
+
+fn audit_error(e: std::io::Error) -> Error {
+    Error::Audit(e.to_string())
+}
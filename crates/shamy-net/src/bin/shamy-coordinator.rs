@@ -0,0 +1,59 @@
+//! REST hub where signers behind firewalls meet to sign, see `shamy_net::http`.
+
+use clap::Parser;
+use shamy_core::schnorr::SigningConfig;
+use shamy_net::{
+    coordinator::Coordinator,
+    http,
+    store::{FileStore, MemoryStore},
+};
+use std::{fmt::Display, path::PathBuf, process};
+
+#[derive(Parser)]
+#[command(version, about = "Coordinate threshold signing sessions over HTTP")]
+struct Args {
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    listen: String,
+
+    #[arg(help = "Application the messages belong to, bound into the challenge")]
+    #[arg(long)]
+    app_id: String,
+
+    #[arg(help = "Purpose of the messages within the application")]
+    #[arg(long)]
+    purpose: String,
+
+    #[arg(help = "Keep sessions in this directory, shared with backup instances")]
+    #[arg(long)]
+    store_dir: Option<PathBuf>,
+
+    #[arg(help = "Name of this instance, unique among instances sharing a store")]
+    #[arg(long, default_value = "shamy-coordinator")]
+    name: String,
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let args = Args::parse();
+    let config = SigningConfig::new(&args.app_id, &args.purpose);
+    let router = match args.store_dir {
+        Some(dir) => {
+            let store = FileStore::new(dir).unwrap_or_else(|e| exit(e));
+            http::router(Coordinator::new(&args.name, store, config))
+        }
+        None => http::router(Coordinator::new(&args.name, MemoryStore::new(), config)),
+    };
+
+    let listener = tokio::net::TcpListener::bind(&args.listen)
+        .await
+        .unwrap_or_else(|e| exit(e));
+    eprintln!("Listening on http://{}", args.listen);
+    axum::serve(listener, router)
+        .await
+        .unwrap_or_else(|e| exit(e));
+}
+
+fn exit(e: impl Display) -> ! {
+    eprintln!("{}", e);
+    process::exit(1);
+}
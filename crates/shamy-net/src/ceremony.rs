@@ -0,0 +1,280 @@
+#![allow(non_snake_case)]
+
+use crate::error::Error;
+use crate::signer::SignerPolicy;
+use crate::transport::{RoundMessage, RoundTransport};
+use k256::{ProjectivePoint, Scalar};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use shamy_core::{
+    backend::{MemoryBackend, ShareBackend},
+    dkg::{self, DkgOutput, Round1Package, Round2Package},
+    proofs::SchnorrProof,
+    schnorr::{SchnorrSignature, SessionId, SigningConfig},
+    threshold::{
+        PartialSignature, Participant, SignerSet, SigningSession, aggregate_nonce_with_signers,
+    },
+    util::{hex_to_pp, hex_to_scalar, pp_to_hex, scalar_to_hex},
+};
+
+//--------------------------------------------------------------------
+// Ceremonies over a RoundTransport
+//--------------------------------------------------------------------
+//
+// DKG (every participant runs `dkg`)
+//   round 1: broadcast commitments
+//   round 2: echo digests of all commitments  (`echo_broadcast`)
+//   round 3: send fᵢ(j) to every j            ⚠️ secret, needs a private channel
+//
+// signing (one `sign_as_coordinator`, t × `sign_as_signer`)
+//   round 1: coordinator → signers   message and signer set
+//   round 2: signers → coordinator   Rᵢ
+//   round 3: coordinator → signers   all Rᵢ
+//   round 4: signers → coordinator   sᵢ
+//
+// signers recompute R and c themselves from round 3, so a coordinator
+// can't get them to answer a challenge for a different message.
+//
+
+#[derive(Serialize, Deserialize)]
+struct Round1Payload {
+    commitments: Vec<String>,
+    proof_R: String,
+    proof_z: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Round2Payload {
+    share: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SignRequest {
+    message: String,
+    signer_ids: Vec<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NonceMessage {
+    nonce: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NoncesMessage {
+    nonces: Vec<(u64, String)>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PartialMessage {
+    s_i: String,
+}
+
+fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+    serde_json::to_vec(value).unwrap()
+}
+
+fn decode<T: DeserializeOwned>(message: &RoundMessage) -> Result<T, Error> {
+    serde_json::from_slice(&message.payload).map_err(|e| {
+        Error::InvalidMessage(format!(
+            "round {} from {}: {}",
+            message.round, message.sender, e
+        ))
+    })
+}
+
+/// run a t-of-n DKG as participant `transport.id()` with participants 1..=n.
+pub async fn dkg<T: RoundTransport>(
+    transport: &mut T,
+    t: usize,
+    n: usize,
+) -> Result<DkgOutput, Error> {
+    let id = transport.id();
+    let peers = (1..=n as u64).filter(|p| *p != id).collect::<Vec<_>>();
+
+    let (secret, package) = dkg::round1(id, t, n)?;
+    let payload = Round1Payload {
+        commitments: package.commitments.iter().map(pp_to_hex).collect(),
+        proof_R: pp_to_hex(&package.proof.R),
+        proof_z: scalar_to_hex(&package.proof.z),
+    };
+    // a participant that sends different commitments to different peers
+    // would split the group into holders of different keys
+    let messages = transport
+        .echo_broadcast(&peers, 1, encode(&payload))
+        .await?;
+
+    let mut round1_packages = vec![package];
+    for message in messages {
+        let payload: Round1Payload = decode(&message)?;
+        round1_packages.push(Round1Package {
+            id: message.sender,
+            commitments: payload
+                .commitments
+                .iter()
+                .map(|C| hex_to_pp(C))
+                .collect::<Result<_, _>>()?,
+            proof: SchnorrProof {
+                R: hex_to_pp(&payload.proof_R)?,
+                z: hex_to_scalar(&payload.proof_z)?,
+            },
+        });
+    }
+
+    for package in dkg::round2(&secret, &round1_packages)? {
+        let payload = Round2Payload {
+            share: scalar_to_hex(&package.share),
+        };
+        transport
+            .send(package.receiver, 3, encode(&payload))
+            .await?;
+    }
+
+    let mut round2_packages = Vec::with_capacity(peers.len());
+    for message in transport.receive(3, &peers).await? {
+        let payload: Round2Payload = decode(&message)?;
+        round2_packages.push(Round2Package {
+            sender: message.sender,
+            receiver: id,
+            share: hex_to_scalar(&payload.share)?,
+        });
+    }
+
+    Ok(dkg::finalize(&secret, &round1_packages, &round2_packages)?)
+}
+
+/// drive a signing session as coordinator with the given signers.
+pub async fn sign_as_coordinator<T: RoundTransport>(
+    transport: &mut T,
+    public_key: ProjectivePoint,
+    message: &[u8],
+    signer_ids: &[u64],
+    config: SigningConfig,
+) -> Result<SchnorrSignature, Error> {
+    let mut session = SigningSession::new(public_key, message, signer_ids, config)?;
+
+    let request = SignRequest {
+        message: hex::encode(message),
+        signer_ids: signer_ids.to_vec(),
+    };
+    transport.broadcast(signer_ids, 1, encode(&request)).await?;
+
+    let mut nonces = Vec::with_capacity(signer_ids.len());
+    for reply in transport.receive(2, signer_ids).await? {
+        let nonce: NonceMessage = decode(&reply)?;
+        let R_i = hex_to_pp(&nonce.nonce)?;
+        session.add_nonce(reply.sender, R_i)?;
+        nonces.push((reply.sender, nonce.nonce));
+    }
+    transport
+        .broadcast(signer_ids, 3, encode(&NoncesMessage { nonces }))
+        .await?;
+
+    for reply in transport.receive(4, signer_ids).await? {
+        let partial: PartialMessage = decode(&reply)?;
+        session.add_partial(PartialSignature::new(
+            reply.sender,
+            hex_to_scalar(&partial.s_i)?,
+        ))?;
+    }
+
+    Ok(session.finalize()?)
+}
+
+/// take part in one signing session run by `coordinator`.
+/// `public_key` and `config` must be known to the signer out of band.
+pub async fn sign_as_signer<T: RoundTransport>(
+    transport: &mut T,
+    coordinator: u64,
+    participant: &Participant,
+    public_key: &ProjectivePoint,
+    config: &SigningConfig,
+) -> Result<(), Error> {
+    let policy = SignerPolicy::allow_any();
+    sign_as_signer_with_policy(
+        transport,
+        coordinator,
+        participant,
+        public_key,
+        config,
+        &policy,
+    )
+    .await
+    .map(|_| ())
+}
+
+/// `sign_as_signer` that only commits to a nonce if `policy` allows the
+/// requested message, returns the message it signed.
+/// a rejected request is not answered, the coordinator times out.
+pub async fn sign_as_signer_with_policy<T: RoundTransport>(
+    transport: &mut T,
+    coordinator: u64,
+    participant: &Participant,
+    public_key: &ProjectivePoint,
+    config: &SigningConfig,
+    policy: &SignerPolicy,
+) -> Result<Vec<u8>, Error> {
+    let backend = MemoryBackend::new(*participant);
+    sign_as_signer_with_backend(transport, coordinator, &backend, public_key, config, policy).await
+}
+
+/// `sign_as_signer_with_policy` with the share held by `backend`, e.g. an
+/// HSM. the nonce is discarded if the session fails after the commit.
+pub async fn sign_as_signer_with_backend<T: RoundTransport, B: ShareBackend + Sync>(
+    transport: &mut T,
+    coordinator: u64,
+    backend: &B,
+    public_key: &ProjectivePoint,
+    config: &SigningConfig,
+    policy: &SignerPolicy,
+) -> Result<Vec<u8>, Error> {
+    let id = backend.id();
+    let request = transport.receive(1, &[coordinator]).await?.remove(0);
+    let request: SignRequest = decode(&request)?;
+    let message = hex::decode(&request.message)
+        .map_err(|e| Error::InvalidMessage(format!("message: {}", e)))?;
+    if !request.signer_ids.contains(&id) {
+        return Err(Error::UnexpectedSigner(id));
+    }
+    policy.check(&message)?;
+
+    // the backend's handle for this nonce, not the session id of the challenge
+    let session = SessionId::random();
+    let R_own = backend.commit(&session)?;
+    let signed = async {
+        let nonce = NonceMessage {
+            nonce: pp_to_hex(&R_own),
+        };
+        transport.send(coordinator, 2, encode(&nonce)).await?;
+
+        let reply = transport.receive(3, &[coordinator]).await?.remove(0);
+        let reply: NoncesMessage = decode(&reply)?;
+        let nonces = reply
+            .nonces
+            .iter()
+            .map(|(id, R_i)| Ok((*id, hex_to_pp(R_i)?)))
+            .collect::<Result<Vec<_>, Error>>()?;
+        let ids = nonces.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+        if ids != request.signer_ids || !nonces.contains(&(id, R_own)) {
+            return Err(Error::InvalidMessage(
+                "nonces don't match the signer set or our own nonce".to_string(),
+            ));
+        }
+
+        let R = aggregate_nonce_with_signers(&nonces, &SignerSet::new(&ids, ids.len())?)?;
+        let c: Scalar = config.challenge(&R, public_key, &message);
+        Ok(backend.partial_sign(&c, &session)?)
+    }
+    .await;
+    let partial = match signed {
+        Ok(partial) => partial,
+        Err(e) => {
+            backend.discard(&session)?;
+            return Err(e);
+        }
+    };
+
+    let partial = PartialMessage {
+        s_i: scalar_to_hex(&partial.s_i),
+    };
+    transport.send(coordinator, 4, encode(&partial)).await?;
+    Ok(message)
+}
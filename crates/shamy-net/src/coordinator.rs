@@ -0,0 +1,260 @@
+#![allow(non_snake_case)]
+
+use crate::audit::{AuditEvent, AuditKind, AuditSink};
+use crate::error::Error;
+use crate::store::{SessionState, SessionStore};
+use k256::{ProjectivePoint, Scalar};
+use sha2::{Digest, Sha256};
+use shamy_core::{
+    schnorr::{SchnorrSignature, SigningConfig},
+    threshold::{
+        PartialSignature, SignerSet, aggregate_nonce_with_signers, finalize_signature_with_signers,
+    },
+    util::{pp_to_hex, scalar_to_hex},
+};
+use std::{collections::BTreeMap, sync::Arc};
+
+/// coordinator of threshold signing sessions whose state lives in a shared
+/// `SessionStore`. if the instance owning a session dies mid-round, a backup
+/// instance calls `take_over` and resumes with the nonce commitments and
+/// partials already collected, so signers don't have to start over.
+///
+/// safety:
+/// - every write is a compare-and-swap on the session version
+/// - only the current owner may write, a takeover fences the previous owner
+/// - a finalized session is immutable, so it can't produce a second signature
+///
+/// with an audit sink every accepted write is recorded after it reached the
+/// store, an `Error::Audit` then means the write itself went through.
+/// rejected messages are recorded on a best effort basis.
+pub struct Coordinator<S> {
+    name: String,
+    store: S,
+    config: SigningConfig,
+    audit: Option<Arc<dyn AuditSink>>,
+}
+
+impl<S: SessionStore> Coordinator<S> {
+    /// `name` must be unique per instance, `config` identical across instances.
+    pub fn new(name: &str, store: S, config: SigningConfig) -> Self {
+        Self {
+            name: name.to_string(),
+            store,
+            config,
+            audit: None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// record session events in `sink`, e.g. an `AuditLog`.
+    pub fn set_audit(&mut self, sink: Arc<dyn AuditSink>) {
+        self.audit = Some(sink);
+    }
+
+    pub fn create_session(
+        &self,
+        session_id: &str,
+        message: &[u8],
+        public_key: ProjectivePoint,
+        signer_ids: &[u64],
+    ) -> Result<(), Error> {
+        SignerSet::new(signer_ids, signer_ids.len())?;
+        let state = SessionState {
+            session_id: session_id.to_string(),
+            message: message.to_vec(),
+            public_key,
+            signer_ids: signer_ids.to_vec(),
+            nonces: BTreeMap::new(),
+            partials: BTreeMap::new(),
+            signature: None,
+            owner: self.name.clone(),
+            epoch: 1,
+            version: 1,
+        };
+
+        self.store
+            .compare_and_swap(None, &state)
+            .map_err(|e| match e {
+                Error::Conflict { .. } => Error::SessionExists(session_id.to_string()),
+                e => e,
+            })?;
+
+        self.audit(
+            &state,
+            AuditKind::SessionCreated {
+                message_sha256: hex::encode(Sha256::digest(message)),
+                signer_ids: signer_ids.to_vec(),
+            },
+        )
+    }
+
+    /// become the owner of a session, fencing off the previous owner.
+    pub fn take_over(&self, session_id: &str) -> Result<SessionState, Error> {
+        let mut state = self.load(session_id)?;
+        let expected = state.version;
+
+        state.owner = self.name.clone();
+        state.epoch += 1;
+        state.version += 1;
+        self.store.compare_and_swap(Some(expected), &state)?;
+
+        self.audit(&state, AuditKind::TakenOver { epoch: state.epoch })?;
+        Ok(state)
+    }
+
+    pub fn state(&self, session_id: &str) -> Result<SessionState, Error> {
+        self.load(session_id)
+    }
+
+    pub fn submit_nonce(
+        &self,
+        session_id: &str,
+        id: u64,
+        R_i: ProjectivePoint,
+    ) -> Result<(), Error> {
+        self.update(session_id, |state| {
+            check_signer(state, id)?;
+            if state.nonces.contains_key(&id) {
+                return Err(Error::DuplicateMessage(id));
+            }
+            state.nonces.insert(id, R_i);
+            let nonce = pp_to_hex(&R_i);
+            Ok(((), AuditKind::NonceReceived { id, nonce }))
+        })
+    }
+
+    /// aggregate nonce R and challenge c, available once every signer's nonce arrived.
+    pub fn challenge(&self, session_id: &str) -> Result<(ProjectivePoint, Scalar), Error> {
+        let state = self.load(session_id)?;
+        self.compute_challenge(&state)
+    }
+
+    pub fn submit_partial(&self, session_id: &str, id: u64, s_i: Scalar) -> Result<(), Error> {
+        self.update(session_id, |state| {
+            check_signer(state, id)?;
+            check_complete(&state.signer_ids, &state.nonces)?;
+            if state.partials.contains_key(&id) {
+                return Err(Error::DuplicateMessage(id));
+            }
+            state.partials.insert(id, s_i);
+            Ok(((), AuditKind::PartialReceived { id }))
+        })
+    }
+
+    /// combine the partials, verify the result and record it in the store.
+    pub fn finalize(&self, session_id: &str) -> Result<SchnorrSignature, Error> {
+        self.update(session_id, |state| {
+            check_complete(&state.signer_ids, &state.partials)?;
+            let (R, _) = self.compute_challenge(state)?;
+
+            let partials = state
+                .partials
+                .iter()
+                .map(|(id, s_i)| PartialSignature::new(*id, *s_i))
+                .collect::<Vec<_>>();
+            let signature = finalize_signature_with_signers(&partials, R, &signer_set(state)?)?;
+            if !signature.verify_with_config(&state.message, &state.public_key, &self.config) {
+                return Err(Error::InvalidSignature);
+            }
+
+            state.signature = Some(signature);
+            let event = AuditKind::Finalized {
+                nonce: pp_to_hex(&signature.R),
+                signature: scalar_to_hex(&signature.s),
+            };
+            Ok((signature, event))
+        })
+    }
+
+    fn compute_challenge(&self, state: &SessionState) -> Result<(ProjectivePoint, Scalar), Error> {
+        check_complete(&state.signer_ids, &state.nonces)?;
+
+        let nonces = state
+            .nonces
+            .iter()
+            .map(|(id, R_i)| (*id, *R_i))
+            .collect::<Vec<_>>();
+        let R = aggregate_nonce_with_signers(&nonces, &signer_set(state)?)?;
+        let c = self.config.challenge(&R, &state.public_key, &state.message);
+
+        Ok((R, c))
+    }
+
+    fn load(&self, session_id: &str) -> Result<SessionState, Error> {
+        self.store
+            .load(session_id)?
+            .ok_or(Error::UnknownSession(session_id.to_string()))
+    }
+
+    /// read-modify-write of a session owned by this instance,
+    /// `f` also returns the audit event for the change.
+    fn update<T>(
+        &self,
+        session_id: &str,
+        f: impl FnOnce(&mut SessionState) -> Result<(T, AuditKind), Error>,
+    ) -> Result<T, Error> {
+        let mut state = self.load(session_id)?;
+        let expected = state.version;
+
+        let checked = match (&state.owner, &state.signature) {
+            (owner, _) if *owner != self.name => Err(Error::Fenced {
+                owner: owner.clone(),
+                epoch: state.epoch,
+            }),
+            (_, Some(_)) => Err(Error::AlreadyFinalized),
+            _ => f(&mut state),
+        };
+        let (result, event) = match checked {
+            Ok(checked) => checked,
+            Err(e) => {
+                let reason = e.to_string();
+                let _ = self.audit(&state, AuditKind::Rejected { reason });
+                return Err(e);
+            }
+        };
+        state.version += 1;
+        self.store.compare_and_swap(Some(expected), &state)?;
+
+        self.audit(&state, event)?;
+        Ok(result)
+    }
+
+    fn audit(&self, state: &SessionState, kind: AuditKind) -> Result<(), Error> {
+        match &self.audit {
+            Some(sink) => sink.record(&AuditEvent::new(
+                &state.public_key,
+                &state.session_id,
+                &self.name,
+                kind,
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+fn check_signer(state: &SessionState, id: u64) -> Result<(), Error> {
+    match state.signer_ids.contains(&id) {
+        true => Ok(()),
+        false => Err(Error::UnexpectedSigner(id)),
+    }
+}
+
+fn signer_set(state: &SessionState) -> Result<SignerSet, Error> {
+    Ok(SignerSet::new(&state.signer_ids, state.signer_ids.len())?)
+}
+
+fn check_complete<T>(signer_ids: &[u64], received: &BTreeMap<u64, T>) -> Result<(), Error> {
+    let missing = signer_ids
+        .iter()
+        .filter(|id| !received.contains_key(id))
+        .copied()
+        .collect::<Vec<_>>();
+
+    match missing.is_empty() {
+        true => Ok(()),
+        false => Err(Error::Incomplete { missing }),
+    }
+}
@@ -0,0 +1,105 @@
+use std::fmt;
+
+/// errors returned by shamy-net.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// the storage backend failed.
+    Store(String),
+    /// another writer changed the session since it was read.
+    Conflict { session_id: String },
+    /// no session with this id exists.
+    UnknownSession(String),
+    /// a session with this id already exists.
+    SessionExists(String),
+    /// another coordinator instance has taken over the session.
+    Fenced { owner: String, epoch: u64 },
+    /// the participant is not part of the session's signer set.
+    UnexpectedSigner(u64),
+    /// the participant already submitted a message for this step.
+    DuplicateMessage(u64),
+    /// the operation needs messages that have not arrived yet.
+    Incomplete { missing: Vec<u64> },
+    /// a nonce batch does not continue where the signer's previous one ended.
+    UnexpectedNonceIndex { id: u64, expected: u64, actual: u64 },
+    /// the nonce was never committed or has already been used.
+    UnknownNonce { id: u64, index: u64 },
+    /// the session already produced a signature.
+    AlreadyFinalized,
+    /// the aggregated signature does not verify.
+    InvalidSignature,
+    /// sending to or receiving from a peer failed.
+    Transport(String),
+    /// not every expected peer answered in time.
+    Timeout { round: u8, missing: Vec<u64> },
+    /// a peer sent a message that can't be decoded or doesn't fit the round.
+    InvalidMessage(String),
+    /// the protocol rejected a message.
+    Core(shamy_core::Error),
+    /// an audit event could not be recorded.
+    Audit(String),
+    /// participants saw different messages from these senders in an echo broadcast.
+    Equivocation { round: u8, ids: Vec<u64> },
+    /// the signer's policy doesn't allow signing this request.
+    Rejected(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Store(e) => write!(f, "Session store error: {}", e),
+            Error::Conflict { session_id } => {
+                write!(f, "Session {} was modified concurrently", session_id)
+            }
+            Error::UnknownSession(id) => write!(f, "Unknown session {}", id),
+            Error::SessionExists(id) => write!(f, "Session {} already exists", id),
+            Error::Fenced { owner, epoch } => write!(
+                f,
+                "Session is owned by coordinator {} (epoch {})",
+                owner, epoch
+            ),
+            Error::UnexpectedSigner(id) => write!(f, "Participant {} is not a signer", id),
+            Error::DuplicateMessage(id) => {
+                write!(f, "Participant {} already submitted this round", id)
+            }
+            Error::Incomplete { missing } => write!(f, "Still waiting for {:?}", missing),
+            Error::UnexpectedNonceIndex {
+                id,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Nonce batch of participant {} starts at index {}, expected {}",
+                id, actual, expected
+            ),
+            Error::UnknownNonce { id, index } => write!(
+                f,
+                "Nonce {} of participant {} is unknown or already used",
+                index, id
+            ),
+            Error::AlreadyFinalized => write!(f, "Session is already finalized"),
+            Error::InvalidSignature => write!(f, "Aggregated signature is invalid"),
+            Error::Transport(e) => write!(f, "Transport error: {}", e),
+            Error::Timeout { round, missing } => {
+                write!(f, "Timed out in round {} waiting for {:?}", round, missing)
+            }
+            Error::InvalidMessage(e) => write!(f, "Invalid message: {}", e),
+            Error::Core(e) => write!(f, "{}", e),
+            Error::Audit(e) => write!(f, "Audit error: {}", e),
+            Error::Rejected(reason) => write!(f, "Request rejected: {}", reason),
+            Error::Equivocation { round, ids } => write!(
+                f,
+                "Participants {:?} sent conflicting round {} messages",
+                ids, round
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<shamy_core::Error> for Error {
+    fn from(e: shamy_core::Error) -> Self {
+        Error::Core(e)
+    }
+}
@@ -0,0 +1,135 @@
+#![allow(non_snake_case)]
+
+use crate::coordinator::Coordinator;
+use crate::error::Error;
+use crate::store::SessionStore;
+use crate::wire::{self, point_from_bytes, point_to_bytes, scalar_from_bytes, scalar_to_bytes};
+use tonic::{Request, Response, Status};
+
+#[allow(clippy::all)]
+mod generated {
+    include!(concat!(env!("OUT_DIR"), "/shamy.v1.Coordinator.rs"));
+}
+
+pub use generated::{coordinator_client::CoordinatorClient, coordinator_server::CoordinatorServer};
+
+/// `shamy.v1.Coordinator` gRPC service backed by a `Coordinator`,
+/// so signers written in any language can join a session.
+/// ⚠️ serve it over TLS, requests are not authenticated otherwise.
+pub struct CoordinatorService<S> {
+    coordinator: Coordinator<S>,
+}
+
+impl<S: SessionStore + Send + Sync + 'static> CoordinatorService<S> {
+    pub fn new(coordinator: Coordinator<S>) -> Self {
+        Self { coordinator }
+    }
+
+    /// tower service to add to a `tonic::transport::Server`.
+    pub fn into_server(self) -> CoordinatorServer<Self> {
+        CoordinatorServer::new(self)
+    }
+}
+
+#[tonic::async_trait]
+impl<S: SessionStore + Send + Sync + 'static> generated::coordinator_server::Coordinator
+    for CoordinatorService<S>
+{
+    async fn create_session(
+        &self,
+        request: Request<wire::CreateSessionRequest>,
+    ) -> Result<Response<wire::CreateSessionResponse>, Status> {
+        let request = request.into_inner();
+        let public_key = point_from_bytes(&request.public_key)?;
+        self.coordinator.create_session(
+            &request.session_id,
+            &request.message,
+            public_key,
+            &request.signer_ids,
+        )?;
+
+        Ok(Response::new(wire::CreateSessionResponse {}))
+    }
+
+    async fn submit_nonce(
+        &self,
+        request: Request<wire::SubmitNonceRequest>,
+    ) -> Result<Response<wire::SubmitNonceResponse>, Status> {
+        let request = request.into_inner();
+        let nonce = request.nonce.ok_or(missing_field("nonce"))?;
+        self.coordinator
+            .submit_nonce(&request.session_id, nonce.id, nonce.point()?)?;
+
+        Ok(Response::new(wire::SubmitNonceResponse {}))
+    }
+
+    async fn get_challenge(
+        &self,
+        request: Request<wire::GetChallengeRequest>,
+    ) -> Result<Response<wire::Challenge>, Status> {
+        let (R, c) = self
+            .coordinator
+            .challenge(&request.into_inner().session_id)?;
+
+        Ok(Response::new(wire::Challenge {
+            nonce: point_to_bytes(&R),
+            challenge: scalar_to_bytes(&c),
+        }))
+    }
+
+    async fn submit_partial(
+        &self,
+        request: Request<wire::SubmitPartialRequest>,
+    ) -> Result<Response<wire::SubmitPartialResponse>, Status> {
+        let request = request.into_inner();
+        let partial = request.partial.ok_or(missing_field("partial"))?;
+        self.coordinator.submit_partial(
+            &request.session_id,
+            partial.id,
+            scalar_from_bytes(&partial.s_i)?,
+        )?;
+
+        Ok(Response::new(wire::SubmitPartialResponse {}))
+    }
+
+    async fn finalize(
+        &self,
+        request: Request<wire::FinalizeRequest>,
+    ) -> Result<Response<wire::Signature>, Status> {
+        let signature = self
+            .coordinator
+            .finalize(&request.into_inner().session_id)?;
+
+        Ok(Response::new(wire::Signature::from(&signature)))
+    }
+}
+
+fn missing_field(name: &str) -> Error {
+    Error::InvalidMessage(format!("missing field {}", name))
+}
+
+impl From<Error> for Status {
+    fn from(e: Error) -> Self {
+        let message = e.to_string();
+        match e {
+            Error::UnknownSession(_) => Status::not_found(message),
+            Error::SessionExists(_) => Status::already_exists(message),
+            Error::Conflict { .. } | Error::Equivocation { .. } => Status::aborted(message),
+            Error::Fenced { .. }
+            | Error::Incomplete { .. }
+            | Error::AlreadyFinalized
+            | Error::UnknownNonce { .. }
+            | Error::UnexpectedNonceIndex { .. } => Status::failed_precondition(message),
+            Error::UnexpectedSigner(_)
+            | Error::DuplicateMessage(_)
+            | Error::InvalidMessage(_)
+            | Error::InvalidSignature
+            | Error::Core(_) => Status::invalid_argument(message),
+            Error::Store(_) | Error::Transport(_) | Error::Timeout { .. } => {
+                Status::unavailable(message)
+            }
+            Error::Rejected(_) => Status::permission_denied(message),
+            Error::Audit(_) => Status::internal(message),
+        }
+    }
+}
@@ -0,0 +1,215 @@
+#![allow(non_snake_case)]
+
+use crate::coordinator::Coordinator;
+use crate::error::Error;
+use crate::store::{SessionState, SessionStore};
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use shamy_core::util::{hex_to_pp, hex_to_scalar, pp_to_hex, scalar_to_hex};
+use std::sync::Arc;
+
+//--------------------------------------------------------------------
+// REST coordinator API
+//--------------------------------------------------------------------
+//
+//   POST /sessions                  {session_id, message, public_key, signer_ids}
+//   GET  /sessions/{id}             signer set, who submitted, signature
+//   POST /sessions/{id}/nonces      {id, nonce}
+//   GET  /sessions/{id}/challenge   {nonce, challenge} once every nonce arrived
+//   POST /sessions/{id}/partials    {id, s_i}
+//   POST /sessions/{id}/finalize    {nonce, s, signature}
+//
+// points, scalars and the message are hex. errors come back as
+// {"error": "..."} with 404 for an unknown session, 409 for a request
+// the session isn't ready for (or past), 400 for an invalid one.
+//
+// signers only make outgoing requests, so they can sit behind a
+// firewall and poll `challenge` until it stops answering 409.
+//
+// ⚠️ requests are not authenticated, serve it behind a TLS proxy that
+//    checks client certificates or tokens.
+//
+
+#[derive(Deserialize)]
+struct CreateSession {
+    session_id: String,
+    message: String,
+    public_key: String,
+    signer_ids: Vec<u64>,
+}
+
+#[derive(Deserialize)]
+struct SubmitNonce {
+    id: u64,
+    nonce: String,
+}
+
+#[derive(Deserialize)]
+struct SubmitPartial {
+    id: u64,
+    s_i: String,
+}
+
+/// routes of the REST API backed by `coordinator`, serve with `axum::serve`.
+pub fn router<S: SessionStore + Send + Sync + 'static>(coordinator: Coordinator<S>) -> Router {
+    Router::new()
+        .route("/sessions", post(create_session::<S>))
+        .route("/sessions/{id}", get(session::<S>))
+        .route("/sessions/{id}/nonces", post(submit_nonce::<S>))
+        .route("/sessions/{id}/challenge", get(challenge::<S>))
+        .route("/sessions/{id}/partials", post(submit_partial::<S>))
+        .route("/sessions/{id}/finalize", post(finalize::<S>))
+        .with_state(Arc::new(coordinator))
+}
+
+type Shared<S> = State<Arc<Coordinator<S>>>;
+
+async fn create_session<S: SessionStore + Send + Sync + 'static>(
+    State(coordinator): Shared<S>,
+    Json(request): Json<CreateSession>,
+) -> Result<(StatusCode, Json<Value>), Error> {
+    let message = hex::decode(&request.message)
+        .map_err(|e| Error::InvalidMessage(format!("message: {}", e)))?;
+    let public_key = hex_to_pp(&request.public_key)?;
+    let session_id = request.session_id.clone();
+    blocking(coordinator, move |c| {
+        c.create_session(
+            &request.session_id,
+            &message,
+            public_key,
+            &request.signer_ids,
+        )
+    })
+    .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(json!({ "session_id": session_id })),
+    ))
+}
+
+async fn session<S: SessionStore + Send + Sync + 'static>(
+    State(coordinator): Shared<S>,
+    Path(session_id): Path<String>,
+) -> Result<Json<Value>, Error> {
+    let state = blocking(coordinator, move |c| c.state(&session_id)).await?;
+    Ok(Json(state_to_json(&state)))
+}
+
+async fn submit_nonce<S: SessionStore + Send + Sync + 'static>(
+    State(coordinator): Shared<S>,
+    Path(session_id): Path<String>,
+    Json(request): Json<SubmitNonce>,
+) -> Result<StatusCode, Error> {
+    let R_i = hex_to_pp(&request.nonce)?;
+    blocking(coordinator, move |c| {
+        c.submit_nonce(&session_id, request.id, R_i)
+    })
+    .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn challenge<S: SessionStore + Send + Sync + 'static>(
+    State(coordinator): Shared<S>,
+    Path(session_id): Path<String>,
+) -> Result<Json<Value>, Error> {
+    let (R, c) = blocking(coordinator, move |c| c.challenge(&session_id)).await?;
+    Ok(Json(json!({
+        "nonce": pp_to_hex(&R),
+        "challenge": scalar_to_hex(&c),
+    })))
+}
+
+async fn submit_partial<S: SessionStore + Send + Sync + 'static>(
+    State(coordinator): Shared<S>,
+    Path(session_id): Path<String>,
+    Json(request): Json<SubmitPartial>,
+) -> Result<StatusCode, Error> {
+    let s_i = hex_to_scalar(&request.s_i)?;
+    blocking(coordinator, move |c| {
+        c.submit_partial(&session_id, request.id, s_i)
+    })
+    .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn finalize<S: SessionStore + Send + Sync + 'static>(
+    State(coordinator): Shared<S>,
+    Path(session_id): Path<String>,
+) -> Result<Json<Value>, Error> {
+    let signature = blocking(coordinator, move |c| c.finalize(&session_id)).await?;
+    Ok(Json(json!({
+        "nonce": pp_to_hex(&signature.R),
+        "s": scalar_to_hex(&signature.s),
+        "signature": hex::encode(signature.to_bytes()?),
+    })))
+}
+
+/// stores may block on files or locks, keep them off the async workers.
+async fn blocking<S, T>(
+    coordinator: Arc<Coordinator<S>>,
+    f: impl FnOnce(&Coordinator<S>) -> Result<T, Error> + Send + 'static,
+) -> Result<T, Error>
+where
+    S: SessionStore + Send + Sync + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(move || f(&coordinator))
+        .await
+        .map_err(|e| Error::Store(e.to_string()))?
+}
+
+fn state_to_json(state: &SessionState) -> Value {
+    json!({
+        "session_id": state.session_id,
+        "message": hex::encode(&state.message),
+        "public_key": pp_to_hex(&state.public_key),
+        "signer_ids": state.signer_ids,
+        "nonces": state
+            .nonces
+            .iter()
+            .map(|(id, R_i)| (id.to_string(), json!(pp_to_hex(R_i))))
+            .collect::<serde_json::Map<_, _>>(),
+        "partials": state.partials.keys().collect::<Vec<_>>(),
+        "signature": state
+            .signature
+            .and_then(|s| s.to_bytes().ok())
+            .map(hex::encode),
+        "owner": state.owner,
+        "epoch": state.epoch,
+    })
+}
+
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            Error::UnknownSession(_) => StatusCode::NOT_FOUND,
+            Error::SessionExists(_)
+            | Error::Conflict { .. }
+            | Error::Fenced { .. }
+            | Error::Incomplete { .. }
+            | Error::AlreadyFinalized
+            | Error::UnknownNonce { .. }
+            | Error::UnexpectedNonceIndex { .. }
+            | Error::Equivocation { .. } => StatusCode::CONFLICT,
+            Error::UnexpectedSigner(_)
+            | Error::DuplicateMessage(_)
+            | Error::InvalidMessage(_)
+            | Error::InvalidSignature
+            | Error::Core(_) => StatusCode::BAD_REQUEST,
+            Error::Store(_) | Error::Transport(_) | Error::Timeout { .. } => {
+                StatusCode::SERVICE_UNAVAILABLE
+            }
+            Error::Rejected(_) => StatusCode::FORBIDDEN,
+            Error::Audit(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, Json(json!({ "error": self.to_string() }))).into_response()
+    }
+}
@@ -0,0 +1,22 @@
+//! networking for shamy ceremonies.
+//!
+//! transports, coordinators and daemons live in this crate so that
+//! `shamy-core` stays free of async runtimes and network dependencies.
+
+pub mod audit;
+pub mod ceremony;
+pub mod coordinator;
+pub mod error;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+#[cfg(feature = "http")]
+pub mod http;
+pub mod pipeline;
+pub mod signer;
+pub mod store;
+pub mod transport;
+pub mod wire;
+#[cfg(feature = "ws")]
+pub mod ws;
+
+pub use error::Error;
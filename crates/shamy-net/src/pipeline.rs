@@ -0,0 +1,288 @@
+#![allow(non_snake_case)]
+
+use crate::error::Error;
+use k256::ProjectivePoint;
+use shamy_core::{
+    frost::{NonceCommitment, NoncePool, group_nonce_with_config},
+    schnorr::{SchnorrSignature, SigningConfig},
+    threshold::{PartialSignature, Participant, SignerSet, finalize_signature_with_signers},
+};
+use std::collections::{BTreeMap, VecDeque};
+
+//--------------------------------------------------------------------
+// Pipelined signing
+//--------------------------------------------------------------------
+//
+// signers pre-commit batches of FROST nonce pairs (D_i[k], E_i[k]) for
+// future sessions. the coordinator binds the next unused pair of every
+// signer to each new message, so R is known as soon as the message arrives:
+//
+//   message k   : ... partials still arriving ...
+//   message k+1 : commitments picked, ticket sent out right away
+//
+// one round trip per signature instead of two.
+//
+// every signer recomputes its nonce from the ticket, see frost.rs:
+//
+//   ρ_i = H(X, m, B, i),   R_i = D_i + ρ_i*E_i,   c = H(R, X, m)
+//
+// a signer never signs a c handed to it. ρ_i binds each nonce to the
+// message and the whole commitment list B, so running many sessions
+// concurrently doesn't open a ROS style forgery like single pre-committed
+// nonces would.
+//
+// ⚠️ every nonce index is used for exactly one session, a signer must never
+//    sign twice with the same index (`SignerNonces` enforces that).
+//
+
+/// a signer's batch of nonce pair commitments `(D, E)`, indices
+/// `first_index..first_index + len`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NonceCommitments {
+    pub id: u64,
+    pub first_index: u64,
+    pub nonces: Vec<(ProjectivePoint, ProjectivePoint)>,
+}
+
+/// signer side: secret nonce pairs of pre-committed batches, each usable once.
+pub struct SignerNonces {
+    next_index: u64,
+    pool: NoncePool,
+}
+
+impl SignerNonces {
+    pub fn new(id: u64) -> Self {
+        Self {
+            next_index: 0,
+            pool: NoncePool::new(id),
+        }
+    }
+
+    /// generate `count` nonce pairs and return their commitments for the coordinator.
+    pub fn commit(&mut self, count: usize) -> NonceCommitments {
+        let first_index = self.next_index;
+        let nonces = self
+            .pool
+            .generate(count)
+            .into_iter()
+            .map(|commitment| (commitment.D, commitment.E))
+            .collect::<Vec<_>>();
+        self.next_index += nonces.len() as u64;
+
+        NonceCommitments {
+            id: self.pool.id(),
+            first_index,
+            nonces,
+        }
+    }
+
+    /// sign the ticket's message with the pair the ticket assigns to this
+    /// signer, which is then deleted. ρ_i, R and c are recomputed from the
+    /// message and the commitment list, `config` must be the coordinator's.
+    pub fn sign(
+        &mut self,
+        participant: &Participant,
+        ticket: &SessionTicket,
+        public_key: &ProjectivePoint,
+        config: &SigningConfig,
+    ) -> Result<PartialSignature, Error> {
+        self.pool
+            .sign(
+                participant,
+                &ticket.commitments,
+                public_key,
+                &ticket.message,
+                config,
+            )
+            .map_err(|e| match e {
+                shamy_core::Error::NonceReused { id, index }
+                | shamy_core::Error::UnknownNonce { id, index } => {
+                    Error::UnknownNonce { id, index }
+                }
+                e => e.into(),
+            })
+    }
+
+    /// number of committed nonce pairs not used yet.
+    pub fn remaining(&self) -> usize {
+        self.pool.remaining()
+    }
+}
+
+/// what signers need to produce their partial for a started session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionTicket {
+    pub seq: u64,
+    pub message: Vec<u8>,
+    /// the nonce pair each signer has to use, one per signer.
+    pub commitments: Vec<NonceCommitment>,
+}
+
+impl SessionTicket {
+    /// nonce index signer `id` has to use.
+    pub fn nonce_index(&self, id: u64) -> Option<u64> {
+        self.commitments
+            .iter()
+            .find(|commitment| commitment.id == id)
+            .map(|commitment| commitment.index)
+    }
+}
+
+struct PipelinedSession {
+    ticket: SessionTicket,
+    R: ProjectivePoint,
+    partials: BTreeMap<u64, PartialSignature>,
+}
+
+/// coordinator side of the pipelined mode. sessions are identified by a
+/// sequence number and can be in flight concurrently.
+pub struct Pipeline {
+    public_key: ProjectivePoint,
+    signer_ids: Vec<u64>,
+    config: SigningConfig,
+    /// unused nonce commitments per signer, in index order.
+    pools: BTreeMap<u64, VecDeque<NonceCommitment>>,
+    /// next index expected from each signer, so an index is never committed twice.
+    next_index: BTreeMap<u64, u64>,
+    sessions: BTreeMap<u64, PipelinedSession>,
+    next_seq: u64,
+}
+
+impl Pipeline {
+    pub fn new(
+        public_key: ProjectivePoint,
+        signer_ids: &[u64],
+        config: SigningConfig,
+    ) -> Result<Self, Error> {
+        SignerSet::new(signer_ids, signer_ids.len())?;
+        Ok(Self {
+            public_key,
+            signer_ids: signer_ids.to_vec(),
+            config,
+            pools: signer_ids.iter().map(|id| (*id, VecDeque::new())).collect(),
+            next_index: signer_ids.iter().map(|id| (*id, 0)).collect(),
+            sessions: BTreeMap::new(),
+            next_seq: 0,
+        })
+    }
+
+    /// add a signer's batch; batches must arrive in index order without gaps.
+    pub fn precommit(&mut self, commitments: NonceCommitments) -> Result<(), Error> {
+        let id = commitments.id;
+        let expected = *self
+            .next_index
+            .get(&id)
+            .ok_or(Error::UnexpectedSigner(id))?;
+        if commitments.first_index != expected {
+            return Err(Error::UnexpectedNonceIndex {
+                id,
+                expected,
+                actual: commitments.first_index,
+            });
+        }
+
+        let count = commitments.nonces.len() as u64;
+        let pool = self.pools.get_mut(&id).unwrap();
+        pool.extend(
+            (expected..)
+                .zip(commitments.nonces)
+                .map(|(index, (D, E))| NonceCommitment { id, index, D, E }),
+        );
+        self.next_index.insert(id, expected + count);
+
+        Ok(())
+    }
+
+    /// number of sessions that can be started with the nonces committed so far.
+    pub fn available(&self) -> usize {
+        self.pools.values().map(VecDeque::len).min().unwrap_or(0)
+    }
+
+    /// start a session for `message`, consuming one nonce of every signer.
+    pub fn start(&mut self, message: &[u8]) -> Result<SessionTicket, Error> {
+        let missing = self
+            .pools
+            .iter()
+            .filter(|(_, pool)| pool.is_empty())
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            return Err(Error::Incomplete { missing });
+        }
+
+        // a rejected set stays in the pools, only a started session consumes it
+        let commitments = self.pools.values().map(|pool| pool[0]).collect::<Vec<_>>();
+        let R = group_nonce_with_config(&commitments, &self.public_key, message, &self.config)?;
+        for pool in self.pools.values_mut() {
+            pool.pop_front();
+        }
+        let ticket = SessionTicket {
+            seq: self.next_seq,
+            message: message.to_vec(),
+            commitments,
+        };
+        self.next_seq += 1;
+        self.sessions.insert(
+            ticket.seq,
+            PipelinedSession {
+                ticket: ticket.clone(),
+                R,
+                partials: BTreeMap::new(),
+            },
+        );
+
+        Ok(ticket)
+    }
+
+    pub fn submit_partial(&mut self, seq: u64, partial: PartialSignature) -> Result<(), Error> {
+        let session = self.session_mut(seq)?;
+        if session.ticket.nonce_index(partial.id).is_none() {
+            return Err(Error::UnexpectedSigner(partial.id));
+        }
+        if session.partials.contains_key(&partial.id) {
+            return Err(Error::DuplicateMessage(partial.id));
+        }
+        session.partials.insert(partial.id, partial);
+
+        Ok(())
+    }
+
+    /// combine and verify the partials of session `seq`, which is then closed.
+    pub fn finalize(&mut self, seq: u64) -> Result<SchnorrSignature, Error> {
+        let session = self.session_mut(seq)?;
+        let missing = session
+            .ticket
+            .commitments
+            .iter()
+            .map(|commitment| commitment.id)
+            .filter(|id| !session.partials.contains_key(id))
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            return Err(Error::Incomplete { missing });
+        }
+
+        let session = self.sessions.remove(&seq).unwrap();
+        let partials = session.partials.into_values().collect::<Vec<_>>();
+        let signature = finalize_signature_with_signers(&partials, session.R, &self.signer_set()?)?;
+        if !signature.verify_with_config(&session.ticket.message, &self.public_key, &self.config) {
+            return Err(Error::InvalidSignature);
+        }
+
+        Ok(signature)
+    }
+
+    /// sequence numbers of sessions still waiting for partials.
+    pub fn in_flight(&self) -> Vec<u64> {
+        self.sessions.keys().copied().collect()
+    }
+
+    fn signer_set(&self) -> Result<SignerSet, Error> {
+        Ok(SignerSet::new(&self.signer_ids, self.signer_ids.len())?)
+    }
+
+    fn session_mut(&mut self, seq: u64) -> Result<&mut PipelinedSession, Error> {
+        self.sessions
+            .get_mut(&seq)
+            .ok_or(Error::UnknownSession(seq.to_string()))
+    }
+}
@@ -0,0 +1,142 @@
+#![allow(non_snake_case)]
+
+use crate::ceremony::sign_as_signer_with_policy;
+use crate::error::Error;
+use crate::transport::RoundTransport;
+use k256::ProjectivePoint;
+use sha2::{Digest, Sha256};
+use shamy_core::{schnorr::SigningConfig, threshold::Participant};
+
+//--------------------------------------------------------------------
+// Signer daemon
+//--------------------------------------------------------------------
+//
+// a long-lived signer keeps its decrypted share in memory and answers
+// the coordinator's sessions (`ceremony::sign_as_signer`) on its own:
+// nonce generation and partial signing need nobody at the keyboard.
+//
+// what it signs is limited by a `SignerPolicy`, checked before a nonce
+// is committed:
+//
+//   allow_any          every message (tests, trusted coordinators)
+//   allow_prefix(p)    messages starting with p
+//   allow_sha256(h)    exactly the message with SHA-256 h
+//
+// a policy without rules rejects everything. a rejected request is not
+// answered, the coordinator sees a timeout for this signer.
+//
+// ⚠️ the policy sees the raw message, framing (app id, purpose) comes
+//    from the daemon's own `SigningConfig`, never from the request.
+//
+
+/// what a signer daemon agrees to sign.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SignerPolicy {
+    any: bool,
+    prefixes: Vec<Vec<u8>>,
+    digests: Vec<[u8; 32]>,
+}
+
+impl SignerPolicy {
+    /// rejects every request until rules are added.
+    pub fn deny_all() -> Self {
+        Self::default()
+    }
+
+    pub fn allow_any() -> Self {
+        Self {
+            any: true,
+            ..Self::default()
+        }
+    }
+
+    /// also allow messages that start with `prefix`.
+    pub fn allow_prefix(mut self, prefix: &[u8]) -> Self {
+        self.prefixes.push(prefix.to_vec());
+        self
+    }
+
+    /// also allow the one message whose SHA-256 is `digest`.
+    pub fn allow_sha256(mut self, digest: [u8; 32]) -> Self {
+        self.digests.push(digest);
+        self
+    }
+
+    pub fn allows(&self, message: &[u8]) -> bool {
+        let digest: [u8; 32] = Sha256::digest(message).into();
+        self.any
+            || self.prefixes.iter().any(|p| message.starts_with(p))
+            || self.digests.contains(&digest)
+    }
+
+    /// `Error::Rejected` naming the message digest unless `allows`.
+    pub fn check(&self, message: &[u8]) -> Result<(), Error> {
+        match self.allows(message) {
+            true => Ok(()),
+            false => Err(Error::Rejected(format!(
+                "message {} is not allowed by the policy",
+                hex::encode(Sha256::digest(message))
+            ))),
+        }
+    }
+}
+
+/// a participant answering signing sessions of one coordinator until stopped.
+pub struct SignerDaemon<T> {
+    transport: T,
+    coordinator: u64,
+    participant: Participant,
+    public_key: ProjectivePoint,
+    config: SigningConfig,
+    policy: SignerPolicy,
+}
+
+impl<T: RoundTransport> SignerDaemon<T> {
+    pub fn new(
+        transport: T,
+        coordinator: u64,
+        participant: Participant,
+        public_key: ProjectivePoint,
+        config: SigningConfig,
+        policy: SignerPolicy,
+    ) -> Self {
+        Self {
+            transport,
+            coordinator,
+            participant,
+            public_key,
+            config,
+            policy,
+        }
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// wait for the next request and take part in its session.
+    /// returns the signed message, `Error::Timeout` for round 1 when no
+    /// request came within the transport's timeout.
+    pub async fn handle_next(&mut self) -> Result<Vec<u8>, Error> {
+        sign_as_signer_with_policy(
+            &mut self.transport,
+            self.coordinator,
+            &self.participant,
+            &self.public_key,
+            &self.config,
+            &self.policy,
+        )
+        .await
+    }
+
+    /// serve forever, reporting every session to `on_session`. idle
+    /// timeouts are not reported, a failed session doesn't stop the loop.
+    pub async fn run(&mut self, mut on_session: impl FnMut(Result<Vec<u8>, Error>)) -> ! {
+        loop {
+            match self.handle_next().await {
+                Err(Error::Timeout { round: 1, .. }) => {}
+                result => on_session(result),
+            }
+        }
+    }
+}
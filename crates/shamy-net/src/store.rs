@@ -0,0 +1,256 @@
+#![allow(non_snake_case)]
+
+use crate::error::Error;
+use k256::{ProjectivePoint, Scalar};
+use serde::{Deserialize, Serialize};
+use shamy_core::{
+    schnorr::SchnorrSignature,
+    util::{hex_to_pp, hex_to_scalar, pp_to_hex, scalar_to_hex},
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::{self, OpenOptions},
+    io::ErrorKind,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+/// everything a coordinator needs to resume a signing session.
+/// shared between a primary and its backups through a `SessionStore`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionState {
+    pub session_id: String,
+    pub message: Vec<u8>,
+    pub public_key: ProjectivePoint,
+    pub signer_ids: Vec<u64>,
+    pub nonces: BTreeMap<u64, ProjectivePoint>,
+    pub partials: BTreeMap<u64, Scalar>,
+    pub signature: Option<SchnorrSignature>,
+    /// coordinator instance allowed to write the session.
+    pub owner: String,
+    /// incremented on every takeover, older owners are fenced off.
+    pub epoch: u64,
+    /// incremented on every write, used for compare-and-swap.
+    pub version: u64,
+}
+
+/// storage backend shared by coordinator instances.
+pub trait SessionStore {
+    fn load(&self, session_id: &str) -> Result<Option<SessionState>, Error>;
+
+    /// store `state` if the stored version is still `expected_version`
+    /// (`None`: the session must not exist yet), `Error::Conflict` otherwise.
+    fn compare_and_swap(
+        &self,
+        expected_version: Option<u64>,
+        state: &SessionState,
+    ) -> Result<(), Error>;
+}
+
+/// lets several coordinator instances in one process share a store.
+impl<T: SessionStore + ?Sized> SessionStore for Arc<T> {
+    fn load(&self, session_id: &str) -> Result<Option<SessionState>, Error> {
+        (**self).load(session_id)
+    }
+
+    fn compare_and_swap(
+        &self,
+        expected_version: Option<u64>,
+        state: &SessionState,
+    ) -> Result<(), Error> {
+        (**self).compare_and_swap(expected_version, state)
+    }
+}
+
+/// in-process store, for coordinators running as threads of one service and for tests.
+#[derive(Default)]
+pub struct MemoryStore {
+    sessions: Mutex<HashMap<String, SessionState>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl SessionStore for MemoryStore {
+    fn load(&self, session_id: &str) -> Result<Option<SessionState>, Error> {
+        let sessions = self.sessions.lock().unwrap();
+        Ok(sessions.get(session_id).cloned())
+    }
+
+    fn compare_and_swap(
+        &self,
+        expected_version: Option<u64>,
+        state: &SessionState,
+    ) -> Result<(), Error> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let current = sessions.get(&state.session_id).map(|s| s.version);
+        if current != expected_version {
+            return Err(Error::Conflict {
+                session_id: state.session_id.clone(),
+            });
+        }
+        sessions.insert(state.session_id.clone(), state.clone());
+
+        Ok(())
+    }
+}
+
+/// one JSON file per session in a directory, e.g. on a shared volume.
+/// writes are serialized with a lock file and made atomic with a rename.
+pub struct FileStore {
+    dir: PathBuf,
+}
+
+impl FileStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self, Error> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).map_err(|e| Error::Store(e.to_string()))?;
+        Ok(Self { dir })
+    }
+
+    fn path(&self, session_id: &str, extension: &str) -> PathBuf {
+        self.dir
+            .join(format!("{}.{}", hex::encode(session_id), extension))
+    }
+
+    fn lock(&self, session_id: &str) -> Result<FileLock, Error> {
+        let path = self.path(session_id, "lock");
+        for _ in 0..100 {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(_) => return Ok(FileLock { path }),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    thread::sleep(Duration::from_millis(10))
+                }
+                Err(e) => return Err(Error::Store(e.to_string())),
+            }
+        }
+
+        Err(Error::Store(format!(
+            "Timed out locking {}",
+            path.display()
+        )))
+    }
+}
+
+struct FileLock {
+    path: PathBuf,
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+impl SessionStore for FileStore {
+    fn load(&self, session_id: &str) -> Result<Option<SessionState>, Error> {
+        match fs::read(self.path(session_id, "json")) {
+            Ok(data) => {
+                let stored: StoredSession =
+                    serde_json::from_slice(&data).map_err(|e| Error::Store(e.to_string()))?;
+                stored.try_into().map(Some)
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(Error::Store(e.to_string())),
+        }
+    }
+
+    fn compare_and_swap(
+        &self,
+        expected_version: Option<u64>,
+        state: &SessionState,
+    ) -> Result<(), Error> {
+        let _lock = self.lock(&state.session_id)?;
+
+        let current = self.load(&state.session_id)?.map(|s| s.version);
+        if current != expected_version {
+            return Err(Error::Conflict {
+                session_id: state.session_id.clone(),
+            });
+        }
+
+        let data = serde_json::to_vec_pretty(&StoredSession::from(state)).unwrap();
+        let tmp = self.path(&state.session_id, "tmp");
+        fs::write(&tmp, data).map_err(|e| Error::Store(e.to_string()))?;
+        fs::rename(&tmp, self.path(&state.session_id, "json"))
+            .map_err(|e| Error::Store(e.to_string()))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredSession {
+    session_id: String,
+    message: String,
+    public_key: String,
+    signer_ids: Vec<u64>,
+    nonces: BTreeMap<u64, String>,
+    partials: BTreeMap<u64, String>,
+    signature: Option<(String, String)>,
+    owner: String,
+    epoch: u64,
+    version: u64,
+}
+
+impl From<&SessionState> for StoredSession {
+    fn from(state: &SessionState) -> Self {
+        Self {
+            session_id: state.session_id.clone(),
+            message: hex::encode(&state.message),
+            public_key: pp_to_hex(&state.public_key),
+            signer_ids: state.signer_ids.clone(),
+            nonces: state
+                .nonces
+                .iter()
+                .map(|(id, R_i)| (*id, pp_to_hex(R_i)))
+                .collect(),
+            partials: state
+                .partials
+                .iter()
+                .map(|(id, s_i)| (*id, scalar_to_hex(s_i)))
+                .collect(),
+            signature: state
+                .signature
+                .map(|sig| (pp_to_hex(&sig.R), scalar_to_hex(&sig.s))),
+            owner: state.owner.clone(),
+            epoch: state.epoch,
+            version: state.version,
+        }
+    }
+}
+
+impl TryFrom<StoredSession> for SessionState {
+    type Error = Error;
+
+    fn try_from(stored: StoredSession) -> Result<Self, Error> {
+        let invalid = |e: shamy_core::Error| Error::Store(e.to_string());
+
+        Ok(Self {
+            session_id: stored.session_id,
+            message: hex::decode(&stored.message).map_err(|e| Error::Store(e.to_string()))?,
+            public_key: hex_to_pp(&stored.public_key).map_err(invalid)?,
+            signer_ids: stored.signer_ids,
+            nonces: stored
+                .nonces
+                .iter()
+                .map(|(id, R_i)| Ok((*id, hex_to_pp(R_i).map_err(invalid)?)))
+                .collect::<Result<_, Error>>()?,
+            partials: stored
+                .partials
+                .iter()
+                .map(|(id, s_i)| Ok((*id, hex_to_scalar(s_i).map_err(invalid)?)))
+                .collect::<Result<_, Error>>()?,
+            signature: match stored.signature {
+                Some((R, s)) => Some(SchnorrSignature::from_hex_parts(&R, &s).map_err(invalid)?),
+                None => None,
+            },
+            owner: stored.owner,
+            epoch: stored.epoch,
+            version: stored.version,
+        })
+    }
+}
@@ -0,0 +1,455 @@
+use crate::error::Error;
+use serde::{Deserialize, Serialize};
+use shamy_core::{
+    hash::tagged_hash,
+    identity::{IdentityKey, IdentityRegistry, SignedMessage},
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    future::Future,
+    net::SocketAddr,
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    sync::mpsc,
+    task::JoinHandle,
+    time::{Instant, sleep, timeout_at},
+};
+
+/// frames above this size are dropped, so a peer can't make us allocate unbounded memory.
+pub const MAX_FRAME_LEN: usize = 1 << 20;
+
+const ECHO_TAG: &[u8] = b"shamy/transport/echo/v1";
+
+//--------------------------------------------------------------------
+// Echo broadcast
+//--------------------------------------------------------------------
+//
+// a plain broadcast lets a sender equivocate: send one set of DKG
+// commitments to some participants and another set to the rest. each
+// half finishes with a different key. echo broadcast adds a round in
+// which everyone confirms what they received:
+//
+//   round r:    every i sends mᵢ to every peer
+//   round r+1:  every i sends (j, H(echo, mⱼ)) for every message it holds,
+//               its own included
+//
+// a participant continues only if every echo matches its own digests.
+// otherwise it aborts with `Error::Equivocation` naming the senders
+// whose message wasn't seen the same by everyone.
+//
+// ⚠️ without `AuthenticatedTransport` an attacker on the network can
+//    forge echoes as well as messages.
+// ⚠️ a lying echo makes an honest sender look inconsistent, the error
+//    proves that the broadcast failed, not who is to blame.
+//
+
+/// one message of a ceremony round, the payload is opaque to the transport.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoundMessage {
+    pub round: u8,
+    pub sender: u64,
+    pub payload: Vec<u8>,
+}
+
+/// moves round messages between the participants of a ceremony.
+/// ⚠️ implementations are not required to authenticate or encrypt,
+///    the sender field is only as trustworthy as the network.
+///    wrap them in `AuthenticatedTransport` to sign every message.
+pub trait RoundTransport: Send + Sync {
+    /// id of the local participant.
+    fn id(&self) -> u64;
+
+    /// send `payload` as our message of `round` to participant `to`.
+    fn send(
+        &self,
+        to: u64,
+        round: u8,
+        payload: Vec<u8>,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// wait for exactly one message of `round` from each of `from`,
+    /// returned in the same order. messages for later rounds are kept.
+    fn receive(
+        &mut self,
+        round: u8,
+        from: &[u64],
+    ) -> impl Future<Output = Result<Vec<RoundMessage>, Error>> + Send;
+
+    /// send the same payload to every participant in `to`.
+    fn broadcast(
+        &self,
+        to: &[u64],
+        round: u8,
+        payload: Vec<u8>,
+    ) -> impl Future<Output = Result<(), Error>> + Send {
+        async move {
+            for id in to {
+                self.send(*id, round, payload.clone()).await?;
+            }
+            Ok(())
+        }
+    }
+
+    /// send `payload` to every peer in `to` and receive theirs, then
+    /// cross-check digests in round `round + 1` so all of them agree on
+    /// every message. returns the peers' messages in the order of `to`.
+    fn echo_broadcast(
+        &mut self,
+        to: &[u64],
+        round: u8,
+        payload: Vec<u8>,
+    ) -> impl Future<Output = Result<Vec<RoundMessage>, Error>> + Send {
+        async move {
+            let echo_round = round
+                .checked_add(1)
+                .ok_or(Error::InvalidMessage("no round after 255".to_string()))?;
+            self.broadcast(to, round, payload.clone()).await?;
+            let messages = self.receive(round, to).await?;
+
+            let mut digests = messages
+                .iter()
+                .map(|m| (m.sender, hex::encode(tagged_hash(ECHO_TAG, &m.payload))))
+                .collect::<BTreeMap<_, _>>();
+            digests.insert(self.id(), hex::encode(tagged_hash(ECHO_TAG, &payload)));
+            let echo = EchoMessage {
+                digests: digests.clone().into_iter().collect(),
+            };
+            self.broadcast(to, echo_round, serde_json::to_vec(&echo).unwrap())
+                .await?;
+
+            let mut conflicting = Vec::new();
+            for reply in self.receive(echo_round, to).await? {
+                let echo: EchoMessage = serde_json::from_slice(&reply.payload).map_err(|e| {
+                    Error::InvalidMessage(format!(
+                        "round {} from {}: {}",
+                        echo_round, reply.sender, e
+                    ))
+                })?;
+                let echoed = echo.digests.into_iter().collect::<BTreeMap<_, _>>();
+                if echoed.keys().ne(digests.keys()) {
+                    return Err(Error::InvalidMessage(format!(
+                        "round {} from {}: echo covers other participants",
+                        echo_round, reply.sender
+                    )));
+                }
+                conflicting.extend(
+                    digests
+                        .iter()
+                        .filter(|(id, digest)| echoed[*id] != **digest)
+                        .map(|(id, _)| *id),
+                );
+            }
+            conflicting.sort_unstable();
+            conflicting.dedup();
+
+            match conflicting.is_empty() {
+                true => Ok(messages),
+                false => Err(Error::Equivocation {
+                    round,
+                    ids: conflicting,
+                }),
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct EchoMessage {
+    digests: Vec<(u64, String)>,
+}
+
+/// signs every payload with our identity key. a received message without
+/// a valid signature from the registered key of its sender fails `receive`. the signature covers `context`, round,
+/// sender and receiver, see `shamy_core::identity`.
+pub struct AuthenticatedTransport<T> {
+    inner: T,
+    key: IdentityKey,
+    registry: IdentityRegistry,
+    context: Vec<u8>,
+}
+
+impl<T: RoundTransport> AuthenticatedTransport<T> {
+    /// `context` names the ceremony, e.g. a session id, every participant
+    /// must use the same.
+    pub fn new(inner: T, key: IdentityKey, registry: IdentityRegistry, context: &[u8]) -> Self {
+        Self {
+            inner,
+            key,
+            registry,
+            context: context.to_vec(),
+        }
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// unwrap and check one message received by the inner transport.
+    fn open(&self, message: RoundMessage) -> Result<RoundMessage, Error> {
+        let signed = SignedMessage::from_bytes(&message.payload).map_err(|e| {
+            Error::InvalidMessage(format!(
+                "round {} from {}: {}",
+                message.round, message.sender, e
+            ))
+        })?;
+        // the envelope is unauthenticated, it must agree with what was signed
+        if (signed.round, signed.sender, signed.receiver)
+            != (message.round, message.sender, self.id())
+        {
+            return Err(Error::InvalidMessage(format!(
+                "round {} from {}: signed for round {} from {} to {}",
+                message.round, message.sender, signed.round, signed.sender, signed.receiver
+            )));
+        }
+        self.registry.verify(&self.context, &signed)?;
+
+        Ok(RoundMessage {
+            round: signed.round,
+            sender: signed.sender,
+            payload: signed.payload,
+        })
+    }
+}
+
+impl<T: RoundTransport> RoundTransport for AuthenticatedTransport<T> {
+    fn id(&self) -> u64 {
+        self.inner.id()
+    }
+
+    async fn send(&self, to: u64, round: u8, payload: Vec<u8>) -> Result<(), Error> {
+        let signed = self.key.sign(&self.context, round, self.id(), to, &payload);
+        self.inner.send(to, round, signed.to_bytes()).await
+    }
+
+    async fn receive(&mut self, round: u8, from: &[u64]) -> Result<Vec<RoundMessage>, Error> {
+        let messages = self.inner.receive(round, from).await?;
+        messages
+            .into_iter()
+            .map(|message| self.open(message))
+            .collect()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Frame {
+    round: u8,
+    sender: u64,
+    payload: String,
+}
+
+/// reference `RoundTransport` over plain TCP: one listener per participant,
+/// a short-lived connection per message carrying a length prefixed JSON frame.
+/// ⚠️ no TLS, run it over a VPN or tunnel when round payloads are secret
+///    (e.g. DKG round 2 shares).
+pub struct TcpTransport {
+    id: u64,
+    local_addr: SocketAddr,
+    peers: HashMap<u64, SocketAddr>,
+    inbox: Inbox,
+    timeout: Duration,
+    accept_task: JoinHandle<()>,
+}
+
+impl TcpTransport {
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+    /// listen on `addr` as participant `id`.
+    pub async fn bind(id: u64, addr: impl ToSocketAddrs) -> Result<Self, Error> {
+        let listener = TcpListener::bind(addr)
+            .await
+            .map_err(|e| Error::Transport(e.to_string()))?;
+        let local_addr = listener
+            .local_addr()
+            .map_err(|e| Error::Transport(e.to_string()))?;
+
+        let (sender, incoming) = mpsc::channel(1024);
+        let accept_task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(read_frames(stream, sender.clone()));
+            }
+        });
+
+        Ok(Self {
+            id,
+            local_addr,
+            peers: HashMap::new(),
+            inbox: Inbox::new(incoming),
+            timeout: Self::DEFAULT_TIMEOUT,
+            accept_task,
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    pub fn add_peer(&mut self, id: u64, addr: SocketAddr) {
+        self.peers.insert(id, addr);
+    }
+
+    /// how long `send` keeps retrying and `receive` keeps waiting.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+}
+
+impl Drop for TcpTransport {
+    fn drop(&mut self) {
+        self.accept_task.abort();
+    }
+}
+
+impl RoundTransport for TcpTransport {
+    fn id(&self) -> u64 {
+        self.id
+    }
+
+    async fn send(&self, to: u64, round: u8, payload: Vec<u8>) -> Result<(), Error> {
+        let addr = *self
+            .peers
+            .get(&to)
+            .ok_or(Error::Transport(format!("Unknown peer {}", to)))?;
+        let frame = serde_json::to_vec(&Frame {
+            round,
+            sender: self.id,
+            payload: hex::encode(payload),
+        })
+        .unwrap();
+
+        // peers may still be starting up, retry until the timeout
+        let deadline = Instant::now() + self.timeout;
+        let mut stream = loop {
+            match TcpStream::connect(addr).await {
+                Ok(stream) => break stream,
+                Err(e) if Instant::now() >= deadline => {
+                    return Err(Error::Transport(format!("Cannot reach peer {}: {}", to, e)));
+                }
+                Err(_) => sleep(Duration::from_millis(50)).await,
+            }
+        };
+
+        let write = async {
+            stream.write_u32(frame.len() as u32).await?;
+            stream.write_all(&frame).await?;
+            stream.shutdown().await
+        };
+        write
+            .await
+            .map_err(|e| Error::Transport(format!("Sending to peer {} failed: {}", to, e)))
+    }
+
+    async fn receive(&mut self, round: u8, from: &[u64]) -> Result<Vec<RoundMessage>, Error> {
+        self.inbox.receive(round, from, self.timeout).await
+    }
+}
+
+/// messages that arrived on a transport, waiting for `receive`.
+pub(crate) struct Inbox {
+    incoming: mpsc::Receiver<RoundMessage>,
+    pending: Vec<RoundMessage>,
+}
+
+impl Inbox {
+    pub(crate) fn new(incoming: mpsc::Receiver<RoundMessage>) -> Self {
+        Self {
+            incoming,
+            pending: Vec::new(),
+        }
+    }
+
+    /// `RoundTransport::receive`, giving up after `timeout`.
+    pub(crate) async fn receive(
+        &mut self,
+        round: u8,
+        from: &[u64],
+        timeout: Duration,
+    ) -> Result<Vec<RoundMessage>, Error> {
+        let deadline = Instant::now() + timeout;
+        let mut received = BTreeMap::new();
+
+        let pending = std::mem::take(&mut self.pending);
+        for message in pending {
+            self.accept(message, round, from, &mut received)?;
+        }
+
+        while received.len() < from.len() {
+            let message = match timeout_at(deadline, self.incoming.recv()).await {
+                Ok(Some(message)) => message,
+                Ok(None) => return Err(Error::Transport("Listener stopped".to_string())),
+                Err(_) => {
+                    return Err(Error::Timeout {
+                        round,
+                        missing: from
+                            .iter()
+                            .filter(|id| !received.contains_key(*id))
+                            .copied()
+                            .collect(),
+                    });
+                }
+            };
+            self.accept(message, round, from, &mut received)?;
+        }
+
+        Ok(from.iter().map(|id| received.remove(id).unwrap()).collect())
+    }
+
+    /// sort an incoming message: keep it for this round, park it for a later
+    /// round, drop it if it's stale or from a stranger.
+    fn accept(
+        &mut self,
+        message: RoundMessage,
+        round: u8,
+        from: &[u64],
+        received: &mut BTreeMap<u64, RoundMessage>,
+    ) -> Result<(), Error> {
+        if message.round > round {
+            self.pending.push(message);
+        } else if message.round == round && from.contains(&message.sender) {
+            let sender = message.sender;
+            if received.insert(sender, message).is_some() {
+                return Err(Error::DuplicateMessage(sender));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+async fn read_frames(mut stream: TcpStream, sender: mpsc::Sender<RoundMessage>) {
+    while let Ok(len) = stream.read_u32().await {
+        let len = len as usize;
+        if len > MAX_FRAME_LEN {
+            return;
+        }
+        let mut buf = vec![0; len];
+        if stream.read_exact(&mut buf).await.is_err() {
+            return;
+        }
+
+        let Ok(frame) = serde_json::from_slice::<Frame>(&buf) else {
+            return;
+        };
+        let Ok(payload) = hex::decode(&frame.payload) else {
+            return;
+        };
+        let message = RoundMessage {
+            round: frame.round,
+            sender: frame.sender,
+            payload,
+        };
+        if sender.send(message).await.is_err() {
+            return;
+        }
+    }
+}
@@ -0,0 +1,324 @@
+#![allow(non_snake_case)]
+
+use crate::error::Error;
+use crate::pipeline;
+use k256::{ProjectivePoint, Scalar};
+use prost::Message;
+use shamy_core::{
+    dkg,
+    proofs::SchnorrProof,
+    schnorr, threshold,
+    util::{bytes_to_pp, hex_to_scalar, pp_to_hex},
+};
+
+//--------------------------------------------------------------------
+// Protobuf wire format (`proto/shamy/v1/shamy.proto`)
+//--------------------------------------------------------------------
+//
+// the structs below mirror the schema field by field, so participants in
+// other languages can generate their own code from the .proto file.
+//
+//   points   33 byte compressed SEC1
+//   scalars  32 byte big endian
+//
+// ⚠️ keep tags in sync with the .proto file, they are the wire format.
+//
+
+#[derive(Clone, PartialEq, Message)]
+pub struct NonceCommitment {
+    #[prost(uint64, tag = "1")]
+    pub id: u64,
+    #[prost(bytes = "vec", tag = "2")]
+    pub nonce: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct NonceCommitments {
+    #[prost(uint64, tag = "1")]
+    pub id: u64,
+    #[prost(uint64, tag = "2")]
+    pub first_index: u64,
+    /// single pre-committed nonces, rejected when decoding.
+    #[prost(bytes = "vec", repeated, tag = "3")]
+    pub nonces: Vec<Vec<u8>>,
+    #[prost(bytes = "vec", repeated, tag = "4")]
+    pub hiding: Vec<Vec<u8>>,
+    #[prost(bytes = "vec", repeated, tag = "5")]
+    pub binding: Vec<Vec<u8>>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct PartialSignature {
+    #[prost(uint64, tag = "1")]
+    pub id: u64,
+    #[prost(bytes = "vec", tag = "2")]
+    pub s_i: Vec<u8>,
+    #[prost(bytes = "vec", optional, tag = "3")]
+    pub R_i: Option<Vec<u8>>,
+    #[prost(bytes = "vec", optional, tag = "4")]
+    pub X_i: Option<Vec<u8>>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct DkgRound1Package {
+    #[prost(uint64, tag = "1")]
+    pub id: u64,
+    #[prost(bytes = "vec", repeated, tag = "2")]
+    pub commitments: Vec<Vec<u8>>,
+    #[prost(bytes = "vec", tag = "3")]
+    pub proof_R: Vec<u8>,
+    #[prost(bytes = "vec", tag = "4")]
+    pub proof_z: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct DkgRound2Package {
+    #[prost(uint64, tag = "1")]
+    pub sender: u64,
+    #[prost(uint64, tag = "2")]
+    pub receiver: u64,
+    #[prost(bytes = "vec", tag = "3")]
+    pub share: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct Signature {
+    #[prost(bytes = "vec", tag = "1")]
+    pub nonce: Vec<u8>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub s: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct CreateSessionRequest {
+    #[prost(string, tag = "1")]
+    pub session_id: String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub message: Vec<u8>,
+    #[prost(bytes = "vec", tag = "3")]
+    pub public_key: Vec<u8>,
+    #[prost(uint64, repeated, tag = "4")]
+    pub signer_ids: Vec<u64>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct CreateSessionResponse {}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct SubmitNonceRequest {
+    #[prost(string, tag = "1")]
+    pub session_id: String,
+    #[prost(message, optional, tag = "2")]
+    pub nonce: Option<NonceCommitment>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct SubmitNonceResponse {}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct GetChallengeRequest {
+    #[prost(string, tag = "1")]
+    pub session_id: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct Challenge {
+    #[prost(bytes = "vec", tag = "1")]
+    pub nonce: Vec<u8>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub challenge: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct SubmitPartialRequest {
+    #[prost(string, tag = "1")]
+    pub session_id: String,
+    #[prost(message, optional, tag = "2")]
+    pub partial: Option<PartialSignature>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct SubmitPartialResponse {}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct FinalizeRequest {
+    #[prost(string, tag = "1")]
+    pub session_id: String,
+}
+
+pub fn point_to_bytes(point: &ProjectivePoint) -> Vec<u8> {
+    hex::decode(pp_to_hex(point)).unwrap()
+}
+
+pub fn point_from_bytes(bytes: &[u8]) -> Result<ProjectivePoint, Error> {
+    Ok(bytes_to_pp(bytes)?)
+}
+
+pub fn scalar_to_bytes(scalar: &Scalar) -> Vec<u8> {
+    scalar.to_bytes().to_vec()
+}
+
+pub fn scalar_from_bytes(bytes: &[u8]) -> Result<Scalar, Error> {
+    Ok(hex_to_scalar(&hex::encode(bytes))?)
+}
+
+/// decode a protobuf message, e.g. `wire::PartialSignature` received from a peer.
+pub fn decode<T: Message + Default>(bytes: &[u8]) -> Result<T, Error> {
+    T::decode(bytes).map_err(|e| Error::InvalidMessage(e.to_string()))
+}
+
+impl NonceCommitment {
+    pub fn new(id: u64, R_i: &ProjectivePoint) -> Self {
+        Self {
+            id,
+            nonce: point_to_bytes(R_i),
+        }
+    }
+
+    pub fn point(&self) -> Result<ProjectivePoint, Error> {
+        point_from_bytes(&self.nonce)
+    }
+}
+
+impl From<&pipeline::NonceCommitments> for NonceCommitments {
+    fn from(commitments: &pipeline::NonceCommitments) -> Self {
+        Self {
+            id: commitments.id,
+            first_index: commitments.first_index,
+            nonces: Vec::new(),
+            hiding: commitments
+                .nonces
+                .iter()
+                .map(|(D, _)| point_to_bytes(D))
+                .collect(),
+            binding: commitments
+                .nonces
+                .iter()
+                .map(|(_, E)| point_to_bytes(E))
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<&NonceCommitments> for pipeline::NonceCommitments {
+    type Error = Error;
+
+    fn try_from(commitments: &NonceCommitments) -> Result<Self, Error> {
+        if !commitments.nonces.is_empty() {
+            return Err(Error::InvalidMessage(
+                "single nonces are not accepted, send hiding and binding pairs".to_string(),
+            ));
+        }
+        if commitments.hiding.len() != commitments.binding.len() {
+            return Err(Error::InvalidMessage(format!(
+                "{} hiding but {} binding nonces",
+                commitments.hiding.len(),
+                commitments.binding.len()
+            )));
+        }
+
+        Ok(Self {
+            id: commitments.id,
+            first_index: commitments.first_index,
+            nonces: commitments
+                .hiding
+                .iter()
+                .zip(&commitments.binding)
+                .map(|(D, E)| Ok((point_from_bytes(D)?, point_from_bytes(E)?)))
+                .collect::<Result<_, Error>>()?,
+        })
+    }
+}
+
+impl From<&threshold::PartialSignature> for PartialSignature {
+    fn from(partial: &threshold::PartialSignature) -> Self {
+        Self {
+            id: partial.id,
+            s_i: scalar_to_bytes(&partial.s_i),
+            R_i: partial.R_i.as_ref().map(point_to_bytes),
+            X_i: partial.X_i.as_ref().map(point_to_bytes),
+        }
+    }
+}
+
+impl TryFrom<&PartialSignature> for threshold::PartialSignature {
+    type Error = Error;
+
+    fn try_from(partial: &PartialSignature) -> Result<Self, Error> {
+        Ok(Self {
+            id: partial.id,
+            s_i: scalar_from_bytes(&partial.s_i)?,
+            R_i: partial.R_i.as_deref().map(point_from_bytes).transpose()?,
+            X_i: partial.X_i.as_deref().map(point_from_bytes).transpose()?,
+        })
+    }
+}
+
+impl From<&dkg::Round1Package> for DkgRound1Package {
+    fn from(package: &dkg::Round1Package) -> Self {
+        Self {
+            id: package.id,
+            commitments: package.commitments.iter().map(point_to_bytes).collect(),
+            proof_R: point_to_bytes(&package.proof.R),
+            proof_z: scalar_to_bytes(&package.proof.z),
+        }
+    }
+}
+
+impl TryFrom<&DkgRound1Package> for dkg::Round1Package {
+    type Error = Error;
+
+    fn try_from(package: &DkgRound1Package) -> Result<Self, Error> {
+        Ok(Self {
+            id: package.id,
+            commitments: package
+                .commitments
+                .iter()
+                .map(|C| point_from_bytes(C))
+                .collect::<Result<_, _>>()?,
+            proof: SchnorrProof {
+                R: point_from_bytes(&package.proof_R)?,
+                z: scalar_from_bytes(&package.proof_z)?,
+            },
+        })
+    }
+}
+
+impl From<&dkg::Round2Package> for DkgRound2Package {
+    fn from(package: &dkg::Round2Package) -> Self {
+        Self {
+            sender: package.sender,
+            receiver: package.receiver,
+            share: scalar_to_bytes(&package.share),
+        }
+    }
+}
+
+impl TryFrom<&DkgRound2Package> for dkg::Round2Package {
+    type Error = Error;
+
+    fn try_from(package: &DkgRound2Package) -> Result<Self, Error> {
+        Ok(Self {
+            sender: package.sender,
+            receiver: package.receiver,
+            share: scalar_from_bytes(&package.share)?,
+        })
+    }
+}
+
+impl From<&schnorr::SchnorrSignature> for Signature {
+    fn from(signature: &schnorr::SchnorrSignature) -> Self {
+        Self {
+            nonce: point_to_bytes(&signature.R),
+            s: scalar_to_bytes(&signature.s),
+        }
+    }
+}
+
+impl TryFrom<&Signature> for schnorr::SchnorrSignature {
+    type Error = Error;
+
+    fn try_from(signature: &Signature) -> Result<Self, Error> {
+        Ok(Self::from_parts(&signature.nonce, &signature.s)?)
+    }
+}
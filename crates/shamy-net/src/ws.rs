@@ -0,0 +1,503 @@
+use crate::error::Error;
+use crate::transport::{Inbox, MAX_FRAME_LEN, RoundMessage, RoundTransport};
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    sync::{Notify, mpsc},
+    task::JoinHandle,
+    time::{Instant, interval, sleep, timeout},
+};
+use tokio_tungstenite::{
+    WebSocketStream, accept_async_with_config, connect_async_with_config,
+    tungstenite::{Message, protocol::WebSocketConfig},
+};
+
+const MIN_BACKOFF: Duration = Duration::from_millis(50);
+const MAX_BACKOFF: Duration = Duration::from_secs(5);
+
+//--------------------------------------------------------------------
+// WebSocket transport
+//--------------------------------------------------------------------
+//
+//   WsClient ──┐
+//   WsClient ──┼── WsServer      clients only dial out, the server
+//   browser  ──┘                 relays messages between them
+//
+// every WebSocket text message is a JSON frame:
+//
+//   hello    {session, id, received, sent}     client → server, first
+//   welcome  {received, resume}                server → client
+//   message  {seq, to, round, sender, payload}
+//
+// each side numbers the messages it sends to the other end of a
+// connection (seq = 1, 2, ...) and keeps them. `received` is the
+// highest seq seen from the other end. after a reconnect both sides
+// resend what the other hasn't seen, so a dropped connection delays
+// round messages instead of losing them. a side that restarted has
+// seen fewer messages than the other one claims to have sent, the
+// other side then starts over from seq 1.
+//
+// keepalive: both ends ping every `keepalive`, a connection that stays
+// silent for 3 intervals is closed and the client reconnects with
+// exponential backoff.
+//
+// the server overwrites `sender` with the id from the client's hello,
+// clients can't pose as each other on the relay. they can still claim
+// any id in their hello.
+//
+// ⚠️ no TLS, put a TLS proxy in front of the server (wss://) when round
+//    payloads are secret, and wrap both ends in `AuthenticatedTransport`.
+// ⚠️ messages are kept for the life of the transport, use one
+//    transport per ceremony.
+//
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Frame {
+    Hello {
+        session: String,
+        id: u64,
+        received: u64,
+        sent: u64,
+    },
+    Welcome {
+        received: u64,
+        resume: u64,
+    },
+    Message {
+        seq: u64,
+        to: u64,
+        round: u8,
+        sender: u64,
+        payload: String,
+    },
+}
+
+impl Frame {
+    fn parse(message: &Message) -> Option<Self> {
+        serde_json::from_str(message.to_text().ok()?).ok()
+    }
+
+    fn encode(&self) -> Message {
+        Message::text(serde_json::to_string(self).unwrap())
+    }
+}
+
+/// what one end of a connection sent and received, kept across reconnects.
+#[derive(Default)]
+struct Link {
+    /// (receiver, message) for the other end, seq is the index + 1.
+    outbox: Vec<(u64, RoundMessage)>,
+    /// highest seq received from the other end.
+    received: u64,
+    /// wakes the connection when the outbox grows.
+    queued: Arc<Notify>,
+    /// ends the connection, e.g. when the client reconnected.
+    stop: Arc<Notify>,
+}
+
+impl Link {
+    fn push(&mut self, to: u64, message: RoundMessage) {
+        self.outbox.push((to, message));
+        self.queued.notify_one();
+    }
+}
+
+fn config() -> WebSocketConfig {
+    // hex doubles the payload, leave room for the JSON around it
+    WebSocketConfig::default().max_message_size(Some(2 * MAX_FRAME_LEN + 1024))
+}
+
+/// `RoundTransport` for the participant the others connect to, usually
+/// the coordinator. messages between two clients go through the server.
+pub struct WsServer {
+    id: u64,
+    local_addr: SocketAddr,
+    hub: Arc<Hub>,
+    inbox: Inbox,
+    timeout: Duration,
+    accept_task: JoinHandle<()>,
+}
+
+struct Hub {
+    id: u64,
+    session: String,
+    keepalive: Duration,
+    links: Mutex<HashMap<u64, Arc<Mutex<Link>>>>,
+    incoming: mpsc::Sender<RoundMessage>,
+}
+
+impl WsServer {
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+    pub const DEFAULT_KEEPALIVE: Duration = Duration::from_secs(10);
+
+    /// listen on `addr` as participant `id` for the clients of `session`.
+    pub async fn bind(
+        id: u64,
+        addr: impl ToSocketAddrs,
+        session: &str,
+        keepalive: Duration,
+    ) -> Result<Self, Error> {
+        let listener = TcpListener::bind(addr)
+            .await
+            .map_err(|e| Error::Transport(e.to_string()))?;
+        let local_addr = listener
+            .local_addr()
+            .map_err(|e| Error::Transport(e.to_string()))?;
+
+        let (sender, incoming) = mpsc::channel(1024);
+        let hub = Arc::new(Hub {
+            id,
+            session: session.to_string(),
+            keepalive,
+            links: Mutex::new(HashMap::new()),
+            incoming: sender,
+        });
+        let accept_task = tokio::spawn({
+            let hub = hub.clone();
+            async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    tokio::spawn(hub.clone().serve(stream));
+                }
+            }
+        });
+
+        Ok(Self {
+            id,
+            local_addr,
+            hub,
+            inbox: Inbox::new(incoming),
+            timeout: Self::DEFAULT_TIMEOUT,
+            accept_task,
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// how long `receive` keeps waiting.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+}
+
+impl Drop for WsServer {
+    fn drop(&mut self) {
+        self.accept_task.abort();
+        for link in self.hub.links.lock().unwrap().values() {
+            link.lock().unwrap().stop.notify_one();
+        }
+    }
+}
+
+impl Hub {
+    /// the link to client `id`, created on first use so messages can be
+    /// queued before the client connects.
+    fn link(&self, id: u64) -> Arc<Mutex<Link>> {
+        self.links.lock().unwrap().entry(id).or_default().clone()
+    }
+
+    async fn serve(self: Arc<Self>, stream: TcpStream) {
+        let Ok(mut ws) = accept_async_with_config(stream, Some(config())).await else {
+            return;
+        };
+        let hello = match timeout(3 * self.keepalive, ws.next()).await {
+            Ok(Some(Ok(message))) => Frame::parse(&message),
+            _ => None,
+        };
+        let Some(Frame::Hello {
+            session,
+            id,
+            mut received,
+            sent,
+        }) = hello
+        else {
+            return;
+        };
+        if session != self.session || id == self.id {
+            let _ = ws.close(None).await;
+            return;
+        }
+
+        let link = self.link(id);
+        let welcome = {
+            let mut link = link.lock().unwrap();
+            // only one connection per client, the newest wins
+            link.stop.notify_one();
+            link.stop = Arc::new(Notify::new());
+            if received > link.outbox.len() as u64 {
+                received = 0;
+            }
+            if link.received > sent {
+                link.received = 0;
+            }
+            Frame::Welcome {
+                received: link.received,
+                resume: received,
+            }
+        };
+        if ws.send(welcome.encode()).await.is_err() {
+            return;
+        }
+
+        drive(
+            ws,
+            &link,
+            received,
+            self.keepalive,
+            &self.incoming,
+            |to, mut message| {
+                message.sender = id;
+                if to == self.id {
+                    return Some(message);
+                }
+                self.link(to).lock().unwrap().push(to, message);
+                None
+            },
+        )
+        .await;
+    }
+}
+
+impl RoundTransport for WsServer {
+    fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// queue `payload` for `to`, it is delivered once `to` is connected.
+    async fn send(&self, to: u64, round: u8, payload: Vec<u8>) -> Result<(), Error> {
+        if to == self.id {
+            return Err(Error::Transport(format!(
+                "Cannot send to ourselves ({})",
+                to
+            )));
+        }
+        let message = RoundMessage {
+            round,
+            sender: self.id,
+            payload,
+        };
+        self.hub.link(to).lock().unwrap().push(to, message);
+        Ok(())
+    }
+
+    async fn receive(&mut self, round: u8, from: &[u64]) -> Result<Vec<RoundMessage>, Error> {
+        self.inbox.receive(round, from, self.timeout).await
+    }
+}
+
+/// `RoundTransport` for a participant behind NAT: one outgoing WebSocket
+/// to a `WsServer`, kept alive with pings and reopened when it drops.
+pub struct WsClient {
+    id: u64,
+    link: Arc<Mutex<Link>>,
+    inbox: Inbox,
+    timeout: Duration,
+    task: JoinHandle<()>,
+}
+
+impl WsClient {
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+    pub const DEFAULT_KEEPALIVE: Duration = Duration::from_secs(10);
+
+    /// join `session` on the server at `url` (`ws://host:port`) as
+    /// participant `id`. returns right away, the connection is made and
+    /// remade in the background.
+    pub fn connect(id: u64, url: &str, session: &str, keepalive: Duration) -> Self {
+        let (sender, incoming) = mpsc::channel(1024);
+        let link = Arc::new(Mutex::new(Link::default()));
+        let task = tokio::spawn(reconnect(
+            id,
+            url.to_string(),
+            session.to_string(),
+            keepalive,
+            link.clone(),
+            sender,
+        ));
+
+        Self {
+            id,
+            link,
+            inbox: Inbox::new(incoming),
+            timeout: Self::DEFAULT_TIMEOUT,
+            task,
+        }
+    }
+
+    /// how long `receive` keeps waiting.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+}
+
+impl Drop for WsClient {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl RoundTransport for WsClient {
+    fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// queue `payload` for `to`, the server relays it.
+    async fn send(&self, to: u64, round: u8, payload: Vec<u8>) -> Result<(), Error> {
+        if to == self.id {
+            return Err(Error::Transport(format!(
+                "Cannot send to ourselves ({})",
+                to
+            )));
+        }
+        let message = RoundMessage {
+            round,
+            sender: self.id,
+            payload,
+        };
+        self.link.lock().unwrap().push(to, message);
+        Ok(())
+    }
+
+    async fn receive(&mut self, round: u8, from: &[u64]) -> Result<Vec<RoundMessage>, Error> {
+        self.inbox.receive(round, from, self.timeout).await
+    }
+}
+
+async fn reconnect(
+    id: u64,
+    url: String,
+    session: String,
+    keepalive: Duration,
+    link: Arc<Mutex<Link>>,
+    incoming: mpsc::Sender<RoundMessage>,
+) {
+    let mut backoff = MIN_BACKOFF;
+    loop {
+        if let Ok((mut ws, _)) = connect_async_with_config(&url, Some(config()), true).await {
+            let hello = {
+                let link = link.lock().unwrap();
+                Frame::Hello {
+                    session: session.clone(),
+                    id,
+                    received: link.received,
+                    sent: link.outbox.len() as u64,
+                }
+            };
+            let welcome = match ws.send(hello.encode()).await {
+                Ok(()) => match timeout(3 * keepalive, ws.next()).await {
+                    Ok(Some(Ok(message))) => Frame::parse(&message),
+                    _ => None,
+                },
+                Err(_) => None,
+            };
+            if let Some(Frame::Welcome { received, resume }) = welcome {
+                backoff = MIN_BACKOFF;
+                link.lock().unwrap().received = resume;
+                drive(ws, &link, received, keepalive, &incoming, |_, message| {
+                    Some(message)
+                })
+                .await;
+            }
+        }
+
+        sleep(backoff).await;
+        backoff = (2 * backoff).min(MAX_BACKOFF);
+    }
+}
+
+/// run one connection until it fails, goes silent or is stopped: send
+/// the outbox from `sent` on, deliver what arrives through `route`
+/// (None when it was relayed elsewhere) to `incoming`.
+async fn drive<S>(
+    mut ws: WebSocketStream<S>,
+    link: &Mutex<Link>,
+    mut sent: u64,
+    keepalive: Duration,
+    incoming: &mpsc::Sender<RoundMessage>,
+    mut route: impl FnMut(u64, RoundMessage) -> Option<RoundMessage>,
+) where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (queued, stop) = {
+        let link = link.lock().unwrap();
+        (link.queued.clone(), link.stop.clone())
+    };
+    let mut ping = interval(keepalive);
+    let mut last_seen = Instant::now();
+
+    loop {
+        let frames = {
+            let link = link.lock().unwrap();
+            link.outbox
+                .iter()
+                .enumerate()
+                .skip(sent as usize)
+                .map(|(i, (to, message))| Frame::Message {
+                    seq: i as u64 + 1,
+                    to: *to,
+                    round: message.round,
+                    sender: message.sender,
+                    payload: hex::encode(&message.payload),
+                })
+                .collect::<Vec<_>>()
+        };
+        for frame in frames {
+            if ws.send(frame.encode()).await.is_err() {
+                return;
+            }
+            sent += 1;
+        }
+
+        tokio::select! {
+            _ = queued.notified() => {}
+            _ = stop.notified() => {
+                let _ = ws.close(None).await;
+                return;
+            }
+            _ = ping.tick() => {
+                if last_seen.elapsed() > 3 * keepalive {
+                    return;
+                }
+                if ws.send(Message::Ping(Default::default())).await.is_err() {
+                    return;
+                }
+            }
+            message = ws.next() => {
+                let Some(Ok(message)) = message else {
+                    return;
+                };
+                last_seen = Instant::now();
+                let Some(Frame::Message { seq, to, round, sender, payload }) = Frame::parse(&message)
+                else {
+                    continue;
+                };
+                let Ok(payload) = hex::decode(payload) else {
+                    return;
+                };
+                {
+                    let mut link = link.lock().unwrap();
+                    // a resend of something we already have
+                    if seq <= link.received {
+                        continue;
+                    }
+                    link.received = seq;
+                }
+                let message = RoundMessage { round, sender, payload };
+                if let Some(message) = route(to, message)
+                    && incoming.send(message).await.is_err()
+                {
+                    return;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use shamy_core::{
+    schnorr::{SigningConfig, compute_nonce_point, generate_nonce},
+    shamir::shamir_keygen,
+    threshold::partial_sign,
+    util::fingerprint,
+};
+use shamy_net::{
+    Error,
+    audit::{AuditEvent, AuditKind, AuditLog, AuditSink, FileSink, SyslogSink, WebhookSink},
+    coordinator::Coordinator,
+    store::MemoryStore,
+};
+use std::{
+    io::{Read, Write},
+    net::{TcpListener, UdpSocket},
+    sync::{Arc, Mutex},
+    thread,
+};
+
+/// keeps events in memory so tests can inspect them.
+#[derive(Default)]
+struct Recorder {
+    events: Mutex<Vec<AuditEvent>>,
+}
+
+impl AuditSink for Recorder {
+    fn record(&self, event: &AuditEvent) -> Result<(), Error> {
+        self.events.lock().unwrap().push(event.clone());
+        Ok(())
+    }
+}
+
+impl Recorder {
+    fn names(&self) -> Vec<&'static str> {
+        let events = self.events.lock().unwrap();
+        events.iter().map(|e| e.kind.name()).collect()
+    }
+}
+
+fn event(kind: AuditKind) -> AuditEvent {
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    AuditEvent::new(&keygen_output.public_key, "session-1", "primary", kind)
+}
+
+#[test]
+fn test_coordinator_records_session() {
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let signers = &keygen_output.participants[..2];
+    let ids = signers.iter().map(|p| p.id).collect::<Vec<_>>();
+    let recorder = Arc::new(Recorder::default());
+
+    let mut coordinator = Coordinator::new(
+        "primary",
+        Arc::new(MemoryStore::new()),
+        SigningConfig::new("treasury", "withdrawal"),
+    );
+    coordinator.set_audit(recorder.clone());
+
+    coordinator
+        .create_session("s", b"withdraw 1 BTC", keygen_output.public_key, &ids)
+        .unwrap();
+    let nonces = signers.iter().map(|_| generate_nonce()).collect::<Vec<_>>();
+    for (p, r_i) in signers.iter().zip(&nonces) {
+        coordinator
+            .submit_nonce("s", p.id, compute_nonce_point(r_i))
+            .unwrap();
+    }
+    assert!(
+        coordinator
+            .submit_nonce("s", 3, compute_nonce_point(&nonces[0]))
+            .is_err()
+    );
+
+    let (_, c) = coordinator.challenge("s").unwrap();
+    for (p, r_i) in signers.iter().zip(&nonces) {
+        let partial = partial_sign(p, r_i, &c);
+        coordinator.submit_partial("s", p.id, partial.s_i).unwrap();
+    }
+    coordinator.finalize("s").unwrap();
+
+    assert_eq!(
+        recorder.names(),
+        [
+            "session_created",
+            "nonce_received",
+            "nonce_received",
+            "rejected",
+            "partial_received",
+            "partial_received",
+            "finalized",
+        ]
+    );
+    let events = recorder.events.lock().unwrap();
+    assert!(
+        events
+            .iter()
+            .all(|e| e.key == fingerprint(&keygen_output.public_key) && e.session_id == "s")
+    );
+    assert!(!events[0].to_json().contains("withdraw"));
+}
+
+#[test]
+fn test_audit_log_routes_per_key() {
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let other = shamir_keygen(3, 2).unwrap();
+    let all = Arc::new(Recorder::default());
+    let per_key = Arc::new(Recorder::default());
+
+    let mut log = AuditLog::new();
+    log.add_sink(all.clone());
+    log.add_key_sink(&keygen_output.public_key, per_key.clone());
+
+    let kind = AuditKind::TakenOver { epoch: 2 };
+    log.record(&AuditEvent::new(
+        &keygen_output.public_key,
+        "a",
+        "c",
+        kind.clone(),
+    ))
+    .unwrap();
+    log.record(&AuditEvent::new(&other.public_key, "b", "c", kind))
+        .unwrap();
+
+    assert_eq!(all.events.lock().unwrap().len(), 2);
+    assert_eq!(per_key.events.lock().unwrap()[0].session_id, "a");
+    assert_eq!(per_key.events.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn test_file_sink_rotates() {
+    let dir = std::env::temp_dir().join(format!("shamy-audit-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("audit.log");
+
+    let sink = FileSink::new(&path, 300, 2).unwrap();
+    for id in 0..10 {
+        sink.record(&event(AuditKind::PartialReceived { id }))
+            .unwrap();
+    }
+
+    let current = std::fs::read_to_string(&path).unwrap();
+    assert!(current.len() <= 300);
+    assert!(current.contains("\"id\":9"));
+    assert!(dir.join("audit.log.1").exists());
+    assert!(dir.join("audit.log.2").exists());
+    assert!(!dir.join("audit.log.3").exists());
+    for line in current.lines() {
+        let value: serde_json::Value = serde_json::from_str(line).unwrap();
+        assert_eq!(value["event"], "partial_received");
+    }
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_syslog_sink() {
+    let collector = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let sink = SyslogSink::udp(collector.local_addr().unwrap(), "shamy").unwrap();
+
+    let reason = "Participant 9 is not a signer".to_string();
+    sink.record(&event(AuditKind::Rejected { reason })).unwrap();
+
+    let mut buf = [0; 2048];
+    let len = collector.recv(&mut buf).unwrap();
+    let message = String::from_utf8_lossy(&buf[..len]);
+    // authpriv.warning
+    assert!(message.starts_with("<84>1 - - shamy "));
+    assert!(message.contains(" rejected - {"));
+    assert!(message.contains("Participant 9 is not a signer"));
+}
+
+#[test]
+fn test_webhook_sink() {
+    assert!(WebhookSink::new("http://siem.example.com/events").is_err());
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/events", listener.local_addr().unwrap());
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = Vec::new();
+        let mut buf = [0; 4096];
+        while !request.ends_with(b"}") {
+            let len = stream.read(&mut buf).unwrap();
+            request.extend_from_slice(&buf[..len]);
+        }
+        stream
+            .write_all(b"HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n")
+            .unwrap();
+        String::from_utf8(request).unwrap()
+    });
+
+    let sink = WebhookSink::insecure(&url).with_header("Authorization", "Bearer token");
+    sink.record(&event(AuditKind::TakenOver { epoch: 2 }))
+        .unwrap();
+
+    let request = server.join().unwrap();
+    assert!(request.starts_with("POST /events"));
+    assert!(
+        request
+            .to_lowercase()
+            .contains("authorization: bearer token")
+    );
+    assert!(request.contains("\"event\":\"taken_over\""));
+}
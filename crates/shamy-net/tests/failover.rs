@@ -0,0 +1,151 @@
+#![allow(non_snake_case)]
+
+use k256::Scalar;
+use shamy_core::{
+    schnorr::{SigningConfig, compute_nonce_point, generate_nonce},
+    shamir::{KeygenOutput, shamir_keygen},
+    threshold::{Participant, partial_sign},
+};
+use shamy_net::{
+    Error,
+    coordinator::Coordinator,
+    store::{FileStore, MemoryStore, SessionStore},
+};
+use std::sync::Arc;
+
+const SESSION: &str = "session-1";
+const MESSAGE: &[u8] = b"withdraw 1 BTC";
+
+fn config() -> SigningConfig {
+    SigningConfig::new("treasury", "withdrawal")
+}
+
+/// signers and their nonces, generated once: they must survive a failover.
+fn signers(keygen_output: &KeygenOutput, t: usize) -> Vec<(Participant, Scalar)> {
+    keygen_output
+        .participants
+        .iter()
+        .take(t)
+        .map(|p| (*p, generate_nonce()))
+        .collect()
+}
+
+fn run_failover<S: SessionStore + Clone>(store: S) {
+    let keygen_output = shamir_keygen(5, 3).unwrap();
+    let signers = signers(&keygen_output, 3);
+    let ids = signers.iter().map(|(p, _)| p.id).collect::<Vec<_>>();
+
+    let primary = Coordinator::new("primary", store.clone(), config());
+    let backup = Coordinator::new("backup", store, config());
+
+    primary
+        .create_session(SESSION, MESSAGE, keygen_output.public_key, &ids)
+        .unwrap();
+    for (p, r_i) in &signers {
+        primary
+            .submit_nonce(SESSION, p.id, compute_nonce_point(r_i))
+            .unwrap();
+    }
+    let (_, c) = primary.challenge(SESSION).unwrap();
+    let (first, _) = signers[0];
+    primary
+        .submit_partial(
+            SESSION,
+            first.id,
+            partial_sign(&first, &signers[0].1, &c).s_i,
+        )
+        .unwrap();
+
+    // the primary dies here, the backup resumes with the same nonces
+    let state = backup.take_over(SESSION).unwrap();
+    assert_eq!(state.partials.len(), 1);
+    assert_eq!(backup.challenge(SESSION).unwrap().1, c);
+
+    for (p, r_i) in &signers[1..] {
+        backup
+            .submit_partial(SESSION, p.id, partial_sign(p, r_i, &c).s_i)
+            .unwrap();
+    }
+
+    // a primary that comes back can't interfere anymore
+    assert!(matches!(
+        primary.finalize(SESSION),
+        Err(Error::Fenced { epoch: 2, .. })
+    ));
+
+    let signature = backup.finalize(SESSION).unwrap();
+    assert!(signature.verify_with_config(MESSAGE, &keygen_output.public_key, &config()));
+
+    assert_eq!(backup.finalize(SESSION), Err(Error::AlreadyFinalized));
+    assert_eq!(backup.state(SESSION).unwrap().signature, Some(signature));
+}
+
+#[test]
+fn test_failover_memory_store() {
+    run_failover(Arc::new(MemoryStore::new()));
+}
+
+#[test]
+fn test_failover_file_store() {
+    let dir = std::env::temp_dir().join(format!("shamy-failover-{}", std::process::id()));
+    run_failover(Arc::new(FileStore::new(&dir).unwrap()));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_session_rejects_out_of_order_and_foreign_messages() {
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let signers = signers(&keygen_output, 2);
+    let ids = signers.iter().map(|(p, _)| p.id).collect::<Vec<_>>();
+    let coordinator = Coordinator::new("primary", MemoryStore::new(), config());
+
+    coordinator
+        .create_session(SESSION, MESSAGE, keygen_output.public_key, &ids)
+        .unwrap();
+    assert_eq!(
+        coordinator.create_session(SESSION, MESSAGE, keygen_output.public_key, &ids),
+        Err(Error::SessionExists(SESSION.to_string()))
+    );
+
+    let (p, r_i) = signers[0];
+    let R_i = compute_nonce_point(&r_i);
+    assert_eq!(
+        coordinator.submit_partial(SESSION, p.id, Scalar::ONE),
+        Err(Error::Incomplete {
+            missing: ids.clone()
+        })
+    );
+    coordinator.submit_nonce(SESSION, p.id, R_i).unwrap();
+    assert_eq!(
+        coordinator.submit_nonce(SESSION, p.id, R_i),
+        Err(Error::DuplicateMessage(p.id))
+    );
+    assert_eq!(
+        coordinator.submit_nonce(SESSION, 3, R_i),
+        Err(Error::UnexpectedSigner(3))
+    );
+    assert!(matches!(
+        coordinator.challenge(SESSION),
+        Err(Error::Incomplete { .. })
+    ));
+}
+
+#[test]
+fn test_concurrent_writes_conflict() {
+    let store = Arc::new(MemoryStore::new());
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let coordinator = Coordinator::new("primary", store.clone(), config());
+    coordinator
+        .create_session(SESSION, MESSAGE, keygen_output.public_key, &[1, 2])
+        .unwrap();
+
+    let stale = store.load(SESSION).unwrap().unwrap();
+    coordinator
+        .submit_nonce(SESSION, 1, compute_nonce_point(&generate_nonce()))
+        .unwrap();
+
+    assert!(matches!(
+        store.compare_and_swap(Some(stale.version), &stale),
+        Err(Error::Conflict { .. })
+    ));
+}
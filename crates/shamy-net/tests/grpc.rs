@@ -0,0 +1,108 @@
+#![cfg(feature = "grpc")]
+
+use shamy_core::{
+    schnorr::{SchnorrSignature, SigningConfig, compute_nonce_point, generate_nonce},
+    shamir::shamir_keygen,
+    threshold::partial_sign,
+    util::hex_to_scalar,
+};
+use shamy_net::{
+    coordinator::Coordinator,
+    grpc::{CoordinatorClient, CoordinatorService},
+    store::MemoryStore,
+    wire,
+};
+use tokio::net::TcpListener;
+use tonic::{Code, transport::Server, transport::server::TcpIncoming};
+
+#[tokio::test]
+async fn test_signing_over_grpc() {
+    let config = SigningConfig::new("treasury", "withdrawal");
+    let coordinator = Coordinator::new("primary", MemoryStore::new(), config.clone());
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(
+        Server::builder()
+            .add_service(CoordinatorService::new(coordinator).into_server())
+            .serve_with_incoming(TcpIncoming::from(listener)),
+    );
+
+    let mut client = CoordinatorClient::connect(format!("http://{}", addr))
+        .await
+        .unwrap();
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let signers = &keygen_output.participants[1..];
+    let message = b"withdraw 1 BTC".to_vec();
+
+    client
+        .create_session(wire::CreateSessionRequest {
+            session_id: "s".to_string(),
+            message: message.clone(),
+            public_key: wire::point_to_bytes(&keygen_output.public_key),
+            signer_ids: signers.iter().map(|p| p.id).collect(),
+        })
+        .await
+        .unwrap();
+
+    let nonces = signers.iter().map(|_| generate_nonce()).collect::<Vec<_>>();
+    for (p, r_i) in signers.iter().zip(&nonces) {
+        let nonce = wire::NonceCommitment::new(p.id, &compute_nonce_point(r_i));
+        client
+            .submit_nonce(wire::SubmitNonceRequest {
+                session_id: "s".to_string(),
+                nonce: Some(nonce),
+            })
+            .await
+            .unwrap();
+    }
+
+    let challenge = client
+        .get_challenge(wire::GetChallengeRequest {
+            session_id: "s".to_string(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    let c = wire::scalar_from_bytes(&challenge.challenge).unwrap();
+    for (p, r_i) in signers.iter().zip(&nonces) {
+        let partial = wire::PartialSignature::from(&partial_sign(p, r_i, &c));
+        client
+            .submit_partial(wire::SubmitPartialRequest {
+                session_id: "s".to_string(),
+                partial: Some(partial),
+            })
+            .await
+            .unwrap();
+    }
+
+    let signature = client
+        .finalize(wire::FinalizeRequest {
+            session_id: "s".to_string(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    let signature = SchnorrSignature::try_from(&signature).unwrap();
+    assert!(signature.verify_with_config(&message, &keygen_output.public_key, &config));
+
+    let status = client
+        .finalize(wire::FinalizeRequest {
+            session_id: "unknown".to_string(),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::NotFound);
+
+    let status = client
+        .submit_partial(wire::SubmitPartialRequest {
+            session_id: "s".to_string(),
+            partial: Some(wire::PartialSignature {
+                id: 1,
+                s_i: wire::scalar_to_bytes(&hex_to_scalar(&hex::encode([1u8; 32])).unwrap()),
+                ..Default::default()
+            }),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::FailedPrecondition);
+}
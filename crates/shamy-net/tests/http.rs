@@ -0,0 +1,130 @@
+#![cfg(feature = "http")]
+#![allow(non_snake_case)]
+
+use serde_json::{Value, json};
+use shamy_core::{
+    schnorr::{SchnorrSignature, SigningConfig, compute_nonce_point, generate_nonce},
+    shamir::shamir_keygen,
+    threshold::partial_sign,
+    util::{hex_to_scalar, pp_to_hex, scalar_to_hex},
+};
+use shamy_net::{coordinator::Coordinator, http, store::MemoryStore};
+
+fn config() -> SigningConfig {
+    SigningConfig::new("shamy-net-tests", "http")
+}
+
+/// serve the REST API on a free port, returns its base url.
+async fn serve() -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let router = http::router(Coordinator::new("hub", MemoryStore::new(), config()));
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+    url
+}
+
+/// one blocking request, as a signer without an async runtime would make it.
+async fn call(method: &'static str, url: String, body: Option<Value>) -> (u16, Value) {
+    tokio::task::spawn_blocking(move || {
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .http_status_as_error(false)
+            .build()
+            .into();
+        let response = match (method, body) {
+            ("GET", _) => agent.get(&url).call(),
+            (_, Some(body)) => agent
+                .post(&url)
+                .header("Content-Type", "application/json")
+                .send(body.to_string()),
+            (_, None) => agent.post(&url).send_empty(),
+        };
+        let mut response = response.unwrap();
+        let status = response.status().as_u16();
+        let body = response.body_mut().read_to_string().unwrap();
+        (status, serde_json::from_str(&body).unwrap_or(Value::Null))
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_signing_session_over_http() {
+    let url = serve().await;
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let X = keygen_output.public_key;
+    let signers = [keygen_output.participants[0], keygen_output.participants[2]];
+    let message = b"rendezvous through the hub";
+
+    let (status, _) = call(
+        "POST",
+        format!("{url}/sessions"),
+        Some(json!({
+            "session_id": "s1",
+            "message": hex::encode(message),
+            "public_key": pp_to_hex(&X),
+            "signer_ids": [1, 3],
+        })),
+    )
+    .await;
+    assert_eq!(status, 201);
+
+    let nonces = signers.map(|_| generate_nonce());
+    for (signer, r_i) in signers.iter().zip(&nonces) {
+        // the challenge isn't there until every nonce is
+        let (status, body) = call("GET", format!("{url}/sessions/s1/challenge"), None).await;
+        assert_eq!(status, 409, "{body}");
+
+        let nonce = json!({ "id": signer.id, "nonce": pp_to_hex(&compute_nonce_point(r_i)) });
+        let (status, _) = call("POST", format!("{url}/sessions/s1/nonces"), Some(nonce)).await;
+        assert_eq!(status, 204);
+    }
+
+    let (status, challenge) = call("GET", format!("{url}/sessions/s1/challenge"), None).await;
+    assert_eq!(status, 200);
+    let c = hex_to_scalar(challenge["challenge"].as_str().unwrap()).unwrap();
+    for (signer, r_i) in signers.iter().zip(&nonces) {
+        let s_i = partial_sign(signer, r_i, &c).s_i;
+        let partial = json!({ "id": signer.id, "s_i": scalar_to_hex(&s_i) });
+        let (status, _) = call("POST", format!("{url}/sessions/s1/partials"), Some(partial)).await;
+        assert_eq!(status, 204);
+    }
+
+    let (status, body) = call("POST", format!("{url}/sessions/s1/finalize"), None).await;
+    assert_eq!(status, 200);
+    let signature =
+        SchnorrSignature::from_bytes(&hex::decode(body["signature"].as_str().unwrap()).unwrap())
+            .unwrap();
+    assert!(signature.verify_with_config(message, &X, &config()));
+
+    let (status, state) = call("GET", format!("{url}/sessions/s1"), None).await;
+    assert_eq!(status, 200);
+    assert_eq!(state["partials"], json!([1, 3]));
+    assert_eq!(state["signature"], body["signature"]);
+}
+
+#[tokio::test]
+async fn test_http_errors() {
+    let url = serve().await;
+    let X = shamir_keygen(3, 2).unwrap().public_key;
+    let session = json!({
+        "session_id": "s1",
+        "message": "00",
+        "public_key": pp_to_hex(&X),
+        "signer_ids": [1, 2],
+    });
+    call("POST", format!("{url}/sessions"), Some(session.clone())).await;
+
+    let (status, body) = call("POST", format!("{url}/sessions"), Some(session)).await;
+    assert_eq!(status, 409);
+    assert!(body["error"].as_str().unwrap().contains("already exists"));
+
+    let (status, _) = call("GET", format!("{url}/sessions/unknown"), None).await;
+    assert_eq!(status, 404);
+
+    let outsider = json!({ "id": 3, "nonce": pp_to_hex(&X) });
+    let (status, _) = call("POST", format!("{url}/sessions/s1/nonces"), Some(outsider)).await;
+    assert_eq!(status, 400);
+    let garbage = json!({ "id": 1, "nonce": "zz" });
+    let (status, _) = call("POST", format!("{url}/sessions/s1/nonces"), Some(garbage)).await;
+    assert_eq!(status, 400);
+}
@@ -0,0 +1,194 @@
+use k256::ProjectivePoint;
+use shamy_core::{
+    schnorr::SigningConfig,
+    shamir::{KeygenOutput, shamir_keygen},
+    threshold::Participant,
+};
+use shamy_net::{
+    Error,
+    pipeline::{NonceCommitments, Pipeline, SignerNonces},
+};
+
+fn config() -> SigningConfig {
+    SigningConfig::new("signing-service", "pipeline")
+}
+
+fn setup(batch: usize) -> (KeygenOutput, Vec<(Participant, SignerNonces)>, Pipeline) {
+    let keygen_output = shamir_keygen(5, 3).unwrap();
+    let mut signers = keygen_output
+        .participants
+        .iter()
+        .take(3)
+        .map(|p| (*p, SignerNonces::new(p.id)))
+        .collect::<Vec<_>>();
+    let ids = signers.iter().map(|(p, _)| p.id).collect::<Vec<_>>();
+
+    let mut pipeline = Pipeline::new(keygen_output.public_key, &ids, config()).unwrap();
+    for (_, nonces) in signers.iter_mut() {
+        pipeline.precommit(nonces.commit(batch)).unwrap();
+    }
+
+    (keygen_output, signers, pipeline)
+}
+
+#[test]
+fn test_pipelined_sessions_overlap() {
+    let (keygen_output, mut signers, mut pipeline) = setup(4);
+    assert_eq!(pipeline.available(), 4);
+
+    // start every session before any partial of the first one arrived
+    let messages = [b"msg 0".as_slice(), b"msg 1", b"msg 2", b"msg 3"];
+    let tickets = messages
+        .iter()
+        .map(|m| pipeline.start(m).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(pipeline.available(), 0);
+    assert_eq!(pipeline.in_flight(), vec![0, 1, 2, 3]);
+
+    // partials arrive in reverse order
+    for ticket in tickets.iter().rev() {
+        for (participant, nonces) in signers.iter_mut() {
+            let partial = nonces
+                .sign(participant, ticket, &keygen_output.public_key, &config())
+                .unwrap();
+            pipeline.submit_partial(ticket.seq, partial).unwrap();
+        }
+        let signature = pipeline.finalize(ticket.seq).unwrap();
+        assert!(signature.verify_with_config(
+            &ticket.message,
+            &keygen_output.public_key,
+            &config()
+        ));
+    }
+    assert!(pipeline.in_flight().is_empty());
+}
+
+#[test]
+fn test_nonces_are_single_use() {
+    let (keygen_output, mut signers, mut pipeline) = setup(1);
+    let ticket = pipeline.start(b"msg").unwrap();
+
+    let (participant, nonces) = &mut signers[0];
+    let public_key = keygen_output.public_key;
+    nonces
+        .sign(participant, &ticket, &public_key, &config())
+        .unwrap();
+    assert_eq!(
+        nonces.sign(participant, &ticket, &public_key, &config()),
+        Err(Error::UnknownNonce {
+            id: participant.id,
+            index: ticket.nonce_index(participant.id).unwrap()
+        })
+    );
+
+    assert!(matches!(
+        pipeline.start(b"next"),
+        Err(Error::Incomplete { .. })
+    ));
+}
+
+#[test]
+fn test_signer_recomputes_challenge() {
+    let (keygen_output, mut signers, mut pipeline) = setup(2);
+    let public_key = keygen_output.public_key;
+    let ticket = pipeline.start(b"msg").unwrap();
+
+    // a ticket whose message was swapped after the nonces were bound gives
+    // partials for the swapped message, which don't combine with the others
+    let mut forged = ticket.clone();
+    forged.message = b"other".to_vec();
+    let (participant, nonces) = &mut signers[0];
+    let partial = nonces
+        .sign(participant, &forged, &public_key, &config())
+        .unwrap();
+    pipeline.submit_partial(ticket.seq, partial).unwrap();
+    for (participant, nonces) in signers.iter_mut().skip(1) {
+        let partial = nonces
+            .sign(participant, &ticket, &public_key, &config())
+            .unwrap();
+        pipeline.submit_partial(ticket.seq, partial).unwrap();
+    }
+    assert!(pipeline.finalize(ticket.seq).is_err());
+
+    // so does a commitment list that doesn't hold the signer's own pair
+    let mut forged = pipeline.start(b"msg").unwrap();
+    let (participant, nonces) = &mut signers[0];
+    forged.commitments.retain(|c| c.id != participant.id);
+    assert!(
+        nonces
+            .sign(participant, &forged, &public_key, &config())
+            .is_err()
+    );
+}
+
+#[test]
+fn test_precommit_rejects_replayed_batch() {
+    let (_, signers, mut pipeline) = setup(2);
+    let (participant, _) = &signers[0];
+
+    let replay = NonceCommitments {
+        id: participant.id,
+        first_index: 0,
+        nonces: vec![],
+    };
+    assert_eq!(
+        pipeline.precommit(replay),
+        Err(Error::UnexpectedNonceIndex {
+            id: participant.id,
+            expected: 2,
+            actual: 0
+        })
+    );
+
+    let stranger = NonceCommitments {
+        id: 5,
+        first_index: 0,
+        nonces: vec![],
+    };
+    assert_eq!(
+        pipeline.precommit(stranger),
+        Err(Error::UnexpectedSigner(5))
+    );
+}
+
+#[test]
+fn test_new_rejects_bad_signer_ids() {
+    let keygen_output = shamir_keygen(5, 3).unwrap();
+    let public_key = keygen_output.public_key;
+    assert!(matches!(
+        Pipeline::new(public_key, &[1, 2, 2], config()),
+        Err(Error::Core(_))
+    ));
+    assert!(matches!(
+        Pipeline::new(public_key, &[0, 1, 2], config()),
+        Err(Error::Core(_))
+    ));
+    assert!(matches!(
+        Pipeline::new(public_key, &[], config()),
+        Err(Error::Core(_))
+    ));
+}
+
+#[test]
+fn test_rejected_start_keeps_nonces() {
+    let keygen_output = shamir_keygen(5, 3).unwrap();
+    let ids = [1, 2, 3];
+    let mut pipeline = Pipeline::new(keygen_output.public_key, &ids, config()).unwrap();
+    for id in &ids[1..] {
+        pipeline
+            .precommit(SignerNonces::new(*id).commit(1))
+            .unwrap();
+    }
+    // a pair at infinity makes R_1 the identity
+    pipeline
+        .precommit(NonceCommitments {
+            id: ids[0],
+            first_index: 0,
+            nonces: vec![(ProjectivePoint::IDENTITY, ProjectivePoint::IDENTITY)],
+        })
+        .unwrap();
+
+    assert!(pipeline.start(b"msg").is_err());
+    assert_eq!(pipeline.available(), 1);
+    assert!(pipeline.in_flight().is_empty());
+}
@@ -0,0 +1,138 @@
+#![allow(non_snake_case)]
+
+use sha2::{Digest, Sha256};
+use shamy_core::{backend::MemoryBackend, schnorr::SigningConfig, shamir::shamir_keygen};
+use shamy_net::{
+    Error, ceremony,
+    signer::{SignerDaemon, SignerPolicy},
+    transport::{RoundTransport, TcpTransport},
+};
+use std::time::Duration;
+
+const COORDINATOR: u64 = 100;
+
+/// transports for `ids` on localhost, all knowing each other.
+async fn connect(ids: &[u64]) -> Vec<TcpTransport> {
+    let mut transports = Vec::new();
+    for id in ids {
+        let mut transport = TcpTransport::bind(*id, "127.0.0.1:0").await.unwrap();
+        transport.set_timeout(Duration::from_millis(500));
+        transports.push(transport);
+    }
+    let addrs = transports
+        .iter()
+        .map(|t| (t.id(), t.local_addr()))
+        .collect::<Vec<_>>();
+    for transport in transports.iter_mut() {
+        for (id, addr) in &addrs {
+            transport.add_peer(*id, *addr);
+        }
+    }
+
+    transports
+}
+
+#[test]
+fn test_signer_policy() {
+    assert!(!SignerPolicy::deny_all().allows(b"anything"));
+    assert!(SignerPolicy::allow_any().allows(b"anything"));
+
+    let policy = SignerPolicy::deny_all()
+        .allow_prefix(b"pay ")
+        .allow_sha256(Sha256::digest(b"rotate keys").into());
+    assert!(policy.allows(b"pay alice 5"));
+    assert!(policy.allows(b"rotate keys"));
+    assert!(!policy.allows(b"rotate keys now"));
+    assert!(matches!(policy.check(b"drain"), Err(Error::Rejected(_))));
+}
+
+#[tokio::test]
+async fn test_signer_daemons_follow_their_policy() {
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let X = keygen_output.public_key;
+    let config = SigningConfig::new("shamy-net-tests", "daemon");
+
+    let mut transports = connect(&[1, 3, COORDINATOR]).await;
+    let mut coordinator = transports.pop().unwrap();
+    let daemon = |transport, index: usize, policy| {
+        let participant = keygen_output.participants[index];
+        SignerDaemon::new(
+            transport,
+            COORDINATOR,
+            participant,
+            X,
+            config.clone(),
+            policy,
+        )
+    };
+    let mut d3 = daemon(transports.pop().unwrap(), 2, SignerPolicy::allow_any());
+    let mut d1 = daemon(
+        transports.pop().unwrap(),
+        0,
+        SignerPolicy::deny_all().allow_prefix(b"pay "),
+    );
+
+    let msg = b"pay alice 5";
+    let (signature, r1, r3) = tokio::join!(
+        ceremony::sign_as_coordinator(&mut coordinator, X, msg, &[1, 3], config.clone()),
+        d1.handle_next(),
+        d3.handle_next(),
+    );
+    assert!(signature.unwrap().verify_with_config(msg, &X, &config));
+    assert_eq!(r1.unwrap(), msg);
+    assert_eq!(r3.unwrap(), msg);
+
+    // signer 1 refuses, the session can't complete
+    let (signature, r1, _) = tokio::join!(
+        ceremony::sign_as_coordinator(&mut coordinator, X, b"drain", &[1, 3], config.clone()),
+        d1.handle_next(),
+        d3.handle_next(),
+    );
+    assert!(matches!(r1, Err(Error::Rejected(_))));
+    assert_eq!(
+        signature,
+        Err(Error::Timeout {
+            round: 2,
+            missing: vec![1]
+        })
+    );
+
+    // an idle daemon just times out waiting for a request
+    assert!(matches!(
+        d1.handle_next().await,
+        Err(Error::Timeout { round: 1, .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_signer_with_backend_discards_aborted_nonces() {
+    let keygen_output = shamir_keygen(3, 2).unwrap();
+    let X = keygen_output.public_key;
+    let config = SigningConfig::new("shamy-net-tests", "backend");
+    let policy = SignerPolicy::allow_any();
+    let backends = [0, 2].map(|i| MemoryBackend::new(keygen_output.participants[i]));
+
+    let mut transports = connect(&[1, 3, COORDINATOR]).await;
+    let mut coordinator = transports.pop().unwrap();
+    let [t1, t3] = &mut transports[..] else {
+        unreachable!()
+    };
+
+    let msg = b"signed in a backend";
+    let (signature, r1, r3) = tokio::join!(
+        ceremony::sign_as_coordinator(&mut coordinator, X, msg, &[1, 3], config.clone()),
+        ceremony::sign_as_signer_with_backend(t1, COORDINATOR, &backends[0], &X, &config, &policy),
+        ceremony::sign_as_signer_with_backend(t3, COORDINATOR, &backends[1], &X, &config, &policy),
+    );
+    assert!(signature.unwrap().verify_with_config(msg, &X, &config));
+    assert_eq!((r1.unwrap(), r3.unwrap()), (msg.to_vec(), msg.to_vec()));
+
+    // signer 1 never answers, signer 3 times out after committing
+    let (signature, r3) = tokio::join!(
+        ceremony::sign_as_coordinator(&mut coordinator, X, msg, &[1, 3], config.clone()),
+        ceremony::sign_as_signer_with_backend(t3, COORDINATOR, &backends[1], &X, &config, &policy),
+    );
+    assert!(signature.is_err());
+    assert!(matches!(r3, Err(Error::Timeout { round: 3, .. })));
+    assert_eq!(backends[1].open_sessions(), 0);
+}
@@ -0,0 +1,222 @@
+use shamy_core::hash::tagged_hash;
+use shamy_core::identity::{IdentityKey, IdentityRegistry};
+use shamy_core::schnorr::SigningConfig;
+use shamy_net::{
+    Error, ceremony,
+    transport::{AuthenticatedTransport, RoundTransport, TcpTransport},
+};
+use std::time::Duration;
+
+const COORDINATOR: u64 = 100;
+
+/// transports for `ids` on localhost, all knowing each other.
+async fn connect(ids: &[u64]) -> Vec<TcpTransport> {
+    let mut transports = Vec::new();
+    for id in ids {
+        transports.push(TcpTransport::bind(*id, "127.0.0.1:0").await.unwrap());
+    }
+    let addrs = transports
+        .iter()
+        .map(|t| (t.id(), t.local_addr()))
+        .collect::<Vec<_>>();
+    for transport in transports.iter_mut() {
+        for (id, addr) in &addrs {
+            transport.add_peer(*id, *addr);
+        }
+    }
+
+    transports
+}
+
+#[tokio::test]
+async fn test_dkg_and_signing_over_tcp() {
+    let mut transports = connect(&[1, 2, 3, COORDINATOR]).await;
+    let mut coordinator = transports.pop().unwrap();
+    let [t1, t2, t3] = &mut transports[..] else {
+        unreachable!()
+    };
+
+    let (o1, o2, o3) = tokio::join!(
+        ceremony::dkg(t1, 2, 3),
+        ceremony::dkg(t2, 2, 3),
+        ceremony::dkg(t3, 2, 3)
+    );
+    let (o1, _, o3) = (o1.unwrap(), o2.unwrap(), o3.unwrap());
+    assert_eq!(o1.public_key, o3.public_key);
+
+    let config = SigningConfig::new("shamy-net-tests", "transport");
+    let msg = b"signed across machines";
+    let (signature, r1, r3) = tokio::join!(
+        ceremony::sign_as_coordinator(
+            &mut coordinator,
+            o1.public_key,
+            msg,
+            &[1, 3],
+            config.clone()
+        ),
+        ceremony::sign_as_signer(t1, COORDINATOR, &o1.participant, &o1.public_key, &config),
+        ceremony::sign_as_signer(t3, COORDINATOR, &o3.participant, &o3.public_key, &config),
+    );
+    r1.unwrap();
+    r3.unwrap();
+    assert!(
+        signature
+            .unwrap()
+            .verify_with_config(msg, &o1.public_key, &config)
+    );
+}
+
+#[tokio::test]
+async fn test_receive_buffers_later_rounds_and_times_out() {
+    let mut transports = connect(&[1, 2]).await;
+    let (a, b) = transports.split_at_mut(1);
+    let (a, b) = (&mut a[0], &mut b[0]);
+    b.set_timeout(Duration::from_millis(200));
+
+    a.send(2, 2, b"second".to_vec()).await.unwrap();
+    a.send(2, 1, b"first".to_vec()).await.unwrap();
+
+    assert_eq!(b.receive(1, &[1]).await.unwrap()[0].payload, b"first");
+    assert_eq!(b.receive(2, &[1]).await.unwrap()[0].payload, b"second");
+    assert_eq!(
+        b.receive(3, &[1]).await,
+        Err(Error::Timeout {
+            round: 3,
+            missing: vec![1]
+        })
+    );
+}
+
+/// identity keys for `ids` and the registry every participant shares.
+fn identities(ids: &[u64]) -> (Vec<IdentityKey>, IdentityRegistry) {
+    let keys = ids
+        .iter()
+        .map(|_| IdentityKey::generate())
+        .collect::<Vec<_>>();
+    let mut registry = IdentityRegistry::new();
+    for (id, key) in ids.iter().zip(&keys) {
+        registry.insert(*id, key.public_key()).unwrap();
+    }
+    (keys, registry)
+}
+
+#[tokio::test]
+async fn test_dkg_over_authenticated_transport() {
+    let (keys, registry) = identities(&[1, 2, 3]);
+    let mut transports = connect(&[1, 2, 3])
+        .await
+        .into_iter()
+        .zip(keys)
+        .map(|(transport, key)| {
+            AuthenticatedTransport::new(transport, key, registry.clone(), b"dkg-session")
+        })
+        .collect::<Vec<_>>();
+    let [t1, t2, t3] = &mut transports[..] else {
+        unreachable!()
+    };
+
+    let (o1, o2, o3) = tokio::join!(
+        ceremony::dkg(t1, 2, 3),
+        ceremony::dkg(t2, 2, 3),
+        ceremony::dkg(t3, 2, 3)
+    );
+    let (o1, o2, o3) = (o1.unwrap(), o2.unwrap(), o3.unwrap());
+    assert_eq!(o1.public_key, o2.public_key);
+    assert_eq!(o1.public_key, o3.public_key);
+}
+
+#[tokio::test]
+async fn test_authenticated_transport_rejects_forgeries() {
+    let (mut keys, registry) = identities(&[1, 2]);
+    let mut transports = connect(&[1, 2]).await;
+    let receiver = transports.pop().unwrap();
+    let sender = transports.pop().unwrap();
+    let mut receiver =
+        AuthenticatedTransport::new(receiver, keys.pop().unwrap(), registry.clone(), b"ctx");
+    let honest_key = keys.pop().unwrap();
+
+    // unsigned, signed with another key, for another ceremony, or replayed
+    // into another round: all fail on receipt
+    sender.send(2, 1, b"plain".to_vec()).await.unwrap();
+    let forged = IdentityKey::generate().sign(b"ctx", 2, 1, 2, b"forged");
+    sender.send(2, 2, forged.to_bytes()).await.unwrap();
+    let other_context = honest_key.sign(b"other", 3, 1, 2, b"payload");
+    sender.send(2, 3, other_context.to_bytes()).await.unwrap();
+    let replayed = honest_key.sign(b"ctx", 4, 1, 2, b"payload");
+    sender.send(2, 5, replayed.to_bytes()).await.unwrap();
+
+    assert!(matches!(
+        receiver.receive(1, &[1]).await,
+        Err(Error::InvalidMessage(_))
+    ));
+    for round in [2, 3] {
+        assert_eq!(
+            receiver.receive(round, &[1]).await,
+            Err(Error::Core(shamy_core::Error::InvalidMessageSignature {
+                round,
+                id: 1
+            }))
+        );
+    }
+    assert!(matches!(
+        receiver.receive(5, &[1]).await,
+        Err(Error::InvalidMessage(_))
+    ));
+
+    let sender = AuthenticatedTransport::new(sender, honest_key, registry, b"ctx");
+    sender.send(2, 6, b"genuine".to_vec()).await.unwrap();
+    assert_eq!(
+        receiver.receive(6, &[1]).await.unwrap()[0].payload,
+        b"genuine"
+    );
+    assert_eq!(sender.inner().id(), 1);
+}
+
+#[tokio::test]
+async fn test_echo_broadcast_detects_equivocation() {
+    let mut transports = connect(&[1, 2, 3]).await;
+    let [dealer, t2, t3] = &mut transports[..] else {
+        unreachable!()
+    };
+    let digest = |payload: &[u8]| hex::encode(tagged_hash(b"shamy/transport/echo/v1", payload));
+
+    // participant 1 shows each peer different commitments, then echoes
+    // what it told 2
+    let equivocate = async {
+        dealer.send(2, 1, b"commitments A".to_vec()).await.unwrap();
+        dealer.send(3, 1, b"commitments B".to_vec()).await.unwrap();
+        let echo = serde_json::json!({ "digests": [
+            (1, digest(b"commitments A")),
+            (2, digest(b"from 2")),
+            (3, digest(b"from 3")),
+        ] });
+        dealer
+            .broadcast(&[2, 3], 2, serde_json::to_vec(&echo).unwrap())
+            .await
+            .unwrap();
+    };
+    let (_, r2, r3) = tokio::join!(
+        equivocate,
+        t2.echo_broadcast(&[1, 3], 1, b"from 2".to_vec()),
+        t3.echo_broadcast(&[1, 2], 1, b"from 3".to_vec()),
+    );
+
+    let expected = Err(Error::Equivocation {
+        round: 1,
+        ids: vec![1],
+    });
+    assert_eq!(r2, expected);
+    assert_eq!(r3, expected);
+}
+
+#[tokio::test]
+async fn test_echo_broadcast_agrees() {
+    let mut transports = connect(&[1, 2]).await;
+    let (a, b) = transports.split_at_mut(1);
+    let (r1, r2) = tokio::join!(
+        a[0].echo_broadcast(&[2], 4, b"one".to_vec()),
+        b[0].echo_broadcast(&[1], 4, b"two".to_vec()),
+    );
+    assert_eq!(r1.unwrap()[0].payload, b"two");
+    assert_eq!(r2.unwrap()[0].payload, b"one");
+}
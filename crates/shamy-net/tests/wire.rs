@@ -0,0 +1,114 @@
+#![allow(non_snake_case)]
+
+use k256::Scalar;
+use prost::Message;
+use shamy_core::{
+    dkg,
+    schnorr::{SchnorrSignature, compute_nonce_point, generate_nonce},
+    threshold::{PartialSignature, Participant, partial_sign},
+};
+use shamy_net::{pipeline::SignerNonces, wire};
+
+#[test]
+fn test_wire_format_is_stable() {
+    let s_i = hex::encode([7u8; 32]);
+    let partial = wire::PartialSignature {
+        id: 1,
+        s_i: hex::decode(&s_i).unwrap(),
+        ..Default::default()
+    };
+
+    // tag 1 varint, tag 2 length delimited
+    let expected = format!("0801{}{}", "1220", s_i);
+    assert_eq!(hex::encode(partial.encode_to_vec()), expected);
+
+    // R_i and X_i follow as tags 3 and 4 when present
+    let R_i = hex::encode([2u8; 33]);
+    let partial = wire::PartialSignature {
+        R_i: Some(hex::decode(&R_i).unwrap()),
+        ..partial
+    };
+    let expected = format!("0801{}{}{}{}", "1220", s_i, "1a21", R_i);
+    assert_eq!(hex::encode(partial.encode_to_vec()), expected);
+}
+
+#[test]
+fn test_wire_roundtrips() {
+    let partial = PartialSignature::new(3, generate_nonce());
+    let encoded = wire::PartialSignature::from(&partial).encode_to_vec();
+    let decoded: wire::PartialSignature = wire::decode(&encoded).unwrap();
+    assert_eq!(PartialSignature::try_from(&decoded).unwrap(), partial);
+
+    let r_i = generate_nonce();
+    let signer = Participant::from_secret(3, generate_nonce());
+    let partial = partial_sign(&signer, &r_i, &Scalar::from(5u64));
+    let encoded = wire::PartialSignature::from(&partial).encode_to_vec();
+    let decoded: wire::PartialSignature = wire::decode(&encoded).unwrap();
+    let decoded = PartialSignature::try_from(&decoded).unwrap();
+    assert_eq!(decoded, partial);
+    assert_eq!(decoded.verify(&Scalar::from(5u64)), Ok(()));
+
+    let signature = SchnorrSignature {
+        R: compute_nonce_point(&generate_nonce()),
+        s: generate_nonce(),
+    };
+    let encoded = wire::Signature::from(&signature).encode_to_vec();
+    let decoded: wire::Signature = wire::decode(&encoded).unwrap();
+    assert_eq!(SchnorrSignature::try_from(&decoded).unwrap(), signature);
+
+    let commitments = SignerNonces::new(2).commit(3);
+    let decoded = wire::NonceCommitments::from(&commitments);
+    assert_eq!(
+        shamy_net::pipeline::NonceCommitments::try_from(&decoded).unwrap(),
+        commitments
+    );
+
+    let (secret, package) = dkg::round1(1, 2, 3).unwrap();
+    let decoded = dkg::Round1Package::try_from(&wire::DkgRound1Package::from(&package)).unwrap();
+    assert_eq!(decoded.commitments, package.commitments);
+    let others = [
+        dkg::round1(2, 2, 3).unwrap().1,
+        dkg::round1(3, 2, 3).unwrap().1,
+    ];
+    let round2 = dkg::round2(&secret, &[package, others[0].clone(), others[1].clone()]).unwrap();
+    let decoded = dkg::Round2Package::try_from(&wire::DkgRound2Package::from(&round2[0])).unwrap();
+    assert_eq!(decoded.share, round2[0].share);
+}
+
+#[test]
+fn test_wire_rejects_bad_encodings() {
+    let nonce = wire::NonceCommitment {
+        id: 1,
+        nonce: vec![5; 33],
+    };
+    assert!(nonce.point().is_err());
+
+    let partial = wire::PartialSignature {
+        id: 1,
+        s_i: vec![1; 31],
+        ..Default::default()
+    };
+    assert!(PartialSignature::try_from(&partial).is_err());
+    let partial = wire::PartialSignature {
+        s_i: vec![1; 32],
+        X_i: Some(vec![5; 33]),
+        ..partial
+    };
+    assert!(PartialSignature::try_from(&partial).is_err());
+    assert!(wire::decode::<wire::Signature>(&[0xff]).is_err());
+
+    let R_i = compute_nonce_point(&generate_nonce());
+    assert_eq!(wire::NonceCommitment::new(1, &R_i).point().unwrap(), R_i);
+
+    // single pre-committed nonces and unpaired batches
+    let mut commitments = wire::NonceCommitments::from(&SignerNonces::new(2).commit(2));
+    commitments.binding.pop();
+    assert!(shamy_net::pipeline::NonceCommitments::try_from(&commitments).is_err());
+    let single = wire::NonceCommitments {
+        id: 2,
+        first_index: 0,
+        nonces: vec![wire::NonceCommitment::new(2, &R_i).nonce],
+        ..Default::default()
+    };
+    assert!(shamy_net::pipeline::NonceCommitments::try_from(&single).is_err());
+}
@@ -0,0 +1,200 @@
+#![cfg(feature = "ws")]
+
+use futures_util::SinkExt;
+use shamy_core::schnorr::SigningConfig;
+use shamy_net::{
+    Error, ceremony,
+    transport::RoundTransport,
+    ws::{WsClient, WsServer},
+};
+use std::{
+    net::SocketAddr,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{
+        TcpListener, TcpStream,
+        tcp::{OwnedReadHalf, OwnedWriteHalf},
+    },
+};
+use tokio_tungstenite::tungstenite::Message;
+
+const COORDINATOR: u64 = 100;
+const KEEPALIVE: Duration = Duration::from_millis(50);
+
+/// forwards TCP connections to `target`. `cut` freezes every connection
+/// made so far without closing it, like a NAT that forgot the mapping.
+struct Proxy {
+    addr: SocketAddr,
+    generation: Arc<AtomicU64>,
+}
+
+impl Proxy {
+    async fn start(target: SocketAddr) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let generation = Arc::new(AtomicU64::new(0));
+        tokio::spawn({
+            let generation = generation.clone();
+            async move {
+                while let Ok((client, _)) = listener.accept().await {
+                    let server = TcpStream::connect(target).await.unwrap();
+                    let born = generation.load(Ordering::SeqCst);
+                    let (client_read, client_write) = client.into_split();
+                    let (server_read, server_write) = server.into_split();
+                    tokio::spawn(pipe(client_read, server_write, generation.clone(), born));
+                    tokio::spawn(pipe(server_read, client_write, generation.clone(), born));
+                }
+            }
+        });
+
+        Self { addr, generation }
+    }
+
+    fn cut(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+async fn pipe(
+    mut from: OwnedReadHalf,
+    mut to: OwnedWriteHalf,
+    generation: Arc<AtomicU64>,
+    born: u64,
+) {
+    let mut buf = [0; 4096];
+    while let Ok(n) = from.read(&mut buf).await {
+        if n == 0 {
+            return;
+        }
+        if generation.load(Ordering::SeqCst) != born {
+            std::future::pending::<()>().await;
+        }
+        if to.write_all(&buf[..n]).await.is_err() {
+            return;
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_dkg_and_signing_over_websocket() {
+    let mut coordinator = WsServer::bind(COORDINATOR, "127.0.0.1:0", "dkg", KEEPALIVE)
+        .await
+        .unwrap();
+    let url = format!("ws://{}", coordinator.local_addr());
+    // clients only reach each other through the server
+    let [mut t1, mut t2, mut t3] =
+        [1, 2, 3].map(|id| WsClient::connect(id, &url, "dkg", KEEPALIVE));
+
+    let (o1, o2, o3) = tokio::join!(
+        ceremony::dkg(&mut t1, 2, 3),
+        ceremony::dkg(&mut t2, 2, 3),
+        ceremony::dkg(&mut t3, 2, 3)
+    );
+    let (o1, o2, _) = (o1.unwrap(), o2.unwrap(), o3.unwrap());
+    assert_eq!(o1.public_key, o2.public_key);
+
+    let config = SigningConfig::new("shamy-net-tests", "ws");
+    let msg = b"signed from behind a NAT";
+    let (signature, r1, r2) = tokio::join!(
+        ceremony::sign_as_coordinator(
+            &mut coordinator,
+            o1.public_key,
+            msg,
+            &[1, 2],
+            config.clone()
+        ),
+        ceremony::sign_as_signer(
+            &mut t1,
+            COORDINATOR,
+            &o1.participant,
+            &o1.public_key,
+            &config
+        ),
+        ceremony::sign_as_signer(
+            &mut t2,
+            COORDINATOR,
+            &o2.participant,
+            &o2.public_key,
+            &config
+        ),
+    );
+    r1.unwrap();
+    r2.unwrap();
+    assert!(
+        signature
+            .unwrap()
+            .verify_with_config(msg, &o1.public_key, &config)
+    );
+}
+
+#[tokio::test]
+async fn test_ws_reconnects_and_resumes() {
+    let mut server = WsServer::bind(COORDINATOR, "127.0.0.1:0", "resume", KEEPALIVE)
+        .await
+        .unwrap();
+    let proxy = Proxy::start(server.local_addr()).await;
+    let mut client = WsClient::connect(1, &format!("ws://{}", proxy.addr), "resume", KEEPALIVE);
+
+    // queued before the client connects
+    server.send(1, 1, b"hello".to_vec()).await.unwrap();
+    client.send(COORDINATOR, 1, b"hi".to_vec()).await.unwrap();
+    assert_eq!(
+        client.receive(1, &[COORDINATOR]).await.unwrap()[0].payload,
+        b"hello"
+    );
+    assert_eq!(server.receive(1, &[1]).await.unwrap()[0].payload, b"hi");
+
+    // the connection goes dead silently, only the keepalive notices
+    proxy.cut();
+    server.send(1, 2, b"still there?".to_vec()).await.unwrap();
+    client.send(COORDINATOR, 2, b"yes".to_vec()).await.unwrap();
+    assert_eq!(
+        client.receive(2, &[COORDINATOR]).await.unwrap()[0].payload,
+        b"still there?"
+    );
+    assert_eq!(server.receive(2, &[1]).await.unwrap()[0].payload, b"yes");
+
+    // nothing was delivered twice
+    client.set_timeout(Duration::from_millis(300));
+    assert!(matches!(
+        client.receive(3, &[COORDINATOR]).await,
+        Err(Error::Timeout { round: 3, .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_ws_server_checks_session_and_sender() {
+    let mut server = WsServer::bind(COORDINATOR, "127.0.0.1:0", "mine", KEEPALIVE)
+        .await
+        .unwrap();
+    server.set_timeout(Duration::from_millis(500));
+    let url = format!("ws://{}", server.local_addr());
+
+    let stranger = WsClient::connect(1, &url, "other", KEEPALIVE);
+    stranger
+        .send(COORDINATOR, 1, b"let me in".to_vec())
+        .await
+        .unwrap();
+    assert!(matches!(
+        server.receive(1, &[1]).await,
+        Err(Error::Timeout { round: 1, .. })
+    ));
+
+    // a client can't send as someone else, the server uses its hello id
+    let mut t2 = WsClient::connect(2, &url, "mine", KEEPALIVE);
+    let (mut forger, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+    for frame in [
+        r#"{"type":"hello","session":"mine","id":3,"received":0,"sent":1}"#,
+        r#"{"type":"message","seq":1,"to":2,"round":1,"sender":1,"payload":"6869"}"#,
+    ] {
+        forger.send(Message::text(frame)).await.unwrap();
+    }
+    let received = t2.receive(1, &[3]).await.unwrap();
+    assert_eq!(received[0].payload, b"hi");
+}